@@ -0,0 +1,97 @@
+// Access tracking - which features get searched and fetched most
+//
+// Opt-in, like metrics: nothing is recorded unless .legend/access.json
+// exists, which `legend optimize --track` creates. While it exists:
+// - search and impact count each feature they return
+// - every save orders features hottest-first in state.lz4
+//
+// The on-disk order is the point. The state is one LZ4 block today, so a
+// read still decodes everything; once the versioned format (PLAN.md) can
+// read a prefix, get_state --compact can stop after the hot subset.
+// Ordering never changes what commands show: show sorts by recency, and
+// diffs (journal, replay) pair features by ID.
+//
+// With encryption on, access.json is encrypted like the state (see
+// encryption.rs), since its keys are feature IDs.
+
+use crate::encryption;
+use crate::storage;
+use crate::types::LegendState;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the access counters inside the Legend directory
+pub const ACCESS_FILE_NAME: &str = "access.json";
+
+/// Feature ID -> times returned by a read command
+pub type AccessCounts = BTreeMap<String, u64>;
+
+fn access_file() -> PathBuf {
+    storage::legend_path(ACCESS_FILE_NAME)
+}
+
+/// Is access tracking on?
+pub fn is_enabled() -> bool {
+    access_file().exists()
+}
+
+/// Counters from a Legend directory; None if tracking is off there
+pub fn counts_in(dir: &Path) -> Option<AccessCounts> {
+    let bytes = encryption::open(fs::read(dir.join(ACCESS_FILE_NAME)).ok()?).ok()?;
+    Some(serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+/// Count one access of each feature, if tracking is on
+///
+/// Never fails: tracking must not break a read that worked
+pub fn record_access<'a>(ids: impl IntoIterator<Item = &'a str>) {
+    let Some(mut counts) = counts_in(&storage::legend_dir()) else {
+        return;
+    };
+    for id in ids {
+        *counts.entry(id.to_string()).or_default() += 1;
+    }
+    let _ = save_counts(&counts);
+}
+
+/// Write access.json (creating it turns tracking on)
+pub fn save_counts(counts: &AccessCounts) -> Result<(), Box<dyn std::error::Error>> {
+    let json = encryption::seal(serde_json::to_string_pretty(counts)?.into_bytes())?;
+    fs::write(access_file(), json).map_err(|e| format!("Failed to write access file: {}", e))?;
+    Ok(())
+}
+
+/// Delete access.json (turns tracking off)
+pub fn remove_counts() -> Result<(), Box<dyn std::error::Error>> {
+    if is_enabled() {
+        fs::remove_file(access_file()).map_err(|e| format!("Failed to remove access file: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Order features most-accessed first; ties keep their current order
+pub fn order_hot_first(state: &mut LegendState, counts: &AccessCounts) {
+    state
+        .features
+        .sort_by_key(|f| std::cmp::Reverse(counts.get(&f.id).copied().unwrap_or(0)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Feature;
+
+    #[test]
+    fn test_order_hot_first() {
+        let mut state = LegendState::new("Demo".to_string());
+        for id in ["a", "b", "c", "d"] {
+            state.features.push(Feature::new(id.to_string(), id.to_string(), "x".to_string(), "d".to_string()));
+        }
+        let counts: AccessCounts = [("c".to_string(), 5), ("b".to_string(), 2)].into_iter().collect();
+
+        order_hot_first(&mut state, &counts);
+        let ids: Vec<_> = state.features.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "b", "a", "d"]);
+    }
+}
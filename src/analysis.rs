@@ -0,0 +1,573 @@
+// Static analysis - which files import which
+//
+// A light, line-based pass over import statements. It is not a compiler:
+// the goal is good-enough hints for `impact --analyze` and
+// `discover --analyze`, where a missed edge costs little.
+//
+// Supported:
+// - Rust:   mod foo;   use crate::a::b;   use super::x;   use self::{y, z};
+// - TS/JS:  import .. from './x'   export .. from '../y'   require('./z')
+// - Python: from .x import y   from pkg.mod import y   import pkg.mod
+//
+// Only imports that resolve to a file in the project count; packages from
+// crates.io/npm/PyPI are ignored.
+//
+// Rust concepts in this file:
+// - BTreeMap/BTreeSet for a deterministic adjacency list
+// - Small recursive parser for Rust use trees
+// - impl Fn as a parameter so tests don't touch the filesystem
+
+use crate::config::LimitsConfig;
+use crate::output;
+use crate::progress::Progress;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// File extensions the analysis understands
+pub const ANALYZED_EXTENSIONS: &[&str] = &["rs", "ts", "tsx", "js", "jsx", "mjs", "cjs", "py"];
+
+/// Extensions tried when resolving an extensionless JS/TS import
+const JS_RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Import edges between project files (paths relative to the project root)
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    imports: BTreeMap<String, BTreeSet<String>>,
+    /// Files left out because the index hit limits.max_index_mb
+    unread: usize,
+}
+
+impl DependencyGraph {
+    /// Build the graph for `files`, reading each with `read`
+    pub fn build<F>(files: &[String], read: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let known: HashSet<&str> = files.iter().map(|f| f.as_str()).collect();
+        let mut imports = BTreeMap::new();
+
+        for file in files {
+            if !is_analyzed(file) {
+                continue;
+            }
+            let content = match read(file) {
+                Some(content) => content,
+                None => continue,
+            };
+            let targets: BTreeSet<String> = imports_in(file, &content, &known)
+                .into_iter()
+                .filter(|target| target != file)
+                .collect();
+            if !targets.is_empty() {
+                imports.insert(file.clone(), targets);
+            }
+        }
+
+        DependencyGraph { imports, unread: 0 }
+    }
+
+    /// Did the size budget leave files out of the graph?
+    pub fn truncated(&self) -> bool {
+        self.unread > 0
+    }
+
+    /// Every file that imports or is imported by something
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        let mut all: BTreeSet<&str> = BTreeSet::new();
+        for (source, targets) in &self.imports {
+            all.insert(source);
+            all.extend(targets.iter().map(|t| t.as_str()));
+        }
+        all.into_iter()
+    }
+
+    /// Files that `file` imports
+    pub fn imports_of(&self, file: &str) -> Vec<&str> {
+        self.imports
+            .get(file)
+            .map(|targets| targets.iter().map(|t| t.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Files that import `file`
+    pub fn importers_of(&self, file: &str) -> Vec<&str> {
+        self.imports
+            .iter()
+            .filter(|(_, targets)| targets.contains(file))
+            .map(|(source, _)| source.as_str())
+            .collect()
+    }
+
+    /// Files connected to `file` by an import in either direction
+    pub fn neighbors(&self, file: &str) -> BTreeSet<String> {
+        self.imports_of(file)
+            .into_iter()
+            .chain(self.importers_of(file))
+            .map(|f| f.to_string())
+            .collect()
+    }
+}
+
+/// Analyze every supported file under `root`
+///
+/// Reads at most `limits.max_index_bytes` of source; files past the budget
+/// are left out of the graph, with a warning
+pub fn analyze_project(root: &Path, limits: &LimitsConfig) -> Result<DependencyGraph, Box<dyn std::error::Error>> {
+    let files = crate::commands::discover::collect_files(root, limits)?;
+    let read_bytes = Cell::new(0u64);
+    let unread = Cell::new(0usize);
+    let progress = Progress::start("analyze", Some(files.iter().filter(|f| is_analyzed(f)).count()));
+    let mut graph = DependencyGraph::build(&files, |file| {
+        progress.tick(file);
+        let path = root.join(file);
+        let size = fs::metadata(&path).ok()?.len();
+        if read_bytes.get() + size > limits.max_index_bytes {
+            unread.set(unread.get() + 1);
+            return None;
+        }
+        read_bytes.set(read_bytes.get() + size);
+        fs::read_to_string(path).ok()
+    });
+    progress.finish();
+
+    graph.unread = unread.get();
+    if graph.truncated() {
+        output::warning(&format!(
+            "Import index reached limits.max_index_mb; {} file(s) were not analyzed. Raise it in .legend/config.toml.",
+            graph.unread
+        ));
+    }
+    Ok(graph)
+}
+
+fn is_analyzed(file: &str) -> bool {
+    extension(file).is_some_and(|ext| ANALYZED_EXTENSIONS.contains(&ext))
+}
+
+fn extension(file: &str) -> Option<&str> {
+    let name = file.rsplit('/').next().unwrap_or(file);
+    name.rsplit_once('.').map(|(_, ext)| ext)
+}
+
+/// Resolved imports of one file
+fn imports_in(file: &str, content: &str, known: &HashSet<&str>) -> Vec<String> {
+    let candidates = match extension(file) {
+        Some("rs") => rust_import_candidates(file, content),
+        Some("py") => python_import_candidates(file, content),
+        Some(_) => js_import_candidates(file, content),
+        None => Vec::new(),
+    };
+
+    // Each import yields candidates in preference order; keep the first that exists
+    candidates
+        .into_iter()
+        .filter_map(|options| options.into_iter().find(|c| known.contains(c.as_str())))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Rust
+// ---------------------------------------------------------------------------
+
+/// Candidate files for every `mod` and `use` in a Rust file
+fn rust_import_candidates(file: &str, content: &str) -> Vec<Vec<String>> {
+    let module_dir = rust_module_dir(file);
+    let crate_root = rust_crate_root(file);
+    let mut results = Vec::new();
+
+    for statement in rust_statements(content) {
+        if let Some(name) = statement.strip_prefix("mod ") {
+            let name = name.trim();
+            results.push(vec![
+                join(&module_dir, &format!("{}.rs", name)),
+                join(&module_dir, &format!("{}/mod.rs", name)),
+            ]);
+        } else if let Some(tree) = statement.strip_prefix("use ") {
+            for path in expand_use_tree(tree.trim()) {
+                let segments: Vec<&str> = path.split("::").collect();
+                let (base, rest) = match segments.first() {
+                    Some(&"crate") => (crate_root.clone(), &segments[1..]),
+                    Some(&"self") => (module_dir.clone(), &segments[1..]),
+                    Some(&"super") => {
+                        let supers = segments.iter().take_while(|s| **s == "super").count();
+                        let mut base = module_dir.clone();
+                        for _ in 0..supers {
+                            base = parent(&base);
+                        }
+                        (base, &segments[supers..])
+                    }
+                    // External crate or std - not a project file
+                    _ => continue,
+                };
+                results.push(rust_path_candidates(&base, rest));
+            }
+        }
+    }
+
+    results
+}
+
+/// `mod`/`use` statements, joined across lines and without `pub`/`;`
+fn rust_statements(content: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+
+        if current.is_none() {
+            let stripped = strip_visibility(line);
+            if stripped.starts_with("use ") || (stripped.starts_with("mod ") && stripped.ends_with(';')) {
+                current = Some(String::new());
+            } else {
+                continue;
+            }
+        }
+
+        if let Some(statement) = current.as_mut() {
+            statement.push_str(strip_visibility(line));
+            statement.push(' ');
+            if line.ends_with(';') {
+                let done = statement.trim().trim_end_matches(';').trim().to_string();
+                statements.push(done);
+                current = None;
+            }
+        }
+    }
+
+    statements
+}
+
+fn strip_visibility(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix("pub(crate) ") {
+        return rest;
+    }
+    line.strip_prefix("pub ").unwrap_or(line)
+}
+
+/// Expand `a::{b, c::{d, e as f}, self}` into ["a::b", "a::c::d", "a::c::e", "a"]
+fn expand_use_tree(tree: &str) -> Vec<String> {
+    let tree: String = tree.chars().filter(|c| !c.is_whitespace() || *c == ' ').collect();
+    let tree = tree.trim();
+
+    match tree.find('{') {
+        None => {
+            let path = tree.split(" as ").next().unwrap_or(tree).trim();
+            let path = path.trim_end_matches("::*").trim_end_matches("::self");
+            if path.is_empty() || path == "self" {
+                Vec::new()
+            } else {
+                vec![path.to_string()]
+            }
+        }
+        Some(open) => {
+            let prefix = tree[..open].trim_end_matches("::");
+            let inner = tree[open + 1..].trim_end_matches('}');
+            split_top_level(inner)
+                .iter()
+                .flat_map(|item| {
+                    let item = item.trim();
+                    if item == "self" {
+                        return vec![prefix.to_string()];
+                    }
+                    expand_use_tree(item)
+                        .into_iter()
+                        .map(|rest| format!("{}::{}", prefix, rest))
+                        .collect()
+                })
+                .collect()
+        }
+    }
+}
+
+/// Split on commas that are not inside braces
+fn split_top_level(s: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+
+    for c in s.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Files that could define `segments` below `base`, longest path first
+///
+/// `use crate::a::b::Thing` may refer to src/a/b.rs (Thing is an item) or
+/// src/a/b/thing.rs - we don't know, so try every prefix.
+fn rust_path_candidates(base: &str, segments: &[&str]) -> Vec<String> {
+    let mut candidates = Vec::new();
+    for len in (1..=segments.len()).rev() {
+        let module = segments[..len].join("/");
+        candidates.push(join(base, &format!("{}.rs", module)));
+        candidates.push(join(base, &format!("{}/mod.rs", module)));
+    }
+    candidates
+}
+
+/// Directory holding a Rust file's child modules
+///
+/// src/main.rs and src/commands/mod.rs own their directory;
+/// src/commands/impact.rs owns src/commands/impact/
+fn rust_module_dir(file: &str) -> String {
+    let dir = parent(file);
+    let name = file.rsplit('/').next().unwrap_or(file);
+    match name {
+        "mod.rs" | "main.rs" | "lib.rs" => dir,
+        _ => join(&dir, name.trim_end_matches(".rs")),
+    }
+}
+
+/// The crate's src/ directory (or the file's directory if there isn't one)
+fn rust_crate_root(file: &str) -> String {
+    match file.rfind("src/") {
+        Some(i) if i == 0 || file[..i].ends_with('/') => file[..i + 3].to_string(),
+        _ => parent(file),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TypeScript / JavaScript
+// ---------------------------------------------------------------------------
+
+/// Candidate files for every relative import/require in a JS/TS file
+fn js_import_candidates(file: &str, content: &str) -> Vec<Vec<String>> {
+    let dir = parent(file);
+    let mut results = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        let is_import = line.starts_with("import ")
+            || line.starts_with("export ")
+            || line.contains("require(")
+            || line.contains("import(")
+            || line.starts_with("} from ");
+        if !is_import {
+            continue;
+        }
+
+        for specifier in quoted_strings(line) {
+            if !specifier.starts_with('.') {
+                continue;
+            }
+            let base = join(&dir, &specifier);
+            let mut options = vec![base.clone()];
+            options.extend(JS_RESOLVE_EXTENSIONS.iter().map(|ext| format!("{}.{}", base, ext)));
+            options.extend(JS_RESOLVE_EXTENSIONS.iter().map(|ext| format!("{}/index.{}", base, ext)));
+            results.push(options);
+        }
+    }
+
+    results
+}
+
+/// Contents of '...' and "..." strings on a line
+fn quoted_strings(line: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\'' || c == '"' || c == '`' {
+            let value: String = chars.by_ref().take_while(|next| *next != c).collect();
+            strings.push(value);
+        }
+    }
+    strings
+}
+
+// ---------------------------------------------------------------------------
+// Python
+// ---------------------------------------------------------------------------
+
+/// Candidate files for every `import`/`from .. import` in a Python file
+fn python_import_candidates(file: &str, content: &str) -> Vec<Vec<String>> {
+    let dir = parent(file);
+    let mut results = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+
+        if let Some(rest) = line.strip_prefix("from ") {
+            let (module, names) = match rest.split_once(" import ") {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let module = module.trim();
+            let dots = module.chars().take_while(|c| *c == '.').count();
+            let module = &module[dots..];
+
+            if dots == 0 {
+                results.push(python_module_candidates("", module));
+                continue;
+            }
+
+            let mut base = dir.clone();
+            for _ in 1..dots {
+                base = parent(&base);
+            }
+            if module.is_empty() {
+                // from . import a, b  ->  sibling modules a and b
+                for name in names.trim_matches(|c| c == '(' || c == ')').split(',') {
+                    let name = name.split(" as ").next().unwrap_or("").trim();
+                    if !name.is_empty() {
+                        results.push(python_module_candidates(&base, name));
+                    }
+                }
+            } else {
+                results.push(python_module_candidates(&base, module));
+            }
+        } else if let Some(rest) = line.strip_prefix("import ") {
+            for module in rest.split(',') {
+                let module = module.split(" as ").next().unwrap_or("").trim();
+                if !module.is_empty() {
+                    results.push(python_module_candidates("", module));
+                }
+            }
+        }
+    }
+
+    results
+}
+
+/// pkg.mod -> pkg/mod.py or pkg/mod/__init__.py (also tried under src/)
+fn python_module_candidates(base: &str, module: &str) -> Vec<String> {
+    let path = module.replace('.', "/");
+    let mut candidates = vec![
+        join(base, &format!("{}.py", path)),
+        join(base, &format!("{}/__init__.py", path)),
+    ];
+    if base.is_empty() {
+        candidates.push(format!("src/{}.py", path));
+        candidates.push(format!("src/{}/__init__.py", path));
+    }
+    candidates
+}
+
+// ---------------------------------------------------------------------------
+// Path helpers (project-relative, '/'-separated)
+// ---------------------------------------------------------------------------
+
+fn parent(path: &str) -> String {
+    match path.rfind('/') {
+        Some(i) => path[..i].to_string(),
+        None => String::new(),
+    }
+}
+
+/// Join and normalize, resolving "." and ".." components
+fn join(base: &str, relative: &str) -> String {
+    let mut parts: Vec<&str> = base.split('/').filter(|p| !p.is_empty()).collect();
+    for component in relative.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            other => parts.push(other),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(files: &[(&str, &str)]) -> DependencyGraph {
+        let names: Vec<String> = files.iter().map(|(name, _)| name.to_string()).collect();
+        DependencyGraph::build(&names, |name| {
+            files.iter().find(|(n, _)| *n == name).map(|(_, c)| c.to_string())
+        })
+    }
+
+    #[test]
+    fn test_rust_imports() {
+        let g = graph(&[
+            ("src/main.rs", "mod commands;\nmod storage;\nuse std::env;\n"),
+            ("src/storage.rs", "use crate::types::{LegendState, Feature};\n"),
+            ("src/types.rs", ""),
+            ("src/commands/mod.rs", "pub mod show;\n"),
+            (
+                "src/commands/show.rs",
+                "use crate::{\n    storage,\n    types::FeatureStatus,\n};\nuse super::mod_helper;\n",
+            ),
+        ]);
+
+        assert_eq!(g.imports_of("src/main.rs"), vec!["src/commands/mod.rs", "src/storage.rs"]);
+        assert_eq!(g.imports_of("src/storage.rs"), vec!["src/types.rs"]);
+        assert_eq!(g.imports_of("src/commands/mod.rs"), vec!["src/commands/show.rs"]);
+        assert_eq!(g.imports_of("src/commands/show.rs"), vec!["src/storage.rs", "src/types.rs"]);
+        assert_eq!(g.importers_of("src/types.rs"), vec!["src/commands/show.rs", "src/storage.rs"]);
+    }
+
+    #[test]
+    fn test_js_imports() {
+        let g = graph(&[
+            ("web/app.tsx", "import { api } from './lib/api';\nimport React from 'react';\n"),
+            ("web/lib/api.ts", "const db = require('../db');\nexport * from \"./types\";\n"),
+            ("web/lib/types.ts", ""),
+            ("web/db/index.js", ""),
+        ]);
+
+        assert_eq!(g.imports_of("web/app.tsx"), vec!["web/lib/api.ts"]);
+        assert_eq!(g.imports_of("web/lib/api.ts"), vec!["web/db/index.js", "web/lib/types.ts"]);
+    }
+
+    #[test]
+    fn test_python_imports() {
+        let g = graph(&[
+            ("app/main.py", "from .models import User\nimport app.db as db\nimport os\n"),
+            ("app/models.py", "from . import db, util  # siblings\n"),
+            ("app/db/__init__.py", ""),
+            ("app/util.py", "from ..scripts.seed import run\n"),
+            ("scripts/seed.py", ""),
+        ]);
+
+        assert_eq!(g.imports_of("app/main.py"), vec!["app/db/__init__.py", "app/models.py"]);
+        assert_eq!(g.imports_of("app/models.py"), vec!["app/db/__init__.py", "app/util.py"]);
+        assert_eq!(g.imports_of("app/util.py"), vec!["scripts/seed.py"]);
+    }
+
+    #[test]
+    fn test_expand_use_tree() {
+        assert_eq!(
+            expand_use_tree("crate::a::{b, c::{d, e as f}, self}"),
+            vec!["crate::a::b", "crate::a::c::d", "crate::a::c::e", "crate::a"]
+        );
+        assert_eq!(expand_use_tree("super::*"), vec!["super"]);
+    }
+
+    #[test]
+    fn test_analyze_project_limits() {
+        let root = std::env::temp_dir().join(format!("legend-analysis-limits-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), "mod a;\nmod b;\n").unwrap();
+        fs::write(root.join("src/a.rs"), "use crate::b;\n").unwrap();
+        fs::write(root.join("src/b.rs"), "").unwrap();
+
+        let roomy = analyze_project(&root, &LimitsConfig::default()).unwrap();
+        let tight = analyze_project(&root, &LimitsConfig { max_files: 10, max_index_bytes: 14 }).unwrap();
+        let few = analyze_project(&root, &LimitsConfig { max_files: 1, max_index_bytes: 1024 }).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(!roomy.truncated());
+        assert_eq!(roomy.files().count(), 3);
+        assert!(tight.truncated());
+        assert!(few.files().count() <= 1);
+    }
+}
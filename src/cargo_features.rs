@@ -0,0 +1,33 @@
+// Cargo features - optional subsystems compiled into this binary
+//
+// The default build has everything. A hook-only install, which just runs
+// get_state and update, can leave the rest out:
+//
+//   cargo install legend --no-default-features --features hook
+//
+// Commands that need a missing subsystem fail with not_built() instead of
+// disappearing, so a script gets a clear message rather than "Unknown
+// command". `legend version --features` lists what this binary has. New
+// heavyweight subsystems get an entry here and a feature in Cargo.toml.
+
+use crate::exit::{self, ExitError};
+
+/// (Cargo feature, compiled in?, what it provides)
+pub const FEATURES: &[(&str, bool, &str)] = &[
+    ("tui", cfg!(feature = "tui"), "show --interactive (raw terminal, signal handling via libc)"),
+    ("docs", cfg!(feature = "docs"), "export --docusaurus / --mkdocs documentation pages"),
+    ("webhooks", cfg!(feature = "webhooks"), "[webhooks] notifications on handoff"),
+];
+
+/// The error for using `what`, which needs the Cargo feature `feature`
+#[cfg_attr(all(feature = "tui", feature = "docs"), allow(dead_code))]
+pub fn not_built(feature: &str, what: &str) -> Box<dyn std::error::Error> {
+    ExitError::new(
+        exit::FAILURE,
+        format!(
+            "{} is not available: this legend was built without the '{}' feature. Reinstall with `cargo install legend` (default features) or add `--features {}`.",
+            what, feature, feature
+        ),
+    )
+    .into()
+}
@@ -0,0 +1,97 @@
+// Clock - where "now" comes from
+//
+// Every timestamp Legend stores goes through current_timestamp(), which
+// asks the clock installed on the current thread (the system clock unless
+// something says otherwise). Tests install a FixedClock so recency,
+// lifecycle, and "N days ago" logic is deterministic; time-travel modes
+// can install one the same way.
+//
+// Why thread-local? cargo test runs tests on parallel threads, so a
+// process-wide override would leak between them. The CLI itself is
+// single-threaded, so for it the two are the same thing.
+//
+// Rust concepts in this file:
+// - Trait objects (Box<dyn Clock>) for a swappable implementation
+// - thread_local! with RefCell for per-thread state
+// - A guard whose Drop undoes the override
+
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of the current time in Unix seconds (UTC)
+pub trait Clock {
+    fn now(&self) -> i64;
+}
+
+/// The real wall clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs() as i64
+    }
+}
+
+/// A clock stuck at one moment
+#[allow(dead_code)]
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Option<Box<dyn Clock>>> = const { RefCell::new(None) };
+}
+
+/// The current time from this thread's clock
+pub fn now() -> i64 {
+    CLOCK.with(|clock| match clock.borrow().as_ref() {
+        Some(clock) => clock.now(),
+        None => SystemClock.now(),
+    })
+}
+
+/// Use `clock` on this thread until the returned guard is dropped
+#[allow(dead_code)]
+pub fn install(clock: impl Clock + 'static) -> ClockGuard {
+    let previous = CLOCK.with(|slot| slot.replace(Some(Box::new(clock))));
+    ClockGuard { previous }
+}
+
+/// Restores the previous clock when dropped
+pub struct ClockGuard {
+    previous: Option<Box<dyn Clock>>,
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CLOCK.with(|slot| *slot.borrow_mut() = previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_and_restore() {
+        let real = now();
+        {
+            let _outer = install(FixedClock(100));
+            assert_eq!(now(), 100);
+            {
+                let _inner = install(FixedClock(200));
+                assert_eq!(now(), 200);
+            }
+            assert_eq!(now(), 100);
+        }
+        assert!(now() >= real);
+    }
+}
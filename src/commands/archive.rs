@@ -0,0 +1,214 @@
+// Archive and prune commands - move finished work out of the active state
+//
+// Usage:
+//   legend archive <id> [<id>...]       - move features to .legend/archive.lz4
+//   legend archive --list [--json]      - archived features, newest first
+//   legend prune --older-than 30d [--status Complete] [--dry-run]
+//                                       - archive every feature in that status
+//                                         not updated for that long
+//
+// Archived features leave `features`, so get_state stays small, but are
+// kept whole in archive.lz4 with the time they were archived. Like a
+// removal, archiving drops dependency edges and the milestone, goal, and
+// focus entries that point at them, and the journal records them as removed.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, ArchivedFeature, Feature, LegendState};
+use serde_json::json;
+use std::collections::HashSet;
+
+const ARCHIVE_USAGE: &str = "Usage: legend archive <id> [<id>...]\n       legend archive --list [--json]";
+const PRUNE_USAGE: &str = "Usage: legend prune --older-than <age, e.g. 30d or 2w> [--status <status>] [--dry-run]";
+
+/// Handle the archive command
+pub fn handle_archive(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args {
+        [] => Err(ExitError::new(exit::USAGE, ARCHIVE_USAGE).into()),
+        [flag] if flag == "--list" => list(false),
+        [a, b] if a == "--list" && b == "--json" => list(true),
+        _ => {
+            if let Some(flag) = args.iter().find(|a| a.starts_with("--")) {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, ARCHIVE_USAGE)).into());
+            }
+            let _lock = storage::lock_state()?;
+            let mut state = storage::load_state()?;
+            let mut selected = HashSet::new();
+            for id in args {
+                let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+                selected.insert(state.features[index].id.clone());
+            }
+            archive_and_save(&mut state, &selected)
+        }
+    }
+}
+
+/// Handle the prune command
+pub fn handle_prune(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut older_than = None;
+    let mut status = "Complete".to_string();
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--dry-run" => dry_run = true,
+            flag @ ("--older-than" | "--status") => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("{} expects a value\n{}", flag, PRUNE_USAGE)))?;
+                if flag == "--older-than" {
+                    older_than = Some(dates::parse_age(value)?);
+                } else {
+                    status = value.clone();
+                }
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, PRUNE_USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+    let older_than = older_than.ok_or_else(|| ExitError::new(exit::USAGE, PRUNE_USAGE))?;
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let selected = prune_candidates(&state.features, &status, current_timestamp() - older_than);
+    if selected.is_empty() {
+        output::info(&format!("Nothing to prune: no {} features older than that.", status));
+        return Ok(());
+    }
+    if dry_run {
+        let mut listed: Vec<&String> = selected.iter().collect();
+        listed.sort();
+        for id in listed {
+            println!("{}", id);
+        }
+        output::diag(
+            output::Level::Info,
+            &format!("Would archive {} feature(s). Run without --dry-run to do it.", selected.len()),
+            json!({"count": selected.len(), "dry_run": true}),
+        );
+        return Ok(());
+    }
+    archive_and_save(&mut state, &selected)
+}
+
+/// Archive `selected`, save both files, and journal the removal
+fn archive_and_save(state: &mut LegendState, selected: &HashSet<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let before = state.clone();
+    let archived = archive_features(state, selected, current_timestamp())?;
+    state.touch();
+    journal::commit(&before, state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!(
+            "Archived {} feature(s): {}. {} remain active.",
+            archived.len(),
+            archived.join(", "),
+            state.features.len()
+        ),
+        json!({"archived": archived, "remaining": state.features.len()}),
+    );
+    Ok(())
+}
+
+/// Move `ids` from the state into archive.lz4; returns the IDs moved
+///
+/// The archive is saved before the caller saves the state, so a failed
+/// state write leaves a feature in both places rather than in neither
+pub fn archive_features(
+    state: &mut LegendState,
+    ids: &HashSet<String>,
+    now: i64,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let removed = state.remove_features(ids);
+    let moved: Vec<String> = removed.iter().map(|f| f.id.clone()).collect();
+
+    let mut archive = storage::load_archive()?;
+    archive
+        .features
+        .extend(removed.into_iter().map(|feature| ArchivedFeature { archived_at: now, feature }));
+    storage::save_archive(&archive)?;
+    Ok(moved)
+}
+
+/// Features in `status` (case-insensitive) last updated before `cutoff`
+fn prune_candidates(features: &[Feature], status: &str, cutoff: i64) -> HashSet<String> {
+    features
+        .iter()
+        .filter(|f| f.status.name().eq_ignore_ascii_case(status) && f.last_updated < cutoff)
+        .map(|f| f.id.clone())
+        .collect()
+}
+
+fn list(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let archive = storage::load_archive()?;
+    let entries: Vec<&ArchivedFeature> = archive.features.iter().rev().collect();
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if entries.is_empty() {
+        output::info("Nothing archived yet.");
+    } else {
+        for entry in entries {
+            println!(
+                "{}  {}  {}",
+                dates::format_date(entry.archived_at),
+                entry.feature.id,
+                entry.feature.name
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::FeatureStatus;
+
+    fn feature(id: &str, status: FeatureStatus, last_updated: i64) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), "d".to_string(), "d".to_string());
+        f.status = status;
+        f.last_updated = last_updated;
+        f
+    }
+
+    #[test]
+    fn test_prune_candidates() {
+        let features = vec![
+            feature("old-done", FeatureStatus::Complete, 10),
+            feature("new-done", FeatureStatus::Complete, 100),
+            feature("old-open", FeatureStatus::Pending, 10),
+        ];
+        assert_eq!(prune_candidates(&features, "complete", 50), HashSet::from(["old-done".to_string()]));
+        assert_eq!(prune_candidates(&features, "Pending", 50), HashSet::from(["old-open".to_string()]));
+    }
+
+    #[test]
+    fn test_archive_features_keeps_them_in_the_archive() {
+        let _env = TestEnv::new(0);
+        let mut state = LegendState::new("Test".to_string());
+        state.features.push(feature("db", FeatureStatus::Complete, 0));
+        let mut api = feature("api", FeatureStatus::InProgress, 0);
+        api.depends_on = vec!["db".to_string()];
+        state.features.push(api);
+
+        let moved = archive_features(&mut state, &HashSet::from(["db".to_string()]), 42).unwrap();
+        assert_eq!(moved, vec!["db"]);
+        assert_eq!(state.features.len(), 1);
+        assert!(state.features[0].depends_on.is_empty());
+
+        let archive = storage::load_archive().unwrap();
+        assert_eq!(archive.features[0].feature.id, "db");
+        assert_eq!(archive.features[0].archived_at, 42);
+    }
+}
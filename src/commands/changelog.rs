@@ -0,0 +1,318 @@
+// Changelog command - how the context memory itself evolved
+//
+// Distinct from release-notes (what shipped in the code): this reads the
+// journal and reports what Legend was taught - features added, removed,
+// renamed, or changed, and decisions recorded or dropped - so you can
+// audit the AI's memory over time.
+//
+// Usage:
+//   legend changelog                          - Keep a Changelog markdown
+//   legend changelog --format json            - raw journal events
+//   legend changelog --since 2024-03-01       - only recent entries
+//
+// Rust concepts in this file:
+// - BTreeMap with Reverse keys for newest-first grouping
+// - Matching on enum variants with struct-like fields
+
+use crate::dates;
+use crate::diff;
+use crate::exit::{self, ExitError};
+use crate::journal::{self, Event, EventKind, Sections};
+use crate::output;
+use crate::types::Decision;
+use serde_json::json;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Requested output format
+#[derive(Debug, PartialEq)]
+enum ChangelogFormat {
+    KeepAChangelog,
+    Json,
+}
+
+/// Entries for one day, bucketed by Keep a Changelog section
+#[derive(Default)]
+struct DayEntries {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// Handle the changelog command
+pub fn handle_changelog(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (format, since) = parse_args(args)?;
+
+    // Decision entries compare against the sections before them, so the
+    // markdown walks the whole journal and only prints from --since on
+    let all_events = journal::read_events()?;
+    let events: Vec<&Event> = all_events
+        .iter()
+        .filter(|e| since.map(|s| e.timestamp >= s).unwrap_or(true))
+        .collect();
+
+    match format {
+        ChangelogFormat::Json => {
+            let json = serde_json::to_string_pretty(&events)
+                .map_err(|e| format!("Failed to serialize events: {}", e))?;
+            println!("{}", json);
+        }
+        ChangelogFormat::KeepAChangelog => {
+            println!("{}", render_keepachangelog(&all_events, since));
+        }
+    }
+
+    output::diag(
+        output::Level::Info,
+        &format!("{} journal event(s)", events.len()),
+        json!({"count": events.len()}),
+    );
+    Ok(())
+}
+
+fn parse_args(
+    args: &[String],
+) -> Result<(ChangelogFormat, Option<i64>), Box<dyn std::error::Error>> {
+    let mut format = ChangelogFormat::KeepAChangelog;
+    let mut since = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                i += 1;
+                format = match args.get(i).map(|s| s.as_str()) {
+                    Some("keepachangelog") | Some("markdown") => ChangelogFormat::KeepAChangelog,
+                    Some("json") => ChangelogFormat::Json,
+                    Some(other) => {
+                        return Err(ExitError::new(
+                            exit::USAGE,
+                            format!("Unknown format '{}'. Use keepachangelog or json.", other),
+                        )
+                        .into())
+                    }
+                    None => return Err(ExitError::new(exit::USAGE, "--format requires a value").into()),
+                };
+            }
+            "--since" => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, "--since requires a date"))?;
+                since = Some(dates::parse_date(value).map_err(|e| ExitError::new(exit::USAGE, e.to_string()))?);
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}", other)).into());
+            }
+        }
+        i += 1;
+    }
+
+    Ok((format, since))
+}
+
+/// Render events as a Keep a Changelog document, newest day first
+fn render_keepachangelog(events: &[Event], since: Option<i64>) -> String {
+    let mut days: BTreeMap<Reverse<String>, DayEntries> = BTreeMap::new();
+    // Decisions as of the last sections event; None before the first one
+    let mut decisions: Option<Vec<Decision>> = None;
+
+    for event in events {
+        // For a sections event that set decisions, the ones before it
+        let previous = match &event.kind {
+            EventKind::SectionsChanged(Sections { decisions: Some(current), .. }) => {
+                Some(decisions.replace(current.clone()))
+            }
+            _ => None,
+        };
+        if since.is_some_and(|s| event.timestamp < s) {
+            continue;
+        }
+        let day = days
+            .entry(Reverse(dates::format_date(event.timestamp)))
+            .or_default();
+
+        match &event.kind {
+            EventKind::Baseline { features } => {
+                for feature in features {
+                    day.added.push(format!(
+                        "`{}` {} (tracked before the journal started)",
+                        feature.id, feature.name
+                    ));
+                }
+            }
+            EventKind::FeatureAdded { feature } => {
+                day.added.push(format!("`{}` {}", feature.id, feature.name));
+            }
+            EventKind::FeatureChanged { id, changes, .. } => {
+                day.changed.push(format!("`{}`: {}", id, diff::describe_changes(changes)));
+            }
+            EventKind::FeatureRemoved { id, name } => {
+                day.removed.push(format!("`{}` {}", id, name));
+            }
+            EventKind::SectionsChanged(sections) => {
+                let (Some(current), Some(previous)) = (&sections.decisions, previous) else {
+                    continue;
+                };
+                match previous {
+                    // The first sections event holds everything from before the journal
+                    None => {
+                        for decision in current {
+                            day.added.push(format!(
+                                "decision #{} {} (recorded before the journal started)",
+                                decision.id, decision.title
+                            ));
+                        }
+                    }
+                    Some(previous) => {
+                        for decision in current.iter().filter(|d| !previous.iter().any(|p| p.id == d.id)) {
+                            day.added.push(format!("decision #{} {}", decision.id, decision.title));
+                        }
+                        for decision in previous.iter().filter(|p| !current.iter().any(|d| d.id == p.id)) {
+                            day.removed.push(format!("decision #{} {}", decision.id, decision.title));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // A sections event that touched no decisions leaves its day empty
+    days.retain(|_, e| !(e.added.is_empty() && e.changed.is_empty() && e.removed.is_empty()));
+
+    let mut out = String::from("# Changelog\n\nHow this project's Legend memory has evolved.\n");
+
+    if days.is_empty() {
+        out.push_str("\nNo journal entries yet.\n");
+        return out;
+    }
+
+    for (Reverse(date), entries) in days {
+        out.push_str(&format!("\n## {}\n", date));
+        push_section(&mut out, "Added", &entries.added);
+        push_section(&mut out, "Changed", &entries.changed);
+        push_section(&mut out, "Removed", &entries.removed);
+    }
+
+    out
+}
+
+fn push_section(out: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n### {}\n\n", title));
+    for item in items {
+        out.push_str(&format!("- {}\n", item));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::FieldChange;
+    use crate::types::Feature;
+    use serde_json::Value;
+
+    fn make_feature(id: &str, name: &str) -> Feature {
+        Feature::new(
+            id.to_string(),
+            name.to_string(),
+            "test".to_string(),
+            "Test feature".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_render_groups_by_day_newest_first() {
+        let day_one = 1709251200; // 2024-03-01
+        let day_two = day_one + 86400;
+        let events = vec![
+            Event {
+                seq: 1,
+                timestamp: day_one,
+                kind: EventKind::FeatureAdded {
+                    feature: make_feature("auth", "Auth"),
+                },
+            },
+            Event {
+                seq: 2,
+                timestamp: day_two,
+                kind: EventKind::FeatureChanged {
+                    id: "auth".to_string(),
+                    changes: vec![
+                        FieldChange {
+                            field: "name".to_string(),
+                            before: Value::from("Auth"),
+                            after: Value::from("Authentication"),
+                        },
+                        FieldChange {
+                            field: "tags".to_string(),
+                            before: Value::Array(vec![]),
+                            after: Value::Array(vec![Value::from("api")]),
+                        },
+                    ],
+                    feature: make_feature("auth", "Authentication"),
+                },
+            },
+        ];
+
+        let md = render_keepachangelog(&events, None);
+
+        let first = md.find("## 2024-03-02").unwrap();
+        let second = md.find("## 2024-03-01").unwrap();
+        assert!(first < second);
+        assert!(md.contains("- `auth`: renamed from \"Auth\" to \"Authentication\"; updated tags"));
+        assert!(md.contains("### Added\n\n- `auth` Auth"));
+    }
+
+    fn decision(id: u32, title: &str) -> Decision {
+        Decision {
+            id,
+            title: title.to_string(),
+            rationale: String::new(),
+            feature_ids: Vec::new(),
+            decided_at: 0,
+        }
+    }
+
+    fn decisions_event(seq: u64, timestamp: i64, decisions: Vec<Decision>) -> Event {
+        Event {
+            seq,
+            timestamp,
+            kind: EventKind::SectionsChanged(Sections {
+                decisions: Some(decisions),
+                ..Sections::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_render_includes_decision_changes() {
+        let day_one = 1709251200; // 2024-03-01
+        let day_two = day_one + 86400;
+        let events = vec![
+            decisions_event(1, day_one, vec![decision(1, "Use bincode")]),
+            decisions_event(2, day_one, vec![decision(1, "Use bincode"), decision(2, "Use JWT")]),
+            Event {
+                seq: 3,
+                timestamp: day_one,
+                kind: EventKind::SectionsChanged(Sections {
+                    conventions: Some(vec!["no unwrap".to_string()]),
+                    ..Sections::default()
+                }),
+            },
+            decisions_event(4, day_two, vec![decision(2, "Use JWT")]),
+        ];
+
+        let md = render_keepachangelog(&events, None);
+        assert!(md.contains("- decision #1 Use bincode (recorded before the journal started)"));
+        assert!(md.contains("- decision #2 Use JWT\n"));
+        assert!(md.contains("## 2024-03-02\n\n### Removed\n\n- decision #1 Use bincode"));
+
+        // Earlier events still set what a later one is compared against
+        let md = render_keepachangelog(&events, Some(day_two));
+        assert!(!md.contains("## 2024-03-01"));
+        assert!(!md.contains("Use JWT"));
+        assert!(md.contains("### Removed\n\n- decision #1 Use bincode"));
+    }
+}
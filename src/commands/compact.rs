@@ -0,0 +1,75 @@
+// Compact command - fold journal events the snapshot is missing into it
+//
+// Usage:
+//   legend compact
+//
+// Events outrun the snapshot when a write died between appending to the
+// journal and saving, or while storage.snapshot_every holds updates back
+// (see journal.rs). Loading already replays them; this saves the result
+// so they stop being replayed. The journal itself is kept whole: it is
+// the audit trail.
+
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::storage;
+
+const USAGE: &str = "Usage: legend compact";
+
+/// Handle the compact command
+pub fn handle_compact(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if !args.is_empty() {
+        return Err(ExitError::new(exit::USAGE, USAGE).into());
+    }
+
+    let _lock = storage::lock_state()?;
+    let pending = journal::unapplied_events_in(&storage::legend_dir())?.len();
+    if pending == 0 {
+        println!("Nothing to compact: the snapshot has every journaled change");
+        return Ok(());
+    }
+
+    // load_state replays the events; saving marks them applied
+    let state = storage::load_state()?;
+    storage::save_state(&state)?;
+    println!("Folded {} journal event(s) into the snapshot", pending);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::Feature;
+    use std::fs;
+
+    #[test]
+    fn test_crash_before_save_is_replayed() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let before = storage::load_state().unwrap();
+        let mut after = before.clone();
+        after.add_feature(Feature::new("auth".to_string(), "Auth".to_string(), "web".to_string(), "d".to_string()));
+        journal::commit(&before, &after).unwrap();
+        let snapshot = fs::read(storage::state_file()).unwrap();
+        let applied_path = storage::legend_path("events.applied");
+        let applied = fs::read(&applied_path).unwrap();
+
+        // A second write that dies after the journal append: the old
+        // snapshot and mark are back, plus half a line of a third append
+        let mut edited = after.clone();
+        edited.features[0].description = "edited".to_string();
+        journal::commit(&after, &edited).unwrap();
+        fs::write(storage::state_file(), &snapshot).unwrap();
+        fs::write(&applied_path, &applied).unwrap();
+        let journal_path = storage::legend_path("events.log");
+        let mut log = fs::read(&journal_path).unwrap();
+        log.extend_from_slice(b"{\"seq\":9,\"timest");
+        fs::write(&journal_path, log).unwrap();
+
+        assert_eq!(journal::unapplied_events_in(&storage::legend_dir()).unwrap().len(), 1);
+        assert_eq!(storage::load_state().unwrap().features[0].description, "edited");
+
+        handle_compact(&[]).unwrap();
+        assert!(journal::unapplied_events_in(&storage::legend_dir()).unwrap().is_empty());
+        assert_eq!(storage::load_state().unwrap().features[0].description, "edited");
+    }
+}
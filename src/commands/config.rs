@@ -0,0 +1,265 @@
+// Config command - read and edit config.toml from the command line
+//
+// Usage:
+//   legend config show [--global]              - effective settings (or just the user file)
+//   legend config show --resolved              - ...with where each value comes from
+//   legend config get <key> [--global]         - one value, e.g. recency.half_life_days
+//   legend config set <key> <value> [--global] - write a value
+//   legend config unset <key> [--global]       - remove a value
+//   legend config edit [--global]              - open the file in an editor
+//   legend config path [--global]              - print the file's location
+//
+// Without --global these work on .legend/config.toml; with it, on the
+// user-level ~/.config/legend/config.toml that every project inherits
+// (see config.rs). `set` keeps comments and layout of the rest of the file.
+//
+// `edit` runs the `editor` setting, then $VISUAL, then $EDITOR, then vi.
+
+use crate::config::{self, Config};
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const USAGE: &str = "Usage: legend config <show|get|set|unset|edit|path> [key] [value] [--global]";
+
+/// Handle the config command
+pub fn handle_config(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let global = args.iter().any(|a| a == "--global");
+    let resolved = args.iter().any(|a| a == "--resolved");
+    let rest: Vec<&str> = args
+        .iter()
+        .filter(|a| *a != "--global" && *a != "--resolved")
+        .map(String::as_str)
+        .collect();
+    if let Some(flag) = rest.iter().find(|a| a.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+
+    match rest.as_slice() {
+        ["show"] | [] if resolved => print!("{}", render_resolved(&resolve()?)),
+        ["show"] | [] => {
+            let config = if global {
+                config::load_config_file(&target_path(true)?)?
+            } else {
+                config::load_config()?
+            };
+            print!("{}", render_entries(&config));
+        }
+        ["get", key] => {
+            let config = if global {
+                config::load_config_file(&target_path(true)?)?
+            } else {
+                config::load_config()?
+            };
+            let value = config.get(key).ok_or_else(|| format!("config: {} is not set", key))?;
+            println!("{}", value);
+        }
+        ["set", key, value] => {
+            let path = target_path(global)?;
+            let content = read_or_empty(&path)?;
+            let updated = config::set_value(&content, key, value)?;
+            // Never write a file the loader would then reject
+            config::parse_config(&updated)?;
+            write_config(&path, &updated)?;
+            println!("Set {} = {} in {}", key, value, path.display());
+        }
+        ["unset", key] => {
+            let path = target_path(global)?;
+            let content = read_or_empty(&path)?;
+            match config::unset_value(&content, key)? {
+                Some(updated) => {
+                    write_config(&path, &updated)?;
+                    println!("Removed {} from {}", key, path.display());
+                }
+                None => println!("{} is not set in {}", key, path.display()),
+            }
+        }
+        ["edit"] => {
+            let path = target_path(global)?;
+            if !path.exists() {
+                write_config(&path, "")?;
+            }
+            open_editor(&path)?;
+        }
+        ["path"] => println!("{}", target_path(global)?.display()),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+
+    Ok(())
+}
+
+/// The file a command edits: the user-level config or the project's
+fn target_path(global: bool) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if global {
+        return config::global_config_path()
+            .ok_or_else(|| "No home directory: set HOME or XDG_CONFIG_HOME for --global".into());
+    }
+    if !storage::legend_dir().exists() {
+        return Err(ExitError::new(
+            exit::NOT_INITIALIZED,
+            "Legend not initialized. Run 'legend init' first (or use --global).",
+        )
+        .into());
+    }
+    Ok(config::config_path())
+}
+
+fn read_or_empty(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e).into())
+}
+
+fn write_config(path: &Path, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e).into())
+}
+
+/// `key = value` lines, one per setting
+fn render_entries(config: &Config) -> String {
+    config
+        .entries()
+        .map(|(key, value)| format!("{} = {}\n", key, value))
+        .collect()
+}
+
+/// Where a setting's effective value comes from
+#[derive(Debug, Clone, PartialEq)]
+enum Origin {
+    Env(String),
+    Project,
+    User,
+    Default,
+}
+
+/// One setting's effective value
+#[derive(Debug, Clone, PartialEq)]
+struct Setting {
+    key: String,
+    value: String,
+    origin: Origin,
+}
+
+/// Every setting with its effective value and origin, sorted by key
+fn resolve() -> Result<Vec<Setting>, Box<dyn std::error::Error>> {
+    let user = match config::global_config_path() {
+        Some(path) => config::load_config_file(&path)?,
+        None => Config::default(),
+    };
+    let project = config::load_config_file(&config::config_path())?;
+    let mut files = user.clone();
+    files.overlay(project.clone());
+    let env = config::env_overrides(std::env::vars(), &files);
+
+    for var in &env.unknown {
+        output::warning(&format!("{} matches no config key and is ignored", var));
+    }
+    Ok(resolve_layers(&user, &project, &env))
+}
+
+fn resolve_layers(user: &Config, project: &Config, env: &config::EnvOverrides) -> Vec<Setting> {
+    let mut keys: Vec<String> = config::KNOWN_KEYS.iter().map(|(key, _)| key.to_string()).collect();
+    for layer in [user, project, &env.config] {
+        keys.extend(layer.entries().map(|(key, _)| key.to_string()));
+    }
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let from_env = env.applied.iter().find(|(_, k)| *k == key);
+            let (value, origin) = if let (Some((var, _)), Some(value)) = (from_env, env.config.get(&key)) {
+                (value.to_string(), Origin::Env(var.clone()))
+            } else if let Some(value) = project.get(&key) {
+                (value.to_string(), Origin::Project)
+            } else if let Some(value) = user.get(&key) {
+                (value.to_string(), Origin::User)
+            } else {
+                let default = config::KNOWN_KEYS.iter().find(|(k, _)| *k == key).and_then(|(_, d)| *d)?;
+                (default.to_string(), Origin::Default)
+            };
+            Some(Setting { key, value, origin })
+        })
+        .collect()
+}
+
+/// `key = value  (origin)` lines
+fn render_resolved(settings: &[Setting]) -> String {
+    let width = settings.iter().map(|s| s.key.len() + s.value.len() + 3).max().unwrap_or(0);
+    settings
+        .iter()
+        .map(|setting| {
+            let origin = match &setting.origin {
+                Origin::Env(var) => format!("env {}", var),
+                Origin::Project => "project .legend/config.toml".to_string(),
+                Origin::User => "user ~/.config/legend/config.toml".to_string(),
+                Origin::Default => "default".to_string(),
+            };
+            let assignment = format!("{} = {}", setting.key, setting.value);
+            format!("{:<width$}  ({})\n", assignment, origin, width = width)
+        })
+        .collect()
+}
+
+fn open_editor(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let editor = config::load_config()?
+        .get("editor")
+        .map(str::to_string)
+        .or_else(|| std::env::var("VISUAL").ok().filter(|v| !v.is_empty()))
+        .or_else(|| std::env::var("EDITOR").ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "vi".to_string());
+
+    // Through sh so editors with arguments ("code --wait") work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()
+        .map_err(|e| format!("Failed to run editor '{}': {}", editor, e))?;
+    if !status.success() {
+        return Err(format!("Editor '{}' exited with {}", editor, status).into());
+    }
+
+    // Catch typos before the next command trips over them
+    config::load_config_file(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_entries() {
+        let config = config::parse_config("editor = \"vim\"\n[recency]\nhalf_life_days = 3\n").unwrap();
+        assert_eq!(render_entries(&config), "editor = vim\nrecency.half_life_days = 3\n");
+    }
+
+    #[test]
+    fn test_resolve_layers_precedence() {
+        let user = config::parse_config("editor = \"vim\"\n[theme]\nname = \"plain\"\n").unwrap();
+        let project = config::parse_config("[theme]\nname = \"colorblind\"\n[recency]\nhalf_life_days = 10\n").unwrap();
+        let vars = [("LEGEND_RECENCY_HALF_LIFE_DAYS".to_string(), "14".to_string())].into_iter();
+        let env = config::env_overrides(vars, &project);
+
+        let settings = resolve_layers(&user, &project, &env);
+        let find = |key: &str| settings.iter().find(|s| s.key == key).cloned().unwrap();
+
+        assert_eq!((find("editor").value, find("editor").origin), ("vim".to_string(), Origin::User));
+        assert_eq!(find("theme.name").origin, Origin::Project);
+        assert_eq!(find("recency.half_life_days").value, "14");
+        assert_eq!(
+            find("recency.half_life_days").origin,
+            Origin::Env("LEGEND_RECENCY_HALF_LIFE_DAYS".to_string())
+        );
+        assert_eq!(find("ids.namespace").origin, Origin::Default);
+        assert!(settings.iter().all(|s| s.key != "signing.key"));
+    }
+}
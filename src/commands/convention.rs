@@ -0,0 +1,138 @@
+// Convention command - the house rules every change should follow
+//
+// Usage:
+//   legend convention add "<rule>"          - e.g. "no unwrap in prod code"
+//   legend convention remove <n>|"<rule>"   - by number (from list) or text
+//   legend convention list [--json]         - numbered, in the order added
+//
+// Unlike a decision, a convention has no context or date: it is one line
+// Claude should keep in mind on every edit. get_state includes the list,
+// and the UserPromptSubmit hook repeats it with each prompt so it is in
+// view when the code gets written, not just at session start.
+
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::LegendState;
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend convention add \"<rule>\"\n       legend convention remove <n>|\"<rule>\"\n       legend convention list [--json]";
+
+/// Handle the convention command
+pub fn handle_convention(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (command, rest) = args.split_first().ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    match (command.as_str(), rest) {
+        ("add", [rule]) => add(rule),
+        ("remove", [which]) => remove(which),
+        ("list", []) => list(false),
+        ("list", [flag]) if flag == "--json" => list(true),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn add(rule: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let rule = rule.trim();
+    if rule.is_empty() {
+        return Err(ExitError::new(exit::USAGE, format!("The convention is empty\n{}", USAGE)).into());
+    }
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    if state.conventions.iter().any(|c| c == rule) {
+        output::info("That convention is already recorded.");
+        return Ok(());
+    }
+    state.conventions.push(rule.to_string());
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let number = state.conventions.len();
+    output::diag(
+        output::Level::Info,
+        &format!("Added convention {}.", number),
+        json!({"number": number}),
+    );
+    Ok(())
+}
+
+fn remove(which: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = find(&state, which).ok_or_else(|| format!("No convention {}. See 'legend convention list'.", which))?;
+    let removed = state.conventions.remove(index);
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Removed convention: {}", removed),
+        json!({"number": index + 1}),
+    );
+    Ok(())
+}
+
+fn list(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let state = storage::load_state()?;
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&state.conventions)?);
+    } else if state.conventions.is_empty() {
+        output::info("No conventions yet. Add one with 'legend convention add \"<rule>\"'.");
+    } else {
+        for (i, rule) in state.conventions.iter().enumerate() {
+            println!("{:>2}. {}", i + 1, rule);
+        }
+    }
+    Ok(())
+}
+
+/// Index of a convention given its 1-based number or its exact text
+fn find(state: &LegendState, which: &str) -> Option<usize> {
+    match which.trim().parse::<usize>() {
+        Ok(n) if n >= 1 && n <= state.conventions.len() => Some(n - 1),
+        _ => state.conventions.iter().position(|c| c == which.trim()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    #[test]
+    fn test_add_and_remove() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        handle_convention(&["add", "use snake_case for table names"].map(String::from)).unwrap();
+        handle_convention(&["add", " no unwrap in prod code "].map(String::from)).unwrap();
+        handle_convention(&["add", "no unwrap in prod code"].map(String::from)).unwrap();
+        assert!(handle_convention(&["add", "  "].map(String::from)).is_err());
+        assert_eq!(storage::load_state().unwrap().conventions.len(), 2);
+
+        handle_convention(&["remove", "1"].map(String::from)).unwrap();
+        assert!(handle_convention(&["remove", "7"].map(String::from)).is_err());
+        handle_convention(&["remove", "no unwrap in prod code"].map(String::from)).unwrap();
+        assert!(storage::load_state().unwrap().conventions.is_empty());
+    }
+
+    #[test]
+    fn test_parallel_adds_keep_every_convention() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let dir = storage::legend_dir();
+        std::thread::scope(|scope| {
+            for writer in ["a", "b"] {
+                let dir = dir.clone();
+                scope.spawn(move || {
+                    storage::TEST_LEGEND_DIR.with(|slot| *slot.borrow_mut() = Some(dir));
+                    for n in 0..15 {
+                        handle_convention(&["add".to_string(), format!("rule {}{}", writer, n)]).unwrap();
+                    }
+                });
+            }
+        });
+        // Without the lock across load and save, one writer's save drops
+        // what the other added in between
+        assert_eq!(storage::load_state().unwrap().conventions.len(), 30);
+    }
+}
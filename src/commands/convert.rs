@@ -0,0 +1,91 @@
+// Convert command - switch the state file between binary and JSON
+//
+// Usage:
+//   legend convert --format json     - pretty JSON, readable in a diff
+//   legend convert --format binary   - bincode + LZ4 (the default)
+//
+// Sets storage.format in .legend/config.toml so later saves keep the new
+// format, then rewrites the state file in it. Loading reads either (see
+// storage.rs), so nothing else has to change.
+
+use crate::config;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage::{self, Format};
+use serde_json::json;
+use std::fs;
+
+const USAGE: &str = "Usage: legend convert --format <binary|json>";
+
+/// Handle the convert command
+pub fn handle_convert(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let target = match args {
+        [flag, name] if flag == "--format" => {
+            Format::parse(name).map_err(|e| ExitError::new(exit::USAGE, format!("{}\n{}", e, USAGE)))?
+        }
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let state = storage::load_state()?;
+    let path = storage::state_file();
+    let before = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+
+    config::set_project_value("storage.format", target.name())?;
+    let configured = Format::configured()?;
+    if configured != target {
+        return Err(format!(
+            "storage.format is set to {} in {}, but {} still wins (see legend config show --resolved)",
+            target.name(),
+            config::config_path().display(),
+            configured.name()
+        )
+        .into());
+    }
+    storage::save_state(&state)?;
+
+    let after = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    output::diag(
+        output::Level::Info,
+        &format!("Converted {} to {} ({} bytes, was {}).", path.display(), target.name(), after, before),
+        json!({"format": target.name(), "bytes": after, "previous_bytes": before}),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::Feature;
+
+    #[test]
+    fn test_convert_roundtrip() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let mut state = storage::load_state().unwrap();
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "web".to_string(), "d".to_string());
+        feature.complexity = Some(2);
+        state.features.push(feature);
+        state.focus.push("auth".to_string());
+        storage::save_state(&state).unwrap();
+
+        handle_convert(&["--format", "json"].map(String::from)).unwrap();
+        let text = fs::read_to_string(storage::state_file()).unwrap();
+        assert!(text.starts_with("{\n  \"format_version\": "), "{}", text);
+        assert!(text.contains("\"id\": \"auth\""));
+        assert_eq!(Format::configured().unwrap(), Format::Json);
+
+        // Later saves stay JSON, and load the same state
+        let loaded = storage::load_state().unwrap();
+        storage::save_state(&loaded).unwrap();
+        assert_eq!(Format::detect(&fs::read(storage::state_file()).unwrap()), Format::Json);
+        assert_eq!(loaded.features[0].complexity, Some(2));
+        assert_eq!(loaded.focus, vec!["auth"]);
+
+        handle_convert(&["--format", "binary"].map(String::from)).unwrap();
+        assert!(fs::read(storage::state_file()).unwrap().starts_with(b"LGND"));
+        assert_eq!(storage::load_state().unwrap().features[0].id, "auth");
+
+        assert!(handle_convert(&["--format", "yaml"].map(String::from)).is_err());
+    }
+}
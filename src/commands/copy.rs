@@ -0,0 +1,214 @@
+// Copy command - clone a feature into another project's Legend state
+//
+// Usage:
+//   legend copy <id> --to <project>             - copy under the same id
+//   legend copy <id> --to <project> --as <new>  - copy under a new id
+//   legend copy <id> --to <project> --with-notes --with-decisions
+//
+// The target is a project name from the registry (`legend projects`), a
+// project directory with its own .legend, or a Legend directory itself
+// (one kept elsewhere with --dir/LEGEND_DIR). It needs `legend init`
+// first. If the id is already taken there, the copy becomes <id>-2,
+// <id>-3, ... so nothing in the target is overwritten.
+//
+// The copy is new work in the other project: status goes back to Pending,
+// timestamps start now, and the context records where it came from.
+// Description, domain, tags, and files are kept as a starting point.
+//
+// Notes and decisions are this project's history, so they stay behind
+// unless asked for: --with-notes brings the notes along, and
+// --with-decisions records each linked decision again in the target
+// (under its next free number), linked to the copy.
+
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::registry;
+use crate::storage;
+use crate::types::{Decision, DecisionLink, Feature, FeatureStatus, LegendState};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str =
+    "Usage: legend copy <id> --to <project> [--as <new-id>] [--with-notes] [--with-decisions]";
+
+/// Handle the copy command
+pub fn handle_copy(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut id = None;
+    let mut to = None;
+    let mut new_id = None;
+    let mut with_notes = false;
+    let mut with_decisions = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--to" => {
+                i += 1;
+                to = Some(args.get(i).ok_or_else(|| format!("--to expects a project name or directory\n{}", USAGE))?.clone());
+            }
+            "--as" => {
+                i += 1;
+                new_id = Some(args.get(i).ok_or_else(|| format!("--as expects an id\n{}", USAGE))?.clone());
+            }
+            "--with-notes" => with_notes = true,
+            "--with-decisions" => with_decisions = true,
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => id = Some(other.to_string()),
+        }
+        i += 1;
+    }
+
+    let (id, to) = match (id, to) {
+        (Some(id), Some(to)) => (id, to),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let source = storage::load_state()?;
+    let feature = source
+        .find_feature(&id)
+        .ok_or_else(|| format!("Feature '{}' not found", id))?;
+
+    let target_dir = target_legend_dir(&to)?;
+    let copied_id = with_legend_dir(target_dir, || {
+        if !storage::is_initialized() {
+            return Err(ExitError::new(
+                exit::NOT_INITIALIZED,
+                format!("{} has no Legend state. Run 'legend init' there first.", to),
+            )
+            .into());
+        }
+        let _lock = storage::lock_state()?;
+        let mut target = storage::load_state()?;
+        let before = target.clone();
+
+        let mut copy = copy_feature(feature, &source.project_name, new_id.as_deref(), &target);
+        if with_notes {
+            copy.notes = feature.notes.clone();
+        }
+        if with_decisions {
+            copy_decisions(feature, &source.decisions, &mut copy, &mut target);
+        }
+        let copied_id = copy.id.clone();
+        target.add_feature(copy);
+
+        journal::commit(&before, &target)?;
+        Ok(copied_id)
+    })?;
+
+    if copied_id == id {
+        println!("Copied {} to {}", id, to);
+    } else {
+        println!("Copied {} to {} as {} (id was taken)", id, to, copied_id);
+    }
+
+    Ok(())
+}
+
+/// The Legend directory `--to` names: a project directory's .legend, a
+/// Legend directory itself, or else a registered project
+fn target_legend_dir(to: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = Path::new(to);
+    if !path.is_dir() {
+        return registry::resolve(to);
+    }
+    let nested = path.join(storage::DEFAULT_LEGEND_DIR);
+    Ok(if nested.is_dir() { nested } else { path.to_path_buf() })
+}
+
+/// Run `f` with storage (state, journal) pointed at another .legend
+fn with_legend_dir<T, F>(dir: PathBuf, f: F) -> Result<T, Box<dyn std::error::Error>>
+where
+    F: FnOnce() -> Result<T, Box<dyn std::error::Error>>,
+{
+    let previous = storage::legend_dir();
+    storage::set_legend_dir(dir);
+    let result = f();
+    storage::set_legend_dir(previous);
+    result
+}
+
+/// The feature as it should appear in the target project
+fn copy_feature(feature: &Feature, source_project: &str, new_id: Option<&str>, target: &LegendState) -> Feature {
+    let base = new_id.unwrap_or(&feature.id);
+    let mut id = base.to_string();
+    let mut n = 2;
+    while target.find_feature(&id).is_some() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let mut copy = Feature::new(id, feature.name.clone(), feature.domain.clone(), feature.description.clone());
+    copy.tags = feature.tags.clone();
+    copy.files_involved = feature.files_involved.clone();
+    copy.status = FeatureStatus::Pending;
+
+    let origin = format!("Copied from {} ({})", source_project, feature.id);
+    copy.context = Some(match &feature.context {
+        Some(context) => format!("{}\n\n{}", context, origin),
+        None => origin,
+    });
+    copy
+}
+
+/// Record the decisions linked to `feature` again in `target`, numbered
+/// after the ones there, and link them to `copy`
+fn copy_decisions(feature: &Feature, decisions: &[Decision], copy: &mut Feature, target: &mut LegendState) {
+    for link in &feature.decisions {
+        let Some(decision) = decisions.iter().find(|d| d.id == link.id) else { continue };
+        let id = target.decisions.iter().map(|d| d.id).max().unwrap_or(0) + 1;
+        target.decisions.push(Decision {
+            id,
+            feature_ids: vec![copy.id.clone()],
+            ..decision.clone()
+        });
+        copy.decisions.push(DecisionLink { id, title: decision.title.clone() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_feature_remaps_taken_id() {
+        let mut feature = Feature::new("rate-limit".to_string(), "Rate limiting".to_string(), "api".to_string(), "Token bucket".to_string());
+        feature.status = FeatureStatus::Complete;
+        feature.tags = vec!["backend".to_string()];
+
+        let mut target = LegendState::new("gateway".to_string());
+        target.features.push(Feature::new("rate-limit".to_string(), "Other".to_string(), "api".to_string(), "x".to_string()));
+
+        let copy = copy_feature(&feature, "billing", None, &target);
+        assert_eq!(copy.id, "rate-limit-2");
+        assert_eq!(copy.status, FeatureStatus::Pending);
+        assert_eq!(copy.tags, vec!["backend"]);
+        assert_eq!(copy.context.as_deref(), Some("Copied from billing (rate-limit)"));
+
+        let renamed = copy_feature(&feature, "billing", Some("throttling"), &target);
+        assert_eq!(renamed.id, "throttling");
+    }
+
+    #[test]
+    fn test_copy_decisions_renumbers_in_target() {
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "web".to_string(), "Login".to_string());
+        feature.decisions = vec![DecisionLink { id: 4, title: "Use JWT".to_string() }];
+        let decisions = vec![Decision {
+            id: 4,
+            title: "Use JWT".to_string(),
+            rationale: "Stateless".to_string(),
+            feature_ids: vec!["auth".to_string(), "api".to_string()],
+            decided_at: 100,
+        }];
+
+        let mut target = LegendState::new("gateway".to_string());
+        target.decisions.push(Decision { id: 1, title: "Other".to_string(), ..decisions[0].clone() });
+        let mut copy = copy_feature(&feature, "web", None, &target);
+        copy_decisions(&feature, &decisions, &mut copy, &mut target);
+
+        assert_eq!(copy.decisions, vec![DecisionLink { id: 2, title: "Use JWT".to_string() }]);
+        let copied = &target.decisions[1];
+        assert_eq!((copied.id, copied.rationale.as_str()), (2, "Stateless"));
+        assert_eq!(copied.feature_ids, vec!["auth"]);
+    }
+}
@@ -0,0 +1,182 @@
+// Coverage command - how much of the codebase Legend knows about
+//
+// Walks the project, keeps source files (by extension), and reports the
+// share that at least one feature lists in files_involved. Untracked
+// files are context Claude will never be told about.
+//
+// Usage:
+//   legend coverage                  - report coverage and untracked files
+//   legend coverage --min 80         - exit 3 if coverage is below 80%
+//   legend coverage --format github  - GitHub Actions annotations
+
+use crate::commands::discover;
+use crate::config::{self, LimitsConfig};
+use crate::exit::{self, ExitError};
+use crate::findings::{self, Finding, OutputFormat};
+use crate::storage;
+use crate::types::Feature;
+use std::path::Path;
+
+/// Extensions that count as source code
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "py", "go", "java", "kt", "rb", "c", "h", "cc", "cpp",
+    "hpp", "cs", "swift", "php", "scala", "ex", "exs", "vue", "svelte",
+];
+
+/// Result of a coverage run
+struct CoverageReport {
+    total: usize,
+    untracked: Vec<String>,
+}
+
+impl CoverageReport {
+    fn covered(&self) -> usize {
+        self.total - self.untracked.len()
+    }
+
+    /// Percentage of source files tracked (100% for an empty project)
+    fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.covered() as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// Handle the coverage command
+pub fn handle_coverage(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (min, format) = parse_args(args)?;
+    let state = storage::load_state()?;
+
+    let limits = LimitsConfig::from_config(&config::load_config()?)?;
+    let files = discover::collect_files(&storage::project_root(), &limits)?;
+    let report = measure_coverage(&state.features, &files);
+
+    let findings: Vec<Finding> = report
+        .untracked
+        .iter()
+        .map(|file| {
+            Finding::warning(None, "source file not tracked by any feature".to_string())
+                .with_file(file)
+        })
+        .collect();
+
+    findings::print_findings(&findings, format, "coverage");
+
+    let summary = format!(
+        "Coverage: {}/{} source files ({:.1}%) tracked by features",
+        report.covered(),
+        report.total,
+        report.percent()
+    );
+    match format {
+        OutputFormat::Text => println!("{}", summary),
+        OutputFormat::Github => println!("::notice title=legend coverage::{}", summary),
+    }
+
+    if let Some(min) = min {
+        if report.percent() < min {
+            let message = format!("coverage {:.1}% is below the minimum {}%", report.percent(), min);
+            if format == OutputFormat::Github {
+                println!("::error title=legend coverage::{}", message);
+            }
+            return Err(Box::new(ExitError::new(exit::CHECK_FAILED, message)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `--min <percent>` and `--format <text|github>`
+fn parse_args(
+    args: &[String],
+) -> Result<(Option<f64>, OutputFormat), Box<dyn std::error::Error>> {
+    let mut min = None;
+    let mut format = OutputFormat::Text;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--min" => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, "--min requires a percentage"))?;
+                let percent = value
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|p| (0.0..=100.0).contains(p))
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("Invalid percentage: {} (expected 0 to 100)", value)))?;
+                min = Some(percent);
+            }
+            "--format" => {
+                i += 1;
+                format = OutputFormat::parse(
+                    args.get(i).ok_or_else(|| ExitError::new(exit::USAGE, "--format requires a value"))?,
+                )?;
+            }
+            other => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}", other)).into()),
+        }
+        i += 1;
+    }
+
+    Ok((min, format))
+}
+
+fn is_source_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|ext| SOURCE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Split the project's source files into tracked and untracked
+fn measure_coverage(features: &[Feature], files: &[String]) -> CoverageReport {
+    let source_files: Vec<&String> = files.iter().filter(|f| is_source_file(f)).collect();
+
+    let untracked = source_files
+        .iter()
+        .filter(|file| !features.iter().any(|feature| feature.involves_file(file)))
+        .map(|file| file.to_string())
+        .collect();
+
+    CoverageReport {
+        total: source_files.len(),
+        untracked,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_coverage() {
+        let mut feature = Feature::new(
+            "core".to_string(),
+            "Core".to_string(),
+            "core".to_string(),
+            "Core code".to_string(),
+        );
+        feature.files_involved = vec!["src/main.rs".to_string(), "src/commands/".to_string()];
+
+        let files: Vec<String> = ["src/main.rs", "src/commands/show.rs", "src/types.rs", "README.md"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let report = measure_coverage(&[feature], &files);
+
+        assert_eq!(report.total, 3);
+        assert_eq!(report.untracked, vec!["src/types.rs"]);
+        assert!((report.percent() - 66.666).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_empty_project_is_fully_covered() {
+        let report = measure_coverage(&[], &[]);
+        assert_eq!(report.percent(), 100.0);
+    }
+}
@@ -0,0 +1,222 @@
+// Decide command - record why an architectural choice was made
+//
+// Usage:
+//   legend decide "<title>" --rationale "<why>"
+//   legend decide "<title>" --rationale "<why>" --feature auth --feature api/sessions
+//   legend decide --list                  - every decision, newest first
+//   legend decisions --feature auth       - just those linked to a feature
+//                                           (`decisions` is `decide --list`)
+//
+// Decisions live in state.lz4 next to the features, so get_state carries
+// them into every session and Claude doesn't relitigate settled choices.
+// `legend search --decisions <keyword>` finds them again.
+//
+// Links go both ways: the decision lists its feature IDs, and each linked
+// feature gets a DecisionLink with the title, so the feature's own entry in
+// get_state says which decisions shape it.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Decision, DecisionLink, LegendState};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend decide \"<title>\" --rationale \"<why>\" [--feature <id>]...\n       legend decide --list [--feature <id>]";
+
+/// Handle the decisions command: `decide --list`
+pub fn handle_decisions(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut list_args = vec!["--list".to_string()];
+    list_args.extend(args.iter().cloned());
+    handle_decide(&list_args)
+}
+
+/// Handle the decide command
+pub fn handle_decide(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut title: Option<String> = None;
+    let mut rationale = None;
+    let mut features: Vec<String> = Vec::new();
+    let mut list = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--list" => list = true,
+            flag @ ("--rationale" | "--feature") => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("{} expects a value\n{}", flag, USAGE)))?
+                    .clone();
+                if flag == "--rationale" {
+                    rationale = Some(value);
+                } else {
+                    features.push(value);
+                }
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other if title.is_none() => title = Some(other.to_string()),
+            _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+        }
+        i += 1;
+    }
+
+    if list {
+        if title.is_some() || rationale.is_some() {
+            return Err(ExitError::new(exit::USAGE, USAGE).into());
+        }
+        let state = storage::load_state()?;
+        let feature_ids = resolve_features(&state, &features)?;
+        let shown: Vec<&Decision> = state
+            .decisions
+            .iter()
+            .rev()
+            .filter(|d| feature_ids.iter().all(|id| d.feature_ids.contains(id)))
+            .collect();
+        if state.decisions.is_empty() {
+            output::info("No decisions recorded yet.");
+        } else if shown.is_empty() {
+            output::info(&format!("No decisions linked to {}.", feature_ids.join(", ")));
+        }
+        for decision in shown {
+            print!("{}", render_decision(decision));
+        }
+        return Ok(());
+    }
+
+    let title = title.filter(|t| !t.trim().is_empty()).ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    let rationale = rationale
+        .filter(|r| !r.trim().is_empty())
+        .ok_or_else(|| ExitError::new(exit::USAGE, format!("A decision needs --rationale: the why is the part worth keeping\n{}", USAGE)))?;
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let feature_ids = resolve_features(&state, &features)?;
+
+    let id = state.decisions.iter().map(|d| d.id).max().unwrap_or(0) + 1;
+    let decision = Decision {
+        id,
+        title: title.trim().to_string(),
+        rationale: rationale.trim().to_string(),
+        feature_ids,
+        decided_at: current_timestamp(),
+    };
+    state.decisions.push(decision);
+    // Every decision, so ones recorded before links went both ways catch up
+    for decision in state.decisions.clone() {
+        link_features(&mut state, &decision);
+    }
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(output::Level::Info, &format!("Recorded decision {}: {}", id, title.trim()), json!({"id": id}));
+    Ok(())
+}
+
+/// Canonical IDs for `--feature` arguments, without repeats
+fn resolve_features(state: &LegendState, features: &[String]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut feature_ids = Vec::new();
+    for id in features {
+        let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+        let id = state.features[index].id.clone();
+        if !feature_ids.contains(&id) {
+            feature_ids.push(id);
+        }
+    }
+    Ok(feature_ids)
+}
+
+/// Give each feature the decision names a link back to it
+fn link_features(state: &mut LegendState, decision: &Decision) {
+    let now = current_timestamp();
+    for feature in state.features.iter_mut().filter(|f| decision.feature_ids.contains(&f.id)) {
+        if !feature.decisions.iter().any(|d| d.id == decision.id) {
+            feature.decisions.push(DecisionLink {
+                id: decision.id,
+                title: decision.title.clone(),
+            });
+            feature.last_updated = now;
+        }
+    }
+}
+
+/// Does the decision mention `keyword` (case-insensitive) in its title,
+/// rationale, or feature IDs?
+pub fn matches_decision(decision: &Decision, keyword: &str) -> bool {
+    let keyword = keyword.to_lowercase();
+    decision.title.to_lowercase().contains(&keyword)
+        || decision.rationale.to_lowercase().contains(&keyword)
+        || decision.feature_ids.iter().any(|id| id.to_lowercase().contains(&keyword))
+}
+
+/// `#3 2024-03-01  Use SQLite  [auth, sync]` and the indented rationale
+fn render_decision(decision: &Decision) -> String {
+    let features = if decision.feature_ids.is_empty() {
+        String::new()
+    } else {
+        format!("  [{}]", decision.feature_ids.join(", "))
+    };
+    format!(
+        "#{} {}  {}{}\n    {}\n",
+        decision.id,
+        dates::format_date(decision.decided_at),
+        decision.title,
+        features,
+        decision.rationale
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision() -> Decision {
+        Decision {
+            id: 2,
+            title: "Store state as bincode".to_string(),
+            rationale: "Reads must stay under 5ms".to_string(),
+            feature_ids: vec!["storage".to_string()],
+            decided_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_matches_decision() {
+        let decision = decision();
+        assert!(matches_decision(&decision, "BINCODE"));
+        assert!(matches_decision(&decision, "5ms"));
+        assert!(matches_decision(&decision, "storage"));
+        assert!(!matches_decision(&decision, "json"));
+    }
+
+    #[test]
+    fn test_decide_links_both_ways() {
+        let _env = crate::testing::TestEnv::initialized(1_000);
+        let mut state = storage::load_state().unwrap();
+        for id in ["auth", "storage"] {
+            state.features.push(crate::types::Feature::new(id.to_string(), id.to_string(), "d".to_string(), "d".to_string()));
+        }
+        storage::save_state(&state).unwrap();
+
+        handle_decide(&["Use sessions", "--rationale", "Simpler", "--feature", "auth"].map(String::from)).unwrap();
+        handle_decide(&["Use bincode", "--rationale", "Fast", "--feature", "storage"].map(String::from)).unwrap();
+
+        let state = storage::load_state().unwrap();
+        assert_eq!(state.features[0].decisions, vec![DecisionLink { id: 1, title: "Use sessions".to_string() }]);
+        assert_eq!(state.features[1].decisions[0].id, 2);
+        assert!(handle_decisions(&["--feature", "missing"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn test_render_decision() {
+        assert_eq!(
+            render_decision(&decision()),
+            "#2 1970-01-01  Store state as bincode  [storage]\n    Reads must stay under 5ms\n"
+        );
+    }
+}
@@ -0,0 +1,497 @@
+// Dedupe command - find features that describe the same thing and merge them
+//
+// Usage:
+//   legend dedupe                    - propose merges (and ask, on a terminal)
+//   legend dedupe --yes              - merge every proposal without asking
+//   legend dedupe --json             - proposals as JSON, nothing is changed
+//   legend dedupe --threshold <0-1>  - how similar is "the same" (default 0.6)
+//
+// Two features are compared by name (character bigrams), description
+// (shared words), and files (shared paths, when both list some). Pairs at
+// or above the threshold are linked, and linked features form a cluster.
+//
+// Merging a cluster keeps its oldest feature (so the older created_at and
+// ID survive) and folds the others into it: tags and files are combined,
+// context is appended, metadata keys the keeper lacks are copied over.
+// The duplicates are then removed.
+//
+// Rust concepts in this file:
+// - A tiny union-find to group pairs into clusters
+// - BTreeSet for word sets with deterministic order
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, LegendState, Relation};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::io::{BufRead, IsTerminal, Write};
+
+const USAGE: &str = "Usage: legend dedupe [--threshold <0-1>] [--yes | --json]";
+
+const DEFAULT_THRESHOLD: f64 = 0.6;
+
+/// A feature proposed for merging into a cluster's keeper
+#[derive(Debug, Clone, Serialize)]
+struct Candidate {
+    id: String,
+    /// Similarity to the keeper, 0.0 - 1.0
+    similarity: f64,
+}
+
+/// Features that look like one feature
+#[derive(Debug, Clone, Serialize)]
+struct Cluster {
+    /// The oldest feature; the others are merged into it
+    keep: String,
+    merge: Vec<Candidate>,
+}
+
+/// What to do with a proposal
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Answer {
+    Merge,
+    Skip,
+    Quit,
+}
+
+/// Handle the dedupe command
+pub fn handle_dedupe(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut threshold = DEFAULT_THRESHOLD;
+    let mut yes = false;
+    let mut json_out = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--threshold" => {
+                i += 1;
+                threshold = args
+                    .get(i)
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|t| (0.0..=1.0).contains(t))
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--threshold expects a number from 0 to 1\n{}", USAGE)))?;
+            }
+            "--yes" => yes = true,
+            "--json" => json_out = true,
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+
+    let before = storage::load_state()?;
+    let clusters = find_clusters(&before.features, threshold);
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&clusters)?);
+        return Ok(());
+    }
+    if clusters.is_empty() {
+        output::diag(output::Level::Info, "No duplicate features found.", json!({"clusters": 0}));
+        return Ok(());
+    }
+
+    let interactive = !yes && std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    if !yes && !interactive {
+        print!("{}", render_clusters(&before, &clusters));
+        output::info("Run 'legend dedupe --yes' to merge these, or run it on a terminal to choose.");
+        return Ok(());
+    }
+
+    let mut state = before.clone();
+    let mut merged = 0;
+    for cluster in &clusters {
+        let answer = if yes { Answer::Merge } else { prompt_cluster(&state, cluster)? };
+        match answer {
+            Answer::Merge => merged += merge_cluster(&mut state, cluster, current_timestamp()),
+            Answer::Skip => continue,
+            Answer::Quit => break,
+        }
+    }
+
+    if merged == 0 {
+        output::info("Nothing merged.");
+        return Ok(());
+    }
+
+    state.touch();
+    let _lock = storage::lock_unchanged(&before)?;
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Merged {} duplicate feature(s); {} remain.", merged, state.features.len()),
+        json!({"merged": merged, "features": state.features.len()}),
+    );
+    Ok(())
+}
+
+/// Group features whose pairwise similarity reaches `threshold`
+///
+/// Clusters come out in state order of their keeper; within a cluster,
+/// candidates are most similar first
+fn find_clusters(features: &[Feature], threshold: f64) -> Vec<Cluster> {
+    let n = features.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for a in 0..n {
+        for b in (a + 1)..n {
+            if similarity(&features[a], &features[b]) >= threshold {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[rb] = ra;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: Vec<Option<usize>> = vec![None; n];
+    for i in 0..n {
+        let r = root(&mut parent, i);
+        match group_of_root[r] {
+            Some(g) => groups[g].push(i),
+            None => {
+                group_of_root[r] = Some(groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| {
+            // Oldest wins; min_by_key keeps the first on ties (state order)
+            let keep = *group.iter().min_by_key(|&&i| features[i].created_at).unwrap();
+            let mut merge: Vec<Candidate> = group
+                .iter()
+                .filter(|&&i| i != keep)
+                .map(|&i| Candidate {
+                    id: features[i].id.clone(),
+                    similarity: round2(similarity(&features[keep], &features[i])),
+                })
+                .collect();
+            merge.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+            Cluster { keep: features[keep].id.clone(), merge }
+        })
+        .collect()
+}
+
+/// How alike two features are, 0.0 - 1.0
+///
+/// Name and description weigh equally; files count when both have some
+fn similarity(a: &Feature, b: &Feature) -> f64 {
+    let name = dice(&a.name, &b.name);
+    let description = jaccard(&words(&a.description), &words(&b.description));
+    if a.files_involved.is_empty() || b.files_involved.is_empty() {
+        return (name + description) / 2.0;
+    }
+    let files = jaccard(
+        &a.files_involved.iter().map(|f| f.trim_start_matches("./").to_string()).collect(),
+        &b.files_involved.iter().map(|f| f.trim_start_matches("./").to_string()).collect(),
+    );
+    0.4 * name + 0.4 * description + 0.2 * files
+}
+
+/// Dice coefficient over character bigrams of the lowercased, alphanumeric text
+fn dice(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (a, mut b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for pair in &a {
+        if let Some(pos) = b.iter().position(|p| p == pair) {
+            b.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+/// Lowercased words of three or more letters (skips "a", "to", "of"...)
+fn words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Shared items over all items
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Merge a cluster's candidates into its keeper; returns how many were merged
+fn merge_cluster(state: &mut LegendState, cluster: &Cluster, now: i64) -> usize {
+    let mut merged = 0;
+    for candidate in &cluster.merge {
+        let Some(index) = state.features.iter().position(|f| f.id == candidate.id) else {
+            continue;
+        };
+        let duplicate = state.features.remove(index);
+        if let Some(keeper) = state.find_feature_mut(&cluster.keep) {
+            absorb(keeper, duplicate);
+            keeper.last_updated = now;
+            merged += 1;
+        }
+        redirect_dependencies(state, &candidate.id, &cluster.keep);
+    }
+    merged
+}
+
+/// Point depends_on entries, parents, blockers, and relations (and
+/// milestones, goals, decisions, and the focus stack) at `to` instead of
+/// the merged-away `from`
+fn redirect_dependencies(state: &mut LegendState, from: &str, to: &str) {
+    let lists = state.milestones.iter_mut().map(|m| &mut m.feature_ids);
+    let lists = lists.chain(state.goals.iter_mut().map(|g| &mut g.feature_ids));
+    let lists = lists.chain(std::iter::once(&mut state.focus));
+    for feature_ids in lists.chain(state.decisions.iter_mut().map(|d| &mut d.feature_ids)) {
+        if let Some(index) = feature_ids.iter().position(|id| id == from) {
+            feature_ids.remove(index);
+            if !feature_ids.iter().any(|id| id == to) {
+                feature_ids.insert(index, to.to_string());
+            }
+        }
+    }
+    for feature in &mut state.features {
+        let mut relations: Vec<Relation> = Vec::new();
+        for mut relation in feature.relations.drain(..) {
+            if relation.target == from {
+                relation.target = to.to_string();
+            }
+            if relation.target != feature.id && !relations.contains(&relation) {
+                relations.push(relation);
+            }
+        }
+        feature.relations = relations;
+
+        if feature.parent_id.as_deref() == Some(from) {
+            feature.parent_id = Some(to.to_string()).filter(|p| p != &feature.id);
+        }
+        if feature.blocked_by.as_deref() == Some(from) {
+            feature.blocked_by = Some(to.to_string()).filter(|b| b != &feature.id);
+        }
+
+        if !feature.depends_on.iter().any(|d| d == from) {
+            continue;
+        }
+        let mut deps = Vec::new();
+        for dep in feature.depends_on.drain(..) {
+            let dep = if dep == from { to.to_string() } else { dep };
+            if dep != feature.id && !deps.contains(&dep) {
+                deps.push(dep);
+            }
+        }
+        feature.depends_on = deps;
+    }
+}
+
+/// Fold `other` into `keeper`
+fn absorb(keeper: &mut Feature, other: Feature) {
+    for tag in other.tags {
+        if !keeper.tags.contains(&tag) {
+            keeper.tags.push(tag);
+        }
+    }
+    for file in other.files_involved {
+        if !keeper.files_involved.contains(&file) {
+            keeper.files_involved.push(file);
+        }
+    }
+    for dep in other.depends_on {
+        if dep != keeper.id && !keeper.depends_on.contains(&dep) {
+            keeper.depends_on.push(dep);
+        }
+    }
+    for relation in other.relations {
+        if relation.target != keeper.id && !keeper.relations.contains(&relation) {
+            keeper.relations.push(relation);
+        }
+    }
+    // Subtasks are numbered per feature, so the duplicate's get new numbers
+    for mut task in other.subtasks {
+        if keeper.subtasks.iter().any(|t| t.title == task.title) {
+            continue;
+        }
+        task.id = keeper.subtasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        keeper.subtasks.push(task);
+    }
+    // Time spent on either was spent on the merged feature
+    keeper.logged_hours += other.logged_hours;
+    keeper.estimate_hours = keeper.estimate_hours.or(other.estimate_hours);
+    // The merged feature is at least as hard and as risky as either half
+    keeper.complexity = keeper.complexity.max(other.complexity);
+    keeper.risk = keeper.risk.max(other.risk);
+    // Both logs are kept, interleaved by time
+    keeper.notes.extend(other.notes);
+    keeper.notes.sort_by_key(|n| n.created_at);
+    for link in other.decisions {
+        if !keeper.decisions.iter().any(|d| d.id == link.id) {
+            keeper.decisions.push(link);
+        }
+    }
+    keeper.context = match (keeper.context.take(), other.context) {
+        (Some(ours), Some(theirs)) if ours != theirs => Some(format!("{}\n\n{}", ours, theirs)),
+        (ours, theirs) => ours.or(theirs),
+    };
+    for (key, value) in other.metadata {
+        keeper.metadata.entry(key).or_insert(value);
+    }
+
+    keeper.created_at = keeper.created_at.min(other.created_at);
+    keeper.started_at = match (keeper.started_at, other.started_at) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+}
+
+/// Proposals as readable text, one block per cluster
+fn render_clusters(state: &LegendState, clusters: &[Cluster]) -> String {
+    let mut out = String::new();
+    for cluster in clusters {
+        out.push_str(&format!("keep   {}\n", label(state, &cluster.keep)));
+        for candidate in &cluster.merge {
+            out.push_str(&format!(
+                "merge  {}  ({:.0}% similar)\n",
+                label(state, &candidate.id),
+                candidate.similarity * 100.0
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `id "Name" (created YYYY-MM-DD)`
+fn label(state: &LegendState, id: &str) -> String {
+    match state.find_feature(id) {
+        Some(f) => format!("{} \"{}\" (created {})", f.id, f.name, dates::format_date(f.created_at)),
+        None => id.to_string(),
+    }
+}
+
+fn prompt_cluster(state: &LegendState, cluster: &Cluster) -> Result<Answer, Box<dyn std::error::Error>> {
+    let stdin = std::io::stdin();
+    let mut stderr = std::io::stderr();
+
+    writeln!(stderr, "\nPossible duplicates:")?;
+    write!(stderr, "{}", render_clusters(state, std::slice::from_ref(cluster)))?;
+    loop {
+        write!(stderr, "Merge into {}? [y]es / [n]o / [q]uit: ", cluster.keep)?;
+        stderr.flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(Answer::Quit);
+        }
+        match line.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(Answer::Merge),
+            "n" | "no" => return Ok(Answer::Skip),
+            "q" | "quit" => return Ok(Answer::Quit),
+            _ => continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, name: &str, description: &str, created_at: i64) -> Feature {
+        let mut f = Feature::new(id.to_string(), name.to_string(), "auth".to_string(), description.to_string());
+        f.created_at = created_at;
+        f
+    }
+
+    #[test]
+    fn test_find_clusters() {
+        let features = vec![
+            feature("login-flow", "Login flow", "Users sign in with email and password", 200),
+            feature("billing", "Billing", "Monthly invoices through Stripe", 50),
+            feature("auth-login", "Login", "Users sign in with email and password", 100),
+            feature("user-login", "User login flow", "Sign in with email and password", 300),
+        ];
+
+        let clusters = find_clusters(&features, DEFAULT_THRESHOLD);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].keep, "auth-login");
+        let ids: Vec<_> = clusters[0].merge.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"login-flow") && ids.contains(&"user-login"));
+
+        assert!(find_clusters(&features, 1.0).is_empty());
+        assert!(similarity(&features[0], &features[1]) < 0.2);
+    }
+
+    #[test]
+    fn test_merge_cluster_combines_fields() {
+        let mut keeper = feature("auth-login", "Login", "d", 100);
+        keeper.tags = vec!["backend".to_string()];
+        keeper.files_involved = vec!["src/auth.rs".to_string()];
+        keeper.context = Some("Needed for SSO".to_string());
+        keeper.metadata.insert("tier".to_string(), json!(1));
+
+        let mut dup = feature("login-flow", "Login flow", "d", 50);
+        dup.tags = vec!["backend".to_string(), "security".to_string()];
+        dup.files_involved = vec!["src/auth.rs".to_string(), "src/session.rs".to_string()];
+        dup.context = Some("Blocks the mobile app".to_string());
+        dup.metadata.insert("tier".to_string(), json!(2));
+        dup.metadata.insert("owner".to_string(), json!("sam"));
+        dup.started_at = Some(60);
+
+        let mut checkout = feature("checkout", "Checkout", "d", 300);
+        checkout.depends_on = vec!["login-flow".to_string(), "auth-login".to_string()];
+
+        let mut state = LegendState::new("Demo".to_string());
+        state.features = vec![keeper, dup, checkout];
+        let cluster = Cluster {
+            keep: "auth-login".to_string(),
+            merge: vec![Candidate { id: "login-flow".to_string(), similarity: 0.7 }],
+        };
+
+        assert_eq!(merge_cluster(&mut state, &cluster, 999), 1);
+        assert_eq!(state.features.len(), 2);
+        assert_eq!(state.features[1].depends_on, vec!["auth-login"]);
+        let merged = &state.features[0];
+        assert_eq!(merged.tags, vec!["backend", "security"]);
+        assert_eq!(merged.files_involved, vec!["src/auth.rs", "src/session.rs"]);
+        assert_eq!(merged.context.as_deref(), Some("Needed for SSO\n\nBlocks the mobile app"));
+        assert_eq!(merged.metadata["tier"], json!(1));
+        assert_eq!(merged.metadata["owner"], json!("sam"));
+        assert_eq!((merged.created_at, merged.started_at, merged.last_updated), (50, Some(60), 999));
+    }
+
+    #[test]
+    fn test_dice() {
+        assert_eq!(dice("Login", "login"), 1.0);
+        assert_eq!(dice("", "login"), 0.0);
+        assert!(dice("Login flow", "Login") > 0.5);
+    }
+}
@@ -0,0 +1,313 @@
+// Diag command - collect what a maintainer needs for a bug report
+//
+// Usage:
+//   legend diag bundle            - write .legend/diag-<timestamp>.txt
+//   legend diag bundle --stdout   - print the bundle instead
+//   legend diag health [--json]   - readiness probe; exit 3 if not ready
+//
+// The bundle describes the environment and the .legend directory (file
+// names, sizes, counts, load errors) plus any crash reports. It never
+// includes feature names, descriptions, or other state contents.
+//
+// `diag health` is the check a supervisor runs: can the state be loaded,
+// when was it last saved, how far the journal lags the snapshot, and
+// whether a command holds the state lock (.legend/state.lock; probed
+// without waiting, and a held lock doesn't make it unready). A
+// future serve mode answers /healthz and /readyz with the same report.
+// It also mentions old-style hook entries (see hooks.rs) and a state file
+// in an older format (see migrations.rs), neither of which affects
+// readiness.
+
+use crate::commands::hooks;
+use crate::dates;
+use crate::diagnostics;
+use crate::diff;
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::metrics;
+use crate::migrations;
+use crate::storage;
+use crate::types::current_timestamp;
+use serde::Serialize;
+use std::fs;
+use std::time::UNIX_EPOCH;
+
+/// Handle the diag command
+pub fn handle_diag(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: legend diag bundle [--stdout]\n       legend diag health [--json]";
+
+    match args.first().map(|s| s.as_str()) {
+        Some("health") => {
+            let json_out = match &args[1..] {
+                [] => false,
+                [flag] if flag == "--json" => true,
+                _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+            };
+            if !storage::is_initialized() {
+                return Err(ExitError::new(exit::NOT_INITIALIZED, "not ready: Legend is not initialized here").into());
+            }
+
+            let health = check_health();
+            if json_out {
+                println!("{}", serde_json::to_string_pretty(&health)?);
+            } else {
+                print!("{}", render_health(&health));
+            }
+            if !health.ready {
+                return Err(ExitError::new(exit::CHECK_FAILED, "not ready").into());
+            }
+            Ok(())
+        }
+        Some("bundle") => {
+            let bundle = build_bundle();
+
+            if args.iter().any(|a| a == "--stdout") {
+                println!("{}", bundle);
+            } else {
+                if !storage::legend_dir().is_dir() {
+                    return Err("No .legend directory here. Use --stdout to print the bundle.".into());
+                }
+                let path = storage::legend_path(&format!("diag-{}.txt", current_timestamp()));
+                fs::write(&path, &bundle)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                println!("✓ Wrote diagnostics bundle to {}", path.display());
+                println!("  It contains no feature contents - safe to attach to a bug report.");
+            }
+            Ok(())
+        }
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+/// Readiness report for `legend diag health`
+#[derive(Debug, Serialize)]
+struct Health {
+    /// The state loaded and the journal is readable
+    ready: bool,
+    /// Load error, if state.lz4 couldn't be read
+    state_error: Option<String>,
+    features: usize,
+    /// Format version state.lz4 was saved in (see migrations.rs); older
+    /// ones are upgraded on the next save
+    format_version: Option<u16>,
+    /// Modification time of state.lz4 (Unix seconds)
+    last_save: Option<i64>,
+    journal_events: usize,
+    /// Journal read error, if any
+    journal_error: Option<String>,
+    /// Features whose snapshot the journal doesn't account for; 0 until
+    /// the journal has started
+    journal_backlog: usize,
+    /// Journaled changes not in state.lz4 yet, after a crash or held back
+    /// by storage.snapshot_every (`legend compact`)
+    journal_unapplied: usize,
+    /// Shell-style Legend hooks in .claude/settings.json (`legend hooks upgrade`)
+    legacy_hooks: usize,
+    /// A command holds .legend/state.lock right now
+    locked: bool,
+    /// Why the lock couldn't be checked, if it couldn't
+    lock_error: Option<String>,
+}
+
+fn check_health() -> Health {
+    let last_save = fs::metadata(storage::state_file())
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    let mut health = Health {
+        ready: false,
+        state_error: None,
+        features: 0,
+        format_version: None,
+        last_save,
+        journal_events: 0,
+        journal_error: None,
+        journal_backlog: 0,
+        journal_unapplied: 0,
+        legacy_hooks: hooks::count_legacy(std::path::Path::new(hooks::SETTINGS_PATH)),
+        locked: false,
+        lock_error: None,
+    };
+
+    match storage::state_locked_in(&storage::legend_dir()) {
+        Ok(locked) => health.locked = locked,
+        Err(e) => health.lock_error = Some(e.to_string()),
+    }
+
+    let state = storage::load_state().map_err(|e| health.state_error = Some(e.to_string()));
+    let events = journal::read_events().map_err(|e| health.journal_error = Some(e.to_string()));
+    if let Ok(events) = &events {
+        health.journal_events = events.len();
+        health.journal_unapplied = journal::unapplied_events_in(&storage::legend_dir()).map_or(0, |e| e.len());
+    }
+    if let (Ok(state), Ok(events)) = (&state, &events) {
+        health.features = state.features.len();
+        health.format_version = storage::state_format_version().ok();
+        if !events.is_empty() {
+            let mismatch = diff::diff_states(&journal::state_from_events(state, events), state);
+            health.journal_backlog = mismatch.added.len() + mismatch.removed.len() + mismatch.changed.len();
+        }
+        health.ready = true;
+    }
+    health
+}
+
+/// One `key: value` line per check, then `ready` or `not ready`
+fn render_health(health: &Health) -> String {
+    let mut out = String::new();
+    match &health.state_error {
+        Some(e) => out.push_str(&format!("state: FAILED ({})\n", e)),
+        None => out.push_str(&format!("state: ok ({} features)\n", health.features)),
+    }
+    if let Some(version) = health.format_version.filter(|&v| v < migrations::CURRENT_VERSION) {
+        out.push_str(&format!(
+            "format: v{} (upgraded to v{} on the next save)\n",
+            version,
+            migrations::CURRENT_VERSION
+        ));
+    }
+    match health.last_save {
+        Some(t) => out.push_str(&format!("last save: {} ({}s ago)\n", dates::format_date(t), (current_timestamp() - t).max(0))),
+        None => out.push_str("last save: unknown\n"),
+    }
+    match &health.journal_error {
+        Some(e) => out.push_str(&format!("journal: FAILED ({})\n", e)),
+        None => out.push_str(&format!(
+            "journal: {} events, {} feature(s) not journaled\n",
+            health.journal_events, health.journal_backlog
+        )),
+    }
+    if health.journal_unapplied > 0 {
+        out.push_str(&format!(
+            "journal: {} event(s) not in the snapshot yet (run legend compact)\n",
+            health.journal_unapplied
+        ));
+    }
+    if health.legacy_hooks > 0 {
+        out.push_str(&format!("hooks: {} old-style (run legend hooks upgrade)\n", health.legacy_hooks));
+    }
+    out.push_str(&format!("lock: {}\n", describe_lock(health.locked, health.lock_error.as_deref())));
+    out.push_str(if health.ready { "ready\n" } else { "not ready\n" });
+    out
+}
+
+fn describe_lock(locked: bool, error: Option<&str>) -> String {
+    match (error, locked) {
+        (Some(e), _) => format!("unknown ({})", e),
+        (None, true) => "held (a legend command is saving)".to_string(),
+        (None, false) => "free".to_string(),
+    }
+}
+
+/// Assemble the bundle text
+fn build_bundle() -> String {
+    let mut out = String::new();
+
+    out.push_str("# Legend diagnostics bundle\n\n");
+    out.push_str(&format!("version: {}\n", env!("CARGO_PKG_VERSION")));
+    out.push_str(&format!("platform: {}-{}\n", std::env::consts::OS, std::env::consts::ARCH));
+    out.push_str(&format!("generated_at: {}\n", current_timestamp()));
+
+    out.push_str("\n## State\n\n");
+    out.push_str(&format!("initialized: {}\n", storage::is_initialized()));
+    out.push_str(&format!("state file: {}\n", diagnostics::describe_state_file()));
+    match storage::load_state() {
+        Ok(state) => out.push_str(&format!(
+            "load: ok ({} features, last_updated {})\n",
+            state.features.len(),
+            state.last_updated
+        )),
+        Err(e) => out.push_str(&format!("load: FAILED ({})\n", e)),
+    }
+    match journal::read_events() {
+        Ok(events) => out.push_str(&format!("journal: {} events\n", events.len())),
+        Err(e) => out.push_str(&format!("journal: FAILED ({})\n", e)),
+    }
+    let lock = match storage::state_locked_in(&storage::legend_dir()) {
+        Ok(locked) => describe_lock(locked, None),
+        Err(e) => describe_lock(false, Some(&e.to_string())),
+    };
+    out.push_str(&format!("lock: {}\n", lock));
+    out.push_str(&format!("metrics enabled: {}\n", metrics::is_enabled()));
+
+    out.push_str("\n## .legend directory\n\n");
+    match fs::read_dir(storage::legend_dir()) {
+        Ok(entries) => {
+            let mut lines: Vec<String> = entries
+                .flatten()
+                .map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    match entry.metadata() {
+                        Ok(meta) if meta.is_dir() => format!("{}/\n", name),
+                        Ok(meta) => format!("{} ({} bytes)\n", name, meta.len()),
+                        Err(_) => format!("{} (unreadable)\n", name),
+                    }
+                })
+                .collect();
+            lines.sort();
+            lines.iter().for_each(|l| out.push_str(l));
+        }
+        Err(e) => out.push_str(&format!("unreadable ({})\n", e)),
+    }
+
+    out.push_str("\n## Crash reports\n");
+    let mut reports: Vec<_> = fs::read_dir(diagnostics::crash_dir())
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    reports.sort();
+
+    if reports.is_empty() {
+        out.push_str("\nnone\n");
+    }
+    for path in reports {
+        out.push_str(&format!("\n### {}\n\n", path.display()));
+        out.push_str(&fs::read_to_string(&path).unwrap_or_else(|e| format!("unreadable ({})\n", e)));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::Feature;
+
+    #[test]
+    fn test_health_reports_lock() {
+        let _env = TestEnv::initialized(1_700_000_000);
+
+        let health = check_health();
+        assert!(health.ready);
+        assert!(!health.locked);
+        assert!(render_health(&health).contains("lock: free\n"));
+
+        let _lock = storage::lock_state().unwrap();
+        let health = check_health();
+        assert!(health.ready && health.locked);
+        assert!(render_health(&health).contains("lock: held"));
+    }
+
+    #[test]
+    fn test_bundle_has_crash_reports_but_no_contents() {
+        let _env = TestEnv::initialized(1_700_000_000);
+        let mut state = storage::load_state().unwrap();
+        state.add_feature(Feature::new(
+            "secret-sauce".to_string(),
+            "Secret sauce".to_string(),
+            "core".to_string(),
+            "The recipe".to_string(),
+        ));
+        storage::save_state(&state).unwrap();
+        fs::create_dir_all(diagnostics::crash_dir()).unwrap();
+        fs::write(diagnostics::crash_dir().join("crash-1.txt"), "panic: index out of bounds\n").unwrap();
+
+        let bundle = build_bundle();
+        assert!(bundle.contains("load: ok (1 features"));
+        assert!(bundle.contains("lock: free\n"));
+        assert!(bundle.contains("state.lz4 ("));
+        assert!(bundle.contains("crash-1.txt\n\npanic: index out of bounds"));
+        assert!(!bundle.contains("secret") && !bundle.contains("recipe"));
+    }
+}
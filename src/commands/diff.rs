@@ -0,0 +1,133 @@
+// Diff command - what changed between two saved states
+//
+// Usage:
+//   legend diff                  - the previous save vs the current state
+//   legend diff <a>              - snapshot a vs the current state
+//   legend diff <a> <b>          - snapshot a vs snapshot b
+//   legend diff ... --json       - the same as JSON
+//
+// A snapshot is a number from `legend history` (12 or #12), `current`,
+// or the path of any state.lz4 file (an old checkout, a backup).
+
+use crate::dates;
+use crate::diff::{self, StateDiff};
+use crate::exit::{self, ExitError};
+use crate::history::{self, Snapshot};
+use crate::storage;
+use crate::types::LegendState;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+const USAGE: &str = "Usage: legend diff [<snapshot-a>] [<snapshot-b>] [--json]\n  snapshots: a number from 'legend history', 'current', or a state file path";
+
+/// Handle the diff command
+pub fn handle_diff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let json_out = args.iter().any(|a| a == "--json");
+    let refs: Vec<&str> = args.iter().map(String::as_str).filter(|a| *a != "--json").collect();
+    if let Some(flag) = refs.iter().find(|a| a.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+
+    let ((from_label, from), (to_label, to)) = match refs.as_slice() {
+        [] => (previous()?, resolve("current")?),
+        [a] => (resolve(a)?, resolve("current")?),
+        [a, b] => (resolve(a)?, resolve(b)?),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+    let changes = diff::diff_states(&from, &to);
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&diff_json(&from_label, &to_label, &changes))?);
+    } else {
+        println!("Comparing {} -> {}", from_label, to_label);
+        let lines = diff::render_lines(&changes);
+        if lines.is_empty() {
+            println!("No differences.");
+        }
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// The save before the current state, from history/
+fn previous() -> Result<(String, LegendState), Box<dyn std::error::Error>> {
+    let mut snapshots = history::snapshots_in(&storage::legend_dir());
+    let current = fs::read(storage::state_file())?;
+    history::pop_current(&mut snapshots, &current);
+    let snapshot = snapshots
+        .pop()
+        .ok_or("No earlier version in .legend/history/ to compare with. Every save from now on is kept.")?;
+    load_snapshot(&snapshot)
+}
+
+/// A label and the state for one snapshot argument
+fn resolve(reference: &str) -> Result<(String, LegendState), Box<dyn std::error::Error>> {
+    if reference == "current" {
+        return Ok(("current".to_string(), storage::load_state()?));
+    }
+    if let Ok(seq) = reference.trim_start_matches('#').parse::<u64>() {
+        let snapshot = history::snapshots_in(&storage::legend_dir())
+            .into_iter()
+            .find(|s| s.seq == seq)
+            .ok_or_else(|| format!("No snapshot #{} in .legend/history/ (see 'legend history')", seq))?;
+        return load_snapshot(&snapshot);
+    }
+    let path = Path::new(reference);
+    if path.is_file() {
+        let bytes = fs::read(path)?;
+        return Ok((reference.to_string(), storage::decode_state(&bytes)?));
+    }
+    Err(ExitError::new(exit::USAGE, format!("'{}' is not a snapshot number, 'current', or a state file\n{}", reference, USAGE)).into())
+}
+
+fn load_snapshot(snapshot: &Snapshot) -> Result<(String, LegendState), Box<dyn std::error::Error>> {
+    let state = storage::decode_state(&fs::read(&snapshot.path)?)?;
+    Ok((format!("#{} ({})", snapshot.seq, dates::format_datetime(snapshot.timestamp)), state))
+}
+
+/// `{"from", "to", "added", "removed", "changed": [{"id", "changes"}]}`
+fn diff_json(from: &str, to: &str, changes: &StateDiff) -> Value {
+    let summary = |f: &crate::types::Feature| json!({"id": f.id, "name": f.name});
+    json!({
+        "from": from,
+        "to": to,
+        "added": changes.added.iter().map(summary).collect::<Vec<_>>(),
+        "removed": changes.removed.iter().map(summary).collect::<Vec<_>>(),
+        "changed": changes
+            .changed
+            .iter()
+            .map(|c| json!({"id": c.id, "changes": c.changes}))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::{Feature, FeatureStatus};
+
+    #[test]
+    fn test_diff_against_previous_save() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let mut state = storage::load_state().unwrap();
+        state.features.push(Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "d".to_string()));
+        storage::save_state(&state).unwrap();
+        state.features[0].status = FeatureStatus::Complete;
+        storage::save_state(&state).unwrap();
+
+        let (label, from) = previous().unwrap();
+        let (_, to) = resolve("current").unwrap();
+        let report = diff_json(&label, "current", &diff::diff_states(&from, &to));
+        assert_eq!(report["from"], json!("#2 (2024-03-01 00:00)"));
+        assert_eq!(report["changed"][0]["changes"][0]["field"], json!("status"));
+
+        let (_, first) = resolve("#1").unwrap();
+        let report = diff_json("#1", "current", &diff::diff_states(&first, &to));
+        assert_eq!(report["added"], json!([{"id": "auth", "name": "Auth"}]));
+        assert!(resolve("#9").is_err());
+    }
+}
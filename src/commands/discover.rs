@@ -10,8 +10,14 @@
 // - Pattern matching on file extensions
 // - Building nested data structures
 
+use crate::analysis::{self, DependencyGraph};
+use crate::config::{self, LimitsConfig};
+use crate::guard;
+use crate::output;
+use crate::progress::Progress;
 use serde::Serialize;
-use std::collections::HashMap;
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -23,6 +29,9 @@ pub struct DiscoveryReport {
     directories: Vec<String>,
     potential_features: Vec<SuggestedFeature>,
     total_files: usize,
+    /// Config limits the scan ran into ([limits] in config.toml)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    limits_hit: Vec<String>,
 }
 
 /// A suggested feature inferred from directory structure
@@ -32,6 +41,9 @@ pub struct SuggestedFeature {
     suggested_name: String,
     suggested_domain: String,
     files: Vec<String>,
+    /// Files elsewhere in the project linked by imports (--analyze only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_files: Vec<String>,
 }
 
 /// Directories to skip during traversal
@@ -53,23 +65,43 @@ const SOURCE_ROOTS: &[&str] = &["src", "lib", "app", "pkg"];
 ///
 /// Walks the given directory (or ".") and prints a JSON discovery report
 /// to stdout with a human-readable summary to stderr.
+///
+/// `--analyze` also parses imports (see analysis.rs) so each suggestion
+/// lists the files outside its directory that it is wired to.
+///
+/// The walk stops at `limits.max_files`; the report says so in
+/// `limits_hit` rather than quietly describing part of the project.
+///
+/// The home directory, `/`, and huge trees outside version control are
+/// refused without `--yes-i-mean-it` (see guard.rs).
 pub fn handle_discover(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
-    // Use first argument as path, default to "."
-    let root_path = if args.is_empty() {
-        PathBuf::from(".")
-    } else {
-        PathBuf::from(&args[0])
-    };
+    let analyze = args.iter().any(|a| a == "--analyze");
+    let limits = LimitsConfig::from_config(&config::load_config()?)?;
+
+    // Use first non-flag argument as path, default to "."
+    let root_path = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
 
     // Canonicalize so the report shows an absolute path
     let root_path = fs::canonicalize(&root_path)?;
+    guard::check("discover", &root_path, args)?;
 
     let mut languages: HashMap<String, usize> = HashMap::new();
     let mut all_files: Vec<PathBuf> = Vec::new();
     let mut top_dirs: Vec<String> = Vec::new();
 
     // Walk the directory tree recursively
-    walk_directory(&root_path, &root_path, &mut languages, &mut all_files)?;
+    let progress = Progress::start("discover", None);
+    walk_directory(&root_path, &root_path, &mut languages, &mut all_files, limits.max_files, &progress)?;
+    progress.finish();
+    let mut limits_hit = Vec::new();
+    if all_files.len() >= limits.max_files {
+        output::warning(&max_files_message(limits.max_files));
+        limits_hit.push("limits.max_files".to_string());
+    }
 
     // Collect notable top-level directories (skip hidden/ignored ones)
     if let Ok(entries) = fs::read_dir(&root_path) {
@@ -86,7 +118,18 @@ pub fn handle_discover(args: &[String]) -> Result<(), Box<dyn std::error::Error>
     top_dirs.sort();
 
     // Detect potential features from source root subdirectories
-    let potential_features = detect_features(&root_path, &all_files);
+    let mut potential_features = detect_features(&root_path, &all_files);
+
+    // Import edges cross directory boundaries that the layout heuristic can't see
+    if analyze {
+        let graph = analysis::analyze_project(&root_path, &limits)?;
+        if graph.truncated() {
+            limits_hit.push("limits.max_index_mb".to_string());
+        }
+        for feature in &mut potential_features {
+            feature.related_files = related_files(&graph, &feature.files);
+        }
+    }
 
     let report = DiscoveryReport {
         root: root_path.to_string_lossy().to_string(),
@@ -94,6 +137,7 @@ pub fn handle_discover(args: &[String]) -> Result<(), Box<dyn std::error::Error>
         directories: top_dirs,
         potential_features,
         total_files: all_files.len(),
+        limits_hit,
     };
 
     // JSON to stdout (for Claude)
@@ -101,33 +145,71 @@ pub fn handle_discover(args: &[String]) -> Result<(), Box<dyn std::error::Error>
     println!("{}", json);
 
     // Summary to stderr (for the user)
-    eprintln!("Discovered {} files in {}", report.total_files, report.root);
-    eprintln!(
-        "Languages: {}",
-        format_language_summary(&report.languages)
-    );
-    eprintln!(
-        "Suggested features: {}",
-        report.potential_features.len()
+    output::diag(
+        output::Level::Info,
+        &format!(
+            "Discovered {} files in {}\nLanguages: {}\nSuggested features: {}",
+            report.total_files,
+            report.root,
+            format_language_summary(&report.languages),
+            report.potential_features.len()
+        ),
+        json!({"files": report.total_files, "suggested_features": report.potential_features.len()}),
     );
 
     Ok(())
 }
 
+/// Collect every file under `root` (skipping SKIP_DIRS), relative to root
+///
+/// Shared with commands that need the project's file list (e.g. coverage).
+/// Stops, with a warning, at `limits.max_files`.
+pub fn collect_files(root: &Path, limits: &LimitsConfig) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut languages: HashMap<String, usize> = HashMap::new();
+    let mut files: Vec<PathBuf> = Vec::new();
+    let progress = Progress::start("discover", None);
+    walk_directory(root, root, &mut languages, &mut files, limits.max_files, &progress)?;
+    progress.finish();
+    if files.len() >= limits.max_files {
+        output::warning(&max_files_message(limits.max_files));
+    }
+
+    let mut relative: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.strip_prefix(root).ok())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    relative.sort();
+
+    Ok(relative)
+}
+
+fn max_files_message(max_files: usize) -> String {
+    format!(
+        "Stopped after {} files (limits.max_files); results cover part of the project. Raise it in .legend/config.toml.",
+        max_files
+    )
+}
+
 /// Recursively walk a directory, collecting file extensions and paths
 ///
-/// `root` is the original scan root (for computing relative paths)
-/// `dir` is the current directory being scanned
+/// `dir` is the current directory being scanned; the walk stops once
+/// `files` holds `max_files` paths. Progress shows paths relative to `root`.
 fn walk_directory(
     root: &Path,
     dir: &Path,
     languages: &mut HashMap<String, usize>,
     files: &mut Vec<PathBuf>,
+    max_files: usize,
+    progress: &Progress,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // read_dir returns an iterator of Result<DirEntry>
     let entries = fs::read_dir(dir)?;
 
     for entry in entries {
+        if files.len() >= max_files {
+            break;
+        }
         // Each entry is Result<DirEntry> - ? unwraps the Ok case
         let entry = entry?;
         let path = entry.path();
@@ -143,7 +225,7 @@ fn walk_directory(
             }
 
             // Recurse into subdirectory
-            walk_directory(root, &path, languages, files)?;
+            walk_directory(root, &path, languages, files, max_files, progress)?;
         } else if path.is_file() {
             // Count file extensions for language detection
             // extension() returns Option<&OsStr>
@@ -155,6 +237,7 @@ fn walk_directory(
                 *languages.entry(ext_str).or_insert(0) += 1;
             }
 
+            progress.tick(&path.strip_prefix(root).unwrap_or(&path).to_string_lossy());
             files.push(path);
         }
     }
@@ -217,6 +300,7 @@ fn detect_features(root: &Path, all_files: &[PathBuf]) -> Vec<SuggestedFeature>
                 suggested_name,
                 suggested_domain: domain,
                 files: dir_files,
+                related_files: Vec::new(),
             });
         }
     }
@@ -226,6 +310,19 @@ fn detect_features(root: &Path, all_files: &[PathBuf]) -> Vec<SuggestedFeature>
     features
 }
 
+/// Files outside `files` that import, or are imported by, any of them
+fn related_files(graph: &DependencyGraph, files: &[String]) -> Vec<String> {
+    let own: BTreeSet<&str> = files.iter().map(|f| f.as_str()).collect();
+    let mut related = BTreeSet::new();
+    for file in files {
+        related.extend(graph.neighbors(file));
+    }
+    related
+        .into_iter()
+        .filter(|f| !own.contains(f.as_str()))
+        .collect()
+}
+
 /// Infer a domain from a directory name using keyword heuristics
 fn infer_domain(dir_name: &str) -> String {
     let name = dir_name.to_lowercase();
@@ -255,7 +352,7 @@ fn infer_domain(dir_name: &str) -> String {
 
 /// Convert a snake_case or lowercase name to Title Case
 fn title_case(s: &str) -> String {
-    s.split(|c: char| c == '_' || c == '-')
+    s.split(['_', '-'])
         .filter(|part| !part.is_empty())
         .map(|part| {
             let mut chars = part.chars();
@@ -0,0 +1,146 @@
+// Export command - write the state to a portable file
+//
+// Usage:
+//   legend export                            - the whole state as JSON
+//   legend export --format yaml|csv|markdown - another format (see export_formats.rs)
+//   legend export --filter "domain:api"      - only matching features
+//   legend export --filter "status:Pending tag:backend" > pending.json
+//
+// Filters use the search syntax (see search::parse_filter). A filtered
+// export carries just the matching features; an unfiltered one also
+// carries milestones, decisions, sessions, goals, conventions, the focus
+// stack and project info, so `legend import <file>` on another machine
+// restores the whole project.
+//
+// `--docusaurus` and `--mkdocs` write Markdown pages instead (see
+// export_docs.rs).
+
+use serde_json::json;
+use super::export_formats::{self, ExportFormat};
+use super::search;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Decision, Feature, Goal, Milestone, ProjectInfo, Session};
+use serde::{Deserialize, Serialize};
+
+const USAGE: &str = "Usage: legend export [--format json|yaml|csv|markdown] [--filter <expr>] [--docusaurus|--mkdocs [--out <dir>]]";
+
+/// What `legend export` writes and `legend import` reads
+///
+/// exported_at/filter are informational; only `features` is required on
+/// import, so get_state output is also accepted. The rest of the state is
+/// left out when empty, which keeps a filtered export to its features
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Export {
+    #[serde(default)]
+    pub project_name: String,
+    #[serde(default)]
+    pub exported_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    pub features: Vec<Feature>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub milestones: Vec<Milestone>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decisions: Vec<Decision>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sessions: Vec<Session>,
+    #[serde(default, skip_serializing_if = "ProjectInfo::is_empty")]
+    pub project: ProjectInfo,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conventions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus: Vec<String>,
+}
+
+/// Handle the export command
+pub fn handle_export(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "docs")]
+    if args.iter().any(|a| super::export_docs::Flavor::from_flag(a).is_some()) {
+        return super::export_docs::handle_export_docs(args);
+    }
+    #[cfg(not(feature = "docs"))]
+    if let Some(flag) = args.iter().find(|a| *a == "--docusaurus" || *a == "--mkdocs") {
+        return Err(crate::cargo_features::not_built("docs", &format!("export {}", flag)));
+    }
+
+    let (rest, filter) = take_filter_flag(args)?;
+    let format = match rest.as_slice() {
+        [] => ExportFormat::Json,
+        [flag, name] if flag == "--format" => {
+            ExportFormat::parse(name).map_err(|e| ExitError::new(exit::USAGE, format!("{}\n{}", e, USAGE)))?
+        }
+        [other, ..] => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into()),
+    };
+    let query = filter.as_deref().map(search::parse_filter).transpose()?;
+
+    let state = storage::load_state()?;
+    let export = if let Some(query) = &query {
+        Export {
+            project_name: state.project_name,
+            exported_at: current_timestamp(),
+            filter,
+            features: state
+                .features
+                .into_iter()
+                .filter(|f| search::matches_query(f, query))
+                .collect(),
+            ..Export::default()
+        }
+    } else {
+        Export {
+            project_name: state.project_name,
+            exported_at: current_timestamp(),
+            filter: None,
+            features: state.features,
+            created_at: Some(state.created_at),
+            milestones: state.milestones,
+            decisions: state.decisions,
+            sessions: state.sessions,
+            project: state.project,
+            goals: state.goals,
+            conventions: state.conventions,
+            focus: state.focus,
+        }
+    };
+
+    let text = export_formats::render(&export, format).map_err(|e| format!("Failed to serialize export: {}", e))?;
+    print!("{}", text);
+    output::diag(
+        output::Level::Info,
+        &format!("Exported {} feature(s).", export.features.len()),
+        json!({"count": export.features.len()}),
+    );
+
+    Ok(())
+}
+
+/// Pull `--filter <expr>` out of args, like journal::take_at_flag
+///
+/// Returns the remaining args alongside the expression
+pub fn take_filter_flag(args: &[String]) -> Result<(Vec<String>, Option<String>), Box<dyn std::error::Error>> {
+    let mut rest = Vec::new();
+    let mut filter = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--filter" {
+            i += 1;
+            filter = Some(
+                args.get(i)
+                    .ok_or("--filter expects an expression, e.g. \"domain:api status:Pending\"")?
+                    .clone(),
+            );
+        } else {
+            rest.push(args[i].clone());
+        }
+        i += 1;
+    }
+
+    Ok((rest, filter))
+}
@@ -0,0 +1,337 @@
+// Export as documentation pages - the state as a Markdown docs folder
+//
+// Usage:
+//   legend export --docusaurus                 - pages in docs/legend/
+//   legend export --mkdocs --out docs/context  - another folder
+//   legend export --mkdocs --filter "status:Complete"
+//
+// Writes index.md (the project and its domains) plus one page per domain,
+// each with frontmatter for the chosen generator. Pages depend only on
+// the state, so re-running with nothing changed rewrites nothing; pages
+// for domains that disappeared are removed. Only files carrying the
+// GENERATED marker are ever overwritten or deleted, so hand-written
+// pages can live in the same folder.
+
+use super::export::take_filter_flag;
+use super::search;
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use crate::text;
+use crate::types::{Feature, LegendState};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const USAGE: &str = "Usage: legend export --docusaurus|--mkdocs [--out <dir>] [--filter <expr>]";
+
+const DEFAULT_DIR: &str = "docs/legend";
+
+/// First line of every generated page's body
+const GENERATED: &str = "<!-- Generated by `legend export`; edits will be overwritten. -->";
+
+/// Which docs generator the frontmatter is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flavor {
+    Docusaurus,
+    MkDocs,
+}
+
+impl Flavor {
+    pub fn from_flag(flag: &str) -> Option<Flavor> {
+        match flag {
+            "--docusaurus" => Some(Flavor::Docusaurus),
+            "--mkdocs" => Some(Flavor::MkDocs),
+            _ => None,
+        }
+    }
+}
+
+/// What a run did to the folder
+#[derive(Debug, Default, PartialEq)]
+struct WriteSummary {
+    written: usize,
+    unchanged: usize,
+    removed: usize,
+}
+
+/// Handle `legend export --docusaurus|--mkdocs`
+pub fn handle_export_docs(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (rest, filter) = take_filter_flag(args)?;
+    let mut flavor = None;
+    let mut out = DEFAULT_DIR.to_string();
+
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i].as_str() {
+            "--out" => {
+                i += 1;
+                out = rest
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--out expects a directory\n{}", USAGE)))?
+                    .clone();
+            }
+            other => match Flavor::from_flag(other) {
+                Some(f) if flavor.is_none_or(|current| current == f) => flavor = Some(f),
+                Some(_) => return Err(ExitError::new(exit::USAGE, format!("Pick one of --docusaurus and --mkdocs\n{}", USAGE)).into()),
+                None => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into()),
+            },
+        }
+        i += 1;
+    }
+    let flavor = flavor.ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    let query = filter.as_deref().map(search::parse_filter).transpose()?;
+
+    let mut state = storage::load_state()?;
+    state.features.retain(|f| query.as_ref().is_none_or(|q| search::matches_query(f, q)));
+
+    let pages = render_pages(&state, flavor);
+    let summary = write_pages(Path::new(&out), &pages)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!(
+            "Exported {} page(s) to {}: {} written, {} unchanged, {} removed.",
+            pages.len(),
+            out,
+            summary.written,
+            summary.unchanged,
+            summary.removed
+        ),
+        json!({
+            "dir": out,
+            "pages": pages.len(),
+            "written": summary.written,
+            "unchanged": summary.unchanged,
+            "removed": summary.removed,
+        }),
+    );
+    Ok(())
+}
+
+/// File name -> contents: index.md plus one page per domain
+fn render_pages(state: &LegendState, flavor: Flavor) -> BTreeMap<String, String> {
+    let mut domains: BTreeMap<&str, Vec<&Feature>> = BTreeMap::new();
+    for feature in &state.features {
+        domains.entry(feature.domain.as_str()).or_default().push(feature);
+    }
+
+    // Slugs are assigned in domain order so they're stable between runs
+    let mut domains: Vec<(&str, Vec<&Feature>)> = domains.into_iter().collect();
+    domains.sort_by(|a, b| text::collate(a.0, b.0));
+    let mut pages = BTreeMap::new();
+    let mut links = Vec::new();
+    for (position, (domain, features)) in domains.iter_mut().enumerate() {
+        features.sort_by(|a, b| text::collate(&a.id, &b.id));
+        let mut slug = slugify(domain);
+        let mut n = 2;
+        while slug == "index" || pages.contains_key(&format!("{}.md", slug)) {
+            slug = format!("{}-{}", slugify(domain), n);
+            n += 1;
+        }
+        let title = if domain.is_empty() { "Unassigned" } else { domain };
+        let file = format!("{}.md", slug);
+        pages.insert(file.clone(), render_domain(title, &slug, features, position + 2, flavor));
+        links.push((title, file, features.len()));
+    }
+
+    pages.insert("index.md".to_string(), render_index(state, &links, flavor));
+    pages
+}
+
+fn render_index(state: &LegendState, domains: &[(&str, String, usize)], flavor: Flavor) -> String {
+    let mut out = frontmatter(&state.project_name, "index", 1, flavor);
+    out.push_str(&format!("# {}\n\n", escape(&state.project_name, flavor)));
+    out.push_str(&format!("{}\n\n", status_counts(state.features.iter())));
+    if domains.is_empty() {
+        out.push_str("No features tracked yet.\n");
+        return out;
+    }
+    out.push_str("| Domain | Features |\n|---|---|\n");
+    for (title, file, count) in domains {
+        out.push_str(&format!("| [{}]({}) | {} |\n", escape(title, flavor), file, count));
+    }
+    out
+}
+
+fn render_domain(title: &str, slug: &str, features: &[&Feature], position: usize, flavor: Flavor) -> String {
+    let mut out = frontmatter(title, slug, position, flavor);
+    out.push_str(&format!("# {}\n\n", escape(title, flavor)));
+    out.push_str(&format!("{}\n", status_counts(features.iter().copied())));
+
+    for feature in features {
+        out.push_str(&format!("\n## {}\n\n", escape(&feature.name, flavor)));
+
+        let mut facts = vec![format!("`{}`", feature.id), status_name(feature).to_string()];
+        if !feature.tags.is_empty() {
+            facts.push(format!("tags: {}", escape(&feature.tags.join(", "), flavor)));
+        }
+        facts.push(format!("updated {}", dates::format_date(feature.last_updated)));
+        out.push_str(&format!("{}\n", facts.join(" · ")));
+
+        if !feature.description.trim().is_empty() {
+            out.push_str(&format!("\n{}\n", escape(feature.description.trim(), flavor)));
+        }
+        if let Some(context) = feature.context.as_deref().filter(|c| !c.trim().is_empty()) {
+            out.push_str(&format!("\n{}\n", escape(context.trim(), flavor)));
+        }
+        if !feature.depends_on.is_empty() {
+            let deps: Vec<String> = feature.depends_on.iter().map(|d| format!("`{}`", d)).collect();
+            out.push_str(&format!("\nDepends on: {}\n", deps.join(", ")));
+        }
+        if !feature.files_involved.is_empty() {
+            out.push_str("\nFiles:\n\n");
+            for file in &feature.files_involved {
+                out.push_str(&format!("- `{}`\n", file));
+            }
+        }
+    }
+    out
+}
+
+/// YAML frontmatter and the generated marker
+///
+/// Titles are written as JSON strings, which YAML reads as-is
+fn frontmatter(title: &str, slug: &str, position: usize, flavor: Flavor) -> String {
+    let title = serde_json::to_string(title).unwrap_or_default();
+    let fields = match flavor {
+        Flavor::Docusaurus => format!("id: {}\ntitle: {}\nsidebar_position: {}\n", slug, title, position),
+        Flavor::MkDocs => format!("title: {}\n", title),
+    };
+    format!("---\n{}---\n\n{}\n\n", fields, GENERATED)
+}
+
+/// "5 features: 2 Complete, 1 InProgress, 2 Pending."
+fn status_counts<'a>(features: impl Iterator<Item = &'a Feature>) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut total = 0;
+    for feature in features {
+        *counts.entry(status_name(feature)).or_default() += 1;
+        total += 1;
+    }
+    let parts: Vec<String> = counts.iter().map(|(status, n)| format!("{} {}", n, status)).collect();
+    if parts.is_empty() {
+        format!("{} features.", total)
+    } else {
+        format!("{} feature(s): {}.", total, parts.join(", "))
+    }
+}
+
+fn status_name(feature: &Feature) -> &str {
+    if feature.planned {
+        return "Planned";
+    }
+    feature.status.name()
+}
+
+/// Docusaurus reads .md as MDX, where `<` and braces start JSX
+fn escape(text: &str, flavor: Flavor) -> String {
+    match flavor {
+        Flavor::Docusaurus => text.replace('<', "\\<").replace('{', "\\{").replace('}', "\\}"),
+        Flavor::MkDocs => text.to_string(),
+    }
+}
+
+/// "Data Layer" -> "data-layer"; empty -> "unassigned"
+fn slugify(domain: &str) -> String {
+    let slug: Vec<String> = domain
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if slug.is_empty() {
+        "unassigned".to_string()
+    } else {
+        slug.join("-")
+    }
+}
+
+/// Write pages that changed and remove generated pages that no longer exist
+fn write_pages(dir: &Path, pages: &BTreeMap<String, String>) -> Result<WriteSummary, Box<dyn std::error::Error>> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let mut summary = WriteSummary::default();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        if !name.ends_with(".md") || pages.contains_key(&name) {
+            continue;
+        }
+        if fs::read_to_string(&path).is_ok_and(|content| content.contains(GENERATED)) {
+            fs::remove_file(&path)?;
+            summary.removed += 1;
+        }
+    }
+
+    for (name, content) in pages {
+        let path = dir.join(name);
+        match fs::read_to_string(&path) {
+            Ok(existing) if existing == *content => summary.unchanged += 1,
+            Ok(existing) if !existing.contains(GENERATED) => {
+                return Err(format!("{} exists and wasn't written by legend export; move it or pick another --out", path.display()).into());
+            }
+            _ => {
+                fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                summary.written += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeatureStatus;
+
+    fn state() -> LegendState {
+        let mut state = LegendState::new("Demo".to_string());
+        let mut login = Feature::new("login".to_string(), "Login".to_string(), "Auth Core".to_string(), "Uses <Session> {tokens}".to_string());
+        login.status = FeatureStatus::Complete;
+        login.files_involved = vec!["src/auth.rs".to_string()];
+        state.features.push(login);
+        state.features.push(Feature::new("billing".to_string(), "Billing".to_string(), "payments".to_string(), "Invoices".to_string()));
+        state
+    }
+
+    #[test]
+    fn test_render_pages() {
+        let pages = render_pages(&state(), Flavor::Docusaurus);
+        assert_eq!(pages.keys().collect::<Vec<_>>(), vec!["auth-core.md", "index.md", "payments.md"]);
+
+        let auth = &pages["auth-core.md"];
+        assert!(auth.starts_with("---\nid: auth-core\ntitle: \"Auth Core\"\nsidebar_position: 2\n---\n"));
+        assert!(auth.contains("Uses \\<Session> \\{tokens\\}"));
+        assert!(auth.contains("- `src/auth.rs`"));
+        assert!(pages["index.md"].contains("| [Auth Core](auth-core.md) | 1 |"));
+
+        let mkdocs = render_pages(&state(), Flavor::MkDocs);
+        assert!(mkdocs["auth-core.md"].starts_with("---\ntitle: \"Auth Core\"\n---\n"));
+        assert!(mkdocs["auth-core.md"].contains("Uses <Session> {tokens}"));
+    }
+
+    #[test]
+    fn test_write_pages_is_idempotent() {
+        let dir = std::env::temp_dir().join(format!("legend-docs-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("handwritten.md"), "# Mine\n").unwrap();
+
+        let pages = render_pages(&state(), Flavor::MkDocs);
+        assert_eq!(write_pages(&dir, &pages).unwrap(), WriteSummary { written: 3, unchanged: 0, removed: 0 });
+        assert_eq!(write_pages(&dir, &pages).unwrap(), WriteSummary { written: 0, unchanged: 3, removed: 0 });
+
+        let mut smaller = state();
+        smaller.features.retain(|f| f.id == "login");
+        let pages = render_pages(&smaller, Flavor::MkDocs);
+        let summary = write_pages(&dir, &pages).unwrap();
+        let handwritten_kept = dir.join("handwritten.md").exists();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(summary, WriteSummary { written: 1, unchanged: 1, removed: 1 });
+        assert!(handwritten_kept);
+        assert_eq!(slugify(""), "unassigned");
+    }
+}
@@ -0,0 +1,346 @@
+// Export formats - how `legend export --format` writes an Export and
+// `legend import` reads one back
+//
+//   json       the Export as is (the default)
+//   yaml       the same tree as YAML (see yaml.rs)
+//   csv        one row per feature, one column per field, for spreadsheets
+//              and other trackers; lists and objects are JSON in the cell
+//   markdown   one readable document; not read back
+//
+// json and yaml carry everything and round-trip exactly. csv carries the
+// features only: a spreadsheet has no place for decisions or sessions.
+
+use super::export::Export;
+use crate::dates;
+use crate::importers;
+use crate::types::Feature;
+use crate::yaml;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Result<ExportFormat, String> {
+        match name.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "yaml" | "yml" => Ok(ExportFormat::Yaml),
+            "csv" => Ok(ExportFormat::Csv),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            _ => Err(format!("Unknown export format '{}' (expected json, yaml, csv or markdown)", name)),
+        }
+    }
+
+    /// From the file's extension, else from what the content looks like
+    /// (Markdown only by extension: it can't be imported anyway)
+    pub fn detect(path: &str, content: &str) -> ExportFormat {
+        let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        if let Ok(format) = ExportFormat::parse(extension) {
+            return format;
+        }
+        let content = content.trim_start();
+        let first_line = content.lines().next().unwrap_or("");
+        if content.starts_with('{') || content.starts_with('[') {
+            ExportFormat::Json
+        } else if first_line.split(',').any(|column| column == "id") {
+            ExportFormat::Csv
+        } else {
+            ExportFormat::Yaml
+        }
+    }
+}
+
+/// The export as text in `format`
+pub fn render(export: &Export, format: ExportFormat) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(match format {
+        ExportFormat::Json => format!("{}\n", serde_json::to_string_pretty(export)?),
+        ExportFormat::Yaml => yaml::to_string(&serde_json::to_value(export)?),
+        ExportFormat::Csv => to_csv(&export.features)?,
+        ExportFormat::Markdown => to_markdown(export),
+    })
+}
+
+/// Read an export written by `render`
+pub fn parse(content: &str, format: ExportFormat) -> Result<Export, Box<dyn std::error::Error>> {
+    match format {
+        ExportFormat::Json => serde_json::from_str(content).map_err(|e| format!("Not a Legend export: {}", e).into()),
+        ExportFormat::Yaml => {
+            let value = yaml::parse(content).map_err(|e| format!("Not a Legend export: {}", e))?;
+            serde_json::from_value(value).map_err(|e| format!("Not a Legend export: {}", e).into())
+        }
+        ExportFormat::Csv => Ok(Export {
+            features: from_csv(content)?,
+            ..Export::default()
+        }),
+        ExportFormat::Markdown => {
+            Err("A Markdown export is for reading and can't be imported; export --format json or yaml instead".into())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CSV
+// ---------------------------------------------------------------------------
+
+/// The columns a sheet leads with; the other fields follow by name
+const LEADING_COLUMNS: [&str; 4] = ["id", "name", "domain", "status"];
+
+/// Every Feature field, each with a sample value of its type
+///
+/// Every Option is Some here, so a cell like "3" can be told apart: a
+/// number under complexity, a string under owner
+fn csv_columns() -> Map<String, Value> {
+    let mut sample = Feature::new(String::new(), String::new(), String::new(), String::new());
+    sample.owner = Some(String::new());
+    sample.blocked_reason = Some(String::new());
+    sample.blocked_by = Some(String::new());
+    sample.context = Some(String::new());
+    sample.parent_id = Some(String::new());
+    sample.estimate_hours = Some(0.0);
+    sample.due_date = Some(0);
+    sample.complexity = Some(0);
+    sample.risk = Some(0);
+    sample.last_updated_by = Some(String::new());
+    sample.started_at = Some(0);
+    sample.completed_at = Some(0);
+    match serde_json::to_value(sample) {
+        Ok(Value::Object(columns)) => columns,
+        _ => Map::new(),
+    }
+}
+
+fn to_csv(features: &[Feature]) -> Result<String, Box<dyn std::error::Error>> {
+    let fields = csv_columns();
+    let columns: Vec<&str> = LEADING_COLUMNS
+        .into_iter()
+        .chain(fields.keys().map(String::as_str).filter(|k| !LEADING_COLUMNS.contains(k)))
+        .collect();
+    let mut out = csv_row(columns.iter().copied());
+    for feature in features {
+        let value = serde_json::to_value(feature)?;
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| match &value[*column] {
+                Value::Null => String::new(),
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        out.push_str(&csv_row(cells.iter().map(String::as_str)));
+    }
+    Ok(out)
+}
+
+/// One CSV line; fields with commas, quotes or newlines are quoted
+fn csv_row<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    let cells: Vec<String> = cells
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect();
+    format!("{}\n", cells.join(","))
+}
+
+/// Features from CSV with a header row; unknown columns are ignored, and
+/// missing columns or empty cells take what a new feature starts with
+fn from_csv(content: &str) -> Result<Vec<Feature>, Box<dyn std::error::Error>> {
+    let columns = csv_columns();
+    let Value::Object(blank) = serde_json::to_value(Feature::new(String::new(), String::new(), String::new(), String::new()))? else {
+        return Err("Failed to serialize a feature".into());
+    };
+    let mut rows = importers::parse_csv(content).into_iter();
+    let header = rows.next().ok_or("The CSV file is empty")?;
+    if !header.iter().any(|h| h == "id") {
+        return Err("Not a Legend CSV export: no \"id\" column".into());
+    }
+
+    let mut features = Vec::new();
+    for (i, row) in rows.enumerate() {
+        let line = i + 2;
+        let mut object = blank.clone();
+        for (name, cell) in header.iter().zip(&row) {
+            let Some(sample) = columns.get(name).filter(|_| !cell.is_empty()) else { continue };
+            let value = match sample {
+                Value::String(_) => Value::String(cell.clone()),
+                _ => serde_json::from_str(cell).map_err(|e| format!("CSV row {}: bad {} '{}': {}", line, name, cell, e))?,
+            };
+            object.insert(name.clone(), value);
+        }
+        if object["id"] == "" {
+            return Err(format!("CSV row {}: no id", line).into());
+        }
+        features.push(serde_json::from_value(Value::Object(object)).map_err(|e| format!("CSV row {}: {}", line, e))?);
+    }
+    Ok(features)
+}
+
+// ---------------------------------------------------------------------------
+// Markdown
+// ---------------------------------------------------------------------------
+
+fn to_markdown(export: &Export) -> String {
+    let mut out = format!("# {}\n\n", export.project_name);
+    if let Some(description) = &export.project.description {
+        out.push_str(&format!("{}\n\n", description));
+    }
+    out.push_str(&format!(
+        "Exported {} · {} feature(s)",
+        dates::format_date(export.exported_at),
+        export.features.len()
+    ));
+    match &export.filter {
+        Some(filter) => out.push_str(&format!(" matching `{}`\n", filter)),
+        None => out.push('\n'),
+    }
+
+    if !export.goals.is_empty() {
+        out.push_str("\n## Goals\n\n");
+        for goal in &export.goals {
+            out.push_str(&format!("- **{}** (`{}`)\n", goal.title, goal.id));
+        }
+    }
+    if !export.milestones.is_empty() {
+        out.push_str("\n## Milestones\n\n");
+        for milestone in &export.milestones {
+            let due = milestone.due.map(|d| format!(", due {}", dates::format_date(d))).unwrap_or_default();
+            out.push_str(&format!("- **{}** (`{}`{})\n", milestone.name, milestone.id, due));
+        }
+    }
+
+    if !export.features.is_empty() {
+        out.push_str("\n## Features\n\n| ID | Name | Domain | Status | Priority |\n|---|---|---|---|---|\n");
+        for f in &export.features {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} | {:?} |\n",
+                f.id,
+                table_cell(&f.name),
+                table_cell(&f.domain),
+                f.status,
+                f.priority
+            ));
+        }
+        for f in &export.features {
+            out.push_str(&format!("\n### {}\n\n", f.name));
+            if !f.description.trim().is_empty() {
+                out.push_str(&format!("{}\n\n", f.description.trim()));
+            }
+            if let Some(context) = f.context.as_deref().filter(|c| !c.trim().is_empty()) {
+                out.push_str(&format!("{}\n\n", context.trim()));
+            }
+            if !f.tags.is_empty() {
+                out.push_str(&format!("- Tags: {}\n", f.tags.join(", ")));
+            }
+            if !f.depends_on.is_empty() {
+                out.push_str(&format!("- Depends on: {}\n", f.depends_on.join(", ")));
+            }
+            for task in &f.subtasks {
+                out.push_str(&format!("- [{}] {}\n", if task.done { "x" } else { " " }, task.title));
+            }
+            for file in &f.files_involved {
+                out.push_str(&format!("- `{}`\n", file));
+            }
+        }
+    }
+
+    if !export.decisions.is_empty() {
+        out.push_str("\n## Decisions\n");
+        for decision in &export.decisions {
+            out.push_str(&format!(
+                "\n### #{} {}\n\n{} ({})\n",
+                decision.id,
+                decision.title,
+                decision.rationale,
+                dates::format_date(decision.decided_at)
+            ));
+        }
+    }
+    if !export.conventions.is_empty() {
+        out.push_str("\n## Conventions\n\n");
+        for convention in &export.conventions {
+            out.push_str(&format!("- {}\n", convention));
+        }
+    }
+    out
+}
+
+/// Pipes and newlines would break the table row
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Decision, FeatureStatus, Subtask};
+
+    fn sample() -> Export {
+        let mut auth = Feature::new("auth".to_string(), "Auth, v2".to_string(), "web".to_string(), "Say \"hi\"\nthen log in".to_string());
+        auth.status = FeatureStatus::InProgress;
+        auth.tags = vec!["backend".to_string()];
+        auth.owner = Some("42".to_string());
+        auth.complexity = Some(3);
+        auth.subtasks.push(Subtask { id: 1, title: "Form".to_string(), done: true, created_at: 5, completed_at: Some(6) });
+        auth.metadata.insert("jira".to_string(), serde_json::json!("AUTH-1"));
+        Export {
+            project_name: "Demo".to_string(),
+            exported_at: 1_709_251_200,
+            features: vec![auth],
+            decisions: vec![Decision {
+                id: 1,
+                title: "Use JWT".to_string(),
+                rationale: "Stateless".to_string(),
+                feature_ids: vec!["auth".to_string()],
+                decided_at: 1_709_251_200,
+            }],
+            conventions: vec!["Tests next to the code".to_string()],
+            ..Export::default()
+        }
+    }
+
+    #[test]
+    fn test_csv_columns_cover_every_option() {
+        let nulls: Vec<String> = csv_columns().into_iter().filter(|(_, v)| v.is_null()).map(|(k, _)| k).collect();
+        assert!(nulls.is_empty(), "give these a Some(..) in csv_columns: {:?}", nulls);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let export = sample();
+        let expected = serde_json::to_value(&export).unwrap();
+        for format in [ExportFormat::Json, ExportFormat::Yaml] {
+            let text = render(&export, format).unwrap();
+            assert_eq!(ExportFormat::detect("export", &text), format);
+            assert_eq!(serde_json::to_value(parse(&text, format).unwrap()).unwrap(), expected, "{:?}", format);
+        }
+
+        let csv = render(&export, ExportFormat::Csv).unwrap();
+        assert!(csv.starts_with("id,name,domain,status,blocked_by,"), "{}", csv);
+        assert_eq!(ExportFormat::detect("export", &csv), ExportFormat::Csv);
+        let features = parse(&csv, ExportFormat::Csv).unwrap().features;
+        assert_eq!(serde_json::to_value(&features).unwrap(), expected["features"]);
+        assert_eq!(features[0].owner.as_deref(), Some("42"));
+
+        // A hand-made sheet needs little more than ids and names
+        let features = from_csv("id,name,complexity,extra\nbilling,Billing,2,x\n").unwrap();
+        assert_eq!(features[0].complexity, Some(2));
+        assert_eq!(features[0].status, FeatureStatus::Pending);
+        assert!(from_csv("id,complexity\nb,lots\n").unwrap_err().to_string().contains("row 2"));
+
+        let markdown = render(&export, ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("| `auth` | Auth, v2 | web | InProgress | Medium |"), "{}", markdown);
+        assert!(markdown.contains("- [x] Form\n"));
+        assert!(markdown.contains("### #1 Use JWT"));
+        assert_eq!(ExportFormat::detect("export.md", &markdown), ExportFormat::Markdown);
+        assert!(parse(&markdown, ExportFormat::Markdown).is_err());
+    }
+}
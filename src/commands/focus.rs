@@ -0,0 +1,152 @@
+// Focus command - what we're working on right now
+//
+// Usage:
+//   legend focus push <id> [<id>...]   - put features on top of the stack
+//   legend focus pop                   - take the top one off
+//   legend focus show [--json]         - the stack, top first
+//   legend focus clear
+//   legend focus watch [--interval <seconds>]   - push/pop from file edits
+//
+// While the stack has anything on it, get_state leads with those features
+// in full ("focus") and lists the rest as one-line summaries, so every
+// agent call in the session starts from the work at hand. A stack rather
+// than a set: a detour (push the bug, fix it, pop) returns to where the
+// session was.
+//
+// `focus watch` (focus_watch.rs) keeps the stack up to date by itself
+// from which features' files are being edited.
+
+use crate::commands::focus_watch;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::LegendState;
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend focus push <feature-id> [<feature-id>...]\n       legend focus pop\n       legend focus show [--json]\n       legend focus clear\n       legend focus watch [--interval <seconds>]";
+
+/// Handle the focus command
+pub fn handle_focus(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (command, rest) = args.split_first().ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    match (command.as_str(), rest) {
+        ("push", ids) if !ids.is_empty() => push(ids),
+        ("pop", []) => pop(),
+        ("show", []) => show(false),
+        ("show", [flag]) if flag == "--json" => show(true),
+        ("clear", []) => clear(),
+        ("watch", rest) => focus_watch::handle_watch(rest),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn push(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    for id in args {
+        let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+        let id = state.features[index].id.clone();
+        push_id(&mut state, id);
+    }
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let top = state.focus.last().cloned().unwrap_or_default();
+    output::diag(
+        output::Level::Info,
+        &format!("Focused on {} ({} on the stack).", top, state.focus.len()),
+        json!({"top": top, "depth": state.focus.len()}),
+    );
+    Ok(())
+}
+
+fn pop() -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let popped = state.focus.pop().ok_or("The focus stack is empty")?;
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let message = match state.focus.last() {
+        Some(top) => format!("Done with {}; back to {}.", popped, top),
+        None => format!("Done with {}; nothing in focus.", popped),
+    };
+    output::diag(output::Level::Info, &message, json!({"popped": popped, "top": state.focus.last()}));
+    Ok(())
+}
+
+fn show(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let state = storage::load_state()?;
+    let stack: Vec<&String> = state.focus.iter().rev().collect();
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&stack)?);
+    } else if stack.is_empty() {
+        output::info("Nothing in focus. Start with 'legend focus push <feature-id>'.");
+    } else {
+        print!("{}", render(&state));
+    }
+    Ok(())
+}
+
+fn clear() -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let count = state.focus.len();
+    state.focus.clear();
+    state.touch();
+    journal::commit(&before, &state)?;
+    output::diag(output::Level::Info, &format!("Cleared {} from focus.", count), json!({"cleared": count}));
+    Ok(())
+}
+
+/// Put `id` on top, moving it up if it's already further down
+fn push_id(state: &mut LegendState, id: String) {
+    state.focus.retain(|f| f != &id);
+    state.focus.push(id);
+}
+
+/// One line per entry, top first: `> auth-sso  InProgress  Single sign-on`
+fn render(state: &LegendState) -> String {
+    let mut out = String::new();
+    for (i, id) in state.focus.iter().rev().enumerate() {
+        let marker = if i == 0 { ">" } else { " " };
+        match state.find_feature(id) {
+            Some(f) => out.push_str(&format!("{} {}  {}  {}\n", marker, id, f.status, f.name)),
+            None => out.push_str(&format!("{} {}  (no longer tracked)\n", marker, id)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::Feature;
+
+    #[test]
+    fn test_push_pop() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let mut state = storage::load_state().unwrap();
+        for id in ["auth", "billing", "search"] {
+            state.features.push(Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string()));
+        }
+        storage::save_state(&state).unwrap();
+
+        handle_focus(&["push", "auth", "billing"].map(String::from)).unwrap();
+        handle_focus(&["push", "auth"].map(String::from)).unwrap();
+        assert!(handle_focus(&["push", "nope"].map(String::from)).is_err());
+        let state = storage::load_state().unwrap();
+        assert_eq!(state.focus, vec!["billing", "auth"]);
+        assert_eq!(render(&state), "> auth  Pending  auth\n  billing  Pending  billing\n");
+
+        handle_focus(&["pop"].map(String::from)).unwrap();
+        assert_eq!(storage::load_state().unwrap().focus, vec!["billing"]);
+        handle_focus(&["clear"].map(String::from)).unwrap();
+        assert!(handle_focus(&["pop"].map(String::from)).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+// Focus watch - keep the focus stack on what is actually being edited
+//
+// Usage:
+//   legend focus watch [--interval <seconds>]   - runs until interrupted
+//
+// Every few seconds it checks the modification time of each file an open
+// feature lists in files_involved. A feature whose files changed in
+// SUSTAINED_POLLS separate checks within SUSTAINED_WINDOW is pushed onto
+// the focus stack; once none of them has changed for IDLE_AFTER it comes
+// off again. So the next session starts on what the human was just doing,
+// without anyone running `legend focus push`.
+//
+// Only features the watcher pushed itself are ever taken off: a manual
+// push stays until it is popped by hand. A feature already on the stack
+// isn't pushed again, so editing two features in turn doesn't make them
+// swap places on every check.
+//
+// A directory entry (`src/ui/`) counts as edited when anything inside it
+// is: the newest modification time under it, directories included, so
+// adding or deleting a file shows up as well as an edit.
+//
+// Why polling and not inotify/FSEvents? Minimal dependencies (see
+// PLAN.md), and the files are few: only the ones features name.
+//
+// With the tui feature, SIGINT/SIGTERM/SIGHUP are caught (signals.rs):
+// a focus change being saved finishes, then the watch stops between
+// checks, so Ctrl-C can't cut a commit in half.
+
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::output;
+#[cfg(feature = "tui")]
+use crate::signals;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, FeatureStatus};
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const USAGE: &str = "Usage: legend focus watch [--interval <seconds>]";
+
+/// Seconds between checks
+const DEFAULT_INTERVAL: u64 = 30;
+
+/// Checks that must see an edit before a feature counts as being worked on
+const SUSTAINED_POLLS: usize = 3;
+
+/// ...all within this many seconds
+const SUSTAINED_WINDOW: i64 = 10 * 60;
+
+/// Seconds without an edit before a pushed feature comes off the stack
+const IDLE_AFTER: i64 = 30 * 60;
+
+/// Handle `legend focus watch`
+pub fn handle_watch(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let interval = match args {
+        [] => DEFAULT_INTERVAL,
+        [flag, value] if flag == "--interval" => value
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| ExitError::new(exit::USAGE, format!("--interval expects a positive number of seconds\n{}", USAGE)))?,
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    output::info(&format!("Watching the files of open features every {}s (Ctrl-C to stop).", interval));
+    let mut scanner = Scanner::new(storage::project_root());
+    let mut inference = Inference::default();
+    #[cfg(feature = "tui")]
+    let _signals = signals::install();
+    loop {
+        let state = storage::load_state()?;
+        let edited = scanner.scan(&state.features);
+        let changes = inference.observe(current_timestamp(), &edited, &state.focus);
+        if !changes.is_empty() {
+            // Reload under the lock: an update may have saved since
+            let _lock = storage::lock_state()?;
+            let mut state = storage::load_state()?;
+            let before = state.clone();
+            for change in &changes {
+                match change {
+                    Change::Push(id) => {
+                        state.focus.retain(|f| f != id);
+                        state.focus.push(id.clone());
+                        output::diag(
+                            output::Level::Info,
+                            &format!("Focused on {} (its files keep changing).", id),
+                            json!({"pushed": id}),
+                        );
+                    }
+                    Change::Drop(id) => {
+                        state.focus.retain(|f| f != id);
+                        output::diag(
+                            output::Level::Info,
+                            &format!("{} has been idle for {}m; off the focus stack.", id, IDLE_AFTER / 60),
+                            json!({"dropped": id}),
+                        );
+                    }
+                }
+            }
+            state.touch();
+            journal::commit(&before, &state)?;
+        }
+        if !wait(interval) {
+            break;
+        }
+    }
+
+    // Stopped by a signal; report it the way a shell expects
+    #[cfg(feature = "tui")]
+    if let Some(signal) = signals::received() {
+        return Err(ExitError::new(exit::signal_code(signal), "Interrupted").into());
+    }
+    Ok(())
+}
+
+/// Sleep until the next check; false if a signal asked us to stop
+///
+/// thread::sleep resumes after a signal, so it sleeps in short steps.
+#[cfg(feature = "tui")]
+fn wait(interval: u64) -> bool {
+    let step = Duration::from_millis(200);
+    let mut left = Duration::from_secs(interval);
+    while signals::received().is_none() && !left.is_zero() {
+        let nap = left.min(step);
+        thread::sleep(nap);
+        left -= nap;
+    }
+    signals::received().is_none()
+}
+
+#[cfg(not(feature = "tui"))]
+fn wait(interval: u64) -> bool {
+    thread::sleep(Duration::from_secs(interval));
+    true
+}
+
+/// Last seen modification time of each watched file
+struct Scanner {
+    /// What files_involved paths are relative to (storage::project_root)
+    root: PathBuf,
+    mtimes: HashMap<String, SystemTime>,
+}
+
+impl Scanner {
+    fn new(root: PathBuf) -> Self {
+        Scanner { root, mtimes: HashMap::new() }
+    }
+
+    /// Open features with a file that changed since the last scan
+    ///
+    /// A file seen for the first time isn't an edit: the first scan only
+    /// takes stock.
+    fn scan(&mut self, features: &[Feature]) -> BTreeSet<String> {
+        let mut edited = BTreeSet::new();
+        for feature in features.iter().filter(|f| f.status != FeatureStatus::Complete) {
+            for file in &feature.files_involved {
+                let Some(modified) = newest_modification(&self.root.join(file)) else {
+                    continue;
+                };
+                if let Some(previous) = self.mtimes.insert(file.clone(), modified) {
+                    if previous != modified {
+                        edited.insert(feature.id.clone());
+                    }
+                }
+            }
+        }
+        edited
+    }
+}
+
+/// The modification time of a file, or the newest one anywhere under a
+/// directory (the directory itself included); None if it can't be read
+fn newest_modification(path: &Path) -> Option<SystemTime> {
+    let meta = fs::metadata(path).ok()?;
+    let mut newest = meta.modified().ok()?;
+    if meta.is_dir() {
+        for entry in fs::read_dir(path).ok()?.flatten() {
+            if let Some(modified) = newest_modification(&entry.path()) {
+                newest = newest.max(modified);
+            }
+        }
+    }
+    Some(newest)
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    Push(String),
+    Drop(String),
+}
+
+/// Which features are being worked on, from when their files changed
+#[derive(Default)]
+struct Inference {
+    /// Checks that saw an edit, per feature, within SUSTAINED_WINDOW
+    recent: HashMap<String, Vec<i64>>,
+    last_edit: HashMap<String, i64>,
+    /// Features the watcher pushed that are still on the stack
+    pushed: Vec<String>,
+}
+
+impl Inference {
+    /// Record a check at `now` that saw `edited` change, and say what to
+    /// push onto or take off `focus` (top last)
+    fn observe(&mut self, now: i64, edited: &BTreeSet<String>, focus: &[String]) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        // Popped by hand since: no longer ours to drop
+        self.pushed.retain(|id| focus.contains(id));
+
+        for id in edited {
+            self.last_edit.insert(id.clone(), now);
+            let checks = self.recent.entry(id.clone()).or_default();
+            checks.push(now);
+            checks.retain(|t| now - t <= SUSTAINED_WINDOW);
+            if checks.len() >= SUSTAINED_POLLS && !focus.contains(id) {
+                self.pushed.push(id.clone());
+                changes.push(Change::Push(id.clone()));
+            }
+        }
+
+        let last_edit = &self.last_edit;
+        self.pushed.retain(|id| {
+            let idle = last_edit.get(id).is_none_or(|t| now - t >= IDLE_AFTER);
+            if idle {
+                changes.push(Change::Drop(id.clone()));
+            }
+            !idle
+        });
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn ids(ids: &[&str]) -> BTreeSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_observe() {
+        let mut inference = Inference::default();
+        let mut focus = vec!["manual".to_string()];
+        let auth = ids(&["auth"]);
+
+        // Two checks with edits aren't enough; the third within the window is
+        assert!(inference.observe(0, &auth, &focus).is_empty());
+        assert!(inference.observe(30, &auth, &focus).is_empty());
+        assert!(inference.observe(60, &ids(&[]), &focus).is_empty());
+        assert_eq!(inference.observe(90, &auth, &focus), vec![Change::Push("auth".to_string())]);
+        focus.push("auth".to_string());
+
+        // Already on the stack: not pushed again
+        assert!(inference.observe(120, &auth, &focus).is_empty());
+
+        // Idle long enough: taken off, and a manual push never is
+        assert!(inference.observe(120 + IDLE_AFTER - 1, &ids(&[]), &focus).is_empty());
+        assert_eq!(
+            inference.observe(120 + IDLE_AFTER, &ids(&["manual"]), &focus),
+            vec![Change::Drop("auth".to_string())]
+        );
+
+        // Edits spread wider than the window don't add up
+        let mut spread = Inference::default();
+        for i in 0..5 {
+            assert!(spread.observe(i * (SUSTAINED_WINDOW / 2 + 1), &auth, &[]).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = std::env::temp_dir().join(format!("legend-focus-watch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("auth.rs");
+        File::create(&path).unwrap().set_modified(SystemTime::UNIX_EPOCH).unwrap();
+
+        let mut auth = Feature::new("auth".to_string(), "Auth".to_string(), "web".to_string(), "d".to_string());
+        auth.files_involved = vec!["auth.rs".to_string(), "missing.rs".to_string()];
+        let mut done = auth.clone();
+        done.id = "done".to_string();
+        done.status = FeatureStatus::Complete;
+        let features = vec![auth, done];
+
+        let mut scanner = Scanner::new(dir.clone());
+        assert!(scanner.scan(&features).is_empty());
+        assert!(scanner.scan(&features).is_empty());
+        File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now()).unwrap();
+        let edited = scanner.scan(&features);
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(edited, ids(&["auth"]));
+    }
+
+    #[test]
+    fn test_scan_directory() {
+        let dir = std::env::temp_dir().join(format!("legend-focus-watch-dir-test-{}", std::process::id()));
+        let nested = dir.join("src/ui/widgets");
+        fs::create_dir_all(&nested).unwrap();
+        let path = nested.join("table.rs");
+        File::create(&path).unwrap().set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        for d in [dir.join("src/ui"), nested.clone()] {
+            File::open(&d).unwrap().set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        }
+
+        let mut ui = Feature::new("ui".to_string(), "UI".to_string(), "ui".to_string(), "d".to_string());
+        ui.files_involved = vec!["src/ui/".to_string()];
+        let features = vec![ui];
+
+        let mut scanner = Scanner::new(dir.clone());
+        assert!(scanner.scan(&features).is_empty());
+        assert!(scanner.scan(&features).is_empty());
+        File::options().write(true).open(&path).unwrap().set_modified(SystemTime::now()).unwrap();
+        let edited = scanner.scan(&features);
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(edited, ids(&["ui"]));
+    }
+}
@@ -10,9 +10,61 @@
 // - Serialize to JSON: ~1ms
 // - Total: ~4ms ✅
 
+use crate::config;
+use crate::deadlines::{self, Deadline};
+use crate::exit::{self, ExitError};
+use crate::file_lists;
+use crate::global::{self, GlobalContext};
+use crate::journal;
+use crate::rollups::{self, DomainRollup};
 use crate::storage;
+use crate::types::{current_timestamp, Feature, FeatureStatus, LegendState, Priority, ProjectInfo};
+use crate::output;
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
 use std::time::Instant;
 
+const USAGE: &str = "Usage: legend get_state [--at <date>] [--or-empty] [--sections <list>] [--hook] [--budget [<bytes>]]\n       legend get_state --continue <token> [--budget <bytes>]";
+
+/// Soft limit on the output when get_state.max_bytes is not set (about
+/// 10k tokens): past it a session starts with a lot of its window used
+const DEFAULT_MAX_BYTES: usize = 40_000;
+
+/// Rough size of a token in JSON, for get_state.max_tokens
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Section order when neither --sections nor get_state.sections is set
+const DEFAULT_SECTIONS: &str = "project,focus,global,conventions,deadlines,domains,goals,features,milestones,decisions,sessions";
+
+/// A top-level part of the output
+///
+/// Agents tend to weigh what comes first, so the order is configurable,
+/// and a section left out of the list is left out of the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// project_name, the project block (if set), created_at, last_updated
+    Project,
+    /// The focus stack's features in full, top first (see commands/focus.rs);
+    /// while it has any, `features` holds one-line summaries
+    Focus,
+    /// Features and decisions from ~/.legend/global.lz4 (see global.rs)
+    Global,
+    /// House rules (see commands/convention.rs)
+    Conventions,
+    /// Overdue and due-soon features (see deadlines.rs)
+    Deadlines,
+    /// Per-domain counts and top features (see rollups.rs)
+    Domains,
+    /// Outcomes and the features linked to them (see commands/goal.rs)
+    Goals,
+    Features,
+    Milestones,
+    Decisions,
+    Sessions,
+}
+
 /// Get current Legend state and output as JSON
 ///
 /// This is the command Claude calls to load project context.
@@ -20,38 +72,625 @@ use std::time::Instant;
 ///
 /// Output: JSON to stdout (Claude parses this)
 /// Timing info: Logged to stderr (won't interfere with JSON output)
-pub fn handle_get_state() -> Result<(), Box<dyn std::error::Error>> {
+///
+/// `--at <date>` reconstructs the state as of that moment from the journal.
+/// That path replays events and is not held to the 5ms target.
+///
+/// `--or-empty` prints an empty state when the repo has no .legend, so a
+/// hook parsing the JSON works in every repo instead of failing
+///
+/// `--sections features,decisions` (or `get_state.sections` in config)
+/// picks which top-level sections are printed, in that order
+///
+/// While `legend focus` has features on its stack, the `focus` section has
+/// them in full and `features` is summarized (as in the compact summary)
+///
+/// The `global` section carries the personal store (`legend --global
+/// update`), and is left out when that store is missing or empty
+///
+/// A files_involved longer than get_state.max_files is folded into
+/// directory globs ("src/api/** (14 files)"); search prints every path
+///
+/// Output over the soft limit (get_state.max_bytes / max_tokens) gets a
+/// warning on stderr. With `--hook`, as the SessionStart hook runs it, the
+/// compact summary is printed instead, so a big state doesn't quietly
+/// crowd out the session's context window
+///
+/// `--budget [<bytes>]` (default: the soft limit) prints every section but
+/// only as many features as fit, then `"continuation"` and `"remaining"`;
+/// `--continue <token>` prints the next chunk of features, so an agent can
+/// pull more of a big state when it needs it instead of all or nothing
+pub fn handle_get_state(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    // Output JSON to stdout (this is what Claude reads)
+    println!("{}", render_state(args)?);
+    Ok(())
+}
+
+/// What get_state prints for `args`; `legend hook SessionStart` prints it too
+pub fn render_state(args: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    let (at, rest) = journal::take_at_flag(args)?;
+    let mut or_empty = false;
+    let mut hook = false;
+    let mut sections_flag = None;
+    let mut budget_flag: Option<Option<usize>> = None;
+    let mut continuation = None;
+    let mut rest = rest.into_iter().peekable();
+    while let Some(extra) = rest.next() {
+        match extra.as_str() {
+            "--or-empty" => or_empty = true,
+            "--hook" => hook = true,
+            "--sections" => {
+                sections_flag = Some(rest.next().ok_or_else(|| ExitError::new(exit::USAGE, format!("--sections expects a list\n{}", USAGE)))?);
+            }
+            "--budget" => {
+                let value = rest.next_if(|v| !v.starts_with("--"));
+                budget_flag = Some(match value {
+                    Some(raw) => Some(
+                        raw.parse().ok().filter(|n| *n > 0).ok_or_else(|| ExitError::new(exit::USAGE, format!("--budget expects a number of bytes, got '{}'", raw)))?,
+                    ),
+                    None => None,
+                });
+            }
+            "--continue" => {
+                let token = rest.next().ok_or_else(|| ExitError::new(exit::USAGE, format!("--continue expects a token\n{}", USAGE)))?;
+                continuation = Some(Continuation::parse(&token)?);
+            }
+            _ => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", extra, USAGE)).into()),
+        }
+    }
+    let paging = budget_flag.is_some() || continuation.is_some();
+    if paging && (at.is_some() || hook || or_empty) {
+        return Err(ExitError::new(exit::USAGE, format!("--budget and --continue page through the current state; they don't combine with --at, --hook, or --or-empty\n{}", USAGE)).into());
+    }
+    if continuation.is_some() && sections_flag.is_some() {
+        return Err(ExitError::new(exit::USAGE, format!("--continue prints features only; drop --sections\n{}", USAGE)).into());
+    }
+    let config = config::load_config()?;
+    let configured = match sections_flag {
+        Some(list) => parse_sections(&list).map_err(|e| format!("--sections: {}", e))?,
+        None => match config.get("get_state.sections") {
+            Some(list) => parse_sections(list).map_err(|e| format!("config: get_state.sections: {}", e))?,
+            None => parse_sections(DEFAULT_SECTIONS)?,
+        },
+    };
+    // A continuation carries only features, summarized if the first page's were
+    let sections = if continuation.is_some() { vec![Section::Features] } else { configured.clone() };
+
+    let max_files = max_files(&config)?;
+    let mut global = if sections.contains(&Section::Global) { global::load() } else { None };
+    if let Some(global) = &mut global {
+        collapse_file_lists(&mut global.features, max_files);
+    }
+
+    if or_empty && !storage::is_initialized() {
+        let state = empty_state();
+        let domains = BTreeMap::new();
+        let ordered = Ordered { state: &state, focus: &[], global: global.as_ref(), deadlines: &[], domains: &domains, sections: &sections };
+        return Ok(serde_json::to_string_pretty(&ordered)?);
+    }
+
     // Measure performance (critical path!)
     let start = Instant::now();
 
     // Load state from disk
     // This does: read file → decompress LZ4 → deserialize bincode
-    let state = storage::load_state()?;
+    let mut state = journal::load_state_at(at)?;
 
     let load_time = start.elapsed();
 
+    // Most important first, so a reader skimming the top sees what matters;
+    // blocked features lead, so a session knows what's stuck before anything else
+    state.sort_by_priority();
+    blocked_first(&mut state);
+    collapse_file_lists(&mut state.features, max_files);
+    let focus: Vec<Feature> = if configured.contains(&Section::Focus) {
+        state.focus.iter().rev().filter_map(|id| state.find_feature(id).cloned()).collect()
+    } else {
+        Vec::new()
+    };
+
+    // The summary comes from the rollups save_state keeps, not a scan;
+    // a past state has none on disk
+    let domains = if !sections.contains(&Section::Domains) {
+        BTreeMap::new()
+    } else if at.is_some() {
+        rollups::Rollups::build(&state).domains
+    } else {
+        rollups::for_state(&storage::legend_dir(), &state).domains
+    };
+
     // Convert to JSON
     // Use to_string_pretty for human-readable output
     // (Claude can parse either compact or pretty JSON)
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| format!("Failed to serialize state to JSON: {}", e))?;
+    // What's due is measured against the moment asked about
+    let deadlines = deadlines::upcoming(&state.features, at.unwrap_or_else(current_timestamp));
 
-    let total_time = start.elapsed();
+    let ordered = Ordered { state: &state, focus: &focus, global: global.as_ref(), deadlines: &deadlines, domains: &domains, sections: &sections };
+    let max_bytes = max_bytes(&config)?;
+    let mut json = if paging && sections.contains(&Section::Features) {
+        let start = match &continuation {
+            Some(token) => token.check(&state)?,
+            None => 0,
+        };
+        let budget = budget_flag.flatten().or(continuation.as_ref().map(|c| c.budget)).unwrap_or(max_bytes);
+        render_page(&ordered, start, budget)?
+    } else {
+        serde_json::to_string_pretty(&ordered).map_err(|e| format!("Failed to serialize state to JSON: {}", e))?
+    };
 
-    // Output JSON to stdout (this is what Claude reads)
-    println!("{}", json);
+    if !paging && json.len() > max_bytes {
+        let fallback = if hook { "printing the compact summary instead" } else { "consider the compact hook style" };
+        output::diag(
+            output::Level::Warning,
+            &format!(
+                "get_state output is {} bytes (~{} tokens), over the {}-byte soft limit; {}",
+                json.len(),
+                json.len() / BYTES_PER_TOKEN,
+                max_bytes,
+                fallback
+            ),
+            json!({
+                "bytes": json.len(),
+                "approx_tokens": json.len() / BYTES_PER_TOKEN,
+                "limit_bytes": max_bytes,
+                "compact": hook,
+            }),
+        );
+        if hook {
+            json = serde_json::to_string_pretty(&compact_state(&state, &focus, global.as_ref(), &deadlines, json.len(), max_bytes))?;
+        }
+    }
+
+    let total_time = start.elapsed();
 
     // Log performance to stderr (doesn't interfere with stdout)
     // This helps us verify we're meeting <5ms target
-    eprintln!("⚡ Loaded state in {}ms (load: {}ms)",
-        total_time.as_millis(),
-        load_time.as_millis()
+    output::diag(
+        output::Level::Info,
+        &format!("⚡ Loaded state in {}ms (load: {}ms)", total_time.as_millis(), load_time.as_millis()),
+        json!({
+            "total_ms": total_time.as_secs_f64() * 1000.0,
+            "load_ms": load_time.as_secs_f64() * 1000.0,
+        }),
     );
 
     // Warn if we're getting close to the 5ms limit
-    if total_time.as_millis() > 5 {
-        eprintln!("⚠️  Warning: get_state took {}ms (target: <5ms)", total_time.as_millis());
+    if at.is_none() && total_time.as_millis() > 5 {
+        output::diag(
+            output::Level::Warning,
+            &format!("get_state took {}ms (target: <5ms)", total_time.as_millis()),
+            json!({"total_ms": total_time.as_secs_f64() * 1000.0, "target_ms": 5}),
+        );
     }
 
-    Ok(())
+    Ok(json)
+}
+
+/// Move Blocked features to the front, keeping the order within each group
+fn blocked_first(state: &mut LegendState) {
+    state.features.sort_by_key(|f| f.status != FeatureStatus::Blocked);
+}
+
+/// Fold each feature's files_involved to at most `limit` entries
+fn collapse_file_lists(features: &mut [Feature], limit: usize) {
+    for feature in features.iter_mut().filter(|f| f.files_involved.len() > limit) {
+        feature.files_involved = file_lists::collapse(&feature.files_involved, limit);
+    }
+}
+
+/// "features, decisions" -> [Features, Decisions]
+fn parse_sections(list: &str) -> Result<Vec<Section>, Box<dyn std::error::Error>> {
+    let mut sections = Vec::new();
+    for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let section = match name {
+            "project" => Section::Project,
+            "focus" => Section::Focus,
+            "global" => Section::Global,
+            "conventions" => Section::Conventions,
+            "deadlines" => Section::Deadlines,
+            "domains" => Section::Domains,
+            "goals" => Section::Goals,
+            "features" => Section::Features,
+            "milestones" => Section::Milestones,
+            "decisions" => Section::Decisions,
+            "sessions" => Section::Sessions,
+            other => {
+                return Err(format!(
+                    "unknown section '{}' (expected a list of project, focus, global, conventions, deadlines, domains, goals, features, milestones, decisions, sessions)",
+                    other
+                )
+                .into())
+            }
+        };
+        if sections.contains(&section) {
+            return Err(format!("'{}' is listed twice", name).into());
+        }
+        sections.push(section);
+    }
+    Ok(sections)
+}
+
+/// The state as a JSON object with only `sections`, in their order
+///
+/// Written entry by entry: serde_json's Map would sort the keys
+#[derive(Clone, Copy)]
+struct Ordered<'a> {
+    state: &'a LegendState,
+    focus: &'a [Feature],
+    global: Option<&'a GlobalContext>,
+    deadlines: &'a [Deadline<'a>],
+    domains: &'a BTreeMap<String, DomainRollup>,
+    sections: &'a [Section],
+}
+
+impl Serialize for Ordered<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let state = self.state;
+        let mut map = serializer.serialize_map(None)?;
+        for section in self.sections {
+            match section {
+                Section::Project => {
+                    map.serialize_entry("project_name", &state.project_name)?;
+                    if !state.project.is_empty() {
+                        map.serialize_entry("project", &state.project)?;
+                    }
+                    map.serialize_entry("created_at", &state.created_at)?;
+                    map.serialize_entry("last_updated", &state.last_updated)?;
+                }
+                Section::Focus => map.serialize_entry("focus", self.focus)?,
+                Section::Global => {
+                    if let Some(global) = self.global {
+                        map.serialize_entry("global", global)?;
+                    }
+                }
+                Section::Conventions => map.serialize_entry("conventions", &state.conventions)?,
+                Section::Deadlines => map.serialize_entry("deadlines", self.deadlines)?,
+                Section::Domains => map.serialize_entry("domains", self.domains)?,
+                Section::Goals => map.serialize_entry("goals", &state.goals)?,
+                Section::Features if self.focus.is_empty() => map.serialize_entry("features", &state.features)?,
+                Section::Features => map.serialize_entry("features", &compact_features(&state.features))?,
+                Section::Milestones => map.serialize_entry("milestones", &state.milestones)?,
+                Section::Decisions => map.serialize_entry("decisions", &state.decisions)?,
+                Section::Sessions => map.serialize_entry("sessions", &state.sessions)?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// Where a paged get_state stopped: the state it read (by last_updated),
+/// the first feature not yet printed, and the budget to keep using
+///
+/// Printed as `<last_updated>.<offset>.<budget>`; agents treat it as opaque
+#[derive(Debug, PartialEq)]
+struct Continuation {
+    last_updated: i64,
+    offset: usize,
+    budget: usize,
+}
+
+impl Continuation {
+    fn parse(token: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let parts: Vec<&str> = token.trim().split('.').collect();
+        match parts.as_slice() {
+            [updated, offset, budget] => match (updated.parse(), offset.parse(), budget.parse()) {
+                (Ok(last_updated), Ok(offset), Ok(budget)) if budget > 0 => Ok(Continuation { last_updated, offset, budget }),
+                _ => Err(format!("Not a continuation token: {}", token).into()),
+            },
+            _ => Err(format!("Not a continuation token: {}", token).into()),
+        }
+    }
+
+    fn token(&self) -> String {
+        format!("{}.{}.{}", self.last_updated, self.offset, self.budget)
+    }
+
+    /// The offset to resume at, if the token still matches `state`
+    ///
+    /// Any save reorders or changes features, so an old token could skip
+    /// or repeat some; starting over is the honest answer
+    fn check(&self, state: &LegendState) -> Result<usize, Box<dyn std::error::Error>> {
+        if self.last_updated != state.last_updated || self.offset > state.features.len() {
+            return Err("The state changed since this continuation token was issued; start over with legend get_state --budget".into());
+        }
+        Ok(self.offset)
+    }
+}
+
+/// One page: `full` with only the features from `start` that fit in
+/// `budget` bytes (always at least one, so paging moves forward)
+#[derive(Serialize)]
+struct Page<'a> {
+    #[serde(flatten)]
+    body: Ordered<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    continuation: Option<String>,
+    remaining: usize,
+}
+
+fn render_page(full: &Ordered, start: usize, budget: usize) -> Result<String, Box<dyn std::error::Error>> {
+    let state = full.state;
+    let focus = full.focus;
+    let all = &state.features[start..];
+    // The other sections stay as they are; only the features change per try
+    let mut shell = state.clone();
+
+    let render = |shell: &mut LegendState, count: usize| -> Result<String, serde_json::Error> {
+        shell.features = all[..count].to_vec();
+        let remaining = all.len() - count;
+        let continuation = (remaining > 0).then(|| {
+            Continuation { last_updated: state.last_updated, offset: start + count, budget }.token()
+        });
+        let body = Ordered { state: shell, ..*full };
+        serde_json::to_string_pretty(&Page { body, continuation, remaining })
+    };
+
+    // Estimate from each feature's size (nested two levels deep, each line
+    // gains 4 spaces of indent), then trim until the real output fits
+    let mut used = render(&mut shell, 0)?.len();
+    let mut count = 0;
+    for feature in all {
+        let json = if focus.is_empty() {
+            serde_json::to_string_pretty(feature)?
+        } else {
+            serde_json::to_string_pretty(&compact_features(std::slice::from_ref(feature))[0])?
+        };
+        used += json.len() + 4 * json.lines().count() + 2;
+        if used > budget && count > 0 {
+            break;
+        }
+        count += 1;
+    }
+    let mut json = render(&mut shell, count)?;
+    while json.len() > budget && count > 1 {
+        count -= 1;
+        json = render(&mut shell, count)?;
+    }
+    Ok(json)
+}
+
+/// The soft limit in bytes: get_state.max_bytes, or max_tokens converted,
+/// whichever is lower
+fn max_bytes(config: &config::Config) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut limit = None;
+    for (key, scale) in [("get_state.max_bytes", 1), ("get_state.max_tokens", BYTES_PER_TOKEN)] {
+        if let Some(raw) = config.get(key) {
+            let value: usize = raw
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| format!("config: {} must be a positive whole number, got '{}'", key, raw))?;
+            limit = Some(limit.map_or(value * scale, |l: usize| l.min(value * scale)));
+        }
+    }
+    Ok(limit.unwrap_or(DEFAULT_MAX_BYTES))
+}
+
+/// get_state.max_files, or the default
+fn max_files(config: &config::Config) -> Result<usize, Box<dyn std::error::Error>> {
+    match config.get("get_state.max_files") {
+        None => Ok(file_lists::DEFAULT_MAX_FILES),
+        Some(raw) => raw
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("config: get_state.max_files must be a positive whole number, got '{}'", raw).into()),
+    }
+}
+
+/// What the hook prints when the full state is over the limit: what's
+/// due, one line of identity per feature, and how to get the rest
+#[derive(Serialize)]
+struct Compact<'a> {
+    project_name: &'a str,
+    #[serde(skip_serializing_if = "ProjectInfo::is_empty")]
+    project: &'a ProjectInfo,
+    compact: String,
+    #[serde(skip_serializing_if = "<[Feature]>::is_empty")]
+    focus: &'a [Feature],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    global: Vec<CompactFeature<'a>>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    conventions: &'a [String],
+    deadlines: &'a [Deadline<'a>],
+    features: Vec<CompactFeature<'a>>,
+    decisions: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct CompactFeature<'a> {
+    id: &'a str,
+    name: &'a str,
+    domain: &'a str,
+    status: &'a FeatureStatus,
+    priority: Priority,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocked_reason: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocked_by: Option<&'a str>,
+}
+
+fn compact_state<'a>(
+    state: &'a LegendState,
+    focus: &'a [Feature],
+    global: Option<&'a GlobalContext>,
+    deadlines: &'a [Deadline<'a>],
+    full_bytes: usize,
+    limit_bytes: usize,
+) -> Compact<'a> {
+    Compact {
+        project_name: &state.project_name,
+        project: &state.project,
+        conventions: &state.conventions,
+        focus,
+        deadlines,
+        compact: format!(
+            "The full state is {} bytes, over the {}-byte soft limit (get_state.max_bytes). Run legend search <keyword>, or legend get_state --budget to page through features with descriptions, context, and files.",
+            full_bytes, limit_bytes
+        ),
+        global: global.map(|g| compact_features(&g.features)).unwrap_or_default(),
+        features: compact_features(&state.features),
+        decisions: state.decisions.iter().map(|d| format!("#{} {}", d.id, d.title)).collect(),
+    }
+}
+
+fn compact_features(features: &[Feature]) -> Vec<CompactFeature<'_>> {
+    features
+        .iter()
+        .map(|f| CompactFeature {
+            id: &f.id,
+            name: &f.name,
+            domain: &f.domain,
+            status: &f.status,
+            priority: f.priority,
+            blocked_reason: f.blocked_reason.as_deref(),
+            blocked_by: f.blocked_by.as_deref(),
+        })
+        .collect()
+}
+
+/// Stand-in for a repo without Legend: no project name, features, or history
+fn empty_state() -> LegendState {
+    let mut state = LegendState::new(String::new());
+    state.created_at = 0;
+    state.last_updated = 0;
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_pick_and_order_keys() {
+        let state = empty_state();
+        let sections = parse_sections("decisions, project").unwrap();
+        let domains = BTreeMap::new();
+        let json = serde_json::to_string(&Ordered { state: &state, focus: &[], global: None, deadlines: &[], domains: &domains, sections: &sections }).unwrap();
+        assert_eq!(json, r#"{"decisions":[],"project_name":"","created_at":0,"last_updated":0}"#);
+
+        let mut described = empty_state();
+        described.project.tech_stack = vec!["Rust".to_string()];
+        let json = serde_json::to_string(&Ordered { state: &described, focus: &[], global: None, deadlines: &[], domains: &domains, sections: &sections }).unwrap();
+        assert!(json.ends_with(r#""project_name":"","project":{"tech_stack":["Rust"]},"created_at":0,"last_updated":0}"#));
+
+        // No global store, no global key
+        let sections = parse_sections("global,decisions").unwrap();
+        let json = serde_json::to_string(&Ordered { state: &state, focus: &[], global: None, deadlines: &[], domains: &domains, sections: &sections }).unwrap();
+        assert_eq!(json, r#"{"decisions":[]}"#);
+        let global = GlobalContext { features: Vec::new(), decisions: Vec::new() };
+        let json = serde_json::to_string(&Ordered { state: &state, focus: &[], global: Some(&global), deadlines: &[], domains: &domains, sections: &sections }).unwrap();
+        assert_eq!(json, r#"{"global":{"features":[],"decisions":[]},"decisions":[]}"#);
+
+        assert!(parse_sections("features,features").is_err());
+        assert!(parse_sections("alerts").is_err());
+    }
+
+    #[test]
+    fn test_focus_leads_and_summarizes_the_rest() {
+        let mut state = empty_state();
+        for id in ["auth", "billing"] {
+            state.features.push(crate::types::Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string()));
+        }
+        let focus = vec![state.features[1].clone()];
+        let sections = parse_sections("focus,features").unwrap();
+        let domains = BTreeMap::new();
+        let ordered = Ordered { state: &state, focus: &focus, global: None, deadlines: &[], domains: &domains, sections: &sections };
+        let json = serde_json::to_value(ordered).unwrap();
+        assert_eq!(json["focus"][0]["description"], "d");
+        assert_eq!(json["features"][1], json!({"id": "billing", "name": "billing", "domain": "web", "status": "Pending", "priority": "Medium"}));
+
+        let json = serde_json::to_value(Ordered { focus: &[], ..ordered }).unwrap();
+        assert_eq!(json["focus"], json!([]));
+        assert_eq!(json["features"][1]["description"], "d");
+    }
+
+    #[test]
+    fn test_pages_fit_the_budget() {
+        let mut state = empty_state();
+        state.last_updated = 77;
+        for i in 0..20 {
+            let id = format!("feature-{:02}", i);
+            state.features.push(crate::types::Feature::new(id.clone(), id, "web".to_string(), "x".repeat(200)));
+        }
+        let sections = parse_sections("project,features").unwrap();
+        let domains = BTreeMap::new();
+        let full = Ordered { state: &state, focus: &[], global: None, deadlines: &[], domains: &domains, sections: &sections };
+
+        let mut seen = Vec::new();
+        let mut start = 0;
+        loop {
+            let json = render_page(&full, start, 2_000).unwrap();
+            assert!(json.len() <= 2_000, "page of {} bytes", json.len());
+            let page: serde_json::Value = serde_json::from_str(&json).unwrap();
+            let ids: Vec<String> = page["features"].as_array().unwrap().iter().map(|f| f["id"].as_str().unwrap().to_string()).collect();
+            assert!(!ids.is_empty());
+            seen.extend(ids);
+            assert_eq!(page["remaining"], json!(20 - seen.len()));
+            match page.get("continuation") {
+                Some(token) => start = Continuation::parse(token.as_str().unwrap()).unwrap().check(&state).unwrap(),
+                None => break,
+            }
+        }
+        let expected: Vec<String> = state.features.iter().map(|f| f.id.clone()).collect();
+        assert_eq!(seen, expected);
+
+        // One feature bigger than the budget still goes out alone
+        let json = render_page(&full, 0, 10).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&json).unwrap()["features"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_continuation_token() {
+        let token = Continuation { last_updated: 1_709_251_200, offset: 12, budget: 40_000 };
+        assert_eq!(token.token(), "1709251200.12.40000");
+        assert_eq!(Continuation::parse("1709251200.12.40000").unwrap(), token);
+        assert!(Continuation::parse("1709251200.12").is_err());
+        assert!(Continuation::parse("a.b.c").is_err());
+
+        let mut state = empty_state();
+        state.last_updated = 1_709_251_200;
+        assert!(token.check(&state).is_err(), "offset past the end");
+        state.features = (0..12)
+            .map(|i| crate::types::Feature::new(i.to_string(), i.to_string(), "web".to_string(), "d".to_string()))
+            .collect();
+        assert_eq!(token.check(&state).unwrap(), 12);
+        state.last_updated += 1;
+        assert!(token.check(&state).is_err());
+    }
+
+    #[test]
+    fn test_blocked_first() {
+        let mut state = empty_state();
+        let statuses = [
+            ("a", FeatureStatus::InProgress),
+            ("b", FeatureStatus::Blocked),
+            ("c", FeatureStatus::Pending),
+            ("d", FeatureStatus::Blocked),
+        ];
+        for (id, status) in statuses {
+            let mut feature = crate::types::Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string());
+            feature.status = status;
+            state.features.push(feature);
+        }
+        blocked_first(&mut state);
+        let ids: Vec<&str> = state.features.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn test_soft_limit() {
+        let limit = |toml: &str| max_bytes(&config::parse_config(toml).unwrap());
+        assert_eq!(limit("").unwrap(), DEFAULT_MAX_BYTES);
+        assert_eq!(limit("[get_state]\nmax_bytes = 9000\nmax_tokens = 1000\n").unwrap(), 4000);
+        assert!(limit("[get_state]\nmax_bytes = 0\n").is_err());
+        assert_eq!(max_files(&config::parse_config("").unwrap()).unwrap(), file_lists::DEFAULT_MAX_FILES);
+        assert!(max_files(&config::parse_config("[get_state]\nmax_files = -1\n").unwrap()).is_err());
+
+        let mut state = empty_state();
+        state.features.push(crate::types::Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "d".to_string()));
+        let compact = serde_json::to_value(compact_state(&state, &[], None, &[], 50_000, 40_000)).unwrap();
+        assert_eq!(compact["features"][0], json!({"id": "auth", "name": "Auth", "domain": "security", "status": "Pending", "priority": "Medium"}));
+        assert!(compact.get("global").is_none());
+    }
 }
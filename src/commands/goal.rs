@@ -0,0 +1,241 @@
+// Goal command - the outcomes features exist for
+//
+// Usage:
+//   legend goal create <id> "<title>" [--target YYYY-MM-DD]
+//   legend goal link <goal-id> <feature-id> [<feature-id>...]
+//   legend goal link <goal-id> --remove <feature-id>...
+//   legend goal show                    - every goal with progress
+//   legend goal show <id> [--json]      - one goal and its features
+//
+// Milestones say when; goals say why. A goal lists feature IDs, and its
+// progress is the share of them that are Complete. Goals are stored in
+// state.lz4, so get_state includes them, and `legend why` names the
+// goals a feature serves.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::text;
+use crate::types::{current_timestamp, FeatureStatus, Goal, LegendState};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend goal create <id> \"<title>\" [--target YYYY-MM-DD]\n       legend goal link <goal-id> [--remove] <feature-id>...\n       legend goal show [<id>] [--json]";
+
+/// Handle the goal command
+pub fn handle_goal(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(String::as_str) {
+        Some("create") => create(&args[1..]),
+        Some("link") => link(&args[1..]),
+        Some("show") => show(&args[1..]),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn create(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut positional = Vec::new();
+    let mut target_date = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--target" => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--target expects a date (YYYY-MM-DD)\n{}", USAGE)))?;
+                target_date = Some(dates::parse_date(value)?);
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => positional.push(other.to_string()),
+        }
+        i += 1;
+    }
+    let (id, title) = match positional.as_slice() {
+        [id, title] if !id.trim().is_empty() && !title.trim().is_empty() => (id.clone(), title.clone()),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    if state.find_goal(&id).is_some() {
+        return Err(format!("Goal '{}' already exists", id).into());
+    }
+    state.goals.push(Goal {
+        id: id.clone(),
+        title,
+        target_date,
+        feature_ids: Vec::new(),
+        created_at: current_timestamp(),
+    });
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Created goal {}. Link the features it needs with 'legend goal link {} <feature-id>'.", id, id),
+        json!({"id": id}),
+    );
+    Ok(())
+}
+
+fn link(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let remove = args.iter().any(|a| a == "--remove");
+    let rest: Vec<&String> = args.iter().filter(|a| *a != "--remove").collect();
+    if let Some(flag) = rest.iter().find(|a| a.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+    let (goal_id, feature_args) = match rest.split_first() {
+        Some((id, features)) if !features.is_empty() => (id.as_str(), features),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let mut feature_ids = Vec::new();
+    for id in feature_args {
+        let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+        feature_ids.push(state.features[index].id.clone());
+    }
+
+    let goal = state
+        .goals
+        .iter_mut()
+        .find(|g| g.id == goal_id)
+        .ok_or_else(|| format!("Goal '{}' not found. Create it with 'legend goal create'.", goal_id))?;
+    if remove {
+        goal.feature_ids.retain(|id| !feature_ids.contains(id));
+    } else {
+        for id in feature_ids {
+            if !goal.feature_ids.contains(&id) {
+                goal.feature_ids.push(id);
+            }
+        }
+    }
+    let count = goal.feature_ids.len();
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Goal {} now has {} feature(s).", goal_id, count),
+        json!({"id": goal_id, "features": count}),
+    );
+    Ok(())
+}
+
+fn show(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let json_out = args.iter().any(|a| a == "--json");
+    let rest: Vec<&String> = args.iter().filter(|a| *a != "--json").collect();
+    if let Some(flag) = rest.iter().find(|a| a.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+
+    let state = storage::load_state()?;
+    let now = current_timestamp();
+    let goals: Vec<&Goal> = match rest.as_slice() {
+        [] => state.goals.iter().collect(),
+        [id] => vec![state.find_goal(id).ok_or_else(|| format!("Goal '{}' not found", id))?],
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    if json_out {
+        let report: Vec<_> = goals
+            .iter()
+            .map(|g| {
+                let (done, total) = progress(&state, g);
+                json!({"goal": g, "complete": done, "total": total})
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if goals.is_empty() {
+        output::info("No goals yet. Create one with 'legend goal create <id> \"<title>\"'.");
+    } else if rest.is_empty() {
+        for goal in goals {
+            println!("{}", summary_line(&state, goal, now));
+        }
+    } else {
+        print!("{}", render_goal(&state, goals[0], now));
+    }
+    Ok(())
+}
+
+/// (Complete, linked) features - IDs no longer in the state don't count
+fn progress(state: &LegendState, goal: &Goal) -> (usize, usize) {
+    let features: Vec<_> = goal.feature_ids.iter().filter_map(|id| state.find_feature(id)).collect();
+    let done = features.iter().filter(|f| f.status == FeatureStatus::Complete).count();
+    (done, features.len())
+}
+
+/// `signup  Self-serve signup  2/5 complete  target 2024-09-30 (in 12 days)`
+fn summary_line(state: &LegendState, goal: &Goal, now: i64) -> String {
+    let (done, total) = progress(state, goal);
+    let target = goal
+        .target_date
+        .map(|date| format!("target {} ({})", dates::format_date(date), dates::days_away(date, now)))
+        .unwrap_or_default();
+    let line = format!("{}  {}  {}/{} complete  {}", goal.id, goal.title, done, total, target);
+    line.trim_end().to_string()
+}
+
+/// The summary line, then one row per feature
+fn render_goal(state: &LegendState, goal: &Goal, now: i64) -> String {
+    let mut out = format!("{}\n\n", summary_line(state, goal, now));
+    if goal.feature_ids.is_empty() {
+        out.push_str("No features linked.\n");
+        return out;
+    }
+    let width = goal.feature_ids.iter().map(|id| text::width(id)).max().unwrap_or(0);
+    for id in &goal.feature_ids {
+        match state.find_feature(id) {
+            Some(f) => out.push_str(&format!("{}  {} {}\n", text::pad(id, width), text::pad(&f.status.to_string(), 12), f.name)),
+            None => out.push_str(&format!("{}  (no longer tracked)\n", text::pad(id, width))),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::SECONDS_PER_DAY;
+    use crate::types::Feature;
+
+    fn state() -> LegendState {
+        let mut state = LegendState::new("Demo".to_string());
+        let mut form = Feature::new("signup-form".to_string(), "Signup form".to_string(), "web".to_string(), "d".to_string());
+        form.status = FeatureStatus::Complete;
+        state.features.push(form);
+        state.features.push(Feature::new("billing".to_string(), "Billing".to_string(), "payments".to_string(), "d".to_string()));
+        state.goals.push(Goal {
+            id: "signup".to_string(),
+            title: "Self-serve signup".to_string(),
+            target_date: Some(10 * SECONDS_PER_DAY),
+            feature_ids: vec!["signup-form".to_string(), "billing".to_string(), "gone".to_string()],
+            created_at: 0,
+        });
+        state
+    }
+
+    #[test]
+    fn test_progress() {
+        let state = state();
+        assert_eq!(progress(&state, &state.goals[0]), (1, 2));
+    }
+
+    #[test]
+    fn test_render_goal() {
+        let state = state();
+        let text = render_goal(&state, &state.goals[0], 0);
+        assert!(text.starts_with("signup  Self-serve signup  1/2 complete  target 1970-01-11 (in 10 days)\n\n"));
+        assert!(text.contains("signup-form  Complete     Signup form\n"));
+        assert!(text.contains("gone         (no longer tracked)\n"));
+    }
+}
@@ -0,0 +1,203 @@
+// Handoff command - record that a feature is changing hands
+//
+// Usage:
+//   legend handoff <id> --to <owner>                   - hand off, summary generated
+//   legend handoff <id> --to <owner> --note "<text>"   - ...with what's in your head
+//   legend handoff <id> --to <owner> --from <owner>    - override the previous owner
+//
+// The handoff is appended to the feature's `handoffs` metadata (from, to,
+// when, a one-line status summary, the note) and `owner` becomes the new
+// owner, so the history travels with the feature and shows up in the
+// journal like any other change. The previous owner defaults to the
+// current `owner`, then to `git config user.name`.
+//
+// Each handoff is also sent to the [webhooks] in config.toml as a
+// "handoff" event (see webhooks.rs).
+
+use crate::config;
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::git;
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature};
+#[cfg(feature = "webhooks")]
+use crate::webhooks;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const USAGE: &str = "Usage: legend handoff <id> --to <owner> [--from <owner>] [--note \"<text>\"]";
+
+/// One entry in a feature's `handoffs` metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Handoff {
+    from: Option<String>,
+    to: String,
+    at: i64,
+    /// Where the feature stood when it changed hands
+    summary: String,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+/// Handle the handoff command
+pub fn handle_handoff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut id = None;
+    let mut to = None;
+    let mut from = None;
+    let mut note = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            flag @ ("--to" | "--from" | "--note") => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("{} expects a value\n{}", flag, USAGE)))?
+                    .clone();
+                match flag {
+                    "--to" => to = Some(value),
+                    "--from" => from = Some(value),
+                    _ => note = Some(value),
+                }
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => id = Some(other.to_string()),
+        }
+        i += 1;
+    }
+
+    let (id, to) = match (id, to) {
+        (Some(id), Some(to)) => (id, to),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = ids::resolve(&state.features, &id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+
+    let now = current_timestamp();
+    let from = from.or_else(|| current_owner(&state.features[index])).or_else(git::user_name);
+    let handoff = Handoff {
+        from,
+        summary: status_summary(&state.features[index], now),
+        to,
+        at: now,
+        note,
+    };
+    record_handoff(&mut state.features[index], &handoff)?;
+    state.touch();
+
+    journal::commit(&before, &state)?;
+
+    let feature = &state.features[index];
+    println!(
+        "Handed off {} from {} to {}: {}",
+        feature.id,
+        handoff.from.as_deref().unwrap_or("(unknown)"),
+        handoff.to,
+        handoff.summary
+    );
+
+    #[cfg(feature = "webhooks")]
+    {
+        let hooks = webhooks::from_config(&config::load_config()?);
+        if !hooks.is_empty() && crate::sandbox::is_active() {
+            output::info(&format!("[sandbox] Not notifying {} webhook(s).", hooks.len()));
+        } else if !hooks.is_empty() {
+            let sent = webhooks::notify(&hooks, "handoff", serde_json::json!({"id": feature.id, "name": feature.name, "handoff": handoff}));
+            output::diag(
+                output::Level::Info,
+                &format!("Notified {} of {} webhook(s).", sent, hooks.len()),
+                serde_json::json!({"webhooks": hooks.len(), "delivered": sent}),
+            );
+        }
+    }
+    #[cfg(not(feature = "webhooks"))]
+    if !config::load_config()?.section("webhooks").is_empty() {
+        output::warning("[webhooks] are configured, but this legend was built without the 'webhooks' feature; none were notified.");
+    }
+    Ok(())
+}
+
+/// The owner field, or the `owner` metadata earlier handoffs wrote
+fn current_owner(feature: &Feature) -> Option<String> {
+    feature
+        .owner
+        .clone()
+        .or_else(|| feature.metadata.get("owner").and_then(Value::as_str).map(str::to_string))
+}
+
+/// "InProgress since 2024-03-01, 4 file(s), last updated 2024-03-10"
+fn status_summary(feature: &Feature, now: i64) -> String {
+    let since = match (&feature.completed_at, &feature.started_at) {
+        (Some(at), _) | (None, Some(at)) => format!(" since {}", dates::format_date(*at)),
+        (None, None) => String::new(),
+    };
+    format!(
+        "{:?}{}, {} file(s), last updated {}",
+        feature.status,
+        since,
+        feature.files_involved.len(),
+        dates::format_date(feature.last_updated.min(now))
+    )
+}
+
+/// Append to the `handoffs` history and make the new owner current
+fn record_handoff(feature: &mut Feature, handoff: &Handoff) -> Result<(), Box<dyn std::error::Error>> {
+    let mut history: Vec<Value> = match feature.metadata.get("handoffs") {
+        Some(Value::Array(entries)) => entries.clone(),
+        Some(_) => return Err(format!("{}: metadata 'handoffs' is not a list", feature.id).into()),
+        None => Vec::new(),
+    };
+    history.push(serde_json::to_value(handoff)?);
+
+    feature.metadata.insert("handoffs".to_string(), Value::Array(history));
+    feature.metadata.remove("owner");
+    feature.owner = Some(handoff.to.clone());
+    feature.last_updated = handoff.at;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeatureStatus;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_handoff_appends_history() {
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "api".to_string(), "d".to_string());
+        feature.set_status(FeatureStatus::InProgress, 1709251200);
+        feature.files_involved = vec!["src/auth.rs".to_string()];
+        feature.last_updated = 1709251200;
+        assert_eq!(status_summary(&feature, 1709337600), "InProgress since 2024-03-01, 1 file(s), last updated 2024-03-01");
+
+        for (from, to) in [(None, "sam"), (Some("sam"), "lee")] {
+            let handoff = Handoff {
+                from: from.map(str::to_string),
+                to: to.to_string(),
+                at: 1709337600,
+                summary: status_summary(&feature, 1709337600),
+                note: Some("Token refresh is half done".to_string()),
+            };
+            record_handoff(&mut feature, &handoff).unwrap();
+        }
+
+        assert_eq!(current_owner(&feature).as_deref(), Some("lee"));
+        assert_eq!(feature.owner.as_deref(), Some("lee"));
+        let history = feature.metadata["handoffs"].as_array().unwrap().clone();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1]["from"], json!("sam"));
+        assert_eq!(history[0]["note"], json!("Token refresh is half done"));
+
+        feature.metadata.insert("handoffs".to_string(), json!("oops"));
+        assert!(record_handoff(&mut feature, &serde_json::from_value(history[0].clone()).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// History and undo commands - step back from a bad update
+//
+// Usage:
+//   legend history [--json]   - saved versions of the state, newest first
+//   legend undo               - restore the version before the last save
+//
+// Every save keeps a copy under .legend/history/ (see history.rs). Undo
+// restores the previous copy and forgets the undone one, so running it
+// again steps further back. If state.lz4 was replaced outside Legend
+// (a git checkout, say), undo goes back to the newest copy instead.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::history::{self, Snapshot};
+use crate::journal;
+use crate::output;
+use crate::storage;
+use serde_json::json;
+use std::fs;
+
+/// Handle the history command
+pub fn handle_history(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let json_out = match args {
+        [] => false,
+        [flag] if flag == "--json" => true,
+        _ => return Err(ExitError::new(exit::USAGE, "Usage: legend history [--json]").into()),
+    };
+
+    let snapshots = history::snapshots_in(&storage::legend_dir());
+    let current = fs::read(storage::state_file()).ok();
+
+    let mut rows = Vec::new();
+    for snapshot in snapshots.iter().rev() {
+        let bytes = fs::read(&snapshot.path)?;
+        let features = storage::decode_state(&bytes).map(|s| s.features.len()).ok();
+        rows.push((snapshot, features, current.as_deref() == Some(bytes.as_slice())));
+    }
+
+    if json_out {
+        let report: Vec<_> = rows
+            .iter()
+            .map(|(s, features, is_current)| {
+                json!({"seq": s.seq, "timestamp": s.timestamp, "features": features, "current": is_current})
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if rows.is_empty() {
+        output::info("No history yet. Every save from now on is kept in .legend/history/.");
+    } else {
+        for (snapshot, features, is_current) in &rows {
+            println!("{}", history_line(snapshot, *features, *is_current));
+        }
+    }
+    Ok(())
+}
+
+/// Handle the undo command
+pub fn handle_undo(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(other) = args.first() {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\nUsage: legend undo", other)).into());
+    }
+
+    let _lock = storage::lock_state()?;
+    let mut snapshots = history::snapshots_in(&storage::legend_dir());
+    let current = fs::read(storage::state_file())?;
+    // Drop the copy of what's on disk now; the one before it is the target
+    let undone = history::pop_current(&mut snapshots, &current);
+    let target = snapshots.pop().ok_or("Nothing to undo: no earlier version in .legend/history/")?;
+
+    let before = storage::decode_state(&current)?;
+    let restored = storage::decode_state(&fs::read(&target.path)?)?;
+    journal::commit(&before, &restored)?;
+
+    // The save above made a fresh copy of `target`; the old ones go
+    for old in undone.iter().chain([&target]) {
+        let _ = fs::remove_file(&old.path);
+    }
+
+    output::diag(
+        output::Level::Info,
+        &format!(
+            "Restored the state saved {} UTC ({} features).",
+            dates::format_datetime(target.timestamp),
+            restored.features.len()
+        ),
+        json!({"seq": target.seq, "timestamp": target.timestamp, "features": restored.features.len()}),
+    );
+    Ok(())
+}
+
+/// `#12  2024-03-01 13:05  14 features  (current)`
+fn history_line(snapshot: &Snapshot, features: Option<usize>, is_current: bool) -> String {
+    let count = match features {
+        Some(n) => format!("{} features", n),
+        None => "unreadable".to_string(),
+    };
+    let marker = if is_current { "  (current)" } else { "" };
+    format!("#{}  {}  {}{}", snapshot.seq, dates::format_datetime(snapshot.timestamp), count, marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_history_line() {
+        let snapshot = Snapshot {
+            seq: 12,
+            timestamp: 1709251200 + 13 * 3600,
+            path: PathBuf::from("000012-1709298000.lz4"),
+        };
+        assert_eq!(history_line(&snapshot, Some(14), true), "#12  2024-03-01 13:00  14 features  (current)");
+        assert_eq!(history_line(&snapshot, None, false), "#12  2024-03-01 13:00  unreadable");
+    }
+}
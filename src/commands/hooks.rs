@@ -0,0 +1,495 @@
+// Hooks command - Claude Code hook snippets, and the helper they call
+//
+// Usage:
+//   legend hooks print                          - settings.json snippet (style: full)
+//   legend hooks print --style <style>          - another strategy
+//   legend hooks print --event SessionStart     - just one event's entry
+//   legend hooks upgrade [--dry-run]            - rewrite old-style entries in place
+//   legend hooks prompt-context                 - what the prompt-aware hook printed before `legend hook`
+//   legend hook <event> [--style <style>]       - what an installed hook runs
+//
+// Styles:
+//   full          The whole state at session start (the compact summary
+//                 past get_state.max_bytes), a reminder on every prompt.
+//                 This is what `legend init` installs.
+//   compact       `legend show` (one line per feature) and recent session
+//                 summaries at session start; Claude asks for details
+//                 with search/get_state.
+//   budgeted      The whole state if it fits a size budget (40000
+//                 characters in the snippet - edit to taste), else the
+//                 compact table.
+//   prompt-aware  Compact at session start; on each prompt, the features
+//                 whose ID, name, domain, or tags appear in it.
+//
+// The snippet is `{"hooks": {...}}` with the chosen entries; paste it into
+// .claude/settings.json (merging with existing hooks) and adjust freely.
+// init builds its hooks from the same templates.
+//
+// Each entry is a single `legend hook <event>` call, so what a hook does
+// can change with the binary. Entries written before that were shell
+// one-liners (`echo '== Legend Context =='; legend get_state ...`);
+// `hooks upgrade` finds those - only exact copies of a template Legend
+// once wrote, so hand-edited and unrelated hooks are left alone - and
+// swaps in the current command for the same style. `diag health` and
+// `init` point at it when they see one.
+
+use crate::commands::{get_state, session, show};
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use crate::types::{Feature, LegendState};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+const USAGE: &str = "Usage: legend hooks print [--event SessionStart|UserPromptSubmit] [--style full|compact|budgeted|prompt-aware]\n       legend hooks upgrade [--dry-run]\n       legend hooks prompt-context";
+
+const HOOK_USAGE: &str = "Usage: legend hook SessionStart|UserPromptSubmit [--style full|compact|budgeted|prompt-aware]";
+
+/// Where init installs the hooks, relative to the project root
+pub const SETTINGS_PATH: &str = ".claude/settings.json";
+
+/// Characters of get_state the budgeted style prints before falling back to the table
+const BUDGET_CHARS: usize = 40_000;
+
+/// Most features prompt-context mentions
+const PROMPT_CONTEXT_LIMIT: usize = 5;
+
+const HINT: &str = "Legend available. Use legend search <keyword>, legend get_state, or pipe JSON to legend update.";
+
+/// Hook events Legend has templates for
+pub const EVENTS: [&str; 2] = ["SessionStart", "UserPromptSubmit"];
+
+/// A hook strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Full,
+    Compact,
+    Budgeted,
+    PromptAware,
+}
+
+impl Style {
+    fn parse(name: &str) -> Option<Style> {
+        match name {
+            "full" => Some(Style::Full),
+            "compact" => Some(Style::Compact),
+            "budgeted" => Some(Style::Budgeted),
+            "prompt-aware" => Some(Style::PromptAware),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Style::Full => "full",
+            Style::Compact => "compact",
+            Style::Budgeted => "budgeted",
+            Style::PromptAware => "prompt-aware",
+        }
+    }
+}
+
+/// The settings.json entry for one event under a style
+pub fn hook_entry(event: &str, style: Style) -> Option<Value> {
+    Some(entry_for(hook_command(event, style)?))
+}
+
+/// `legend hook <event>`, with --style where the style changes what it does
+fn hook_command(event: &str, style: Style) -> Option<String> {
+    let style = match (event, style) {
+        (_, Style::Full) | ("UserPromptSubmit", Style::Compact | Style::Budgeted) => None,
+        // Prompt-aware sessions start like compact ones
+        ("SessionStart", Style::PromptAware) => Some(Style::Compact),
+        ("SessionStart" | "UserPromptSubmit", style) => Some(style),
+        _ => return None,
+    };
+    Some(match style {
+        Some(style) => format!("legend hook {} --style {}", event, style.name()),
+        None => format!("legend hook {}", event),
+    })
+}
+
+fn entry_for(command: String) -> Value {
+    json!({
+        "matcher": "",
+        "hooks": [{
+            "type": "command",
+            "command": command
+        }]
+    })
+}
+
+/// The shell one-liner an event's hook ran under a style before `legend
+/// hook`; these are what `hooks upgrade` recognizes
+fn legacy_command(event: &str, style: Style) -> Option<String> {
+    let command = match (event, style) {
+        ("SessionStart", Style::Full) => {
+            "echo '== Legend Context =='; legend get_state 2>/dev/null || echo 'Legend state not found'".to_string()
+        }
+        ("SessionStart", Style::Compact | Style::PromptAware) => {
+            "echo '== Legend Context (details: legend search <keyword>) =='; legend show 2>/dev/null || echo 'Legend state not found'; legend session recap 2>/dev/null".to_string()
+        }
+        ("SessionStart", Style::Budgeted) => "echo '== Legend Context =='; \
+             s=$(legend get_state 2>/dev/null) || { echo 'Legend state not found'; exit 0; }; \
+             if [ ${#s} -le 40000 ]; then echo \"$s\"; else legend show; fi"
+            .to_string(),
+        ("UserPromptSubmit", Style::PromptAware) => "legend --if-initialized hooks prompt-context".to_string(),
+        ("UserPromptSubmit", _) => format!("echo '{}'", json!({"additionalContext": HINT})),
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// The current command replacing an old-style one, if `command` is one
+fn upgraded_command(event: &str, command: &str) -> Option<String> {
+    // The first init wrote the prompt reminder with a space after the colon
+    let first_prompt_hook = format!("echo '{{\"additionalContext\": \"{}\"}}'", HINT);
+    if event == "UserPromptSubmit" && command == first_prompt_hook {
+        return hook_command(event, Style::Full);
+    }
+    [Style::Full, Style::Compact, Style::Budgeted, Style::PromptAware]
+        .into_iter()
+        .find(|style| legacy_command(event, *style).as_deref() == Some(command))
+        .and_then(|style| hook_command(event, style))
+}
+
+/// Is this hook command one of Legend's?
+pub fn is_legend_command(command: &str) -> bool {
+    ["legend hook ", "legend get_state", "legend show", "legend --if-initialized"]
+        .iter()
+        .any(|needle| command.contains(needle))
+}
+
+/// Rewrite old-style Legend commands in a settings.json value; returns
+/// how many were changed
+pub fn upgrade_settings(settings: &mut Value) -> usize {
+    let mut upgraded = 0;
+    let Some(events) = settings.get_mut("hooks").and_then(Value::as_object_mut) else {
+        return 0;
+    };
+    for (event, entries) in events.iter_mut() {
+        let entries = entries.as_array_mut().into_iter().flatten();
+        let hooks = entries.filter_map(|e| e.get_mut("hooks").and_then(Value::as_array_mut)).flatten();
+        for hook in hooks {
+            let replacement = hook.get("command").and_then(Value::as_str).and_then(|c| upgraded_command(event, c));
+            if let Some(command) = replacement {
+                hook["command"] = json!(command);
+                upgraded += 1;
+            }
+        }
+    }
+    upgraded
+}
+
+/// Old-style Legend hook commands in the settings file at `path` (0 if
+/// there is none or it can't be parsed)
+pub fn count_legacy(path: &Path) -> usize {
+    let Some(mut settings) = fs::read_to_string(path).ok().and_then(|c| serde_json::from_str::<Value>(&c).ok()) else {
+        return 0;
+    };
+    upgrade_settings(&mut settings)
+}
+
+/// Handle the hooks command
+pub fn handle_hooks(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(String::as_str) {
+        Some("print") => print_snippet(&args[1..]),
+        Some("upgrade") => upgrade(&args[1..]),
+        Some("prompt-context") if args.len() == 1 => prompt_context(),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn upgrade(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let dry_run = match args {
+        [] => false,
+        [flag] if flag == "--dry-run" => true,
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+    let path = Path::new(SETTINGS_PATH);
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => {
+            output::info(&format!("No {} here; nothing to upgrade.", SETTINGS_PATH));
+            return Ok(());
+        }
+    };
+    let mut settings: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", SETTINGS_PATH, e))?;
+
+    let upgraded = upgrade_settings(&mut settings);
+    if upgraded == 0 {
+        output::info("Legend hooks are already current.");
+    } else if dry_run {
+        output::info(&format!("{} old-style Legend hook(s) would be rewritten:", upgraded));
+        println!("{}", serde_json::to_string_pretty(&settings)?);
+    } else {
+        fs::write(path, serde_json::to_string_pretty(&settings)?)
+            .map_err(|e| format!("Failed to write {}: {}", SETTINGS_PATH, e))?;
+        output::info(&format!("✓ Rewrote {} old-style Legend hook(s) in {}", upgraded, SETTINGS_PATH));
+    }
+    Ok(())
+}
+
+/// Handle `legend hook <event>`: what the installed hook entry runs
+///
+/// Failing to load the state prints the same "not found" line the shell
+/// one-liners did, so a session still starts
+pub fn handle_hook(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut event = None;
+    let mut style = Style::Full;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--style" => {
+                i += 1;
+                style = args
+                    .get(i)
+                    .and_then(|s| Style::parse(s))
+                    .ok_or_else(|| ExitError::new(exit::USAGE, HOOK_USAGE))?;
+            }
+            name if event.is_none() => {
+                event = Some(*EVENTS.iter().find(|e| e.eq_ignore_ascii_case(name)).ok_or_else(|| ExitError::new(exit::USAGE, HOOK_USAGE))?);
+            }
+            _ => return Err(ExitError::new(exit::USAGE, HOOK_USAGE).into()),
+        }
+        i += 1;
+    }
+
+    match (event.ok_or_else(|| ExitError::new(exit::USAGE, HOOK_USAGE))?, style) {
+        ("SessionStart", Style::Full) => {
+            println!("== Legend Context ==");
+            match get_state::render_state(&["--hook".to_string()]) {
+                Ok(json) => println!("{}", json),
+                Err(_) => println!("Legend state not found"),
+            }
+        }
+        ("SessionStart", Style::Budgeted) => {
+            println!("== Legend Context ==");
+            match get_state::render_state(&[]) {
+                Ok(json) if json.chars().count() <= BUDGET_CHARS => println!("{}", json),
+                Ok(_) => show::handle_show(&[])?,
+                Err(_) => println!("Legend state not found"),
+            }
+        }
+        ("SessionStart", _) => {
+            println!("== Legend Context (details: legend search <keyword>) ==");
+            if show::handle_show(&[]).is_err() {
+                println!("Legend state not found");
+            }
+            let _ = session::handle_session(&["recap".to_string()]);
+        }
+        (_, Style::PromptAware) => {
+            if storage::is_initialized() {
+                prompt_context()?;
+            }
+        }
+        _ => {
+            let state = storage::is_initialized().then(storage::load_state).and_then(Result::ok);
+            let context = match &state {
+                Some(state) => with_conventions(HINT.to_string(), state),
+                None => HINT.to_string(),
+            };
+            println!("{}", json!({"additionalContext": context}));
+        }
+    }
+    Ok(())
+}
+
+fn print_snippet(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut style = Style::Full;
+    let mut event = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--style" => {
+                i += 1;
+                style = args
+                    .get(i)
+                    .and_then(|s| Style::parse(s))
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--style expects full, compact, budgeted, or prompt-aware\n{}", USAGE)))?;
+            }
+            "--event" => {
+                i += 1;
+                let name = args.get(i).map(String::as_str).unwrap_or_default();
+                event = Some(
+                    *EVENTS
+                        .iter()
+                        .find(|e| e.eq_ignore_ascii_case(name))
+                        .ok_or_else(|| ExitError::new(exit::USAGE, format!("--event expects SessionStart or UserPromptSubmit\n{}", USAGE)))?,
+                );
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+
+    println!("{}", serde_json::to_string_pretty(&snippet(style, event))?);
+    Ok(())
+}
+
+/// `{"hooks": {"<event>": [<entry>]}}` for one or every event
+fn snippet(style: Style, event: Option<&str>) -> Value {
+    let mut hooks = serde_json::Map::new();
+    for name in EVENTS.iter().filter(|name| event.is_none_or(|e| e == **name)) {
+        if let Some(entry) = hook_entry(name, style) {
+            hooks.insert(name.to_string(), json!([entry]));
+        }
+    }
+    json!({ "hooks": hooks })
+}
+
+/// `legend hooks prompt-context`: read the UserPromptSubmit payload on
+/// stdin and point Claude at the features the prompt mentions
+fn prompt_context() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    // Claude Code sends {"prompt": "...", ...}; plain text works too
+    let prompt = serde_json::from_str::<Value>(&input)
+        .ok()
+        .and_then(|v| v.get("prompt").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or(input);
+
+    let state = storage::load_state()?;
+    let context = with_conventions(context_for_prompt(&state, &prompt), &state);
+    println!("{}", json!({"additionalContext": context}));
+    Ok(())
+}
+
+/// Words of three or more characters, lowercased
+fn tokens(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn feature_tokens(feature: &Feature) -> BTreeSet<String> {
+    let mut words = tokens(&feature.id);
+    words.extend(tokens(&feature.name));
+    words.extend(tokens(&feature.domain));
+    for tag in &feature.tags {
+        words.extend(tokens(tag));
+    }
+    words
+}
+
+/// The features a prompt mentions (most matched words, then most recent),
+/// or the general hint when it mentions none
+fn context_for_prompt(state: &LegendState, prompt: &str) -> String {
+    let words = tokens(prompt);
+    let mut hits: Vec<(usize, &Feature)> = state
+        .features
+        .iter()
+        .map(|f| (feature_tokens(f).intersection(&words).count(), f))
+        .filter(|(n, _)| *n > 0)
+        .collect();
+    hits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.recency_score.total_cmp(&a.1.recency_score)));
+
+    if hits.is_empty() {
+        return HINT.to_string();
+    }
+    let listed: Vec<String> = hits
+        .iter()
+        .take(PROMPT_CONTEXT_LIMIT)
+        .map(|(_, f)| format!("{} ({}): {}", f.id, f.status, f.name))
+        .collect();
+    format!(
+        "Legend features related to this prompt: {}. Run legend search <id> for details; pipe JSON to legend update after changes.",
+        listed.join("; ")
+    )
+}
+
+/// The project's conventions after `context`, so every prompt carries them
+fn with_conventions(context: String, state: &LegendState) -> String {
+    if state.conventions.is_empty() {
+        return context;
+    }
+    let rules: Vec<&str> = state.conventions.iter().map(|c| c.trim_end_matches('.')).collect();
+    format!("{} Project conventions: {}.", context, rules.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_styles() {
+        let full = snippet(Style::Full, None);
+        let session = full["hooks"]["SessionStart"][0]["hooks"][0]["command"].as_str().unwrap();
+        assert_eq!(session, "legend hook SessionStart");
+        assert_eq!(full["hooks"]["UserPromptSubmit"][0]["hooks"][0]["command"], json!("legend hook UserPromptSubmit"));
+        let compact = snippet(Style::Compact, Some("SessionStart"));
+        assert_eq!(compact["hooks"]["SessionStart"][0]["hooks"][0]["command"], json!("legend hook SessionStart --style compact"));
+
+        let aware = snippet(Style::PromptAware, Some("UserPromptSubmit"));
+        assert!(aware["hooks"].get("SessionStart").is_none());
+        assert_eq!(
+            aware["hooks"]["UserPromptSubmit"][0]["hooks"][0]["command"],
+            json!("legend hook UserPromptSubmit --style prompt-aware")
+        );
+
+        for style in [Style::Full, Style::Compact, Style::Budgeted, Style::PromptAware] {
+            for event in EVENTS {
+                let entry = hook_entry(event, style).unwrap();
+                assert!(is_legend_command(entry["hooks"][0]["command"].as_str().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_upgrade_settings() {
+        let legacy = |event: &str, style: Style| entry_for(legacy_command(event, style).unwrap());
+        let mut settings = json!({
+            "hooks": {
+                "SessionStart": [legacy("SessionStart", Style::Budgeted), entry_for("./scripts/warm-cache.sh".to_string())],
+                "UserPromptSubmit": [
+                    legacy("UserPromptSubmit", Style::PromptAware),
+                    entry_for(format!("echo '{{\"additionalContext\": \"{}\"}}'", HINT)),
+                    entry_for("legend --if-initialized get_state".to_string()),
+                ]
+            },
+            "permissions": {"allow": ["Bash(legend:*)"]}
+        });
+        let untouched = settings["permissions"].clone();
+
+        assert_eq!(upgrade_settings(&mut settings), 3);
+        let command = |event: &str, i: usize| settings["hooks"][event][i]["hooks"][0]["command"].as_str().unwrap().to_string();
+        assert_eq!(command("SessionStart", 0), "legend hook SessionStart --style budgeted");
+        assert_eq!(command("SessionStart", 1), "./scripts/warm-cache.sh");
+        assert_eq!(command("UserPromptSubmit", 0), "legend hook UserPromptSubmit --style prompt-aware");
+        assert_eq!(command("UserPromptSubmit", 1), "legend hook UserPromptSubmit");
+        // Hand-written Legend commands are not templates and stay as they are
+        assert_eq!(command("UserPromptSubmit", 2), "legend --if-initialized get_state");
+        assert_eq!(settings["permissions"], untouched);
+
+        assert_eq!(upgrade_settings(&mut settings), 0);
+    }
+
+    #[test]
+    fn test_context_for_prompt() {
+        let mut state = LegendState::new("Demo".to_string());
+        let mut auth = Feature::new("auth-login".to_string(), "Login".to_string(), "security".to_string(), "d".to_string());
+        auth.tags = vec!["sessions".to_string()];
+        state.features.push(auth);
+        state.features.push(Feature::new("billing".to_string(), "Invoices".to_string(), "payments".to_string(), "d".to_string()));
+
+        let context = context_for_prompt(&state, "Why do login sessions expire so fast?");
+        assert!(context.starts_with("Legend features related to this prompt: auth-login (Pending): Login."));
+        assert!(!context.contains("billing"));
+        assert_eq!(context_for_prompt(&state, "fix the readme"), HINT);
+
+        assert_eq!(with_conventions("Hint.".to_string(), &state), "Hint.");
+        state.conventions = vec!["No unwrap in prod code.".to_string(), "snake_case table names".to_string()];
+        assert_eq!(
+            with_conventions("Hint.".to_string(), &state),
+            "Hint. Project conventions: No unwrap in prod code; snake_case table names."
+        );
+    }
+}
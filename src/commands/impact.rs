@@ -0,0 +1,300 @@
+// Impact command - what else might break if I touch this?
+//
+// Usage:
+//   legend impact <feature-id>   - features sharing files with that feature
+//   legend impact <file>         - features tracking that file (or its directory)
+//   legend impact <x> --analyze  - also follow imports between files (see analysis.rs)
+//
+// Output: JSON array on stdout (for Claude), strongest overlap first,
+// with a one-line summary on stderr.
+//
+// Overlap strength is the number of shared file entries plus, with
+// --analyze, the number of files linked to the target by an import.
+// Directory entries ("src/auth/") overlap every path below them.
+// Links between a target feature and others count too, one each: a
+// feature that depends on it, one it depends on itself, and relations in
+// either direction (blocks, relates_to, duplicates, and blocked_by, which
+// counts as the blocker's `blocks`).
+//
+// --analyze scans the project root (the directory holding .legend), so
+// its paths line up with files_involved from any subdirectory.
+
+use crate::access;
+use crate::analysis::{self, DependencyGraph};
+use crate::config::{self, LimitsConfig};
+use crate::deps;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use crate::types::{Feature, LegendState, RelationKind};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+
+/// One feature affected by a change
+#[derive(Debug, Serialize)]
+struct ImpactEntry {
+    id: String,
+    name: String,
+    strength: usize,
+    shared_files: Vec<String>,
+    /// Files importing, or imported by, the target (--analyze only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    linked_files: Vec<String>,
+    /// Lists the target feature in depends_on
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    depends_on_target: bool,
+    /// Listed in the target feature's depends_on
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    target_depends_on: bool,
+    /// Relation kinds between it and the target feature, either way
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    relations: Vec<&'static str>,
+}
+
+/// Handle the impact command
+pub fn handle_impact(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: legend impact <feature-id|file> [--analyze]";
+
+    let mut target: Option<&String> = None;
+    let mut use_imports = false;
+    for arg in args {
+        match arg.as_str() {
+            "--analyze" => use_imports = true,
+            flag if flag.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+            }
+            _ => target = Some(arg),
+        }
+    }
+    let target = target.ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+
+    let state = storage::load_state()?;
+    let graph = if use_imports {
+        let limits = LimitsConfig::from_config(&config::load_config()?)?;
+        Some(analysis::analyze_project(&storage::project_root(), &limits)?)
+    } else {
+        None
+    };
+    let (description, entries) = analyze(&state, target, graph.as_ref());
+    access::record_access(state.find_feature(target).map(|f| f.id.as_str()));
+
+    println!("{}", serde_json::to_string_pretty(&entries)?);
+    output::diag(
+        output::Level::Info,
+        &format!("{} feature(s) overlap with {}", entries.len(), description),
+        json!({"count": entries.len()}),
+    );
+
+    Ok(())
+}
+
+/// Find features overlapping `target` (a feature ID, or else a file path)
+///
+/// With a dependency graph, features tracking files that import (or are
+/// imported by) the target's files count too.
+/// Returns a description of what was analyzed and the affected features
+fn analyze(
+    state: &LegendState,
+    target: &str,
+    graph: Option<&DependencyGraph>,
+) -> (String, Vec<ImpactEntry>) {
+    let target_feature = state.find_feature(target);
+    let (description, target_id, target_files) = match target_feature {
+        Some(feature) => (
+            format!("feature '{}'", feature.id),
+            Some(feature.id.as_str()),
+            feature.files_involved.clone(),
+        ),
+        None => (format!("file '{}'", target), None, vec![target.to_string()]),
+    };
+
+    let linked: Vec<String> = graph
+        .map(|g| import_neighbors(g, &target_files))
+        .unwrap_or_default();
+    let dependent_ids: Vec<&str> = target_id
+        .map(|id| deps::dependents(&state.features, id).iter().map(|f| f.id.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut entries: Vec<ImpactEntry> = state
+        .features
+        .iter()
+        .filter(|f| Some(f.id.as_str()) != target_id)
+        .filter_map(|feature| {
+            let shared = shared_files(feature, &target_files);
+            let linked_files: Vec<String> = linked
+                .iter()
+                .filter(|file| feature.involves_file(file))
+                .cloned()
+                .collect();
+            let depends_on_target = dependent_ids.contains(&feature.id.as_str());
+            let target_depends_on = target_feature.is_some_and(|t| t.depends_on.contains(&feature.id));
+            let relations = target_feature.map(|t| relations_between(t, feature)).unwrap_or_default();
+            let strength = shared.len()
+                + linked_files.len()
+                + usize::from(depends_on_target)
+                + usize::from(target_depends_on)
+                + relations.len();
+            if strength == 0 {
+                return None;
+            }
+            Some(ImpactEntry {
+                id: feature.id.clone(),
+                name: feature.name.clone(),
+                strength,
+                shared_files: shared,
+                linked_files,
+                depends_on_target,
+                target_depends_on,
+                relations,
+            })
+        })
+        .collect();
+
+    // Strongest first, then by ID so output is stable
+    entries.sort_by(|a, b| b.strength.cmp(&a.strength).then_with(|| a.id.cmp(&b.id)));
+
+    (description, entries)
+}
+
+/// Relation kinds linking `a` and `b` in either direction, each once
+fn relations_between(a: &Feature, b: &Feature) -> Vec<&'static str> {
+    let mut kinds = BTreeSet::new();
+    for (from, to) in [(a, b), (b, a)] {
+        kinds.extend(from.relations.iter().filter(|r| r.target == to.id).map(|r| r.kind.as_str()));
+        if to.blocked_by.as_deref() == Some(from.id.as_str()) {
+            kinds.insert(RelationKind::Blocks.as_str());
+        }
+    }
+    kinds.into_iter().collect()
+}
+
+/// The entries of `feature.files_involved` that overlap any of `targets`
+fn shared_files(feature: &Feature, targets: &[String]) -> Vec<String> {
+    feature
+        .files_involved
+        .iter()
+        .filter(|file| targets.iter().any(|t| paths_overlap(file, t)))
+        .cloned()
+        .collect()
+}
+
+/// Project files linked by an import to any of `targets`, excluding the targets
+fn import_neighbors(graph: &DependencyGraph, targets: &[String]) -> Vec<String> {
+    let is_target = |file: &str| targets.iter().any(|t| paths_overlap(t, file));
+
+    let mut neighbors = BTreeSet::new();
+    for file in graph.files().filter(|f| is_target(f)) {
+        neighbors.extend(graph.neighbors(file));
+    }
+    neighbors.into_iter().filter(|f| !is_target(f)).collect()
+}
+
+/// Same file, or one is a directory entry containing the other
+fn paths_overlap(a: &str, b: &str) -> bool {
+    let a = a.trim_start_matches("./");
+    let b = b.trim_start_matches("./");
+    a == b || (a.ends_with('/') && b.starts_with(a)) || (b.ends_with('/') && a.starts_with(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Relation;
+
+    fn feature(id: &str, files: &[&str]) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), "core".to_string(), "d".to_string());
+        f.files_involved = files.iter().map(|s| s.to_string()).collect();
+        f
+    }
+
+    #[test]
+    fn test_analyze_feature() {
+        let mut state = LegendState::new("Test".to_string());
+        state.features = vec![
+            feature("auth", &["src/auth.rs", "src/session.rs", "src/db/"]),
+            feature("session", &["src/session.rs", "src/db/pool.rs"]),
+            feature("billing", &["src/db/pool.rs"]),
+            feature("docs", &["README.md"]),
+        ];
+
+        let (_, entries) = analyze(&state, "auth", None);
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["session", "billing"]);
+        assert_eq!(entries[0].strength, 2);
+
+        let (description, entries) = analyze(&state, "./src/db/pool.rs", None);
+        assert!(description.starts_with("file"));
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_analyze_follows_imports() {
+        let mut state = LegendState::new("Test".to_string());
+        state.features = vec![
+            feature("auth", &["src/auth.rs"]),
+            feature("storage", &["src/storage.rs"]),
+            feature("docs", &["README.md"]),
+        ];
+        let files = vec!["src/auth.rs".to_string(), "src/storage.rs".to_string()];
+        let graph = DependencyGraph::build(&files, |file| match file {
+            "src/auth.rs" => Some("use crate::storage;\n".to_string()),
+            _ => Some(String::new()),
+        });
+
+        let (_, entries) = analyze(&state, "auth", Some(&graph));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "storage");
+        assert_eq!(entries[0].linked_files, vec!["src/storage.rs"]);
+        assert!(entries[0].shared_files.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_includes_dependents() {
+        let mut state = LegendState::new("Test".to_string());
+        let mut checkout = feature("checkout", &["src/cart.rs"]);
+        checkout.depends_on = vec!["auth".to_string()];
+        state.features = vec![feature("auth", &["src/auth.rs"]), checkout];
+
+        let (_, entries) = analyze(&state, "auth", None);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].depends_on_target);
+        assert_eq!(entries[0].strength, 1);
+    }
+
+    #[test]
+    fn test_analyze_includes_dependencies_and_relations() {
+        let mut state = LegendState::new("Test".to_string());
+        let mut auth = feature("auth", &["src/auth.rs"]);
+        auth.depends_on = vec!["db".to_string()];
+        auth.relations = vec![Relation { kind: RelationKind::RelatesTo, target: "sso".to_string() }];
+        let mut checkout = feature("checkout", &["src/cart.rs"]);
+        checkout.blocked_by = Some("auth".to_string());
+        let mut legacy = feature("legacy-login", &["src/old.rs"]);
+        legacy.relations = vec![Relation { kind: RelationKind::Duplicates, target: "auth".to_string() }];
+        state.features = vec![
+            auth,
+            feature("db", &["src/db.rs"]),
+            feature("sso", &["src/sso.rs"]),
+            checkout,
+            legacy,
+            feature("docs", &["README.md"]),
+        ];
+
+        let (_, entries) = analyze(&state, "auth", None);
+        let found = |id: &str| entries.iter().find(|e| e.id == id).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert!(found("db").target_depends_on);
+        assert_eq!(found("sso").relations, vec!["relates_to"]);
+        assert_eq!(found("checkout").relations, vec!["blocks"]);
+        assert_eq!(found("legacy-login").relations, vec!["duplicates"]);
+        assert!(entries.iter().all(|e| e.strength == 1));
+    }
+
+    #[test]
+    fn test_paths_overlap() {
+        assert!(paths_overlap("src/db/", "src/db/pool.rs"));
+        assert!(paths_overlap("./src/a.rs", "src/a.rs"));
+        assert!(!paths_overlap("src/db", "src/db/pool.rs"));
+    }
+}
@@ -0,0 +1,403 @@
+// Import command - seed Legend from an export file
+//
+// Usage:
+//   legend import <export.json|.yaml|.csv>         (from `legend export`)
+//   legend import --format yaml <file>             - when the extension doesn't say
+//   legend import --from trello <board.json>
+//   legend import --from notion-csv <database.csv>
+//   legend import --from github-projects <items.json>
+//   legend import ... --domain <d>          - domain for items whose export has none
+//   legend import ... --filter "status:Pending" - only import matching features
+//
+// Parsing of third-party formats lives in importers.rs; this file turns
+// items into features, filters them (same syntax as search), and merges.
+// Import only ever adds: a feature whose id already exists is left
+// untouched and reported as skipped, so re-running an import is safe.
+// The rest of a full Legend export is merged the same way (merge_rest).
+
+use serde_json::json;
+use super::export::{self, Export};
+use super::export_formats::{self, ExportFormat};
+use super::search;
+use crate::config;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::importers::{self, ImportedItem, Source};
+use crate::journal;
+use crate::output;
+use crate::progress::Progress;
+use crate::storage;
+use crate::types::{Feature, LegendState};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+const DEFAULT_DOMAIN: &str = "imported";
+const USAGE: &str = "Usage: legend import <file> [--format json|yaml|csv] [--filter <expr>]
+       legend import --from trello|notion-csv|github-projects <file> [--domain <d>] [--filter <expr>]";
+
+/// Handle the import command
+pub fn handle_import(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (args, filter) = export::take_filter_flag(args)?;
+    let query = filter.as_deref().map(search::parse_filter).transpose()?;
+
+    let mut from = None;
+    let mut format = None;
+    let mut domain = DEFAULT_DOMAIN.to_string();
+    let mut path = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--from" => {
+                i += 1;
+                from = Some(
+                    args.get(i)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, format!("--from expects a source\n{}", USAGE)))?
+                        .clone(),
+                );
+            }
+            "--format" => {
+                i += 1;
+                let name = args.get(i).ok_or_else(|| ExitError::new(exit::USAGE, format!("--format expects json, yaml or csv\n{}", USAGE)))?;
+                format = Some(ExportFormat::parse(name)?);
+            }
+            "--domain" => {
+                i += 1;
+                domain = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--domain expects a value\n{}", USAGE)))?
+                    .clone();
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            file => path = Some(file.to_string()),
+        }
+        i += 1;
+    }
+
+    let from = from.unwrap_or_else(|| "legend".to_string());
+    let path = path.ok_or_else(|| ExitError::new(exit::USAGE, format!("Missing export file\n{}", USAGE)))?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut export = if from == "legend" {
+        let format = format.unwrap_or_else(|| ExportFormat::detect(&path, &content));
+        export_formats::parse(&content, format)?
+    } else if format.is_some() {
+        return Err(ExitError::new(exit::USAGE, format!("--format is for Legend exports; --from {} reads its own format\n{}", from, USAGE)).into());
+    } else {
+        Export {
+            features: items_to_features(importers::parse(Source::parse(&from)?, &content)?, &domain),
+            ..Export::default()
+        }
+    };
+    let candidates = std::mem::take(&mut export.features);
+
+    // Imported IDs follow the project's namespacing setting like any new feature
+    let namespace = config::namespaced_ids(&config::load_config()?)?;
+
+    let total = candidates.len();
+    let candidates: Vec<Feature> = candidates
+        .into_iter()
+        .map(|mut f| {
+            if namespace {
+                f.id = ids::namespaced(&f.domain, &f.id);
+            }
+            f
+        })
+        .filter(|f| query.as_ref().is_none_or(|q| search::matches_query(f, q)))
+        .collect();
+    if query.is_some() {
+        output::diag(
+            output::Level::Info,
+            &format!("Filter matched {} of {} features in {}", candidates.len(), total, path),
+            json!({"matched": candidates.len(), "total": total}),
+        );
+    }
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let focus = std::mem::take(&mut export.focus);
+    let (extras, renumbered) = merge_rest(&mut state, export);
+    let candidates = candidates
+        .into_iter()
+        .map(|mut f| {
+            for link in &mut f.decisions {
+                link.id = renumbered.get(&link.id).copied().unwrap_or(link.id);
+            }
+            f
+        })
+        .collect();
+    let (added, skipped) = merge_features(&mut state, candidates);
+    if state.focus.is_empty() {
+        state.focus = focus.into_iter().filter(|id| state.find_feature(id).is_some()).collect();
+    }
+
+    for id in &skipped {
+        output::diag(
+            output::Level::Warning,
+            &format!("Skipped {}: a feature with this id already exists", id),
+            json!({"id": id}),
+        );
+    }
+
+    if added.is_empty() && extras == 0 && state.focus == before.focus {
+        println!("Nothing imported from {} ({} skipped)", path, skipped.len());
+        return Ok(());
+    }
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let extras = if extras > 0 { format!(" and {} other item(s)", extras) } else { String::new() };
+    println!(
+        "Imported {} features{} from {} ({} skipped): {} features total",
+        added.len(),
+        extras,
+        path,
+        skipped.len(),
+        state.features.len()
+    );
+
+    Ok(())
+}
+
+/// Turn imported cards/rows into features with slug ids
+fn items_to_features(items: Vec<ImportedItem>, default_domain: &str) -> Vec<Feature> {
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut features = Vec::new();
+
+    for item in items {
+        // Two cards with the same title in one export become name, name-2, ...
+        let base = slugify(&item.name);
+        let mut id = base.clone();
+        let mut n = 2;
+        while taken.contains(&id) {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        taken.insert(id.clone());
+
+        let mut feature = Feature::new(
+            id,
+            item.name,
+            item.domain.map(|d| slugify(&d)).unwrap_or_else(|| default_domain.to_string()),
+            item.description,
+        );
+        feature.status = item.status;
+        feature.blocked_reason = item.blocked_reason;
+        feature.tags = item.tags;
+        feature.context = item.link.map(|link| format!("Imported from {}", link));
+        features.push(feature);
+    }
+
+    features
+}
+
+/// Add features whose id is new; returns (added ids, skipped ids)
+fn merge_features(state: &mut LegendState, features: Vec<Feature>) -> (Vec<String>, Vec<String>) {
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+
+    let progress = Progress::start("import", Some(features.len()));
+    for feature in features {
+        progress.tick(&feature.id);
+        if state.find_feature(&feature.id).is_some() {
+            skipped.push(feature.id);
+        } else {
+            added.push(feature.id.clone());
+            state.features.push(feature);
+        }
+    }
+    progress.finish();
+
+    (added, skipped)
+}
+
+/// Add the rest of a Legend export, add-only like features
+///
+/// Milestones and goals come in when their id is new; decisions and
+/// sessions when they aren't already recorded (same title and time, same
+/// start), numbered after ours; conventions when the text is new. Project
+/// info fills only fields left unset, and a project with no features yet
+/// takes the export's name. Returns how many items were added, and the
+/// export's decision ids mapped to ours
+fn merge_rest(state: &mut LegendState, export: Export) -> (usize, HashMap<u32, u32>) {
+    let mut added = 0;
+    let mut renumbered = HashMap::new();
+
+    if state.features.is_empty() && !export.project_name.is_empty() {
+        state.project_name = export.project_name;
+        state.created_at = export.created_at.unwrap_or(state.created_at);
+    }
+
+    for milestone in export.milestones {
+        if !state.milestones.iter().any(|m| m.id == milestone.id) {
+            state.milestones.push(milestone);
+            added += 1;
+        }
+    }
+    for goal in export.goals {
+        if !state.goals.iter().any(|g| g.id == goal.id) {
+            state.goals.push(goal);
+            added += 1;
+        }
+    }
+    for mut decision in export.decisions {
+        let existing = state
+            .decisions
+            .iter()
+            .find(|d| d.title == decision.title && d.decided_at == decision.decided_at);
+        let id = match existing {
+            Some(d) => d.id,
+            None => state.decisions.iter().map(|d| d.id).max().unwrap_or(0) + 1,
+        };
+        renumbered.insert(decision.id, id);
+        if existing.is_none() {
+            decision.id = id;
+            state.decisions.push(decision);
+            added += 1;
+        }
+    }
+    for mut session in export.sessions {
+        if !state.sessions.iter().any(|s| s.started_at == session.started_at) {
+            session.id = state.sessions.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+            state.sessions.push(session);
+            added += 1;
+        }
+    }
+    for convention in export.conventions {
+        if !state.conventions.contains(&convention) {
+            state.conventions.push(convention);
+            added += 1;
+        }
+    }
+
+    let project = &mut state.project;
+    project.description = project.description.take().or(export.project.description);
+    project.repo_url = project.repo_url.take().or(export.project.repo_url);
+    project.conventions = project.conventions.take().or(export.project.conventions);
+    if project.tech_stack.is_empty() {
+        project.tech_stack = export.project.tech_stack;
+    }
+
+    (added, renumbered)
+}
+
+/// "OAuth Login (v2)" -> "oauth-login-v2"
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Decision, FeatureStatus, Milestone};
+
+    fn item(name: &str) -> ImportedItem {
+        ImportedItem {
+            name: name.to_string(),
+            description: String::new(),
+            status: FeatureStatus::InProgress,
+            blocked_reason: None,
+            tags: vec!["api".to_string()],
+            domain: None,
+            link: None,
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("OAuth Login (v2)"), "oauth-login-v2");
+        assert_eq!(slugify("  --Rate   limiting--"), "rate-limiting");
+        assert_eq!(slugify("✨"), "untitled");
+    }
+
+    #[test]
+    fn test_items_to_features() {
+        let features = items_to_features(vec![item("Billing"), item("Billing")], "imported");
+
+        let ids: Vec<&str> = features.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["billing", "billing-2"]);
+        assert_eq!(features[0].domain, "imported");
+        assert_eq!(features[0].status, FeatureStatus::InProgress);
+        assert_eq!(features[0].tags, vec!["api"]);
+
+        let mut blocked = item("Webhooks");
+        blocked.status = FeatureStatus::Blocked;
+        blocked.blocked_reason = Some("In \"Blocked\" on Trello".to_string());
+        let features = items_to_features(vec![blocked], "imported");
+        assert_eq!(features[0].blocked_reason.as_deref(), Some("In \"Blocked\" on Trello"));
+    }
+
+    #[test]
+    fn test_merge_features_never_overwrites() {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features.push(Feature::new(
+            "search".to_string(),
+            "Search".to_string(),
+            "core".to_string(),
+            "Existing".to_string(),
+        ));
+
+        let incoming = items_to_features(vec![item("Search"), item("Billing")], "imported");
+        let (added, skipped) = merge_features(&mut state, incoming);
+
+        assert_eq!(added, vec!["billing"]);
+        assert_eq!(skipped, vec!["search"]);
+        assert_eq!(state.find_feature("search").unwrap().description, "Existing");
+    }
+
+    #[test]
+    fn test_merge_rest_adds_what_is_new() {
+        let decision = |id: u32, title: &str| Decision {
+            id,
+            title: title.to_string(),
+            rationale: String::new(),
+            feature_ids: Vec::new(),
+            decided_at: 100,
+        };
+        let mut state = LegendState::new("Ours".to_string());
+        state.features.push(Feature::new("a".to_string(), "A".to_string(), "core".to_string(), String::new()));
+        state.decisions = vec![decision(1, "Ours"), decision(2, "Shared")];
+        state.project.repo_url = Some("ours".to_string());
+        state.conventions = vec!["Keep it small".to_string()];
+
+        let export = Export {
+            project_name: "Theirs".to_string(),
+            decisions: vec![decision(1, "Shared"), decision(2, "Theirs")],
+            milestones: vec![Milestone { id: "v1".to_string(), name: "V1".to_string(), due: None, feature_ids: Vec::new(), created_at: 0 }],
+            conventions: vec!["Keep it small".to_string(), "Test first".to_string()],
+            project: crate::types::ProjectInfo {
+                description: Some("theirs".to_string()),
+                repo_url: Some("theirs".to_string()),
+                ..Default::default()
+            },
+            ..Export::default()
+        };
+        let (added, renumbered) = merge_rest(&mut state, export);
+
+        assert_eq!(added, 3);
+        assert_eq!(state.project_name, "Ours");
+        let titles: Vec<(u32, &str)> = state.decisions.iter().map(|d| (d.id, d.title.as_str())).collect();
+        assert_eq!(titles, vec![(1, "Ours"), (2, "Shared"), (3, "Theirs")]);
+        assert_eq!(renumbered, HashMap::from([(1, 2), (2, 3)]));
+        assert_eq!(state.conventions, vec!["Keep it small", "Test first"]);
+        assert_eq!(state.project.repo_url.as_deref(), Some("ours"));
+        assert_eq!(state.project.description.as_deref(), Some("theirs"));
+    }
+}
@@ -5,7 +5,14 @@
 // Layer 4: Add serialization (bincode + LZ4) ✓
 // Layer 11: Claude Code hooks setup ✓
 
-use crate::storage;
+use crate::commands::hooks::{self, Style};
+use crate::config;
+use crate::encryption;
+use crate::exit::{self, ExitError};
+use crate::guard;
+use crate::output;
+use crate::registry;
+use crate::storage::{self, Format};
 use crate::types::LegendState;
 use serde_json::{json, Value};
 use std::fs;
@@ -15,22 +22,57 @@ use std::path::Path;
 ///
 /// Creates `.legend/` directory and sets up initial state structure.
 /// Safe to run multiple times - won't error if directory already exists.
-pub fn handle_init() -> Result<(), Box<dyn std::error::Error>> {
-    let legend_dir = Path::new(".legend");
+///
+/// Refuses the home directory, the filesystem root, and huge trees outside
+/// version control unless `--yes-i-mean-it` is given (see guard.rs).
+///
+/// `--format json` stores the state as JSON from the start (see storage.rs).
+/// `--encrypt` encrypts it with age (see encryption.rs); in a project that
+/// is already set up, it turns encryption on and re-saves the state.
+///
+/// Either way the project is added to ~/.legend/projects.json (see
+/// registry.rs), so projects set up by older versions get listed too.
+pub fn handle_init(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let legend_dir = storage::legend_dir();
+    let format = match args.iter().position(|a| a == "--format") {
+        Some(i) => {
+            let name = args.get(i + 1).map(String::as_str).unwrap_or_default();
+            Some(Format::parse(name).map_err(|e| ExitError::new(exit::USAGE, e.to_string()))?)
+        }
+        None => None,
+    };
+    let encrypt = args.iter().any(|a| a == "--encrypt");
 
     // Check if already initialized
     if storage::is_initialized() {
         println!("Legend already initialized in this directory");
-        println!("  .legend/ directory exists");
-        println!("  Use 'legend show' to view current state");
+        println!("  {}/ directory exists", legend_dir.display());
+        if encrypt {
+            let _lock = storage::lock_state()?;
+            let state = storage::load_state()?;
+            enable_encryption()?;
+            storage::save_state(&state)?;
+            println!("  Re-saved the state encrypted; history/ copies and events.log lines from before stay as they were");
+        } else {
+            println!("  Use 'legend show' to view current state");
+        }
+        register(&legend_dir);
         return Ok(());
     }
 
+    guard::check("init", &std::env::current_dir()?, args)?;
+
     // Create .legend directory
     // R* principle: Add context to errors - tell user what failed
-    fs::create_dir_all(legend_dir).map_err(|e| {
+    fs::create_dir_all(&legend_dir).map_err(|e| {
         format!("Failed to create .legend directory: {}", e)
     })?;
+    if let Some(format) = format {
+        config::set_project_value("storage.format", format.name())?;
+    }
+    if encrypt {
+        enable_encryption()?;
+    }
 
     // Create initial state
     // For now, we'll use a default project name
@@ -38,13 +80,14 @@ pub fn handle_init() -> Result<(), Box<dyn std::error::Error>> {
     let project_name = "My Project".to_string();
     let state = LegendState::new(project_name);
 
-    // Save the initial state to disk (bincode + LZ4)
+    // Save the initial state to disk (bincode + LZ4, or JSON if configured)
     // This serializes and compresses the state
     storage::save_state(&state)?;
 
     println!("✓ Initialized Legend");
-    println!("  Created .legend/ directory");
-    println!("  Saved initial state to .legend/state.lz4");
+    println!("  Created {}/ directory", legend_dir.display());
+    println!("  Saved initial state to {}", storage::state_file().display());
+    register(&legend_dir);
 
     // Set up Claude Code hooks in this project
     setup_claude_hooks()?;
@@ -52,6 +95,27 @@ pub fn handle_init() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Set up age encryption and say where the key is
+fn enable_encryption() -> Result<(), Box<dyn std::error::Error>> {
+    let (recipient, identity) = encryption::enable()?;
+    println!("  Encrypting the state to {}", recipient);
+    println!("  Key: {} (back it up: without it the state can't be read)", identity.display());
+    Ok(())
+}
+
+/// Add the project to the registry; a failure there only warns, since the
+/// project itself is set up
+fn register(legend_dir: &Path) {
+    // Unit tests and --sandbox runs leave the real registry alone
+    if cfg!(test) || crate::sandbox::is_active() {
+        return;
+    }
+    match registry::register(legend_dir) {
+        Ok(name) => println!("  Registered as '{}' (legend -p {} <command> works from anywhere)", name, name),
+        Err(e) => output::warning(&format!("Not added to the project registry: {}", e)),
+    }
+}
+
 /// Set up Claude Code hooks in .claude/settings.json
 ///
 /// Creates or merges Legend hooks into the project's Claude Code configuration.
@@ -60,22 +124,9 @@ fn setup_claude_hooks() -> Result<(), Box<dyn std::error::Error>> {
     let claude_dir = Path::new(".claude");
     let settings_path = claude_dir.join("settings.json");
 
-    // The Legend hooks configuration
-    let legend_session_hook = json!({
-        "matcher": "",
-        "hooks": [{
-            "type": "command",
-            "command": "echo '== Legend Context =='; legend get_state 2>/dev/null || echo 'Legend state not found'"
-        }]
-    });
-
-    let legend_prompt_hook = json!({
-        "matcher": "",
-        "hooks": [{
-            "type": "command",
-            "command": "echo '{\"additionalContext\": \"Legend available. Use legend search <keyword>, legend get_state, or pipe JSON to legend update.\"}'"
-        }]
-    });
+    // The Legend hooks configuration (`legend hooks print` shows the other styles)
+    let legend_session_hook = hooks::hook_entry("SessionStart", Style::Full).expect("SessionStart template");
+    let legend_prompt_hook = hooks::hook_entry("UserPromptSubmit", Style::Full).expect("UserPromptSubmit template");
 
     // Check if settings.json exists
     if settings_path.exists() {
@@ -91,6 +142,10 @@ fn setup_claude_hooks() -> Result<(), Box<dyn std::error::Error>> {
         // Check if Legend hooks already exist
         if has_legend_hooks(&settings) {
             println!("  Claude Code hooks already configured");
+            let legacy = hooks::upgrade_settings(&mut settings);
+            if legacy > 0 {
+                println!("  {} of them use an old hook form; run 'legend hooks upgrade' to update", legacy);
+            }
             return Ok(());
         }
 
@@ -131,7 +186,7 @@ fn setup_claude_hooks() -> Result<(), Box<dyn std::error::Error>> {
 
 /// Check if Legend hooks are already configured
 fn has_legend_hooks(settings: &Value) -> bool {
-    // Check SessionStart hooks for legend get_state
+    // Check SessionStart hooks for a Legend command (any hook style)
     if let Some(session_hooks) = settings
         .get("hooks")
         .and_then(|h| h.get("SessionStart"))
@@ -141,7 +196,7 @@ fn has_legend_hooks(settings: &Value) -> bool {
             if let Some(hooks) = hook_entry.get("hooks").and_then(|h| h.as_array()) {
                 for hook in hooks {
                     if let Some(cmd) = hook.get("command").and_then(|c| c.as_str()) {
-                        if cmd.contains("legend get_state") {
+                        if hooks::is_legend_command(cmd) {
                             return true;
                         }
                     }
@@ -0,0 +1,148 @@
+// Lint command - quality warnings for the context Legend hands to Claude
+//
+// A state can be valid (see validate.rs) but still be poor context:
+// one-word descriptions, features with no files, work marked InProgress
+// that nobody has touched in a month. Lint points those out.
+//
+// Usage:
+//   legend lint                  - report warnings, always exit 0
+//   legend lint --check          - exit 3 if any warning is found (for CI)
+//   legend lint --format github  - GitHub Actions annotations
+//
+// A feature's warnings carry its first files_involved entry, so on a PR
+// the annotation shows up on that file.
+
+use crate::findings::{self, Finding};
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, FeatureStatus};
+use serde_json::json;
+
+/// Descriptions shorter than this rarely tell Claude enough
+const MIN_DESCRIPTION_LEN: usize = 20;
+
+/// InProgress features untouched for this long are probably stale
+const STALE_AFTER_DAYS: i64 = 30;
+
+/// Handle the lint command
+pub fn handle_lint(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let options = findings::parse_check_args(args)?;
+    let state = storage::load_state()?;
+
+    let findings = lint_features(&state.features, current_timestamp());
+
+    findings::print_findings(&findings, options.format, "lint");
+    output::diag(
+        output::Level::Info,
+        &format!("Linted {} feature(s): {} warning(s)", state.features.len(), findings.len()),
+        json!({"features": state.features.len(), "warnings": findings.len()}),
+    );
+
+    findings::check_result(&findings, options.check, "lint")
+}
+
+/// Run every quality check against the features
+fn lint_features(features: &[Feature], now: i64) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    // Planned stubs are thin on purpose until promoted
+    for feature in features.iter().filter(|f| !f.planned) {
+        let id = Some(feature.id.as_str());
+        let start = findings.len();
+
+        if feature.description.trim().chars().count() < MIN_DESCRIPTION_LEN {
+            findings.push(Finding::warning(
+                id,
+                format!("description is shorter than {} characters", MIN_DESCRIPTION_LEN),
+            ));
+        } else if feature.description.trim() == feature.name.trim() {
+            findings.push(Finding::warning(id, "description just repeats the name".to_string()));
+        }
+
+        if feature.files_involved.is_empty() {
+            findings.push(Finding::warning(id, "no files_involved tracked".to_string()));
+        }
+
+        if feature.tags.is_empty() {
+            findings.push(Finding::warning(id, "no tags".to_string()));
+        }
+
+        let idle_days = (now - feature.last_updated) / (24 * 60 * 60);
+        if feature.status == FeatureStatus::InProgress && idle_days >= STALE_AFTER_DAYS {
+            findings.push(Finding::warning(
+                id,
+                format!("InProgress but not updated in {} days", idle_days),
+            ));
+        }
+
+        if feature.status == FeatureStatus::Blocked && feature.blocked_reason.is_none() && feature.context.is_none() {
+            findings.push(Finding::warning(
+                id,
+                "Blocked without a blocked_reason or context explaining why".to_string(),
+            ));
+        }
+
+        if let Some(file) = feature.files_involved.first() {
+            for finding in &mut findings[start..] {
+                finding.file = Some(file.clone());
+            }
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn good_feature(id: &str) -> Feature {
+        let mut f = Feature::new(
+            id.to_string(),
+            "Authentication".to_string(),
+            "security".to_string(),
+            "Login and logout with JWT session tokens".to_string(),
+        );
+        f.tags = vec!["backend".to_string()];
+        f.files_involved = vec!["src/auth.rs".to_string()];
+        f
+    }
+
+    #[test]
+    fn test_good_feature_is_clean() {
+        let f = good_feature("auth");
+        assert!(lint_features(&[f], current_timestamp()).is_empty());
+    }
+
+    #[test]
+    fn test_thin_feature_warnings() {
+        let mut f = good_feature("auth");
+        f.description = "Auth".to_string();
+        f.tags.clear();
+        f.files_involved.clear();
+
+        let findings = lint_features(&[f], current_timestamp());
+
+        assert_eq!(findings.len(), 3);
+    }
+
+    #[test]
+    fn test_planned_stub_is_skipped() {
+        let mut f = Feature::new("later".to_string(), "Later".to_string(), String::new(), String::new());
+        f.planned = true;
+        assert!(lint_features(&[f], current_timestamp()).is_empty());
+    }
+
+    #[test]
+    fn test_stale_in_progress() {
+        let mut f = good_feature("auth");
+        f.status = FeatureStatus::InProgress;
+        let now = f.last_updated + 45 * 24 * 60 * 60;
+
+        let findings = lint_features(&[f], now);
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("45 days"));
+        assert_eq!(findings[0].file.as_deref(), Some("src/auth.rs"));
+    }
+}
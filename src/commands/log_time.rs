@@ -0,0 +1,78 @@
+// Log-time command - record hours spent on a feature
+//
+// Usage:
+//   legend log-time <feature-id> <hours>    - add hours (2, 2.5, 0.25)
+//
+// Hours add up in `logged_hours`; set the expected total with an update
+// (`{"id": "auth", "estimate_hours": 8}`). `legend stats --time` totals
+// both per domain.
+
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend log-time <feature-id> <hours>";
+
+/// Handle the log-time command
+pub fn handle_log_time(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (id, hours) = match args {
+        [id, hours] => (id, parse_hours(hours)?),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &mut state.features[index];
+    log_hours(feature, hours, current_timestamp());
+    let (feature_id, logged, estimate) = (feature.id.clone(), feature.logged_hours, feature.estimate_hours);
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let of_estimate = estimate.map(|e| format!(" of {}h estimated", e)).unwrap_or_default();
+    output::diag(
+        output::Level::Info,
+        &format!("{}: {}h logged{}.", feature_id, logged, of_estimate),
+        json!({"id": feature_id, "logged_hours": logged, "estimate_hours": estimate}),
+    );
+    Ok(())
+}
+
+/// A positive number of hours
+fn parse_hours(value: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    match value.parse::<f64>() {
+        Ok(hours) if hours.is_finite() && hours > 0.0 => Ok(hours),
+        _ => Err(ExitError::new(exit::USAGE, format!("'{}' is not a positive number of hours\n{}", value, USAGE)).into()),
+    }
+}
+
+fn log_hours(feature: &mut Feature, hours: f64, now: i64) {
+    feature.logged_hours += hours;
+    feature.last_updated = now;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_time_adds_up() {
+        let _env = crate::testing::TestEnv::initialized(1_000);
+        let mut state = storage::load_state().unwrap();
+        state.features.push(Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "d".to_string()));
+        storage::save_state(&state).unwrap();
+
+        handle_log_time(&["auth".to_string(), "2.5".to_string()]).unwrap();
+        handle_log_time(&["auth".to_string(), "1".to_string()]).unwrap();
+        assert!(handle_log_time(&["auth".to_string(), "-1".to_string()]).is_err());
+        assert!(handle_log_time(&["auth".to_string(), "soon".to_string()]).is_err());
+
+        assert_eq!(storage::load_state().unwrap().features[0].logged_hours, 3.5);
+    }
+}
@@ -0,0 +1,497 @@
+// Merge command - three-way merge of a diverged copy of the state
+//
+// Usage:
+//   legend merge <theirs>                    - merge another state into ours
+//   legend merge <theirs> --base <ancestor>  - ...using an explicit ancestor
+//   legend merge <theirs> --conflicts-file   - write conflicts instead of prompting
+//   legend merge --continue                  - apply a resolved conflicts file
+//
+// <theirs> and --base each accept:
+//   path/to/state.lz4     - a copied .legend/state.lz4
+//   path/to/export.json   - `legend export` (or get_state) output
+//   git:<ref>             - .legend/state.lz4 as committed at <ref>
+//
+// For git:<ref> the ancestor defaults to the state at `git merge-base HEAD
+// <ref>`. Without any ancestor the merge is two-way: features on only one
+// side are kept, and every differing field is a conflict.
+//
+// Features are paired by ID and merged field by field (through
+// serde_json::Value, like diff.rs). A field changed on one side only takes
+// that side's value; a field changed differently on both sides is a
+// conflict. Timestamps are never conflicts - the newest last_updated wins.
+// A feature deleted on one side and edited on the other is kept.
+//
+// Conflicts are prompted for on a terminal. Otherwise (or with
+// --conflicts-file) they go to .legend/merge-conflicts.json, each with a
+// "take": "ours" the user can flip to "theirs", and the command exits 3;
+// `legend merge --continue` then applies it. Nothing is saved while
+// conflicts are pending.
+//
+// The conflicts file is plaintext even with encryption on, so it can be
+// edited; it holds the merged state, so merge warns when it writes one
+// in an encrypted project.
+
+use crate::commands::export::Export;
+use crate::encryption;
+use crate::exit::{self, ExitError};
+use crate::git;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{Feature, LegendState};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: legend merge <theirs> [--base <ancestor>] [--conflicts-file]\n       legend merge --continue";
+
+const CONFLICTS_FILE_NAME: &str = "merge-conflicts.json";
+
+/// Path of the committed state, for git:<ref> sources
+const STATE_PATH_IN_REPO: &str = ".legend/state.lz4";
+
+/// Timestamp fields: merged by rule, never reported as conflicts
+const VOLATILE_FIELDS: &[&str] = &["last_updated", "recency_score", "created_at"];
+
+/// Which side a conflict resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Take {
+    Ours,
+    Theirs,
+}
+
+/// One field changed differently on both sides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub id: String,
+    pub field: String,
+    pub base: Value,
+    pub ours: Value,
+    pub theirs: Value,
+    pub take: Take,
+}
+
+/// Result of merging two states
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// Merged state, with every conflict resolved to ours
+    pub merged: LegendState,
+    pub conflicts: Vec<Conflict>,
+    /// Features taken from theirs (added there, or only edited there)
+    pub from_theirs: usize,
+    /// Human-readable notes (deleted-vs-edited features kept, etc.)
+    pub notes: Vec<String>,
+}
+
+/// Contents of .legend/merge-conflicts.json
+#[derive(Debug, Serialize, Deserialize)]
+struct ConflictsFile {
+    /// Our state's last_updated when the merge started, to detect edits since
+    ours_last_updated: i64,
+    conflicts: Vec<Conflict>,
+    merged: LegendState,
+}
+
+/// Handle the merge command
+pub fn handle_merge(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut theirs_spec = None;
+    let mut base_spec = None;
+    let mut conflicts_file = false;
+    let mut resume = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--base" => {
+                i += 1;
+                base_spec = Some(args.get(i).ok_or_else(|| format!("--base expects a state\n{}", USAGE))?.clone());
+            }
+            "--conflicts-file" => conflicts_file = true,
+            "--continue" => resume = true,
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => theirs_spec = Some(other.to_string()),
+        }
+        i += 1;
+    }
+
+    if resume {
+        return continue_merge();
+    }
+    let theirs_spec = theirs_spec.ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+
+    let ours = storage::load_state()?;
+    let theirs = load_source(&theirs_spec)?;
+    let base = match base_spec {
+        Some(spec) => Some(load_source(&spec)?),
+        None => default_base(&theirs_spec),
+    };
+    if base.is_none() {
+        output::warning("No common ancestor given (--base); merging two-way, so every differing field is a conflict.");
+    }
+
+    let mut result = merge_states(base.as_ref(), &ours, &theirs);
+    for note in &result.notes {
+        output::warning(note);
+    }
+
+    let interactive = !conflicts_file && std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    if !result.conflicts.is_empty() {
+        if !interactive {
+            let path = conflicts_path();
+            let file = ConflictsFile {
+                ours_last_updated: ours.last_updated,
+                conflicts: result.conflicts,
+                merged: result.merged,
+            };
+            fs::write(&path, serde_json::to_string_pretty(&file)?)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            if encryption::is_enabled()? {
+                output::warning(&format!(
+                    "{} is not encrypted; it is removed by 'legend merge --continue'.",
+                    path.display()
+                ));
+            }
+            return Err(ExitError::new(
+                exit::CHECK_FAILED,
+                format!(
+                    "{} conflict(s) written to {}. Set each \"take\" to \"ours\" or \"theirs\", then run 'legend merge --continue'.",
+                    file.conflicts.len(),
+                    path.display()
+                ),
+            )
+            .into());
+        }
+        prompt_conflicts(&mut result.conflicts)?;
+        apply_resolutions(&mut result.merged, &result.conflicts)?;
+    }
+
+    result.merged.touch();
+    let _lock = storage::lock_unchanged(&ours)?;
+    journal::commit(&ours, &result.merged)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!(
+            "Merged {}: {} feature(s) from theirs, {} conflict(s) resolved.",
+            theirs_spec,
+            result.from_theirs,
+            result.conflicts.len()
+        ),
+        json!({"from_theirs": result.from_theirs, "conflicts": result.conflicts.len()}),
+    );
+    Ok(())
+}
+
+/// `legend merge --continue`: apply the resolved conflicts file
+fn continue_merge() -> Result<(), Box<dyn std::error::Error>> {
+    let path = conflicts_path();
+    let content = fs::read_to_string(&path)
+        .map_err(|_| format!("No merge in progress ({} not found)", path.display()))?;
+    let mut file: ConflictsFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let _lock = storage::lock_state()?;
+    let ours = storage::load_state()?;
+    if ours.last_updated != file.ours_last_updated {
+        return Err(format!(
+            "State changed since the merge started. Delete {} and run 'legend merge' again.",
+            path.display()
+        )
+        .into());
+    }
+
+    apply_resolutions(&mut file.merged, &file.conflicts)?;
+    file.merged.touch();
+    journal::commit(&ours, &file.merged)?;
+    fs::remove_file(&path).map_err(|e| format!("Merged, but failed to remove {}: {}", path.display(), e))?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Merge complete: {} conflict(s) resolved.", file.conflicts.len()),
+        json!({"conflicts": file.conflicts.len()}),
+    );
+    Ok(())
+}
+
+fn conflicts_path() -> PathBuf {
+    storage::legend_path(CONFLICTS_FILE_NAME)
+}
+
+/// Load a state from a state.lz4 file, an export JSON file, or git:<ref>
+fn load_source(spec: &str) -> Result<LegendState, Box<dyn std::error::Error>> {
+    if let Some(rev) = spec.strip_prefix("git:") {
+        let bytes = git::show_file(rev, STATE_PATH_IN_REPO)?;
+        return storage::decode_state(&bytes).map_err(|e| format!("{} at {}: {}", STATE_PATH_IN_REPO, rev, e).into());
+    }
+
+    let bytes = fs::read(spec).map_err(|e| format!("Failed to read {}: {}", spec, e))?;
+    if let Ok(state) = storage::decode_state(&bytes) {
+        return Ok(state);
+    }
+
+    let export: Export = serde_json::from_slice(&bytes)
+        .map_err(|_| format!("{} is neither a state.lz4 file nor a Legend JSON export", spec))?;
+    let mut state = LegendState::new(export.project_name);
+    state.features = export.features;
+    Ok(state)
+}
+
+/// The ancestor for git:<ref>: our state at the merge base with <ref>
+fn default_base(theirs_spec: &str) -> Option<LegendState> {
+    let rev = theirs_spec.strip_prefix("git:")?;
+    let merge_base = git::run_git(&["merge-base", "HEAD", rev]).ok()?;
+    let bytes = git::show_file(merge_base.trim(), STATE_PATH_IN_REPO).ok()?;
+    storage::decode_state(&bytes).ok()
+}
+
+/// Three-way merge of two states against an optional common ancestor
+///
+/// Output order follows ours, with features only in theirs appended
+pub fn merge_states(base: Option<&LegendState>, ours: &LegendState, theirs: &LegendState) -> MergeResult {
+    let by_id = |state: &LegendState| -> HashMap<String, Feature> {
+        state.features.iter().map(|f| (f.id.clone(), f.clone())).collect()
+    };
+    let base_by_id = base.map(by_id).unwrap_or_default();
+    let theirs_by_id = by_id(theirs);
+    let ours_by_id = by_id(ours);
+
+    let mut merged = ours.clone();
+    merged.features = Vec::new();
+    merged.created_at = ours.created_at.min(theirs.created_at);
+
+    let mut result = MergeResult {
+        merged,
+        conflicts: Vec::new(),
+        from_theirs: 0,
+        notes: Vec::new(),
+    };
+
+    for our in &ours.features {
+        let ancestor = base_by_id.get(&our.id);
+        match (theirs_by_id.get(&our.id), ancestor) {
+            (Some(their), _) => {
+                let (feature, conflicts) = merge_features(ancestor, our, their);
+                if !unchanged(our, &feature) {
+                    result.from_theirs += 1;
+                }
+                result.conflicts.extend(conflicts);
+                result.merged.features.push(feature);
+            }
+            // Removed in theirs
+            (None, Some(old)) => {
+                if unchanged(old, our) {
+                    continue;
+                }
+                result.notes.push(format!("Kept {}: removed in theirs, but edited here", our.id));
+                result.merged.features.push(our.clone());
+            }
+            // Added here
+            (None, None) => result.merged.features.push(our.clone()),
+        }
+    }
+
+    for their in &theirs.features {
+        if ours_by_id.contains_key(&their.id) {
+            continue;
+        }
+        // Removed here (if there's no ancestor version, it was added in theirs)
+        if let Some(old) = base_by_id.get(&their.id) {
+            if unchanged(old, their) {
+                continue;
+            }
+            result.notes.push(format!("Kept {}: removed here, but edited in theirs", their.id));
+        }
+        result.from_theirs += 1;
+        result.merged.features.push(their.clone());
+    }
+
+    result
+}
+
+/// Did a feature change at all (ignoring timestamps) since the ancestor?
+fn unchanged(base: &Feature, feature: &Feature) -> bool {
+    crate::diff::diff_features(base, feature).is_empty()
+}
+
+/// Merge one feature field by field; conflicting fields keep ours
+fn merge_features(base: Option<&Feature>, ours: &Feature, theirs: &Feature) -> (Feature, Vec<Conflict>) {
+    let to_map = |feature: &Feature| match serde_json::to_value(feature) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    let base_map = base.map(to_map);
+    let ours_map = to_map(ours);
+    let theirs_map = to_map(theirs);
+
+    let mut merged = ours_map.clone();
+    let mut conflicts = Vec::new();
+
+    // The newer side's timestamps (and so recency) win
+    if theirs.last_updated > ours.last_updated {
+        for field in ["last_updated", "recency_score"] {
+            if let Some(value) = theirs_map.get(field) {
+                merged.insert(field.to_string(), value.clone());
+            }
+        }
+    }
+
+    for (field, our) in &ours_map {
+        if VOLATILE_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        let their = theirs_map.get(field).cloned().unwrap_or(Value::Null);
+        if our == &their {
+            continue;
+        }
+
+        let old = base_map.as_ref().and_then(|map| map.get(field));
+        match old {
+            Some(old) if old == our => {
+                merged.insert(field.clone(), their);
+            }
+            Some(old) if old == &their => {}
+            _ => conflicts.push(Conflict {
+                id: ours.id.clone(),
+                field: field.clone(),
+                base: old.cloned().unwrap_or(Value::Null),
+                ours: our.clone(),
+                theirs: their,
+                take: Take::Ours,
+            }),
+        }
+    }
+
+    let feature = serde_json::from_value(Value::Object(merged)).unwrap_or_else(|_| ours.clone());
+    (feature, conflicts)
+}
+
+/// Set every conflict resolved to theirs in the merged state
+fn apply_resolutions(merged: &mut LegendState, conflicts: &[Conflict]) -> Result<(), Box<dyn std::error::Error>> {
+    for conflict in conflicts.iter().filter(|c| c.take == Take::Theirs) {
+        let feature = merged
+            .find_feature_mut(&conflict.id)
+            .ok_or_else(|| format!("Conflict for unknown feature '{}'", conflict.id))?;
+
+        let mut value = serde_json::to_value(&*feature)?;
+        if let Some(map) = value.as_object_mut() {
+            map.insert(conflict.field.clone(), conflict.theirs.clone());
+        }
+        *feature = serde_json::from_value(value)
+            .map_err(|e| format!("Invalid value for {}.{}: {}", conflict.id, conflict.field, e))?;
+    }
+    Ok(())
+}
+
+/// Ask for each conflict on the terminal
+fn prompt_conflicts(conflicts: &mut [Conflict]) -> Result<(), Box<dyn std::error::Error>> {
+    let stdin = std::io::stdin();
+    let mut stderr = std::io::stderr();
+
+    for conflict in conflicts.iter_mut() {
+        writeln!(stderr, "\nConflict in {}.{}", conflict.id, conflict.field)?;
+        writeln!(stderr, "  base:   {}", conflict.base)?;
+        writeln!(stderr, "  ours:   {}", conflict.ours)?;
+        writeln!(stderr, "  theirs: {}", conflict.theirs)?;
+
+        conflict.take = loop {
+            write!(stderr, "Keep [o]urs or [t]heirs? ")?;
+            stderr.flush()?;
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Err("Merge aborted; nothing was saved".into());
+            }
+            match line.trim().to_lowercase().as_str() {
+                "o" | "ours" => break Take::Ours,
+                "t" | "theirs" => break Take::Theirs,
+                _ => continue,
+            }
+        };
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeatureStatus;
+
+    fn state(features: Vec<Feature>) -> LegendState {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features = features;
+        state
+    }
+
+    fn feature(id: &str) -> Feature {
+        Feature::new(id.to_string(), format!("Feature {}", id), "api".to_string(), "d".to_string())
+    }
+
+    #[test]
+    fn test_merge_non_conflicting_edits() {
+        let base = state(vec![feature("auth"), feature("gone")]);
+
+        let mut ours = base.clone();
+        ours.features[0].status = FeatureStatus::Complete;
+
+        let mut theirs = base.clone();
+        theirs.features[0].description = "Login with OAuth".to_string();
+        theirs.features.retain(|f| f.id != "gone");
+        theirs.features.push(feature("billing"));
+
+        let result = merge_states(Some(&base), &ours, &theirs);
+        assert!(result.conflicts.is_empty());
+
+        let ids: Vec<_> = result.merged.features.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["auth", "billing"]);
+        assert_eq!(result.merged.features[0].status, FeatureStatus::Complete);
+        assert_eq!(result.merged.features[0].description, "Login with OAuth");
+    }
+
+    #[test]
+    fn test_merge_conflict_and_resolution() {
+        let base = state(vec![feature("auth")]);
+        let mut ours = base.clone();
+        ours.features[0].status = FeatureStatus::Complete;
+        let mut theirs = base.clone();
+        theirs.features[0].status = FeatureStatus::Blocked;
+
+        let mut result = merge_states(Some(&base), &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].field, "status");
+        assert_eq!(result.conflicts[0].base, Value::from("Pending"));
+        assert_eq!(result.merged.features[0].status, FeatureStatus::Complete);
+
+        result.conflicts[0].take = Take::Theirs;
+        apply_resolutions(&mut result.merged, &result.conflicts).unwrap();
+        assert_eq!(result.merged.features[0].status, FeatureStatus::Blocked);
+    }
+
+    #[test]
+    fn test_merge_keeps_deleted_but_edited() {
+        let base = state(vec![feature("auth")]);
+        let ours = state(Vec::new());
+        let mut theirs = base.clone();
+        theirs.features[0].status = FeatureStatus::InProgress;
+
+        let result = merge_states(Some(&base), &ours, &theirs);
+        assert_eq!(result.merged.features.len(), 1);
+        assert_eq!(result.notes.len(), 1);
+    }
+
+    #[test]
+    fn test_two_way_merge() {
+        let mut ours = state(vec![feature("auth")]);
+        ours.features[0].status = FeatureStatus::Complete;
+        let theirs = state(vec![feature("auth"), feature("billing")]);
+
+        let result = merge_states(None, &ours, &theirs);
+        assert_eq!(result.merged.features.len(), 2);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.from_theirs, 1);
+    }
+}
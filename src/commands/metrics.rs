@@ -0,0 +1,78 @@
+// Metrics command - manage the opt-in local usage metrics
+//
+// Usage:
+//   legend metrics enable        - start recording (creates .legend/metrics.json)
+//   legend metrics disable       - stop recording and delete the file
+//   legend metrics show          - table of counts and latencies
+//   legend metrics show --json   - raw metrics, e.g. to share in a bug report
+//   legend metrics reset         - clear counters, keep recording
+
+use crate::metrics::{self, Metrics};
+use crate::storage;
+use crate::types::current_timestamp;
+
+/// Handle the metrics command
+pub fn handle_metrics(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: legend metrics <enable|disable|show|reset> [--json]";
+
+    let subcommand = args.first().ok_or(USAGE)?;
+
+    match subcommand.as_str() {
+        "enable" | "reset" => {
+            if !storage::is_initialized() {
+                return Err("Legend not initialized. Run 'legend init' first.".into());
+            }
+            let metrics = Metrics {
+                enabled_at: current_timestamp(),
+                ..Metrics::default()
+            };
+            metrics::save_metrics(&metrics)?;
+            println!("✓ Metrics recording to .legend/metrics.json (local only)");
+        }
+        "disable" => {
+            metrics::remove_metrics()?;
+            println!("✓ Metrics disabled and .legend/metrics.json removed");
+        }
+        "show" => {
+            if !metrics::is_enabled() {
+                println!("Metrics are disabled. Run 'legend metrics enable' to opt in.");
+                return Ok(());
+            }
+            let metrics = metrics::load_metrics()?;
+            if args.iter().any(|a| a == "--json") {
+                println!("{}", serde_json::to_string_pretty(&metrics)?);
+            } else {
+                print_table(&metrics);
+            }
+        }
+        other => {
+            return Err(format!("Unknown metrics subcommand: {}\n{}", other, USAGE).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Print metrics as a table, busiest command first
+fn print_table(metrics: &Metrics) {
+    if metrics.commands.is_empty() {
+        println!("No commands recorded yet.");
+        return;
+    }
+
+    let mut rows: Vec<_> = metrics.commands.iter().collect();
+    rows.sort_by_key(|(_, m)| std::cmp::Reverse(m.count));
+
+    println!("{:<18} {:>8} {:>10} {:>10}", "COMMAND", "RUNS", "AVG (ms)", "MAX (ms)");
+    println!("{}", "-".repeat(49));
+
+    for (command, m) in rows {
+        println!(
+            "{:<18} {:>8} {:>10.2} {:>10.2}",
+            command,
+            m.count,
+            m.average_millis(),
+            m.max_micros as f64 / 1000.0
+        );
+    }
+}
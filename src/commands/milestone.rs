@@ -0,0 +1,256 @@
+// Milestone command - group features into a multi-week effort
+//
+// Usage:
+//   legend milestone create <id> "<name>" [--due YYYY-MM-DD]
+//   legend milestone assign <milestone-id> <feature-id> [<feature-id>...]
+//   legend milestone assign <milestone-id> --remove <feature-id>...
+//   legend milestone show                   - every milestone with progress
+//   legend milestone show <id> [--json]     - one milestone and its features
+//
+// A milestone lists feature IDs; the features themselves don't change.
+// Progress is the share of listed features that are Complete. Milestones
+// are stored in state.lz4 beside the features, so get_state includes them.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::text;
+use crate::types::{current_timestamp, FeatureStatus, LegendState, Milestone};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend milestone create <id> \"<name>\" [--due YYYY-MM-DD]\n       legend milestone assign <milestone-id> [--remove] <feature-id>...\n       legend milestone show [<id>] [--json]";
+
+/// Handle the milestone command
+pub fn handle_milestone(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(String::as_str) {
+        Some("create") => create(&args[1..]),
+        Some("assign") => assign(&args[1..]),
+        Some("show") => show(&args[1..]),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn create(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut positional = Vec::new();
+    let mut due = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--due" => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--due expects a date (YYYY-MM-DD)\n{}", USAGE)))?;
+                due = Some(dates::parse_date(value)?);
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => positional.push(other.to_string()),
+        }
+        i += 1;
+    }
+    let (id, name) = match positional.as_slice() {
+        [id, name] if !id.trim().is_empty() && !name.trim().is_empty() => (id.clone(), name.clone()),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    if state.find_milestone(&id).is_some() {
+        return Err(format!("Milestone '{}' already exists", id).into());
+    }
+    state.milestones.push(Milestone {
+        id: id.clone(),
+        name,
+        due,
+        feature_ids: Vec::new(),
+        created_at: current_timestamp(),
+    });
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Created milestone {}. Add features with 'legend milestone assign {} <feature-id>'.", id, id),
+        json!({"id": id}),
+    );
+    Ok(())
+}
+
+fn assign(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let remove = args.iter().any(|a| a == "--remove");
+    let rest: Vec<&String> = args.iter().filter(|a| *a != "--remove").collect();
+    if let Some(flag) = rest.iter().find(|a| a.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+    let (milestone_id, feature_args) = match rest.split_first() {
+        Some((id, features)) if !features.is_empty() => (id.as_str(), features),
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let mut feature_ids = Vec::new();
+    for id in feature_args {
+        let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+        feature_ids.push(state.features[index].id.clone());
+    }
+
+    let milestone = state
+        .milestones
+        .iter_mut()
+        .find(|m| m.id == milestone_id)
+        .ok_or_else(|| format!("Milestone '{}' not found. Create it with 'legend milestone create'.", milestone_id))?;
+    if remove {
+        milestone.feature_ids.retain(|id| !feature_ids.contains(id));
+    } else {
+        for id in feature_ids {
+            if !milestone.feature_ids.contains(&id) {
+                milestone.feature_ids.push(id);
+            }
+        }
+    }
+    let count = milestone.feature_ids.len();
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Milestone {} now has {} feature(s).", milestone_id, count),
+        json!({"id": milestone_id, "features": count}),
+    );
+    Ok(())
+}
+
+fn show(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let json_out = args.iter().any(|a| a == "--json");
+    let rest: Vec<&String> = args.iter().filter(|a| *a != "--json").collect();
+    if let Some(flag) = rest.iter().find(|a| a.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+
+    let state = storage::load_state()?;
+    let now = current_timestamp();
+    let milestones: Vec<&Milestone> = match rest.as_slice() {
+        [] => state.milestones.iter().collect(),
+        [id] => vec![state.find_milestone(id).ok_or_else(|| format!("Milestone '{}' not found", id))?],
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    if json_out {
+        let report: Vec<_> = milestones
+            .iter()
+            .map(|m| {
+                let (done, total) = progress(&state, m);
+                json!({"milestone": m, "complete": done, "total": total})
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if milestones.is_empty() {
+        output::info("No milestones yet. Create one with 'legend milestone create <id> \"<name>\"'.");
+    } else if rest.is_empty() {
+        for milestone in milestones {
+            println!("{}", summary_line(&state, milestone, now));
+        }
+    } else {
+        print!("{}", render_milestone(&state, milestones[0], now));
+    }
+    Ok(())
+}
+
+/// (Complete, listed) features - IDs no longer in the state don't count
+fn progress(state: &LegendState, milestone: &Milestone) -> (usize, usize) {
+    let features: Vec<_> = milestone.feature_ids.iter().filter_map(|id| state.find_feature(id)).collect();
+    let done = features.iter().filter(|f| f.status == FeatureStatus::Complete).count();
+    (done, features.len())
+}
+
+/// "due 2024-03-01 (in 12 days)", "due 2024-03-01 (5 days overdue)", or ""
+fn due_label(milestone: &Milestone, now: i64) -> String {
+    milestone.due.map(|due| dates::due_label(due, now)).unwrap_or_default()
+}
+
+/// `q3-launch  Q3 launch  3/7 complete  due 2024-09-30 (in 12 days)`
+fn summary_line(state: &LegendState, milestone: &Milestone, now: i64) -> String {
+    let (done, total) = progress(state, milestone);
+    let line = format!("{}  {}  {}/{} complete  {}", milestone.id, milestone.name, done, total, due_label(milestone, now));
+    line.trim_end().to_string()
+}
+
+/// The summary line, then one row per feature
+fn render_milestone(state: &LegendState, milestone: &Milestone, now: i64) -> String {
+    let mut out = format!("{}\n\n", summary_line(state, milestone, now));
+    if milestone.feature_ids.is_empty() {
+        out.push_str("No features assigned.\n");
+        return out;
+    }
+    let width = milestone.feature_ids.iter().map(|id| text::width(id)).max().unwrap_or(0);
+    for id in &milestone.feature_ids {
+        match state.find_feature(id) {
+            Some(f) => out.push_str(&format!("{}  {} {}\n", text::pad(id, width), text::pad(&f.status.to_string(), 12), f.name)),
+            None => out.push_str(&format!("{}  (no longer tracked)\n", text::pad(id, width))),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::SECONDS_PER_DAY;
+    use crate::types::Feature;
+
+    fn state() -> LegendState {
+        let mut state = LegendState::new("Demo".to_string());
+        let mut login = Feature::new("login".to_string(), "Login".to_string(), "auth".to_string(), "d".to_string());
+        login.status = FeatureStatus::Complete;
+        state.features.push(login);
+        state.features.push(Feature::new("sso".to_string(), "SSO".to_string(), "auth".to_string(), "d".to_string()));
+        state.milestones.push(Milestone {
+            id: "launch".to_string(),
+            name: "Launch".to_string(),
+            due: Some(10 * SECONDS_PER_DAY),
+            feature_ids: vec!["login".to_string(), "sso".to_string(), "gone".to_string()],
+            created_at: 0,
+        });
+        state
+    }
+
+    #[test]
+    fn test_progress_and_due() {
+        let state = state();
+        let milestone = &state.milestones[0];
+        assert_eq!(progress(&state, milestone), (1, 2));
+        assert_eq!(due_label(milestone, 7 * SECONDS_PER_DAY + 60), "due 1970-01-11 (in 3 days)");
+        assert_eq!(due_label(milestone, 12 * SECONDS_PER_DAY), "due 1970-01-11 (2 days overdue)");
+        assert_eq!(due_label(milestone, 10 * SECONDS_PER_DAY + 3600), "due 1970-01-11 (today)");
+    }
+
+    #[test]
+    fn test_render_milestone() {
+        let state = state();
+        let text = render_milestone(&state, &state.milestones[0], 0);
+        assert!(text.starts_with("launch  Launch  1/2 complete  due 1970-01-11 (in 10 days)\n\n"));
+        assert!(text.contains("login  Complete     Login\n"));
+        assert!(text.contains("gone   (no longer tracked)\n"));
+    }
+
+    #[test]
+    fn test_render_milestone_wide_ids() {
+        let mut state = state();
+        state.features[1].id = "登录".to_string();
+        state.milestones[0].feature_ids = vec!["login".to_string(), "登录".to_string()];
+        let text = render_milestone(&state, &state.milestones[0], 0);
+        // 登录 takes four columns, so it gets one space of padding, not three
+        assert!(text.contains("login  Complete     Login\n"));
+        assert!(text.contains("登录   Pending      SSO\n"));
+    }
+}
@@ -8,5 +8,60 @@ pub mod get_state;
 pub mod update;
 
 pub mod show;
+#[cfg(feature = "tui")]
+pub mod show_interactive;
 pub mod search;
 pub mod discover;
+pub mod pr_description;
+pub mod release_notes;
+pub mod changelog;
+pub mod validate;
+pub mod lint;
+pub mod coverage;
+pub mod metrics;
+pub mod diag;
+pub mod replay;
+pub mod stats;
+pub mod impact;
+pub mod onboard;
+pub mod verify;
+pub mod copy;
+pub mod export;
+#[cfg(feature = "docs")]
+pub mod export_docs;
+pub mod export_formats;
+pub mod import;
+pub mod merge;
+pub mod warm;
+pub mod optimize;
+pub mod convert;
+pub mod compact;
+pub mod config;
+pub mod dedupe;
+pub mod split;
+pub mod handoff;
+pub mod plan;
+pub mod suggest_status;
+pub mod next;
+pub mod hooks;
+pub mod task;
+pub mod milestone;
+pub mod goal;
+pub mod focus;
+pub mod focus_watch;
+pub mod service;
+pub mod sync;
+pub mod convention;
+pub mod project;
+pub mod projects;
+pub mod decide;
+pub mod session;
+pub mod history;
+pub mod diff;
+pub mod archive;
+pub mod version;
+pub mod note;
+pub mod log_time;
+pub mod recent;
+pub mod relate;
+pub mod why;
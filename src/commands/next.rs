@@ -0,0 +1,152 @@
+// Next command - which feature to pick up now
+//
+// Usage:
+//   legend next           - the best candidate, then a few runners-up
+//   legend next --json    - the same list with scores
+//
+// A candidate is open (not Complete or Blocked), not a planned stub, and
+// not waiting on a dependency. It is scored like show orders features
+// (priority weight plus recency), less a penalty for risk and a smaller
+// one for complexity, so among equally urgent work the safer, smaller
+// piece comes first. A feature without a risk or complexity counts as 3,
+// the middle of the 1-5 scale.
+
+use crate::deps;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use crate::types::{Feature, FeatureStatus, LegendState};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend next [--json]";
+
+/// How many candidates are listed
+const LIMIT: usize = 5;
+
+/// Score taken off at risk 5 (none at risk 1); a Priority level is 0.5-1.0
+const RISK_WEIGHT: f64 = 0.6;
+
+/// Score taken off at complexity 5 (none at complexity 1)
+const COMPLEXITY_WEIGHT: f64 = 0.3;
+
+/// Handle the next command
+pub fn handle_next(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let json_out = match args {
+        [] => false,
+        [flag] if flag == "--json" => true,
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+
+    let state = storage::load_state()?;
+    let ranked = rank(&state);
+
+    if json_out {
+        let report: Vec<_> = ranked
+            .iter()
+            .take(LIMIT)
+            .map(|(score, f)| {
+                json!({
+                    "id": f.id,
+                    "name": f.name,
+                    "status": f.status,
+                    "priority": f.priority,
+                    "complexity": f.complexity,
+                    "risk": f.risk,
+                    "score": (score * 100.0).round() / 100.0,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if ranked.is_empty() {
+        output::info("Nothing ready: every open feature is blocked, waiting on a dependency, or only planned.");
+    } else {
+        print!("{}", render(&ranked));
+    }
+    Ok(())
+}
+
+/// Ready features, best first
+fn rank(state: &LegendState) -> Vec<(f64, &Feature)> {
+    let mut ranked: Vec<(f64, &Feature)> = state
+        .features
+        .iter()
+        .filter(|f| !matches!(f.status, FeatureStatus::Complete | FeatureStatus::Blocked))
+        .filter(|f| !f.planned && deps::waiting_on(f, &state.features).is_empty())
+        .map(|f| (score(f), f))
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+    ranked
+}
+
+fn score(feature: &Feature) -> f64 {
+    // 1 -> 0.0, 5 -> 1.0
+    let scale = |value: Option<u8>| (f64::from(value.unwrap_or(3).clamp(1, 5)) - 1.0) / 4.0;
+    feature.ordering_score() - RISK_WEIGHT * scale(feature.risk) - COMPLEXITY_WEIGHT * scale(feature.complexity)
+}
+
+/// `auth-sso  Single sign-on  (InProgress, High priority, risk 2, complexity 3)`
+fn describe(feature: &Feature) -> String {
+    let mut facts = vec![feature.status.to_string(), format!("{:?} priority", feature.priority)];
+    if let Some(risk) = feature.risk {
+        facts.push(format!("risk {}", risk));
+    }
+    if let Some(complexity) = feature.complexity {
+        facts.push(format!("complexity {}", complexity));
+    }
+    format!("{}  {}  ({})", feature.id, feature.name, facts.join(", "))
+}
+
+fn render(ranked: &[(f64, &Feature)]) -> String {
+    let mut out = format!("Next: {}\n", describe(ranked[0].1));
+    if ranked.len() > 1 {
+        out.push_str("\nAlso ready:\n");
+        for (_, feature) in ranked.iter().skip(1).take(LIMIT - 1) {
+            out.push_str(&format!("  {}\n", describe(feature)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Priority;
+
+    fn feature(id: &str, priority: Priority, risk: Option<u8>) -> Feature {
+        let mut feature = Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string());
+        feature.priority = priority;
+        feature.risk = risk;
+        feature
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features.push(feature("risky", Priority::High, Some(5)));
+        state.features.push(feature("safe", Priority::High, Some(1)));
+        state.features.push(feature("unrated", Priority::High, None));
+        state.features.push(feature("critical", Priority::Critical, Some(5)));
+        let mut done = feature("done", Priority::Critical, None);
+        done.status = FeatureStatus::Complete;
+        state.features.push(done);
+        let mut waiting = feature("waiting", Priority::Critical, None);
+        waiting.depends_on = vec!["risky".to_string()];
+        state.features.push(waiting);
+
+        let ids: Vec<&str> = rank(&state).iter().map(|(_, f)| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["critical", "safe", "unrated", "risky"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut state = LegendState::new("Demo".to_string());
+        let mut sso = feature("auth-sso", Priority::High, Some(2));
+        sso.complexity = Some(3);
+        state.features.push(sso);
+        state.features.push(feature("billing", Priority::Low, None));
+        assert_eq!(
+            render(&rank(&state)),
+            "Next: auth-sso  auth-sso  (Pending, High priority, risk 2, complexity 3)\n\nAlso ready:\n  billing  billing  (Pending, Low priority)\n"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+// Note command - a running log of what happened on a feature
+//
+// Usage:
+//   legend note <feature-id> "<text>"                  - append a note
+//   legend note <feature-id> "<text>" --author <name>  - ...by someone else
+//   legend note <feature-id> [--json]                  - the log, oldest first
+//   legend note --from-stdin <feature-id>              - append what's piped in
+//
+// Notes are appended, never edited: an update can rewrite description and
+// context, but not this history. The author defaults to
+// `git config user.name`.
+//
+// --from-stdin is for hooks: a Stop hook can pipe the agent's closing
+// summary straight into the feature it was about. Captured output comes
+// with color codes, spinner redraws, and blank lines at the end, so the
+// text is cleaned first, and nothing to say is not an error.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::git;
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, Note};
+use serde_json::json;
+use std::io::{self, Read};
+
+const USAGE: &str = "Usage: legend note <feature-id> \"<text>\" [--author <name>]\n       legend note --from-stdin <feature-id> [--author <name>]\n       legend note <feature-id> [--json]";
+
+/// Handle the note command
+pub fn handle_note(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let from_stdin = args.iter().any(|a| a == "--from-stdin");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--from-stdin").cloned().collect();
+    let (id, rest) = args.split_first().ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+
+    let mut text = None;
+    let mut author = None;
+    let mut json_out = false;
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i].as_str() {
+            "--json" => json_out = true,
+            "--author" => {
+                i += 1;
+                author = Some(
+                    rest.get(i)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, format!("--author expects a name\n{}", USAGE)))?
+                        .clone(),
+                );
+            }
+            other if other.starts_with("--") || text.is_some() => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => text = Some(other.to_string()),
+        }
+        i += 1;
+    }
+
+    if from_stdin {
+        if text.is_some() || json_out {
+            return Err(ExitError::new(exit::USAGE, USAGE).into());
+        }
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Failed to read stdin: {}", e))?;
+        let text = clean_captured(&input);
+        if text.is_empty() {
+            output::info("Nothing on stdin; no note added.");
+            return Ok(());
+        }
+        return add(id, &text, author.or_else(git::user_name));
+    }
+
+    match text {
+        Some(text) if !json_out => add(id, &text, author.or_else(git::user_name)),
+        None if author.is_none() => list(id, json_out),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn add(id: &str, text: &str, author: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    if text.trim().is_empty() {
+        return Err(ExitError::new(exit::USAGE, format!("The note is empty\n{}", USAGE)).into());
+    }
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &mut state.features[index];
+    add_note(feature, text.trim(), author, current_timestamp());
+    let (feature_id, count) = (feature.id.clone(), feature.notes.len());
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Added note {} to {}.", count, feature_id),
+        json!({"id": feature_id, "notes": count}),
+    );
+    Ok(())
+}
+
+fn list(id: &str, json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let state = storage::load_state()?;
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &state.features[index];
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&feature.notes)?);
+    } else if feature.notes.is_empty() {
+        output::info(&format!("{} has no notes. Add one with 'legend note {} \"<text>\"'.", feature.id, feature.id));
+    } else {
+        for note in &feature.notes {
+            println!("{}", render_note(note));
+        }
+    }
+    Ok(())
+}
+
+fn add_note(feature: &mut Feature, text: &str, author: Option<String>, now: i64) {
+    feature.notes.push(Note {
+        created_at: now,
+        text: text.to_string(),
+        author,
+    });
+    feature.last_updated = now;
+}
+
+/// Terminal output as note text: escape sequences and control characters
+/// removed, a line redrawn with \r kept as its last version, trailing
+/// spaces trimmed, and runs of blank lines (and any at either end) dropped
+fn clean_captured(input: &str) -> String {
+    let mut plain = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: ESC [ params, ending in a byte from @ to ~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: ESC ] text, ending in BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\t' | '\r' => plain.push(c),
+            c if c.is_control() => {}
+            c => plain.push(c),
+        }
+    }
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in plain.split('\n') {
+        let line = line.rsplit('\r').next().unwrap_or("").trim_end();
+        if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// `2024-03-01 14:05  alice  Rolled back the cache change`
+pub fn render_note(note: &Note) -> String {
+    match &note.author {
+        Some(author) => format!("{}  {}  {}", dates::format_datetime(note.created_at), author, note.text),
+        None => format!("{}  {}", dates::format_datetime(note.created_at), note.text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    #[test]
+    fn test_note_appends_with_author() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let mut state = storage::load_state().unwrap();
+        state.features.push(Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "d".to_string()));
+        storage::save_state(&state).unwrap();
+
+        handle_note(&["auth", "Tried JWT, reverted", "--author", "alice"].map(String::from)).unwrap();
+        handle_note(&["auth", "Back to sessions", "--author", "bob"].map(String::from)).unwrap();
+        assert!(handle_note(&["auth", "a", "b"].map(String::from)).is_err());
+
+        let notes = storage::load_state().unwrap().features.remove(0).notes;
+        assert_eq!(notes.len(), 2);
+        assert_eq!(render_note(&notes[0]), "2024-03-01 00:00  alice  Tried JWT, reverted");
+    }
+
+    #[test]
+    fn test_clean_captured() {
+        let captured = "\x1b[1m\x1b[32mDone.\x1b[0m Summary:  \r\n\n\n\
+            - fixed \x1b]8;;https://x\x07the callback\x1b]8;;\x1b\\\n\
+            \tworking...\r- added tests\x08\n\n  \n";
+        assert_eq!(clean_captured(captured), "Done. Summary:\n\n- fixed the callback\n- added tests");
+        assert_eq!(clean_captured("\x1b[2K\r \n\n"), "");
+    }
+}
@@ -0,0 +1,251 @@
+// Onboard command - a Markdown orientation tour of the project
+//
+// Usage:
+//   legend onboard              - print the tour to stdout
+//   legend onboard --top <n>    - include more (or fewer) than five features
+//
+// Written for two audiences: a person joining the project, and a fresh
+// agent context that needs the lay of the land before its first task.
+// Conventions and decisions will join the tour once Legend records them.
+//
+// Importance score
+// ----------------
+// There is no single "importance" field, so the tour ranks features by a
+// blend of signals Legend already has (each term is in 0..=1):
+//
+//   recency            x 1.0   what the team is touching now
+//   status             x 0.5   in-progress > blocked > complete > pending
+//   breadth            x 0.5   how many files the feature spans
+//   centrality         x 0.5   share of other features it overlaps with
+//   has context        + 0.25  someone wrote down why/how
+
+use crate::exit::{self, ExitError};
+use crate::storage;
+use crate::types::{Feature, FeatureStatus, LegendState};
+use std::collections::BTreeMap;
+
+const DEFAULT_TOP: usize = 5;
+const MAX_FIRST_FILES: usize = 8;
+
+/// Handle the onboard command
+pub fn handle_onboard(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut top = DEFAULT_TOP;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--top" => {
+                i += 1;
+                top = args
+                    .get(i)
+                    .and_then(|v| v.parse().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, "--top expects a positive number"))?;
+            }
+            other => {
+                return Err(ExitError::new(
+                    exit::USAGE,
+                    format!("Unknown argument: {}\nUsage: legend onboard [--top <n>]", other),
+                )
+                .into());
+            }
+        }
+        i += 1;
+    }
+
+    let state = storage::load_state()?;
+    let root = storage::project_root();
+    println!("{}", render_tour(&state, top, |file| root.join(file).exists()));
+
+    Ok(())
+}
+
+/// Rank features by importance score, highest first
+fn ranked_features(state: &LegendState) -> Vec<(&Feature, f64)> {
+    let mut ranked: Vec<(&Feature, f64)> = state
+        .features
+        .iter()
+        .map(|f| (f, importance_score(f, state)))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.id.cmp(&b.0.id))
+    });
+    ranked
+}
+
+/// Blend of recency, status, breadth, centrality, and context (see top of file)
+fn importance_score(feature: &Feature, state: &LegendState) -> f64 {
+    let status = match feature.status {
+        FeatureStatus::InProgress => 1.0,
+        FeatureStatus::Blocked => 0.75,
+        FeatureStatus::Complete => 0.5,
+        FeatureStatus::Pending | FeatureStatus::Custom(_) => 0.25,
+    };
+
+    // ln(1 + files) normalized so 10 files scores 1.0
+    let breadth = ((1.0 + feature.files_involved.len() as f64).ln() / 11f64.ln()).min(1.0);
+
+    let others = state.features.len().saturating_sub(1);
+    let centrality = if others == 0 {
+        0.0
+    } else {
+        let overlapping = state
+            .features
+            .iter()
+            .filter(|other| other.id != feature.id)
+            .filter(|other| feature.files_involved.iter().any(|f| other.involves_file(f)))
+            .count();
+        overlapping as f64 / others as f64
+    };
+
+    let context = if feature.context.as_deref().is_some_and(|c| !c.trim().is_empty()) {
+        0.25
+    } else {
+        0.0
+    };
+
+    feature.recency_score + 0.5 * status + 0.5 * breadth + 0.5 * centrality + context
+}
+
+/// Files to read first: those of the top features, most shared first
+fn first_files<F>(state: &LegendState, top: &[&Feature], exists: F) -> Vec<(String, usize)>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for feature in top {
+        for file in &feature.files_involved {
+            if file.ends_with('/') || !exists(file) {
+                continue;
+            }
+            let used_by = state.features.iter().filter(|f| f.involves_file(file)).count();
+            counts.insert(file.clone(), used_by);
+        }
+    }
+
+    let mut files: Vec<(String, usize)> = counts.into_iter().collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    files.truncate(MAX_FIRST_FILES);
+    files
+}
+
+/// Render the tour as Markdown
+fn render_tour<F>(state: &LegendState, top: usize, exists: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    let mut out = String::new();
+    out.push_str(&format!("# Welcome to {}\n\n", state.project_name));
+
+    if state.features.is_empty() {
+        out.push_str("Legend isn't tracking any features yet. Run `legend discover` to get started.\n");
+        return out;
+    }
+
+    // Project context
+    let mut domains: BTreeMap<&str, usize> = BTreeMap::new();
+    for feature in &state.features {
+        *domains.entry(feature.domain.as_str()).or_insert(0) += 1;
+    }
+    let count_status = |status: FeatureStatus| state.features.iter().filter(|f| f.status == status).count();
+
+    out.push_str("## Project at a glance\n\n");
+    out.push_str(&format!(
+        "- {} features: {} in progress, {} blocked, {} pending, {} complete\n",
+        state.features.len(),
+        count_status(FeatureStatus::InProgress),
+        count_status(FeatureStatus::Blocked),
+        count_status(FeatureStatus::Pending),
+        count_status(FeatureStatus::Complete),
+    ));
+    let domain_list: Vec<String> = domains
+        .iter()
+        .map(|(domain, count)| format!("{} ({})", domain, count))
+        .collect();
+    out.push_str(&format!("- Domains: {}\n\n", domain_list.join(", ")));
+
+    // Most important features
+    let ranked = ranked_features(state);
+    let top_features: Vec<&Feature> = ranked.iter().take(top).map(|(f, _)| *f).collect();
+
+    out.push_str(&format!("## The {} features to know first\n\n", top_features.len()));
+    for (i, feature) in top_features.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. **{}** (`{}`, {}, {:?})\n",
+            i + 1,
+            feature.name,
+            feature.id,
+            feature.domain,
+            feature.status
+        ));
+        out.push_str(&format!("   {}\n", feature.description));
+        if let Some(context) = feature.context.as_deref().filter(|c| !c.trim().is_empty()) {
+            out.push_str(&format!("   > {}\n", context.lines().next().unwrap_or("")));
+        }
+    }
+    out.push('\n');
+
+    // Where to start reading
+    let files = first_files(state, &top_features, exists);
+    if !files.is_empty() {
+        out.push_str("## Suggested first files\n\n");
+        for (file, used_by) in files {
+            let note = if used_by > 1 {
+                format!(" - shared by {} features", used_by)
+            } else {
+                String::new()
+            };
+            out.push_str(&format!("- `{}`{}\n", file, note));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("Run `legend show` for everything Legend tracks, or `legend search <keyword>` to dig in.\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, status: FeatureStatus, files: &[&str]) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_uppercase(), "core".to_string(), format!("{} desc", id));
+        f.status = status;
+        f.files_involved = files.iter().map(|s| s.to_string()).collect();
+        f
+    }
+
+    fn sample_state() -> LegendState {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features = vec![
+            feature("storage", FeatureStatus::InProgress, &["src/storage.rs", "src/types.rs"]),
+            feature("search", FeatureStatus::Complete, &["src/search.rs", "src/types.rs"]),
+            feature("idea", FeatureStatus::Pending, &[]),
+        ];
+        state
+    }
+
+    #[test]
+    fn test_ranking_prefers_active_central_features() {
+        let state = sample_state();
+        let ids: Vec<&str> = ranked_features(&state).iter().map(|(f, _)| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["storage", "search", "idea"]);
+    }
+
+    #[test]
+    fn test_render_tour() {
+        let state = sample_state();
+        let tour = render_tour(&state, 2, |file| file != "src/search.rs");
+
+        assert!(tour.starts_with("# Welcome to Demo\n"));
+        assert!(tour.contains("- 3 features: 1 in progress, 0 blocked, 1 pending, 1 complete"));
+        assert!(tour.contains("## The 2 features to know first"));
+        assert!(tour.contains("1. **STORAGE** (`storage`, core, InProgress)"));
+        assert!(!tour.contains("**IDEA**"));
+        // Shared file comes first; missing files are skipped
+        assert!(tour.contains("- `src/types.rs` - shared by 2 features\n- `src/storage.rs`\n"));
+        assert!(!tour.contains("src/search.rs`"));
+    }
+}
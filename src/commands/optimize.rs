@@ -0,0 +1,56 @@
+// Optimize command - lay out state.lz4 hottest features first
+//
+// Usage:
+//   legend optimize            - reorder state.lz4 by access counts now
+//   legend optimize --track    - start counting accesses (.legend/access.json)
+//   legend optimize --untrack  - stop counting and forget the counts
+//
+// While tracking is on, every save keeps the hot-first order; this command
+// is for applying it right away. See access.rs for what is counted.
+
+use crate::access::{self, AccessCounts};
+use crate::exit::{self, ExitError};
+use crate::storage;
+
+const USAGE: &str = "Usage: legend optimize [--track | --untrack]";
+
+/// Handle the optimize command
+pub fn handle_optimize(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("--track") if args.len() == 1 => {
+            if !access::is_enabled() {
+                access::save_counts(&AccessCounts::new())?;
+            }
+            println!("Access tracking on: search and impact results are counted in .legend/access.json");
+            return Ok(());
+        }
+        Some("--untrack") if args.len() == 1 => {
+            access::remove_counts()?;
+            println!("Access tracking off: .legend/access.json removed");
+            return Ok(());
+        }
+        Some(other) => {
+            return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+        }
+    }
+
+    let Some(counts) = access::counts_in(&storage::legend_dir()) else {
+        return Err("No access data yet. Run 'legend optimize --track' first.".into());
+    };
+
+    // save_state applies the order while tracking is on
+    let _lock = storage::lock_state()?;
+    let state = storage::load_state()?;
+    storage::save_state(&state)?;
+
+    let mut hottest: Vec<(&String, &u64)> = counts.iter().filter(|(id, _)| state.find_feature(id).is_some()).collect();
+    hottest.sort_by(|a, b| b.1.cmp(a.1));
+    let summary: Vec<String> = hottest.iter().take(5).map(|(id, n)| format!("{} ({})", id, n)).collect();
+
+    println!("Reordered {} feature(s), hottest first", state.features.len());
+    if !summary.is_empty() {
+        println!("  {}", summary.join(", "));
+    }
+    Ok(())
+}
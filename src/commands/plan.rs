@@ -0,0 +1,211 @@
+// Plan and promote commands - reserve an ID for work that hasn't started
+//
+// Usage:
+//   legend plan <id> "<one-liner>" [--domain <d>]  - record a planned stub
+//   legend promote <id> --description "<text>" [--name <n>] [--domain <d>]
+//                       [--context <text>] [--files a,b] [--tags a,b]
+//
+// A planned stub is a feature with `planned: true`, the one-liner as its
+// name, and no description. Agents see it in get_state (and show lists it
+// as Planned) so they know the work is coming and don't invent a second
+// ID for it. Lint leaves stubs alone - being thin is the point.
+//
+// `promote` turns the stub into a real feature. It fails, changing
+// nothing, until name, domain, and description are all filled in, either
+// already or by its flags.
+
+use crate::config;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature};
+use serde_json::json;
+
+const PLAN_USAGE: &str = "Usage: legend plan <id> \"<one-liner>\" [--domain <d>]";
+
+const PROMOTE_USAGE: &str = "Usage: legend promote <id> [--description <text>] [--name <n>] [--domain <d>] [--context <text>] [--files a,b] [--tags a,b]";
+
+/// Handle the plan command
+pub fn handle_plan(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut domain = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--domain" => {
+                i += 1;
+                domain = Some(args.get(i).ok_or_else(|| ExitError::new(exit::USAGE, PLAN_USAGE))?.clone());
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, PLAN_USAGE)).into());
+            }
+            other => positional.push(other.to_string()),
+        }
+        i += 1;
+    }
+
+    let (id, summary) = match positional.as_slice() {
+        [id, summary] => (id.clone(), summary.clone()),
+        _ => return Err(ExitError::new(exit::USAGE, PLAN_USAGE).into()),
+    };
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+
+    let mut stub = planned_stub(&id, &summary, domain.as_deref());
+    if !stub.domain.is_empty() && config::namespaced_ids(&config::load_config()?)? {
+        stub.id = ids::namespaced(&stub.domain, &stub.id);
+    }
+    if let Some(existing) = state.find_feature(&stub.id) {
+        let kind = if existing.planned { "planned" } else { "tracked" };
+        return Err(format!("'{}' is already {}", stub.id, kind).into());
+    }
+
+    let id = stub.id.clone();
+    state.add_feature(stub);
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Planned {}. Run 'legend promote {}' once the work is defined.", id, id),
+        json!({"id": id}),
+    );
+    Ok(())
+}
+
+/// Handle the promote command
+pub fn handle_promote(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut id = None;
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            flag @ ("--description" | "--name" | "--domain" | "--context" | "--files" | "--tags") => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("{} expects a value\n{}", flag, PROMOTE_USAGE)))?;
+                fields.push((flag.trim_start_matches("--").to_string(), value.clone()));
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, PROMOTE_USAGE)).into());
+            }
+            other => id = Some(other.to_string()),
+        }
+        i += 1;
+    }
+    let id = id.ok_or_else(|| ExitError::new(exit::USAGE, PROMOTE_USAGE))?;
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = ids::resolve(&state.features, &id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    if !state.features[index].planned {
+        return Err(format!("'{}' is not a planned stub; use 'legend update' to change it", id).into());
+    }
+
+    promote(&mut state.features[index], &fields, current_timestamp())?;
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let id = &state.features[index].id;
+    output::diag(output::Level::Info, &format!("Promoted {} to a tracked feature.", id), json!({"id": id}));
+    Ok(())
+}
+
+/// A stub: the one-liner as its name, domain from --domain or the ID prefix
+fn planned_stub(id: &str, summary: &str, domain: Option<&str>) -> Feature {
+    let domain = domain
+        .map(str::to_string)
+        .or_else(|| ids::split_id(id).0.map(str::to_string))
+        .unwrap_or_default();
+    let mut stub = Feature::new(id.to_string(), summary.to_string(), domain, String::new());
+    stub.planned = true;
+    stub
+}
+
+/// Apply promote's fields and clear `planned`, or explain what's missing
+///
+/// The feature is only changed if the result is complete
+fn promote(feature: &mut Feature, fields: &[(String, String)], now: i64) -> Result<(), Box<dyn std::error::Error>> {
+    let mut promoted = feature.clone();
+    let list = |value: &str| -> Vec<String> {
+        value.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+    };
+    for (field, value) in fields {
+        match field.as_str() {
+            "description" => promoted.description = value.clone(),
+            "name" => promoted.name = value.clone(),
+            "domain" => promoted.domain = value.clone(),
+            "context" => promoted.context = Some(value.clone()),
+            "files" => promoted.files_involved = list(value),
+            "tags" => promoted.tags = list(value),
+            _ => unreachable!("flags are matched in handle_promote"),
+        }
+    }
+
+    let missing: Vec<&str> = [
+        ("name", &promoted.name),
+        ("domain", &promoted.domain),
+        ("description", &promoted.description),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.trim().is_empty())
+    .map(|(field, _)| field)
+    .collect();
+    if !missing.is_empty() {
+        let flags: Vec<String> = missing.iter().map(|f| format!("--{}", f)).collect();
+        return Err(ExitError::new(
+            exit::USAGE,
+            format!("{} is missing {}; pass {}", feature.id, missing.join(", "), flags.join(" ")),
+        )
+        .into());
+    }
+
+    promoted.planned = false;
+    promoted.last_updated = now;
+    *feature = promoted;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_planned_stub() {
+        let stub = planned_stub("api/rate-limit", "Throttle noisy clients", None);
+        assert!(stub.planned);
+        assert_eq!((stub.name.as_str(), stub.domain.as_str(), stub.description.as_str()), ("Throttle noisy clients", "api", ""));
+        assert_eq!(planned_stub("rate-limit", "x", Some("gateway")).domain, "gateway");
+        assert_eq!(planned_stub("rate-limit", "x", None).domain, "");
+    }
+
+    #[test]
+    fn test_promote_requires_complete_fields() {
+        let mut feature = planned_stub("rate-limit", "Throttle noisy clients", None);
+
+        let err = promote(&mut feature, &fields(&[("tags", "backend")]), 10).unwrap_err();
+        assert_eq!(err.to_string(), "rate-limit is missing domain, description; pass --domain --description");
+        assert!(feature.planned && feature.tags.is_empty());
+
+        promote(
+            &mut feature,
+            &fields(&[("domain", "api"), ("description", "Token bucket per API key"), ("files", "src/limit.rs, src/api.rs")]),
+            10,
+        )
+        .unwrap();
+        assert!(!feature.planned);
+        assert_eq!(feature.files_involved, vec!["src/limit.rs", "src/api.rs"]);
+        assert_eq!(feature.last_updated, 10);
+    }
+}
@@ -0,0 +1,279 @@
+// PR description command - summarize the current branch per feature
+//
+// Reads the commits on the current branch (relative to a base branch),
+// matches the files they touch against each feature's files_involved,
+// and prints a Markdown PR body to stdout. Each feature lists its linked
+// decisions and the subtasks still open, so reviewers see what's left.
+//
+// Usage:
+//   legend pr-description                 - compare against main/master
+//   legend pr-description --base develop  - compare against another ref
+//
+// Rust concepts in this file:
+// - Building strings incrementally with push_str and format!
+// - BTreeSet for sorted, de-duplicated collections
+// - Borrowing features (&Feature) while grouping commits
+
+use crate::exit::{self, ExitError};
+use crate::git::{self, Commit};
+use crate::output;
+use crate::storage;
+use crate::types::Feature;
+use serde_json::json;
+use std::collections::BTreeSet;
+
+/// Commits and files on the branch that belong to one feature
+struct FeatureChanges<'a> {
+    feature: &'a Feature,
+    commits: Vec<&'a Commit>,
+    files: BTreeSet<String>,
+}
+
+/// Handle the pr-description command
+///
+/// Output: Markdown to stdout (paste into the PR body)
+/// Summary: counts to stderr
+pub fn handle_pr_description(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let base = parse_args(args)?;
+
+    let base = match base {
+        Some(b) => b,
+        None => git::default_base_branch()
+            .ok_or("Could not find a base branch. Use --base <ref>.")?,
+    };
+
+    let state = storage::load_state()?;
+    let commits = git::commits_in_range(&format!("{}..HEAD", base))?;
+
+    if commits.is_empty() {
+        return Err(format!("No commits between {} and HEAD", base).into());
+    }
+
+    let (changes, untracked) = group_by_feature(&state.features, &commits);
+    let markdown = render_markdown(&changes, &untracked, commits.len());
+
+    println!("{}", markdown);
+    output::diag(
+        output::Level::Info,
+        &format!(
+            "Summarized {} commit(s) across {} feature(s) against {}",
+            commits.len(),
+            changes.len(),
+            base
+        ),
+        json!({"commits": commits.len(), "features": changes.len(), "base": base}),
+    );
+
+    Ok(())
+}
+
+/// Parse arguments, returning the --base ref if one was given
+fn parse_args(args: &[String]) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let mut base = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--base" => {
+                i += 1;
+                base = Some(
+                    args.get(i)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--base requires a value"))?
+                        .clone(),
+                );
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}", other)).into());
+            }
+        }
+        i += 1;
+    }
+
+    Ok(base)
+}
+
+/// Match commits to features through the files they touch
+///
+/// A commit belongs to every feature that tracks at least one of its files.
+/// Returns the per-feature changes (in state order) plus the changed files
+/// that no feature tracks.
+fn group_by_feature<'a>(
+    features: &'a [Feature],
+    commits: &'a [Commit],
+) -> (Vec<FeatureChanges<'a>>, BTreeSet<String>) {
+    let mut changes: Vec<FeatureChanges> = Vec::new();
+
+    for feature in features {
+        let mut entry = FeatureChanges {
+            feature,
+            commits: Vec::new(),
+            files: BTreeSet::new(),
+        };
+
+        for commit in commits {
+            let touched: Vec<&String> = commit
+                .files
+                .iter()
+                .filter(|f| feature.involves_file(f))
+                .collect();
+
+            if !touched.is_empty() {
+                entry.commits.push(commit);
+                entry.files.extend(touched.into_iter().cloned());
+            }
+        }
+
+        if !entry.commits.is_empty() {
+            changes.push(entry);
+        }
+    }
+
+    let untracked = commits
+        .iter()
+        .flat_map(|c| c.files.iter())
+        .filter(|f| !features.iter().any(|feature| feature.involves_file(f)))
+        .cloned()
+        .collect();
+
+    (changes, untracked)
+}
+
+/// Render the grouped changes as a Markdown PR body
+fn render_markdown(
+    changes: &[FeatureChanges],
+    untracked: &BTreeSet<String>,
+    commit_count: usize,
+) -> String {
+    let mut out = String::new();
+
+    out.push_str("## Summary\n\n");
+    out.push_str(&format!(
+        "This branch touches {} tracked feature(s) across {} commit(s).\n",
+        changes.len(),
+        commit_count
+    ));
+
+    for change in changes {
+        let feature = change.feature;
+        out.push_str(&format!("\n### {} (`{}`)\n\n", feature.name, feature.id));
+        out.push_str(&format!(
+            "**Status:** {:?} · **Domain:** {}\n\n",
+            feature.status, feature.domain
+        ));
+        out.push_str(&format!("{}\n", feature.description));
+
+        if !feature.decisions.is_empty() {
+            out.push_str("\nDecisions:\n");
+            for decision in &feature.decisions {
+                out.push_str(&format!("- #{} {}\n", decision.id, decision.title));
+            }
+        }
+
+        let open: Vec<_> = feature.subtasks.iter().filter(|t| !t.done).collect();
+        if !open.is_empty() {
+            out.push_str("\nStill to do:\n");
+            for subtask in open {
+                out.push_str(&format!("- [ ] {}\n", subtask.title));
+            }
+        }
+
+        out.push_str("\nCommits:\n");
+        for commit in &change.commits {
+            out.push_str(&format!("- `{}` {}\n", commit.hash, commit.subject));
+        }
+
+        out.push_str("\nFiles changed:\n");
+        for file in &change.files {
+            out.push_str(&format!("- `{}`\n", file));
+        }
+    }
+
+    if !untracked.is_empty() {
+        out.push_str("\n## Other changes\n\nFiles not tracked by any feature:\n");
+        for file in untracked {
+            out.push_str(&format!("- `{}`\n", file));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DecisionLink, Subtask};
+
+    fn make_feature(id: &str, files: &[&str]) -> Feature {
+        let mut f = Feature::new(
+            id.to_string(),
+            format!("Feature {}", id),
+            "test".to_string(),
+            "Test feature".to_string(),
+        );
+        f.files_involved = files.iter().map(|s| s.to_string()).collect();
+        f
+    }
+
+    fn make_commit(hash: &str, files: &[&str]) -> Commit {
+        Commit {
+            hash: hash.to_string(),
+            subject: format!("Commit {}", hash),
+            files: files.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_feature() {
+        let features = vec![
+            make_feature("auth", &["src/auth.rs"]),
+            make_feature("storage", &["src/storage.rs"]),
+            make_feature("ui", &["src/ui/"]),
+        ];
+        let commits = vec![
+            make_commit("a1", &["src/auth.rs", "Cargo.toml"]),
+            make_commit("b2", &["src/ui/table.rs"]),
+        ];
+
+        let (changes, untracked) = group_by_feature(&features, &commits);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].feature.id, "auth");
+        assert_eq!(changes[0].commits[0].hash, "a1");
+        assert_eq!(changes[1].feature.id, "ui");
+        assert!(changes[1].files.contains("src/ui/table.rs"));
+        assert_eq!(untracked.into_iter().collect::<Vec<_>>(), vec!["Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let features = vec![make_feature("auth", &["src/auth.rs"])];
+        let commits = vec![make_commit("a1", &["src/auth.rs"])];
+        let (changes, untracked) = group_by_feature(&features, &commits);
+
+        let md = render_markdown(&changes, &untracked, commits.len());
+
+        assert!(md.contains("### Feature auth (`auth`)"));
+        assert!(md.contains("- `a1` Commit a1"));
+        assert!(md.contains("- `src/auth.rs`"));
+        assert!(!md.contains("Other changes"));
+    }
+
+    #[test]
+    fn test_render_markdown_lists_decisions_and_open_subtasks() {
+        let mut feature = make_feature("auth", &["src/auth.rs"]);
+        feature.decisions = vec![DecisionLink { id: 3, title: "Use JWT".to_string() }];
+        feature.subtasks = vec![
+            Subtask { id: 1, title: "Login form".to_string(), done: true, created_at: 0, completed_at: Some(1) },
+            Subtask { id: 2, title: "Password reset".to_string(), done: false, created_at: 0, completed_at: None },
+        ];
+        let features = vec![feature];
+        let commits = vec![make_commit("a1", &["src/auth.rs"])];
+        let (changes, untracked) = group_by_feature(&features, &commits);
+
+        let md = render_markdown(&changes, &untracked, commits.len());
+
+        assert!(md.contains("Decisions:\n- #3 Use JWT\n"));
+        assert!(md.contains("Still to do:\n- [ ] Password reset\n"));
+        assert!(!md.contains("Login form"));
+    }
+}
@@ -0,0 +1,150 @@
+// Project command - what the project is, for a new session's orientation
+//
+// Usage:
+//   legend project [show] [--json]       - the name and project block
+//   legend project set <key> <value>     - name, description, repo_url, tech_stack, conventions
+//   legend project unset <key>
+//
+// `tech_stack` takes a comma-separated list. The block is stored in
+// state.lz4 and get_state prints it right after the project name, so an
+// agent knows what it's looking at before it reads a single feature.
+
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::LegendState;
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend project [show] [--json]\n       legend project set <key> <value>\n       legend project unset <key>\nKeys: name, description, repo_url, tech_stack (comma-separated), conventions";
+
+/// Handle the project command
+pub fn handle_project(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(String::as_str) {
+        None | Some("--json") => show(args),
+        Some("show") => show(&args[1..]),
+        Some("set") => match &args[1..] {
+            [key, value] => edit(key, Some(value)),
+            _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+        },
+        Some("unset") => match &args[1..] {
+            [key] => edit(key, None),
+            _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+        },
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn show(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let json_out = match args {
+        [] => false,
+        [flag] if flag == "--json" => true,
+        _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+    };
+    let state = storage::load_state()?;
+    if json_out {
+        let report = json!({"project_name": state.project_name, "project": state.project});
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", render(&state));
+    }
+    Ok(())
+}
+
+fn edit(key: &str, value: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    apply(&mut state, key, value)?;
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let message = match value {
+        Some(_) => format!("Set project {}.", key),
+        None => format!("Unset project {}.", key),
+    };
+    output::diag(output::Level::Info, &message, json!({"key": key, "set": value.is_some()}));
+    Ok(())
+}
+
+/// Set (Some) or clear (None) one key of the project block
+fn apply(state: &mut LegendState, key: &str, value: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let value = value.map(str::trim);
+    if value == Some("") {
+        return Err(ExitError::new(exit::USAGE, format!("Empty value for {}; use 'legend project unset {}'", key, key)).into());
+    }
+    let text = value.map(str::to_string);
+    let project = &mut state.project;
+    match key {
+        "name" => match value {
+            Some(name) => state.project_name = name.to_string(),
+            None => return Err(ExitError::new(exit::USAGE, "The project name can be changed but not unset").into()),
+        },
+        "description" => project.description = text,
+        "repo_url" => project.repo_url = text,
+        "tech_stack" => {
+            project.tech_stack = value
+                .map(|list| list.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default();
+        }
+        "conventions" => project.conventions = text,
+        other => return Err(ExitError::new(exit::USAGE, format!("Unknown key: {}\n{}", other, USAGE)).into()),
+    }
+    Ok(())
+}
+
+/// One labeled line per set field
+fn render(state: &LegendState) -> String {
+    let project = &state.project;
+    let mut out = format!("Name:         {}\n", state.project_name);
+    if let Some(description) = &project.description {
+        out.push_str(&format!("Description:  {}\n", description));
+    }
+    if let Some(url) = &project.repo_url {
+        out.push_str(&format!("Repository:   {}\n", url));
+    }
+    if !project.tech_stack.is_empty() {
+        out.push_str(&format!("Tech stack:   {}\n", project.tech_stack.join(", ")));
+    }
+    if let Some(conventions) = &project.conventions {
+        out.push_str(&format!("Conventions:  {}\n", conventions));
+    }
+    if project.is_empty() {
+        out.push_str("\nNothing else recorded. Add it with 'legend project set <key> <value>'.\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let mut state = LegendState::new("My Project".to_string());
+        apply(&mut state, "name", Some("Legend")).unwrap();
+        apply(&mut state, "tech_stack", Some("Rust, bincode,, LZ4 ")).unwrap();
+        apply(&mut state, "description", Some("Context memory for agents")).unwrap();
+        assert_eq!(state.project_name, "Legend");
+        assert_eq!(state.project.tech_stack, vec!["Rust", "bincode", "LZ4"]);
+
+        apply(&mut state, "description", None).unwrap();
+        assert_eq!(state.project.description, None);
+        assert!(apply(&mut state, "name", None).is_err());
+        assert!(apply(&mut state, "repo_url", Some("  ")).is_err());
+        assert!(apply(&mut state, "license", Some("MIT")).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let mut state = LegendState::new("Legend".to_string());
+        assert!(render(&state).contains("Nothing else recorded"));
+
+        apply(&mut state, "repo_url", Some("https://github.com/nickthorpe71/legend")).unwrap();
+        apply(&mut state, "tech_stack", Some("Rust")).unwrap();
+        assert_eq!(
+            render(&state),
+            "Name:         Legend\nRepository:   https://github.com/nickthorpe71/legend\nTech stack:   Rust\n"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// Projects command - every Legend project on this machine
+//
+// Usage:
+//   legend projects [--json]          - name, features, last activity, location
+//   legend projects forget <name>     - drop one (its .legend is untouched)
+//   legend -p <name> <command>        - run any command against one
+//
+// The list comes from ~/.legend/projects.json (see registry.rs), which
+// `legend init` adds to. Most recently active first. Listing drops
+// projects whose .legend is gone (moved, deleted); one whose state is
+// there but can't be read is listed as missing until it is forgotten.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::registry::{self, RegisteredProject};
+use crate::sandbox;
+use crate::storage;
+use crate::text;
+use serde::Serialize;
+use serde_json::json;
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: legend projects [--json]\n       legend projects forget <name>";
+
+/// One line of the listing
+#[derive(Debug, Serialize)]
+struct ProjectSummary {
+    name: String,
+    dir: PathBuf,
+    /// None when the state couldn't be read
+    features: Option<usize>,
+    last_activity: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Handle the projects command
+pub fn handle_projects(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args {
+        [] => list(false),
+        [flag] if flag == "--json" => list(true),
+        [command, name] if command == "forget" => forget(name),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn list(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // Pruning writes ~/.legend/projects.json, which --sandbox doesn't cover
+    if !sandbox::is_active() {
+        let gone = registry::prune()?;
+        if !gone.is_empty() {
+            let names: Vec<&str> = gone.iter().map(|p| p.name.as_str()).collect();
+            output::diag(
+                output::Level::Info,
+                &format!("Dropped {} whose .legend no longer exists.", names.join(", ")),
+                json!({"pruned": names}),
+            );
+        }
+    }
+    let mut summaries: Vec<ProjectSummary> = registry::load()?.projects.iter().map(summarize).collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+    } else if summaries.is_empty() {
+        output::info("No projects registered yet. `legend init` in a repository adds it.");
+    } else {
+        print!("{}", render(&summaries));
+    }
+    Ok(())
+}
+
+fn summarize(project: &RegisteredProject) -> ProjectSummary {
+    let (features, last_activity, error) = match storage::load_state_in(&project.dir) {
+        Ok(state) => (Some(state.features.len()), Some(state.last_updated), None),
+        Err(e) => (None, None, Some(e.to_string())),
+    };
+    ProjectSummary {
+        name: project.name.clone(),
+        dir: project.dir.clone(),
+        features,
+        last_activity,
+        error,
+    }
+}
+
+/// `web   12 features   2024-03-01 09:30   /home/me/web/.legend`
+fn render(summaries: &[ProjectSummary]) -> String {
+    let width = summaries.iter().map(|s| text::width(&s.name)).max().unwrap_or(0);
+    let mut out = String::new();
+    for summary in summaries {
+        let status = match (summary.features, summary.last_activity) {
+            (Some(count), Some(at)) => format!("{:>4} features   {}", count, dates::format_datetime(at)),
+            _ => format!("{:<30}", "missing"),
+        };
+        out.push_str(&format!("{}  {}   {}\n", text::pad(&summary.name, width), status, summary.dir.display()));
+    }
+    out
+}
+
+fn forget(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if !registry::forget(name)? {
+        return Err(format!("No project named '{}' (see legend projects)", name).into());
+    }
+    output::diag(
+        output::Level::Info,
+        &format!("Forgot {}; its .legend directory is untouched.", name),
+        json!({"forgotten": name}),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let summaries = vec![
+            ProjectSummary {
+                name: "web".to_string(),
+                dir: PathBuf::from("/src/web/.legend"),
+                features: Some(12),
+                last_activity: Some(1_709_285_400),
+                error: None,
+            },
+            ProjectSummary {
+                name: "old-api".to_string(),
+                dir: PathBuf::from("/src/old-api/.legend"),
+                features: None,
+                last_activity: None,
+                error: Some("not initialized".to_string()),
+            },
+        ];
+        assert_eq!(
+            render(&summaries),
+            "web        12 features   2024-03-01 09:30   /src/web/.legend\n\
+             old-api  missing                          /src/old-api/.legend\n"
+        );
+    }
+}
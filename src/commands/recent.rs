@@ -0,0 +1,196 @@
+// Recent command - what changed lately, one line per feature
+//
+// Usage:
+//   legend recent                  - features updated in the last 48 hours
+//   legend recent --since 7d       - ...in a different window (48h, 7d, 2w,
+//                                    or a date like 2024-03-01)
+//   legend recent --limit <n>      - at most n features (default: 10)
+//   legend recent --json           - the same as JSON
+//
+// Each line says what changed, from the journal: the field changes of every
+// event in the window are folded into one before -> after per field, so a
+// feature moved Pending -> InProgress -> Complete reads "status Pending ->
+// Complete". Features updated without a journaled change say "touched".
+
+use crate::dates;
+use crate::diff::{self, FieldChange};
+use crate::exit::{self, ExitError};
+use crate::journal::{self, Event, EventKind};
+use crate::storage;
+use crate::text;
+use crate::types::{current_timestamp, Feature};
+use serde::Serialize;
+
+const USAGE: &str = "Usage: legend recent [--since <48h|7d|2w|date>] [--limit <n>] [--json]";
+
+/// Window when --since is not given
+const DEFAULT_WINDOW: &str = "48h";
+
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Debug, Serialize)]
+struct RecentEntry {
+    id: String,
+    name: String,
+    last_updated: i64,
+    /// Added in the window rather than changed
+    added: bool,
+    summary: String,
+    changes: Vec<FieldChange>,
+}
+
+/// Handle the recent command
+pub fn handle_recent(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut since = DEFAULT_WINDOW.to_string();
+    let mut limit = DEFAULT_LIMIT;
+    let mut json_out = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--json" => json_out = true,
+            flag @ ("--since" | "--limit") => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("{} expects a value\n{}", flag, USAGE)))?;
+                if flag == "--since" {
+                    since = value.clone();
+                } else {
+                    limit = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, format!("--limit expects a positive number\n{}", USAGE)))?;
+                }
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+    let cutoff = match dates::parse_age(&since) {
+        Ok(age) => current_timestamp() - age,
+        Err(_) => dates::parse_date(&since)
+            .map_err(|_| ExitError::new(exit::USAGE, format!("'{}' is not an age or a date\n{}", since, USAGE)))?,
+    };
+
+    let state = storage::load_state()?;
+    let events = journal::read_events()?;
+    let entries = recent_entries(&state.features, &events, cutoff, limit);
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if entries.is_empty() {
+        println!("Nothing updated since {}.", dates::format_datetime(cutoff));
+    } else {
+        let width = entries.iter().map(|e| text::width(&e.id)).max().unwrap_or(0);
+        for entry in &entries {
+            println!(
+                "{}  {}  {}",
+                dates::format_datetime(entry.last_updated),
+                text::pad(&entry.id, width),
+                entry.summary
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The `limit` features updated at or after `cutoff`, newest first
+fn recent_entries(features: &[Feature], events: &[Event], cutoff: i64, limit: usize) -> Vec<RecentEntry> {
+    let mut recent: Vec<&Feature> = features.iter().filter(|f| f.last_updated >= cutoff).collect();
+    recent.sort_by(|a, b| b.last_updated.cmp(&a.last_updated).then_with(|| a.id.cmp(&b.id)));
+    recent.truncate(limit);
+
+    let window: Vec<&Event> = events.iter().filter(|e| e.timestamp >= cutoff).collect();
+    recent
+        .into_iter()
+        .map(|feature| {
+            let (added, changes) = fold_changes(&window, &feature.id);
+            let summary = match (added, changes.is_empty()) {
+                (true, true) => "added".to_string(),
+                (true, false) => format!("added; {}", diff::describe_changes(&changes)),
+                (false, true) => "touched".to_string(),
+                (false, false) => diff::describe_changes(&changes),
+            };
+            RecentEntry {
+                id: feature.id.clone(),
+                name: feature.name.clone(),
+                last_updated: feature.last_updated,
+                added,
+                summary,
+                changes,
+            }
+        })
+        .collect()
+}
+
+/// Whether `id` was added in these events, and its net change per field
+///
+/// Fields that end where they started are dropped
+fn fold_changes(events: &[&Event], id: &str) -> (bool, Vec<FieldChange>) {
+    let mut added = false;
+    let mut folded: Vec<FieldChange> = Vec::new();
+    for event in events {
+        match &event.kind {
+            EventKind::FeatureAdded { feature } if feature.id == id => added = true,
+            EventKind::FeatureChanged { id: changed, changes, .. } if changed == id => {
+                for change in changes {
+                    match folded.iter_mut().find(|c| c.field == change.field) {
+                        Some(existing) => existing.after = change.after.clone(),
+                        None => folded.push(change.clone()),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    folded.retain(|c| c.before != c.after);
+    (added, folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn changed(timestamp: i64, id: &str, field: &str, before: serde_json::Value, after: serde_json::Value) -> Event {
+        let feature = Feature::new(id.to_string(), id.to_string(), "d".to_string(), "d".to_string());
+        Event {
+            seq: 0,
+            timestamp,
+            kind: EventKind::FeatureChanged {
+                id: id.to_string(),
+                changes: vec![FieldChange { field: field.to_string(), before, after }],
+                feature,
+            },
+        }
+    }
+
+    #[test]
+    fn test_recent_entries_fold_the_window() {
+        let mut features: Vec<Feature> = ["auth", "billing", "old"]
+            .iter()
+            .map(|id| Feature::new(id.to_string(), id.to_string(), "d".to_string(), "d".to_string()))
+            .collect();
+        features[0].last_updated = 300;
+        features[1].last_updated = 200;
+        features[2].last_updated = 50;
+
+        let events = vec![
+            changed(50, "auth", "status", json!("Blocked"), json!("Pending")),
+            changed(150, "auth", "status", json!("Pending"), json!("InProgress")),
+            changed(300, "auth", "status", json!("InProgress"), json!("Complete")),
+            changed(300, "auth", "notes", json!([]), json!([{"text": "shipped"}])),
+            changed(200, "billing", "tags", json!([]), json!(["api"])),
+            changed(210, "billing", "tags", json!(["api"]), json!([])),
+        ];
+
+        let entries = recent_entries(&features, &events, 100, 10);
+        let lines: Vec<(&str, &str)> = entries.iter().map(|e| (e.id.as_str(), e.summary.as_str())).collect();
+        assert_eq!(lines, vec![("auth", "status Pending → Complete; note added"), ("billing", "touched")]);
+        assert_eq!(recent_entries(&features, &events, 100, 1).len(), 1);
+    }
+}
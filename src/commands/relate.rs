@@ -0,0 +1,168 @@
+// Relate command - typed links between features
+//
+// Usage:
+//   legend relate <a> blocks <b>             - a is in b's way
+//   legend relate <a> relates_to <b>         - worth reading together
+//   legend relate <a> duplicates <b>         - the same work (see dedupe)
+//   legend relate <a> <kind> <b> --remove    - take a link out
+//   legend relate <a> [--json]               - a's links, both directions
+//
+// Relations are stored on `a` and appear in get_state and search output,
+// so Claude can follow them from one feature to the next. Removing a
+// feature (archive, prune, dedupe) drops or redirects links to it.
+
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, Relation, RelationKind};
+use serde::Serialize;
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend relate <a> <blocks|relates_to|duplicates> <b> [--remove]\n       legend relate <a> [--json]";
+
+/// One link, from the listed feature's point of view
+#[derive(Debug, PartialEq, Serialize)]
+struct Link {
+    from: String,
+    kind: RelationKind,
+    to: String,
+}
+
+/// Handle the relate command
+pub fn handle_relate(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let remove = args.iter().any(|a| a == "--remove");
+    let json_out = args.iter().any(|a| a == "--json");
+    let words: Vec<&str> = args.iter().map(String::as_str).filter(|a| !matches!(*a, "--remove" | "--json")).collect();
+    if let Some(flag) = words.iter().find(|w| w.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+    }
+
+    match words.as_slice() {
+        [id] if !remove => list(id, json_out),
+        [from, kind, to] if !json_out => {
+            let kind = RelationKind::parse(kind).ok_or_else(|| {
+                ExitError::new(exit::USAGE, format!("Unknown relation '{}'\n{}", kind, USAGE))
+            })?;
+            relate(from, kind, to, remove)
+        }
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn relate(from: &str, kind: RelationKind, to: &str, remove: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let from_index = ids::resolve(&state.features, from).ok_or_else(|| format!("Feature '{}' not found", from))?;
+    let to_index = ids::resolve(&state.features, to).ok_or_else(|| format!("Feature '{}' not found", to))?;
+    if from_index == to_index {
+        return Err(format!("A feature can't be related to itself ({} {} {})", from, kind.as_str(), to).into());
+    }
+    let target = state.features[to_index].id.clone();
+    let feature = &mut state.features[from_index];
+    let relation = Relation { kind, target };
+    let sentence = format!("{} {} {}", feature.id, kind.as_str(), relation.target);
+
+    let changed = if remove {
+        unlink(feature, &relation, current_timestamp())
+    } else {
+        link(feature, relation, current_timestamp())
+    };
+    if !changed {
+        output::info(&format!("Nothing to do: {} {}.", sentence, if remove { "was not recorded" } else { "is already recorded" }));
+        return Ok(());
+    }
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    let verb = if remove { "Removed" } else { "Recorded" };
+    output::diag(output::Level::Info, &format!("{}: {}.", verb, sentence), json!({"relation": sentence, "removed": remove}));
+    Ok(())
+}
+
+fn list(id: &str, json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let state = storage::load_state()?;
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let links = links_of(&state.features, &state.features[index].id);
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&links)?);
+    } else if links.is_empty() {
+        output::info(&format!("{} has no relations. Add one with 'legend relate {} blocks <id>'.", state.features[index].id, state.features[index].id));
+    } else {
+        for link in &links {
+            println!("{} {} {}", link.from, link.kind.as_str(), link.to);
+        }
+    }
+    Ok(())
+}
+
+/// Add `relation` unless it's already there
+fn link(feature: &mut Feature, relation: Relation, now: i64) -> bool {
+    if feature.relations.contains(&relation) {
+        return false;
+    }
+    feature.relations.push(relation);
+    feature.last_updated = now;
+    true
+}
+
+fn unlink(feature: &mut Feature, relation: &Relation, now: i64) -> bool {
+    let count = feature.relations.len();
+    feature.relations.retain(|r| r != relation);
+    if feature.relations.len() == count {
+        return false;
+    }
+    feature.last_updated = now;
+    true
+}
+
+/// Links from `id`, then links to it from other features
+fn links_of(features: &[Feature], id: &str) -> Vec<Link> {
+    let outgoing = features.iter().filter(|f| f.id == id).flat_map(|f| {
+        f.relations.iter().map(move |r| Link { from: f.id.clone(), kind: r.kind, to: r.target.clone() })
+    });
+    let incoming = features.iter().filter(|f| f.id != id).flat_map(|f| {
+        f.relations
+            .iter()
+            .filter(|r| r.target == id)
+            .map(move |r| Link { from: f.id.clone(), kind: r.kind, to: r.target.clone() })
+    });
+    outgoing.chain(incoming).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    #[test]
+    fn test_relate_both_directions() {
+        let _env = TestEnv::initialized(1_000);
+        let mut state = storage::load_state().unwrap();
+        for id in ["auth", "billing", "checkout"] {
+            state.features.push(Feature::new(id.to_string(), id.to_string(), "d".to_string(), "d".to_string()));
+        }
+        storage::save_state(&state).unwrap();
+
+        handle_relate(&["auth", "blocks", "billing"].map(String::from)).unwrap();
+        handle_relate(&["checkout", "relates-to", "auth"].map(String::from)).unwrap();
+        handle_relate(&["auth", "blocks", "billing"].map(String::from)).unwrap();
+        assert!(handle_relate(&["auth", "follows", "billing"].map(String::from)).is_err());
+        assert!(handle_relate(&["auth", "blocks", "auth"].map(String::from)).is_err());
+
+        let state = storage::load_state().unwrap();
+        assert_eq!(state.features[0].relations.len(), 1);
+        let links: Vec<String> = links_of(&state.features, "auth")
+            .iter()
+            .map(|l| format!("{} {} {}", l.from, l.kind.as_str(), l.to))
+            .collect();
+        assert_eq!(links, vec!["auth blocks billing", "checkout relates_to auth"]);
+
+        handle_relate(&["auth", "blocks", "billing", "--remove"].map(String::from)).unwrap();
+        assert!(storage::load_state().unwrap().features[0].relations.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+// Release notes command - list completed features for CHANGELOG.md
+//
+// Usage:
+//   legend release-notes --since v0.2.0             - since a git tag
+//   legend release-notes --since 2024-03-01         - since a date
+//   legend release-notes --since v0.2.0 --until v0.3.0
+//
+// A feature counts as "completed in the window" when it is Complete and
+// its completed_at falls inside [since, until). Features completed before
+// Legend recorded completed_at fall back to their last update.
+//
+// Rust concepts in this file:
+// - BTreeMap for output grouped and sorted by key (domain)
+// - Option combinators (unwrap_or, map)
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::git;
+use crate::output;
+use crate::storage;
+use crate::text;
+use crate::types::{current_timestamp, Feature};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Maximum length of the one-line description in a release note entry
+const MAX_SUMMARY_LEN: usize = 100;
+
+/// Parsed release-notes arguments
+struct ReleaseWindow {
+    since: i64,
+    until: Option<i64>,
+}
+
+/// Handle the release-notes command
+///
+/// Output: Markdown to stdout (ready to paste into CHANGELOG.md)
+pub fn handle_release_notes(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let window = parse_args(args)?;
+    let state = storage::load_state()?;
+
+    let completed = completed_in_window(&state.features, &window);
+    let release_date = dates::format_date(window.until.unwrap_or_else(current_timestamp));
+    let markdown = render_release_notes(&completed, release_date);
+
+    println!("{}", markdown);
+    output::diag(
+        output::Level::Info,
+        &format!("{} feature(s) completed in the window", completed.len()),
+        json!({"count": completed.len()}),
+    );
+
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> Result<ReleaseWindow, Box<dyn std::error::Error>> {
+    let mut since: Option<i64> = None;
+    let mut until: Option<i64> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--since" => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, "--since requires a tag or date"))?;
+                since = Some(resolve_point_in_time(value)?);
+            }
+            "--until" => {
+                i += 1;
+                let value = args
+                    .get(i)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, "--until requires a tag or date"))?;
+                until = Some(resolve_point_in_time(value)?);
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}", other)).into());
+            }
+        }
+        i += 1;
+    }
+
+    let since = since.ok_or_else(|| {
+        ExitError::new(exit::USAGE, "Usage: legend release-notes --since <tag|date> [--until <tag|date>]")
+    })?;
+
+    Ok(ReleaseWindow { since, until })
+}
+
+/// Turn "v1.2.0" or "2024-03-01" into a Unix timestamp
+///
+/// Dates are tried first; anything else must be a git ref
+fn resolve_point_in_time(value: &str) -> Result<i64, Box<dyn std::error::Error>> {
+    if let Ok(ts) = dates::parse_date(value) {
+        return Ok(ts);
+    }
+
+    if git::ref_exists(value) {
+        return git::commit_timestamp(value);
+    }
+
+    Err(format!("'{}' is neither a date (YYYY-MM-DD) nor a git ref", value).into())
+}
+
+/// Complete features whose completion falls inside the window
+fn completed_in_window<'a>(features: &'a [Feature], window: &ReleaseWindow) -> Vec<&'a Feature> {
+    features
+        .iter()
+        .filter(|f| f.is_complete())
+        .filter(|f| {
+            let completed = f.completed_at.unwrap_or(f.last_updated);
+            completed >= window.since && window.until.map(|until| completed < until).unwrap_or(true)
+        })
+        .collect()
+}
+
+/// Render completed features grouped by domain
+fn render_release_notes(features: &[&Feature], date: String) -> String {
+    let mut by_domain: BTreeMap<&str, Vec<&Feature>> = BTreeMap::new();
+    for feature in features {
+        by_domain.entry(feature.domain.as_str()).or_default().push(feature);
+    }
+
+    let mut out = format!("## [Unreleased] - {}\n", date);
+
+    if by_domain.is_empty() {
+        out.push_str("\nNo features completed in this window.\n");
+        return out;
+    }
+
+    let mut by_domain: Vec<(&str, Vec<&Feature>)> = by_domain.into_iter().collect();
+    by_domain.sort_by(|a, b| text::collate(a.0, b.0));
+    for (domain, mut domain_features) in by_domain {
+        domain_features.sort_by(|a, b| text::collate(&a.name, &b.name));
+
+        out.push_str(&format!("\n### {}\n\n", domain));
+        for feature in domain_features {
+            out.push_str(&format!(
+                "- **{}**: {}\n",
+                feature.name,
+                one_line_summary(&feature.description)
+            ));
+        }
+    }
+
+    out
+}
+
+/// First line of a description, shortened to MAX_SUMMARY_LEN columns
+fn one_line_summary(description: &str) -> String {
+    let first_line = description.lines().next().unwrap_or("").trim();
+
+    if text::width(first_line) <= MAX_SUMMARY_LEN {
+        first_line.to_string()
+    } else {
+        let cut = text::truncate(first_line, MAX_SUMMARY_LEN - 3, "");
+        format!("{}...", cut.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeatureStatus;
+
+    fn make_feature(id: &str, domain: &str, status: FeatureStatus, last_updated: i64) -> Feature {
+        let mut f = Feature::new(
+            id.to_string(),
+            id.to_uppercase(),
+            domain.to_string(),
+            format!("{} description\nSecond line", id),
+        );
+        f.status = status;
+        f.last_updated = last_updated;
+        f
+    }
+
+    #[test]
+    fn test_completed_in_window() {
+        let features = vec![
+            make_feature("old", "api", FeatureStatus::Complete, 100),
+            make_feature("new", "api", FeatureStatus::Complete, 500),
+            make_feature("wip", "api", FeatureStatus::InProgress, 500),
+            make_feature("later", "api", FeatureStatus::Complete, 900),
+        ];
+        let window = ReleaseWindow { since: 200, until: Some(800) };
+
+        let completed = completed_in_window(&features, &window);
+
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, "new");
+    }
+
+    #[test]
+    fn test_completed_at_wins_over_last_updated() {
+        // Completed inside the window, edited (e.g. a typo fix) after it
+        let mut edited = make_feature("edited", "api", FeatureStatus::Complete, 900);
+        edited.completed_at = Some(300);
+        let window = ReleaseWindow { since: 200, until: Some(800) };
+
+        assert_eq!(completed_in_window(&[edited], &window).len(), 1);
+    }
+
+    #[test]
+    fn test_render_groups_by_domain() {
+        let a = make_feature("auth", "security", FeatureStatus::Complete, 0);
+        let b = make_feature("routes", "api", FeatureStatus::Complete, 0);
+
+        let md = render_release_notes(&[&a, &b], "2024-03-01".to_string());
+
+        assert!(md.starts_with("## [Unreleased] - 2024-03-01"));
+        let api_pos = md.find("### api").unwrap();
+        let security_pos = md.find("### security").unwrap();
+        assert!(api_pos < security_pos);
+        assert!(md.contains("- **AUTH**: auth description\n"));
+        assert!(!md.contains("Second line"));
+    }
+
+    #[test]
+    fn test_one_line_summary_truncates() {
+        let long = "x".repeat(150);
+        let summary = one_line_summary(&long);
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_LEN);
+        assert!(summary.ends_with("..."));
+    }
+}
@@ -0,0 +1,151 @@
+// Replay command - rebuild state from the event journal
+//
+// Usage:
+//   legend replay                   - replay every event and verify against state.lz4
+//   legend replay --seq <n>         - print the state as of event n (JSON)
+//   legend replay --until <date>    - print the state as of a date or timestamp (JSON)
+//
+// A full replay that doesn't match the snapshot means a write skipped the
+// journal (or one of the files was edited by hand). That exits 3 so it can
+// run in CI like the other checks.
+
+use crate::dates;
+use crate::diff;
+use crate::exit::{self, ExitError};
+use crate::journal::{self, ReplayLimit, Sections};
+use crate::output;
+use crate::storage;
+use serde_json::json;
+
+/// Handle the replay command
+pub fn handle_replay(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let limit = parse_args(args)?;
+
+    let snapshot = storage::load_state()?;
+    let events = journal::read_events()?;
+    let applied = journal::events_until(&events, limit);
+
+    if limit != ReplayLimit::All {
+        let state = journal::state_from_events(&snapshot, applied);
+        println!("{}", serde_json::to_string_pretty(&state)?);
+        output::diag(
+            output::Level::Info,
+            &format!(
+                "Replayed {} of {} events ({} features)",
+                applied.len(),
+                events.len(),
+                state.features.len()
+            ),
+            json!({"applied": applied.len(), "events": events.len(), "features": state.features.len()}),
+        );
+        return Ok(());
+    }
+
+    if events.is_empty() {
+        output::info("Journal is empty - nothing to replay yet.\n  History starts with the next 'legend update'.");
+        return Ok(());
+    }
+
+    let replayed = journal::state_from_events(&snapshot, applied);
+    let mismatch = diff::diff_states(&replayed, &snapshot);
+    let sections = Sections::changed(&replayed, &snapshot);
+
+    if mismatch.is_empty() && sections.is_none() {
+        println!(
+            "✓ Replayed {} events: {} features, matches state.lz4",
+            events.len(),
+            replayed.features.len()
+        );
+        return Ok(());
+    }
+
+    output::info("Replayed state does not match state.lz4:");
+    for feature in &mismatch.added {
+        output::diag(
+            output::Level::Warning,
+            &format!("{} is in the snapshot but not in the journal", feature.id),
+            json!({"id": feature.id}),
+        );
+    }
+    for feature in &mismatch.removed {
+        output::diag(
+            output::Level::Warning,
+            &format!("{} is in the journal but not in the snapshot", feature.id),
+            json!({"id": feature.id}),
+        );
+    }
+    for changed in &mismatch.changed {
+        let fields: Vec<&str> = changed.changes.iter().map(|c| c.field.as_str()).collect();
+        output::diag(
+            output::Level::Warning,
+            &format!("{} differs in: {}", changed.id, fields.join(", ")),
+            json!({"id": changed.id, "fields": fields}),
+        );
+    }
+    if let Some(sections) = sections {
+        let names = sections.names();
+        output::diag(
+            output::Level::Warning,
+            &format!("The snapshot's {} differ from the journal's", names.join(", ")),
+            json!({"sections": names}),
+        );
+    }
+
+    Err(ExitError::new(exit::CHECK_FAILED, "journal replay does not match the snapshot").into())
+}
+
+/// Parse --seq / --until into a replay limit
+fn parse_args(args: &[String]) -> Result<ReplayLimit, Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: legend replay [--seq <n> | --until <YYYY-MM-DD|timestamp>]";
+
+    let mut limit = ReplayLimit::All;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--seq" => {
+                i += 1;
+                let value = args.get(i).ok_or("--seq requires an event number")?;
+                let seq = value
+                    .parse()
+                    .map_err(|_| format!("Invalid event number '{}'", value))?;
+                limit = ReplayLimit::Seq(seq);
+            }
+            "--until" => {
+                i += 1;
+                let value = args.get(i).ok_or("--until requires a date")?;
+                limit = ReplayLimit::Timestamp(dates::parse_date(value)?);
+            }
+            other => {
+                return Err(ExitError::new(
+                    exit::USAGE,
+                    format!("Unknown argument: {}\n{}", other, USAGE),
+                )
+                .into());
+            }
+        }
+        i += 1;
+    }
+
+    Ok(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&[]).unwrap(), ReplayLimit::All);
+        assert_eq!(parse_args(&args(&["--seq", "7"])).unwrap(), ReplayLimit::Seq(7));
+        assert_eq!(
+            parse_args(&args(&["--until", "2024-03-01"])).unwrap(),
+            ReplayLimit::Timestamp(1709251200)
+        );
+        assert!(parse_args(&args(&["--seq", "x"])).is_err());
+    }
+}
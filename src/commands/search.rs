@@ -11,8 +11,16 @@
 // - Collecting filtered results into a Vec
 // - Command-line argument handling
 
-use crate::storage;
-use crate::types::Feature;
+use super::decide;
+use crate::access;
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::output;
+use crate::text;
+use crate::types::{Decision, Feature};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend search <query> [--domain <d>] [--tag <t>] [--status <s>] [--meta <k=v>] [--source <s>] [--owner <o>] [--at <date>]\n       legend search --decisions [<keyword>]";
 
 /// Handle the search command
 ///
@@ -21,6 +29,12 @@ use crate::types::Feature;
 ///   legend search --domain <domain>   - filter by domain
 ///   legend search --tag <tag>         - filter by tag
 ///   legend search --status <status>   - filter by status
+///   legend search auth --at 2024-03-01 - search the state as of a date
+///   legend search api/rate            - domain prefix + keyword
+///   legend search --meta tier=1       - filter by a metadata value
+///   legend search --source human      - last updated by that source
+///   legend search --owner alice       - assigned to alice
+///   legend search --decisions sqlite  - decisions (legend decide), not features
 ///
 /// Flags can be combined:
 ///   legend search auth --domain security --status Pending
@@ -28,14 +42,18 @@ use crate::types::Feature;
 /// Output: JSON array of matching features (for Claude)
 pub fn handle_search(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     if args.is_empty() {
-        return Err("Usage: legend search <query> [--domain <d>] [--tag <t>] [--status <s>]".into());
+        return Err(ExitError::new(exit::USAGE, USAGE).into());
     }
 
     // Parse arguments into a SearchQuery
-    let query = parse_args(args)?;
+    let (at, args) = journal::take_at_flag(args)?;
+    if args.iter().any(|a| a == "--decisions") {
+        return search_decisions(&args, at);
+    }
+    let query = parse_args(&args)?;
 
-    // Load state
-    let state = storage::load_state()?;
+    // Load state (reconstructed from the journal when --at is given)
+    let state = journal::load_state_at(at)?;
 
     // Filter features based on query
     // This uses iterator chaining - each .filter() narrows the results
@@ -47,22 +65,57 @@ pub fn handle_search(args: &[String]) -> Result<(), Box<dyn std::error::Error>>
 
     if results.is_empty() {
         println!("[]");
-        eprintln!("No features matched the search.");
+        output::diag(output::Level::Info, "No features matched the search.", json!({"count": 0}));
         return Ok(());
     }
 
+    // Historical searches say nothing about what's hot now
+    if at.is_none() {
+        access::record_access(results.iter().map(|f| f.id.as_str()));
+    }
+
     // Output as JSON for Claude to consume
     let json = serde_json::to_string_pretty(&results)
         .map_err(|e| format!("Failed to serialize results: {}", e))?;
 
     println!("{}", json);
-    eprintln!("Found {} matching feature(s).", results.len());
+    output::diag(
+        output::Level::Info,
+        &format!("Found {} matching feature(s).", results.len()),
+        json!({"count": results.len()}),
+    );
+
+    Ok(())
+}
+
+/// `legend search --decisions [<keyword>]`: matching decisions as JSON
+///
+/// Feature filters don't apply to decisions, so only a keyword is allowed
+fn search_decisions(args: &[String], at: Option<i64>) -> Result<(), Box<dyn std::error::Error>> {
+    let words: Vec<&str> = args.iter().map(String::as_str).filter(|a| *a != "--decisions").collect();
+    if let Some(flag) = words.iter().find(|w| w.starts_with("--")) {
+        return Err(ExitError::new(exit::USAGE, format!("{} filters features; --decisions only takes a keyword", flag)).into());
+    }
+    let keyword = words.join(" ");
 
+    let state = journal::load_state_at(at)?;
+    let results: Vec<&Decision> = state
+        .decisions
+        .iter()
+        .filter(|d| decide::matches_decision(d, &keyword))
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&results)?);
+    output::diag(
+        output::Level::Info,
+        &format!("Found {} matching decision(s).", results.len()),
+        json!({"count": results.len()}),
+    );
     Ok(())
 }
 
 /// Parsed search query with optional filters
-struct SearchQuery {
+pub struct SearchQuery {
     /// Free-text keyword to match against id, name, description, context
     keyword: Option<String>,
     /// Filter by domain
@@ -71,6 +124,12 @@ struct SearchQuery {
     tag: Option<String>,
     /// Filter by status (as string, matched case-insensitively)
     status: Option<String>,
+    /// Metadata filters: "key=value" or just "key" (all must match)
+    meta: Vec<String>,
+    /// Filter by last_updated_by (the update's `source`)
+    source: Option<String>,
+    /// Filter by owner
+    owner: Option<String>,
 }
 
 /// Parse command-line args into a SearchQuery
@@ -81,6 +140,9 @@ fn parse_args(args: &[String]) -> Result<SearchQuery, Box<dyn std::error::Error>
     let mut domain: Option<String> = None;
     let mut tag: Option<String> = None;
     let mut status: Option<String> = None;
+    let mut meta: Vec<String> = Vec::new();
+    let mut source: Option<String> = None;
+    let mut owner: Option<String> = None;
 
     // Walk through args, consuming flags and their values
     let mut i = 0;
@@ -90,7 +152,7 @@ fn parse_args(args: &[String]) -> Result<SearchQuery, Box<dyn std::error::Error>
                 i += 1;
                 domain = Some(
                     args.get(i)
-                        .ok_or("--domain requires a value")?
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--domain requires a value"))?
                         .clone(),
                 );
             }
@@ -98,7 +160,7 @@ fn parse_args(args: &[String]) -> Result<SearchQuery, Box<dyn std::error::Error>
                 i += 1;
                 tag = Some(
                     args.get(i)
-                        .ok_or("--tag requires a value")?
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--tag requires a value"))?
                         .clone(),
                 );
             }
@@ -106,10 +168,37 @@ fn parse_args(args: &[String]) -> Result<SearchQuery, Box<dyn std::error::Error>
                 i += 1;
                 status = Some(
                     args.get(i)
-                        .ok_or("--status requires a value")?
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--status requires a value"))?
+                        .clone(),
+                );
+            }
+            "--meta" => {
+                i += 1;
+                meta.push(
+                    args.get(i)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--meta requires key=value (or just key)"))?
+                        .clone(),
+                );
+            }
+            "--source" => {
+                i += 1;
+                source = Some(
+                    args.get(i)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--source requires a value"))?
+                        .clone(),
+                );
+            }
+            "--owner" => {
+                i += 1;
+                owner = Some(
+                    args.get(i)
+                        .ok_or_else(|| ExitError::new(exit::USAGE, "--owner requires a value"))?
                         .clone(),
                 );
             }
+            flag if flag.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", flag, USAGE)).into());
+            }
             other => {
                 // Not a flag - treat as keyword
                 // If multiple non-flag words, join them
@@ -129,30 +218,65 @@ fn parse_args(args: &[String]) -> Result<SearchQuery, Box<dyn std::error::Error>
         domain,
         tag,
         status,
+        meta,
+        source,
+        owner,
     })
 }
 
+/// Parse a filter expression (used by export/import --filter)
+///
+/// Same filters as the search flags, written as field:value words:
+///   "domain:api status:Pending"  ==  --domain api --status Pending
+///   "meta:tier=1"                ==  --meta tier=1
+///   "source:human"               ==  --source human
+///   "owner:alice"                ==  --owner alice
+/// Words without a known field prefix are keywords, as in `legend search`
+pub fn parse_filter(expression: &str) -> Result<SearchQuery, Box<dyn std::error::Error>> {
+    let mut args = Vec::new();
+    for word in expression.split_whitespace() {
+        match word.split_once(':') {
+            Some((field @ ("domain" | "tag" | "status" | "meta" | "source" | "owner"), value)) if !value.is_empty() => {
+                args.push(format!("--{}", field));
+                args.push(value.to_string());
+            }
+            _ => args.push(word.to_string()),
+        }
+    }
+    parse_args(&args)
+}
+
 /// Check if a feature matches the search query
 ///
 /// All provided filters must match (AND logic)
-/// Keyword search is case-insensitive across multiple fields
-fn matches_query(feature: &Feature, query: &SearchQuery) -> bool {
+/// Keyword search ignores case and diacritics ("cafe" finds "Café"; see
+/// text::fold) across multiple fields
+pub fn matches_query(feature: &Feature, query: &SearchQuery) -> bool {
     // Check keyword (if provided) - search across multiple fields
     if let Some(ref kw) = query.keyword {
-        let kw_lower = kw.to_lowercase();
-        let matches_keyword = feature.id.to_lowercase().contains(&kw_lower)
-            || feature.name.to_lowercase().contains(&kw_lower)
-            || feature.domain.to_lowercase().contains(&kw_lower)
-            || feature.description.to_lowercase().contains(&kw_lower)
-            || feature
-                .context
-                .as_ref()
-                .map(|c| c.to_lowercase().contains(&kw_lower))
-                .unwrap_or(false)
-            || feature
-                .tags
-                .iter()
-                .any(|t| t.to_lowercase().contains(&kw_lower));
+        let kw_lower = text::fold(kw);
+        let contains = |term: &str| {
+            text::fold(&feature.id).contains(term)
+                || text::fold(&feature.name).contains(term)
+                || text::fold(&feature.domain).contains(term)
+                || text::fold(&feature.description).contains(term)
+                || feature
+                    .context
+                    .as_ref()
+                    .map(|c| text::fold(c).contains(term))
+                    .unwrap_or(false)
+                || feature
+                    .tags
+                    .iter()
+                    .any(|t| text::fold(t).contains(term))
+        };
+
+        // "api/" or "api/rate" - a namespaced ID prefix (see ids.rs):
+        // the part before the slash is the domain, the rest a keyword
+        let matches_prefix = kw_lower.split_once('/').is_some_and(|(domain, rest)| {
+            text::fold(&feature.domain) == domain && (rest.is_empty() || contains(rest))
+        });
+        let matches_keyword = contains(&kw_lower) || matches_prefix;
 
         if !matches_keyword {
             return false;
@@ -161,7 +285,7 @@ fn matches_query(feature: &Feature, query: &SearchQuery) -> bool {
 
     // Check domain filter
     if let Some(ref d) = query.domain {
-        if feature.domain.to_lowercase() != d.to_lowercase() {
+        if text::fold(&feature.domain) != text::fold(d) {
             return false;
         }
     }
@@ -176,8 +300,26 @@ fn matches_query(feature: &Feature, query: &SearchQuery) -> bool {
 
     // Check status filter
     if let Some(ref s) = query.status {
-        let status_str = format!("{:?}", feature.status); // Debug format gives variant name
-        if status_str.to_lowercase() != s.to_lowercase() {
+        if !feature.status.name().eq_ignore_ascii_case(s) {
+            return false;
+        }
+    }
+
+    // Check metadata filters
+    if !query.meta.iter().all(|m| matches_meta(feature, m)) {
+        return false;
+    }
+
+    // Check source filter (features never tagged match no source)
+    if let Some(ref s) = query.source {
+        if !feature.last_updated_by.as_ref().is_some_and(|by| by.eq_ignore_ascii_case(s)) {
+            return false;
+        }
+    }
+
+    // Check owner filter (unassigned features match no owner)
+    if let Some(ref o) = query.owner {
+        if !feature.owner.as_ref().is_some_and(|owner| owner.eq_ignore_ascii_case(o)) {
             return false;
         }
     }
@@ -185,6 +327,26 @@ fn matches_query(feature: &Feature, query: &SearchQuery) -> bool {
     true
 }
 
+/// Does the feature's metadata satisfy "key=value" (or have "key")?
+///
+/// Strings compare case-insensitively like the other filters; other values
+/// compare as JSON, so tier=1 and beta=true work
+fn matches_meta(feature: &Feature, filter: &str) -> bool {
+    let (key, expected) = match filter.split_once('=') {
+        Some((key, value)) => (key, Some(value)),
+        None => (filter, None),
+    };
+
+    match (feature.metadata.get(key), expected) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some(serde_json::Value::String(actual)), Some(expected)) => actual.eq_ignore_ascii_case(expected),
+        (Some(actual), Some(expected)) => {
+            serde_json::from_str::<serde_json::Value>(expected).is_ok_and(|v| &v == actual)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +371,9 @@ mod tests {
             domain: None,
             tag: None,
             status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(matches_query(&f, &q));
     }
@@ -221,6 +386,9 @@ mod tests {
             domain: None,
             tag: None,
             status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(matches_query(&f, &q));
     }
@@ -233,6 +401,9 @@ mod tests {
             domain: None,
             tag: None,
             status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(!matches_query(&f, &q));
     }
@@ -245,6 +416,9 @@ mod tests {
             domain: Some("security".to_string()),
             tag: None,
             status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(matches_query(&f, &q));
     }
@@ -259,6 +433,9 @@ mod tests {
             domain: Some("security".to_string()),
             tag: None,
             status: Some("InProgress".to_string()),
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(matches_query(&f, &q));
     }
@@ -271,10 +448,89 @@ mod tests {
             domain: Some("security".to_string()),
             tag: None,
             status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(matches_query(&f, &q));
     }
 
+    #[test]
+    fn test_diacritic_insensitive() {
+        let f = make_feature("cafe-menu", "Café Menü", "Boutique", "Prix à la carte");
+        let q = |kw: &str| SearchQuery {
+            keyword: Some(kw.to_string()),
+            domain: None,
+            tag: None,
+            status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
+        };
+        assert!(matches_query(&f, &q("cafe menu")));
+        assert!(matches_query(&f, &q("CAFÉ")));
+        assert!(matches_query(&f, &q("a la carte")));
+        assert!(!matches_query(&f, &q("cafes")));
+    }
+
+    #[test]
+    fn test_domain_prefix_keyword() {
+        let f = make_feature("rate-limit", "Rate limiting", "api", "Token bucket");
+
+        assert!(matches_query(&f, &parse_filter("api/").unwrap()));
+        assert!(matches_query(&f, &parse_filter("api/bucket").unwrap()));
+        assert!(!matches_query(&f, &parse_filter("auth/bucket").unwrap()));
+    }
+
+    #[test]
+    fn test_meta_filter() {
+        let mut f = make_feature("billing", "Billing", "payments", "Invoices");
+        f.metadata.insert("tier".to_string(), serde_json::json!(1));
+        f.metadata.insert("cost_center".to_string(), serde_json::json!("Platform"));
+
+        let query = |args: &[&str]| parse_args(&args.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+        assert!(matches_query(&f, &query(&["--meta", "tier=1"])));
+        assert!(matches_query(&f, &query(&["--meta", "cost_center=platform", "--meta", "tier"])));
+        assert!(!matches_query(&f, &query(&["--meta", "tier=2"])));
+        assert!(!matches_query(&f, &query(&["--meta", "owner"])));
+        assert!(matches_query(&f, &parse_filter("meta:tier=1").unwrap()));
+    }
+
+    #[test]
+    fn test_source_filter() {
+        let mut f = make_feature("billing", "Billing", "payments", "Invoices");
+        let query = |args: &[&str]| parse_args(&args.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+        assert!(!matches_query(&f, &query(&["--source", "human"])));
+
+        f.last_updated_by = Some("Human".to_string());
+        assert!(matches_query(&f, &query(&["--source", "human"])));
+        assert!(!matches_query(&f, &query(&["--source", "aider"])));
+        assert!(matches_query(&f, &parse_filter("source:human").unwrap()));
+    }
+
+    #[test]
+    fn test_owner_filter() {
+        let mut f = make_feature("billing", "Billing", "payments", "Invoices");
+        let query = |args: &[&str]| parse_args(&args.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+        assert!(!matches_query(&f, &query(&["--owner", "alice"])));
+
+        f.owner = Some("Alice".to_string());
+        assert!(matches_query(&f, &query(&["--owner", "alice"])));
+        assert!(!matches_query(&f, &query(&["--owner", "bob"])));
+        assert!(matches_query(&f, &parse_filter("owner:alice status:pending").unwrap()));
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let mut f = make_feature("rate-limit", "Rate limiting", "api", "Token bucket");
+        f.status = FeatureStatus::Pending;
+
+        assert!(matches_query(&f, &parse_filter("domain:api status:pending").unwrap()));
+        assert!(matches_query(&f, &parse_filter("bucket domain:API").unwrap()));
+        assert!(!matches_query(&f, &parse_filter("status:Complete").unwrap()));
+        assert!(!matches_query(&f, &parse_filter("domain:auth").unwrap()));
+    }
+
     #[test]
     fn test_tag_filter() {
         let f = make_feature("feat1", "Feature", "cli", "Something");
@@ -283,6 +539,9 @@ mod tests {
             domain: None,
             tag: Some("backend".to_string()),
             status: None,
+            meta: Vec::new(),
+            source: None,
+            owner: None,
         };
         assert!(matches_query(&f, &q));
     }
@@ -0,0 +1,405 @@
+// Service command - keep `legend focus watch` running as a user service
+//
+// Usage:
+//   legend service install --watch [--interval <seconds>]
+//   legend service status [--json]
+//   legend service uninstall
+//
+// install writes a unit for the platform's service manager and starts it:
+// - Linux: ~/.config/systemd/user/legend-<project>.service, enabled with
+//   `systemctl --user enable --now`. Restart=on-failure, and `legend diag
+//   health` runs first, so a project whose state can't load isn't started
+//   over and over.
+// - macOS: ~/Library/LaunchAgents/dev.legend.<project>.plist, loaded with
+//   `launchctl load -w` (RunAtLoad, KeepAlive).
+// Both run the absolute path of this binary (std::env::current_exe) in
+// the project root with --dir set to its Legend directory, so they don't
+// depend on PATH or on where the service manager starts them. Nothing
+// needs root.
+//
+// --serve is refused: Legend has no serve mode yet (see PLAN.md), and a
+// unit for one would only fail on start.
+//
+// If the service manager can't be reached (no user session bus in a
+// container, say), the file is still written and the command that starts
+// it is printed.
+
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const USAGE: &str = "Usage: legend service install --watch [--interval <seconds>]\n       legend service status [--json]\n       legend service uninstall";
+
+/// Which service manager the units are for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Manager {
+    Systemd,
+    Launchd,
+}
+
+/// Where this project's service lives and what it is called
+#[derive(Debug)]
+struct Service {
+    manager: Manager,
+    /// systemd unit name or launchd label
+    name: String,
+    path: PathBuf,
+}
+
+/// Handle the service command
+pub fn handle_service(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (command, rest) = args.split_first().ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    match (command.as_str(), rest) {
+        ("install", rest) => install(parse_install_args(rest)?),
+        ("status", []) => status(false),
+        ("status", [flag]) if flag == "--json" => status(true),
+        ("uninstall", []) => uninstall(),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+/// The --interval for `focus watch`, if given; --watch is required
+fn parse_install_args(args: &[String]) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    let mut watch = false;
+    let mut interval = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--watch" => watch = true,
+            "--serve" => {
+                return Err(ExitError::new(
+                    exit::USAGE,
+                    "Legend has no serve mode yet; only --watch (legend focus watch) can run as a service",
+                )
+                .into())
+            }
+            "--interval" => {
+                i += 1;
+                let seconds = args.get(i).and_then(|v| v.parse().ok()).filter(|n: &u64| *n > 0);
+                interval = Some(seconds.ok_or_else(|| {
+                    ExitError::new(exit::USAGE, format!("--interval expects a positive number of seconds\n{}", USAGE))
+                })?);
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+
+    if !watch {
+        return Err(ExitError::new(exit::USAGE, USAGE).into());
+    }
+    Ok(interval)
+}
+
+fn install(interval: Option<u64>) -> Result<(), Box<dyn std::error::Error>> {
+    if !storage::is_initialized() {
+        return Err(ExitError::new(exit::NOT_INITIALIZED, "Legend is not initialized here. Run 'legend init' first.").into());
+    }
+    let root = fs::canonicalize(storage::project_root())?;
+    let legend_dir = fs::canonicalize(storage::legend_dir())?;
+    let exe = std::env::current_exe().map_err(|e| format!("Can't find the legend binary: {}", e))?;
+    let service = this_service(&root)?;
+
+    let mut program = vec![exe.display().to_string(), "--dir".to_string(), legend_dir.display().to_string()];
+    let watch = |program: &mut Vec<String>| {
+        program.extend(["focus", "watch"].map(String::from));
+        if let Some(seconds) = interval {
+            program.extend(["--interval".to_string(), seconds.to_string()]);
+        }
+    };
+    let contents = match service.manager {
+        Manager::Systemd => {
+            let mut health = program.clone();
+            health.extend(["diag", "health"].map(String::from));
+            watch(&mut program);
+            render_systemd_unit(&root, &health, &program)
+        }
+        Manager::Launchd => {
+            watch(&mut program);
+            render_launchd_plist(&service.name, &root, &program)
+        }
+    };
+
+    if let Some(parent) = service.path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    storage::write_atomic(&service.path, contents.as_bytes())?;
+    println!("✓ Wrote {}", service.path.display());
+
+    let path = service.path.display().to_string();
+    let start: Vec<Vec<&str>> = match service.manager {
+        Manager::Systemd => vec![
+            vec!["systemctl", "--user", "daemon-reload"],
+            vec!["systemctl", "--user", "enable", "--now", &service.name],
+        ],
+        Manager::Launchd => vec![vec!["launchctl", "load", "-w", &path]],
+    };
+    match run_all(&start) {
+        Ok(()) => println!("✓ Started {} (legend service status to check on it)", service.name),
+        Err(e) => output::warning(&format!(
+            "Couldn't start {} ({}); start it with: {}",
+            service.name,
+            e,
+            start.last().map(|c| c.join(" ")).unwrap_or_default()
+        )),
+    }
+    Ok(())
+}
+
+fn status(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let service = this_service(&fs::canonicalize(storage::project_root())?)?;
+    let installed = service.path.exists();
+    let running = installed && is_running(&service);
+
+    if json_out {
+        let report = json!({
+            "name": service.name,
+            "path": service.path,
+            "installed": installed,
+            "running": running,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !installed {
+        println!("{}: not installed (legend service install --watch)", service.name);
+    } else {
+        println!("{}: installed, {}", service.name, if running { "running" } else { "not running" });
+        println!("  {}", service.path.display());
+    }
+    Ok(())
+}
+
+fn uninstall() -> Result<(), Box<dyn std::error::Error>> {
+    let service = this_service(&fs::canonicalize(storage::project_root())?)?;
+    if !service.path.exists() {
+        println!("Nothing to uninstall: {} doesn't exist", service.path.display());
+        return Ok(());
+    }
+
+    let path = service.path.display().to_string();
+    let stop: Vec<Vec<&str>> = match service.manager {
+        Manager::Systemd => vec![vec!["systemctl", "--user", "disable", "--now", &service.name]],
+        Manager::Launchd => vec![vec!["launchctl", "unload", "-w", &path]],
+    };
+    if let Err(e) = run_all(&stop) {
+        output::warning(&format!("Couldn't stop {} ({}); removing its file anyway", service.name, e));
+    }
+    fs::remove_file(&service.path).map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+    if service.manager == Manager::Systemd {
+        let _ = run_all(&[vec!["systemctl", "--user", "daemon-reload"]]);
+    }
+    println!("✓ Removed {}", service.name);
+    Ok(())
+}
+
+/// The service for the project at `root` on this platform
+fn this_service(root: &Path) -> Result<Service, Box<dyn std::error::Error>> {
+    let manager = if cfg!(target_os = "macos") {
+        Manager::Launchd
+    } else if cfg!(target_os = "linux") {
+        Manager::Systemd
+    } else {
+        return Err("legend service supports systemd (Linux) and launchd (macOS)".into());
+    };
+    let home = std::env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from);
+    let config_home = std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    service_for(manager, root, home, config_home)
+}
+
+fn service_for(
+    manager: Manager,
+    root: &Path,
+    home: Option<PathBuf>,
+    config_home: Option<PathBuf>,
+) -> Result<Service, Box<dyn std::error::Error>> {
+    let project = slug(&root.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default());
+    let no_home = || "No home directory: set HOME to install a service";
+    Ok(match manager {
+        Manager::Systemd => {
+            let name = format!("legend-{}.service", project);
+            let base = match config_home {
+                Some(dir) => dir,
+                None => home.ok_or_else(no_home)?.join(".config"),
+            };
+            Service { manager, path: base.join("systemd/user").join(&name), name }
+        }
+        Manager::Launchd => {
+            let name = format!("dev.legend.{}", project);
+            let path = home.ok_or_else(no_home)?.join("Library/LaunchAgents").join(format!("{}.plist", name));
+            Service { manager, name, path }
+        }
+    })
+}
+
+/// Lowercase letters, digits, and dashes: safe in unit names and labels
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    match slug.trim_end_matches('-') {
+        "" => "project".to_string(),
+        slug => slug.to_string(),
+    }
+}
+
+/// A systemd user unit running `program` in `root`, after `health` passes
+fn render_systemd_unit(root: &Path, health: &[String], program: &[String]) -> String {
+    let command = |args: &[String]| args.iter().map(|a| systemd_quote(a)).collect::<Vec<_>>().join(" ");
+    format!(
+        "[Unit]\n\
+         Description=Legend focus watch for {root}\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         WorkingDirectory={root}\n\
+         ExecStartPre={health}\n\
+         ExecStart={program}\n\
+         Restart=on-failure\n\
+         RestartSec=10\n\
+         # focus watch exits 128+n when SIGINT/SIGTERM stops it\n\
+         SuccessExitStatus=130 143\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        // Paths aren't quoted here, only their specifiers escaped
+        root = root.display().to_string().replace('%', "%%"),
+        health = command(health),
+        program = command(program),
+    )
+}
+
+/// Quote a word for a systemd command line (and escape its specifiers)
+fn systemd_quote(word: &str) -> String {
+    format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\"").replace('%', "%%"))
+}
+
+/// A launchd agent running `program` in `root`, kept alive
+fn render_launchd_plist(label: &str, root: &Path, program: &[String]) -> String {
+    let arguments: String = program
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \x20   <key>Label</key>\n\
+         \x20   <string>{label}</string>\n\
+         \x20   <key>ProgramArguments</key>\n\
+         \x20   <array>\n\
+         {arguments}\
+         \x20   </array>\n\
+         \x20   <key>WorkingDirectory</key>\n\
+         \x20   <string>{root}</string>\n\
+         \x20   <key>RunAtLoad</key>\n\
+         \x20   <true/>\n\
+         \x20   <key>KeepAlive</key>\n\
+         \x20   <true/>\n\
+         </dict>\n\
+         </plist>\n",
+        label = xml_escape(label),
+        root = xml_escape(&root.display().to_string()),
+    )
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Is the service running, as far as its manager says?
+fn is_running(service: &Service) -> bool {
+    let query: &[&str] = match service.manager {
+        Manager::Systemd => &["systemctl", "--user", "is-active", "--quiet", &service.name],
+        Manager::Launchd => &["launchctl", "list", &service.name],
+    };
+    run_all(&[query.to_vec()]).is_ok()
+}
+
+/// Run each command in turn, stopping at the first that fails
+fn run_all(commands: &[Vec<&str>]) -> Result<(), String> {
+    for command in commands {
+        let (program, args) = command.split_first().ok_or("empty command")?;
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| format!("{}: {}", program, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(if stderr.is_empty() { format!("{} failed", command.join(" ")) } else { stderr });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_install_args() {
+        assert_eq!(parse_install_args(&strings(&["--watch"])).unwrap(), None);
+        assert_eq!(parse_install_args(&strings(&["--watch", "--interval", "10"])).unwrap(), Some(10));
+        for bad in [&[][..], &["--serve"], &["--watch", "--interval", "0"], &["--watch", "--bogus"]] {
+            let err = parse_install_args(&strings(bad)).unwrap_err();
+            assert_eq!(exit::code_for(err.as_ref()), exit::USAGE, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_service_for() {
+        let root = Path::new("/work/My App");
+        let home = Some(PathBuf::from("/home/ana"));
+
+        let unit = service_for(Manager::Systemd, root, home.clone(), None).unwrap();
+        assert_eq!(unit.name, "legend-my-app.service");
+        assert_eq!(unit.path, Path::new("/home/ana/.config/systemd/user/legend-my-app.service"));
+        let unit = service_for(Manager::Systemd, root, None, Some(PathBuf::from("/xdg"))).unwrap();
+        assert_eq!(unit.path, Path::new("/xdg/systemd/user/legend-my-app.service"));
+
+        let agent = service_for(Manager::Launchd, root, home, None).unwrap();
+        assert_eq!(agent.name, "dev.legend.my-app");
+        assert_eq!(agent.path, Path::new("/home/ana/Library/LaunchAgents/dev.legend.my-app.plist"));
+        assert!(service_for(Manager::Launchd, root, None, None).is_err());
+    }
+
+    #[test]
+    fn test_render_systemd_unit() {
+        let root = Path::new("/work/my app");
+        let health = strings(&["/bin/legend", "--dir", "/work/my app/.legend", "diag", "health"]);
+        let program = strings(&["/bin/legend", "--dir", "/work/my app/.legend", "focus", "watch"]);
+
+        let unit = render_systemd_unit(root, &health, &program);
+        assert!(unit.contains("WorkingDirectory=/work/my app\n"));
+        assert!(unit.contains("ExecStartPre=\"/bin/legend\" \"--dir\" \"/work/my app/.legend\" \"diag\" \"health\"\n"));
+        assert!(unit.contains("ExecStart=\"/bin/legend\" \"--dir\" \"/work/my app/.legend\" \"focus\" \"watch\"\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert_eq!(systemd_quote("50% \"off\""), "\"50%% \\\"off\\\"\"");
+    }
+
+    #[test]
+    fn test_render_launchd_plist() {
+        let program = strings(&["/bin/legend", "--dir", "/work/R&D/.legend", "focus", "watch"]);
+        let plist = render_launchd_plist("dev.legend.r-d", Path::new("/work/R&D"), &program);
+
+        assert!(plist.contains("    <key>Label</key>\n    <string>dev.legend.r-d</string>\n"));
+        assert!(plist.contains("        <string>/work/R&amp;D/.legend</string>\n"));
+        assert!(plist.contains("    <key>WorkingDirectory</key>\n    <string>/work/R&amp;D</string>\n"));
+        assert!(plist.contains("    <key>KeepAlive</key>\n    <true/>\n"));
+    }
+}
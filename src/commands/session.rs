@@ -0,0 +1,220 @@
+// Session command - remember what each stretch of work was about
+//
+// Usage:
+//   legend session start                  - open a session
+//   legend session end ["<summary>"]      - close it, recording the features touched
+//   legend session list [--json]          - every session, newest first
+//   legend session recap                  - recent summaries (used by the SessionStart hook)
+//
+// A session is a start and end time, the features updated in between, and
+// a line saying what happened. Sessions live in state.lz4, so get_state
+// includes them; the compact SessionStart hook prints `recap` so the next
+// conversation starts where the last one stopped.
+
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, LegendState, Session};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend session start\n       legend session end [\"<summary>\"]\n       legend session list [--json]\n       legend session recap";
+
+/// Sessions `recap` prints
+const RECAP_LIMIT: usize = 3;
+
+/// Handle the session command
+pub fn handle_session(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match (args.first().map(String::as_str), &args[args.len().min(1)..]) {
+        (Some("start"), []) => start(),
+        (Some("end"), []) => end(None),
+        (Some("end"), [summary]) if !summary.starts_with("--") => end(Some(summary)),
+        (Some("list"), []) => list(false),
+        (Some("list"), [flag]) if flag == "--json" => list(true),
+        (Some("recap"), []) => {
+            let state = storage::load_state()?;
+            print!("{}", recap(&state.sessions, RECAP_LIMIT));
+            Ok(())
+        }
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn start() -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    if let Some(open) = state.sessions.iter().find(|s| s.ended_at.is_none()) {
+        return Err(format!("Session {} is still open. End it with 'legend session end \"<summary>\"'.", open.id).into());
+    }
+
+    let id = state.sessions.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+    state.sessions.push(Session {
+        id,
+        started_at: current_timestamp(),
+        ended_at: None,
+        summary: None,
+        features_touched: Vec::new(),
+    });
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(output::Level::Info, &format!("Started session {}.", id), json!({"id": id}));
+    Ok(())
+}
+
+fn end(summary: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = state
+        .sessions
+        .iter()
+        .position(|s| s.ended_at.is_none())
+        .ok_or("No open session. Start one with 'legend session start'.")?;
+
+    let touched = touched_since(&state, state.sessions[index].started_at);
+    let session = &mut state.sessions[index];
+    session.ended_at = Some(current_timestamp());
+    session.summary = summary.map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+    session.features_touched = touched;
+    let (id, count) = (session.id, session.features_touched.len());
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Ended session {} ({} feature(s) touched).", id, count),
+        json!({"id": id, "features_touched": count}),
+    );
+    Ok(())
+}
+
+fn list(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let state = storage::load_state()?;
+    let sessions: Vec<&Session> = state.sessions.iter().rev().collect();
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+    } else if sessions.is_empty() {
+        output::info("No sessions yet. Start one with 'legend session start'.");
+    } else {
+        for session in sessions {
+            println!("{}", render_session(session));
+        }
+    }
+    Ok(())
+}
+
+/// IDs of the features updated at or after `since`
+fn touched_since(state: &LegendState, since: i64) -> Vec<String> {
+    state
+        .features
+        .iter()
+        .filter(|f| f.last_updated >= since)
+        .map(|f| f.id.clone())
+        .collect()
+}
+
+/// `#3 2024-03-01 (1h 20m)  Finished token refresh  [auth, api]`
+fn render_session(session: &Session) -> String {
+    let length = match session.ended_at {
+        Some(end) => {
+            let minutes = (end - session.started_at).max(0) / 60;
+            format!("{}h {:02}m", minutes / 60, minutes % 60)
+        }
+        None => "open".to_string(),
+    };
+    let mut line = format!("#{} {} ({})", session.id, dates::format_date(session.started_at), length);
+    if let Some(summary) = &session.summary {
+        line.push_str(&format!("  {}", summary));
+    }
+    if !session.features_touched.is_empty() {
+        line.push_str(&format!("  [{}]", session.features_touched.join(", ")));
+    }
+    line
+}
+
+/// The last `limit` ended sessions with a summary, oldest first; empty
+/// when there are none so the hook prints nothing
+fn recap(sessions: &[Session], limit: usize) -> String {
+    let mut recent: Vec<&Session> = sessions
+        .iter()
+        .rev()
+        .filter(|s| s.ended_at.is_some() && s.summary.is_some())
+        .take(limit)
+        .collect();
+    if recent.is_empty() {
+        return String::new();
+    }
+    recent.reverse();
+
+    let mut out = String::from("== Recent sessions ==\n");
+    for session in recent {
+        out.push_str(&render_session(session));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: u32, summary: Option<&str>) -> Session {
+        Session {
+            id,
+            started_at: 0,
+            ended_at: Some(4800),
+            summary: summary.map(String::from),
+            features_touched: vec!["auth".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_render_session() {
+        assert_eq!(render_session(&session(3, Some("Token refresh"))), "#3 1970-01-01 (1h 20m)  Token refresh  [auth]");
+
+        let mut open = session(4, None);
+        open.ended_at = None;
+        open.features_touched.clear();
+        assert_eq!(render_session(&open), "#4 1970-01-01 (open)");
+    }
+
+    #[test]
+    fn test_recap_skips_open_and_unsummarized() {
+        let mut open = session(4, Some("Still going"));
+        open.ended_at = None;
+        let sessions = vec![session(1, Some("One")), session(2, Some("Two")), session(3, None), open];
+
+        assert_eq!(recap(&sessions, 1), "== Recent sessions ==\n#2 1970-01-01 (1h 20m)  Two  [auth]\n");
+        assert!(recap(&sessions, 5).starts_with("== Recent sessions ==\n#1 "));
+        assert_eq!(recap(&[], 3), "");
+    }
+
+    #[test]
+    fn test_start_and_end_record_touched_features() {
+        let mut env = crate::testing::TestEnv::initialized(1_000);
+        let mut state = storage::load_state().unwrap();
+        let mut old = crate::types::Feature::new("old".to_string(), "Old".to_string(), "d".to_string(), "d".to_string());
+        old.last_updated = 500;
+        state.features.push(old);
+        storage::save_state(&state).unwrap();
+
+        env.set_now(2_000);
+        handle_session(&["start".to_string()]).unwrap();
+        let mut state = storage::load_state().unwrap();
+        state.features.push(crate::types::Feature::new("new".to_string(), "New".to_string(), "d".to_string(), "d".to_string()));
+        storage::save_state(&state).unwrap();
+
+        env.set_now(2_000 + 3_600);
+        handle_session(&["end".to_string(), "Added new".to_string()]).unwrap();
+
+        let session = storage::load_state().unwrap().sessions.remove(0);
+        assert_eq!((session.started_at, session.ended_at), (2_000, Some(5_600)));
+        assert_eq!(session.features_touched, vec!["new"]);
+        assert_eq!(session.summary.as_deref(), Some("Added new"));
+    }
+}
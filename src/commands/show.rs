@@ -8,82 +8,524 @@
 // - Iterator methods: map, filter, collect
 // - Display trait basics (how Rust converts types to strings)
 
+use crate::dates;
+use crate::deadlines::{self, Urgency};
+use crate::deps;
+use crate::exit::{self, ExitError};
+use crate::hierarchy;
+use crate::journal;
+use crate::output;
+use crate::rollups::{self, DomainRollup, Rollups};
 use crate::storage;
-use crate::types::FeatureStatus;
+use crate::text;
+use crate::theme::{Glyphs, Theme};
+use crate::types::{current_timestamp, Feature, FeatureStatus};
 
 /// Handle the show command
 ///
-/// Loads state and prints a formatted table sorted by recency
-pub fn handle_show() -> Result<(), Box<dyn std::error::Error>> {
-    let mut state = storage::load_state()?;
+/// Loads state and prints a formatted table sorted by priority and recency.
+/// `--at <date>` shows the state as it was at that moment.
+/// `--interactive` opens a keyboard-driven list (see show_interactive.rs).
+/// `--oneline` prints one short line per feature with a status mark.
+/// `--tree` prints the same lines with children indented under parents.
+/// `--by domain` prints one line per domain from the rollups (see rollups.rs).
+/// The table starts with what's blocked and why.
+pub fn handle_show(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str = "Usage: legend show [--at <date>] [--oneline | --tree | --by domain | --interactive]";
+
+    let (at, rest) = journal::take_at_flag(args)?;
+    let mut interactive = false;
+    let mut oneline = false;
+    let mut tree = false;
+    let mut by_domain = false;
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--interactive" | "-i" => interactive = true,
+            "--oneline" => oneline = true,
+            "--tree" => tree = true,
+            "--by" => match rest.next().as_deref() {
+                Some("domain") => by_domain = true,
+                _ => return Err(ExitError::new(exit::USAGE, format!("--by expects domain\n{}", USAGE)).into()),
+            },
+            other => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into()),
+        }
+    }
+    if [oneline, tree, by_domain].iter().filter(|v| **v).count() > 1 {
+        return Err(ExitError::new(exit::USAGE, format!("--oneline, --tree, and --by are different views\n{}", USAGE)).into());
+    }
+
+    if interactive {
+        if at.is_some() {
+            return Err(ExitError::new(exit::USAGE, "--interactive edits the current state; it can't be combined with --at").into());
+        }
+        if oneline || tree || by_domain {
+            let other = if tree { "tree" } else if by_domain { "by" } else { "oneline" };
+            return Err(ExitError::new(exit::USAGE, format!("--interactive and --{} are different views\n{}", other, USAGE)).into());
+        }
+        #[cfg(feature = "tui")]
+        return super::show_interactive::run();
+        #[cfg(not(feature = "tui"))]
+        return Err(crate::cargo_features::not_built("tui", "show --interactive"));
+    }
+
+    let mut state = journal::load_state_at(at)?;
+
+    if let Some(at) = at {
+        println!("State as of {}", dates::format_date(at));
+        println!();
+    }
 
     if state.features.is_empty() {
         println!("No features tracked yet. Use 'legend update' to add features.");
         return Ok(());
     }
 
-    // Sort by recency score (highest first)
-    // sort_by uses a closure that compares two features
-    // partial_cmp handles f64 comparison (which can be NaN)
-    // We reverse (b.cmp(a)) so highest recency comes first
-    state.features.sort_by(|a, b| {
-        b.recency_score
-            .partial_cmp(&a.recency_score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    // Per-domain lines come from the rollups save_state keeps, not a scan;
+    // a past state (--at) has none on disk
+    if by_domain {
+        let rollups = match at {
+            Some(_) => Rollups::build(&state),
+            None => rollups::for_state(&storage::legend_dir(), &state),
+        };
+        if output::screen_reader() {
+            for (domain, rollup) in &rollups.domains {
+                println!("{}", domain_screen_reader_line(domain, rollup));
+            }
+        } else {
+            for line in domain_lines(&rollups, &Theme::load()?) {
+                println!("{}", line);
+            }
+        }
+        return Ok(());
+    }
+
+    // Sort by priority, then recency (highest first; see Feature::ordering_score)
+    state.sort_by_priority();
+
+    let complete = state
+        .features
+        .iter()
+        .filter(|f| f.status == FeatureStatus::Complete)
+        .count();
+    let total = state.features.len();
+
+    let now = current_timestamp();
+
+    // One labeled sentence per feature - tables (and --oneline marks) read badly aloud
+    if output::screen_reader() {
+        for feature in &state.features {
+            println!("{}", screen_reader_line(feature, &deps::waiting_on(feature, &state.features), now));
+        }
+        println!("{} of {} features complete.", complete, total);
+        return Ok(());
+    }
+
+    // Colors come from [theme] in config.toml (plain when piped)
+    let theme = Theme::load()?;
+
+    if tree {
+        for line in tree_lines(&state.features, Glyphs::load()?, &theme) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    if oneline {
+        let glyphs = Glyphs::load()?;
+        for feature in &state.features {
+            println!("{}", oneline_line(feature, glyphs, &theme));
+        }
+        return Ok(());
+    }
+
+    // What's stuck comes first, above the table
+    let blocked = blocked_lines(&state.features, &theme);
+    if !blocked.is_empty() {
+        for line in &blocked {
+            println!("{}", line);
+        }
+        println!();
+    }
 
     // Print header
-    println!(
-        "{:<20} {:<14} {:<12} {:<8} {}",
-        "ID", "DOMAIN", "STATUS", "RECENCY", "NAME"
+    let header = format!(
+        "{:<20} {:<14} {:<12} {:<12} {:<8} NAME",
+        "ID", "DOMAIN", "STATUS", "OWNER", "RECENCY"
     );
-    println!("{}", "-".repeat(72));
+    println!("{}", theme.accent(&header));
+    println!("{}", "-".repeat(RULE_WIDTH));
 
     // Print each feature
     for feature in &state.features {
-        let status_str = status_label(feature.status);
+        // Pad before coloring - escape codes would throw off the width
+        let status_str = theme.status(&feature.status, &text::pad(&truncate(feature_label(feature), 11), 12));
         let recency_str = format!("{:.0}%", feature.recency_score * 100.0);
+        let progress = match feature.subtask_progress() {
+            Some((done, total)) => format!(" {}/{}", done, total),
+            None => String::new(),
+        };
+        let waiting = deps::waiting_on(feature, &state.features);
+        let waits = if waiting.is_empty() {
+            String::new()
+        } else {
+            theme.status(&FeatureStatus::Blocked, &format!(" (waits on: {})", waiting.join(", ")))
+        };
+        // Overdue in the blocked color, due soon in the accent
+        let due = match (deadlines::urgency(feature, now), feature.due_date) {
+            (Some(Urgency::Overdue), Some(due)) => {
+                theme.status(&FeatureStatus::Blocked, &format!(" {}", dates::due_label(due, now)))
+            }
+            (Some(Urgency::DueSoon), Some(due)) => theme.accent(&format!(" {}", dates::due_label(due, now))),
+            _ => String::new(),
+        };
 
+        // Padded by columns, not chars, so wide names and emoji line up
         println!(
-            "{:<20} {:<14} {:<12} {:<8} {}",
-            truncate(&feature.id, 19),
-            truncate(&feature.domain, 13),
+            "{} {} {} {} {:<8} {}{}{}{}",
+            text::pad(&truncate(&feature.id, 19), 20),
+            text::pad(&truncate(&feature.domain, 13), 14),
             status_str,
+            text::pad(&truncate(feature.owner.as_deref().unwrap_or("-"), 11), 12),
             recency_str,
             feature.name,
+            progress,
+            waits,
+            due,
         );
     }
 
     // Summary line
-    println!("{}", "-".repeat(72));
-
-    let complete = state
-        .features
-        .iter()
-        .filter(|f| f.status == FeatureStatus::Complete)
-        .count();
-    let total = state.features.len();
-
+    println!("{}", "-".repeat(RULE_WIDTH));
     println!("{}/{} features complete", complete, total);
 
     Ok(())
 }
 
-/// Convert FeatureStatus to a display string
-fn status_label(status: FeatureStatus) -> &'static str {
-    match status {
-        FeatureStatus::Pending => "Pending",
-        FeatureStatus::InProgress => "InProgress",
-        FeatureStatus::Blocked => "Blocked",
-        FeatureStatus::Complete => "Complete",
+/// Width of the rules above and below the table
+const RULE_WIDTH: usize = 85;
+
+/// The status column: planned stubs show as Planned whatever their status
+fn feature_label(feature: &Feature) -> &str {
+    if feature.planned {
+        "Planned"
+    } else {
+        feature.status.name()
     }
 }
 
-/// Truncate a string to max_len, adding ".." if truncated
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
+/// The block above the table, empty when nothing is blocked:
+///
+/// ```text
+/// Blocked (2)
+///   billing: No API keys yet (blocked by vendor-keys)
+///   search: no reason given
+/// ```
+fn blocked_lines(features: &[Feature], theme: &Theme) -> Vec<String> {
+    let blocked: Vec<&Feature> = features.iter().filter(|f| f.status == FeatureStatus::Blocked).collect();
+    if blocked.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec![theme.status(&FeatureStatus::Blocked, &format!("Blocked ({})", blocked.len()))];
+    for feature in blocked {
+        let by = match &feature.blocked_by {
+            Some(blocker) => format!(" (blocked by {})", blocker),
+            None => String::new(),
+        };
+        lines.push(format!(
+            "  {}: {}{}",
+            feature.id,
+            feature.blocked_reason.as_deref().unwrap_or("no reason given"),
+            by
+        ));
+    }
+    lines
+}
+
+/// The `--by domain` table, one row per domain:
+///
+/// ```text
+/// DOMAIN         FEATURES DONE   BLOCKED LAST ACTIVE TOP
+/// auth           4        1      0       2026-10-14  mfa, oauth, sso
+/// ```
+fn domain_lines(rollups: &Rollups, theme: &Theme) -> Vec<String> {
+    let header = format!(
+        "{:<14} {:<8} {:<6} {:<7} {:<11} TOP",
+        "DOMAIN", "FEATURES", "DONE", "BLOCKED", "LAST ACTIVE"
+    );
+    let mut lines = vec![theme.accent(&header), "-".repeat(RULE_WIDTH)];
+    for (domain, rollup) in &rollups.domains {
+        let blocked = rollup.count(&FeatureStatus::Blocked);
+        let blocked = match blocked {
+            0 => format!("{:<7}", blocked),
+            _ => theme.status(&FeatureStatus::Blocked, &format!("{:<7}", blocked)),
+        };
+        lines.push(format!(
+            "{} {:<8} {:<6} {} {:<11} {}",
+            text::pad(&truncate(domain, 13), 14),
+            rollup.features,
+            rollup.count(&FeatureStatus::Complete),
+            blocked,
+            dates::format_date(rollup.last_updated),
+            rollup.top_features.join(", ")
+        ));
+    }
+    lines
+}
+
+/// "Domain auth: 4 features, 1 complete, 0 blocked, last active 2026-10-14, top mfa, oauth, sso."
+fn domain_screen_reader_line(domain: &str, rollup: &DomainRollup) -> String {
+    format!(
+        "Domain {}: {} features, {} complete, {} blocked, last active {}, top {}.",
+        domain,
+        rollup.features,
+        rollup.count(&FeatureStatus::Complete),
+        rollup.count(&FeatureStatus::Blocked),
+        dates::format_date(rollup.last_updated),
+        rollup.top_features.join(", ")
+    )
+}
+
+/// `● auth [security] In-Progress 84%`, with done/total tasks if any
+fn oneline_line(feature: &Feature, glyphs: Glyphs, theme: &Theme) -> String {
+    let label = match feature_label(feature) {
+        "InProgress" => "In-Progress",
+        label => label,
+    };
+    let mut line = format!(
+        "{} {} [{}] {} {:.0}%",
+        theme.status(&feature.status, glyphs.status(&feature.status, feature.planned)),
+        feature.id,
+        feature.domain,
+        label,
+        feature.recency_score * 100.0
+    );
+    if let Some((done, total)) = feature.subtask_progress() {
+        line.push_str(&format!(" {}/{}", done, total));
+    }
+    line
+}
+
+/// Oneline lines in tree order, children under their parent:
+///
+/// ```text
+/// ○ onboarding [product] Pending 40%
+/// ├─ ● signup [auth] In-Progress 84%
+/// │  └─ ○ signup-form [web] Pending 80%
+/// └─ ✔ welcome-email [email] Complete 12%
+/// ```
+fn tree_lines(features: &[Feature], glyphs: Glyphs, theme: &Theme) -> Vec<String> {
+    let (branch, last, through, gap) = match glyphs {
+        Glyphs::Ascii => ("|-- ", "`-- ", "|   ", "    "),
+        Glyphs::Unicode | Glyphs::Emoji => ("├─ ", "└─ ", "│  ", "   "),
+    };
+    let order = hierarchy::tree_order(features);
+    // For each open ancestor level: do more of its siblings follow?
+    let mut open: Vec<bool> = Vec::new();
+    let mut lines = Vec::new();
+    for (i, (depth, feature)) in order.iter().enumerate() {
+        let mut prefix = String::new();
+        if *depth > 0 {
+            let has_next_sibling = order[i + 1..]
+                .iter()
+                .find(|(d, _)| d <= depth)
+                .is_some_and(|(d, _)| d == depth);
+            open.truncate(depth - 1);
+            for more in &open {
+                prefix.push_str(if *more { through } else { gap });
+            }
+            prefix.push_str(if has_next_sibling { branch } else { last });
+            open.push(has_next_sibling);
+        }
+        lines.push(format!("{}{}", prefix, oneline_line(feature, glyphs, theme)));
+    }
+    lines
+}
+
+/// "Feature auth: status InProgress, domain security, recency 84 percent, name Authentication."
+///
+/// A parent follows as ", part of onboarding", an owner as ", owner alice", subtasks as ", tasks 3 of 7 done",
+/// unfinished dependencies as ", waits on db, cache", a blocker as ", blocked: No API keys yet, by vendor-keys",
+/// a close or missed deadline as ", due 2024-03-01 (2 days overdue)."
+fn screen_reader_line(feature: &Feature, waiting: &[&str], now: i64) -> String {
+    let mut blocked = String::new();
+    if let Some(reason) = &feature.blocked_reason {
+        blocked.push_str(&format!(", blocked: {}", reason));
+    }
+    if let Some(blocker) = &feature.blocked_by {
+        blocked.push_str(&format!(", by {}", blocker));
+    }
+    let parent = match &feature.parent_id {
+        Some(parent) => format!(", part of {}", parent),
+        None => String::new(),
+    };
+    let owner = match &feature.owner {
+        Some(owner) => format!(", owner {}", owner),
+        None => String::new(),
+    };
+    let tasks = match feature.subtask_progress() {
+        Some((done, total)) => format!(", tasks {} of {} done", done, total),
+        None => String::new(),
+    };
+    let waits = if waiting.is_empty() {
+        String::new()
     } else {
-        format!("{}..", &s[..max_len - 2])
+        format!(", waits on {}", waiting.join(", "))
+    };
+    let due = match (deadlines::urgency(feature, now), feature.due_date) {
+        (Some(_), Some(due)) => format!(", {}", dates::due_label(due, now)),
+        _ => String::new(),
+    };
+    format!(
+        "Feature {}: status {}, domain {}, recency {:.0} percent, name {}{}{}{}{}{}{}.",
+        feature.id,
+        feature_label(feature),
+        feature.domain,
+        feature.recency_score * 100.0,
+        feature.name,
+        parent,
+        owner,
+        tasks,
+        waits,
+        blocked,
+        due
+    )
+}
+
+/// Truncate a string to max_len columns, adding ".." if truncated
+fn truncate(s: &str, max_len: usize) -> String {
+    text::truncate(s, max_len, "..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oneline_line() {
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "Login".to_string());
+        feature.status = FeatureStatus::InProgress;
+        feature.recency_score = 0.842;
+        assert_eq!(oneline_line(&feature, Glyphs::Unicode, &Theme::plain()), "● auth [security] In-Progress 84%");
+
+        feature.planned = true;
+        assert_eq!(oneline_line(&feature, Glyphs::Ascii, &Theme::plain()), "? auth [security] Planned 84%");
+    }
+
+    #[test]
+    fn test_blocked_lines() {
+        let feature = |id: &str, status: FeatureStatus| {
+            let mut f = Feature::new(id.to_string(), id.to_string(), "api".to_string(), "d".to_string());
+            f.status = status;
+            f
+        };
+        assert!(blocked_lines(&[feature("auth", FeatureStatus::InProgress)], &Theme::plain()).is_empty());
+
+        let mut billing = feature("billing", FeatureStatus::Blocked);
+        billing.blocked_reason = Some("No API keys yet".to_string());
+        billing.blocked_by = Some("vendor-keys".to_string());
+        let features = vec![feature("auth", FeatureStatus::InProgress), billing, feature("search", FeatureStatus::Blocked)];
+        assert_eq!(
+            blocked_lines(&features, &Theme::plain()),
+            vec!["Blocked (2)", "  billing: No API keys yet (blocked by vendor-keys)", "  search: no reason given"]
+        );
+    }
+
+    #[test]
+    fn test_domain_lines() {
+        let mut state = crate::types::LegendState::new("Demo".to_string());
+        for (id, domain, status) in [
+            ("login", "auth", FeatureStatus::Complete),
+            ("oauth", "auth", FeatureStatus::InProgress),
+            ("invoices", "billing", FeatureStatus::Blocked),
+        ] {
+            let mut f = Feature::new(id.to_string(), id.to_string(), domain.to_string(), "d".to_string());
+            f.status = status;
+            f.last_updated = 1_760_400_000;
+            state.features.push(f);
+        }
+        let rollups = Rollups::build(&state);
+
+        let lines = domain_lines(&rollups, &Theme::plain());
+        assert_eq!(lines[2], "auth           2        1      0       2025-10-14  login, oauth");
+        assert_eq!(lines[3], "billing        1        0      1       2025-10-14  invoices");
+        assert_eq!(
+            domain_screen_reader_line("billing", &rollups.domains["billing"]),
+            "Domain billing: 1 features, 0 complete, 1 blocked, last active 2025-10-14, top invoices."
+        );
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        // Byte 17 falls inside "é"; this used to panic
+        assert_eq!(truncate("authentification-é-flow", 19), "authentification-..");
+        assert_eq!(truncate("résumé-builder-über", 19), "résumé-builder-über");
+        assert_eq!(truncate("日本語のログイン機能を追加", 19), "日本語のログイン..");
+    }
+
+    #[test]
+    fn test_tree_lines() {
+        let feature = |id: &str, parent: Option<&str>| {
+            let mut f = Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string());
+            f.parent_id = parent.map(str::to_string);
+            f
+        };
+        let features = vec![
+            feature("onboarding", None),
+            feature("signup", Some("onboarding")),
+            feature("signup-form", Some("signup")),
+            feature("welcome", Some("onboarding")),
+            feature("billing", None),
+        ];
+
+        assert_eq!(
+            tree_lines(&features, Glyphs::Unicode, &Theme::plain()),
+            vec![
+                "○ onboarding [web] Pending 100%",
+                "├─ ○ signup [web] Pending 100%",
+                "│  └─ ○ signup-form [web] Pending 100%",
+                "└─ ○ welcome [web] Pending 100%",
+                "○ billing [web] Pending 100%",
+            ]
+        );
+        assert_eq!(tree_lines(&features, Glyphs::Ascii, &Theme::plain())[2], "|   `-- . signup-form [web] Pending 100%");
+    }
+
+    #[test]
+    fn test_screen_reader_line() {
+        let mut feature = Feature::new(
+            "auth".to_string(),
+            "Authentication".to_string(),
+            "security".to_string(),
+            "Login".to_string(),
+        );
+        feature.status = FeatureStatus::InProgress;
+        feature.recency_score = 0.842;
+
+        assert_eq!(
+            screen_reader_line(&feature, &[], 0),
+            "Feature auth: status InProgress, domain security, recency 84 percent, name Authentication."
+        );
+        assert_eq!(
+            screen_reader_line(&feature, &["db", "cache"], 0),
+            "Feature auth: status InProgress, domain security, recency 84 percent, name Authentication, waits on db, cache."
+        );
+
+        feature.subtasks.push(crate::types::Subtask {
+            id: 1,
+            title: "Schema".to_string(),
+            done: true,
+            created_at: 0,
+            completed_at: Some(1),
+        });
+        assert!(screen_reader_line(&feature, &[], 0).ends_with("name Authentication, tasks 1 of 1 done."));
+
+        feature.owner = Some("lee".to_string());
+        assert!(screen_reader_line(&feature, &[], 0).ends_with("name Authentication, owner lee, tasks 1 of 1 done."));
+
+        feature.due_date = Some(0);
+        let two_days = 2 * dates::SECONDS_PER_DAY;
+        assert!(screen_reader_line(&feature, &[], two_days).ends_with("tasks 1 of 1 done, due 1970-01-01 (2 days overdue)."));
+        assert!(screen_reader_line(&feature, &[], -60 * two_days).ends_with("tasks 1 of 1 done."));
     }
 }
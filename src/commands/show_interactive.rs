@@ -0,0 +1,241 @@
+// Interactive show - quick housekeeping without building update JSON
+//
+// Usage:
+//   legend show --interactive
+//
+// Keys:
+//   up/down (or k/j)   select a feature
+//   s                  cycle status: Pending -> InProgress -> Blocked -> Complete
+//   a                  archive the feature (moves it to .legend/archive.lz4)
+//   enter              show details (any key to go back)
+//   q / esc            quit
+//
+// Every change is saved immediately and journaled like a normal update.
+
+use super::archive;
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::signals;
+use crate::storage;
+use crate::terminal::{self, Key, RawMode};
+use crate::text;
+use crate::theme::Theme;
+use crate::types::{current_timestamp, Feature, FeatureStatus, LegendState};
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+/// Run the interactive list until the user quits
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut state = storage::load_state()?;
+    if state.features.is_empty() {
+        println!("No features tracked yet. Use 'legend update' to add features.");
+        return Ok(());
+    }
+
+    let theme = Theme::load()?;
+    // Ctrl-C or a kill leaves through the loop, so the terminal is restored
+    let _signals = signals::install();
+    let _raw = RawMode::enable()?;
+    let mut selected = 0;
+    let mut message = String::new();
+
+    loop {
+        // Keep the selection valid after an archive shrinks the list
+        selected = selected.min(state.features.len().saturating_sub(1));
+
+        terminal::clear_screen();
+        print!("{}", render_list(&state.features, selected, &message, &theme));
+        io::stdout().flush()?;
+        message.clear();
+
+        match terminal::read_key()? {
+            Key::Up | Key::Char('k') => selected = selected.saturating_sub(1),
+            Key::Down | Key::Char('j') if selected + 1 < state.features.len() => selected += 1,
+            Key::Char('s') => {
+                let id = state.features[selected].id.clone();
+                message = edit(&mut state, |state| {
+                    let feature = state.find_feature_mut(&id).ok_or_else(|| format!("{} was deleted meanwhile", id))?;
+                    feature.set_status(next_status(&feature.status), current_timestamp());
+                    feature.touch();
+                    Ok(format!("{} -> {}", feature.id, feature.status))
+                })?;
+            }
+            Key::Char('a') => {
+                let id = state.features[selected].id.clone();
+                message = edit(&mut state, |state| {
+                    archive::archive_features(state, &HashSet::from([id.clone()]), current_timestamp())?;
+                    Ok(format!("Archived {}", id))
+                })?;
+            }
+            Key::Enter => {
+                terminal::clear_screen();
+                print!("{}", render_detail(&state.features[selected]));
+                io::stdout().flush()?;
+                if terminal::read_key()? == Key::Interrupt {
+                    break;
+                }
+            }
+            Key::Char('q') | Key::Escape | Key::Interrupt => break,
+            _ => {}
+        }
+
+        // Archived the last feature - nothing left to show
+        if state.features.is_empty() {
+            break;
+        }
+    }
+
+    terminal::clear_screen();
+    io::stdout().flush()?;
+
+    // Every change is saved as it's made, so there's nothing to flush;
+    // report the signal the way a shell expects (128 + its number)
+    match signals::received() {
+        Some(signal) => Err(ExitError::new(exit::signal_code(signal), "Interrupted").into()),
+        None => Ok(()),
+    }
+}
+
+/// Make a change in the interactive view and save it; returns its message
+///
+/// The view can stay open for a long time, so the change is made to the
+/// state as it is now (under the lock), not the copy loaded at the start,
+/// and `state` is refreshed with the result
+fn edit(
+    state: &mut LegendState,
+    change: impl FnOnce(&mut LegendState) -> Result<String, Box<dyn std::error::Error>>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let before = storage::load_state()?;
+    let mut after = before.clone();
+    let message = change(&mut after)?;
+    journal::commit(&before, &after)?;
+    *state = after;
+    Ok(message)
+}
+
+/// The status `s` moves a feature to
+///
+/// Cycles through the built-in statuses; a custom status moves on to Pending
+fn next_status(status: &FeatureStatus) -> FeatureStatus {
+    match status {
+        FeatureStatus::Pending => FeatureStatus::InProgress,
+        FeatureStatus::InProgress => FeatureStatus::Blocked,
+        FeatureStatus::Blocked => FeatureStatus::Complete,
+        FeatureStatus::Complete | FeatureStatus::Custom(_) => FeatureStatus::Pending,
+    }
+}
+
+/// The feature list with the selected row highlighted (reverse video)
+///
+/// The selected row is left uncolored so the highlight stays readable
+fn render_list(features: &[Feature], selected: usize, message: &str, theme: &Theme) -> String {
+    let mut out = String::new();
+    out.push_str("↑/↓ select  s status  a archive  enter details  q quit\n\n");
+
+    for (i, feature) in features.iter().enumerate() {
+        let id = text::pad(&text::truncate(&feature.id, 19, ""), 20);
+        let status = text::pad(&text::truncate(&feature.status.to_string(), 11, ""), 12);
+        if i == selected {
+            out.push_str(&format!("\x1b[7m> {} {} {}\x1b[0m\n", id, status, feature.name));
+        } else {
+            let status = theme.status(&feature.status, &status);
+            out.push_str(&format!("  {} {} {}\n", id, status, feature.name));
+        }
+    }
+
+    if !message.is_empty() {
+        out.push_str(&format!("\n{}\n", message));
+    }
+    out
+}
+
+/// Everything about one feature
+fn render_detail(feature: &Feature) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{} ({})\n\n", feature.name, feature.id));
+    out.push_str(&format!("Domain:  {}\n", feature.domain));
+    out.push_str(&format!("Status:  {}\n", feature.status));
+    out.push_str(&format!("Priority: {:?}\n", feature.priority));
+    if let Some(source) = &feature.last_updated_by {
+        out.push_str(&format!("Updated: {} by {}\n", dates::format_date(feature.last_updated), source));
+    }
+    if !feature.tags.is_empty() {
+        out.push_str(&format!("Tags:    {}\n", feature.tags.join(", ")));
+    }
+    match feature.estimate_hours {
+        Some(estimate) => out.push_str(&format!("Time:    {}h of {}h estimated\n", feature.logged_hours, estimate)),
+        None if feature.logged_hours > 0.0 => out.push_str(&format!("Time:    {}h logged\n", feature.logged_hours)),
+        None => {}
+    }
+    out.push_str(&format!("\n{}\n", feature.description));
+    if let Some(context) = &feature.context {
+        out.push_str(&format!("\nContext:\n{}\n", context));
+    }
+    if !feature.files_involved.is_empty() {
+        out.push_str("\nFiles:\n");
+        for file in &feature.files_involved {
+            out.push_str(&format!("  {}\n", file));
+        }
+    }
+    if let Some((done, total)) = feature.subtask_progress() {
+        out.push_str(&format!("\nTasks ({}/{}):\n", done, total));
+        for task in &feature.subtasks {
+            out.push_str(&format!("  [{}] {} {}\n", if task.done { "x" } else { " " }, task.id, task.title));
+        }
+    }
+    if !feature.decisions.is_empty() {
+        out.push_str("\nDecisions:\n");
+        for decision in &feature.decisions {
+            out.push_str(&format!("  #{} {}\n", decision.id, decision.title));
+        }
+    }
+    if !feature.notes.is_empty() {
+        out.push_str("\nNotes:\n");
+        for note in &feature.notes {
+            out.push_str(&format!("  {}\n", super::note::render_note(note)));
+        }
+    }
+    out.push_str("\n(press any key to go back)\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_status_cycles() {
+        let mut status = FeatureStatus::Pending;
+        for _ in 0..4 {
+            status = next_status(&status);
+        }
+        assert_eq!(status, FeatureStatus::Pending);
+        assert_eq!(next_status(&FeatureStatus::Blocked), FeatureStatus::Complete);
+        assert_eq!(next_status(&FeatureStatus::Custom("InReview".to_string())), FeatureStatus::Pending);
+    }
+
+    #[test]
+    fn test_render_list_highlights_selection() {
+        let features = vec![
+            Feature::new("a".to_string(), "Alpha".to_string(), "core".to_string(), "d".to_string()),
+            Feature::new("b".to_string(), "Beta".to_string(), "core".to_string(), "d".to_string()),
+        ];
+        let out = render_list(&features, 1, "a -> InProgress", &Theme::plain());
+
+        assert!(out.contains("  a "));
+        assert!(out.contains("\x1b[7m> b "));
+        assert!(out.ends_with("\na -> InProgress\n"));
+    }
+
+    #[test]
+    fn test_render_detail_shows_source() {
+        let mut feature = Feature::new("a".to_string(), "Alpha".to_string(), "core".to_string(), "d".to_string());
+        assert!(!render_detail(&feature).contains("Updated:"));
+
+        feature.last_updated_by = Some("aider".to_string());
+        feature.last_updated = 0;
+        assert!(render_detail(&feature).contains(&format!("Updated: {} by aider\n", dates::format_date(0))));
+    }
+}
@@ -0,0 +1,396 @@
+// Split command - break one oversized feature into several
+//
+// Usage:
+//   legend split <id> --into <new-id> [<new-id>...]  - split (asks on a terminal)
+//   legend split <id> --into a b --assign src/a/=a   - route files (dirs match below)
+//   legend split <id> --into a b --section 2=b       - route context paragraph 2
+//
+// The new features start as copies of the original - domain, tags, status,
+// lifecycle timestamps, metadata - each with the files and context sections
+// assigned to it. Context sections are the paragraphs of the original's
+// context (separated by blank lines). Whatever isn't assigned stays with
+// the original, which is kept.
+//
+// On a terminal, every file and section not routed by a flag is asked
+// about. The link is recorded in metadata: each new feature gets
+// `split_from`, the original gets `split_into`.
+
+use crate::config;
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, LegendState};
+use serde_json::json;
+use std::io::{BufRead, IsTerminal, Write};
+
+const USAGE: &str =
+    "Usage: legend split <id> --into <new-id> [<new-id>...] [--assign <path>=<new-id>] [--section <n>=<new-id>]";
+
+/// Where each file and context section of the original goes
+///
+/// `None` keeps the item with the original; `Some(i)` moves it to the
+/// i-th new feature
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SplitPlan {
+    into: Vec<String>,
+    files: Vec<(String, Option<usize>)>,
+    sections: Vec<(String, Option<usize>)>,
+}
+
+/// Handle the split command
+pub fn handle_split(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut id = None;
+    let mut into: Vec<String> = Vec::new();
+    let mut assigns: Vec<(String, String)> = Vec::new();
+    let mut section_assigns: Vec<(usize, String)> = Vec::new();
+
+    let mut i = 0;
+    let mut in_into = false;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--into" => in_into = true,
+            "--assign" => {
+                in_into = false;
+                i += 1;
+                let (path, target) = args
+                    .get(i)
+                    .and_then(|v| v.rsplit_once('='))
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--assign expects <path>=<new-id>\n{}", USAGE)))?;
+                assigns.push((path.to_string(), target.to_string()));
+            }
+            "--section" => {
+                in_into = false;
+                i += 1;
+                let (n, target) = args
+                    .get(i)
+                    .and_then(|v| v.split_once('='))
+                    .and_then(|(n, target)| Some((n.parse::<usize>().ok().filter(|n| *n > 0)?, target)))
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("--section expects <n>=<new-id>\n{}", USAGE)))?;
+                section_assigns.push((n, target.to_string()));
+            }
+            other if other.starts_with("--") => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other if in_into => into.push(other.to_string()),
+            other if id.is_none() => id = Some(other.to_string()),
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unexpected argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+
+    let id = id.ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    if into.is_empty() {
+        return Err(ExitError::new(exit::USAGE, format!("--into needs at least one new feature ID\n{}", USAGE)).into());
+    }
+
+    let before = storage::load_state()?;
+    let index = ids::resolve(&before.features, &id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let original = &before.features[index];
+    let original_id = original.id.clone();
+
+    // New IDs follow the same namespacing rule as `legend update`
+    if config::namespaced_ids(&config::load_config()?)? {
+        into = into.iter().map(|new_id| ids::namespaced(&original.domain, new_id)).collect();
+    }
+    check_new_ids(&before, &into)?;
+
+    let mut plan = initial_plan(original, into);
+    for (path, target) in &assigns {
+        assign_files(&mut plan, path, target)?;
+    }
+    for (n, target) in &section_assigns {
+        let target = target_index(&plan, target)?;
+        let section = plan
+            .sections
+            .get_mut(n - 1)
+            .ok_or_else(|| format!("{} has no context section {}", original.id, n))?;
+        section.1 = Some(target);
+    }
+
+    if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() {
+        prompt_plan(&mut plan, &original.id, assigns.len(), section_assigns.len())?;
+    }
+
+    let mut state = before.clone();
+    let created = apply_split(&mut state, index, &plan, current_timestamp());
+    let _lock = storage::lock_unchanged(&before)?;
+    journal::commit(&before, &state)?;
+
+    for feature in &created {
+        println!(
+            "{}: {} file(s), {} context section(s)",
+            feature.id,
+            feature.files_involved.len(),
+            feature.context.as_deref().map(|c| sections(c).len()).unwrap_or(0)
+        );
+    }
+    output::diag(
+        output::Level::Info,
+        &format!("Split {} into {} feature(s).", original_id, created.len()),
+        json!({"original": original_id, "into": plan.into}),
+    );
+    Ok(())
+}
+
+/// New IDs must be distinct and not already taken
+fn check_new_ids(state: &LegendState, into: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    for (i, new_id) in into.iter().enumerate() {
+        if state.find_feature(new_id).is_some() {
+            return Err(format!("Feature '{}' already exists; pick another ID", new_id).into());
+        }
+        if into[..i].contains(new_id) {
+            return Err(ExitError::new(exit::USAGE, format!("'{}' is listed twice in --into", new_id)).into());
+        }
+    }
+    Ok(())
+}
+
+/// Everything stays with the original until assigned
+fn initial_plan(original: &Feature, into: Vec<String>) -> SplitPlan {
+    SplitPlan {
+        into,
+        files: original.files_involved.iter().map(|f| (f.clone(), None)).collect(),
+        sections: original
+            .context
+            .as_deref()
+            .map(sections)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| (s, None))
+            .collect(),
+    }
+}
+
+/// Paragraphs of a context, split on blank lines
+fn sections(context: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in context.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                out.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        out.push(current.join("\n"));
+    }
+    out
+}
+
+fn target_index(plan: &SplitPlan, target: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    plan.into
+        .iter()
+        .position(|new_id| new_id == target || ids::split_id(new_id).1 == target)
+        .ok_or_else(|| format!("'{}' is not one of the --into IDs", target).into())
+}
+
+/// Route the files at `path` (or below it, for a directory) to `target`
+fn assign_files(plan: &mut SplitPlan, path: &str, target: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let target = target_index(plan, target)?;
+    let path = path.trim_start_matches("./");
+    let mut matched = false;
+    for (file, slot) in plan.files.iter_mut() {
+        let file_path = file.trim_start_matches("./");
+        let dir = path.trim_end_matches('/');
+        if file_path == path || file_path.starts_with(&format!("{}/", dir)) {
+            *slot = Some(target);
+            matched = true;
+        }
+    }
+    if !matched {
+        return Err(format!("--assign {}: no file of the feature matches", path).into());
+    }
+    Ok(())
+}
+
+/// Ask where each item goes; flags already given are not asked again
+fn prompt_plan(
+    plan: &mut SplitPlan,
+    original: &str,
+    assigned_files: usize,
+    assigned_sections: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stdin = std::io::stdin();
+    let mut stderr = std::io::stderr();
+
+    writeln!(stderr, "Where should each part go?")?;
+    for (n, new_id) in plan.into.iter().enumerate() {
+        writeln!(stderr, "  [{}] {}", n + 1, new_id)?;
+    }
+    writeln!(stderr, "  [Enter] keep in {}", original)?;
+
+    let mut ask = |item: &str| -> Result<Option<usize>, Box<dyn std::error::Error>> {
+        loop {
+            write!(stderr, "{}: ", item)?;
+            stderr.flush()?;
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Err("Split aborted; nothing was saved".into());
+            }
+            match line.trim() {
+                "" => return Ok(None),
+                n => match n.parse::<usize>() {
+                    Ok(n) if (1..=plan.into.len()).contains(&n) => return Ok(Some(n - 1)),
+                    _ => continue,
+                },
+            }
+        }
+    };
+
+    if assigned_files == 0 {
+        for (file, slot) in plan.files.iter_mut() {
+            *slot = ask(file)?;
+        }
+    }
+    if assigned_sections == 0 {
+        for (section, slot) in plan.sections.iter_mut() {
+            let first_line = section.lines().next().unwrap_or_default();
+            *slot = ask(&format!("context \"{}\"", first_line))?;
+        }
+    }
+    Ok(())
+}
+
+/// "login-sso" (or "auth/login-sso") -> "Login sso"
+fn name_from_id(id: &str) -> String {
+    let words = ids::split_id(id).1.replace(['-', '_'], " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => id.to_string(),
+    }
+}
+
+/// Create the new features after the original and move assigned items to them
+///
+/// Returns the new features
+fn apply_split(state: &mut LegendState, index: usize, plan: &SplitPlan, now: i64) -> Vec<Feature> {
+    let original = state.features[index].clone();
+
+    let mut created: Vec<Feature> = plan
+        .into
+        .iter()
+        .map(|new_id| {
+            let mut feature = original.clone();
+            feature.id = new_id.clone();
+            feature.name = name_from_id(new_id);
+            feature.files_involved = Vec::new();
+            feature.context = None;
+            feature.created_at = now;
+            feature.last_updated = now;
+            feature.recency_score = 1.0;
+            feature.metadata.remove("split_into");
+            feature.metadata.insert("split_from".to_string(), json!(original.id));
+            feature
+        })
+        .collect();
+
+    let mut kept_files = Vec::new();
+    for (file, slot) in &plan.files {
+        match slot {
+            Some(target) => created[*target].files_involved.push(file.clone()),
+            None => kept_files.push(file.clone()),
+        }
+    }
+    let mut kept_sections = Vec::new();
+    let mut moved_sections: Vec<Vec<String>> = vec![Vec::new(); created.len()];
+    for (section, slot) in &plan.sections {
+        match slot {
+            Some(target) => moved_sections[*target].push(section.clone()),
+            None => kept_sections.push(section.clone()),
+        }
+    }
+    for (feature, moved) in created.iter_mut().zip(moved_sections) {
+        if !moved.is_empty() {
+            feature.context = Some(moved.join("\n\n"));
+        }
+    }
+
+    let kept = &mut state.features[index];
+    kept.files_involved = kept_files;
+    kept.context = (!kept_sections.is_empty()).then(|| kept_sections.join("\n\n"));
+    kept.metadata.insert("split_into".to_string(), json!(plan.into));
+    kept.last_updated = now;
+
+    for (offset, feature) in created.iter().enumerate() {
+        state.features.insert(index + 1 + offset, feature.clone());
+    }
+    state.last_updated = now;
+    created
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn original() -> Feature {
+        let mut f = Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "Everything auth".to_string());
+        f.tags = vec!["backend".to_string()];
+        f.files_involved = vec![
+            "src/auth/login.rs".to_string(),
+            "src/auth/sso/saml.rs".to_string(),
+            "src/auth/sso/oidc.rs".to_string(),
+            "src/auth/mod.rs".to_string(),
+        ];
+        f.context = Some("Password login first.\nBcrypt.\n\nSSO for enterprise.\n\nShared session layer.".to_string());
+        f
+    }
+
+    #[test]
+    fn test_sections() {
+        assert_eq!(sections("a\nb\n\n\nc\n"), vec!["a\nb", "c"]);
+        assert!(sections("  \n").is_empty());
+    }
+
+    #[test]
+    fn test_split_moves_assigned_parts() {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features = vec![original(), Feature::new("other".to_string(), "O".to_string(), "x".to_string(), "d".to_string())];
+
+        let mut plan = initial_plan(&state.features[0], vec!["auth-login".to_string(), "auth-sso".to_string()]);
+        assign_files(&mut plan, "src/auth/login.rs", "auth-login").unwrap();
+        assign_files(&mut plan, "./src/auth/sso/", "auth-sso").unwrap();
+        plan.sections[0].1 = Some(0);
+        plan.sections[1].1 = Some(1);
+        assert!(assign_files(&mut plan, "src/nope.rs", "auth-sso").is_err());
+        assert!(target_index(&plan, "billing").is_err());
+
+        let created = apply_split(&mut state, 0, &plan, 500);
+        assert_eq!(created.len(), 2);
+
+        let ids: Vec<_> = state.features.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["auth", "auth-login", "auth-sso", "other"]);
+
+        let kept = &state.features[0];
+        assert_eq!(kept.files_involved, vec!["src/auth/mod.rs"]);
+        assert_eq!(kept.context.as_deref(), Some("Shared session layer."));
+        assert_eq!(kept.metadata["split_into"], json!(["auth-login", "auth-sso"]));
+
+        let sso = &state.features[2];
+        assert_eq!(sso.name, "Auth sso");
+        assert_eq!(sso.files_involved, vec!["src/auth/sso/saml.rs", "src/auth/sso/oidc.rs"]);
+        assert_eq!(sso.context.as_deref(), Some("SSO for enterprise."));
+        assert_eq!(sso.tags, vec!["backend"]);
+        assert_eq!(sso.metadata["split_from"], json!("auth"));
+        assert_eq!(sso.created_at, 500);
+        assert_eq!(state.features[1].context.as_deref(), Some("Password login first.\nBcrypt."));
+    }
+
+    #[test]
+    fn test_check_new_ids() {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features.push(original());
+        assert!(check_new_ids(&state, &["a".to_string(), "b".to_string()]).is_ok());
+        assert!(check_new_ids(&state, &["auth".to_string()]).is_err());
+        assert!(check_new_ids(&state, &["a".to_string(), "a".to_string()]).is_err());
+        assert_eq!(name_from_id("security/login_sso"), "Login sso");
+    }
+}
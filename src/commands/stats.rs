@@ -0,0 +1,770 @@
+// Stats command - summary numbers and a week-by-week activity heatmap
+//
+// Usage:
+//   legend stats                  - summary + heatmap per domain (last 12 weeks)
+//   legend stats --by feature     - one heatmap row per feature instead
+//   legend stats --weeks <n>      - how many weeks to show
+//   legend stats --json           - the same data as JSON
+//   legend stats --cycle-time     - cycle time per domain + burndown of open features
+//   legend stats --cycle-time --csv  - the same as CSV (metric,key,value rows)
+//   legend stats --time           - estimated and logged hours per domain
+//
+// Activity comes from the journal: every feature_added, feature_changed,
+// and feature_removed event counts as one update in the week it happened.
+// The baseline event is not activity - it's what existed before history.
+//
+// Cycle time is start (first InProgress, else creation) to completion, from
+// each feature's lifecycle timestamps. Features completed before Legend
+// recorded completed_at have no cycle time and are left out.
+//
+// The burndown counts features open (created, not yet complete) at the end
+// of each week. It is read from the lifecycle timestamps, so features that
+// were removed since don't appear; an old Complete feature without
+// completed_at counts as done from its last update.
+//
+// Time totals only count features with an estimate or logged hours (see
+// `legend log-time`), so a domain nobody tracks time in doesn't show up.
+//
+// Rust concepts in this file:
+// - BTreeMap for deterministic grouping
+// - Integer division to bucket timestamps into weeks
+
+use crate::dates::{self, SECONDS_PER_DAY, SECONDS_PER_WEEK};
+use crate::exit::{self, ExitError};
+use crate::journal::{self, Event, EventKind};
+use crate::output;
+use crate::rollups::{self, Rollups};
+use crate::storage;
+use crate::text;
+use crate::theme::Theme;
+use crate::types::{current_timestamp, FeatureStatus, LegendState};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+const DEFAULT_WEEKS: usize = 12;
+
+/// Heatmap shades, from "a little" to "the busiest cell"
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+/// How heatmap rows are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum GroupBy {
+    Domain,
+    Feature,
+}
+
+/// One row of the heatmap
+#[derive(Debug, Serialize)]
+struct HeatmapRow {
+    name: String,
+    counts: Vec<u32>,
+    total: u32,
+}
+
+/// Updates per row per week, oldest week first
+#[derive(Debug, Serialize)]
+struct Heatmap {
+    by: GroupBy,
+    /// Monday of each week, "YYYY-MM-DD"
+    weeks: Vec<String>,
+    rows: Vec<HeatmapRow>,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    features: usize,
+    by_status: BTreeMap<String, usize>,
+    by_domain: BTreeMap<String, usize>,
+    journal_events: usize,
+    /// Median days from start to completion; None until something completes
+    median_cycle_days: Option<f64>,
+    /// Completed features the median is taken over
+    cycle_time_features: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    summary: Summary,
+    heatmap: Heatmap,
+}
+
+/// Cycle-time percentiles for one domain (or "all"), in days
+#[derive(Debug, Serialize)]
+struct CycleTimeRow {
+    domain: String,
+    features: usize,
+    p50_days: f64,
+    p85_days: f64,
+    p95_days: f64,
+}
+
+/// Features still open at the end of a week
+#[derive(Debug, Serialize)]
+struct BurndownPoint {
+    /// Monday of the week, "YYYY-MM-DD"
+    week: String,
+    open: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct CycleTimeReport {
+    /// Per domain, then an "all" row; empty until something completes
+    cycle_time: Vec<CycleTimeRow>,
+    burndown: Vec<BurndownPoint>,
+}
+
+/// Estimated and logged hours for one domain (or "all")
+#[derive(Debug, Serialize)]
+struct TimeRow {
+    domain: String,
+    /// Features with an estimate or logged hours
+    features: usize,
+    estimate_hours: f64,
+    logged_hours: f64,
+}
+
+/// Handle the stats command
+pub fn handle_stats(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    const USAGE: &str =
+        "Usage: legend stats [--by domain|feature] [--weeks <n>] [--json] [--cycle-time [--csv] | --time]";
+
+    let mut by = GroupBy::Domain;
+    let mut weeks = DEFAULT_WEEKS;
+    let mut json = false;
+    let mut csv = false;
+    let mut cycle_time = false;
+    let mut time = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--by" => {
+                i += 1;
+                by = match args.get(i).map(|s| s.as_str()) {
+                    Some("domain") => GroupBy::Domain,
+                    Some("feature") => GroupBy::Feature,
+                    _ => return Err(ExitError::new(exit::USAGE, format!("--by expects domain or feature\n{}", USAGE)).into()),
+                };
+            }
+            "--weeks" => {
+                i += 1;
+                weeks = args
+                    .get(i)
+                    .and_then(|v| v.parse().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, "--weeks expects a positive number"))?;
+            }
+            "--json" => json = true,
+            "--csv" => csv = true,
+            "--cycle-time" => cycle_time = true,
+            "--time" => time = true,
+            other => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into()),
+        }
+        i += 1;
+    }
+    if csv && !cycle_time {
+        return Err(ExitError::new(exit::USAGE, format!("--csv is only available with --cycle-time\n{}", USAGE)).into());
+    }
+    if time && cycle_time {
+        return Err(ExitError::new(exit::USAGE, format!("--time and --cycle-time are separate reports\n{}", USAGE)).into());
+    }
+
+    let state = storage::load_state()?;
+
+    if time {
+        let rows = time_rows(&state);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        } else if output::screen_reader() {
+            print!("{}", render_time_lines(&rows));
+        } else {
+            print_time(&rows, &Theme::load()?);
+        }
+        return Ok(());
+    }
+
+    if cycle_time {
+        let report = CycleTimeReport {
+            cycle_time: cycle_time_rows(&state),
+            burndown: build_burndown(&state, weeks, current_timestamp()),
+        };
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else if csv {
+            print!("{}", render_cycle_time_csv(&report));
+        } else if output::screen_reader() {
+            print!("{}", render_cycle_time_lines(&report));
+        } else {
+            print_cycle_time(&report, &Theme::load()?);
+        }
+        return Ok(());
+    }
+
+    let events = journal::read_events()?;
+
+    let stats = Stats {
+        summary: summarize(&state, &rollups::for_state(&storage::legend_dir(), &state), events.len()),
+        heatmap: build_heatmap(&events, by, weeks, current_timestamp()),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        let theme = Theme::load()?;
+        print_summary(&stats.summary, &theme);
+        println!();
+        if output::screen_reader() {
+            print!("{}", render_heatmap_lines(&stats.heatmap));
+        } else {
+            print_heatmap(&stats.heatmap, &theme);
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts come from the domain rollups; cycle times still need every feature
+fn summarize(state: &LegendState, rollups: &Rollups, journal_events: usize) -> Summary {
+    let mut by_status = BTreeMap::new();
+    let mut by_domain = BTreeMap::new();
+    for (domain, rollup) in &rollups.domains {
+        by_domain.insert(domain.clone(), rollup.features);
+        for (status, count) in &rollup.by_status {
+            *by_status.entry(status.clone()).or_insert(0) += count;
+        }
+    }
+
+    let cycle_times: Vec<i64> = state.features.iter().filter_map(|f| f.cycle_time()).collect();
+
+    Summary {
+        features: state.features.len(),
+        by_status,
+        by_domain,
+        journal_events,
+        median_cycle_days: median(&cycle_times).map(|secs| secs / SECONDS_PER_DAY as f64),
+        cycle_time_features: cycle_times.len(),
+    }
+}
+
+/// Median of a set of durations (seconds)
+fn median(values: &[i64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) as f64 / 2.0),
+        _ => Some(sorted[mid] as f64),
+    }
+}
+
+/// Nearest-rank percentile (0-100) of a set of durations (seconds)
+fn percentile(values: &[i64], pct: usize) -> Option<i64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Cycle-time percentiles per domain plus an overall "all" row
+fn cycle_time_rows(state: &LegendState) -> Vec<CycleTimeRow> {
+    let mut by_domain: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    for feature in &state.features {
+        if let Some(secs) = feature.cycle_time() {
+            by_domain.entry(&feature.domain).or_default().push(secs);
+        }
+    }
+    if by_domain.is_empty() {
+        return Vec::new();
+    }
+
+    let all: Vec<i64> = by_domain.values().flatten().copied().collect();
+    let row = |domain: &str, times: &[i64]| {
+        let days = |pct| percentile(times, pct).unwrap_or(0) as f64 / SECONDS_PER_DAY as f64;
+        CycleTimeRow {
+            domain: domain.to_string(),
+            features: times.len(),
+            p50_days: median(times).unwrap_or(0.0) / SECONDS_PER_DAY as f64,
+            p85_days: days(85),
+            p95_days: days(95),
+        }
+    };
+
+    let mut rows: Vec<CycleTimeRow> = by_domain.iter().map(|(domain, times)| row(domain, times)).collect();
+    rows.push(row("all", &all));
+    rows
+}
+
+/// Hours per domain plus an overall "all" row; empty when nothing is tracked
+fn time_rows(state: &LegendState) -> Vec<TimeRow> {
+    let mut by_domain: BTreeMap<&str, TimeRow> = BTreeMap::new();
+    let tracked = state.features.iter().filter(|f| f.estimate_hours.is_some() || f.logged_hours > 0.0);
+    for feature in tracked {
+        let row = by_domain.entry(&feature.domain).or_insert_with(|| TimeRow {
+            domain: feature.domain.clone(),
+            features: 0,
+            estimate_hours: 0.0,
+            logged_hours: 0.0,
+        });
+        row.features += 1;
+        row.estimate_hours += feature.estimate_hours.unwrap_or(0.0);
+        row.logged_hours += feature.logged_hours;
+    }
+    if by_domain.is_empty() {
+        return Vec::new();
+    }
+
+    let mut rows: Vec<TimeRow> = by_domain.into_values().collect();
+    let all = TimeRow {
+        domain: "all".to_string(),
+        features: rows.iter().map(|r| r.features).sum(),
+        estimate_hours: rows.iter().map(|r| r.estimate_hours).sum(),
+        logged_hours: rows.iter().map(|r| r.logged_hours).sum(),
+    };
+    rows.push(all);
+    rows
+}
+
+/// Open features at the end of each of the `weeks` weeks ending with the one containing `now`
+fn build_burndown(state: &LegendState, weeks: usize, now: i64) -> Vec<BurndownPoint> {
+    let last_week = dates::week_start(now);
+    (0..weeks)
+        .rev()
+        .map(|back| {
+            let week = last_week - back as i64 * SECONDS_PER_WEEK;
+            let end = (week + SECONDS_PER_WEEK - 1).min(now);
+            let open = state
+                .features
+                .iter()
+                .filter(|f| f.created_at <= end)
+                .filter(|f| {
+                    let done = match f.completed_at {
+                        Some(at) => Some(at),
+                        None if f.status == FeatureStatus::Complete => Some(f.last_updated),
+                        None => None,
+                    };
+                    done.is_none_or(|at| at > end)
+                })
+                .count();
+            BurndownPoint { week: dates::format_date(week), open }
+        })
+        .collect()
+}
+
+/// Bucket journal activity into the `weeks` weeks ending with the one containing `now`
+fn build_heatmap(events: &[Event], by: GroupBy, weeks: usize, now: i64) -> Heatmap {
+    let last_week = dates::week_start(now);
+    let first_week = last_week - (weeks as i64 - 1) * SECONDS_PER_WEEK;
+
+    // Removed events only carry the ID, so remember each feature's domain
+    let mut domains: HashMap<String, String> = HashMap::new();
+    let mut rows: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+
+    for event in events {
+        let (id, domain) = match &event.kind {
+            EventKind::Baseline { features } => {
+                for feature in features {
+                    domains.insert(feature.id.clone(), feature.domain.clone());
+                }
+                continue;
+            }
+            EventKind::FeatureAdded { feature } | EventKind::FeatureChanged { feature, .. } => {
+                domains.insert(feature.id.clone(), feature.domain.clone());
+                (feature.id.clone(), feature.domain.clone())
+            }
+            EventKind::FeatureRemoved { id, .. } => {
+                let domain = domains.get(id).cloned().unwrap_or_else(|| "(unknown)".to_string());
+                (id.clone(), domain)
+            }
+            EventKind::SectionsChanged(_) => continue,
+        };
+
+        let week = dates::week_start(event.timestamp);
+        if week < first_week || week > last_week {
+            continue;
+        }
+
+        let key = match by {
+            GroupBy::Domain => domain,
+            GroupBy::Feature => id,
+        };
+        let index = ((week - first_week) / SECONDS_PER_WEEK) as usize;
+        rows.entry(key).or_insert_with(|| vec![0; weeks])[index] += 1;
+    }
+
+    let mut rows: Vec<HeatmapRow> = rows
+        .into_iter()
+        .map(|(name, counts)| HeatmapRow {
+            name,
+            total: counts.iter().sum(),
+            counts,
+        })
+        .collect();
+    // Busiest first; BTreeMap order breaks ties alphabetically
+    rows.sort_by_key(|row| std::cmp::Reverse(row.total));
+
+    Heatmap {
+        by,
+        weeks: (0..weeks as i64)
+            .map(|w| dates::format_date(first_week + w * SECONDS_PER_WEEK))
+            .collect(),
+        rows,
+    }
+}
+
+/// Pick a shade for a cell relative to the busiest cell
+fn shade(count: u32, max: u32) -> char {
+    if count == 0 || max == 0 {
+        return '·';
+    }
+    let level = ((count as usize * SHADES.len()).div_ceil(max as usize)).clamp(1, SHADES.len());
+    SHADES[level - 1]
+}
+
+fn print_summary(summary: &Summary, theme: &Theme) {
+    println!("Features: {}", summary.features);
+    let statuses: Vec<String> = summary
+        .by_status
+        .iter()
+        .map(|(status, count)| {
+            let text = format!("{} {}", count, status);
+            theme.status(&FeatureStatus::from_name(status), &text)
+        })
+        .collect();
+    if !statuses.is_empty() {
+        println!("  {}", statuses.join(", "));
+    }
+    println!("Domains: {}", summary.by_domain.len());
+    println!("Journal events: {}", summary.journal_events);
+    if let Some(days) = summary.median_cycle_days {
+        println!(
+            "Cycle time: median {:.1} days over {} completed feature(s)",
+            days, summary.cycle_time_features
+        );
+    }
+}
+
+fn print_heatmap(heatmap: &Heatmap, theme: &Theme) {
+    let label = match heatmap.by {
+        GroupBy::Domain => "DOMAIN",
+        GroupBy::Feature => "FEATURE",
+    };
+    println!("Activity by week ({} weeks)", heatmap.weeks.len());
+
+    if heatmap.rows.is_empty() {
+        println!("  No journaled updates in this window.");
+        return;
+    }
+
+    let max = heatmap
+        .rows
+        .iter()
+        .flat_map(|row| row.counts.iter().copied())
+        .max()
+        .unwrap_or(0);
+
+    let header = format!("{:<20} {} TOTAL", label, " ".repeat(heatmap.weeks.len() * 2));
+    println!("{}", theme.accent(&header));
+    for row in &heatmap.rows {
+        let cells: String = row
+            .counts
+            .iter()
+            .map(|count| match *count {
+                0 => format!("{} ", shade(0, max)),
+                n => format!("{} ", theme.accent(&shade(n, max).to_string())),
+            })
+            .collect();
+        println!("{} {} {}", text::pad(&truncate(&row.name, 19), 20), cells.trim_end(), row.total);
+    }
+
+    if let (Some(first), Some(last)) = (heatmap.weeks.first(), heatmap.weeks.last()) {
+        println!("{:<20} {} .. {}   (busiest week: {} updates)", "", first, last, max);
+    }
+}
+
+/// The heatmap as labeled lines for --screen-reader: busy weeks only
+fn render_heatmap_lines(heatmap: &Heatmap) -> String {
+    let label = match heatmap.by {
+        GroupBy::Domain => "Domain",
+        GroupBy::Feature => "Feature",
+    };
+    let mut out = format!("Activity over the last {} weeks.\n", heatmap.weeks.len());
+    if heatmap.rows.is_empty() {
+        out.push_str("No journaled updates in this window.\n");
+        return out;
+    }
+
+    for row in &heatmap.rows {
+        let weeks: Vec<String> = row
+            .counts
+            .iter()
+            .zip(&heatmap.weeks)
+            .filter(|(count, _)| **count > 0)
+            .map(|(count, week)| format!("week of {}: {}", week, count))
+            .collect();
+        out.push_str(&format!(
+            "{} {}: {} updates; {}.\n",
+            label,
+            row.name,
+            row.total,
+            weeks.join(", ")
+        ));
+    }
+    out
+}
+
+const BURNDOWN_WIDTH: usize = 40;
+
+fn print_cycle_time(report: &CycleTimeReport, theme: &Theme) {
+    println!("Cycle time (days, start to completion)");
+    if report.cycle_time.is_empty() {
+        println!("  No completed features with lifecycle timestamps yet.");
+    } else {
+        println!("{}", theme.accent(&format!("{:<20} {:>8} {:>7} {:>7} {:>7}", "DOMAIN", "FEATURES", "P50", "P85", "P95")));
+        for row in &report.cycle_time {
+            println!(
+                "{} {:>8} {:>7.1} {:>7.1} {:>7.1}",
+                text::pad(&truncate(&row.domain, 19), 20),
+                row.features,
+                row.p50_days,
+                row.p85_days,
+                row.p95_days
+            );
+        }
+    }
+
+    println!();
+    println!("Open features by week ({} weeks)", report.burndown.len());
+    let max = report.burndown.iter().map(|p| p.open).max().unwrap_or(0).max(1);
+    for point in &report.burndown {
+        let bar = "█".repeat((point.open * BURNDOWN_WIDTH).div_ceil(max));
+        println!("{}  {} {}", point.week, theme.accent(&bar), point.open);
+    }
+}
+
+/// The cycle-time report as labeled lines for --screen-reader
+fn render_cycle_time_lines(report: &CycleTimeReport) -> String {
+    let mut out = String::from("Cycle time in days, start to completion.\n");
+    if report.cycle_time.is_empty() {
+        out.push_str("No completed features with lifecycle timestamps yet.\n");
+    }
+    for row in &report.cycle_time {
+        out.push_str(&format!(
+            "Domain {}: {} features; median {:.1}, 85th percentile {:.1}, 95th percentile {:.1}.\n",
+            row.domain, row.features, row.p50_days, row.p85_days, row.p95_days
+        ));
+    }
+    out.push_str(&format!("Open features over the last {} weeks.\n", report.burndown.len()));
+    for point in &report.burndown {
+        out.push_str(&format!("Week of {}: {} open.\n", point.week, point.open));
+    }
+    out
+}
+
+fn print_time(rows: &[TimeRow], theme: &Theme) {
+    println!("Time (hours)");
+    if rows.is_empty() {
+        println!("  No estimates or logged time yet. Log some with 'legend log-time <id> <hours>'.");
+        return;
+    }
+    println!("{}", theme.accent(&format!("{:<20} {:>8} {:>9} {:>9}", "DOMAIN", "FEATURES", "ESTIMATE", "LOGGED")));
+    for row in rows {
+        println!(
+            "{} {:>8} {:>9.1} {:>9.1}",
+            text::pad(&truncate(&row.domain, 19), 20),
+            row.features,
+            row.estimate_hours,
+            row.logged_hours
+        );
+    }
+}
+
+/// The time report as labeled lines for --screen-reader
+fn render_time_lines(rows: &[TimeRow]) -> String {
+    if rows.is_empty() {
+        return "No estimates or logged time yet.\n".to_string();
+    }
+    rows.iter()
+        .map(|row| {
+            format!(
+                "Domain {}: {} features; {:.1} hours estimated, {:.1} logged.\n",
+                row.domain, row.features, row.estimate_hours, row.logged_hours
+            )
+        })
+        .collect()
+}
+
+/// The cycle-time report as `metric,key,value` rows, one table for spreadsheets
+fn render_cycle_time_csv(report: &CycleTimeReport) -> String {
+    let mut out = String::from("metric,key,value\n");
+    for row in &report.cycle_time {
+        let domain = csv_field(&row.domain);
+        out.push_str(&format!("cycle_time_features,{},{}\n", domain, row.features));
+        out.push_str(&format!("cycle_time_p50_days,{},{:.2}\n", domain, row.p50_days));
+        out.push_str(&format!("cycle_time_p85_days,{},{:.2}\n", domain, row.p85_days));
+        out.push_str(&format!("cycle_time_p95_days,{},{:.2}\n", domain, row.p95_days));
+    }
+    for point in &report.burndown {
+        out.push_str(&format!("open_features,{},{}\n", point.week, point.open));
+    }
+    out
+}
+
+/// Quote a CSV field when it needs it
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    text::truncate(s, max, "…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Feature;
+
+    fn feature(id: &str, domain: &str) -> Feature {
+        Feature::new(id.to_string(), id.to_string(), domain.to_string(), "d".to_string())
+    }
+
+    fn event(seq: u64, timestamp: i64, kind: EventKind) -> Event {
+        Event { seq, timestamp, kind }
+    }
+
+    #[test]
+    fn test_build_heatmap() {
+        let now = dates::parse_date("2024-03-20").unwrap();
+        let this_week = dates::week_start(now);
+        let last_week = this_week - SECONDS_PER_WEEK;
+
+        let events = vec![
+            event(1, last_week, EventKind::Baseline { features: vec![feature("a", "api")] }),
+            event(2, last_week + 10, EventKind::FeatureAdded { feature: feature("b", "ui") }),
+            event(3, this_week, EventKind::FeatureChanged {
+                id: "a".to_string(),
+                changes: Vec::new(),
+                feature: feature("a", "api"),
+            }),
+            event(4, this_week + 5, EventKind::FeatureRemoved { id: "a".to_string(), name: "a".to_string() }),
+            // Too old for a 2-week window
+            event(5, last_week - SECONDS_PER_WEEK, EventKind::FeatureAdded { feature: feature("c", "ui") }),
+        ];
+
+        let heatmap = build_heatmap(&events, GroupBy::Domain, 2, now);
+        assert_eq!(heatmap.weeks, vec!["2024-03-11", "2024-03-18"]);
+        assert_eq!(heatmap.rows[0].name, "api");
+        assert_eq!(heatmap.rows[0].counts, vec![0, 2]);
+        assert_eq!(heatmap.rows[1].name, "ui");
+        assert_eq!(heatmap.rows[1].counts, vec![1, 0]);
+
+        let by_feature = build_heatmap(&events, GroupBy::Feature, 2, now);
+        assert_eq!(by_feature.rows.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+
+        let lines = render_heatmap_lines(&heatmap);
+        assert!(lines.contains("Domain api: 2 updates; week of 2024-03-18: 2.\n"));
+        assert!(lines.contains("Domain ui: 1 updates; week of 2024-03-11: 1.\n"));
+    }
+
+    #[test]
+    fn test_summary_cycle_time() {
+        let mut state = LegendState::new("Demo".to_string());
+        for (id, days) in [("a", 1), ("b", 3), ("c", 10)] {
+            let mut f = feature(id, "api");
+            f.started_at = Some(0);
+            f.completed_at = Some(days * SECONDS_PER_DAY);
+            f.status = FeatureStatus::Complete;
+            state.features.push(f);
+        }
+        state.features.push(feature("open", "api"));
+
+        let summary = summarize(&state, &Rollups::build(&state), 0);
+        assert_eq!(summary.by_status["Complete"], 3);
+        assert_eq!(summary.by_domain["api"], 4);
+        assert_eq!(summary.cycle_time_features, 3);
+        assert_eq!(summary.median_cycle_days, Some(3.0));
+        assert_eq!(median(&[1, 2, 3, 4]), Some(2.5));
+        assert_eq!(median(&[]), None);
+    }
+
+    #[test]
+    fn test_cycle_time_rows() {
+        let mut state = LegendState::new("Demo".to_string());
+        for (id, domain, days) in [("a", "api", 1), ("b", "api", 3), ("c", "ui", 10)] {
+            let mut f = feature(id, domain);
+            f.started_at = Some(0);
+            f.completed_at = Some(days * SECONDS_PER_DAY);
+            state.features.push(f);
+        }
+
+        let rows = cycle_time_rows(&state);
+        let names: Vec<_> = rows.iter().map(|r| r.domain.as_str()).collect();
+        assert_eq!(names, vec!["api", "ui", "all"]);
+        assert_eq!((rows[0].features, rows[0].p50_days, rows[0].p95_days), (2, 2.0, 3.0));
+        assert_eq!((rows[2].features, rows[2].p50_days, rows[2].p85_days), (3, 3.0, 10.0));
+        assert_eq!(percentile(&[5, 1, 3, 2, 4], 50), Some(3));
+        assert_eq!(percentile(&[], 95), None);
+
+        let csv = render_cycle_time_csv(&CycleTimeReport { cycle_time: rows, burndown: Vec::new() });
+        assert!(csv.starts_with("metric,key,value\ncycle_time_features,api,2\n"));
+        assert!(csv.contains("cycle_time_p95_days,all,10.00\n"));
+        assert!(cycle_time_rows(&LegendState::new("Empty".to_string())).is_empty());
+    }
+
+    #[test]
+    fn test_time_rows() {
+        let mut state = LegendState::new("Test".to_string());
+        let mut auth = feature("auth", "security");
+        auth.estimate_hours = Some(8.0);
+        auth.logged_hours = 3.5;
+        let mut billing = feature("billing", "payments");
+        billing.logged_hours = 2.0;
+        state.features = vec![auth, billing, feature("untracked", "payments")];
+
+        let rows = time_rows(&state);
+        let summary: Vec<(&str, usize, f64, f64)> =
+            rows.iter().map(|r| (r.domain.as_str(), r.features, r.estimate_hours, r.logged_hours)).collect();
+        assert_eq!(summary, vec![("payments", 1, 0.0, 2.0), ("security", 1, 8.0, 3.5), ("all", 2, 8.0, 5.5)]);
+        assert!(time_rows(&LegendState::new("Empty".to_string())).is_empty());
+    }
+
+    #[test]
+    fn test_build_burndown() {
+        let now = dates::parse_date("2024-03-20").unwrap();
+        let this_week = dates::week_start(now);
+        let last_week = this_week - SECONDS_PER_WEEK;
+
+        let mut state = LegendState::new("Demo".to_string());
+        let mut done = feature("done", "api");
+        done.created_at = last_week - SECONDS_PER_WEEK;
+        done.completed_at = Some(this_week + 10);
+        let mut legacy = feature("legacy", "api");
+        legacy.created_at = last_week - SECONDS_PER_WEEK;
+        legacy.status = FeatureStatus::Complete;
+        legacy.last_updated = last_week + 10;
+        let mut new = feature("new", "ui");
+        new.created_at = this_week + 20;
+        state.features.extend([done, legacy, new]);
+
+        let burndown = build_burndown(&state, 3, now);
+        let points: Vec<_> = burndown.iter().map(|p| (p.week.as_str(), p.open)).collect();
+        assert_eq!(points, vec![("2024-03-04", 2), ("2024-03-11", 1), ("2024-03-18", 1)]);
+
+        let lines = render_cycle_time_lines(&CycleTimeReport { cycle_time: Vec::new(), burndown });
+        assert!(lines.contains("No completed features"));
+        assert!(lines.contains("Week of 2024-03-11: 1 open.\n"));
+    }
+
+    #[test]
+    fn test_shade() {
+        assert_eq!(shade(0, 8), '·');
+        assert_eq!(shade(1, 8), '░');
+        assert_eq!(shade(8, 8), '█');
+        assert_eq!(shade(5, 8), '▓');
+    }
+}
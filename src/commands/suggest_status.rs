@@ -0,0 +1,278 @@
+// Suggest-status command - status changes the git history hints at
+//
+// Usage:
+//   legend suggest-status                  - list suggestions for review
+//   legend suggest-status --apply          - apply every listed suggestion
+//   legend suggest-status --only start     - just one kind (start | stale)
+//   legend suggest-status --json           - suggestions as JSON
+//   legend suggest-status --stale-days <n> --recent-days <n> --min-commits <n>
+//
+// Two heuristics, both from the files a feature tracks:
+// - stale?  InProgress, but none of its files has been committed to in
+//           --stale-days (default 21). Suggests Blocked, so it stands out
+//           until someone says why.
+// - start?  Pending, but its files show --min-commits (default 3) commits
+//           in the last --recent-days (default 14). Suggests InProgress.
+//
+// Features without files are skipped - there's nothing to compare. These
+// are hints: nothing changes without --apply.
+
+use crate::dates::SECONDS_PER_DAY;
+use crate::exit::{self, ExitError};
+use crate::git::{self, Commit};
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::text;
+use crate::types::{current_timestamp, Feature, FeatureStatus};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+const USAGE: &str = "Usage: legend suggest-status [--apply] [--json] [--only start|stale] [--stale-days <n>] [--recent-days <n>] [--min-commits <n>]";
+
+/// What the heuristic noticed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    Stale,
+    Start,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Stale => "stale?",
+            Kind::Start => "start?",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Suggestion {
+    id: String,
+    kind: Kind,
+    current: FeatureStatus,
+    suggested: FeatureStatus,
+    reason: String,
+}
+
+/// Thresholds, in days and commits
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    stale_days: i64,
+    recent_days: i64,
+    min_commits: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            stale_days: 21,
+            recent_days: 14,
+            min_commits: 3,
+        }
+    }
+}
+
+/// Handle the suggest-status command
+pub fn handle_suggest_status(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = Options::default();
+    let mut apply = false;
+    let mut json_out = false;
+    let mut only = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--apply" => apply = true,
+            "--json" => json_out = true,
+            "--only" => {
+                i += 1;
+                only = Some(match args.get(i).map(String::as_str) {
+                    Some("start") => Kind::Start,
+                    Some("stale") => Kind::Stale,
+                    _ => return Err(ExitError::new(exit::USAGE, format!("--only expects start or stale\n{}", USAGE)).into()),
+                });
+            }
+            flag @ ("--stale-days" | "--recent-days" | "--min-commits") => {
+                i += 1;
+                let n: i64 = args
+                    .get(i)
+                    .and_then(|v| v.parse().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| ExitError::new(exit::USAGE, format!("{} expects a positive number\n{}", flag, USAGE)))?;
+                match flag {
+                    "--stale-days" => options.stale_days = n,
+                    "--recent-days" => options.recent_days = n,
+                    _ => options.min_commits = n as usize,
+                }
+            }
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+        i += 1;
+    }
+
+    let _lock = storage::lock_state()?;
+    let before = storage::load_state()?;
+    let now = current_timestamp();
+
+    let recent = git::commits_since(now - options.recent_days * SECONDS_PER_DAY)?;
+    let mut last_changes = HashMap::new();
+    for feature in &before.features {
+        if feature.status == FeatureStatus::InProgress && !feature.files_involved.is_empty() {
+            last_changes.insert(feature.id.clone(), git::last_change(&feature.files_involved)?);
+        }
+    }
+
+    let mut suggestions = suggest(&before.features, &recent, &last_changes, now, options);
+    if let Some(kind) = only {
+        suggestions.retain(|s| s.kind == kind);
+    }
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&suggestions)?);
+    } else if suggestions.is_empty() {
+        output::info("No status suggestions: tracked files and statuses agree.");
+    } else {
+        print!("{}", render_suggestions(&suggestions));
+    }
+
+    if !apply || suggestions.is_empty() {
+        if !apply && !json_out && !suggestions.is_empty() {
+            output::info("Review the list, then run with --apply (and --only, to pick a kind).");
+        }
+        return Ok(());
+    }
+
+    let mut state = before.clone();
+    for suggestion in &suggestions {
+        if let Some(feature) = state.find_feature_mut(&suggestion.id) {
+            feature.set_status(suggestion.suggested.clone(), now);
+            feature.last_updated = now;
+        }
+    }
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Applied {} status change(s).", suggestions.len()),
+        json!({"applied": suggestions.len()}),
+    );
+    Ok(())
+}
+
+/// Compare features with git activity
+///
+/// `recent` holds the commits in the recent window; `last_changes` the
+/// newest commit time for each InProgress feature's files
+fn suggest(
+    features: &[Feature],
+    recent: &[Commit],
+    last_changes: &HashMap<String, Option<i64>>,
+    now: i64,
+    options: Options,
+) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+
+    for feature in features.iter().filter(|f| !f.files_involved.is_empty()) {
+        match feature.status {
+            FeatureStatus::InProgress => {
+                let last = last_changes.get(&feature.id).copied().flatten();
+                let reason = match last {
+                    Some(at) if now - at >= options.stale_days * SECONDS_PER_DAY => {
+                        format!("files untouched for {} days", (now - at) / SECONDS_PER_DAY)
+                    }
+                    None => "files never committed".to_string(),
+                    Some(_) => continue,
+                };
+                suggestions.push(Suggestion {
+                    id: feature.id.clone(),
+                    kind: Kind::Stale,
+                    current: feature.status.clone(),
+                    suggested: FeatureStatus::Blocked,
+                    reason,
+                });
+            }
+            FeatureStatus::Pending if !feature.planned => {
+                let commits = recent
+                    .iter()
+                    .filter(|c| c.files.iter().any(|f| feature.involves_file(f)))
+                    .count();
+                if commits >= options.min_commits {
+                    suggestions.push(Suggestion {
+                        id: feature.id.clone(),
+                        kind: Kind::Start,
+                        current: feature.status.clone(),
+                        suggested: FeatureStatus::InProgress,
+                        reason: format!("{} commits in the last {} days", commits, options.recent_days),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    suggestions
+}
+
+/// `stale?  auth   InProgress -> Blocked   files untouched for 35 days`
+fn render_suggestions(suggestions: &[Suggestion]) -> String {
+    let width = suggestions.iter().map(|s| text::width(&s.id)).max().unwrap_or(0);
+    suggestions
+        .iter()
+        .map(|s| {
+            let change = format!("{:?} -> {:?}", s.current, s.suggested);
+            format!("{}  {}  {:<24}  {}\n", s.kind.label(), text::pad(&s.id, width), change, s.reason)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, status: FeatureStatus, file: &str) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), "core".to_string(), "d".to_string());
+        f.status = status;
+        f.files_involved = vec![file.to_string()];
+        f
+    }
+
+    fn commit(file: &str) -> Commit {
+        Commit { hash: "abc".to_string(), subject: "s".to_string(), files: vec![file.to_string()] }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let now = 100 * SECONDS_PER_DAY;
+        let features = vec![
+            feature("old", FeatureStatus::InProgress, "src/old.rs"),
+            feature("fresh", FeatureStatus::InProgress, "src/fresh.rs"),
+            feature("busy", FeatureStatus::Pending, "src/busy/"),
+            feature("quiet", FeatureStatus::Pending, "src/quiet.rs"),
+            feature("done", FeatureStatus::Complete, "src/busy/a.rs"),
+        ];
+        let recent = vec![commit("src/busy/a.rs"), commit("src/busy/b.rs"), commit("src/busy/a.rs"), commit("src/quiet.rs")];
+        let last_changes: HashMap<String, Option<i64>> = [
+            ("old".to_string(), Some(now - 30 * SECONDS_PER_DAY)),
+            ("fresh".to_string(), Some(now - SECONDS_PER_DAY)),
+        ]
+        .into_iter()
+        .collect();
+
+        let suggestions = suggest(&features, &recent, &last_changes, now, Options::default());
+        let summary: Vec<_> = suggestions.iter().map(|s| (s.id.as_str(), s.kind, s.suggested.clone())).collect();
+        assert_eq!(
+            summary,
+            vec![("old", Kind::Stale, FeatureStatus::Blocked), ("busy", Kind::Start, FeatureStatus::InProgress)]
+        );
+        assert_eq!(suggestions[0].reason, "files untouched for 30 days");
+        assert_eq!(suggestions[1].reason, "3 commits in the last 14 days");
+
+        let text = render_suggestions(&suggestions);
+        assert!(text.starts_with("stale?  old   InProgress -> Blocked"));
+    }
+}
@@ -0,0 +1,87 @@
+// Sync command - the queue of journal events waiting for the sync remote
+//
+// Usage:
+//   legend sync status [--json]   - what's queued and how the last push went
+//   legend sync flush             - push the queue now
+//
+// Pushes also happen after every other command (see src/sync.rs); flush
+// is for not waiting out the retry delay after the network comes back.
+
+use crate::config;
+use crate::dates;
+use crate::exit::{self, ExitError};
+use crate::storage;
+use crate::sync;
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend sync <status [--json]|flush>";
+
+/// Handle the sync command
+pub fn handle_sync(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (command, rest) = args.split_first().ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+    if !storage::is_initialized() {
+        return Err(ExitError::new(exit::NOT_INITIALIZED, "Legend not initialized. Run 'legend init' first.").into());
+    }
+    match (command.as_str(), rest) {
+        ("status", []) => status(false),
+        ("status", [flag]) if flag == "--json" => status(true),
+        ("flush", []) => flush(),
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn status(json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = config::load_config()?;
+    let command = sync::push_command(&config);
+    let status = sync::load_status()?;
+    let queued = sync::queued(&status)?;
+
+    if json_out {
+        let report = json!({
+            "configured": command.is_some(),
+            "queued": queued.len(),
+            "oldest_queued": queued.first().map(|e| e.timestamp),
+            "pushed_seq": status.pushed_seq,
+            "last_attempt": status.last_attempt,
+            "last_push": status.last_push,
+            "last_error": status.last_error,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if command.is_none() {
+        println!("Sync is not set up (set sync.push in .legend/config.toml)");
+        if queued.is_empty() {
+            return Ok(());
+        }
+    }
+    match queued.first() {
+        None => println!("Nothing queued: the remote has every change"),
+        Some(oldest) => println!(
+            "{} change(s) queued, oldest from {}",
+            queued.len(),
+            dates::format_datetime(oldest.timestamp)
+        ),
+    }
+    if let Some(pushed) = status.last_push {
+        println!("  last push: {}", dates::format_datetime(pushed));
+    }
+    if let (Some(error), Some(attempt)) = (&status.last_error, status.last_attempt) {
+        println!("  last attempt failed at {}: {}", dates::format_datetime(attempt), error);
+        println!("  retried after a later command, or now with legend sync flush");
+    }
+    Ok(())
+}
+
+fn flush() -> Result<(), Box<dyn std::error::Error>> {
+    let config = config::load_config()?;
+    let command = sync::push_command(&config)
+        .ok_or("Sync is not set up: set sync.push in .legend/config.toml to the command that pushes")?;
+    match sync::flush(command) {
+        Ok(0) => println!("Nothing queued"),
+        Ok(n) => println!("✓ Pushed {} change(s)", n),
+        Err(e) => return Err(format!("Push failed, changes stay queued: {}", e).into()),
+    }
+    Ok(())
+}
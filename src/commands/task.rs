@@ -0,0 +1,164 @@
+// Task command - a checklist inside a feature
+//
+// Usage:
+//   legend task add <feature-id> "<title>"      - new subtask (prints its number)
+//   legend task done <feature-id> <n> [<n>...]  - check subtasks off
+//   legend task list <feature-id> [--json]      - the checklist
+//
+// Subtasks are numbered per feature and never renumbered, so "task 3"
+// keeps meaning the same thing. `legend show` prints done/total next to
+// each feature that has any.
+
+use crate::exit::{self, ExitError};
+use crate::ids;
+use crate::journal;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Feature, Subtask};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend task add <feature-id> \"<title>\"\n       legend task done <feature-id> <n> [<n>...]\n       legend task list <feature-id> [--json]";
+
+/// Handle the task command
+pub fn handle_task(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("add"), Some(id)) if args.len() == 3 => add(id, &args[2]),
+        (Some("done"), Some(id)) if args.len() >= 3 => done(id, &args[2..]),
+        (Some("list"), Some(id)) => {
+            let json_out = match &args[2..] {
+                [] => false,
+                [flag] if flag == "--json" => true,
+                _ => return Err(ExitError::new(exit::USAGE, USAGE).into()),
+            };
+            list(id, json_out)
+        }
+        _ => Err(ExitError::new(exit::USAGE, USAGE).into()),
+    }
+}
+
+fn add(id: &str, title: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if title.trim().is_empty() {
+        return Err(ExitError::new(exit::USAGE, format!("The subtask needs a title\n{}", USAGE)).into());
+    }
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &mut state.features[index];
+    let number = add_subtask(feature, title.trim(), current_timestamp());
+    let feature_id = feature.id.clone();
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("Added task {} to {}.", number, feature_id),
+        json!({"id": feature_id, "task": number}),
+    );
+    Ok(())
+}
+
+fn done(id: &str, numbers: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let numbers: Vec<u32> = numbers
+        .iter()
+        .map(|n| n.parse().map_err(|_| ExitError::new(exit::USAGE, format!("'{}' is not a task number\n{}", n, USAGE))))
+        .collect::<Result<_, _>>()?;
+
+    let _lock = storage::lock_state()?;
+    let mut state = storage::load_state()?;
+    let before = state.clone();
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &mut state.features[index];
+    complete_subtasks(feature, &numbers, current_timestamp())?;
+    let feature_id = feature.id.clone();
+    let (finished, total) = feature.subtask_progress().unwrap_or_default();
+
+    state.touch();
+    journal::commit(&before, &state)?;
+
+    output::diag(
+        output::Level::Info,
+        &format!("{}: {}/{} tasks done.", feature_id, finished, total),
+        json!({"id": feature_id, "done": finished, "total": total}),
+    );
+    Ok(())
+}
+
+fn list(id: &str, json_out: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let state = storage::load_state()?;
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &state.features[index];
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&feature.subtasks)?);
+    } else if feature.subtasks.is_empty() {
+        output::info(&format!("{} has no tasks. Add one with 'legend task add {} \"<title>\"'.", feature.id, feature.id));
+    } else {
+        print!("{}", render_subtasks(feature));
+    }
+    Ok(())
+}
+
+/// Append a subtask numbered after the highest so far; returns its number
+fn add_subtask(feature: &mut Feature, title: &str, now: i64) -> u32 {
+    let number = feature.subtasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+    feature.subtasks.push(Subtask {
+        id: number,
+        title: title.to_string(),
+        done: false,
+        created_at: now,
+        completed_at: None,
+    });
+    feature.last_updated = now;
+    number
+}
+
+/// Check off subtasks; fails, changing nothing, if a number is unknown
+fn complete_subtasks(feature: &mut Feature, numbers: &[u32], now: i64) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(missing) = numbers.iter().find(|n| !feature.subtasks.iter().any(|t| t.id == **n)) {
+        return Err(format!("{} has no task {}", feature.id, missing).into());
+    }
+    for task in feature.subtasks.iter_mut().filter(|t| numbers.contains(&t.id) && !t.done) {
+        task.done = true;
+        task.completed_at = Some(now);
+    }
+    feature.last_updated = now;
+    Ok(())
+}
+
+/// `[x] 1  Write the migration` lines and a done/total footer
+fn render_subtasks(feature: &Feature) -> String {
+    let width = feature.subtasks.iter().map(|t| t.id.to_string().len()).max().unwrap_or(1);
+    let mut out = String::new();
+    for task in &feature.subtasks {
+        let mark = if task.done { "x" } else { " " };
+        out.push_str(&format!("[{}] {:>width$}  {}\n", mark, task.id, task.title, width = width));
+    }
+    let (finished, total) = feature.subtask_progress().unwrap_or_default();
+    out.push_str(&format!("{}/{} done\n", finished, total));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_complete_subtasks() {
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "d".to_string());
+        assert_eq!(add_subtask(&mut feature, "Schema", 10), 1);
+        assert_eq!(add_subtask(&mut feature, "Endpoints", 10), 2);
+        feature.subtasks.remove(0);
+        assert_eq!(add_subtask(&mut feature, "Docs", 10), 3);
+
+        assert_eq!(complete_subtasks(&mut feature, &[1], 20).unwrap_err().to_string(), "auth has no task 1");
+        assert_eq!(feature.subtask_progress(), Some((0, 2)));
+
+        complete_subtasks(&mut feature, &[3], 20).unwrap();
+        assert_eq!(feature.subtask_progress(), Some((1, 2)));
+        assert_eq!(feature.subtasks[1].completed_at, Some(20));
+        assert_eq!(render_subtasks(&feature), "[ ] 2  Endpoints\n[x] 3  Docs\n1/2 done\n");
+    }
+}
@@ -3,6 +3,10 @@
 // This is the WRITE PATH (100-500ms acceptable)
 // Called after Claude finishes responding, so latency is hidden
 //
+// A save rewrites the whole snapshot, however few features changed;
+// the only shortcut is skipping the save when the update changed nothing
+// (see PLAN.md for why there is no partial write).
+//
 // Rust concepts in this file:
 // - std::io::stdin() for reading input
 // - HashMap for O(1) lookups during merge
@@ -10,12 +14,20 @@
 // - Iterators and closures for data transformation
 // - Time handling for recency scores
 
-use crate::storage::{load_state, save_state};
-use crate::types::{Feature, FeatureStatus, LegendState};
+use crate::config::{self, RecencyConfig};
+use crate::dates;
+use crate::deps;
+use crate::diff;
+use crate::git;
+use crate::hierarchy;
+use crate::ids;
+use crate::journal;
+use crate::storage::{load_state, lock_state};
+use crate::validators;
+use crate::types::{current_timestamp, Feature, FeatureStatus, LegendState, Metadata, Priority};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::io::{self, Read};
-use std::time::{SystemTime, UNIX_EPOCH};
 
 // Update struct - what Claude sends us via stdin
 //
@@ -31,6 +43,11 @@ pub struct Update {
     // Optional: features to remove by ID
     #[serde(default)]
     pub remove_features: Vec<String>,
+
+    // Optional: who is writing ("claude-code", "aider", "human"), stored
+    // as last_updated_by on every feature this update touches
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 // FeatureUpdate - a single feature being added or updated
@@ -44,9 +61,41 @@ pub struct FeatureUpdate {
     pub domain: Option<String>,
     pub description: Option<String>,
     pub status: Option<FeatureStatus>,
+    pub priority: Option<Priority>,
+    // New features default to `git config user.name`
+    pub owner: Option<String>,
+    // Required when the status becomes Blocked, and only accepted while it
+    // is Blocked; blocked_by must be a known feature ID ("" clears it)
+    pub blocked_reason: Option<String>,
+    pub blocked_by: Option<String>,
     pub tags: Option<Vec<String>>,
     pub context: Option<String>,
     pub files_involved: Option<Vec<String>>,
+    // Must exist and not lead back to this feature; "" clears it
+    pub parent_id: Option<String>,
+    // Replaces the list; every ID must exist and no cycle may form
+    pub depends_on: Option<Vec<String>>,
+    // Merged key by key into the existing metadata; null deletes a key
+    pub metadata: Option<Metadata>,
+    // Hours expected; logged hours only grow through `legend log-time`
+    pub estimate_hours: Option<f64>,
+    // "YYYY-MM-DD"; "" clears it
+    #[serde(default, deserialize_with = "due_date_format")]
+    pub due_date: Option<Option<i64>>,
+    // 1-5 each; see `legend next`
+    pub complexity: Option<u8>,
+    pub risk: Option<u8>,
+}
+
+// due_date as sent (a date string) -> Some(timestamp), or Some(None) to clear
+fn due_date_format<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Option<i64>>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    if value.is_empty() {
+        return Ok(Some(None));
+    }
+    dates::parse_date(&value)
+        .map(|due| Some(Some(due)))
+        .map_err(|e| serde::de::Error::custom(format!("due_date: {}", e)))
 }
 
 /// Handle the update command
@@ -55,9 +104,9 @@ pub struct FeatureUpdate {
 /// 1. Read JSON from stdin
 /// 2. Parse into Update struct
 /// 3. Load existing state
-/// 4. Merge updates into state
+/// 4. Merge updates into state (and run any configured validators)
 /// 5. Recalculate recency scores
-/// 6. Save state back to disk
+/// 6. Append the changes to the journal and save state back to disk
 pub fn handle_update() -> Result<(), Box<dyn std::error::Error>> {
     // Step 1: Read JSON from stdin
     // This allows piping: echo '{"features": [...]}' | legend update
@@ -73,20 +122,56 @@ pub fn handle_update() -> Result<(), Box<dyn std::error::Error>> {
 
     // Step 2: Parse JSON into Update struct
     // serde_json::from_str automatically deserializes based on the type
-    let update: Update = serde_json::from_str(&input)
+    let mut update: Update = serde_json::from_str(&input)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
     // Step 3: Load existing state
+    // Locked until we return, so a parallel update (another hook) can't
+    // save in between and lose its features or ours.
+    // Keep a copy so the journal can record what this update changed
+    let _lock = lock_state()?;
     let mut state = load_state()?;
+    let before = state.clone();
 
     // Step 4: Merge updates into state
-    merge_updates(&mut state, update)?;
+    // Settings (ID namespacing, recency half-lives) come from .legend/config.toml
+    let config = config::load_config()?;
+    // A status outside the built-in four must be declared in statuses.custom
+    for feature in &mut update.features {
+        if let Some(status) = &feature.status {
+            let status = config::resolve_status(status, &config).map_err(|e| format!("Feature '{}': {}", feature.id, e))?;
+            feature.status = Some(status);
+        }
+    }
+    merge_updates(&mut state, update, config::namespaced_ids(&config)?)?;
+
+    // Nothing touched (e.g. a hook sending an empty update): skip the save.
+    // A change is journaled, and rewrites the whole snapshot unless
+    // storage.snapshot_every lets it wait (see journal.rs).
+    if !features_touched(&before, &state) {
+        println!(
+            "State unchanged: {} features total",
+            state.features.len()
+        );
+        return Ok(());
+    }
+
+    // Organization rules from [validators] may reject the change
+    let checks = validators::from_config(&config);
+    if !checks.is_empty() {
+        let payload = validators::diff_payload(&diff::diff_states(&before, &state));
+        validators::run_validators(&checks, &payload)?;
+    }
 
     // Step 5: Recalculate recency scores for all features
-    recalculate_recency_scores(&mut state);
+    // Half-lives default to 7 days when not configured
+    let recency = RecencyConfig::from_config(&config)?;
+    recalculate_recency_scores(&mut state, &recency);
 
-    // Step 6: Save state back to disk
-    save_state(&state)?;
+    // Step 6: Journal the changes, then save state back to disk
+    // (journal first, so a crash in between is replayed on the next load;
+    // with storage.snapshot_every the save waits for more changes)
+    journal::commit_deferred(&before, &state, config::snapshot_every(&config)?)?;
 
     // Report what we did
     println!(
@@ -97,6 +182,21 @@ pub fn handle_update() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Did the merge add, remove, change, or touch any feature?
+///
+/// "Touched" covers an update that re-sends identical values - that still
+/// bumps last_updated (and so recency), which is worth persisting.
+fn features_touched(before: &LegendState, after: &LegendState) -> bool {
+    if !diff::diff_states(before, after).is_empty() {
+        return true;
+    }
+    before
+        .features
+        .iter()
+        .zip(&after.features)
+        .any(|(old, new)| old.last_updated != new.last_updated)
+}
+
 /// Merge incoming updates into existing state
 ///
 /// Strategy:
@@ -105,9 +205,13 @@ pub fn handle_update() -> Result<(), Box<dyn std::error::Error>> {
 ///   - If exists: update only the provided fields
 ///   - If new: create with required fields, defaults for rest
 /// - Remove any features in the remove list
+///
+/// With `namespace` on, IDs may be bare slugs (see ids.rs) and new
+/// features get a `domain/` prefix
 fn merge_updates(
     state: &mut LegendState,
     update: Update,
+    namespace: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let now = current_timestamp();
 
@@ -121,15 +225,45 @@ fn merge_updates(
         .map(|(i, f)| (f.id.clone(), i))
         .collect();
 
+    // Owner of new features that don't name one; git is asked at most once
+    let mut default_owner: Option<Option<String>> = None;
+
     // Process each feature update
-    for feature_update in update.features {
-        if let Some(&index) = id_to_index.get(&feature_update.id) {
+    for mut feature_update in update.features {
+        if feature_update.estimate_hours.is_some_and(|h| !h.is_finite() || h < 0.0) {
+            return Err(format!("Feature '{}': estimate_hours must be zero or more", feature_update.id).into());
+        }
+        for (field, value) in [("complexity", feature_update.complexity), ("risk", feature_update.risk)] {
+            if value.is_some_and(|v| !(1..=5).contains(&v)) {
+                return Err(format!("Feature '{}': {} must be 1 to 5", feature_update.id, field).into());
+            }
+        }
+        let mut index = id_to_index.get(&feature_update.id).copied();
+        if index.is_none() && namespace {
+            index = ids::resolve(&state.features, &feature_update.id);
+            if index.is_none() {
+                if let Some(domain) = &feature_update.domain {
+                    feature_update.id = ids::namespaced(domain, &feature_update.id);
+                    index = id_to_index.get(&feature_update.id).copied();
+                }
+            }
+        }
+
+        let current = index.map(|i| &state.features[i].status);
+        check_blocked(&feature_update, current).map_err(|e| format!("Feature '{}': {}", feature_update.id, e))?;
+
+        if let Some(index) = index {
             // Feature exists - update it in place
             let existing = &mut state.features[index];
             apply_update(existing, feature_update, now);
+            existing.last_updated_by = update.source.clone();
         } else {
             // New feature - create it
-            let new_feature = create_feature_from_update(feature_update, now)?;
+            if feature_update.owner.is_none() {
+                feature_update.owner = default_owner.get_or_insert_with(git::user_name).clone();
+            }
+            let mut new_feature = create_feature_from_update(feature_update, now)?;
+            new_feature.last_updated_by = update.source.clone();
             let new_index = state.features.len();
             id_to_index.insert(new_feature.id.clone(), new_index);
             state.features.push(new_feature);
@@ -140,12 +274,66 @@ fn merge_updates(
     // We need to filter, not iterate and remove (borrow checker!)
     if !update.remove_features.is_empty() {
         // Create a set for O(1) removal checks
-        let remove_set: std::collections::HashSet<_> =
-            update.remove_features.into_iter().collect();
+        let remove_set: std::collections::HashSet<_> = update
+            .remove_features
+            .into_iter()
+            .map(|id| match ids::resolve(&state.features, &id) {
+                Some(index) if namespace => state.features[index].id.clone(),
+                _ => id,
+            })
+            .collect();
+
+        // Removing a feature also removes the edges pointing at it,
+        // and takes it out of any milestone, goal, or the focus stack
+        state.remove_features(&remove_set);
+    }
 
-        // retain() keeps elements where the closure returns true
-        state.features.retain(|f| !remove_set.contains(&f.id));
+    // Dependencies, parents, and blockers may name features by bare slug, like update IDs
+    if namespace {
+        let resolved: Vec<Vec<String>> = state
+            .features
+            .iter()
+            .map(|f| {
+                f.depends_on
+                    .iter()
+                    .map(|dep| match ids::resolve(&state.features, dep) {
+                        Some(index) => state.features[index].id.clone(),
+                        None => dep.clone(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let parents: Vec<Option<String>> = state
+            .features
+            .iter()
+            .map(|f| {
+                f.parent_id.as_ref().map(|parent| match ids::resolve(&state.features, parent) {
+                    Some(index) => state.features[index].id.clone(),
+                    None => parent.clone(),
+                })
+            })
+            .collect();
+        let blockers: Vec<Option<String>> = state
+            .features
+            .iter()
+            .map(|f| {
+                f.blocked_by.as_ref().map(|blocker| match ids::resolve(&state.features, blocker) {
+                    Some(index) => state.features[index].id.clone(),
+                    None => blocker.clone(),
+                })
+            })
+            .collect();
+        for (((feature, depends_on), parent_id), blocked_by) in
+            state.features.iter_mut().zip(resolved).zip(parents).zip(blockers)
+        {
+            feature.depends_on = depends_on;
+            feature.parent_id = parent_id;
+            feature.blocked_by = blocked_by;
+        }
     }
+    deps::check_dependencies(&state.features).map_err(|e| format!("Update rejected: {}", e))?;
+    hierarchy::check_parents(&state.features).map_err(|e| format!("Update rejected: {}", e))?;
+    check_blockers(&state.features).map_err(|e| format!("Update rejected: {}", e))?;
 
     // Update state's last_updated timestamp
     state.touch();
@@ -153,6 +341,43 @@ fn merge_updates(
     Ok(())
 }
 
+/// A feature moving into Blocked must say why, and the blocker fields
+/// only make sense on a feature that ends up Blocked
+///
+/// `current` is the feature's status before the update (None if it is new)
+fn check_blocked(update: &FeatureUpdate, current: Option<&FeatureStatus>) -> Result<(), String> {
+    let status = update.status.as_ref().or(current);
+    let gives_blocker = update.blocked_reason.is_some() || update.blocked_by.as_deref().is_some_and(|b| !b.is_empty());
+    if status != Some(&FeatureStatus::Blocked) {
+        if gives_blocker {
+            return Err("blocked_reason and blocked_by need status Blocked".to_string());
+        }
+        return Ok(());
+    }
+    let newly_blocked = current != Some(&FeatureStatus::Blocked);
+    if newly_blocked && update.blocked_reason.as_deref().is_none_or(|r| r.trim().is_empty()) {
+        return Err("status Blocked needs a blocked_reason".to_string());
+    }
+    Ok(())
+}
+
+/// Every blocked_by names another feature in the state
+fn check_blockers(features: &[Feature]) -> Result<(), String> {
+    let ids: std::collections::HashSet<&str> = features.iter().map(|f| f.id.as_str()).collect();
+    for feature in features {
+        match &feature.blocked_by {
+            Some(blocker) if blocker == &feature.id => {
+                return Err(format!("'{}' can't be blocked by itself", feature.id));
+            }
+            Some(blocker) if !ids.contains(blocker.as_str()) => {
+                return Err(format!("'{}' is blocked by unknown feature '{}'", feature.id, blocker));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// Apply an update to an existing feature
 ///
 /// Only updates fields that are Some (provided)
@@ -174,7 +399,24 @@ fn apply_update(feature: &mut Feature, update: FeatureUpdate, now: i64) {
     }
 
     if let Some(status) = update.status {
-        feature.status = status;
+        feature.set_status(status, now);
+    }
+
+    // After the status: leaving Blocked has already cleared these
+    if update.blocked_reason.is_some() {
+        feature.blocked_reason = update.blocked_reason;
+    }
+
+    if let Some(blocked_by) = update.blocked_by {
+        feature.blocked_by = Some(blocked_by).filter(|b| !b.is_empty());
+    }
+
+    if let Some(priority) = update.priority {
+        feature.priority = priority;
+    }
+
+    if update.owner.is_some() {
+        feature.owner = update.owner;
     }
 
     if let Some(tags) = update.tags {
@@ -192,6 +434,34 @@ fn apply_update(feature: &mut Feature, update: FeatureUpdate, now: i64) {
         feature.files_involved = files;
     }
 
+    if let Some(parent_id) = update.parent_id {
+        feature.parent_id = Some(parent_id).filter(|p| !p.is_empty());
+    }
+
+    if let Some(depends_on) = update.depends_on {
+        feature.depends_on = depends_on;
+    }
+
+    if let Some(metadata) = update.metadata {
+        merge_metadata(&mut feature.metadata, metadata);
+    }
+
+    if update.estimate_hours.is_some() {
+        feature.estimate_hours = update.estimate_hours;
+    }
+
+    if let Some(due_date) = update.due_date {
+        feature.due_date = due_date;
+    }
+
+    if update.complexity.is_some() {
+        feature.complexity = update.complexity;
+    }
+
+    if update.risk.is_some() {
+        feature.risk = update.risk;
+    }
+
     // Always update the timestamp when touched
     feature.last_updated = now;
 }
@@ -217,40 +487,80 @@ fn create_feature_from_update(
         format!("New feature '{}' requires 'description' field", update.id)
     })?;
 
-    Ok(Feature {
+    let mut feature = Feature {
         id: update.id,
         name,
         domain,
         description,
-        status: update.status.unwrap_or(FeatureStatus::Pending),
+        status: FeatureStatus::Pending,
+        priority: update.priority.unwrap_or_default(),
+        owner: update.owner,
+        blocked_reason: None,
+        blocked_by: None,
         tags: update.tags.unwrap_or_default(),
         context: update.context,
         files_involved: update.files_involved.unwrap_or_default(),
+        parent_id: update.parent_id.filter(|p| !p.is_empty()),
+        depends_on: update.depends_on.unwrap_or_default(),
+        relations: Vec::new(),
+        subtasks: Vec::new(),
+        notes: Vec::new(),
+        decisions: Vec::new(),
+        estimate_hours: update.estimate_hours,
+        logged_hours: 0.0,
+        due_date: update.due_date.flatten(),
+        complexity: update.complexity,
+        risk: update.risk,
         created_at: now,
         last_updated: now,
+        last_updated_by: None,
         recency_score: 1.0, // New features start at max recency
-    })
+        metadata: {
+            let mut metadata = Metadata::new();
+            merge_metadata(&mut metadata, update.metadata.unwrap_or_default());
+            metadata
+        },
+        started_at: None,
+        completed_at: None,
+        planned: false,
+    };
+    // Through set_status so a feature created InProgress is also "started"
+    feature.set_status(update.status.unwrap_or(FeatureStatus::Pending), now);
+    feature.blocked_reason = update.blocked_reason;
+    feature.blocked_by = update.blocked_by.filter(|b| !b.is_empty());
+    Ok(feature)
+}
+
+/// Apply incoming metadata: set each key, or remove it when the value is null
+///
+/// Keys not mentioned in the update are kept, so writers only need to send
+/// the fields they own
+fn merge_metadata(existing: &mut Metadata, incoming: Metadata) {
+    for (key, value) in incoming {
+        if value.is_null() {
+            existing.remove(&key);
+        } else {
+            existing.insert(key, value);
+        }
+    }
 }
 
 /// Recalculate recency scores for all features
 ///
 /// Algorithm: Exponential decay based on time since last update
 /// - Most recent feature gets score 1.0
-/// - Score decays by half every 7 days (configurable)
+/// - Score decays by half every half-life (7 days unless configured
+///   per domain/tag in .legend/config.toml)
 ///
 /// Why exponential decay?
 /// - Recent work is more relevant than old work
 /// - Smooth curve (no sudden drops)
 /// - Easy to tune with half-life parameter
-fn recalculate_recency_scores(state: &mut LegendState) {
+fn recalculate_recency_scores(state: &mut LegendState, recency: &RecencyConfig) {
     let now = current_timestamp();
 
-    // Half-life in seconds (7 days)
-    // After 7 days, a feature's recency score is halved
-    const HALF_LIFE_SECONDS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
-
     // Natural log of 2 (for decay formula)
-    const LN_2: f64 = 0.693147;
+    const LN_2: f64 = std::f64::consts::LN_2;
 
     for feature in &mut state.features {
         // Time since last update in seconds
@@ -258,7 +568,8 @@ fn recalculate_recency_scores(state: &mut LegendState) {
 
         // Exponential decay formula: score = e^(-λt)
         // where λ = ln(2) / half_life
-        let decay_rate = LN_2 / HALF_LIFE_SECONDS;
+        // Infra decisions can stay relevant for months, UI tweaks for days
+        let decay_rate = LN_2 / recency.half_life_seconds(feature);
         let score = (-decay_rate * age_seconds).exp();
 
         // Clamp to reasonable range [0.01, 1.0]
@@ -267,14 +578,6 @@ fn recalculate_recency_scores(state: &mut LegendState) {
     }
 }
 
-/// Get current Unix timestamp
-fn current_timestamp() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_secs() as i64
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,6 +618,206 @@ mod tests {
         assert_eq!(update.features[0].status, Some(FeatureStatus::Complete));
     }
 
+    #[test]
+    fn test_features_touched() {
+        let mut before = LegendState::new("Test".to_string());
+        before.add_feature(Feature::new(
+            "auth".to_string(),
+            "Auth".to_string(),
+            "security".to_string(),
+            "Login flow".to_string(),
+        ));
+
+        let mut after = before.clone();
+        assert!(!features_touched(&before, &after));
+
+        after.features[0].last_updated += 5;
+        assert!(features_touched(&before, &after));
+
+        let mut after = before.clone();
+        after.features[0].description = "OAuth login flow".to_string();
+        assert!(features_touched(&before, &after));
+    }
+
+    #[test]
+    fn test_merge_keeps_notes() {
+        let mut state = LegendState::new("Test".to_string());
+        let mut feature = Feature::new("db".to_string(), "DB".to_string(), "api".to_string(), "Schema".to_string());
+        feature.notes.push(crate::types::Note { created_at: 1, text: "Chose Postgres".to_string(), author: None });
+        state.features.push(feature);
+
+        // notes is not an update field, so even an explicit list is ignored
+        let update: Update =
+            serde_json::from_str(r#"{"features": [{"id": "db", "description": "Tables", "notes": []}]}"#).unwrap();
+        merge_updates(&mut state, update, false).unwrap();
+        assert_eq!(state.features[0].description, "Tables");
+        assert_eq!(state.features[0].notes.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_records_source() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"source": "aider", "features": [{"id": "db", "name": "DB", "domain": "api", "description": "Schema"}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, false).unwrap();
+        assert_eq!(state.features[0].last_updated_by.as_deref(), Some("aider"));
+
+        let untagged: Update = serde_json::from_str(r#"{"features": [{"id": "db", "status": "Complete"}]}"#).unwrap();
+        merge_updates(&mut state, untagged, false).unwrap();
+        assert_eq!(state.features[0].last_updated_by, None);
+    }
+
+    #[test]
+    fn test_merge_depends_on() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"features": [
+                {"id": "db", "name": "DB", "domain": "api", "description": "Schema"},
+                {"id": "auth", "name": "Auth", "domain": "api", "description": "Login", "depends_on": ["db"]}
+            ]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, false).unwrap();
+        assert_eq!(state.features[1].depends_on, vec!["db"]);
+
+        let cycle: Update = serde_json::from_str(r#"{"features": [{"id": "db", "depends_on": ["auth"]}]}"#).unwrap();
+        let err = merge_updates(&mut state.clone(), cycle, false).unwrap_err();
+        assert_eq!(err.to_string(), "Update rejected: dependency cycle: db -> auth -> db");
+
+        let unknown: Update = serde_json::from_str(r#"{"features": [{"id": "db", "depends_on": ["ghost"]}]}"#).unwrap();
+        assert!(merge_updates(&mut state.clone(), unknown, false).is_err());
+
+        let remove: Update = serde_json::from_str(r#"{"remove_features": ["db"]}"#).unwrap();
+        merge_updates(&mut state, remove, false).unwrap();
+        assert!(state.features[0].depends_on.is_empty());
+    }
+
+    #[test]
+    fn test_merge_blocked() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"features": [
+                {"id": "keys", "name": "Keys", "domain": "ops", "description": "Vendor keys"},
+                {"id": "billing", "name": "Billing", "domain": "api", "description": "Plans"}
+            ]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, false).unwrap();
+
+        let no_reason: Update = serde_json::from_str(r#"{"features": [{"id": "billing", "status": "Blocked"}]}"#).unwrap();
+        let err = merge_updates(&mut state.clone(), no_reason, false).unwrap_err();
+        assert_eq!(err.to_string(), "Feature 'billing': status Blocked needs a blocked_reason");
+
+        let not_blocked: Update =
+            serde_json::from_str(r#"{"features": [{"id": "billing", "blocked_reason": "Keys"}]}"#).unwrap();
+        assert!(merge_updates(&mut state.clone(), not_blocked, false).is_err());
+
+        let unknown: Update = serde_json::from_str(
+            r#"{"features": [{"id": "billing", "status": "Blocked", "blocked_reason": "Keys", "blocked_by": "ghost"}]}"#,
+        )
+        .unwrap();
+        assert!(merge_updates(&mut state.clone(), unknown, false).is_err());
+
+        let block: Update = serde_json::from_str(
+            r#"{"features": [{"id": "billing", "status": "Blocked", "blocked_reason": "No API keys yet", "blocked_by": "keys"}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, block, false).unwrap();
+        assert_eq!(state.features[1].blocked_reason.as_deref(), Some("No API keys yet"));
+        assert_eq!(state.features[1].blocked_by.as_deref(), Some("keys"));
+
+        // Already blocked: other fields change without restating the reason
+        let touch: Update = serde_json::from_str(r#"{"features": [{"id": "billing", "description": "Plans and invoices"}]}"#).unwrap();
+        merge_updates(&mut state, touch, false).unwrap();
+        assert_eq!(state.features[1].blocked_by.as_deref(), Some("keys"));
+
+        let unblock: Update = serde_json::from_str(r#"{"features": [{"id": "billing", "status": "InProgress"}]}"#).unwrap();
+        merge_updates(&mut state, unblock, false).unwrap();
+        assert_eq!((state.features[1].blocked_reason.as_deref(), state.features[1].blocked_by.as_deref()), (None, None));
+    }
+
+    #[test]
+    fn test_merge_namespaced_ids() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"features": [{"id": "rate-limiting", "name": "Rate limiting", "domain": "api", "description": "Token bucket"}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, true).unwrap();
+        assert_eq!(state.features[0].id, "api/rate-limiting");
+
+        // The bare slug still finds it
+        let status: Update =
+            serde_json::from_str(r#"{"features": [{"id": "rate-limiting", "status": "Complete"}]}"#).unwrap();
+        merge_updates(&mut state, status, true).unwrap();
+        assert_eq!(state.features.len(), 1);
+        assert_eq!(state.features[0].status, FeatureStatus::Complete);
+
+        let remove: Update = serde_json::from_str(r#"{"remove_features": ["rate-limiting"]}"#).unwrap();
+        merge_updates(&mut state, remove, true).unwrap();
+        assert!(state.features.is_empty());
+    }
+
+    #[test]
+    fn test_merge_metadata() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"features": [{"id": "api", "name": "API", "domain": "api", "description": "d",
+                "metadata": {"tier": 1, "cost_center": "platform", "gone": null}}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, false).unwrap();
+        assert_eq!(state.features[0].metadata.len(), 2);
+
+        let change: Update = serde_json::from_str(
+            r#"{"features": [{"id": "api", "metadata": {"tier": 2, "cost_center": null}}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, change, false).unwrap();
+        assert_eq!(state.features[0].metadata.len(), 1);
+        assert_eq!(state.features[0].metadata["tier"], 2);
+    }
+
+    #[test]
+    fn test_merge_due_date() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"features": [{"id": "launch", "name": "Launch", "domain": "web", "description": "d", "due_date": "2024-03-01"}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, false).unwrap();
+        assert_eq!(state.features[0].due_date, Some(1709251200));
+
+        let untouched: Update = serde_json::from_str(r#"{"features": [{"id": "launch", "status": "InProgress"}]}"#).unwrap();
+        merge_updates(&mut state, untouched, false).unwrap();
+        assert_eq!(state.features[0].due_date, Some(1709251200));
+
+        let clear: Update = serde_json::from_str(r#"{"features": [{"id": "launch", "due_date": ""}]}"#).unwrap();
+        merge_updates(&mut state, clear, false).unwrap();
+        assert_eq!(state.features[0].due_date, None);
+
+        assert!(serde_json::from_str::<Update>(r#"{"features": [{"id": "launch", "due_date": "March"}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_merge_complexity_and_risk() {
+        let mut state = LegendState::new("Test".to_string());
+        let create: Update = serde_json::from_str(
+            r#"{"features": [{"id": "sso", "name": "SSO", "domain": "auth", "description": "d", "risk": 4}]}"#,
+        )
+        .unwrap();
+        merge_updates(&mut state, create, false).unwrap();
+        let rate: Update = serde_json::from_str(r#"{"features": [{"id": "sso", "complexity": 2}]}"#).unwrap();
+        merge_updates(&mut state, rate, false).unwrap();
+        assert_eq!((state.features[0].complexity, state.features[0].risk), (Some(2), Some(4)));
+
+        let out_of_range: Update = serde_json::from_str(r#"{"features": [{"id": "sso", "risk": 6}]}"#).unwrap();
+        assert!(merge_updates(&mut state, out_of_range, false).is_err());
+        assert_eq!(state.features[0].risk, Some(4));
+    }
+
     #[test]
     fn test_recency_decay() {
         let mut state = LegendState::new("Test".to_string());
@@ -339,7 +842,7 @@ mod tests {
         state.add_feature(new_feature);
 
         // Recalculate scores
-        recalculate_recency_scores(&mut state);
+        recalculate_recency_scores(&mut state, &RecencyConfig::default());
 
         // New feature should have higher recency score
         let old_score = state.find_feature("old").unwrap().recency_score;
@@ -349,4 +852,26 @@ mod tests {
         assert!(new_score > 0.9, "Recent feature should be close to 1.0");
         assert!(old_score < 0.1, "30-day-old feature should have low recency");
     }
+
+    #[test]
+    fn test_recency_uses_domain_half_life() {
+        let mut state = LegendState::new("Test".to_string());
+
+        let mut infra = Feature::new(
+            "infra".to_string(),
+            "Infra".to_string(),
+            "infra".to_string(),
+            "Deployment setup".to_string(),
+        );
+        infra.last_updated = current_timestamp() - (30 * 24 * 60 * 60);
+        state.add_feature(infra);
+
+        let mut recency = RecencyConfig::default();
+        recency.domains.insert("infra".to_string(), 90.0);
+        recalculate_recency_scores(&mut state, &recency);
+
+        // 30 days against a 90-day half-life keeps ~79% of the score
+        let score = state.find_feature("infra").unwrap().recency_score;
+        assert!(score > 0.75 && score < 0.85, "got {}", score);
+    }
 }
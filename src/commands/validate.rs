@@ -0,0 +1,239 @@
+// Validate command - structural checks on the stored state
+//
+// Catches things that make the state wrong (not just low quality):
+// duplicate IDs, missing required fields, files that no longer exist,
+// dependencies or parents that are unknown or in a cycle, unknown
+// blockers, and (with `[ids] namespace = true`) IDs that aren't `domain/slug`.
+// For quality warnings see lint.rs.
+//
+// Usage:
+//   legend validate                  - report problems, always exit 0
+//   legend validate --check          - exit 3 if any problem is found (for CI)
+//   legend validate --format github  - GitHub Actions annotations
+
+use crate::config;
+use crate::deps;
+use crate::findings::{self, Finding};
+use crate::hierarchy;
+use crate::ids;
+use crate::output;
+use crate::storage;
+use crate::types::Feature;
+use serde_json::json;
+use std::collections::HashSet;
+
+/// Handle the validate command
+pub fn handle_validate(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let options = findings::parse_check_args(args)?;
+    let state = storage::load_state()?;
+    let namespace = config::namespaced_ids(&config::load_config()?)?;
+
+    let root = storage::project_root();
+    let findings = validate_features(&state.features, namespace, |path| root.join(path).exists());
+
+    findings::print_findings(&findings, options.format, "validate");
+    output::diag(
+        output::Level::Info,
+        &format!("Validated {} feature(s): {} problem(s)", state.features.len(), findings.len()),
+        json!({"features": state.features.len(), "problems": findings.len()}),
+    );
+
+    findings::check_result(&findings, options.check, "validate")
+}
+
+/// Run every structural check
+///
+/// `file_exists` is passed in so tests don't depend on the real filesystem
+fn validate_features(
+    features: &[Feature],
+    namespace: bool,
+    file_exists: impl Fn(&str) -> bool,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen_ids: HashSet<&str> = HashSet::new();
+    let all_ids: HashSet<&str> = features.iter().map(|f| f.id.as_str()).collect();
+
+    for feature in features {
+        let id = Some(feature.id.as_str());
+
+        if !seen_ids.insert(feature.id.as_str()) {
+            findings.push(Finding::error(id, "duplicate feature ID".to_string()));
+        }
+
+        if feature.id.trim().is_empty() {
+            findings.push(Finding::error(None, format!("feature '{}' has an empty ID", feature.name)));
+        } else if feature.id.chars().any(char::is_whitespace) {
+            findings.push(Finding::error(id, "ID contains whitespace".to_string()));
+        } else if namespace {
+            if let Some(problem) = ids::namespace_problem(feature) {
+                findings.push(Finding::error(id, problem));
+            }
+        }
+
+        // Planned stubs get their domain and description at `legend promote`
+        for (field, value) in [
+            ("name", &feature.name),
+            ("domain", &feature.domain),
+            ("description", &feature.description),
+        ] {
+            if value.trim().is_empty() && !(feature.planned && field != "name") {
+                findings.push(Finding::error(id, format!("required field '{}' is empty", field)));
+            }
+        }
+
+        let mut seen_files: HashSet<&str> = HashSet::new();
+        for file in &feature.files_involved {
+            if !seen_files.insert(file.as_str()) {
+                findings.push(
+                    Finding::warning(id, "file listed more than once".to_string()).with_file(file),
+                );
+            }
+            if !file_exists(file) {
+                findings.push(
+                    Finding::warning(id, "tracked file does not exist".to_string()).with_file(file),
+                );
+            }
+        }
+
+        for dep in &feature.depends_on {
+            if dep == &feature.id {
+                findings.push(Finding::error(id, "depends on itself".to_string()));
+            } else if !all_ids.contains(dep.as_str()) {
+                findings.push(Finding::error(id, format!("depends on unknown feature '{}'", dep)));
+            }
+        }
+
+        if let Some(parent) = &feature.parent_id {
+            if parent == &feature.id {
+                findings.push(Finding::error(id, "is its own parent".to_string()));
+            } else if !all_ids.contains(parent.as_str()) {
+                findings.push(Finding::error(id, format!("has unknown parent '{}'", parent)));
+            }
+        }
+
+        if let Some(blocker) = &feature.blocked_by {
+            if blocker == &feature.id {
+                findings.push(Finding::error(id, "is blocked by itself".to_string()));
+            } else if !all_ids.contains(blocker.as_str()) {
+                findings.push(Finding::error(id, format!("is blocked by unknown feature '{}'", blocker)));
+            }
+        }
+
+        for relation in &feature.relations {
+            if relation.target == feature.id {
+                findings.push(Finding::error(id, format!("{} itself", relation.kind.as_str())));
+            } else if !all_ids.contains(relation.target.as_str()) {
+                findings.push(Finding::warning(
+                    id,
+                    format!("{} unknown feature '{}'", relation.kind.as_str(), relation.target),
+                ));
+            }
+        }
+    }
+
+    // Self-dependencies are reported above; a cycle needs two features
+    if let Some(cycle) = deps::find_cycle(features).filter(|c| c.len() > 2) {
+        findings.push(Finding::error(Some(cycle[0].as_str()), format!("dependency cycle: {}", cycle.join(" -> "))));
+    }
+    if let Some(cycle) = hierarchy::find_cycle(features) {
+        findings.push(Finding::error(Some(cycle[0].as_str()), format!("parent cycle: {}", cycle.join(" -> "))));
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::Severity;
+
+    fn make_feature(id: &str) -> Feature {
+        Feature::new(
+            id.to_string(),
+            format!("Feature {}", id),
+            "test".to_string(),
+            "Test feature".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_valid_state_has_no_findings() {
+        let features = vec![make_feature("a"), make_feature("b")];
+        assert!(validate_features(&features, false, |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_and_empty_fields() {
+        let mut broken = make_feature("a");
+        broken.description = "  ".to_string();
+        let features = vec![make_feature("a"), broken, make_feature("has space")];
+
+        let findings = validate_features(&features, false, |_| true);
+
+        assert_eq!(findings.len(), 3);
+        assert!(findings.iter().all(|f| f.severity == Severity::Error));
+        assert!(findings.iter().any(|f| f.message == "duplicate feature ID"));
+        assert!(findings.iter().any(|f| f.message.contains("'description'")));
+        assert!(findings.iter().any(|f| f.message.contains("whitespace")));
+    }
+
+    #[test]
+    fn test_planned_stub_needs_only_a_name() {
+        let mut stub = Feature::new("later".to_string(), "Later".to_string(), String::new(), String::new());
+        stub.planned = true;
+        assert!(validate_features(&[stub.clone()], false, |_| true).is_empty());
+
+        stub.planned = false;
+        assert_eq!(validate_features(&[stub], false, |_| true).len(), 2);
+    }
+
+    #[test]
+    fn test_dependency_problems() {
+        let mut a = make_feature("a");
+        a.depends_on = vec!["b".to_string(), "ghost".to_string()];
+        let mut b = make_feature("b");
+        b.depends_on = vec!["a".to_string()];
+
+        let findings = validate_features(&[a, b], false, |_| true);
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, vec!["depends on unknown feature 'ghost'", "dependency cycle: a -> b -> a"]);
+    }
+
+    #[test]
+    fn test_parent_problems() {
+        let mut a = make_feature("a");
+        a.parent_id = Some("b".to_string());
+        let mut b = make_feature("b");
+        b.parent_id = Some("a".to_string());
+        let mut c = make_feature("c");
+        c.parent_id = Some("ghost".to_string());
+
+        let findings = validate_features(&[a, b, c], false, |_| true);
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, vec!["has unknown parent 'ghost'", "parent cycle: a -> b -> a"]);
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let mut feature = make_feature("a");
+        feature.files_involved = vec!["src/here.rs".to_string(), "src/gone.rs".to_string()];
+
+        let findings = validate_features(&[feature], false, |path| path == "src/here.rs");
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file.as_deref(), Some("src/gone.rs"));
+    }
+
+    #[test]
+    fn test_namespaced_ids() {
+        let mut namespaced = make_feature("test/a");
+        namespaced.domain = "test".to_string();
+        let features = vec![namespaced, make_feature("b")];
+
+        assert!(validate_features(&features, false, |_| true).is_empty());
+
+        let findings = validate_features(&features, true, |_| true);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].feature_id.as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,74 @@
+// Verify command - check state.lz4 against its signature
+//
+// Usage:
+//   legend verify    - exit 0 if the state is signed by a trusted key,
+//                      exit 3 if it is unsigned, untrusted, or modified
+//
+// Signing is optional and configured under [signing] in config.toml;
+// see signing.rs for the key setup.
+//
+// Only the snapshot is signed, not events.log. Loading replays journal
+// events the snapshot doesn't have yet (see journal.rs), so while there
+// are any, what commands see isn't what was signed: verify fails until
+// `legend compact` saves (and signs) them into the snapshot.
+
+use crate::config;
+use crate::exit::{self, ExitError};
+use crate::journal;
+use crate::signing::{self, SigningConfig};
+use crate::storage;
+use std::path::Path;
+
+/// Handle the verify command
+pub fn handle_verify(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(other) = args.first() {
+        return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\nUsage: legend verify", other)).into());
+    }
+
+    check_journal_applied(&storage::legend_dir())?;
+    let signing = SigningConfig::from_config(&config::load_config()?);
+    let state_file = storage::state_file();
+    let identity = signing::verify(&state_file, &signing.allowed_signers)?;
+
+    println!("✓ {} is signed by {}", state_file.display(), identity);
+    Ok(())
+}
+
+/// Fail if loading would replay unsigned journal events over the snapshot
+fn check_journal_applied(dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let pending = journal::unapplied_events_in(dir)?.len();
+    if pending > 0 {
+        return Err(ExitError::new(
+            exit::CHECK_FAILED,
+            format!(
+                "events.log has {} event(s) the signed snapshot doesn't include; run 'legend compact' to save and sign them",
+                pending
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use std::fs;
+
+    #[test]
+    fn test_unapplied_journal_fails_verify() {
+        let _env = TestEnv::initialized(1_709_251_200);
+        let dir = storage::legend_dir();
+        let before = storage::load_state().unwrap();
+        let mut after = before.clone();
+        after.conventions.push("no unwrap in prod code".to_string());
+        journal::commit(&before, &after).unwrap();
+        assert!(check_journal_applied(&dir).is_ok());
+
+        // As if the save after the journal append never happened
+        fs::write(dir.join("events.applied"), "0\n").unwrap();
+        let err = check_journal_applied(&dir).unwrap_err();
+        assert_eq!(exit::code_for(err.as_ref()), exit::CHECK_FAILED);
+    }
+}
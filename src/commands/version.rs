@@ -0,0 +1,67 @@
+// Version command - which legend this is and what it was built with
+//
+// Usage:
+//   legend version              - "legend 0.2.0"
+//   legend version --features   - ...plus each optional subsystem, on or off
+//   legend version --json       - the same as JSON (features included)
+//
+// Optional subsystems are Cargo features (see cargo_features.rs), so a
+// slim hook-only install can tell you what it left out.
+
+use crate::cargo_features::FEATURES;
+use crate::exit::{self, ExitError};
+use serde_json::json;
+
+const USAGE: &str = "Usage: legend version [--features] [--json]";
+
+/// Handle the version command
+pub fn handle_version(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut features = false;
+    let mut json_out = false;
+    for arg in args {
+        match arg.as_str() {
+            "--features" => features = true,
+            "--json" => json_out = true,
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+        }
+    }
+
+    if json_out {
+        let compiled: serde_json::Map<String, serde_json::Value> =
+            FEATURES.iter().map(|(name, on, _)| (name.to_string(), json!(on))).collect();
+        let report = json!({"version": env!("CARGO_PKG_VERSION"), "features": compiled});
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("legend {}", env!("CARGO_PKG_VERSION"));
+    if features {
+        for line in feature_lines() {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// `  + tui        show --interactive (...)`, `-` for one left out
+fn feature_lines() -> Vec<String> {
+    FEATURES
+        .iter()
+        .map(|(name, on, provides)| format!("  {} {:<10} {}", if *on { '+' } else { '-' }, name, provides))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_lines_mark_what_is_compiled() {
+        let lines = feature_lines();
+        assert_eq!(lines.len(), FEATURES.len());
+        let tui = lines.iter().find(|l| l.contains(" tui ")).unwrap();
+        assert_eq!(tui.trim_start().starts_with('+'), cfg!(feature = "tui"));
+    }
+}
@@ -0,0 +1,100 @@
+// Warm command - preload the read path into the OS page cache
+//
+// Usage:
+//   legend warm          - read state, archive, config, and the binary
+//   legend warm --quiet  - same, without the summary line
+//
+// get_state's <5ms target assumes its files are already in memory. On a
+// cold disk (first command after boot, a network home directory, a fresh
+// container) the first read can take far longer. Running `legend warm`
+// from a shell profile or ahead of the SessionStart hook pays that cost
+// early. Reading a file end to end is all the kernel needs to cache it, so
+// no platform-specific fadvise/readahead calls (or libc dependency).
+//
+// Safe anywhere: outside an initialized project it does nothing and still
+// exits 0. Nothing is printed on stdout, so hook output stays clean.
+
+use crate::config;
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Handle the warm command
+pub fn handle_warm(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut quiet = false;
+    for arg in args {
+        match arg.as_str() {
+            "--quiet" | "-q" => quiet = true,
+            other => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\nUsage: legend warm [--quiet]", other)).into())
+            }
+        }
+    }
+
+    if !storage::is_initialized() {
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let (files, bytes) = preload(&warm_paths());
+
+    // Decoding once also faults in the decompression and bincode code paths
+    let features = storage::load_state().map(|state| state.features.len()).unwrap_or(0);
+
+    if !quiet {
+        let millis = start.elapsed().as_secs_f64() * 1000.0;
+        output::diag(
+            output::Level::Info,
+            &format!(
+                "Warmed {} file(s), {} KB, {} feature(s) in {:.1}ms",
+                files,
+                bytes.div_ceil(1024),
+                features,
+                millis
+            ),
+            json!({"files": files, "bytes": bytes, "features": features, "total_ms": millis}),
+        );
+    }
+    Ok(())
+}
+
+/// Files on the get_state / show / search read path
+fn warm_paths() -> Vec<PathBuf> {
+    let mut paths = vec![
+        storage::state_file(),
+        storage::archive_file(),
+        config::config_path(),
+    ];
+    if let Ok(exe) = std::env::current_exe() {
+        paths.push(exe);
+    }
+    paths
+}
+
+/// Read each existing file in full; returns (files read, total bytes)
+fn preload(paths: &[PathBuf]) -> (usize, u64) {
+    paths
+        .iter()
+        .filter_map(|path| fs::read(path).ok())
+        .fold((0, 0), |(files, bytes), content| (files + 1, bytes + content.len() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preload_skips_missing_files() {
+        let path = std::env::temp_dir().join(format!("legend-warm-test-{}", std::process::id()));
+        fs::write(&path, b"hello").unwrap();
+
+        let (files, bytes) = preload(&[path.clone(), PathBuf::from("/nonexistent/legend/state.lz4")]);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!((files, bytes), (1, 5));
+    }
+}
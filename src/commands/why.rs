@@ -0,0 +1,230 @@
+// Why command - how a feature came to be what it is
+//
+// Usage:
+//   legend why <feature-id>            - the story, oldest first
+//   legend why <feature-id> --no-git   - without commits
+//   legend why <feature-id> --json     - the same entries as JSON
+//
+// Pulls together everything Legend knows about one feature, in order:
+// the update that created it (and where from: a split, a copy), each
+// journaled change, the goals it serves, the decisions that shaped it,
+// the sessions that touched it, its notes, and the commits to its files. This is the
+// question the rest of the tool exists to answer months later.
+
+use crate::dates;
+use crate::diff::{self, FieldChange};
+use crate::exit::{self, ExitError};
+use crate::git::{self, Commit};
+use crate::ids;
+use crate::journal::{self, Event, EventKind};
+use crate::storage;
+use crate::types::{Feature, LegendState};
+use serde::Serialize;
+
+const USAGE: &str = "Usage: legend why <feature-id> [--no-git] [--json]";
+
+/// Commits listed at most, newest kept
+const MAX_COMMITS: usize = 20;
+
+/// Fields with entries of their own, left out of "changed" lines
+const OWN_ENTRIES: &[&str] = &["notes", "decisions"];
+
+/// One step in the story
+#[derive(Debug, PartialEq, Serialize)]
+struct Entry {
+    at: i64,
+    /// created, changed, goal, decision, session, note, or commit
+    kind: &'static str,
+    text: String,
+}
+
+/// Handle the why command
+pub fn handle_why(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut id = None;
+    let mut use_git = true;
+    let mut json_out = false;
+    for arg in args {
+        match arg.as_str() {
+            "--no-git" => use_git = false,
+            "--json" => json_out = true,
+            other if other.starts_with("--") || id.is_some() => {
+                return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}\n{}", other, USAGE)).into());
+            }
+            other => id = Some(other),
+        }
+    }
+    let id = id.ok_or_else(|| ExitError::new(exit::USAGE, USAGE))?;
+
+    let state = storage::load_state()?;
+    let index = ids::resolve(&state.features, id).ok_or_else(|| format!("Feature '{}' not found", id))?;
+    let feature = &state.features[index];
+    let events = journal::read_events()?;
+    // Outside a git repo (or with no files) there are simply no commits
+    let commits = if use_git && !feature.files_involved.is_empty() {
+        git::commits_touching(&feature.files_involved, MAX_COMMITS).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let entries = story(feature, &state, &events, &commits);
+
+    if json_out {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    println!("{} - {} [{}] {}", feature.id, feature.name, feature.domain, feature.status);
+    println!("{}", feature.description);
+    println!();
+    for entry in &entries {
+        println!("{}  {:<8}  {}", dates::format_datetime(entry.at), entry.kind, entry.text);
+    }
+    Ok(())
+}
+
+/// Every entry for `feature`, oldest first
+fn story(feature: &Feature, state: &LegendState, events: &[Event], commits: &[(i64, Commit)]) -> Vec<Entry> {
+    let mut entries = vec![origin(feature, events)];
+
+    for event in events {
+        if let EventKind::FeatureChanged { id, changes, .. } = &event.kind {
+            let shown: Vec<FieldChange> =
+                changes.iter().filter(|c| !OWN_ENTRIES.contains(&c.field.as_str())).cloned().collect();
+            if id == &feature.id && !shown.is_empty() {
+                entries.push(Entry { at: event.timestamp, kind: "changed", text: diff::describe_changes(&shown) });
+            }
+        }
+    }
+    for goal in state.goals.iter().filter(|g| g.feature_ids.contains(&feature.id)) {
+        entries.push(Entry { at: goal.created_at, kind: "goal", text: format!("serves {}: {}", goal.id, goal.title) });
+    }
+    for decision in state.decisions.iter().filter(|d| d.feature_ids.contains(&feature.id)) {
+        entries.push(Entry {
+            at: decision.decided_at,
+            kind: "decision",
+            text: format!("#{} {}: {}", decision.id, decision.title, decision.rationale),
+        });
+    }
+    for session in state.sessions.iter().filter(|s| s.features_touched.contains(&feature.id)) {
+        let summary = session.summary.as_deref().unwrap_or("(no summary)");
+        entries.push(Entry { at: session.started_at, kind: "session", text: format!("#{} {}", session.id, summary) });
+    }
+    for note in &feature.notes {
+        let text = match &note.author {
+            Some(author) => format!("{}: {}", author, note.text),
+            None => note.text.clone(),
+        };
+        entries.push(Entry { at: note.created_at, kind: "note", text });
+    }
+    for (at, commit) in commits {
+        entries.push(Entry { at: *at, kind: "commit", text: format!("{} {}", commit.hash, commit.subject) });
+    }
+
+    // Stable, so entries at the same second keep the order above
+    entries.sort_by_key(|e| e.at);
+    entries
+}
+
+/// Where the feature came from: its FeatureAdded event, or the baseline
+/// if it predates the journal
+fn origin(feature: &Feature, events: &[Event]) -> Entry {
+    let mut text = None;
+    let mut at = feature.created_at;
+    for event in events {
+        match &event.kind {
+            EventKind::FeatureAdded { feature: added } if added.id == feature.id => {
+                text = Some(match &added.last_updated_by {
+                    Some(source) => format!("created by an update from {}", source),
+                    None => "created".to_string(),
+                });
+                at = event.timestamp;
+                break;
+            }
+            EventKind::Baseline { features } if features.iter().any(|f| f.id == feature.id) => {
+                text = Some("already tracked when the journal started".to_string());
+                at = feature.created_at.min(event.timestamp);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let mut text = text.unwrap_or_else(|| "created".to_string());
+    if let Some(from) = feature.metadata.get("split_from").and_then(|v| v.as_str()) {
+        text.push_str(&format!(", split from {}", from));
+    }
+    if let Some(source) = feature.context.as_deref().and_then(|c| c.lines().find_map(|l| l.strip_prefix("Copied from "))) {
+        text.push_str(&format!(", copied from {}", source));
+    }
+    Entry { at, kind: "created", text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Decision, Goal, Note, Session};
+    use serde_json::json;
+
+    #[test]
+    fn test_story_is_chronological() {
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "d".to_string());
+        feature.created_at = 100;
+        feature.last_updated_by = Some("claude-code".to_string());
+        feature.notes.push(Note { created_at: 400, text: "Reverted JWT".to_string(), author: Some("sam".to_string()) });
+
+        let mut state = LegendState::new("demo".to_string());
+        state.decisions.push(Decision {
+            id: 1,
+            title: "Use sessions".to_string(),
+            rationale: "Revocable".to_string(),
+            feature_ids: vec!["auth".to_string()],
+            decided_at: 300,
+        });
+        state.sessions.push(Session {
+            id: 2,
+            started_at: 250,
+            ended_at: Some(260),
+            summary: Some("Login form".to_string()),
+            features_touched: vec!["auth".to_string()],
+        });
+        state.goals.push(Goal {
+            id: "signup".to_string(),
+            title: "Self-serve signup".to_string(),
+            target_date: None,
+            feature_ids: vec!["auth".to_string()],
+            created_at: 50,
+        });
+        state.features.push(feature.clone());
+
+        let events = vec![
+            Event { seq: 1, timestamp: 100, kind: EventKind::FeatureAdded { feature: feature.clone() } },
+            Event {
+                seq: 2,
+                timestamp: 200,
+                kind: EventKind::FeatureChanged {
+                    id: "auth".to_string(),
+                    changes: vec![
+                        FieldChange { field: "status".to_string(), before: json!("Pending"), after: json!("InProgress") },
+                        FieldChange { field: "notes".to_string(), before: json!([]), after: json!([{}]) },
+                    ],
+                    feature: feature.clone(),
+                },
+            },
+        ];
+        let commits = vec![(350, Commit { hash: "abc123".to_string(), subject: "Add login".to_string(), files: Vec::new() })];
+
+        let lines: Vec<(i64, &str, String)> =
+            story(&feature, &state, &events, &commits).into_iter().map(|e| (e.at, e.kind, e.text)).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (50, "goal", "serves signup: Self-serve signup".to_string()),
+                (100, "created", "created by an update from claude-code".to_string()),
+                (200, "changed", "status Pending → InProgress".to_string()),
+                (250, "session", "#2 Login form".to_string()),
+                (300, "decision", "#1 Use sessions: Revocable".to_string()),
+                (350, "commit", "abc123 Add login".to_string()),
+                (400, "note", "sam: Reverted JWT".to_string()),
+            ]
+        );
+    }
+}
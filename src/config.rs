@@ -0,0 +1,784 @@
+// Config - optional settings in .legend/config.toml
+//
+// Every setting has a default, so a missing file is the normal case.
+//
+// User-wide defaults live in ~/.config/legend/config.toml (or
+// $XDG_CONFIG_HOME/legend/config.toml) in the same format. Both files are
+// merged key by key, and the project's value wins, so a team setting in
+// the repo always beats a personal default.
+//
+// Any key can also be set for one run with a LEGEND_* environment
+// variable: the key upper-cased with dots and spaces as underscores, e.g.
+// LEGEND_RECENCY_HALF_LIFE_DAYS=14 or LEGEND_RECENCY_DOMAINS_INFRA=90.
+// Precedence, highest first:
+//
+//   1. LEGEND_* environment variables
+//   2. .legend/config.toml (project)
+//   3. ~/.config/legend/config.toml (user)
+//   4. built-in defaults
+//
+// `legend config show --resolved` prints where each value came from.
+//
+// Why a hand-rolled parser and not the toml crate? Minimal dependencies
+// (see PLAN.md). Legend's config only needs a small subset of TOML:
+//
+//   # comments
+//   [section]            and   [section.subsection]
+//   key = "string"       key = 42       key = 1.5       key = true
+//   "quoted key" = 3
+//
+// Values are kept as strings in a flat map keyed by "section.key" and
+// converted on lookup, so adding a setting never touches the parser.
+//
+// Rust concepts in this file:
+// - BTreeMap as a sorted, flat key/value store
+// - str::strip_prefix / strip_suffix for small parsers
+
+use crate::storage;
+use crate::types::{Feature, FeatureStatus};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the config inside the Legend directory
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Directory of the user-level config, under ~/.config or $XDG_CONFIG_HOME
+const GLOBAL_CONFIG_DIR_NAME: &str = "legend";
+
+/// Default recency half-life (days) when nothing more specific is set
+pub const DEFAULT_HALF_LIFE_DAYS: f64 = 7.0;
+
+/// Fixed keys Legend reads, with their built-in defaults
+///
+/// Used to map environment variables back to keys and to show defaults
+/// in `legend config show --resolved`
+pub const KNOWN_KEYS: &[(&str, Option<&str>)] = &[
+    ("editor", None),
+    ("encryption.identity", Some("~/.legend/age-key.txt")),
+    ("encryption.recipients", None),
+    ("get_state.max_bytes", Some("40000")),
+    ("get_state.max_files", Some("12")),
+    ("get_state.max_tokens", None),
+    ("get_state.sections", Some("project,focus,global,conventions,deadlines,domains,goals,features,milestones,decisions,sessions")),
+    ("ids.namespace", Some("false")),
+    ("limits.max_files", Some("100000")),
+    ("limits.max_index_mb", Some("256")),
+    ("recency.half_life_days", Some("7")),
+    ("signing.allowed_signers", Some(".legend/allowed_signers")),
+    ("signing.key", None),
+    ("statuses.custom", None),
+    ("storage.format", Some("binary")),
+    ("storage.snapshot_every", Some("1")),
+    ("sync.push", None),
+    ("theme.accent", None),
+    ("theme.glyphs", Some("unicode")),
+    ("theme.name", Some("default")),
+];
+
+/// Sections whose keys are names the user picks (domains, tags, ...)
+const OPEN_SECTIONS: &[&str] = &["recency.domains", "recency.tags", "theme.status", "validators", "webhooks"];
+
+/// LEGEND_* variables that are switches, not config keys
+const NON_CONFIG_VARS: &[&str] = &["LEGEND_DIAG", "LEGEND_DIR", "LEGEND_EVENT", "LEGEND_PROGRESS", "LEGEND_SCREEN_READER", "LEGEND_VALIDATOR"];
+
+const ENV_PREFIX: &str = "LEGEND_";
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// Parsed config: "section.key" -> raw value
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    values: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Raw string value for a dotted key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|v| v.as_str())
+    }
+
+    /// Numeric value for a dotted key (error if present but not a number)
+    pub fn get_f64(&self, key: &str) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(raw) => raw
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("config: {} must be a number, got '{}'", key, raw).into()),
+        }
+    }
+
+    /// Boolean value for a dotted key (error if present but not true/false)
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, Box<dyn std::error::Error>> {
+        match self.get(key) {
+            None => Ok(None),
+            Some("true") => Ok(Some(true)),
+            Some("false") => Ok(Some(false)),
+            Some(raw) => Err(format!("config: {} must be true or false, got '{}'", key, raw).into()),
+        }
+    }
+
+    /// Every "section.key" and raw value, sorted by key
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Layer `other` on top: its keys replace ours
+    pub fn overlay(&mut self, other: Config) {
+        self.values.extend(other.values);
+    }
+
+    /// All keys directly under `section`, with the section prefix removed
+    pub fn section(&self, section: &str) -> Vec<(&str, &str)> {
+        let prefix = format!("{}.", section);
+        self.values
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(&prefix)
+                    .filter(|rest| !rest.contains('.'))
+                    .map(|rest| (rest, value.as_str()))
+            })
+            .collect()
+    }
+}
+
+/// Path of .legend/config.toml
+pub fn config_path() -> PathBuf {
+    storage::legend_path(CONFIG_FILE_NAME)
+}
+
+/// Path of the user-level config, if a home directory is known
+pub fn global_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join(GLOBAL_CONFIG_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+/// Load the user-level config with .legend/config.toml on top
+///
+/// Defaults if neither file exists
+pub fn load_config() -> Result<Config, Box<dyn std::error::Error>> {
+    let mut config = match global_config_path() {
+        Some(path) => load_config_file(&path)?,
+        None => Config::default(),
+    };
+    config.overlay(load_config_file(&config_path())?);
+    let env = env_overrides(std::env::vars(), &config);
+    config.overlay(env.config);
+    Ok(config)
+}
+
+/// Config set through LEGEND_* environment variables
+#[derive(Debug, Default)]
+pub struct EnvOverrides {
+    pub config: Config,
+    /// (variable, key) for each variable applied
+    pub applied: Vec<(String, String)>,
+    /// LEGEND_* variables that match no key
+    pub unknown: Vec<String>,
+}
+
+/// Map LEGEND_* variables onto config keys
+///
+/// A variable matches a key already set in `files` first (keeping its
+/// exact spelling), then a known key, then a key in an open section
+/// (LEGEND_RECENCY_TAGS_UI -> recency.tags.ui)
+pub fn env_overrides(vars: impl Iterator<Item = (String, String)>, files: &Config) -> EnvOverrides {
+    let mut overrides = EnvOverrides::default();
+
+    for (var, value) in vars {
+        let Some(name) = var.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if NON_CONFIG_VARS.contains(&var.as_str()) {
+            continue;
+        }
+
+        let key = files
+            .values
+            .keys()
+            .find(|key| env_name(key) == name)
+            .cloned()
+            .or_else(|| {
+                KNOWN_KEYS
+                    .iter()
+                    .find(|(key, _)| env_name(key) == name)
+                    .map(|(key, _)| key.to_string())
+            })
+            .or_else(|| {
+                OPEN_SECTIONS.iter().find_map(|section| {
+                    let rest = name.strip_prefix(&env_name(section))?.strip_prefix('_')?;
+                    (!rest.is_empty()).then(|| format!("{}.{}", section, rest.to_lowercase()))
+                })
+            });
+
+        match key {
+            Some(key) => {
+                overrides.config.values.insert(key.clone(), value);
+                overrides.applied.push((var, key));
+            }
+            None => overrides.unknown.push(var),
+        }
+    }
+
+    overrides
+}
+
+/// "recency.half_life_days" -> "RECENCY_HALF_LIFE_DAYS"
+pub fn env_name(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+/// Load one config file, or an empty config if it doesn't exist
+pub fn load_config_file(path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    parse_config(&content).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+/// Parse the TOML subset described at the top of this file
+pub fn parse_config(content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+    let mut values = BTreeMap::new();
+    let mut section = String::new();
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {}: unclosed section header", i + 1))?;
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected key = value", i + 1))?;
+
+        let key = unquote(key.trim());
+        if key.is_empty() {
+            return Err(format!("line {}: missing key", i + 1).into());
+        }
+
+        let full_key = if section.is_empty() {
+            key
+        } else {
+            format!("{}.{}", section, key)
+        };
+        values.insert(full_key, unquote(value.trim()));
+    }
+
+    Ok(Config { values })
+}
+
+/// Drop a trailing # comment (a # inside quotes is kept)
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Remove surrounding double quotes, if any
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Set `key` in config file text, keeping comments and layout
+///
+/// An existing line is rewritten in place; a new key goes at the end of
+/// its section, and a new section at the end of the file
+pub fn set_value(content: &str, key: &str, value: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if value.contains('"') || value.contains('\n') {
+        return Err(format!("config: values can't contain quotes or newlines ({})", key).into());
+    }
+    let (section, name) = split_key(key)?;
+    let line = format!("{} = {}", format_key(name), format_value(value));
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let found = locate(&lines, section, name);
+    match found {
+        Located::Key(i) => lines[i] = line,
+        Located::SectionEnd(i) => lines.insert(i, line),
+        Located::Missing => {
+            if section.is_empty() {
+                lines.insert(0, line);
+            } else {
+                if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{}]", section));
+                lines.push(line);
+            }
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+/// Set `key` in .legend/config.toml, keeping the rest of the file
+pub fn set_project_value(key: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = config_path();
+    let content = if path.exists() {
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+    } else {
+        String::new()
+    };
+    let updated = set_value(&content, key, value)?;
+    parse_config(&updated)?;
+    fs::write(&path, updated).map_err(|e| format!("Failed to write {}: {}", path.display(), e).into())
+}
+
+/// Remove `key` from config file text; None if it wasn't set
+pub fn unset_value(content: &str, key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let (section, name) = split_key(key)?;
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    match locate(&lines, section, name) {
+        Located::Key(i) => {
+            lines.remove(i);
+            let mut updated = lines.join("\n");
+            updated.push('\n');
+            Ok(Some(updated))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Where a key is (or would go) in a config file
+enum Located {
+    Key(usize),
+    /// Line index just after the section's last key
+    SectionEnd(usize),
+    Missing,
+}
+
+fn locate(lines: &[String], section: &str, name: &str) -> Located {
+    let mut current = String::new();
+    let mut section_seen = section.is_empty();
+    let mut section_end = None;
+
+    for (i, raw_line) in lines.iter().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if let Some(header) = line.strip_prefix('[') {
+            current = header.trim_end_matches(']').trim().to_string();
+            if current == section {
+                section_seen = true;
+                section_end = Some(i + 1);
+            }
+            continue;
+        }
+        if current != section || line.is_empty() {
+            continue;
+        }
+        section_end = Some(i + 1);
+        if let Some((key, _)) = line.split_once('=') {
+            if unquote(key.trim()) == name {
+                return Located::Key(i);
+            }
+        }
+    }
+
+    match section_end {
+        Some(i) if section_seen => Located::SectionEnd(i),
+        _ => Located::Missing,
+    }
+}
+
+/// "recency.domains.infra" -> ("recency.domains", "infra")
+fn split_key(key: &str) -> Result<(&str, &str), Box<dyn std::error::Error>> {
+    let (section, name) = key.rsplit_once('.').unwrap_or(("", key));
+    if name.is_empty() {
+        return Err(format!("config: '{}' is not a valid key", key).into());
+    }
+    Ok((section, name))
+}
+
+fn format_key(name: &str) -> String {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// Numbers and booleans bare, everything else quoted
+fn format_value(value: &str) -> String {
+    if value == "true" || value == "false" || value.parse::<f64>().is_ok() {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+/// Recency half-lives: a global default plus per-domain and per-tag overrides
+///
+/// ```toml
+/// [recency]
+/// half_life_days = 7
+///
+/// [recency.domains]
+/// infra = 90
+///
+/// [recency.tags]
+/// ui = 2
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecencyConfig {
+    pub default_days: f64,
+    pub domains: BTreeMap<String, f64>,
+    pub tags: BTreeMap<String, f64>,
+}
+
+impl Default for RecencyConfig {
+    fn default() -> Self {
+        RecencyConfig {
+            default_days: DEFAULT_HALF_LIFE_DAYS,
+            domains: BTreeMap::new(),
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+impl RecencyConfig {
+    /// Read the [recency] sections of a config
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let default_days = config
+            .get_f64("recency.half_life_days")?
+            .unwrap_or(DEFAULT_HALF_LIFE_DAYS);
+
+        Ok(RecencyConfig {
+            default_days: positive_days("recency.half_life_days", default_days)?,
+            domains: half_life_map(config, "recency.domains")?,
+            tags: half_life_map(config, "recency.tags")?,
+        })
+    }
+
+    /// Half-life in seconds for one feature
+    ///
+    /// A matching tag wins over the domain (tags are the more specific
+    /// label); with several matching tags the longest half-life is used.
+    /// Otherwise the domain override, otherwise the global default.
+    pub fn half_life_seconds(&self, feature: &Feature) -> f64 {
+        let from_tags = feature
+            .tags
+            .iter()
+            .filter_map(|tag| self.tags.get(tag))
+            .copied()
+            .reduce(f64::max);
+
+        let days = from_tags
+            .or_else(|| self.domains.get(&feature.domain).copied())
+            .unwrap_or(self.default_days);
+
+        days * SECONDS_PER_DAY
+    }
+}
+
+/// Read every key under a section as a half-life in days
+fn half_life_map(
+    config: &Config,
+    section: &str,
+) -> Result<BTreeMap<String, f64>, Box<dyn std::error::Error>> {
+    config
+        .section(section)
+        .into_iter()
+        .map(|(name, raw)| {
+            let key = format!("{}.{}", section, name);
+            let days: f64 = raw
+                .parse()
+                .map_err(|_| format!("config: {} must be a number, got '{}'", key, raw))?;
+            Ok((name.to_string(), positive_days(&key, days)?))
+        })
+        .collect()
+}
+
+fn positive_days(key: &str, days: f64) -> Result<f64, Box<dyn std::error::Error>> {
+    if days > 0.0 && days.is_finite() {
+        Ok(days)
+    } else {
+        Err(format!("config: {} must be a positive number of days", key).into())
+    }
+}
+
+/// Whether feature IDs must look like `domain/slug`
+///
+/// ```toml
+/// [ids]
+/// namespace = true
+/// ```
+pub fn namespaced_ids(config: &Config) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(config.get_bool("ids.namespace")?.unwrap_or(false))
+}
+
+/// Journal events `legend update` lets pile up before it rewrites the
+/// snapshot (see journal.rs); 1 saves it on every update
+///
+/// ```toml
+/// [storage]
+/// snapshot_every = 50
+/// ```
+pub fn snapshot_every(config: &Config) -> Result<u64, Box<dyn std::error::Error>> {
+    Ok(positive_count(config, "storage.snapshot_every")?.unwrap_or(1))
+}
+
+/// Statuses a project adds to the built-in four
+///
+/// ```toml
+/// [statuses]
+/// custom = "InReview, Deferred"
+/// ```
+pub fn custom_statuses(config: &Config) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut names: Vec<String> = Vec::new();
+    for name in config.get("statuses.custom").unwrap_or("").split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        if !matches!(FeatureStatus::from_name(name), FeatureStatus::Custom(_)) {
+            return Err(format!("config: statuses.custom: '{}' is already a built-in status", name).into());
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("config: statuses.custom: '{}' must be letters, digits, - or _", name).into());
+        }
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// A status as written in an update or on the command line: a built-in,
+/// or one of statuses.custom in its configured spelling
+pub fn resolve_status(status: &FeatureStatus, config: &Config) -> Result<FeatureStatus, Box<dyn std::error::Error>> {
+    let FeatureStatus::Custom(name) = status else {
+        return Ok(status.clone());
+    };
+    let custom = custom_statuses(config)?;
+    match custom.iter().find(|c| c.eq_ignore_ascii_case(name)) {
+        Some(configured) => Ok(FeatureStatus::Custom(configured.clone())),
+        None => {
+            let mut known: Vec<&str> = FeatureStatus::BUILT_IN.iter().map(|s| s.name()).collect();
+            known.extend(custom.iter().map(String::as_str));
+            Err(format!(
+                "Unknown status '{}' (expected one of {}; add custom ones to statuses.custom in .legend/config.toml)",
+                name,
+                known.join(", ")
+            )
+            .into())
+        }
+    }
+}
+
+/// Caps on how much of a project discover, coverage, and `--analyze` read
+///
+/// ```toml
+/// [limits]
+/// max_files = 100000   # files walked
+/// max_index_mb = 256   # source read to build the import index
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitsConfig {
+    pub max_files: usize,
+    pub max_index_bytes: u64,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        LimitsConfig {
+            max_files: 100_000,
+            max_index_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+impl LimitsConfig {
+    /// Read the [limits] section of a config
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let defaults = LimitsConfig::default();
+        let max_files = positive_count(config, "limits.max_files")?.map_or(defaults.max_files, |n| n as usize);
+        let max_index_bytes = positive_count(config, "limits.max_index_mb")?
+            .map_or(defaults.max_index_bytes, |mb| mb * 1024 * 1024);
+        Ok(LimitsConfig {
+            max_files,
+            max_index_bytes,
+        })
+    }
+}
+
+fn positive_count(config: &Config, key: &str) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    match config.get(key) {
+        None => Ok(None),
+        Some(raw) => match raw.parse::<u64>() {
+            Ok(n) if n > 0 => Ok(Some(n)),
+            _ => Err(format!("config: {} must be a positive whole number, got '{}'", key, raw).into()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Legend settings
+[recency]
+half_life_days = 10   # global default
+
+[recency.domains]
+infra = 90
+"web ui" = 3
+
+[recency.tags]
+ui = 2
+architecture = 60
+"#;
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse_config(SAMPLE).unwrap();
+        assert_eq!(config.get("recency.half_life_days"), Some("10"));
+        assert_eq!(config.get("recency.domains.web ui"), Some("3"));
+        assert_eq!(config.section("recency").len(), 1);
+        assert_eq!(config.section("recency.tags").len(), 2);
+
+        assert!(parse_config("[recency\n").is_err());
+        assert!(parse_config("just words\n").unwrap_err().to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_half_life_lookup() {
+        let recency = RecencyConfig::from_config(&parse_config(SAMPLE).unwrap()).unwrap();
+        let day = SECONDS_PER_DAY;
+
+        let mut feature = Feature::new(
+            "f".to_string(),
+            "F".to_string(),
+            "infra".to_string(),
+            "desc".to_string(),
+        );
+        assert_eq!(recency.half_life_seconds(&feature), 90.0 * day);
+
+        feature.tags = vec!["ui".to_string(), "architecture".to_string()];
+        assert_eq!(recency.half_life_seconds(&feature), 60.0 * day);
+
+        feature.tags.clear();
+        feature.domain = "backend".to_string();
+        assert_eq!(recency.half_life_seconds(&feature), 10.0 * day);
+    }
+
+    #[test]
+    fn test_rejects_bad_half_life() {
+        let config = parse_config("[recency.domains]\ninfra = soon\n").unwrap();
+        assert!(RecencyConfig::from_config(&config).is_err());
+
+        let config = parse_config("[recency]\nhalf_life_days = 0\n").unwrap();
+        assert!(RecencyConfig::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_namespaced_ids() {
+        assert!(!namespaced_ids(&Config::default()).unwrap());
+        assert!(namespaced_ids(&parse_config("[ids]\nnamespace = true\n").unwrap()).unwrap());
+        assert!(namespaced_ids(&parse_config("[ids]\nnamespace = yes\n").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_custom_statuses() {
+        let config = parse_config("[statuses]\ncustom = \"InReview, Deferred\"\n").unwrap();
+        let custom = |name: &str| FeatureStatus::Custom(name.to_string());
+        assert_eq!(resolve_status(&custom("inreview"), &config).unwrap(), custom("InReview"));
+        assert_eq!(resolve_status(&FeatureStatus::Blocked, &config).unwrap(), FeatureStatus::Blocked);
+        assert!(resolve_status(&custom("Shipped"), &config).is_err());
+        assert!(resolve_status(&custom("InReview"), &Config::default()).is_err());
+        assert!(custom_statuses(&parse_config("[statuses]\ncustom = \"Complete\"\n").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        assert_eq!(LimitsConfig::from_config(&Config::default()).unwrap(), LimitsConfig::default());
+
+        let limits = LimitsConfig::from_config(&parse_config("[limits]\nmax_files = 500\nmax_index_mb = 2\n").unwrap()).unwrap();
+        assert_eq!(limits.max_files, 500);
+        assert_eq!(limits.max_index_bytes, 2 * 1024 * 1024);
+
+        assert!(LimitsConfig::from_config(&parse_config("[limits]\nmax_files = 0\n").unwrap()).is_err());
+        assert!(LimitsConfig::from_config(&parse_config("[limits]\nmax_files = lots\n").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_overlay_prefers_project() {
+        let mut config = parse_config("editor = \"vim\"\n[recency]\nhalf_life_days = 3\n").unwrap();
+        config.overlay(parse_config("[recency]\nhalf_life_days = 10\n").unwrap());
+        assert_eq!(config.get("recency.half_life_days"), Some("10"));
+        assert_eq!(config.get("editor"), Some("vim"));
+    }
+
+    #[test]
+    fn test_set_and_unset_value() {
+        let content = "editor = \"vim\"\n\n[recency]  # decay\nhalf_life_days = 7\n\n[ids]\nnamespace = true\n";
+
+        let updated = set_value(content, "recency.half_life_days", "14").unwrap();
+        assert!(updated.contains("[recency]  # decay\nhalf_life_days = 14\n"));
+
+        let updated = set_value(&updated, "recency.domains.web ui", "2").unwrap();
+        assert!(updated.ends_with("[recency.domains]\n\"web ui\" = 2\n"));
+
+        let updated = set_value(&updated, "ids.prefix", "api").unwrap();
+        assert!(updated.contains("namespace = true\nprefix = \"api\"\n"));
+
+        let updated = set_value(&updated, "theme", "plain").unwrap();
+        assert!(updated.starts_with("editor = \"vim\"\ntheme = \"plain\"\n"));
+
+        let config = parse_config(&updated).unwrap();
+        assert_eq!(config.get("recency.domains.web ui"), Some("2"));
+        assert_eq!(config.get("ids.prefix"), Some("api"));
+
+        let removed = unset_value(&updated, "ids.prefix").unwrap().unwrap();
+        assert!(parse_config(&removed).unwrap().get("ids.prefix").is_none());
+        assert!(unset_value(&removed, "ids.prefix").unwrap().is_none());
+        assert!(set_value(content, "editor", "say \"hi\"").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let files = parse_config("[recency.domains]\nInfra = 90\n").unwrap();
+        let vars = [
+            ("LEGEND_RECENCY_HALF_LIFE_DAYS", "14"),
+            ("LEGEND_RECENCY_DOMAINS_INFRA", "30"),
+            ("LEGEND_RECENCY_TAGS_UI", "2"),
+            ("LEGEND_SCREEN_READER", "1"),
+            ("LEGEND_NOT_A_KEY", "x"),
+            ("HOME", "/root"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+
+        let env = env_overrides(vars, &files);
+        assert_eq!(env.config.get("recency.half_life_days"), Some("14"));
+        assert_eq!(env.config.get("recency.domains.Infra"), Some("30"));
+        assert_eq!(env.config.get("recency.tags.ui"), Some("2"));
+        assert_eq!(env.applied.len(), 3);
+        assert_eq!(env.unknown, vec!["LEGEND_NOT_A_KEY"]);
+    }
+}
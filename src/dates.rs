@@ -0,0 +1,189 @@
+// Date helpers - convert between Unix timestamps and calendar dates
+//
+// Legend stores every time as i64 Unix seconds (UTC). Users type dates
+// as "2024-03-01", so we need a small bridge between the two.
+//
+// Why not chrono? Minimal dependencies (see PLAN.md). The civil-date
+// conversions below are Howard Hinnant's well-known algorithms and are
+// exact for the proleptic Gregorian calendar.
+
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+pub const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+/// Parse a user-supplied date into a Unix timestamp (UTC midnight)
+///
+/// Accepts:
+/// - "YYYY-MM-DD" (e.g. "2024-03-01")
+/// - Raw Unix seconds (e.g. "1709251200")
+pub fn parse_date(input: &str) -> Result<i64, Box<dyn std::error::Error>> {
+    let input = input.trim();
+
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        return input
+            .parse::<i64>()
+            .map_err(|e| format!("Invalid timestamp '{}': {}", input, e).into());
+    }
+
+    let parts: Vec<&str> = input.split('-').collect();
+    if parts.len() != 3 {
+        return Err(format!("Invalid date '{}'. Expected YYYY-MM-DD.", input).into());
+    }
+
+    let year: i64 = parts[0]
+        .parse()
+        .map_err(|_| format!("Invalid year in '{}'", input))?;
+    let month: u32 = parts[1]
+        .parse()
+        .map_err(|_| format!("Invalid month in '{}'", input))?;
+    let day: u32 = parts[2]
+        .parse()
+        .map_err(|_| format!("Invalid day in '{}'", input))?;
+
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(format!("Invalid date '{}'", input).into());
+    }
+
+    Ok(days_from_civil(year, month, day) * SECONDS_PER_DAY)
+}
+
+/// Parse an age like "48h", "30d", or "2w" into seconds
+pub fn parse_age(input: &str) -> Result<i64, Box<dyn std::error::Error>> {
+    let input = input.trim();
+    let invalid = || format!("Invalid age '{}'. Expected hours, days, or weeks, e.g. 48h, 30d, or 2w.", input);
+    let (number, unit) = match input.char_indices().last() {
+        Some((i, 'h')) => (&input[..i], 60 * 60),
+        Some((i, 'd')) => (&input[..i], SECONDS_PER_DAY),
+        Some((i, 'w')) => (&input[..i], SECONDS_PER_WEEK),
+        _ => return Err(invalid().into()),
+    };
+    let count: u32 = number.parse().map_err(|_| invalid())?;
+    Ok(i64::from(count) * unit)
+}
+
+/// Format a Unix timestamp as "YYYY-MM-DD" (UTC)
+pub fn format_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Format a Unix timestamp as "YYYY-MM-DD HH:MM" (UTC)
+pub fn format_datetime(timestamp: i64) -> String {
+    let minutes = timestamp.rem_euclid(SECONDS_PER_DAY) / 60;
+    format!("{} {:02}:{:02}", format_date(timestamp), minutes / 60, minutes % 60)
+}
+
+/// Calendar days (UTC) from `now` to `due`: 0 on the day, negative once past
+///
+/// Counted by date, not in 24-hour steps, so something due today isn't
+/// "in 0 days" at 9am and "overdue" at noon
+pub fn days_until(due: i64, now: i64) -> i64 {
+    due.div_euclid(SECONDS_PER_DAY) - now.div_euclid(SECONDS_PER_DAY)
+}
+
+/// "due 2024-03-01 (in 12 days)", "due 2024-03-01 (today)", or "due 2024-03-01 (5 days overdue)"
+pub fn due_label(due: i64, now: i64) -> String {
+    format!("due {} ({})", format_date(due), days_away(due, now))
+}
+
+/// "in 12 days", "today", or "5 days overdue"
+pub fn days_away(due: i64, now: i64) -> String {
+    match days_until(due, now) {
+        0 => "today".to_string(),
+        d if d > 0 => format!("in {} days", d),
+        d => format!("{} days overdue", -d),
+    }
+}
+
+/// Start of the week (Monday 00:00 UTC) containing a timestamp
+pub fn week_start(timestamp: i64) -> i64 {
+    let days = timestamp.div_euclid(SECONDS_PER_DAY);
+    // 1970-01-01 was a Thursday, three days after a Monday
+    let days_since_monday = (days + 3).rem_euclid(7);
+    (days - days_since_monday) * SECONDS_PER_DAY
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a calendar date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Calendar date for a count of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_date("2024-03-01").unwrap(), 1709251200);
+        assert_eq!(parse_date("1709251200").unwrap(), 1709251200);
+    }
+
+    #[test]
+    fn test_parse_date_invalid() {
+        assert!(parse_date("2024-02-30").is_err());
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("March 1st").is_err());
+        assert!(parse_date("").is_err());
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d").unwrap(), 30 * SECONDS_PER_DAY);
+        assert_eq!(parse_age("2w").unwrap(), 2 * SECONDS_PER_WEEK);
+        assert_eq!(parse_age("48h").unwrap(), 2 * SECONDS_PER_DAY);
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("1y").is_err());
+    }
+
+    #[test]
+    fn test_format_date_roundtrip() {
+        assert_eq!(format_date(1709251200), "2024-03-01");
+        assert_eq!(format_date(1709251200 + 3600), "2024-03-01");
+        assert_eq!(format_date(parse_date("2000-02-29").unwrap()), "2000-02-29");
+        assert_eq!(format_datetime(1709251200 + 13 * 3600 + 5 * 60 + 59), "2024-03-01 13:05");
+    }
+
+    #[test]
+    fn test_week_start() {
+        // 2024-03-06 was a Wednesday; its week starts Monday 2024-03-04
+        let wednesday = parse_date("2024-03-06").unwrap() + 15 * 3600;
+        assert_eq!(format_date(week_start(wednesday)), "2024-03-04");
+
+        let monday = parse_date("2024-03-04").unwrap();
+        assert_eq!(week_start(monday), monday);
+    }
+}
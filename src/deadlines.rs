@@ -0,0 +1,98 @@
+// Deadlines - features whose due_date is close or past
+//
+// A feature is overdue the day after its due_date and due soon from
+// DUE_SOON_DAYS before it; a Complete feature is neither. `show` marks
+// both, and get_state lists them in its `deadlines` section, so the
+// SessionStart hook opens with what's about to slip.
+
+use crate::dates;
+use crate::types::Feature;
+use serde::Serialize;
+
+/// How far ahead a due date counts as "due soon"
+pub const DUE_SOON_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Urgency {
+    Overdue,
+    DueSoon,
+}
+
+/// Is `feature` overdue or due soon at `now`? None if neither
+pub fn urgency(feature: &Feature, now: i64) -> Option<Urgency> {
+    if feature.is_complete() {
+        return None;
+    }
+    match dates::days_until(feature.due_date?, now) {
+        d if d < 0 => Some(Urgency::Overdue),
+        d if d <= DUE_SOON_DAYS => Some(Urgency::DueSoon),
+        _ => None,
+    }
+}
+
+/// One entry of get_state's `deadlines` section
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Deadline<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    /// "YYYY-MM-DD"
+    pub due_date: String,
+    /// Calendar days left; negative once overdue
+    pub days_left: i64,
+}
+
+/// Overdue and due-soon features, soonest (most overdue) first
+pub fn upcoming(features: &[Feature], now: i64) -> Vec<Deadline<'_>> {
+    let mut deadlines: Vec<Deadline> = features
+        .iter()
+        .filter(|f| urgency(f, now).is_some())
+        .filter_map(|f| {
+            let due = f.due_date?;
+            Some(Deadline {
+                id: &f.id,
+                name: &f.name,
+                due_date: dates::format_date(due),
+                days_left: dates::days_until(due, now),
+            })
+        })
+        .collect();
+    deadlines.sort_by(|a, b| a.days_left.cmp(&b.days_left).then_with(|| a.id.cmp(b.id)));
+    deadlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dates::SECONDS_PER_DAY;
+    use crate::types::FeatureStatus;
+
+    fn due(id: &str, day: i64) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string());
+        f.due_date = Some(day * SECONDS_PER_DAY);
+        f
+    }
+
+    #[test]
+    fn test_urgency() {
+        let now = 10 * SECONDS_PER_DAY + 3600;
+        assert_eq!(urgency(&due("a", 9), now), Some(Urgency::Overdue));
+        assert_eq!(urgency(&due("a", 10), now), Some(Urgency::DueSoon));
+        assert_eq!(urgency(&due("a", 10 + DUE_SOON_DAYS), now), Some(Urgency::DueSoon));
+        assert_eq!(urgency(&due("a", 11 + DUE_SOON_DAYS), now), None);
+
+        let mut done = due("a", 9);
+        done.status = FeatureStatus::Complete;
+        assert_eq!(urgency(&done, now), None);
+    }
+
+    #[test]
+    fn test_upcoming() {
+        let mut undated = due("undated", 0);
+        undated.due_date = None;
+        let features = vec![due("later", 14), due("far", 40), due("late", 8), undated];
+        let deadlines = upcoming(&features, 10 * SECONDS_PER_DAY);
+        let summary: Vec<(&str, i64)> = deadlines.iter().map(|d| (d.id, d.days_left)).collect();
+        assert_eq!(summary, vec![("late", -2), ("later", 4)]);
+        assert_eq!(deadlines[0].due_date, "1970-01-09");
+    }
+}
@@ -0,0 +1,140 @@
+// Dependencies - which features must be done before which
+//
+// A feature lists the IDs it waits on in `depends_on`. The edges form a
+// graph that must stay acyclic: `legend update` rejects an update that
+// references an unknown ID or closes a loop, and validate reports either
+// if the state got that way by other means.
+//
+// A feature is waiting while any of its dependencies is not Complete;
+// show marks those, and impact counts dependents as affected.
+//
+// Rust concepts in this file:
+// - Depth-first search with an explicit path to report the cycle
+
+use crate::types::{Feature, FeatureStatus};
+use std::collections::HashMap;
+
+/// Why the dependency edges are invalid, or Ok
+pub fn check_dependencies(features: &[Feature]) -> Result<(), String> {
+    let by_id: HashMap<&str, &Feature> = features.iter().map(|f| (f.id.as_str(), f)).collect();
+
+    for feature in features {
+        for dep in &feature.depends_on {
+            if dep == &feature.id {
+                return Err(format!("{} depends on itself", feature.id));
+            }
+            if !by_id.contains_key(dep.as_str()) {
+                return Err(format!("{} depends on unknown feature '{}'", feature.id, dep));
+            }
+        }
+    }
+
+    match find_cycle(features) {
+        Some(cycle) => Err(format!("dependency cycle: {}", cycle.join(" -> "))),
+        None => Ok(()),
+    }
+}
+
+/// A cycle as a path that ends where it starts, if there is one
+pub fn find_cycle(features: &[Feature]) -> Option<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit<'a>(
+        id: &'a str,
+        by_id: &HashMap<&'a str, &'a Feature>,
+        marks: &mut HashMap<&'a str, Mark>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        match marks.get(id) {
+            Some(Mark::Done) => return None,
+            Some(Mark::Visiting) => {
+                let start = path.iter().position(|p| *p == id).unwrap_or(0);
+                let mut cycle: Vec<String> = path[start..].iter().map(|s| s.to_string()).collect();
+                cycle.push(id.to_string());
+                return Some(cycle);
+            }
+            None => {}
+        }
+
+        marks.insert(id, Mark::Visiting);
+        path.push(id);
+        if let Some(feature) = by_id.get(id) {
+            for dep in &feature.depends_on {
+                if let Some(cycle) = visit(dep, by_id, marks, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        marks.insert(id, Mark::Done);
+        None
+    }
+
+    let by_id: HashMap<&str, &Feature> = features.iter().map(|f| (f.id.as_str(), f)).collect();
+    let mut marks = HashMap::new();
+    features
+        .iter()
+        .find_map(|f| visit(&f.id, &by_id, &mut marks, &mut Vec::new()))
+}
+
+/// The dependencies of `feature` that aren't Complete yet
+pub fn waiting_on<'a>(feature: &'a Feature, features: &[Feature]) -> Vec<&'a str> {
+    feature
+        .depends_on
+        .iter()
+        .filter(|dep| {
+            features
+                .iter()
+                .find(|f| &f.id == *dep)
+                .is_none_or(|f| f.status != FeatureStatus::Complete)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Features that list `id` in depends_on
+pub fn dependents<'a>(features: &'a [Feature], id: &str) -> Vec<&'a Feature> {
+    features.iter().filter(|f| f.depends_on.iter().any(|d| d == id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, deps: &[&str]) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), "core".to_string(), "d".to_string());
+        f.depends_on = deps.iter().map(|d| d.to_string()).collect();
+        f
+    }
+
+    #[test]
+    fn test_check_dependencies() {
+        let ok = vec![feature("a", &[]), feature("b", &["a"]), feature("c", &["a", "b"])];
+        assert!(check_dependencies(&ok).is_ok());
+
+        let unknown = vec![feature("a", &["ghost"])];
+        assert_eq!(check_dependencies(&unknown).unwrap_err(), "a depends on unknown feature 'ghost'");
+
+        let self_loop = vec![feature("a", &["a"])];
+        assert_eq!(check_dependencies(&self_loop).unwrap_err(), "a depends on itself");
+
+        let cycle = vec![feature("a", &["b"]), feature("b", &["c"]), feature("c", &["a"]), feature("d", &["a"])];
+        assert_eq!(check_dependencies(&cycle).unwrap_err(), "dependency cycle: a -> b -> c -> a");
+    }
+
+    #[test]
+    fn test_waiting_on_and_dependents() {
+        let mut done = feature("done", &[]);
+        done.status = FeatureStatus::Complete;
+        let features = vec![done, feature("open", &[]), feature("c", &["done", "open"])];
+
+        assert_eq!(waiting_on(&features[2], &features), vec!["open"]);
+        assert!(waiting_on(&features[0], &features).is_empty());
+        let ids: Vec<_> = dependents(&features, "open").iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["c"]);
+    }
+}
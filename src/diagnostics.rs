@@ -0,0 +1,198 @@
+// Diagnostics - crash reports and bug-report bundles
+//
+// A panic in a hook shows up as a cryptic failure in Claude Code. The
+// panic hook installed here writes a crash report to .legend/crash/ and
+// prints a short, friendly message instead of a raw backtrace.
+//
+// Except for a write to a closed stdout (`legend show | head`): println!
+// panics on the broken pipe, and that is the reader having had enough,
+// not a crash, so the process just exits 0.
+//
+// Privacy rule for everything in this file: describe the state file,
+// never its contents. Feature names, descriptions, and context stay out
+// of crash reports and bundles, and so do command arguments past the
+// flag names. (The compressed bytes can contain literal text, so we only
+// ever report the size prefix, not raw bytes.)
+
+use crate::encryption;
+use crate::migrations;
+use crate::output;
+use crate::storage;
+use crate::types::current_timestamp;
+use serde_json::json;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory (inside the Legend directory) where crash reports are written
+const CRASH_DIR_NAME: &str = "crash";
+
+pub fn crash_dir() -> PathBuf {
+    storage::legend_path(CRASH_DIR_NAME)
+}
+
+/// Install a panic hook that writes a crash report and explains what to do
+///
+/// `args` are the process arguments, captured up front so the report can
+/// say which command crashed
+pub fn install_panic_hook(args: Vec<String>) {
+    std::panic::set_hook(Box::new(move |info| {
+        if info.payload_as_str().is_some_and(is_broken_pipe) {
+            std::process::exit(0);
+        }
+        let backtrace = Backtrace::force_capture();
+        let report = render_crash_report(&args, &info.to_string(), &backtrace.to_string());
+
+        let saved = write_crash_report(&report);
+        if output::diag_json() {
+            let fields = match &saved {
+                Ok(path) => json!({"crash_report": path.display().to_string()}),
+                Err(_) => json!({"crash_report_text": report}),
+            };
+            output::diag(output::Level::Error, "Legend crashed unexpectedly", fields);
+            return;
+        }
+
+        match saved {
+            Ok(path) => {
+                eprintln!("Legend crashed unexpectedly. Sorry about that!");
+                eprintln!("  A crash report was saved to {}", path.display());
+                eprintln!("  Run 'legend diag bundle' and attach the output to a bug report.");
+            }
+            Err(_) => {
+                // Couldn't save the report - print it so it isn't lost
+                eprintln!("Legend crashed unexpectedly.\n\n{}", report);
+            }
+        }
+    }));
+}
+
+/// Whether a panic message is println! failing on a closed stdout
+fn is_broken_pipe(panic_message: &str) -> bool {
+    panic_message.starts_with("failed printing to stdout") && panic_message.contains("Broken pipe")
+}
+
+/// Build the text of a crash report
+fn render_crash_report(args: &[String], panic_message: &str, backtrace: &str) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Legend crash report ({})\n", current_timestamp()));
+    out.push_str(&format!("version: {}\n", env!("CARGO_PKG_VERSION")));
+    out.push_str(&format!("platform: {}-{}\n", std::env::consts::OS, std::env::consts::ARCH));
+    out.push_str(&format!("command: {}\n", args.get(1).map(|s| s.as_str()).unwrap_or("")));
+    out.push_str(&format!("args: {}\n", redact_args(args.get(2..).unwrap_or(&[]))));
+    out.push_str(&format!("state file: {}\n", describe_state_file()));
+    out.push_str(&format!("\npanic: {}\n", panic_message));
+    out.push_str(&format!("\nbacktrace:\n{}\n", backtrace));
+    out
+}
+
+/// The arguments after the command with everything but flag names
+/// replaced: values are notes, decisions, and descriptions
+fn redact_args(args: &[String]) -> String {
+    let kept: Vec<&str> = args
+        .iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((flag, _)) if flag.starts_with("--") => flag,
+            _ if arg.starts_with('-') => arg.as_str(),
+            _ => "<redacted>",
+        })
+        .collect();
+    kept.join(" ")
+}
+
+/// Save a crash report to .legend/crash/ (or the temp dir outside a project)
+fn write_crash_report(report: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = if storage::legend_dir().is_dir() {
+        crash_dir()
+    } else {
+        std::env::temp_dir().join("legend-crash")
+    };
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("crash-{}.txt", current_timestamp()));
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// One-line description of the state file: size and header, no content
+pub fn describe_state_file() -> String {
+    match fs::read(storage::state_file()) {
+        Ok(bytes) => describe_state_header(&bytes),
+        Err(e) => format!("unreadable ({})", e),
+    }
+}
+
+/// Describe the compressed state from its first bytes
+///
+/// (A JSON state file, see storage.rs, is only said to be one.)
+///
+/// After the format header (see migrations.rs), LZ4 with prepend_size=true
+/// starts with the uncompressed length as a little-endian u32. A wildly
+/// wrong value points at corruption.
+fn describe_state_header(bytes: &[u8]) -> String {
+    if encryption::is_encrypted(bytes) {
+        return format!("age-encrypted, {} bytes on disk", bytes.len());
+    }
+    if storage::Format::detect(bytes) == storage::Format::Json {
+        return format!("json, {} bytes on disk", bytes.len());
+    }
+    let (version, block) = match migrations::split_header(bytes) {
+        Some((header, block)) => (format!("format v{}, ", header.version), block),
+        None => (String::new(), bytes),
+    };
+    let Some(size) = block.first_chunk::<4>() else {
+        return format!("{}{} bytes (too short for an LZ4 header)", version, bytes.len());
+    };
+
+    format!(
+        "{}{} bytes on disk, lz4 header says {} bytes uncompressed",
+        version,
+        bytes.len(),
+        u32::from_le_bytes(*size)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_state_header() {
+        let bytes = [0x10, 0x27, 0x00, 0x00, 0xAA, 0xBB];
+        assert_eq!(
+            describe_state_header(&bytes),
+            "6 bytes on disk, lz4 header says 10000 bytes uncompressed"
+        );
+        assert!(describe_state_header(&[1, 2]).contains("too short"));
+
+        let headered = [&migrations::header(9, &bytes)[..], &bytes].concat();
+        assert_eq!(
+            describe_state_header(&headered),
+            "format v9, 12 bytes on disk, lz4 header says 10000 bytes uncompressed"
+        );
+        assert_eq!(describe_state_header(b"{\n  \"format_version\": 9\n}\n"), "json, 26 bytes on disk");
+        assert_eq!(describe_state_header(b"age-encryption.org/v1\n"), "age-encrypted, 22 bytes on disk");
+    }
+
+    #[test]
+    fn test_is_broken_pipe() {
+        assert!(is_broken_pipe("failed printing to stdout: Broken pipe (os error 32)"));
+        assert!(!is_broken_pipe("failed printing to stdout: No space left on device (os error 28)"));
+        assert!(!is_broken_pipe("index out of bounds"));
+    }
+
+    #[test]
+    fn test_render_crash_report() {
+        let args: Vec<String> = ["legend", "search", "auth", "--domain", "payments", "--limit=5"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let report = render_crash_report(&args, "index out of bounds", "0: main");
+
+        assert!(report.contains("command: search\n"));
+        assert!(report.contains("args: <redacted> --domain <redacted> --limit\n"));
+        assert!(!report.contains("auth") && !report.contains("payments"));
+        assert!(report.contains("panic: index out of bounds"));
+        assert!(report.contains("backtrace:\n0: main"));
+    }
+}
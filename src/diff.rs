@@ -0,0 +1,272 @@
+// State diffing - what changed between two versions of LegendState
+//
+// Used by the journal (to record what each write did) and anywhere we
+// need to explain a change to the user.
+//
+// Field-level comparison goes through serde_json::Value so new Feature
+// fields are picked up automatically - no per-field match to maintain.
+//
+// Rust concepts in this file:
+// - serde_json::Value as a generic, inspectable representation
+// - HashMap lookups to pair features by ID
+
+use crate::types::{Feature, LegendState};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Fields that change on every write and carry no meaning on their own
+const IGNORED_FIELDS: &[&str] = &["last_updated", "recency_score", "created_at"];
+
+/// One field that differs between two versions of a feature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// A feature present in both states with at least one changed field
+#[derive(Debug, Clone)]
+pub struct FeatureDiff {
+    pub id: String,
+    pub changes: Vec<FieldChange>,
+    pub after: Feature,
+}
+
+/// Everything that differs between two states
+#[derive(Debug, Clone, Default)]
+pub struct StateDiff {
+    pub added: Vec<Feature>,
+    pub removed: Vec<Feature>,
+    pub changed: Vec<FeatureDiff>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// One line per added (+), removed (-), and changed (~) feature, with an
+/// indented `field: before -> after` line per changed field
+pub fn render_lines(diff: &StateDiff) -> Vec<String> {
+    let mut lines = Vec::new();
+    for feature in &diff.added {
+        lines.push(format!("+ {} ({})", feature.id, feature.name));
+    }
+    for feature in &diff.removed {
+        lines.push(format!("- {} ({})", feature.id, feature.name));
+    }
+    for changed in &diff.changed {
+        lines.push(format!("~ {}", changed.id));
+        for change in &changed.changes {
+            lines.push(format!("    {}: {} -> {}", change.field, change.before, change.after));
+        }
+    }
+    lines
+}
+
+/// Summarize field changes in a phrase: renames and status flips spelled
+/// out, files and notes counted, everything else listed by field name
+///
+/// `status Pending → InProgress; files +2; note added; updated tags`
+pub fn describe_changes(changes: &[FieldChange]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut other_fields: Vec<&str> = Vec::new();
+
+    for change in changes {
+        match change.field.as_str() {
+            "name" => parts.push(format!(
+                "renamed from \"{}\" to \"{}\"",
+                display_value(&change.before),
+                display_value(&change.after)
+            )),
+            "status" => parts.push(format!(
+                "status {} → {}",
+                display_value(&change.before),
+                display_value(&change.after)
+            )),
+            "files_involved" => {
+                let (added, removed) = count_items(&change.before, &change.after);
+                let mut counts = Vec::new();
+                if added > 0 {
+                    counts.push(format!("+{}", added));
+                }
+                if removed > 0 {
+                    counts.push(format!("-{}", removed));
+                }
+                if counts.is_empty() {
+                    other_fields.push("files_involved");
+                } else {
+                    parts.push(format!("files {}", counts.join(" ")));
+                }
+            }
+            "notes" => match count_items(&change.before, &change.after) {
+                (1, _) => parts.push("note added".to_string()),
+                (n, _) if n > 1 => parts.push(format!("{} notes added", n)),
+                _ => other_fields.push("notes"),
+            },
+            field => other_fields.push(field),
+        }
+    }
+
+    if !other_fields.is_empty() {
+        parts.push(format!("updated {}", other_fields.join(", ")));
+    }
+
+    parts.join("; ")
+}
+
+/// Strings without their JSON quotes, everything else as JSON
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// (items only in `after`, items only in `before`) of two JSON arrays
+fn count_items(before: &Value, after: &Value) -> (usize, usize) {
+    let empty = Vec::new();
+    let before = before.as_array().unwrap_or(&empty);
+    let after = after.as_array().unwrap_or(&empty);
+    (
+        after.iter().filter(|v| !before.contains(v)).count(),
+        before.iter().filter(|v| !after.contains(v)).count(),
+    )
+}
+
+/// Compare two states feature by feature
+///
+/// Features are paired by ID. Output order follows `after` for added and
+/// changed features, and `before` for removed ones.
+pub fn diff_states(before: &LegendState, after: &LegendState) -> StateDiff {
+    let before_by_id: HashMap<&str, &Feature> =
+        before.features.iter().map(|f| (f.id.as_str(), f)).collect();
+    let after_by_id: HashMap<&str, &Feature> =
+        after.features.iter().map(|f| (f.id.as_str(), f)).collect();
+
+    let mut diff = StateDiff::default();
+
+    for feature in &after.features {
+        match before_by_id.get(feature.id.as_str()) {
+            None => diff.added.push(feature.clone()),
+            Some(old) => {
+                let changes = diff_features(old, feature);
+                if !changes.is_empty() {
+                    diff.changed.push(FeatureDiff {
+                        id: feature.id.clone(),
+                        changes,
+                        after: feature.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for feature in &before.features {
+        if !after_by_id.contains_key(feature.id.as_str()) {
+            diff.removed.push(feature.clone());
+        }
+    }
+
+    diff
+}
+
+/// Compare two versions of the same feature field by field
+pub fn diff_features(before: &Feature, after: &Feature) -> Vec<FieldChange> {
+    let before_value = serde_json::to_value(before).unwrap_or(Value::Null);
+    let after_value = serde_json::to_value(after).unwrap_or(Value::Null);
+
+    let (Some(before_map), Some(after_map)) = (before_value.as_object(), after_value.as_object())
+    else {
+        return Vec::new();
+    };
+
+    after_map
+        .iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, new)| {
+            let old = before_map.get(field).cloned().unwrap_or(Value::Null);
+            if &old == new {
+                None
+            } else {
+                Some(FieldChange {
+                    field: field.clone(),
+                    before: old,
+                    after: new.clone(),
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FeatureStatus;
+    use serde_json::json;
+
+    fn make_feature(id: &str) -> Feature {
+        Feature::new(
+            id.to_string(),
+            format!("Feature {}", id),
+            "test".to_string(),
+            "Test feature".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_diff_states() {
+        let mut before = LegendState::new("Test".to_string());
+        before.features = vec![make_feature("keep"), make_feature("gone"), make_feature("edit")];
+
+        let mut after = before.clone();
+        after.features.retain(|f| f.id != "gone");
+        after.features.push(make_feature("new"));
+        let edited = after.features.iter_mut().find(|f| f.id == "edit").unwrap();
+        edited.status = FeatureStatus::Complete;
+        edited.last_updated += 100;
+
+        let diff = diff_states(&before, &after);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].id, "new");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].id, "gone");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].changes.len(), 1);
+        assert_eq!(diff.changed[0].changes[0].field, "status");
+        assert_eq!(diff.changed[0].changes[0].after, Value::from("Complete"));
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        let change = |field: &str, before: Value, after: Value| FieldChange {
+            field: field.to_string(),
+            before,
+            after,
+        };
+        let changes = vec![
+            change("status", json!("Pending"), json!("InProgress")),
+            change("files_involved", json!(["a.rs"]), json!(["a.rs", "b.rs", "c.rs"])),
+            change("notes", json!([]), json!([{"text": "hi"}])),
+            change("tags", json!([]), json!(["api"])),
+        ];
+        assert_eq!(
+            describe_changes(&changes),
+            "status Pending → InProgress; files +2; note added; updated tags"
+        );
+    }
+
+    #[test]
+    fn test_timestamps_only_is_not_a_change() {
+        let before = make_feature("a");
+        let mut after = before.clone();
+        after.last_updated += 60;
+        after.recency_score = 0.5;
+
+        assert!(diff_features(&before, &after).is_empty());
+    }
+}
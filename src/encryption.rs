@@ -0,0 +1,282 @@
+// Encryption - optional encryption at rest, with age
+//
+// `legend init --encrypt` sets it up: it creates an age identity (a
+// private key file) unless there already is one, and adds its public key
+// to encryption.recipients in .legend/config.toml. From then on every
+// save encrypts state.lz4 to those recipients, and with it the copies in
+// history/, archive.lz4, and each batch appended to events.log. Loading
+// decrypts with the identity, so no command notices the difference.
+// access.json (see access.rs) is sealed the same way, and rollups.json,
+// a cache of per-domain summaries, isn't kept at all: readers build the
+// rollups from the decrypted state instead.
+//
+// These stay plaintext, and hold no feature data unless noted:
+// - config.toml, allowed_signers, state.lz4.sig, events.applied (a
+//   sequence number), and state.lock
+// - metrics.json: command names, counts, and timings (see metrics.rs)
+// - crash/ reports, which include the command line that crashed
+// - merge-conflicts.json, written by `legend merge` for hand editing: the
+//   conflicting values and the merged state, until `merge --continue`
+//   removes it (merge warns when it writes one in an encrypted project)
+//
+//   [encryption]
+//   recipients = "age1qyq..., ssh-ed25519 AAAA..."   # comma-separated
+//   identity = "~/.legend/age-key.txt"               # default shown
+//
+// The identity file can itself be passphrase-protected (`age -p`); age
+// then asks for the passphrase on the terminal at each load, which suits
+// interactive use but not hooks. Unset encryption.recipients to go back:
+// the next save writes plain state, and encrypted files still load.
+//
+// Why the age CLI and not a crate? Minimal dependencies (see PLAN.md),
+// same as signing.rs with ssh-keygen: Legend contains no cryptography,
+// only calls to a tool built for it.
+
+use crate::config::{self, Config};
+use crate::signing::expand_home;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Every age file starts with this
+const MAGIC: &[u8] = b"age-encryption.org/";
+
+/// An encrypted journal batch: `age:<last seq>:<base64>`
+const SEALED_LINE_PREFIX: &str = "age:";
+
+/// Where the identity is created unless encryption.identity says otherwise
+pub const DEFAULT_IDENTITY: &str = "~/.legend/age-key.txt";
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The [encryption] section
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptionConfig {
+    pub recipients: Vec<String>,
+    pub identity: PathBuf,
+}
+
+impl EncryptionConfig {
+    pub fn from_config(config: &Config) -> Self {
+        EncryptionConfig {
+            recipients: config
+                .get("encryption.recipients")
+                .unwrap_or("")
+                .split(',')
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(String::from)
+                .collect(),
+            identity: expand_home(config.get("encryption.identity").unwrap_or(DEFAULT_IDENTITY)),
+        }
+    }
+
+    /// Saves encrypt only when there is someone to encrypt to
+    pub fn enabled(&self) -> bool {
+        !self.recipients.is_empty()
+    }
+}
+
+fn load() -> Result<EncryptionConfig, Box<dyn std::error::Error>> {
+    Ok(EncryptionConfig::from_config(&config::load_config()?))
+}
+
+/// Whether saves encrypt in this project
+pub fn is_enabled() -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(load()?.enabled())
+}
+
+/// Whether `bytes` are an age file
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// `bytes` encrypted to the configured recipients; as they are if
+/// encryption is off
+pub fn seal(bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let encryption = load()?;
+    if !encryption.enabled() {
+        return Ok(bytes);
+    }
+    let mut args = vec!["-e".to_string()];
+    for recipient in &encryption.recipients {
+        args.push("-r".to_string());
+        args.push(recipient.clone());
+    }
+    run_age(&args, bytes).map_err(|e| format!("Failed to encrypt the state: {}", e).into())
+}
+
+/// `bytes` decrypted with the configured identity; as they are if they
+/// aren't encrypted
+pub fn open(bytes: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if !is_encrypted(&bytes) {
+        return Ok(bytes);
+    }
+    let encryption = load()?;
+    if !encryption.identity.exists() {
+        return Err(format!(
+            "The state is encrypted, and the identity to decrypt it isn't at {} (set encryption.identity)",
+            encryption.identity.display()
+        )
+        .into());
+    }
+    let args = ["-d".to_string(), "-i".to_string(), encryption.identity.to_string_lossy().to_string()];
+    run_age(&args, bytes).map_err(|e| format!("Failed to decrypt the state: {}", e).into())
+}
+
+/// Journal lines as one encrypted line, if encryption is on
+///
+/// `last_seq` stays readable so the next append can number its events
+/// without decrypting the journal
+pub fn seal_lines(lines: String, last_seq: u64) -> Result<String, Box<dyn std::error::Error>> {
+    let sealed = seal(lines.clone().into_bytes())?;
+    if !is_encrypted(&sealed) {
+        return Ok(lines);
+    }
+    Ok(format!("{}{}:{}\n", SEALED_LINE_PREFIX, last_seq, base64_encode(&sealed)))
+}
+
+/// The journal lines inside a line from seal_lines; None for a plain line
+pub fn open_line(line: &str) -> Option<Result<String, Box<dyn std::error::Error>>> {
+    let (_, encoded) = line.strip_prefix(SEALED_LINE_PREFIX)?.split_once(':')?;
+    Some(
+        base64_decode(encoded)
+            .ok_or_else(|| "bad base64".into())
+            .and_then(open)
+            .and_then(|bytes| String::from_utf8(bytes).map_err(Into::into)),
+    )
+}
+
+/// The last sequence number in a line from seal_lines
+pub fn sealed_line_seq(line: &str) -> Option<u64> {
+    line.strip_prefix(SEALED_LINE_PREFIX)?.split_once(':')?.0.parse().ok()
+}
+
+/// Turn encryption on for this project, creating the identity if needed;
+/// returns its public key and the identity's path
+pub fn enable() -> Result<(String, PathBuf), Box<dyn std::error::Error>> {
+    let mut encryption = load()?;
+    let identity = encryption.identity.clone();
+    if !identity.exists() {
+        if let Some(parent) = identity.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let args = ["-o".to_string(), identity.to_string_lossy().to_string()];
+        run_tool("age-keygen", &args, Vec::new())?;
+    }
+    let public = run_tool("age-keygen", &["-y".to_string(), identity.to_string_lossy().to_string()], Vec::new())?;
+    let recipient = String::from_utf8_lossy(&public).trim().to_string();
+    if recipient.is_empty() {
+        return Err(format!("age-keygen -y printed no public key for {}", identity.display()).into());
+    }
+
+    if !encryption.recipients.contains(&recipient) {
+        encryption.recipients.push(recipient.clone());
+    }
+    config::set_project_value("encryption.recipients", &encryption.recipients.join(", "))?;
+    Ok((recipient, identity))
+}
+
+fn run_age(args: &[String], input: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    run_tool("age", args, input)
+}
+
+/// Run an age tool with `input` on stdin; its stdout
+fn run_tool(program: &str, args: &[String], input: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run {} ({}); install age from https://age-encryption.org", program, e))?;
+
+    // Written from another thread: a large state would otherwise fill the
+    // stdout pipe while we are still blocked writing stdin
+    let mut stdin = child.stdin.take().ok_or("no stdin")?;
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(output.stdout)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (u32::from(*b) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end().trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut n = 0u32;
+    for (i, c) in text.bytes().enumerate() {
+        let value = BASE64_ALPHABET.iter().position(|&a| a == c)? as u32;
+        n = (n << 6) | value;
+        if i % 4 == 3 {
+            out.extend_from_slice(&[(n >> 16) as u8, (n >> 8) as u8, n as u8]);
+            n = 0;
+        }
+    }
+    match text.len() % 4 {
+        0 => {}
+        2 => out.push((n >> 4) as u8),
+        3 => out.extend_from_slice(&[(n >> 10) as u8, (n >> 2) as u8]),
+        _ => return None,
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encryption_config() {
+        let config = config::parse_config("[encryption]\nrecipients = \"age1abc, age1def\"\nidentity = \"/keys/legend.txt\"\n").unwrap();
+        let encryption = EncryptionConfig::from_config(&config);
+        assert_eq!(encryption.recipients, vec!["age1abc", "age1def"]);
+        assert_eq!(encryption.identity, PathBuf::from("/keys/legend.txt"));
+        assert!(encryption.enabled());
+        assert!(!EncryptionConfig::from_config(&Config::default()).enabled());
+
+        // SSH recipients have a space in them
+        let config = config::parse_config("[encryption]\nrecipients = \"ssh-ed25519 AAAAC3Nz me@host, age1abc\"\n").unwrap();
+        assert_eq!(EncryptionConfig::from_config(&config).recipients, vec!["ssh-ed25519 AAAAC3Nz me@host", "age1abc"]);
+
+        assert!(is_encrypted(b"age-encryption.org/v1\n-> X25519 ..."));
+        assert!(!is_encrypted(b"LGND\x0a\x00"));
+    }
+
+    #[test]
+    fn test_sealed_lines() {
+        for bytes in [&b""[..], b"f", b"fo", b"foo", b"age-encryption.org/v1\n\x00\xff\x10"] {
+            assert_eq!(base64_decode(&base64_encode(bytes)).unwrap(), bytes);
+        }
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_decode("Zm9v!"), None);
+
+        let line = format!("{}41:{}", SEALED_LINE_PREFIX, base64_encode(b"not age"));
+        assert_eq!(sealed_line_seq(&line), Some(41));
+        // Not an age file inside, so there is nothing to decrypt
+        assert_eq!(open_line(&line).unwrap().unwrap(), "not age");
+        assert!(open_line("{\"seq\":1}").is_none());
+        assert_eq!(sealed_line_seq("{\"seq\":1}"), None);
+    }
+}
@@ -0,0 +1,72 @@
+// Exit codes - the contract scripts and CI pipelines can rely on
+//
+//   0  success
+//   1  general failure (I/O, bad input, ...)
+//   2  usage error (unknown command or flag)
+//   3  a --check / --min threshold was not met
+//   4  Legend is not initialized in this directory
+//   128+n  stopped by signal n (interactive mode; 130 for Ctrl-C)
+//
+// Commands return errors as Box<dyn Error> like everywhere else. When a
+// specific code matters they return an ExitError, and main() picks the
+// code out with downcast_ref.
+
+use std::fmt;
+
+pub const FAILURE: i32 = 1;
+pub const USAGE: i32 = 2;
+pub const CHECK_FAILED: i32 = 3;
+pub const NOT_INITIALIZED: i32 = 4;
+
+/// Exit code for a process stopped by `signal`, as shells report it
+#[cfg(feature = "tui")]
+pub fn signal_code(signal: i32) -> i32 {
+    128 + signal
+}
+
+/// An error that carries the process exit code it should produce
+#[derive(Debug)]
+pub struct ExitError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl ExitError {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        ExitError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ExitError {}
+
+/// Pick the exit code for an error (FAILURE unless it says otherwise)
+pub fn code_for(error: &(dyn std::error::Error + 'static)) -> i32 {
+    error
+        .downcast_ref::<ExitError>()
+        .map(|e| e.code)
+        .unwrap_or(FAILURE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_for() {
+        let check: Box<dyn std::error::Error> = Box::new(ExitError::new(CHECK_FAILED, "2 problems"));
+        let plain: Box<dyn std::error::Error> = "boom".into();
+
+        assert_eq!(code_for(check.as_ref()), CHECK_FAILED);
+        assert_eq!(code_for(plain.as_ref()), FAILURE);
+        assert_eq!(check.to_string(), "2 problems");
+    }
+}
@@ -0,0 +1,106 @@
+// File lists - collapse a long files_involved into directory globs
+//
+// A feature that touches forty files puts forty paths in every get_state,
+// most of which tell an agent no more than "src/api/". collapse() keeps a
+// short list as it is and folds a long one into globs with counts:
+//
+//   src/api/** (14 files), src/db/pool.rs, tests/** (3 files)
+//
+// It groups by the deepest directory level that brings the list under
+// the limit, so the globs stay as specific as the room allows. Only the
+// output is collapsed; the stored list, search, and the rest keep every
+// path.
+
+use std::collections::BTreeMap;
+
+/// Entries per feature in get_state when get_state.max_files is not set
+pub const DEFAULT_MAX_FILES: usize = 12;
+
+/// `files` as at most `limit` entries (unchanged if it already fits)
+pub fn collapse(files: &[String], limit: usize) -> Vec<String> {
+    if files.len() <= limit {
+        return files.to_vec();
+    }
+    let deepest = files.iter().map(|f| directories(f).len()).max().unwrap_or(0);
+    for depth in (1..=deepest).rev() {
+        let collapsed = group(files, depth);
+        if collapsed.len() <= limit {
+            return collapsed;
+        }
+    }
+    vec![glob("", files.len())]
+}
+
+/// Group files by their first `depth` directories, in order of first appearance
+fn group(files: &[String], depth: usize) -> Vec<String> {
+    let mut order: Vec<String> = Vec::new();
+    let mut members: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+    for file in files {
+        let dirs = directories(file);
+        // A file above `depth` stands for itself
+        let key = if dirs.len() < depth { file.clone() } else { dirs[..depth].join("/") };
+        let entry = members.entry(key.clone()).or_default();
+        if entry.is_empty() {
+            order.push(key);
+        }
+        entry.push(file);
+    }
+    order
+        .into_iter()
+        .map(|key| match members[&key].as_slice() {
+            [only] => only.to_string(),
+            many => glob(&key, many.len()),
+        })
+        .collect()
+}
+
+/// "src/api/x.rs" -> ["src", "api"]; a trailing "/" marks a directory entry
+fn directories(path: &str) -> Vec<&str> {
+    let path = path.trim_start_matches("./");
+    let mut parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    if !path.ends_with('/') {
+        parts.pop();
+    }
+    parts
+}
+
+/// "src/api/** (14 files)"
+fn glob(dir: &str, count: usize) -> String {
+    let prefix = if dir.is_empty() { String::new() } else { format!("{}/", dir) };
+    format!("{}** ({} files)", prefix, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_short_list_unchanged() {
+        let files = paths(&["src/a.rs", "src/b.rs"]);
+        assert_eq!(collapse(&files, 2), files);
+    }
+
+    #[test]
+    fn test_collapse_deepest_level_that_fits() {
+        let files = paths(&[
+            "src/api/users.rs",
+            "src/api/orders.rs",
+            "src/api/v2/users.rs",
+            "src/db/pool.rs",
+            "README.md",
+        ]);
+        assert_eq!(collapse(&files, 4), vec!["src/api/** (3 files)", "src/db/pool.rs", "README.md"]);
+        assert_eq!(collapse(&files, 2), vec!["src/** (4 files)", "README.md"]);
+        assert_eq!(collapse(&files, 1), vec!["** (5 files)"]);
+    }
+
+    #[test]
+    fn test_directory_entries() {
+        let files = paths(&["src/auth/", "src/auth/sso.rs", "./docs/auth.md"]);
+        assert_eq!(collapse(&files, 2), vec!["src/auth/** (2 files)", "./docs/auth.md"]);
+    }
+}
@@ -0,0 +1,243 @@
+// Findings - problems reported by lint, validate, and coverage
+//
+// All three checks produce the same shape of result so they can share
+// output formatting and the --check exit-code behavior.
+//
+// Output formats:
+// - text:   `warning[auth]: message (src/auth.rs)` for terminals
+// - github: `::warning file=src/auth.rs,title=...::message` workflow
+//           commands, so GitHub Actions shows findings inline on PRs
+
+use crate::exit::{self, ExitError};
+use serde::Serialize;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A single problem found in the state
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Feature the problem belongs to (if any)
+    pub feature_id: Option<String>,
+    /// File the problem points at (if any)
+    pub file: Option<String>,
+    pub message: String,
+}
+
+impl Finding {
+    pub fn error(feature_id: Option<&str>, message: String) -> Self {
+        Finding {
+            severity: Severity::Error,
+            feature_id: feature_id.map(|s| s.to_string()),
+            file: None,
+            message,
+        }
+    }
+
+    pub fn warning(feature_id: Option<&str>, message: String) -> Self {
+        Finding {
+            severity: Severity::Warning,
+            feature_id: feature_id.map(|s| s.to_string()),
+            file: None,
+            message,
+        }
+    }
+
+    /// Attach a file path to the finding
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+
+/// How findings are printed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Github,
+}
+
+impl OutputFormat {
+    pub fn parse(value: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "github" => Ok(OutputFormat::Github),
+            other => Err(ExitError::new(exit::USAGE, format!("Unknown format '{}'. Use text or github.", other)).into()),
+        }
+    }
+}
+
+/// Flags shared by lint and validate
+pub struct CheckOptions {
+    pub check: bool,
+    pub format: OutputFormat,
+}
+
+/// Parse `--check` and `--format <text|github>`
+pub fn parse_check_args(args: &[String]) -> Result<CheckOptions, Box<dyn std::error::Error>> {
+    let mut options = CheckOptions {
+        check: false,
+        format: OutputFormat::Text,
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--check" => options.check = true,
+            "--format" => {
+                i += 1;
+                options.format = OutputFormat::parse(args.get(i).ok_or_else(|| ExitError::new(exit::USAGE, "--format requires a value"))?)?;
+            }
+            other => return Err(ExitError::new(exit::USAGE, format!("Unknown argument: {}", other)).into()),
+        }
+        i += 1;
+    }
+
+    Ok(options)
+}
+
+/// Print findings to stdout in the requested format
+///
+/// `command` names the check in GitHub annotation titles ("legend lint")
+pub fn print_findings(findings: &[Finding], format: OutputFormat, command: &str) {
+    for finding in findings {
+        match format {
+            OutputFormat::Text => println!("{}", format_finding(finding)),
+            OutputFormat::Github => println!("{}", format_github_annotation(finding, command)),
+        }
+    }
+}
+
+fn format_finding(finding: &Finding) -> String {
+    let level = match finding.severity {
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    };
+
+    let mut line = match &finding.feature_id {
+        Some(id) => format!("{}[{}]: {}", level, id, finding.message),
+        None => format!("{}: {}", level, finding.message),
+    };
+
+    if let Some(file) = &finding.file {
+        line.push_str(&format!(" ({})", file));
+    }
+
+    line
+}
+
+/// Format a finding as a GitHub Actions workflow command
+///
+/// See: https://docs.github.com/actions/using-workflows/workflow-commands-for-github-actions
+fn format_github_annotation(finding: &Finding, command: &str) -> String {
+    let level = match finding.severity {
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    };
+
+    let title = match &finding.feature_id {
+        Some(id) => format!("legend {} ({})", command, id),
+        None => format!("legend {}", command),
+    };
+
+    let mut properties = Vec::new();
+    if let Some(file) = &finding.file {
+        properties.push(format!("file={}", escape_property(file)));
+    }
+    properties.push(format!("title={}", escape_property(&title)));
+
+    format!(
+        "::{} {}::{}",
+        level,
+        properties.join(","),
+        escape_data(&finding.message)
+    )
+}
+
+/// Escape the message part of a workflow command
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value (also reserves ':' and ',')
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Apply the --check contract
+///
+/// Without --check, findings are informational and the command succeeds.
+/// With --check, any finding fails the command with exit code 3.
+pub fn check_result(
+    findings: &[Finding],
+    check: bool,
+    command: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if check && !findings.is_empty() {
+        return Err(Box::new(ExitError::new(
+            exit::CHECK_FAILED,
+            format!("{} --check failed: {} problem(s) found", command, findings.len()),
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_finding() {
+        let finding = Finding::warning(Some("auth"), "no files tracked".to_string());
+        assert_eq!(format_finding(&finding), "warning[auth]: no files tracked");
+
+        let finding = Finding::error(None, "missing".to_string()).with_file("src/a.rs");
+        assert_eq!(format_finding(&finding), "error: missing (src/a.rs)");
+    }
+
+    #[test]
+    fn test_format_github_annotation() {
+        let finding = Finding::warning(Some("auth"), "100% stale\nreally".to_string())
+            .with_file("src/a,b.rs");
+
+        assert_eq!(
+            format_github_annotation(&finding, "lint"),
+            "::warning file=src/a%2Cb.rs,title=legend lint (auth)::100%25 stale%0Areally"
+        );
+
+        let finding = Finding::error(None, "broken".to_string());
+        assert_eq!(
+            format_github_annotation(&finding, "validate"),
+            "::error title=legend validate::broken"
+        );
+    }
+
+    #[test]
+    fn test_parse_check_args() {
+        let args: Vec<String> = ["--format", "github", "--check"].iter().map(|s| s.to_string()).collect();
+        let options = parse_check_args(&args).unwrap();
+        assert!(options.check);
+        assert_eq!(options.format, OutputFormat::Github);
+
+        let args = vec!["--format".to_string(), "xml".to_string()];
+        assert!(parse_check_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_check_result() {
+        let findings = vec![Finding::warning(None, "x".to_string())];
+
+        assert!(check_result(&findings, false, "lint").is_ok());
+        let err = check_result(&findings, true, "lint").unwrap_err();
+        assert_eq!(exit::code_for(err.as_ref()), exit::CHECK_FAILED);
+        assert!(check_result(&[], true, "lint").is_ok());
+    }
+}
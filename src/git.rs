@@ -0,0 +1,208 @@
+// Git helpers - thin wrappers around the `git` CLI
+//
+// Why shell out instead of linking libgit2?
+// - Minimal dependencies (see PLAN.md)
+// - Every developer machine running Legend already has git
+// - The commands we need are simple and their output is stable
+//
+// Rust concepts in this file:
+// - std::process::Command for running child processes
+// - Converting raw bytes (Vec<u8>) into String with from_utf8_lossy
+
+use std::process::Command;
+
+/// A single commit with the files it touched
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub subject: String,
+    pub files: Vec<String>,
+}
+
+/// Run a git command and return its stdout
+///
+/// Returns error if git is missing or the command exits non-zero
+/// (stderr from git is included in the message)
+pub fn run_git(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.join(" "), stderr.trim()).into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// `git config user.name`, or None when unset or git is missing
+pub fn user_name() -> Option<String> {
+    let name = run_git(&["config", "user.name"]).ok()?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Raw contents of a file at a revision (`git show <rev>:<path>`)
+///
+/// Bytes rather than a String, so binary files like state.lz4 survive
+pub fn show_file(rev: &str, path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let spec = format!("{}:{}", rev, path);
+    let output = Command::new("git")
+        .args(["show", &spec])
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git show {} failed: {}", spec, stderr.trim()).into());
+    }
+
+    Ok(output.stdout)
+}
+
+/// Check whether a ref (branch, tag, commit) exists
+pub fn ref_exists(name: &str) -> bool {
+    run_git(&["rev-parse", "--verify", "--quiet", name]).is_ok()
+}
+
+/// Commit time (Unix seconds) of a ref such as a tag or branch
+pub fn commit_timestamp(name: &str) -> Result<i64, Box<dyn std::error::Error>> {
+    let output = run_git(&["log", "-1", "--format=%ct", name])?;
+    output
+        .trim()
+        .parse::<i64>()
+        .map_err(|e| format!("Unexpected git timestamp for {}: {}", name, e).into())
+}
+
+/// Guess the default base branch for the current repository
+///
+/// Tries the remote's HEAD first (origin/main, origin/master, ...),
+/// then falls back to local `main` or `master`
+pub fn default_base_branch() -> Option<String> {
+    if let Ok(out) = run_git(&["symbolic-ref", "--quiet", "refs/remotes/origin/HEAD"]) {
+        let name = out.trim().trim_start_matches("refs/remotes/").to_string();
+        if !name.is_empty() {
+            return Some(name);
+        }
+    }
+
+    ["main", "master"]
+        .iter()
+        .find(|b| ref_exists(b))
+        .map(|b| b.to_string())
+}
+
+/// List commits in `range` (e.g. "main..HEAD") with the files each touched
+pub fn commits_in_range(range: &str) -> Result<Vec<Commit>, Box<dyn std::error::Error>> {
+    // %x1e (record separator) starts each commit, %x1f (unit separator)
+    // splits hash from subject. --name-only appends the touched files.
+    let output = run_git(&["log", "--format=%x1e%h%x1f%s", "--name-only", range])?;
+    Ok(parse_log_with_files(&output))
+}
+
+/// Commits made since a Unix timestamp, with the files each touched
+pub fn commits_since(since: i64) -> Result<Vec<Commit>, Box<dyn std::error::Error>> {
+    let since = format!("--since=@{}", since);
+    let output = run_git(&["log", &since, "--format=%x1e%h%x1f%s", "--name-only"])?;
+    Ok(parse_log_with_files(&output))
+}
+
+/// Commit time of the newest commit touching any of `paths`
+///
+/// None if none of them has ever been committed
+pub fn last_change(paths: &[String]) -> Result<Option<i64>, Box<dyn std::error::Error>> {
+    let mut args = vec!["log", "-1", "--format=%ct", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let output = run_git(&args)?;
+    match output.trim() {
+        "" => Ok(None),
+        ts => ts
+            .parse::<i64>()
+            .map(Some)
+            .map_err(|e| format!("Unexpected git timestamp: {}", e).into()),
+    }
+}
+
+/// The newest `limit` commits touching any of `paths`, as (commit time,
+/// commit); `files` is left empty
+pub fn commits_touching(paths: &[String], limit: usize) -> Result<Vec<(i64, Commit)>, Box<dyn std::error::Error>> {
+    let limit = format!("-{}", limit);
+    let mut args = vec!["log", limit.as_str(), "--format=%ct%x1f%h%x1f%s", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    Ok(parse_dated_log(&run_git(&args)?))
+}
+
+/// Parse the output of `git log --format=%ct%x1f%h%x1f%s`
+fn parse_dated_log(output: &str) -> Vec<(i64, Commit)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            let timestamp = fields.next()?.trim().parse().ok()?;
+            let commit = Commit {
+                hash: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or("").to_string(),
+                files: Vec::new(),
+            };
+            Some((timestamp, commit))
+        })
+        .collect()
+}
+
+/// Parse the output of `git log --format=%x1e%h%x1f%s --name-only`
+fn parse_log_with_files(output: &str) -> Vec<Commit> {
+    output
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next()?;
+            let (hash, subject) = header.split_once('\x1f')?;
+
+            let files = lines
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect();
+
+            Some(Commit {
+                hash: hash.to_string(),
+                subject: subject.to_string(),
+                files,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_with_files() {
+        let output = "\x1eabc123\x1fAdd login\n\nsrc/auth.rs\nsrc/main.rs\n\x1edef456\x1fFix typo\n\nREADME.md\n";
+
+        let commits = parse_log_with_files(output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].subject, "Add login");
+        assert_eq!(commits[0].files, vec!["src/auth.rs", "src/main.rs"]);
+        assert_eq!(commits[1].files, vec!["README.md"]);
+    }
+
+    #[test]
+    fn test_parse_log_empty() {
+        assert!(parse_log_with_files("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_dated_log() {
+        let commits = parse_dated_log("1709251200\x1fabc123\x1fAdd login\nnot a commit\n");
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].0, 1_709_251_200);
+        assert_eq!(commits[0].1.subject, "Add login");
+    }
+}
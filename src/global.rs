@@ -0,0 +1,117 @@
+// Global - personal context shared by every project
+//
+// ~/.legend/global.lz4 is an ordinary Legend state (same format as a
+// project's state.lz4) for things that outlive any one repository: coding
+// conventions, preferences, how you like commits written. Features and
+// decisions recorded there show up in the `global` section of every
+// project's get_state, so a new session starts with them.
+//
+// `legend --global update|show|search` runs the normal command against it:
+// like --sandbox, storage is pointed somewhere else and the commands don't
+// know the difference.
+
+use crate::exit::{self, ExitError};
+use crate::output;
+use crate::storage;
+use crate::types::{Decision, Feature, LegendState};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the global state inside ~/.legend
+pub const GLOBAL_FILE_NAME: &str = "global.lz4";
+
+/// Commands that take --global
+pub const GLOBAL_COMMANDS: &[&str] = &["update", "show", "search"];
+
+/// ~/.legend; None without a home directory
+pub fn global_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join(".legend"))
+}
+
+/// Point storage at the global state for the rest of the run, creating it
+/// (empty) the first time
+pub fn use_global_store(command: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if !GLOBAL_COMMANDS.contains(&command) {
+        return Err(ExitError::new(exit::USAGE, format!("--global works with {}", GLOBAL_COMMANDS.join(", "))).into());
+    }
+    let dir = global_dir().ok_or("No home directory: set HOME for --global")?;
+    if crate::sandbox::is_active() && !dir.join(GLOBAL_FILE_NAME).exists() {
+        return Err("--sandbox --global needs an existing global store; run a --global command without --sandbox first".into());
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    storage::set_legend_dir(dir.clone());
+    storage::set_state_file_name(GLOBAL_FILE_NAME);
+    // Checked again under the lock, so two first runs don't both create it
+    // and the second wipe what the first saved
+    let _lock = storage::lock_state_in(&dir)?;
+    if !storage::is_initialized() {
+        storage::save_state_in(&dir, &LegendState::new("Global".to_string()))?;
+    }
+    Ok(())
+}
+
+/// What get_state shows from the global state
+#[derive(Debug, Serialize)]
+pub struct GlobalContext {
+    pub features: Vec<Feature>,
+    pub decisions: Vec<Decision>,
+}
+
+/// The global context, if there is a global state with anything in it
+///
+/// An unreadable file warns instead of failing: get_state must still work
+pub fn load() -> Option<GlobalContext> {
+    load_from(&global_dir()?.join(GLOBAL_FILE_NAME))
+}
+
+fn load_from(path: &Path) -> Option<GlobalContext> {
+    let bytes = fs::read(path).ok()?;
+    let mut state = match storage::decode_state(&bytes) {
+        Ok(state) => state,
+        Err(e) => {
+            output::warning(&format!("Global state {} not loaded: {}", path.display(), e));
+            return None;
+        }
+    };
+    if state.features.is_empty() && state.decisions.is_empty() {
+        return None;
+    }
+    state.sort_by_priority();
+    Some(GlobalContext {
+        features: state.features,
+        decisions: state.decisions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    #[test]
+    fn test_load_from() {
+        let _env = TestEnv::new(1_700_000_000);
+        let path = storage::legend_path(GLOBAL_FILE_NAME);
+        assert!(load_from(&path).is_none());
+
+        let mut state = LegendState::new("Global".to_string());
+        storage::save_state(&state).unwrap();
+        fs::rename(storage::state_file(), &path).unwrap();
+        assert!(load_from(&path).is_none(), "an empty global state adds nothing");
+
+        state.features.push(Feature::new(
+            "commit-style".to_string(),
+            "Commit style".to_string(),
+            "conventions".to_string(),
+            "Imperative subject lines under 72 characters".to_string(),
+        ));
+        storage::save_state(&state).unwrap();
+        fs::rename(storage::state_file(), &path).unwrap();
+        let global = load_from(&path).unwrap();
+        assert_eq!(global.features[0].id, "commit-style");
+        assert!(global.decisions.is_empty());
+    }
+}
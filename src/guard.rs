@@ -0,0 +1,187 @@
+// Guard - refuse to init or discover somewhere that is clearly not a project
+//
+// `legend init` in ~ leaves a .legend and .claude/settings.json there;
+// `legend discover /` walks the whole disk. Both are usually a missing
+// `cd`. Before doing anything, those commands ask suspicion() about their
+// directory: the home directory, the filesystem root, or a very large tree
+// that isn't inside a repository. A suspicious directory needs
+// --yes-i-mean-it, and the error names the project most likely meant (the
+// most recently touched repository just below it).
+
+use crate::exit::{self, ExitError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Flag that lets init/discover run in a suspicious directory anyway
+pub const CONFIRM_FLAG: &str = "--yes-i-mean-it";
+
+/// Entries (files and directories) that make a tree outside a repository "very large"
+const LARGE_DIR_ENTRIES: usize = 20_000;
+
+/// A directory containing one of these is a repository root
+const VCS_MARKERS: &[&str] = &[".git", ".hg", ".svn", ".jj"];
+
+/// Why a directory doesn't look like a project
+#[derive(Debug, PartialEq)]
+pub enum Suspicion {
+    Home,
+    FilesystemRoot,
+    /// Over LARGE_DIR_ENTRIES entries and not inside a repository
+    Large,
+}
+
+impl Suspicion {
+    fn describe(&self) -> String {
+        match self {
+            Suspicion::Home => "your home directory".to_string(),
+            Suspicion::FilesystemRoot => "the filesystem root".to_string(),
+            Suspicion::Large => format!("a directory with over {} entries and no version control", LARGE_DIR_ENTRIES),
+        }
+    }
+}
+
+/// Fail unless `dir` looks like a project or `args` has CONFIRM_FLAG
+///
+/// `command` is only used in the message ("init", "discover")
+pub fn check(command: &str, dir: &Path, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if args.iter().any(|a| a == CONFIRM_FLAG) {
+        return Ok(());
+    }
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let Some(suspicion) = suspicion(&dir, home.as_deref(), LARGE_DIR_ENTRIES) else {
+        return Ok(());
+    };
+
+    let mut message = format!(
+        "Refusing to run `legend {}` in {} ({})",
+        command,
+        suspicion.describe(),
+        dir.display()
+    );
+    if let Some(project) = likely_project(&dir) {
+        message.push_str(&format!("\n  Did you mean {}?", project.display()));
+    }
+    message.push_str(&format!("\n  Run it from the project directory, or pass {} to go ahead", CONFIRM_FLAG));
+    Err(ExitError::new(exit::USAGE, message).into())
+}
+
+/// Why `dir` (canonical) is a suspicious place for a Legend project, if it is
+pub fn suspicion(dir: &Path, home: Option<&Path>, large_entries: usize) -> Option<Suspicion> {
+    if dir.parent().is_none() {
+        return Some(Suspicion::FilesystemRoot);
+    }
+    let home = home.map(|h| fs::canonicalize(h).unwrap_or_else(|_| h.to_path_buf()));
+    if home.as_deref() == Some(dir) {
+        return Some(Suspicion::Home);
+    }
+    if !in_repository(dir) && has_at_least(dir, large_entries) {
+        return Some(Suspicion::Large);
+    }
+    None
+}
+
+/// Is `dir` a repository root or somewhere inside one?
+fn in_repository(dir: &Path) -> bool {
+    dir.ancestors().any(is_repository)
+}
+
+fn is_repository(dir: &Path) -> bool {
+    VCS_MARKERS.iter().any(|marker| dir.join(marker).exists())
+}
+
+/// Does the tree under `dir` have at least `limit` entries? Stops counting there
+fn has_at_least(dir: &Path, limit: usize) -> bool {
+    let mut pending = vec![dir.to_path_buf()];
+    let mut count = 0;
+    while let Some(next) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&next) else { continue };
+        for entry in entries.flatten() {
+            count += 1;
+            if count >= limit {
+                return true;
+            }
+            // DirEntry::file_type doesn't follow symlinks, so no loops
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                pending.push(entry.path());
+            }
+        }
+    }
+    false
+}
+
+/// The most recently modified repository one or two levels below `dir`
+pub fn likely_project(dir: &Path) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    for child in subdirectories(dir) {
+        if is_repository(&child) {
+            candidates.push(child);
+        } else {
+            candidates.extend(subdirectories(&child).into_iter().filter(|d| is_repository(d)));
+        }
+    }
+    candidates
+        .into_iter()
+        .max_by_key(|d| fs::metadata(d).and_then(|m| m.modified()).ok())
+}
+
+/// Non-hidden subdirectories of `dir`
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| e.path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspicion() {
+        let dir = std::env::temp_dir().join(format!("legend-guard-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("notes/2024")).unwrap();
+        fs::write(dir.join("notes/2024/a.md"), "").unwrap();
+        let dir = fs::canonicalize(&dir).unwrap();
+
+        assert_eq!(suspicion(Path::new("/"), None, LARGE_DIR_ENTRIES), Some(Suspicion::FilesystemRoot));
+        assert_eq!(suspicion(&dir, Some(&dir), LARGE_DIR_ENTRIES), Some(Suspicion::Home));
+        assert_eq!(suspicion(&dir, None, LARGE_DIR_ENTRIES), None);
+        // notes, notes/2024, notes/2024/a.md
+        assert_eq!(suspicion(&dir, None, 3), Some(Suspicion::Large));
+        assert_eq!(suspicion(&dir, None, 4), None);
+
+        // Size doesn't matter inside a repository
+        fs::create_dir(dir.join(".git")).unwrap();
+        assert_eq!(suspicion(&dir.join("notes"), None, 1), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_likely_project() {
+        let dir = std::env::temp_dir().join(format!("legend-guard-likely-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("Downloads")).unwrap();
+        fs::create_dir_all(dir.join(".cache/tool/.git")).unwrap();
+        assert_eq!(likely_project(&dir), None);
+
+        fs::create_dir_all(dir.join("code/app/.git")).unwrap();
+        assert_eq!(likely_project(&dir), Some(dir.join("code/app")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_confirmed() {
+        let args = vec![CONFIRM_FLAG.to_string()];
+        assert!(check("discover", Path::new("/"), &args).is_ok());
+        let refused = check("discover", Path::new("/"), &[]).unwrap_err();
+        assert_eq!(exit::code_for(refused.as_ref()), exit::USAGE);
+        assert!(refused.to_string().contains("the filesystem root"));
+    }
+}
@@ -0,0 +1,138 @@
+// Hierarchy - epics, features, and tasks through parent_id
+//
+// A feature may name one parent; the links form a forest. `legend update`
+// rejects a parent that doesn't exist or a chain that loops back on
+// itself, and validate reports either if the state got that way by other
+// means. Removing a parent promotes its children to the top level.
+//
+// Unlike depends_on, a parent says nothing about order: it groups work,
+// and `show --tree` draws the groups.
+
+use crate::types::Feature;
+use std::collections::{HashMap, HashSet};
+
+/// Why the parent links are invalid, or Ok
+pub fn check_parents(features: &[Feature]) -> Result<(), String> {
+    let ids: HashSet<&str> = features.iter().map(|f| f.id.as_str()).collect();
+    for feature in features {
+        let Some(parent) = &feature.parent_id else { continue };
+        if parent == &feature.id {
+            return Err(format!("{} is its own parent", feature.id));
+        }
+        if !ids.contains(parent.as_str()) {
+            return Err(format!("{} has unknown parent '{}'", feature.id, parent));
+        }
+    }
+    match find_cycle(features) {
+        Some(cycle) => Err(format!("parent cycle: {}", cycle.join(" -> "))),
+        None => Ok(()),
+    }
+}
+
+/// A chain of parents that comes back to where it started, first ID
+/// repeated at the end (`a -> b -> a`), if there is one
+///
+/// Self-parents are left to the caller
+pub fn find_cycle(features: &[Feature]) -> Option<Vec<String>> {
+    let parents: HashMap<&str, &str> = features
+        .iter()
+        .filter_map(|f| f.parent_id.as_deref().filter(|p| *p != f.id).map(|p| (f.id.as_str(), p)))
+        .collect();
+
+    for feature in features {
+        let mut chain = vec![feature.id.as_str()];
+        let mut next = parents.get(feature.id.as_str()).copied();
+        while let Some(id) = next {
+            if id == feature.id {
+                chain.push(id);
+                return Some(chain.into_iter().map(str::to_string).collect());
+            }
+            // Leads into a loop that doesn't include this feature
+            if chain.contains(&id) {
+                break;
+            }
+            chain.push(id);
+            next = parents.get(id).copied();
+        }
+    }
+    None
+}
+
+/// Features in tree order with their depth: each root, then its children
+/// (depth 1), their children, and so on
+///
+/// Siblings keep their order in `features`. A feature whose parent is
+/// missing, or caught in a cycle, is drawn as a root so nothing disappears
+pub fn tree_order(features: &[Feature]) -> Vec<(usize, &Feature)> {
+    let ids: HashSet<&str> = features.iter().map(|f| f.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&Feature>> = HashMap::new();
+    let mut roots = Vec::new();
+    for feature in features {
+        match feature.parent_id.as_deref().filter(|p| ids.contains(p) && *p != feature.id) {
+            Some(parent) => children.entry(parent).or_default().push(feature),
+            None => roots.push(feature),
+        }
+    }
+
+    fn visit<'a>(
+        feature: &'a Feature,
+        depth: usize,
+        children: &HashMap<&str, Vec<&'a Feature>>,
+        placed: &mut HashSet<&'a str>,
+        out: &mut Vec<(usize, &'a Feature)>,
+    ) {
+        if !placed.insert(&feature.id) {
+            return;
+        }
+        out.push((depth, feature));
+        for child in children.get(feature.id.as_str()).into_iter().flatten() {
+            visit(child, depth + 1, children, placed, out);
+        }
+    }
+
+    let mut placed = HashSet::new();
+    let mut out = Vec::new();
+    for root in roots {
+        visit(root, 0, &children, &mut placed, &mut out);
+    }
+    // Whatever is left sits on a cycle; start each loop at its first member
+    for feature in features {
+        visit(feature, 0, &children, &mut placed, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, parent: Option<&str>) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), "core".to_string(), "d".to_string());
+        f.parent_id = parent.map(str::to_string);
+        f
+    }
+
+    #[test]
+    fn test_check_parents() {
+        let ok = vec![feature("epic", None), feature("login", Some("epic")), feature("form", Some("login"))];
+        assert!(check_parents(&ok).is_ok());
+
+        assert_eq!(check_parents(&[feature("a", Some("ghost"))]).unwrap_err(), "a has unknown parent 'ghost'");
+        assert_eq!(check_parents(&[feature("a", Some("a"))]).unwrap_err(), "a is its own parent");
+        let cycle = vec![feature("a", Some("b")), feature("b", Some("a"))];
+        assert_eq!(check_parents(&cycle).unwrap_err(), "parent cycle: a -> b -> a");
+    }
+
+    #[test]
+    fn test_tree_order() {
+        let features = vec![
+            feature("form", Some("login")),
+            feature("epic", None),
+            feature("billing", None),
+            feature("login", Some("epic")),
+            feature("orphan", Some("gone")),
+        ];
+        let order: Vec<(usize, &str)> = tree_order(&features).into_iter().map(|(d, f)| (d, f.id.as_str())).collect();
+        assert_eq!(order, vec![(0, "epic"), (1, "login"), (2, "form"), (0, "billing"), (0, "orphan")]);
+    }
+}
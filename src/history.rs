@@ -0,0 +1,128 @@
+// History - recent versions of state.lz4, so a bad update can be undone
+//
+// Every save_state copies the file it just wrote to
+// .legend/history/<seq>-<timestamp>.lz4. Only the newest HISTORY_LIMIT
+// copies are kept. The first save in a directory without history also
+// keeps the state it replaced, so even the first update can be undone.
+//
+// Snapshots are whole compressed states, not diffs: a copy costs one
+// file write on the (already slow) write path, and restoring one is a
+// plain load. The journal still records what each undo changed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory of snapshots inside the Legend directory
+const HISTORY_DIR_NAME: &str = "history";
+
+/// Snapshots kept; older ones are deleted on save
+pub const HISTORY_LIMIT: usize = 50;
+
+/// One saved version of the state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub seq: u64,
+    /// Unix timestamp of the save
+    pub timestamp: i64,
+    pub path: PathBuf,
+}
+
+fn history_dir(dir: &Path) -> PathBuf {
+    dir.join(HISTORY_DIR_NAME)
+}
+
+/// Snapshots in a Legend directory, oldest first
+pub fn snapshots_in(dir: &Path) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = fs::read_dir(history_dir(dir))
+        .map(|entries| entries.flatten().filter_map(|e| parse_snapshot(e.path())).collect())
+        .unwrap_or_default();
+    snapshots.sort_by_key(|s| s.seq);
+    snapshots
+}
+
+/// Take the newest snapshot off `snapshots` if it is a copy of `current`
+/// (the state file's bytes), leaving the previous version last
+pub fn pop_current(snapshots: &mut Vec<Snapshot>, current: &[u8]) -> Option<Snapshot> {
+    let newest = snapshots.last()?;
+    if fs::read(&newest.path).ok()? == current {
+        snapshots.pop()
+    } else {
+        None
+    }
+}
+
+/// `000012-1700000000.lz4` -> seq 12, timestamp 1700000000
+fn parse_snapshot(path: PathBuf) -> Option<Snapshot> {
+    let stem = path.file_name()?.to_str()?.strip_suffix(".lz4")?;
+    let (seq, timestamp) = stem.split_once('-')?;
+    Some(Snapshot {
+        seq: seq.parse().ok()?,
+        timestamp: timestamp.parse().ok()?,
+        path,
+    })
+}
+
+/// Copy the state file about to be replaced, if there is no history yet
+///
+/// Called by save_state before it writes
+pub fn keep_baseline(dir: &Path, state_file: &Path, now: i64) -> Result<(), Box<dyn std::error::Error>> {
+    if state_file.exists() && snapshots_in(dir).is_empty() {
+        copy_snapshot(dir, state_file, 1, now)?;
+    }
+    Ok(())
+}
+
+/// Copy the state file just written and drop snapshots past the limit
+///
+/// Called by save_state after it writes
+pub fn record(dir: &Path, state_file: &Path, now: i64) -> Result<(), Box<dyn std::error::Error>> {
+    let existing = snapshots_in(dir);
+    let seq = existing.last().map_or(1, |s| s.seq + 1);
+    copy_snapshot(dir, state_file, seq, now)?;
+
+    let excess = (existing.len() + 1).saturating_sub(HISTORY_LIMIT);
+    for old in existing.iter().take(excess) {
+        let _ = fs::remove_file(&old.path);
+    }
+    Ok(())
+}
+
+fn copy_snapshot(dir: &Path, state_file: &Path, seq: u64, now: i64) -> Result<(), Box<dyn std::error::Error>> {
+    let history = history_dir(dir);
+    fs::create_dir_all(&history).map_err(|e| format!("Failed to create {}: {}", history.display(), e))?;
+    let path = history.join(format!("{:06}-{}.lz4", seq, now));
+    fs::copy(state_file, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_baseline_and_prunes() {
+        let dir = std::env::temp_dir().join(format!("legend-history-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let state_file = dir.join("state.lz4");
+
+        // Nothing to keep before the first state exists
+        keep_baseline(&dir, &state_file, 1).unwrap();
+        assert!(snapshots_in(&dir).is_empty());
+
+        fs::write(&state_file, "v0").unwrap();
+        keep_baseline(&dir, &state_file, 5).unwrap();
+        for version in 1..=HISTORY_LIMIT as i64 {
+            fs::write(&state_file, format!("v{}", version)).unwrap();
+            keep_baseline(&dir, &state_file, 10 + version).unwrap();
+            record(&dir, &state_file, 10 + version).unwrap();
+        }
+        let snapshots = snapshots_in(&dir);
+        let oldest = fs::read_to_string(&snapshots[0].path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(snapshots.len(), HISTORY_LIMIT);
+        assert_eq!(snapshots.last().unwrap().seq, HISTORY_LIMIT as u64 + 1);
+        assert_eq!(snapshots.last().unwrap().timestamp, 10 + HISTORY_LIMIT as i64);
+        assert_eq!(oldest, "v1");
+    }
+}
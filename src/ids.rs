@@ -0,0 +1,101 @@
+// Feature ID namespacing - optional `domain/slug` IDs
+//
+// Enabled with `[ids] namespace = true` in .legend/config.toml. When on:
+// - new features get their domain prefixed automatically
+//   ("rate-limiting" in domain "api" is stored as "api/rate-limiting")
+// - updates may still refer to a feature by its bare slug, as long as
+//   only one feature has that slug
+// - validate reports IDs that are not `<domain>/<slug>`
+//
+// Search understands the prefix either way: `legend search api/` lists
+// the api domain, `legend search api/rate` narrows it to "rate".
+
+use crate::types::Feature;
+
+/// Split "api/rate-limiting" into (Some("api"), "rate-limiting")
+pub fn split_id(id: &str) -> (Option<&str>, &str) {
+    match id.split_once('/') {
+        Some((prefix, slug)) => (Some(prefix), slug),
+        None => (None, id),
+    }
+}
+
+/// The ID a new feature gets: prefixed with its domain unless it already
+/// carries a prefix
+pub fn namespaced(domain: &str, id: &str) -> String {
+    if id.contains('/') {
+        id.to_string()
+    } else {
+        format!("{}/{}", domain, id)
+    }
+}
+
+/// Index of the feature an ID refers to
+///
+/// Exact matches win; a bare slug matches a namespaced ID if it is unique
+pub fn resolve(features: &[Feature], id: &str) -> Option<usize> {
+    if let Some(index) = features.iter().position(|f| f.id == id) {
+        return Some(index);
+    }
+    if id.contains('/') {
+        return None;
+    }
+
+    let mut matches = features
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| split_id(&f.id) == (Some(f.domain.as_str()), id));
+    match (matches.next(), matches.next()) {
+        (Some((index, _)), None) => Some(index),
+        _ => None,
+    }
+}
+
+/// Why a feature's ID doesn't follow `<domain>/<slug>`, if it doesn't
+pub fn namespace_problem(feature: &Feature) -> Option<String> {
+    match split_id(&feature.id) {
+        (None, _) => Some(format!("ID is not namespaced (expected '{}/{}')", feature.domain, feature.id)),
+        (Some(_), slug) if slug.is_empty() || slug.contains('/') => {
+            Some("ID must be exactly '<domain>/<slug>'".to_string())
+        }
+        (Some(prefix), slug) if prefix != feature.domain => Some(format!(
+            "ID prefix '{}' does not match domain '{}' (expected '{}/{}')",
+            prefix, feature.domain, feature.domain, slug
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, domain: &str) -> Feature {
+        Feature::new(id.to_string(), id.to_string(), domain.to_string(), "d".to_string())
+    }
+
+    #[test]
+    fn test_namespaced() {
+        assert_eq!(namespaced("api", "rate-limiting"), "api/rate-limiting");
+        assert_eq!(namespaced("api", "web/login"), "web/login");
+    }
+
+    #[test]
+    fn test_resolve_bare_slug() {
+        let features = vec![feature("api/limits", "api"), feature("web/login", "web"), feature("api/login", "api")];
+
+        assert_eq!(resolve(&features, "api/limits"), Some(0));
+        assert_eq!(resolve(&features, "limits"), Some(0));
+        // Ambiguous slugs must be written out in full
+        assert_eq!(resolve(&features, "login"), None);
+        assert_eq!(resolve(&features, "auth/limits"), None);
+    }
+
+    #[test]
+    fn test_namespace_problem() {
+        assert_eq!(namespace_problem(&feature("api/limits", "api")), None);
+        assert!(namespace_problem(&feature("limits", "api")).unwrap().contains("'api/limits'"));
+        assert!(namespace_problem(&feature("web/limits", "api")).unwrap().contains("does not match"));
+        assert!(namespace_problem(&feature("api/", "api")).is_some());
+    }
+}
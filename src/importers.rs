@@ -0,0 +1,393 @@
+// Importers - turn project-management exports into Legend features
+//
+// Supported sources:
+// - trello           Board JSON (Board menu -> Print and export -> JSON)
+// - notion-csv       Database CSV export (... -> Export -> Markdown & CSV)
+// - github-projects  `gh project item-list <n> --owner <o> --format json`
+//
+// Each adapter only extracts ImportedItems; mapping them onto features
+// (IDs, merging into state) happens once in commands/import.rs.
+//
+// Mapping, for every source:
+//   card/row title        -> name (and a slug for the id)
+//   list/column/status    -> status (see status_from_label); a blocked
+//                            one also names it as the blocked_reason
+//   labels/tags           -> tags
+//   description/body      -> description (Trello: the card name if empty)
+//   link to the original  -> context
+
+use crate::types::FeatureStatus;
+use serde_json::Value;
+
+/// One card, row, or project item, source-agnostic
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedItem {
+    pub name: String,
+    pub description: String,
+    pub status: FeatureStatus,
+    pub blocked_reason: Option<String>,
+    pub tags: Vec<String>,
+    pub domain: Option<String>,
+    pub link: Option<String>,
+}
+
+/// Which export format a file is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Trello,
+    NotionCsv,
+    GithubProjects,
+}
+
+impl Source {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name {
+            "trello" => Ok(Source::Trello),
+            "notion-csv" | "notion" => Ok(Source::NotionCsv),
+            "github-projects" | "github" => Ok(Source::GithubProjects),
+            other => Err(format!(
+                "Unknown import source '{}'. Expected legend, trello, notion-csv, or github-projects.",
+                other
+            )
+            .into()),
+        }
+    }
+}
+
+/// Parse an export file's contents into items
+pub fn parse(source: Source, content: &str) -> Result<Vec<ImportedItem>, Box<dyn std::error::Error>> {
+    match source {
+        Source::Trello => parse_trello(content),
+        Source::NotionCsv => parse_notion_csv(content),
+        Source::GithubProjects => parse_github_projects(content),
+    }
+}
+
+/// Map a list name / status column onto a FeatureStatus
+///
+/// Matches common board vocabulary; anything unrecognized is Pending
+pub fn status_from_label(label: &str) -> FeatureStatus {
+    let label = label.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| label.contains(w));
+
+    if has(&["not started", "to do", "todo", "backlog"]) {
+        FeatureStatus::Pending
+    } else if has(&["done", "complete", "shipped", "released", "closed", "finished"]) {
+        FeatureStatus::Complete
+    } else if has(&["block", "stuck", "waiting", "on hold"]) {
+        FeatureStatus::Blocked
+    } else if has(&["progress", "doing", "active", "review", "started", "wip"]) {
+        FeatureStatus::InProgress
+    } else {
+        FeatureStatus::Pending
+    }
+}
+
+/// Why an item is blocked, from the list or status it came from: the
+/// exports don't say more, and a Blocked feature should say something
+fn blocked_reason(status: &FeatureStatus, label: &str, source: &str) -> Option<String> {
+    (*status == FeatureStatus::Blocked).then(|| format!("In \"{}\" on {}", label.trim(), source))
+}
+
+// ---------------------------------------------------------------------------
+// Trello
+// ---------------------------------------------------------------------------
+
+fn parse_trello(content: &str) -> Result<Vec<ImportedItem>, Box<dyn std::error::Error>> {
+    let board: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Not a Trello board export: {}", e))?;
+
+    let lists = board["lists"].as_array().cloned().unwrap_or_default();
+    let list_name = |id: &str| {
+        lists
+            .iter()
+            .find(|l| l["id"].as_str() == Some(id))
+            .and_then(|l| l["name"].as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let cards = board["cards"]
+        .as_array()
+        .ok_or("Not a Trello board export: no \"cards\" array")?;
+
+    let mut items = Vec::new();
+    for card in cards {
+        let name = card["name"].as_str().unwrap_or("").trim();
+        if name.is_empty() {
+            continue;
+        }
+
+        // Archived cards are finished work as far as Legend is concerned
+        let list = list_name(card["idList"].as_str().unwrap_or(""));
+        let status = if card["closed"].as_bool() == Some(true) {
+            FeatureStatus::Complete
+        } else {
+            status_from_label(&list)
+        };
+
+        // Plenty of cards are just a title
+        let description = match card["desc"].as_str().unwrap_or("").trim() {
+            "" => name,
+            desc => desc,
+        };
+
+        let tags = card["labels"]
+            .as_array()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|l| l["name"].as_str())
+                    .filter(|n| !n.is_empty())
+                    .map(|n| n.to_lowercase())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        items.push(ImportedItem {
+            name: name.to_string(),
+            description: description.to_string(),
+            blocked_reason: blocked_reason(&status, &list, "Trello"),
+            status,
+            tags,
+            domain: None,
+            link: card["shortUrl"].as_str().map(|s| s.to_string()),
+        });
+    }
+
+    Ok(items)
+}
+
+// ---------------------------------------------------------------------------
+// Notion CSV
+// ---------------------------------------------------------------------------
+
+fn parse_notion_csv(content: &str) -> Result<Vec<ImportedItem>, Box<dyn std::error::Error>> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut rows = parse_csv(content).into_iter();
+
+    let header: Vec<String> = rows
+        .next()
+        .ok_or("CSV export is empty")?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let name_col = column(&["name", "title", "task", "feature"])
+        .ok_or("CSV export has no Name/Title column")?;
+    let status_col = column(&["status", "stage", "state"]);
+    let tags_col = column(&["tags", "labels", "tag", "label"]);
+    let description_col = column(&["description", "notes", "summary", "details"]);
+    let domain_col = column(&["domain", "area", "category", "project"]);
+    let link_col = column(&["url", "link"]);
+
+    let cell = |row: &[String], col: Option<usize>| {
+        col.and_then(|c| row.get(c))
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut items = Vec::new();
+    for row in rows {
+        let name = cell(&row, Some(name_col));
+        if name.is_empty() {
+            continue;
+        }
+
+        let domain = cell(&row, domain_col);
+        let link = cell(&row, link_col);
+        let label = cell(&row, status_col);
+        let status = status_from_label(&label);
+
+        items.push(ImportedItem {
+            name,
+            description: cell(&row, description_col),
+            blocked_reason: blocked_reason(&status, &label, "Notion"),
+            status,
+            // Notion multi-selects export as "a, b, c"
+            tags: cell(&row, tags_col)
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            domain: Some(domain).filter(|d| !d.is_empty()),
+            link: Some(link).filter(|l| !l.is_empty()),
+        });
+    }
+
+    Ok(items)
+}
+
+/// Minimal RFC 4180 CSV: quoted fields, "" escapes, newlines inside quotes
+pub fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.into_iter()
+        .filter(|r| !(r.len() == 1 && r[0].trim().is_empty()))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// GitHub Projects
+// ---------------------------------------------------------------------------
+
+fn parse_github_projects(content: &str) -> Result<Vec<ImportedItem>, Box<dyn std::error::Error>> {
+    let export: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Not a GitHub Projects export: {}", e))?;
+
+    let entries = export["items"]
+        .as_array()
+        .or_else(|| export.as_array())
+        .ok_or("Not a GitHub Projects export: no \"items\" array")?;
+
+    let mut items = Vec::new();
+    for entry in entries {
+        let name = entry["title"]
+            .as_str()
+            .or_else(|| entry["content"]["title"].as_str())
+            .unwrap_or("")
+            .trim();
+        if name.is_empty() {
+            continue;
+        }
+
+        let tags = entry["labels"]
+            .as_array()
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|l| l.as_str().or_else(|| l["name"].as_str()))
+                    .map(|n| n.to_lowercase())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let label = entry["status"].as_str().unwrap_or("");
+        let status = status_from_label(label);
+
+        items.push(ImportedItem {
+            name: name.to_string(),
+            description: entry["content"]["body"].as_str().unwrap_or("").trim().to_string(),
+            blocked_reason: blocked_reason(&status, label, "GitHub Projects"),
+            status,
+            tags,
+            domain: entry["repository"]
+                .as_str()
+                .and_then(|r| r.rsplit('/').next())
+                .map(|r| r.to_string()),
+            link: entry["content"]["url"].as_str().map(|s| s.to_string()),
+        });
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_from_label() {
+        assert_eq!(status_from_label("Done ✅"), FeatureStatus::Complete);
+        assert_eq!(status_from_label("In Progress"), FeatureStatus::InProgress);
+        assert_eq!(status_from_label("Blocked"), FeatureStatus::Blocked);
+        assert_eq!(status_from_label("Backlog"), FeatureStatus::Pending);
+        assert_eq!(status_from_label(""), FeatureStatus::Pending);
+    }
+
+    #[test]
+    fn test_parse_trello() {
+        let json = r#"{
+            "name": "Roadmap",
+            "lists": [{"id": "l1", "name": "Doing"}, {"id": "l2", "name": "To Do"}],
+            "cards": [
+                {"name": "OAuth login", "desc": "Google + GitHub", "idList": "l1",
+                 "labels": [{"name": "Backend"}], "closed": false, "shortUrl": "https://trello.com/c/x"},
+                {"name": "Old card", "desc": "", "idList": "l2", "labels": [], "closed": true}
+            ]
+        }"#;
+
+        let items = parse(Source::Trello, json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].status, FeatureStatus::InProgress);
+        assert_eq!(items[0].tags, vec!["backend"]);
+        assert_eq!(items[0].link.as_deref(), Some("https://trello.com/c/x"));
+        assert_eq!(items[1].status, FeatureStatus::Complete);
+        assert_eq!(items[0].blocked_reason, None);
+    }
+
+    #[test]
+    fn test_parse_trello_empty_desc_and_blocked_list() {
+        let json = r#"{
+            "lists": [{"id": "l1", "name": "Blocked "}],
+            "cards": [{"name": "Stripe webhooks", "desc": "  ", "idList": "l1", "closed": false}]
+        }"#;
+
+        let items = parse(Source::Trello, json).unwrap();
+        assert_eq!(items[0].description, "Stripe webhooks");
+        assert_eq!(items[0].status, FeatureStatus::Blocked);
+        assert_eq!(items[0].blocked_reason.as_deref(), Some("In \"Blocked\" on Trello"));
+    }
+
+    #[test]
+    fn test_parse_notion_csv() {
+        let csv = "\u{feff}Name,Status,Tags,Notes\r\n\
+                   Search,Done,\"api, backend\",\"Full-text, with \"\"fuzzy\"\" matching\"\r\n\
+                   ,Todo,,\r\n\
+                   Billing,Not started,,\"Line one\nline two\"\r\n";
+
+        let items = parse(Source::NotionCsv, csv).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "Search");
+        assert_eq!(items[0].status, FeatureStatus::Complete);
+        assert_eq!(items[0].tags, vec!["api", "backend"]);
+        assert_eq!(items[0].description, "Full-text, with \"fuzzy\" matching");
+        assert_eq!(items[1].status, FeatureStatus::Pending);
+        assert_eq!(items[1].description, "Line one\nline two");
+
+        assert!(parse(Source::NotionCsv, "Status\nDone\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_github_projects() {
+        let json = r#"{"items": [
+            {"title": "Rate limiting", "status": "In Progress", "labels": ["api"],
+             "repository": "acme/gateway",
+             "content": {"body": "Token bucket per key", "url": "https://github.com/acme/gateway/issues/4"}},
+            {"content": {"title": "Draft item"}, "status": "Todo"}
+        ], "totalCount": 2}"#;
+
+        let items = parse(Source::GithubProjects, json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].status, FeatureStatus::InProgress);
+        assert_eq!(items[0].domain.as_deref(), Some("gateway"));
+        assert_eq!(items[0].description, "Token bucket per key");
+        assert_eq!(items[1].name, "Draft item");
+    }
+}
@@ -0,0 +1,715 @@
+// Journal - append-only log of how the memory changed over time
+//
+// Every write that changes the state appends events to .legend/events.log,
+// one JSON object per line: one per feature added, changed, or removed,
+// and one with whichever other sections (decisions, goals, sessions, ...)
+// changed, each as a whole. The snapshot in state.lz4 says what Legend
+// knows *now*; the journal says how it got there.
+//
+// Why JSON lines and not bincode?
+// - Append is a single write, no rewrite of the whole file
+// - Readable with `tail`/`jq` when auditing
+// - Tolerant of schema changes (unknown/missing fields are fine)
+//
+// The first write to a fresh journal records a Baseline event with the
+// features that already existed, and every other section as it was, so
+// the log is complete on its own. (A journal started before sections
+// were journaled has no such record; until one of its events mentions a
+// section, replay takes that section from the snapshot.)
+//
+// It is also a write-ahead log. commit() appends (and syncs) the events
+// before the snapshot is replaced, and every save notes in events.applied
+// how far into the journal the snapshot goes. If Legend dies in between,
+// the next load_state replays the events past that point, so the change
+// isn't lost; `legend compact` saves them into the snapshot for good.
+// Replaying is safe to repeat: each event carries the whole feature or
+// section.
+//
+// That makes the journal a way to write only what changed, too. With
+// `storage.snapshot_every = n` in config.toml, `legend update` goes
+// through commit_deferred(): it appends the changed features and leaves
+// state.lz4 alone until n events are waiting, then saves the snapshot
+// with all of them. Recency scores of the features an update didn't
+// change aren't journaled, so until that save they read as of the last
+// one (they were only ever as fresh as the last update anyway).
+//
+// With encryption on (see encryption.rs), each append is one line holding
+// its events encrypted, prefixed with the last sequence number in it.
+
+use crate::diff::{self, FieldChange};
+use crate::encryption;
+use crate::output;
+use crate::storage;
+use crate::types::{current_timestamp, Decision, Feature, Goal, LegendState, Milestone, ProjectInfo, Session};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the event journal inside the Legend directory
+const JOURNAL_FILE_NAME: &str = "events.log";
+
+/// File name of the journal offset the snapshot includes events up to
+const APPLIED_FILE_NAME: &str = "events.applied";
+
+fn journal_file() -> PathBuf {
+    storage::legend_path(JOURNAL_FILE_NAME)
+}
+
+/// A single journal entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Monotonic sequence number, starting at 1
+    pub seq: u64,
+    /// Unix timestamp when the event was recorded
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// What happened
+///
+/// Serialized with a "type" tag: {"seq":1,"timestamp":..,"type":"feature_added",...}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// Features that existed before journaling started
+    Baseline { features: Vec<Feature> },
+    FeatureAdded { feature: Feature },
+    FeatureChanged {
+        id: String,
+        changes: Vec<FieldChange>,
+        feature: Feature,
+    },
+    FeatureRemoved { id: String, name: String },
+    /// Everything besides features that changed (or, first in a fresh
+    /// journal, all of it)
+    SectionsChanged(Sections),
+}
+
+/// The parts of the state besides features, as journaled
+///
+/// Only the sections a change touched are set, each to its whole value
+/// after the change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sections {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestones: Option<Vec<Milestone>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decisions: Option<Vec<Decision>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<Vec<Session>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<ProjectInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goals: Option<Vec<Goal>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conventions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<Vec<String>>,
+}
+
+impl Sections {
+    /// Every section of `state`
+    pub fn all(state: &LegendState) -> Self {
+        Sections {
+            project_name: Some(state.project_name.clone()),
+            milestones: Some(state.milestones.clone()),
+            decisions: Some(state.decisions.clone()),
+            sessions: Some(state.sessions.clone()),
+            project: Some(state.project.clone()),
+            goals: Some(state.goals.clone()),
+            conventions: Some(state.conventions.clone()),
+            focus: Some(state.focus.clone()),
+        }
+    }
+
+    /// The sections that differ between the two states, as in `after`;
+    /// None if they are all the same
+    pub fn changed(before: &LegendState, after: &LegendState) -> Option<Self> {
+        fn pick<T: PartialEq + Clone>(before: &T, after: &T) -> Option<T> {
+            (before != after).then(|| after.clone())
+        }
+        let sections = Sections {
+            project_name: pick(&before.project_name, &after.project_name),
+            milestones: pick(&before.milestones, &after.milestones),
+            decisions: pick(&before.decisions, &after.decisions),
+            sessions: pick(&before.sessions, &after.sessions),
+            project: pick(&before.project, &after.project),
+            goals: pick(&before.goals, &after.goals),
+            conventions: pick(&before.conventions, &after.conventions),
+            focus: pick(&before.focus, &after.focus),
+        };
+        (sections != Sections::default()).then_some(sections)
+    }
+
+    /// Names of the sections that are set
+    pub fn names(&self) -> Vec<&'static str> {
+        [
+            ("project_name", self.project_name.is_some()),
+            ("milestones", self.milestones.is_some()),
+            ("decisions", self.decisions.is_some()),
+            ("sessions", self.sessions.is_some()),
+            ("project", self.project.is_some()),
+            ("goals", self.goals.is_some()),
+            ("conventions", self.conventions.is_some()),
+            ("focus", self.focus.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+
+    /// Put the sections that are set into `state`
+    fn apply(&self, state: &mut LegendState) {
+        let sections = self.clone();
+        if let Some(value) = sections.project_name {
+            state.project_name = value;
+        }
+        if let Some(value) = sections.milestones {
+            state.milestones = value;
+        }
+        if let Some(value) = sections.decisions {
+            state.decisions = value;
+        }
+        if let Some(value) = sections.sessions {
+            state.sessions = value;
+        }
+        if let Some(value) = sections.project {
+            state.project = value;
+        }
+        if let Some(value) = sections.goals {
+            state.goals = value;
+        }
+        if let Some(value) = sections.conventions {
+            state.conventions = value;
+        }
+        if let Some(value) = sections.focus {
+            state.focus = value;
+        }
+    }
+}
+
+/// Journal the changes from `before` to `after`, then save `after`
+///
+/// The events reach the disk first, so a crash before the save is undone
+/// by the next load (see the top of this file)
+pub fn commit(before: &LegendState, after: &LegendState) -> Result<(), Box<dyn std::error::Error>> {
+    commit_deferred(before, after, 1)
+}
+
+/// Journal the changes from `before` to `after`, and save `after` only
+/// once `snapshot_every` events wait outside the snapshot (1: always)
+///
+/// Until then the journal append is the whole write, and loads replay
+/// it. A feature touched without changing (last_updated alone, which
+/// the journal doesn't carry) saves right away so the touch isn't lost.
+pub fn commit_deferred(
+    before: &LegendState,
+    after: &LegendState,
+    snapshot_every: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = storage::lock_state()?;
+    let dir = storage::legend_dir();
+
+    // Without a mark (first write, or a journal deleted since) events
+    // appended now would never be replayed
+    let journal_len = fs::metadata(dir.join(JOURNAL_FILE_NAME)).map(|meta| meta.len()).unwrap_or(0);
+    if read_applied_in(&dir).is_none_or(|applied| applied > journal_len) {
+        write_applied(&dir, journal_len)?;
+    }
+
+    record_changes(before, after)?;
+    if snapshot_every > 1
+        && !touched_only(before, after)
+        && (unapplied_events_in(&dir)?.len() as u64) < snapshot_every
+    {
+        return Ok(());
+    }
+    storage::save_state(after)
+}
+
+/// Does `after` have a feature whose last_updated moved without any
+/// journaled field changing?
+fn touched_only(before: &LegendState, after: &LegendState) -> bool {
+    let changed = diff::diff_states(before, after).changed;
+    after.features.iter().any(|feature| {
+        before
+            .features
+            .iter()
+            .find(|old| old.id == feature.id)
+            .is_some_and(|old| old.last_updated != feature.last_updated)
+            && !changed.iter().any(|c| c.id == feature.id)
+    })
+}
+
+/// Record the difference between two states as journal events
+///
+/// No-op if nothing changed. Starts the journal with a Baseline of
+/// `before` if this is the first recorded write.
+fn record_changes(
+    before: &LegendState,
+    after: &LegendState,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state_diff = diff::diff_states(before, after);
+    let sections = Sections::changed(before, after);
+    if state_diff.is_empty() && sections.is_none() {
+        return Ok(());
+    }
+
+    let mut kinds: Vec<EventKind> = Vec::new();
+
+    if !journal_file().exists() {
+        if !before.features.is_empty() {
+            kinds.push(EventKind::Baseline {
+                features: before.features.clone(),
+            });
+        }
+        kinds.push(EventKind::SectionsChanged(Sections::all(before)));
+    }
+
+    for feature in state_diff.added {
+        kinds.push(EventKind::FeatureAdded { feature });
+    }
+    for changed in state_diff.changed {
+        kinds.push(EventKind::FeatureChanged {
+            id: changed.id,
+            changes: changed.changes,
+            feature: changed.after,
+        });
+    }
+    for feature in state_diff.removed {
+        kinds.push(EventKind::FeatureRemoved {
+            id: feature.id,
+            name: feature.name,
+        });
+    }
+    kinds.extend(sections.map(EventKind::SectionsChanged));
+
+    append_events(kinds)
+}
+
+/// Append events to the journal, assigning sequence numbers
+fn append_events(kinds: Vec<EventKind>) -> Result<(), Box<dyn std::error::Error>> {
+    let first_seq = last_seq()? + 1;
+    let count = kinds.len() as u64;
+    let now = current_timestamp();
+
+    // Build all lines first so the append is a single write
+    let mut lines = String::new();
+    for (seq, kind) in (first_seq..).zip(kinds) {
+        let event = Event {
+            seq,
+            timestamp: now,
+            kind,
+        };
+        let line = serde_json::to_string(&event)
+            .map_err(|e| format!("Failed to serialize journal event: {}", e))?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    let lines = encryption::seal_lines(lines, first_seq + count - 1)?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_file())
+        .map_err(|e| format!("Failed to open journal: {}", e))?;
+
+    file.write_all(lines.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|e| format!("Failed to append to journal: {}", e))?;
+
+    Ok(())
+}
+
+/// Note that the snapshot in `dir` now includes the whole journal
+///
+/// Called by every save (see storage.rs)
+pub fn mark_applied_in(dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let journal_len = fs::metadata(dir.join(JOURNAL_FILE_NAME)).map(|meta| meta.len()).unwrap_or(0);
+    write_applied(dir, journal_len)
+}
+
+fn write_applied(dir: &Path, offset: u64) -> Result<(), Box<dyn std::error::Error>> {
+    storage::write_atomic(&dir.join(APPLIED_FILE_NAME), format!("{}\n", offset).as_bytes())
+}
+
+/// Journal offset the snapshot in `dir` goes up to; None before the
+/// first commit (or if the mark is unreadable)
+fn read_applied_in(dir: &Path) -> Option<u64> {
+    fs::read_to_string(dir.join(APPLIED_FILE_NAME)).ok()?.trim().parse().ok()
+}
+
+/// Events journaled after the snapshot in `dir` was saved, oldest first
+///
+/// Only a crash between the two leaves any. Costs one stat when there
+/// are none, so load_state can always check.
+pub fn unapplied_events_in(dir: &Path) -> Result<Vec<Event>, Box<dyn std::error::Error>> {
+    let Some(applied) = read_applied_in(dir) else {
+        return Ok(Vec::new());
+    };
+    let path = dir.join(JOURNAL_FILE_NAME);
+    let journal_len = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    if journal_len <= applied {
+        return Ok(Vec::new());
+    }
+
+    let mut file = File::open(&path).map_err(|e| format!("Failed to read journal: {}", e))?;
+    let mut tail = String::new();
+    file.seek(SeekFrom::Start(applied))
+        .and_then(|_| file.read_to_string(&mut tail))
+        .map_err(|e| format!("Failed to read journal: {}", e))?;
+
+    // A line without its newline is an append cut short; its save never
+    // happened either, so there is nothing to redo
+    let complete = tail.rfind('\n').map_or("", |end| &tail[..=end]);
+    parse_events(complete)
+}
+
+/// Apply events journaled after the snapshot (see unapplied_events_in)
+///
+/// An unreadable journal is reported but doesn't stop the load: the
+/// snapshot on its own is still a consistent state
+pub fn replay_unapplied_in(dir: &Path, state: &mut LegendState) {
+    match unapplied_events_in(dir) {
+        Ok(events) => {
+            for event in &events {
+                apply(state, event);
+                state.last_updated = state.last_updated.max(event.timestamp);
+            }
+        }
+        Err(e) => output::warning(&format!("Journal not replayed: {}", e)),
+    }
+}
+
+/// Read every event in the journal (oldest first)
+///
+/// A missing journal is not an error - it just means no history yet
+pub fn read_events() -> Result<Vec<Event>, Box<dyn std::error::Error>> {
+    let path = journal_file();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read journal: {}", e))?;
+
+    parse_events(&content)
+}
+
+/// Sequence number of the newest event; 0 for an empty journal
+///
+/// Reads only the last line, so an encrypted journal isn't decrypted
+pub fn last_seq() -> Result<u64, Box<dyn std::error::Error>> {
+    let content = match fs::read_to_string(journal_file()) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(format!("Failed to read journal: {}", e).into()),
+    };
+    let Some(line) = content.lines().rev().find(|line| !line.trim().is_empty()) else {
+        return Ok(0);
+    };
+    if let Some(seq) = encryption::sealed_line_seq(line) {
+        return Ok(seq);
+    }
+    let event: Event = serde_json::from_str(line).map_err(|e| format!("Corrupt journal entry at the end: {}", e))?;
+    Ok(event.seq)
+}
+
+/// Parse journal content, one event (or encrypted batch) per non-empty line
+fn parse_events(content: &str) -> Result<Vec<Event>, Box<dyn std::error::Error>> {
+    let mut events = Vec::new();
+    for (i, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        if let Some(batch) = encryption::open_line(line) {
+            let batch = batch.map_err(|e| format!("Journal line {}: {}", i + 1, e))?;
+            events.extend(parse_events(&batch)?);
+            continue;
+        }
+        events.push(
+            serde_json::from_str(line).map_err(|e| format!("Corrupt journal entry on line {}: {}", i + 1, e))?,
+        );
+    }
+    Ok(events)
+}
+
+/// Where a replay stops (inclusive)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayLimit {
+    All,
+    Seq(u64),
+    Timestamp(i64),
+}
+
+/// The prefix of `events` that falls within `limit`
+///
+/// Events are stored oldest first, so this is a simple take_while
+pub fn events_until(events: &[Event], limit: ReplayLimit) -> &[Event] {
+    let count = events
+        .iter()
+        .take_while(|event| match limit {
+            ReplayLimit::All => true,
+            ReplayLimit::Seq(seq) => event.seq <= seq,
+            ReplayLimit::Timestamp(ts) => event.timestamp <= ts,
+        })
+        .count();
+    &events[..count]
+}
+
+/// Rebuild the feature list from scratch by applying events in order
+///
+/// Changed features replace the old version in place, so the result keeps
+/// the same ordering as the snapshot that produced the events
+#[cfg(test)]
+fn replay(events: &[Event]) -> Vec<Feature> {
+    let mut state = LegendState::new(String::new());
+    for event in events {
+        apply(&mut state, event);
+    }
+    state.features
+}
+
+/// Apply one event to a state; applying it twice changes nothing
+fn apply(state: &mut LegendState, event: &Event) {
+    let features = &mut state.features;
+    match &event.kind {
+        EventKind::Baseline { features: baseline } => {
+            *features = baseline.clone();
+        }
+        EventKind::FeatureAdded { feature } | EventKind::FeatureChanged { feature, .. } => {
+            match features.iter_mut().find(|f| f.id == feature.id) {
+                Some(existing) => *existing = feature.clone(),
+                None => features.push(feature.clone()),
+            }
+        }
+        EventKind::FeatureRemoved { id, .. } => {
+            features.retain(|f| &f.id != id);
+        }
+        EventKind::SectionsChanged(sections) => sections.apply(state),
+    }
+}
+
+/// The state rebuilt from `events`
+///
+/// A section no event mentions keeps the snapshot's value: it can only
+/// come from a journal started before sections were journaled
+pub fn state_from_events(snapshot: &LegendState, events: &[Event]) -> LegendState {
+    let mut state = LegendState {
+        features: Vec::new(),
+        last_updated: events.last().map(|e| e.timestamp).unwrap_or(snapshot.created_at),
+        ..snapshot.clone()
+    };
+    for event in events {
+        apply(&mut state, event);
+    }
+    state
+}
+
+/// Load the state, or reconstruct it as of `at` from the journal
+///
+/// Used by the `--at` flag on show, get_state, and search
+pub fn load_state_at(at: Option<i64>) -> Result<LegendState, Box<dyn std::error::Error>> {
+    let snapshot = storage::load_state()?;
+    let at = match at {
+        Some(at) => at,
+        None => return Ok(snapshot),
+    };
+
+    let events = read_events()?;
+    let first = events
+        .first()
+        .ok_or("No journal history yet - --at needs at least one recorded update")?;
+    if at < first.timestamp {
+        output::warning(&format!(
+            "Journal starts at {}; nothing is known before then",
+            crate::dates::format_date(first.timestamp)
+        ));
+    }
+
+    Ok(state_from_events(
+        &snapshot,
+        events_until(&events, ReplayLimit::Timestamp(at)),
+    ))
+}
+
+/// Pull `--at <date>` out of an argument list
+///
+/// Returns the timestamp (if given) and the remaining arguments
+pub fn take_at_flag(args: &[String]) -> Result<(Option<i64>, Vec<String>), Box<dyn std::error::Error>> {
+    let mut at = None;
+    let mut rest = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--at" {
+            let value = iter.next().ok_or("--at requires a date or timestamp")?;
+            at = Some(crate::dates::parse_date(value)?);
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    Ok((at, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature(id: &str, description: &str) -> Feature {
+        Feature::new(
+            id.to_string(),
+            id.to_string(),
+            "core".to_string(),
+            description.to_string(),
+        )
+    }
+
+    fn event(seq: u64, timestamp: i64, kind: EventKind) -> Event {
+        Event { seq, timestamp, kind }
+    }
+
+    #[test]
+    fn test_commit_deferred_saves_every_n_events() {
+        use crate::testing::TestEnv;
+
+        let _env = TestEnv::initialized(1_709_251_200);
+        let mut state = storage::load_state().unwrap();
+        let before = state.clone();
+        state.add_feature(feature("seed", "saved"));
+        commit(&before, &state).unwrap();
+        let snapshot = fs::read(storage::state_file()).unwrap();
+
+        // Two events wait in the journal; the snapshot isn't rewritten
+        for id in ["a", "b"] {
+            let before = state.clone();
+            state.add_feature(feature(id, "new"));
+            commit_deferred(&before, &state, 3).unwrap();
+        }
+        assert_eq!(fs::read(storage::state_file()).unwrap(), snapshot);
+        assert_eq!(unapplied_events_in(&storage::legend_dir()).unwrap().len(), 2);
+        assert_eq!(storage::load_state().unwrap().features.len(), 3);
+
+        // The third reaches snapshot_every and saves them all
+        let before = state.clone();
+        state.features[0].description = "edited".to_string();
+        commit_deferred(&before, &state, 3).unwrap();
+        assert!(unapplied_events_in(&storage::legend_dir()).unwrap().is_empty());
+        assert_eq!(storage::load_state().unwrap().features[0].description, "edited");
+
+        // A touch the journal can't carry is saved at once
+        let before = state.clone();
+        state.features[1].last_updated += 60;
+        commit_deferred(&before, &state, 3).unwrap();
+        assert_eq!(storage::load_state().unwrap().features[1].last_updated, state.features[1].last_updated);
+    }
+
+    #[test]
+    fn test_replay_applies_events_in_order() {
+        let events = vec![
+            event(1, 100, EventKind::Baseline { features: vec![feature("a", "first")] }),
+            event(2, 200, EventKind::FeatureAdded { feature: feature("b", "second") }),
+            event(
+                3,
+                300,
+                EventKind::FeatureChanged {
+                    id: "a".to_string(),
+                    changes: Vec::new(),
+                    feature: feature("a", "edited"),
+                },
+            ),
+            event(4, 400, EventKind::FeatureRemoved { id: "b".to_string(), name: "b".to_string() }),
+        ];
+
+        let all = replay(&events);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].description, "edited");
+
+        let at_seq_2 = replay(events_until(&events, ReplayLimit::Seq(2)));
+        assert_eq!(at_seq_2.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(at_seq_2[0].description, "first");
+
+        assert_eq!(events_until(&events, ReplayLimit::Timestamp(299)).len(), 2);
+        assert!(events_until(&events, ReplayLimit::Timestamp(50)).is_empty());
+    }
+
+    #[test]
+    fn test_take_at_flag() {
+        let args: Vec<String> = ["auth", "--at", "2024-03-01", "--domain", "api"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (at, rest) = take_at_flag(&args).unwrap();
+        assert_eq!(at, Some(1709251200));
+        assert_eq!(rest, vec!["auth", "--domain", "api"]);
+
+        assert!(take_at_flag(&["--at".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_event_roundtrip() {
+        let event = Event {
+            seq: 3,
+            timestamp: 1700000000,
+            kind: EventKind::FeatureRemoved {
+                id: "auth".to_string(),
+                name: "Authentication".to_string(),
+            },
+        };
+
+        let line = serde_json::to_string(&event).unwrap();
+        assert!(line.contains("\"type\":\"feature_removed\""));
+
+        let parsed = parse_events(&format!("{}\n\n", line)).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].seq, 3);
+        assert!(matches!(parsed[0].kind, EventKind::FeatureRemoved { .. }));
+    }
+
+    #[test]
+    fn test_sections_are_replayed() {
+        let _env = crate::testing::TestEnv::initialized(1_709_251_200);
+        let before = storage::load_state().unwrap();
+        let mut decided = before.clone();
+        decided.decisions.push(Decision {
+            id: 1,
+            title: "Store state as bincode".to_string(),
+            rationale: "Reads must stay under 5ms".to_string(),
+            feature_ids: Vec::new(),
+            decided_at: 1_709_251_200,
+        });
+        commit(&before, &decided).unwrap();
+        let snapshot = fs::read(storage::state_file()).unwrap();
+        let applied_path = storage::legend_path(APPLIED_FILE_NAME);
+        let applied = fs::read(&applied_path).unwrap();
+
+        // A write that dies after the journal append leaves the old snapshot
+        let mut edited = decided.clone();
+        edited.decisions[0].title = "Store state as JSON".to_string();
+        edited.conventions.push("no unwrap in prod code".to_string());
+        commit(&decided, &edited).unwrap();
+        fs::write(storage::state_file(), &snapshot).unwrap();
+        fs::write(&applied_path, &applied).unwrap();
+
+        let loaded = storage::load_state().unwrap();
+        assert_eq!(loaded.decisions, edited.decisions);
+        assert_eq!(loaded.conventions, edited.conventions);
+
+        // ...and --at sees each version in its turn: 1 is the baseline,
+        // 2 the decision, 3 the edit
+        let events = read_events().unwrap();
+        assert!(state_from_events(&loaded, events_until(&events, ReplayLimit::Seq(1))).decisions.is_empty());
+        let at_decision = state_from_events(&loaded, events_until(&events, ReplayLimit::Seq(2)));
+        assert_eq!(at_decision.decisions, decided.decisions);
+        assert!(at_decision.conventions.is_empty());
+        assert_eq!(Sections::changed(&state_from_events(&loaded, &events), &loaded), None);
+    }
+
+    #[test]
+    fn test_parse_corrupt_line() {
+        let result = parse_events("{not json}\n");
+        assert!(result.unwrap_err().to_string().contains("line 1"));
+    }
+}
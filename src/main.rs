@@ -1,19 +1,65 @@
+use exit::ExitError;
+use serde_json::json;
 use std::env;
+use std::path::PathBuf;
+use std::time::Instant;
 
 // Declare our modules
 // This tells Rust to look for types.rs, storage.rs, and commands/ in the same directory
 mod types;
 mod storage;
+mod migrations;
 mod commands;
-
-// Import types we'll use (later layers will use these)
-use types::{Feature, FeatureStatus, LegendState};
+mod git;
+mod dates;
+mod deadlines;
+mod text;
+mod diff;
+mod journal;
+mod exit;
+mod findings;
+mod metrics;
+mod diagnostics;
+mod sandbox;
+mod config;
+mod analysis;
+#[cfg(feature = "tui")]
+mod terminal;
+mod importers;
+mod yaml;
+mod ids;
+mod theme;
+mod output;
+mod validators;
+mod signing;
+mod access;
+#[cfg(feature = "webhooks")]
+mod webhooks;
+#[cfg(feature = "tui")]
+mod signals;
+mod deps;
+mod hierarchy;
+mod history;
+mod clock;
+mod cargo_features;
+mod progress;
+mod rollups;
+mod guard;
+mod global;
+mod registry;
+mod sync;
+mod encryption;
+mod file_lists;
+#[cfg(test)]
+mod testing;
 
 fn main() {
     // R* principle: Keep main thin, call into run() for error handling
+    // Exit codes are part of the CLI contract - see exit.rs
     if let Err(e) = run() {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
+        let code = exit::code_for(e.as_ref());
+        output::diag(output::Level::Error, &e.to_string(), json!({"exit_code": code}));
+        std::process::exit(code);
     }
 }
 
@@ -21,7 +67,17 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     // Collect command-line arguments into a Vec<String>
     // Why Vec and not array? We don't know the arg count at compile time
     // env::args() returns an iterator, .collect() gathers into Vec
-    let args: Vec<String> = env::args().collect();
+    let raw_args: Vec<String> = env::args().collect();
+
+    // Panics write a crash report instead of dumping a raw backtrace
+    diagnostics::install_panic_hook(raw_args.clone());
+
+    // Global flags may appear anywhere; strip them before dispatching
+    let (flags, args) = split_global_flags(raw_args)?;
+    output::set_diag_json(flags.diag_json);
+    if let Some(mode) = flags.progress {
+        progress::set_mode(mode);
+    }
 
     // args[0] is always the program name ("legend")
     // We need at least 2 args: program name + command
@@ -34,38 +90,300 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     // Why &str not String? We're borrowing, not taking ownership
     // args[1] is a String, &args[1] gives us &String, which coerces to &str
     let command = &args[1];
+    output::set_screen_reader(flags.screen_reader);
+
+    // -p, --dir, LEGEND_DIR, or the nearest .legend up from here (not for init)
+    let explicit = match (&flags.project, &flags.dir) {
+        (Some(_), Some(_)) => return Err(ExitError::new(exit::USAGE, "-p and --dir both say where the state is; use one").into()),
+        (Some(name), None) => Some(registry::resolve(name)?),
+        (None, dir) => dir.clone(),
+    };
+    if let Some(dir) = storage::locate_legend_dir(explicit, command != "init") {
+        storage::set_legend_dir(dir);
+    }
+
+    // Before --global below, which may create ~/.legend
+    if flags.sandbox {
+        sandbox::check(command, &args[2..])?;
+    }
+
+    // Hooks installed globally run in repos that never adopted Legend
+    if flags.if_initialized && !storage::is_initialized() && !matches!(command.as_str(), "init" | "help" | "version") {
+        return Ok(());
+    }
+
+    // The personal store in ~/.legend instead of this project's
+    if flags.global {
+        global::use_global_store(command)?;
+    }
+
+    // Sandboxed runs leave .legend untouched, metrics included
+    if flags.sandbox {
+        return sandbox::run_sandboxed(|| dispatch(command, &args[2..]));
+    }
+
+    // Time the command for the opt-in local metrics (no-op when disabled)
+    let start = Instant::now();
+    let result = dispatch(command, &args[2..]);
+    metrics::record_command(command, start.elapsed());
+
+    // Push whatever the sync remote hasn't taken yet (no-op without [sync])
+    if !flags.global && command != "sync" && storage::is_initialized() {
+        sync::retry_queued();
+    }
+
+    result
+}
+
+// Flags that apply to every command
+#[derive(Debug, Default, PartialEq)]
+struct GlobalFlags {
+    sandbox: bool,
+    screen_reader: bool,
+    diag_json: bool,
+    if_initialized: bool,
+    progress: Option<progress::Mode>,
+    global: bool,
+    dir: Option<PathBuf>,
+    project: Option<String>,
+}
+
+// Pull global flags out of the argument list, leaving the rest in order
+fn split_global_flags(args: Vec<String>) -> Result<(GlobalFlags, Vec<String>), Box<dyn std::error::Error>> {
+    let mut flags = GlobalFlags::default();
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sandbox" => flags.sandbox = true,
+            "--screen-reader" => flags.screen_reader = true,
+            "--if-initialized" => flags.if_initialized = true,
+            // Only before the command: `config --global` has its own meaning
+            "--global" if rest.len() == 1 => flags.global = true,
+            "--diag" => match args.next().as_deref() {
+                Some("json") => flags.diag_json = true,
+                Some("text") => flags.diag_json = false,
+                _ => return Err(ExitError::new(exit::USAGE, "--diag expects json or text").into()),
+            },
+            "--dir" => match args.next() {
+                Some(dir) if !dir.is_empty() => flags.dir = Some(PathBuf::from(dir)),
+                _ => return Err(ExitError::new(exit::USAGE, "--dir expects the path of a Legend directory").into()),
+            },
+            "-p" => match args.next() {
+                Some(name) if !name.is_empty() => flags.project = Some(name),
+                _ => return Err(ExitError::new(exit::USAGE, "-p expects a project name (see legend projects)").into()),
+            },
+            "--progress" => match args.next().as_deref().and_then(progress::Mode::parse) {
+                Some(mode) => flags.progress = Some(mode),
+                None => return Err(ExitError::new(exit::USAGE, "--progress expects bar, json, or off").into()),
+            },
+            _ => rest.push(arg),
+        }
+    }
+    Ok((flags, rest))
+}
 
+// Route a command name to its handler
+fn dispatch(command: &str, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     // Match on the command string
     // R* principle: Match is for scannable control flow
     // Each arm is simple - just call a handler function
-    match command.as_str() {
+    match command {
         "help" | "--help" | "-h" => {
             print_help();
         }
+        "log-time" => {
+            handle_log_time(args)?;
+        }
+        "note" => {
+            handle_note(args)?;
+        }
+        "version" | "--version" | "-V" => {
+            handle_version(args)?;
+        }
+        "recent" => {
+            handle_recent(args)?;
+        }
+        "relate" => {
+            handle_relate(args)?;
+        }
+        "why" => {
+            handle_why(args)?;
+        }
         "init" => {
-            handle_init()?;
+            handle_init(args)?;
         }
         "get_state" => {
-            handle_get_state()?;
+            handle_get_state(args)?;
         }
         "update" => {
             handle_update()?;
         }
         "show" => {
-            handle_show()?;
+            handle_show(args)?;
         }
         "search" => {
-            handle_search(&args[2..])?;
+            handle_search(args)?;
         }
         "discover" => {
-            handle_discover(&args[2..])?;
+            handle_discover(args)?;
+        }
+        "pr-description" => {
+            handle_pr_description(args)?;
+        }
+        "release-notes" => {
+            handle_release_notes(args)?;
+        }
+        "changelog" => {
+            handle_changelog(args)?;
+        }
+        "validate" => {
+            handle_validate(args)?;
+        }
+        "lint" => {
+            handle_lint(args)?;
+        }
+        "coverage" => {
+            handle_coverage(args)?;
+        }
+        "metrics" => {
+            handle_metrics(args)?;
+        }
+        "diag" => {
+            handle_diag(args)?;
+        }
+        "replay" => {
+            handle_replay(args)?;
+        }
+        "stats" => {
+            handle_stats(args)?;
+        }
+        "impact" => {
+            handle_impact(args)?;
+        }
+        "onboard" => {
+            handle_onboard(args)?;
+        }
+        "verify" => {
+            handle_verify(args)?;
+        }
+        "copy" => {
+            handle_copy(args)?;
+        }
+        "export" => {
+            handle_export(args)?;
+        }
+        "import" => {
+            handle_import(args)?;
+        }
+        "merge" => {
+            handle_merge(args)?;
+        }
+        "warm" => {
+            handle_warm(args)?;
+        }
+        "optimize" => {
+            handle_optimize(args)?;
+        }
+        "convert" => {
+            handle_convert(args)?;
+        }
+        "compact" => {
+            handle_compact(args)?;
+        }
+        "config" => {
+            handle_config(args)?;
+        }
+        "dedupe" => {
+            handle_dedupe(args)?;
+        }
+        "split" => {
+            handle_split(args)?;
+        }
+        "handoff" => {
+            handle_handoff(args)?;
+        }
+        "plan" => {
+            handle_plan(args)?;
+        }
+        "promote" => {
+            handle_promote(args)?;
+        }
+        "suggest-status" => {
+            handle_suggest_status(args)?;
+        }
+        "next" => {
+            handle_next(args)?;
+        }
+        "hooks" => {
+            handle_hooks(args)?;
+        }
+        "task" => {
+            handle_task(args)?;
+        }
+        "milestone" => {
+            handle_milestone(args)?;
+        }
+        "goal" => {
+            handle_goal(args)?;
+        }
+        "convention" => {
+            handle_convention(args)?;
+        }
+        "focus" => {
+            handle_focus(args)?;
+        }
+        "service" => {
+            handle_service(args)?;
+        }
+        "sync" => {
+            handle_sync(args)?;
+        }
+        "hook" => {
+            handle_hook(args)?;
+        }
+        "project" => {
+            handle_project(args)?;
+        }
+        "projects" => {
+            handle_projects(args)?;
+        }
+        "decide" => {
+            handle_decide(args)?;
+        }
+        "decisions" => {
+            handle_decisions(args)?;
+        }
+        "session" => {
+            handle_session(args)?;
+        }
+        "history" => {
+            handle_history(args)?;
+        }
+        "undo" => {
+            handle_undo(args)?;
+        }
+        "diff" => {
+            handle_diff(args)?;
+        }
+        "archive" => {
+            handle_archive(args)?;
+        }
+        "prune" => {
+            handle_prune(args)?;
         }
         // Unknown command - this is the catch-all
         unknown => {
-            eprintln!("Unknown command: {}", unknown);
-            eprintln!();
+            output::diag(
+                output::Level::Error,
+                &format!("Unknown command: {}", unknown),
+                json!({"exit_code": exit::USAGE}),
+            );
+            if !output::diag_json() {
+                eprintln!();
+            }
             print_help();
-            std::process::exit(1);
+            std::process::exit(exit::USAGE);
         }
     }
 
@@ -78,19 +396,221 @@ fn print_help() {
     println!("Legend - Lightweight context memory for AI-assisted development");
     println!();
     println!("Usage:");
-    println!("  legend <command> [options]");
+    println!("  legend [--sandbox] [--screen-reader] [--diag json] [--progress json] [--if-initialized] [--global] [--dir <path> | -p <name>] <command> [options]");
+    println!();
+    println!("Global options:");
+    println!("  --sandbox           Run against a temporary copy of .legend and");
+    println!("                      print the resulting diff; real state is untouched");
+    println!("                      (commands that write outside .legend are refused)");
+    println!("  --screen-reader     Labeled lines instead of tables in show and stats");
+    println!("                      (also LEGEND_SCREEN_READER=1)");
+    println!("  --diag json         Timings, warnings, and errors on stderr as one JSON");
+    println!("                      record per line (also LEGEND_DIAG=json)");
+    println!("  --progress <mode>   Progress of discover, analysis, and import on stderr:");
+    println!("                      bar, json (one record per update), or off");
+    println!("                      (also LEGEND_PROGRESS; default: bar on a terminal)");
+    println!("  --if-initialized    Do nothing (exit 0) if this repo has no .legend state,");
+    println!("                      for hooks installed globally");
+    println!("  --global            Before update, show, or search: use the personal store");
+    println!("                      in ~/.legend/global.lz4 (shown in every get_state)");
+    println!("  --dir <path>        Keep the state in <path> instead of .legend (also LEGEND_DIR);");
+    println!("                      otherwise the nearest .legend in this or a parent directory");
+    println!("  -p <name>           The registered project <name>, from any directory (see projects)");
     println!();
     println!("Commands:");
     println!("  help                Show this help message");
     println!("  init                Initialize .legend directory");
+    println!("    --yes-i-mean-it   Allow ~, /, or a huge tree outside version control");
+    println!("    --format json     Store the state as diffable JSON (default: binary)");
+    println!("    --encrypt         Encrypt the state at rest with age (also in an existing project)");
     println!("  get_state           Print current state as JSON");
+    println!("    --or-empty        Print an empty state instead of failing when not initialized");
+    println!("    --sections <list> Which top-level sections, in order (default: get_state.sections)");
+    println!("    --hook            Print a compact summary past get_state.max_bytes (for hooks)");
+    println!("    --budget [<n>]    Only the features that fit in n bytes (default: max_bytes),");
+    println!("                      plus a continuation token");
+    println!("    --continue <t>    The next chunk of features after a --budget call");
     println!("  update              Update feature state from stdin");
     println!("  show                Display human-readable state");
+    println!("    --interactive     Arrow keys to select, s status, a archive, enter details");
+    println!("    --oneline         One line per feature with a status mark ([theme] glyphs)");
+    println!("    --tree            Oneline view with children indented under parent_id");
+    println!("    --by domain       One line per domain: counts, last activity, top features");
     println!("  search <query>      Search features by keyword");
     println!("    --domain <d>      Filter by domain");
     println!("    --tag <t>         Filter by tag");
     println!("    --status <s>      Filter by status");
+    println!("    --meta <k=v>      Filter by a metadata value (or just <k> to require it)");
+    println!("    --owner <o>       Filter by owner");
+    println!("    --at <date>       Search the state as of a date (also for show/get_state)");
     println!("  discover [path]     Scan project and suggest features");
+    println!("    --analyze         Follow imports to find related files");
+    println!("    --yes-i-mean-it   Allow ~, /, or a huge tree outside version control");
+    println!("  pr-description      Markdown PR body grouped by feature");
+    println!("    --base <ref>      Branch to compare against (default: main/master)");
+    println!("  release-notes       Completed features grouped by domain");
+    println!("    --since <tag|date> Start of the window (required)");
+    println!("    --until <tag|date> End of the window (default: now)");
+    println!("  changelog           How the Legend memory itself has changed");
+    println!("    --format <f>      keepachangelog (default) or json");
+    println!("    --since <date>    Only entries on or after this date");
+    println!("  validate            Check state for structural problems");
+    println!("  lint                Check features for low-quality context");
+    println!("    --check           Exit 3 if any problem is found (CI)");
+    println!("    --format <f>      text (default) or github annotations");
+    println!("  coverage            Share of source files tracked by features");
+    println!("    --min <percent>   Exit 3 if coverage is below the threshold");
+    println!("    --format <f>      text (default) or github annotations");
+    println!("  metrics <cmd>       Opt-in local usage metrics");
+    println!("    enable|disable    Start/stop recording to .legend/metrics.json");
+    println!("    show [--json]     Counts and latencies per command");
+    println!("    reset             Clear recorded counters");
+    println!("  diag bundle         Collect diagnostics for a bug report");
+    println!("    --stdout          Print instead of writing .legend/diag-*.txt");
+    println!("  diag health         Readiness probe: state load, last save, journal lag");
+    println!("    --json            As JSON (exit 3 when not ready)");
+    println!("  replay              Rebuild state from the journal and verify it");
+    println!("    --seq <n>         Print the state as of event n instead");
+    println!("    --until <date>    Print the state as of a date/timestamp instead");
+    println!("  stats               Summary and week-by-week activity heatmap");
+    println!("    --by <g>          Heatmap rows per domain (default) or feature");
+    println!("    --weeks <n>       Weeks to show (default: 12)");
+    println!("    --json            Print the data as JSON");
+    println!("    --cycle-time      Cycle-time percentiles per domain and a burndown instead");
+    println!("    --csv             With --cycle-time, print metric,key,value rows");
+    println!("    --time            Estimated and logged hours per domain instead");
+    println!("  impact <id|file>    Features sharing files with a feature or file");
+    println!("    --analyze         Also follow imports (Rust, TS/JS, Python)");
+    println!("  onboard             Markdown orientation tour for newcomers");
+    println!("    --top <n>         Number of key features to include (default: 5)");
+    println!("  verify              Check state.lz4 against its signature ([signing])");
+    println!("  copy <id>           Copy a feature into another project's state");
+    println!("    --to <dir>        Project directory with its own .legend (required)");
+    println!("    --as <id>         Id to use there (default: same id, -2 on conflict)");
+    println!("  export              The whole state as portable JSON (for import elsewhere)");
+    println!("    --format <f>      json, yaml, csv (features only), or markdown (to read)");
+    println!("    --filter <expr>   Only matching features, e.g. \"domain:api status:Pending\"");
+    println!("    --docusaurus      Markdown pages (index + one per domain) instead of JSON");
+    println!("    --mkdocs          Same, with MkDocs frontmatter");
+    println!("    --out <dir>       Folder for the pages (default: docs/legend)");
+    println!("  import <file>       Add what an export file has that this project doesn't");
+    println!("    --from <source>   legend (default), trello, notion-csv, or github-projects");
+    println!("    --format <f>      json, yaml, or csv for a Legend export (default: from the file)");
+    println!("    --domain <d>      Domain for items without one (default: imported)");
+    println!("    --filter <expr>   Only import matching features (same syntax as export)");
+    println!("  merge <theirs>      Three-way merge of a diverged state (file, export, git:<ref>)");
+    println!("    --base <state>    Common ancestor (default for git:<ref>: the merge base)");
+    println!("    --conflicts-file  Write conflicts to .legend/merge-conflicts.json");
+    println!("    --continue        Apply a resolved conflicts file");
+    println!("  warm                Preload state into the page cache (for cold disks)");
+    println!("    --quiet           No summary line");
+    println!("  optimize            Store the most-accessed features first in state.lz4");
+    println!("    --track           Start counting search/impact hits (.legend/access.json)");
+    println!("    --untrack         Stop counting and remove the counts");
+    println!("  convert             Rewrite state.lz4 in another storage format");
+    println!("    --format <f>      binary (bincode + LZ4) or json (sets storage.format)");
+    println!("  compact             Save journal events a crash kept out of the snapshot");
+    println!("  config <cmd>        Read or edit .legend/config.toml");
+    println!("    show | get <key>  Effective settings (user config with project on top)");
+    println!("    show --resolved   ...and where each comes from (env, project, user, default)");
+    println!("    set <key> <value> Write a setting (unset <key> removes it)");
+    println!("    edit | path       Open the file in $EDITOR, or print its location");
+    println!("    --global          Use ~/.config/legend/config.toml instead");
+    println!("  dedupe              Find near-duplicate features and merge them");
+    println!("    --yes             Merge every proposal without asking");
+    println!("    --json            Print the proposals as JSON (changes nothing)");
+    println!("    --threshold <n>   Similarity from 0 to 1 (default: 0.6)");
+    println!("  split <id>          Break a feature into several (asks on a terminal)");
+    println!("    --into <ids...>   IDs of the new features");
+    println!("    --assign <p>=<id> Move a file (or a directory's files) to a new feature");
+    println!("    --section <n>=<id> Move context paragraph n to a new feature");
+    println!("  handoff <id>        Record that a feature changes hands (notifies [webhooks])");
+    println!("    --to <owner>      The new owner (required)");
+    println!("    --from <owner>    Previous owner (default: current owner, then git user)");
+    println!("    --note <text>     What the next person should know");
+    println!("  plan <id> <line>    Reserve an ID for upcoming work (a Planned stub)");
+    println!("    --domain <d>      Domain of the stub (default: the ID's prefix)");
+    println!("  promote <id>        Turn a Planned stub into a real feature");
+    println!("    --description <t> Required unless already set; also --name --domain");
+    println!("    --context <t>     Background; --files a,b and --tags a,b as lists");
+    println!("  suggest-status      Status hints from git activity (stale? / start?)");
+    println!("    --apply           Apply the listed suggestions");
+    println!("    --only <kind>     Just start or stale suggestions");
+    println!("    --json            Print the suggestions as JSON");
+    println!("    --stale-days <n>  InProgress with files idle this long (default: 21)");
+    println!("    --recent-days <n> Window for start? (default: 14), with --min-commits (3)");
+    println!("  next                What to pick up: ready features by priority, recency, and risk");
+    println!("    --json            As JSON, with scores");
+    println!("  hooks print         Claude Code hook snippet for .claude/settings.json");
+    println!("    --style <s>       full (what init installs), compact, budgeted, prompt-aware");
+    println!("    --event <e>       Just SessionStart or UserPromptSubmit");
+    println!("  hooks upgrade       Rewrite old shell-style Legend hooks to 'legend hook <event>'");
+    println!("    --dry-run         Print the rewritten settings instead of saving them");
+    println!("  hook <event>        What an installed hook runs (--style as for hooks print)");
+    println!("  task add <id> <t>   Add a subtask to a feature's checklist");
+    println!("  task done <id> <n>  Check off subtask n (several numbers allowed)");
+    println!("  task list <id>      The checklist (--json for JSON)");
+    println!("  note <id> <text>    Append to a feature's log (update never overwrites notes)");
+    println!("    --author <name>   Who wrote it (default: git user.name)");
+    println!("    --from-stdin      Take the text from stdin, minus color codes (for a Stop hook)");
+    println!("  note <id>           The log, oldest first (--json for JSON)");
+    println!("  relate <a> <k> <b>  Link features: blocks, relates_to, or duplicates");
+    println!("    --remove          Take the link out");
+    println!("  relate <id>         A feature's links, both directions (--json for JSON)");
+    println!("  log-time <id> <h>   Add hours spent (estimate_hours is set by update)");
+    println!("  milestone create <id> <name>  Group features into a milestone");
+    println!("    --due <date>      Due date (YYYY-MM-DD)");
+    println!("  milestone assign <m> <ids>    Add features (--remove to take them out)");
+    println!("  milestone show [<m>]          Progress per milestone (--json for JSON)");
+    println!("  goal create <id> <title>      An outcome features exist for (in get_state)");
+    println!("    --target <date>   Target date (YYYY-MM-DD)");
+    println!("  goal link <g> <ids>           Link features (--remove to unlink)");
+    println!("  goal show [<g>]               Progress per goal (--json for JSON)");
+    println!("  focus push <ids>              What we're on now: in full at the top of get_state");
+    println!("  focus pop                     Back to what was focused before");
+    println!("  focus show | clear            The stack, top first (--json for JSON) / empty it");
+    println!("  focus watch                   Push features whose files keep changing, pop them when idle");
+    println!("    --interval <s>    Seconds between checks (default: 30)");
+    println!("  service install --watch       Run focus watch as a user service (systemd or launchd)");
+    println!("    --interval <s>    Seconds between checks (default: 30)");
+    println!("  service status | uninstall    Whether it's installed and running (--json) / remove it");
+    println!("  sync status                   Changes queued for the sync remote (--json for JSON)");
+    println!("  sync flush                    Push them now (sync.push in config runs after every command)");
+    println!("  convention add <rule>         A rule every change should follow (in get_state and prompts)");
+    println!("  convention remove <n>|<rule>  Drop one (numbers from list)");
+    println!("  convention list               Numbered, in order (--json for JSON)");
+    println!("  projects            Every project init has set up: features, last activity (--json)");
+    println!("    forget <name>     Drop one from the list (its .legend stays)");
+    println!("  project [show]      Name, description, repo, tech stack, conventions (--json)");
+    println!("  project set <k> <v> Set one (tech_stack is comma-separated; unset <k> clears)");
+    println!("  decide <title>      Record an architectural decision (in get_state)");
+    println!("    --rationale <t>   Why (required)");
+    println!("    --feature <id>    Feature it shapes (repeatable)");
+    println!("    --list            Every decision, newest first");
+    println!("  decisions           The same list (--feature <id> for those linked to it)");
+    println!("  session start       Begin a work session");
+    println!("  session end [<s>]   End it with a summary; records the features touched");
+    println!("  session list        Sessions, newest first (--json for JSON)");
+    println!("  session recap       Recent summaries (printed by the compact SessionStart hook)");
+    println!("  why <id>            How a feature got here: creation, changes, decisions,");
+    println!("                      sessions, notes, and commits, oldest first (--json, --no-git)");
+    println!("  recent              What changed in the last 48 hours, one line per feature");
+    println!("    --since <a>       A different window (7d, 2w, or a date); --limit <n> (10)");
+    println!("  history             Saved versions of the state (--json for JSON)");
+    println!("  undo                Restore the state from before the last save");
+    println!("  diff [<a>] [<b>]    Field-level changes between snapshots (default: last save)");
+    println!("    --json            As JSON");
+    println!("  archive <id>...     Move features to .legend/archive.lz4 (--list to see them)");
+    println!("  prune               Archive old features in one status");
+    println!("    --older-than <a>  Not updated for this long (30d, 2w; required)");
+    println!("    --status <s>      Status to prune (default: Complete)");
+    println!("    --dry-run         List what would be archived");
+    println!("  version             Print the version");
+    println!("    --features        ...and which optional subsystems are compiled in");
+    println!();
+    println!("Exit codes:");
+    println!("  0 success, 1 failure, 2 usage error, 3 check failed, 4 not initialized,");
+    println!("  128+n stopped by signal n (show --interactive)");
 }
 
 fn handle_search(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
@@ -100,14 +620,14 @@ fn handle_search(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
 // Command handlers
 // R* principle: Working code first, implement functionality layer by layer
 
-fn handle_init() -> Result<(), Box<dyn std::error::Error>> {
+fn handle_init(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     // Delegate to the real implementation in commands/init.rs
-    commands::init::handle_init()
+    commands::init::handle_init(args)
 }
 
-fn handle_get_state() -> Result<(), Box<dyn std::error::Error>> {
+fn handle_get_state(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     // Delegate to the real implementation in commands/get_state.rs
-    commands::get_state::handle_get_state()
+    commands::get_state::handle_get_state(args)
 }
 
 fn handle_update() -> Result<(), Box<dyn std::error::Error>> {
@@ -115,10 +635,272 @@ fn handle_update() -> Result<(), Box<dyn std::error::Error>> {
     commands::update::handle_update()
 }
 
-fn handle_show() -> Result<(), Box<dyn std::error::Error>> {
-    commands::show::handle_show()
+fn handle_show(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::show::handle_show(args)
 }
 
 fn handle_discover(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     commands::discover::handle_discover(args)
 }
+
+fn handle_pr_description(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::pr_description::handle_pr_description(args)
+}
+
+fn handle_release_notes(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::release_notes::handle_release_notes(args)
+}
+
+fn handle_changelog(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::changelog::handle_changelog(args)
+}
+
+fn handle_validate(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::validate::handle_validate(args)
+}
+
+fn handle_lint(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::lint::handle_lint(args)
+}
+
+fn handle_coverage(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::coverage::handle_coverage(args)
+}
+
+fn handle_metrics(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::metrics::handle_metrics(args)
+}
+
+fn handle_diag(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::diag::handle_diag(args)
+}
+
+fn handle_replay(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::replay::handle_replay(args)
+}
+
+fn handle_stats(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::stats::handle_stats(args)
+}
+
+fn handle_impact(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::impact::handle_impact(args)
+}
+
+fn handle_onboard(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::onboard::handle_onboard(args)
+}
+
+fn handle_verify(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::verify::handle_verify(args)
+}
+
+fn handle_copy(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::copy::handle_copy(args)
+}
+
+fn handle_export(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::export::handle_export(args)
+}
+
+fn handle_import(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::import::handle_import(args)
+}
+
+fn handle_merge(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::merge::handle_merge(args)
+}
+
+fn handle_warm(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::warm::handle_warm(args)
+}
+
+fn handle_optimize(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::optimize::handle_optimize(args)
+}
+
+fn handle_convert(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::convert::handle_convert(args)
+}
+
+fn handle_compact(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::compact::handle_compact(args)
+}
+
+fn handle_config(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::config::handle_config(args)
+}
+
+fn handle_dedupe(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::dedupe::handle_dedupe(args)
+}
+
+fn handle_split(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::split::handle_split(args)
+}
+
+fn handle_handoff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::handoff::handle_handoff(args)
+}
+
+fn handle_plan(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::plan::handle_plan(args)
+}
+
+fn handle_promote(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::plan::handle_promote(args)
+}
+
+fn handle_suggest_status(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::suggest_status::handle_suggest_status(args)
+}
+
+fn handle_next(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::next::handle_next(args)
+}
+
+fn handle_hooks(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::hooks::handle_hooks(args)
+}
+
+fn handle_hook(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::hooks::handle_hook(args)
+}
+
+fn handle_task(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::task::handle_task(args)
+}
+
+fn handle_milestone(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::milestone::handle_milestone(args)
+}
+
+fn handle_goal(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::goal::handle_goal(args)
+}
+
+fn handle_focus(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::focus::handle_focus(args)
+}
+
+fn handle_service(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::service::handle_service(args)
+}
+
+fn handle_sync(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::sync::handle_sync(args)
+}
+
+fn handle_convention(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::convention::handle_convention(args)
+}
+
+fn handle_project(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::project::handle_project(args)
+}
+
+fn handle_decide(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::decide::handle_decide(args)
+}
+
+fn handle_decisions(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::decide::handle_decisions(args)
+}
+
+fn handle_session(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::session::handle_session(args)
+}
+
+fn handle_history(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::history::handle_history(args)
+}
+
+fn handle_undo(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::history::handle_undo(args)
+}
+
+fn handle_diff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::diff::handle_diff(args)
+}
+
+fn handle_archive(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::archive::handle_archive(args)
+}
+
+fn handle_prune(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::archive::handle_prune(args)
+}
+
+fn handle_log_time(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::log_time::handle_log_time(args)
+}
+
+fn handle_note(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::note::handle_note(args)
+}
+
+fn handle_version(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::version::handle_version(args)
+}
+
+fn handle_why(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::why::handle_why(args)
+}
+
+fn handle_relate(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::relate::handle_relate(args)
+}
+
+fn handle_projects(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::projects::handle_projects(args)
+}
+
+fn handle_recent(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    commands::recent::handle_recent(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_global_flags() {
+        let to_args = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let (flags, rest) = split_global_flags(to_args(&["legend", "--screen-reader", "update", "--sandbox", "--diag", "json"])).unwrap();
+        assert!(flags.sandbox);
+        assert!(flags.screen_reader);
+        assert!(flags.diag_json);
+        assert_eq!(rest, vec!["legend", "update"]);
+
+        let (flags, _) = split_global_flags(to_args(&["legend", "show"])).unwrap();
+        assert_eq!(flags, GlobalFlags::default());
+
+        let (flags, rest) = split_global_flags(to_args(&["legend", "--if-initialized", "get_state"])).unwrap();
+        assert!(flags.if_initialized);
+        assert_eq!(rest, vec!["legend", "get_state"]);
+
+        assert!(split_global_flags(to_args(&["legend", "--diag", "xml", "show"])).is_err());
+
+        let (flags, rest) = split_global_flags(to_args(&["legend", "--progress", "json", "discover"])).unwrap();
+        assert_eq!(flags.progress, Some(progress::Mode::Json));
+        assert_eq!(rest, vec!["legend", "discover"]);
+        assert!(split_global_flags(to_args(&["legend", "--progress", "spinner", "discover"])).is_err());
+
+        let (flags, rest) = split_global_flags(to_args(&["legend", "--global", "update"])).unwrap();
+        assert!(flags.global);
+        assert_eq!(rest, vec!["legend", "update"]);
+        let (flags, rest) = split_global_flags(to_args(&["legend", "config", "--global", "show"])).unwrap();
+        assert!(!flags.global);
+        assert_eq!(rest, vec!["legend", "config", "--global", "show"]);
+
+        let (flags, rest) = split_global_flags(to_args(&["legend", "--dir", "/tmp/state", "show"])).unwrap();
+        assert_eq!(flags.dir, Some(PathBuf::from("/tmp/state")));
+        assert_eq!(rest, vec!["legend", "show"]);
+        assert!(split_global_flags(to_args(&["legend", "show", "--dir"])).is_err());
+
+        let (flags, rest) = split_global_flags(to_args(&["legend", "-p", "web", "get_state"])).unwrap();
+        assert_eq!(flags.project.as_deref(), Some("web"));
+        assert_eq!(rest, vec!["legend", "get_state"]);
+    }
+}
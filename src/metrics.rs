@@ -0,0 +1,128 @@
+// Metrics - opt-in, local-only command usage and latency counters
+//
+// Nothing is recorded unless the user runs `legend metrics enable`, which
+// creates .legend/metrics.json. The presence of that file *is* the opt-in:
+// no config to read, no extra cost on the hot path when disabled.
+//
+// What is stored: command names, run counts, and timings. Never feature
+// data, arguments, or paths. Nothing is ever sent over the network - the
+// file is there for the user to inspect (or share voluntarily).
+// Having no project data in it, it stays plaintext under encryption.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::storage;
+use crate::types::current_timestamp;
+
+/// File name of the local metrics store inside the Legend directory
+const METRICS_FILE_NAME: &str = "metrics.json";
+
+fn metrics_file() -> PathBuf {
+    storage::legend_path(METRICS_FILE_NAME)
+}
+
+/// Counters for a single command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    pub count: u64,
+    pub total_micros: u64,
+    pub max_micros: u64,
+    pub last_run: i64,
+}
+
+impl CommandMetrics {
+    pub fn average_millis(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total_micros as f64 / self.count as f64 / 1000.0
+        }
+    }
+}
+
+/// Everything in metrics.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Metrics {
+    pub enabled_at: i64,
+    pub commands: BTreeMap<String, CommandMetrics>,
+}
+
+impl Metrics {
+    /// Add one run of `command` taking `elapsed`
+    pub fn record(&mut self, command: &str, elapsed: Duration, now: i64) {
+        let micros = elapsed.as_micros() as u64;
+        let entry = self.commands.entry(command.to_string()).or_default();
+        entry.count += 1;
+        entry.total_micros += micros;
+        entry.max_micros = entry.max_micros.max(micros);
+        entry.last_run = now;
+    }
+}
+
+/// Is the user opted in?
+pub fn is_enabled() -> bool {
+    metrics_file().exists()
+}
+
+/// Record a command run if metrics are enabled
+///
+/// Never fails: metrics must not break a hook or a command that worked
+pub fn record_command(command: &str, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+
+    if let Ok(mut metrics) = load_metrics() {
+        metrics.record(command, elapsed, current_timestamp());
+        let _ = save_metrics(&metrics);
+    }
+}
+
+/// Load metrics.json (error if missing or unreadable)
+pub fn load_metrics() -> Result<Metrics, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(metrics_file())
+        .map_err(|e| format!("Failed to read metrics file: {}", e))?;
+    let metrics = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse metrics file: {}", e))?;
+    Ok(metrics)
+}
+
+/// Write metrics.json (creating it opts the user in)
+pub fn save_metrics(metrics: &Metrics) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(metrics)?;
+    fs::write(metrics_file(), json).map_err(|e| format!("Failed to write metrics file: {}", e))?;
+    Ok(())
+}
+
+/// Delete metrics.json (opts the user out)
+pub fn remove_metrics() -> Result<(), Box<dyn std::error::Error>> {
+    if is_enabled() {
+        fs::remove_file(metrics_file()).map_err(|e| format!("Failed to remove metrics file: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates() {
+        let mut metrics = Metrics::default();
+
+        metrics.record("get_state", Duration::from_micros(2000), 10);
+        metrics.record("get_state", Duration::from_micros(4000), 20);
+        metrics.record("update", Duration::from_micros(90000), 30);
+
+        let get_state = &metrics.commands["get_state"];
+        assert_eq!(get_state.count, 2);
+        assert_eq!(get_state.max_micros, 4000);
+        assert_eq!(get_state.last_run, 20);
+        assert!((get_state.average_millis() - 3.0).abs() < f64::EPSILON);
+        assert_eq!(metrics.commands["update"].count, 1);
+    }
+}
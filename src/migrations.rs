@@ -0,0 +1,981 @@
+// Migrations - reading state files written by older versions of Legend
+//
+// Every state file starts with a header: b"LGND" and the format version
+// as a little-endian u16, then (since v10) a CRC-32 of the LZ4 block as a
+// little-endian u32, then the LZ4 block as before. The version says
+// exactly which layout the bincode inside has, so loading decodes that
+// layout and converts it forward, instead of guessing. Files saved before
+// the header existed have none (their first bytes are the LZ4 size
+// prefix, which would have to be over 1GB to read as "LGND"); for those
+// the newest layout that decodes wins, as it always did.
+//
+// Versions:
+//   0  Legend 0.2.0: features in bincode       LegacyState<LegacyFeatureV0>
+//   1  + feature metadata                     LegacyState<LegacyFeatureV1>
+//   2  features as JSON                       LegacyStateV1
+//   3  + milestones                           LegacyStateV2
+//   4  + decisions                            LegacyStateV3
+//   5  + sessions                             LegacyStateV4
+//   6  + project block                        LegacyStateV5
+//   7  + goals                                LegacyStateV6
+//   8  + conventions                          LegacyStateV7
+//   9  + focus stack                          LegacyStateV8
+//  10  + checksum in the header               LegacyStateV8
+//  11  features in bincode again              LegendState
+//
+// Why did features go back to bincode? Parsing a JSON string per feature
+// made loading 2000 features about 4x slower (see PERFORMANCE.md), and
+// loading is the read path every hook waits on.
+//
+// Adding a top-level field to LegendState: copy the current layout here
+// as the next LegacyStateVn (From it to LegendState with the new field
+// empty), give it the current version in upgrade(), and bump
+// CURRENT_VERSION. Adding a field to Feature is the same, one level
+// down: copy Feature as the next LegacyFeatureVn, and the state layout
+// as one with `features: Vec<LegacyFeatureVn>`. Fields inside a
+// json_format list (milestones, decisions, ...) or metadata are JSON and
+// need none of this, as long as they have #[serde(default)].
+//
+// A checksum that doesn't match means the file is damaged; load_state
+// then falls back to a copy in history/ (see storage.rs).
+//
+// Loading upgrades in memory; the next save writes the current version.
+// JSON state files (storage.format = "json") carry the same version in a
+// format_version field but need no layouts: serde fills in new fields.
+// A file from a newer Legend is refused with a message saying so, rather
+// than misread.
+
+use crate::types::{Archive, ArchivedFeature, Decision, Feature, FeatureStatus, Goal, LegendState, Metadata, Milestone, Priority, ProjectInfo, Session};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+#[cfg(test)]
+use serde::Serialize;
+
+/// First bytes of every state file with a version
+const MAGIC: &[u8; 4] = b"LGND";
+
+/// Format version of the state files this build writes
+pub const CURRENT_VERSION: u16 = 11;
+
+/// First version whose header has a checksum
+const CHECKSUM_SINCE: u16 = 10;
+
+/// What a state file's header says
+#[derive(Debug, PartialEq)]
+pub struct Header {
+    pub version: u16,
+    /// CRC-32 of the LZ4 block (None before CHECKSUM_SINCE)
+    pub checksum: Option<u32>,
+}
+
+/// The header for a state file in `version` holding `block`
+pub fn header(version: u16, block: &[u8]) -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.extend_from_slice(&version.to_le_bytes());
+    if version >= CHECKSUM_SINCE {
+        header.extend_from_slice(&crc32(block).to_le_bytes());
+    }
+    header
+}
+
+/// (header, LZ4 block) of a file with a header; None for an older file
+pub fn split_header(bytes: &[u8]) -> Option<(Header, &[u8])> {
+    let rest = bytes.strip_prefix(MAGIC.as_slice())?;
+    let (version, rest) = rest.split_first_chunk::<2>()?;
+    let version = u16::from_le_bytes(*version);
+    if version < CHECKSUM_SINCE {
+        return Some((Header { version, checksum: None }, rest));
+    }
+    let (checksum, block) = rest.split_first_chunk::<4>()?;
+    let checksum = Some(u32::from_le_bytes(*checksum));
+    Some((Header { version, checksum }, block))
+}
+
+/// Check the block against the header's checksum, if it has one
+pub fn verify(header: &Header, block: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    match header.checksum {
+        Some(expected) if crc32(block) != expected => Err(format!(
+            "The state file is corrupt (checksum {:08x}, expected {:08x})",
+            crc32(block),
+            expected
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// CRC-32 lookup table (IEEE polynomial, reflected), built at compile time
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 as in zip, gzip, and PNG
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| CRC_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+/// Decode bincode in the layout of `version` and bring it up to date
+pub fn upgrade(version: u16, serialized: &[u8]) -> Result<LegendState, Box<dyn std::error::Error>> {
+    Ok(match version {
+        0 => decode::<LegacyState<LegacyFeatureV0>>(serialized)?.into(),
+        1 => decode::<LegacyState<LegacyFeatureV1>>(serialized)?.into(),
+        2 => decode::<LegacyStateV1>(serialized)?.into(),
+        3 => decode::<LegacyStateV2>(serialized)?.into(),
+        4 => decode::<LegacyStateV3>(serialized)?.into(),
+        5 => decode::<LegacyStateV4>(serialized)?.into(),
+        6 => decode::<LegacyStateV5>(serialized)?.into(),
+        7 => decode::<LegacyStateV6>(serialized)?.into(),
+        8 => decode::<LegacyStateV7>(serialized)?.into(),
+        // 10 only added the checksum
+        9 | 10 => decode::<LegacyStateV8>(serialized)?.into(),
+        CURRENT_VERSION => decode::<LegendState>(serialized)?,
+        newer => return Err(too_new(newer)),
+    })
+}
+
+/// The error for a state file from a newer Legend
+pub fn too_new(version: u16) -> Box<dyn std::error::Error> {
+    format!(
+        "The state file is format v{}, from a newer Legend (this one reads up to v{}); upgrade legend to read it",
+        version, CURRENT_VERSION
+    )
+    .into()
+}
+
+/// Version and contents of a file saved before the header: the newest
+/// layout that decodes
+pub fn detect(serialized: &[u8]) -> Result<(u16, LegendState), Box<dyn std::error::Error>> {
+    let newest = upgrade(CURRENT_VERSION, serialized);
+    newest.map(|state| (CURRENT_VERSION, state)).or_else(|err| {
+        (0..CURRENT_VERSION)
+            .rev()
+            .find_map(|version| upgrade(version, serialized).ok().map(|state| (version, state)))
+            .ok_or(err)
+    })
+}
+
+/// Archives in the layouts before features were JSON (archives have no header)
+pub fn upgrade_archive(serialized: &[u8]) -> Result<Archive, Box<dyn std::error::Error>> {
+    decode::<LegacyArchive<LegacyFeatureV1>>(serialized)
+        .map(Archive::from)
+        .or_else(|_| decode::<LegacyArchive<LegacyFeatureV0>>(serialized).map(Archive::from))
+}
+
+fn decode<T: DeserializeOwned>(serialized: &[u8]) -> Result<T, Box<dyn std::error::Error>> {
+    bincode::deserialize(serialized).map_err(|e| format!("Failed to deserialize state: {}", e).into())
+}
+
+/// State layout before features were stored as JSON (see types.rs)
+///
+/// bincode isn't self-describing, so an added field shifts everything
+/// after it; old layouts have to be spelled out to read old files
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyState<F> {
+    project_name: String,
+    features: Vec<F>,
+    created_at: i64,
+    last_updated: i64,
+}
+
+/// State layout with features as JSON, before milestones
+///
+/// State-level lists added since are JSON too (see types.rs json_format),
+/// but a new top-level field still needs a layout here and a version
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV1 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+}
+
+impl From<LegacyStateV1> for LegendState {
+    fn from(old: LegacyStateV1) -> Self {
+        LegendState::from(LegacyStateV2 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: Vec::new(),
+        })
+    }
+}
+
+/// State layout with milestones, before decisions
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV2 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+}
+
+impl From<LegacyStateV2> for LegendState {
+    fn from(old: LegacyStateV2) -> Self {
+        LegendState::from(LegacyStateV3 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: Vec::new(),
+        })
+    }
+}
+
+/// State layout with decisions, before sessions
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV3 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+    #[serde(with = "crate::types::json_format")]
+    decisions: Vec<Decision>,
+}
+
+impl From<LegacyStateV3> for LegendState {
+    fn from(old: LegacyStateV3) -> Self {
+        LegendState::from(LegacyStateV4 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: old.decisions,
+            sessions: Vec::new(),
+        })
+    }
+}
+
+/// State layout with sessions, before the project block
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV4 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+    #[serde(with = "crate::types::json_format")]
+    decisions: Vec<Decision>,
+    #[serde(with = "crate::types::json_format")]
+    sessions: Vec<Session>,
+}
+
+impl From<LegacyStateV4> for LegendState {
+    fn from(old: LegacyStateV4) -> Self {
+        LegendState::from(LegacyStateV5 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: old.decisions,
+            sessions: old.sessions,
+            project: ProjectInfo::default(),
+        })
+    }
+}
+
+/// State layout with the project block, before goals
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV5 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+    #[serde(with = "crate::types::json_format")]
+    decisions: Vec<Decision>,
+    #[serde(with = "crate::types::json_format")]
+    sessions: Vec<Session>,
+    #[serde(with = "crate::types::json_format")]
+    project: ProjectInfo,
+}
+
+impl From<LegacyStateV5> for LegendState {
+    fn from(old: LegacyStateV5) -> Self {
+        LegendState::from(LegacyStateV6 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: old.decisions,
+            sessions: old.sessions,
+            project: old.project,
+            goals: Vec::new(),
+        })
+    }
+}
+
+/// State layout with goals, before conventions
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV6 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+    #[serde(with = "crate::types::json_format")]
+    decisions: Vec<Decision>,
+    #[serde(with = "crate::types::json_format")]
+    sessions: Vec<Session>,
+    #[serde(with = "crate::types::json_format")]
+    project: ProjectInfo,
+    #[serde(with = "crate::types::json_format")]
+    goals: Vec<Goal>,
+}
+
+impl From<LegacyStateV6> for LegendState {
+    fn from(old: LegacyStateV6) -> Self {
+        LegendState::from(LegacyStateV7 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: old.decisions,
+            sessions: old.sessions,
+            project: old.project,
+            goals: old.goals,
+            conventions: Vec::new(),
+        })
+    }
+}
+
+/// State layout with conventions, before the focus stack
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV7 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+    #[serde(with = "crate::types::json_format")]
+    decisions: Vec<Decision>,
+    #[serde(with = "crate::types::json_format")]
+    sessions: Vec<Session>,
+    #[serde(with = "crate::types::json_format")]
+    project: ProjectInfo,
+    #[serde(with = "crate::types::json_format")]
+    goals: Vec<Goal>,
+    #[serde(with = "crate::types::json_format")]
+    conventions: Vec<String>,
+}
+
+impl From<LegacyStateV7> for LegendState {
+    fn from(old: LegacyStateV7) -> Self {
+        LegendState::from(LegacyStateV8 {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: old.decisions,
+            sessions: old.sessions,
+            project: old.project,
+            goals: old.goals,
+            conventions: old.conventions,
+            focus: Vec::new(),
+        })
+    }
+}
+
+/// State layout with the focus stack, the last with features as JSON
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyStateV8 {
+    project_name: String,
+    #[serde(with = "crate::types::features_format")]
+    features: Vec<Feature>,
+    created_at: i64,
+    last_updated: i64,
+    #[serde(with = "crate::types::json_format")]
+    milestones: Vec<Milestone>,
+    #[serde(with = "crate::types::json_format")]
+    decisions: Vec<Decision>,
+    #[serde(with = "crate::types::json_format")]
+    sessions: Vec<Session>,
+    #[serde(with = "crate::types::json_format")]
+    project: ProjectInfo,
+    #[serde(with = "crate::types::json_format")]
+    goals: Vec<Goal>,
+    #[serde(with = "crate::types::json_format")]
+    conventions: Vec<String>,
+    #[serde(with = "crate::types::json_format")]
+    focus: Vec<String>,
+}
+
+impl From<LegacyStateV8> for LegendState {
+    fn from(old: LegacyStateV8) -> Self {
+        LegendState {
+            project_name: old.project_name,
+            features: old.features,
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: old.milestones,
+            decisions: old.decisions,
+            sessions: old.sessions,
+            project: old.project,
+            goals: old.goals,
+            conventions: old.conventions,
+            focus: old.focus,
+        }
+    }
+}
+
+/// Feature layout of Legend 0.2.0
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyFeatureV0 {
+    id: String,
+    name: String,
+    domain: String,
+    tags: Vec<String>,
+    status: FeatureStatus,
+    description: String,
+    context: Option<String>,
+    files_involved: Vec<String>,
+    created_at: i64,
+    last_updated: i64,
+    recency_score: f64,
+}
+
+/// Feature layout once `metadata` was added, before lifecycle timestamps
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyFeatureV1 {
+    id: String,
+    name: String,
+    domain: String,
+    tags: Vec<String>,
+    status: FeatureStatus,
+    description: String,
+    context: Option<String>,
+    files_involved: Vec<String>,
+    created_at: i64,
+    last_updated: i64,
+    recency_score: f64,
+    #[serde(with = "crate::types::metadata_format")]
+    metadata: Metadata,
+}
+
+impl From<LegacyFeatureV0> for Feature {
+    fn from(f: LegacyFeatureV0) -> Self {
+        Feature {
+            id: f.id,
+            name: f.name,
+            domain: f.domain,
+            tags: f.tags,
+            status: f.status,
+            priority: Priority::Medium,
+            owner: None,
+            blocked_reason: None,
+            blocked_by: None,
+            description: f.description,
+            context: f.context,
+            files_involved: f.files_involved,
+            parent_id: None,
+            depends_on: Vec::new(),
+            relations: Vec::new(),
+            subtasks: Vec::new(),
+            notes: Vec::new(),
+            decisions: Vec::new(),
+            estimate_hours: None,
+            logged_hours: 0.0,
+            due_date: None,
+            complexity: None,
+            risk: None,
+            created_at: f.created_at,
+            last_updated: f.last_updated,
+            last_updated_by: None,
+            recency_score: f.recency_score,
+            metadata: Metadata::new(),
+            started_at: None,
+            completed_at: None,
+            planned: false,
+        }
+    }
+}
+
+impl From<LegacyFeatureV1> for Feature {
+    fn from(f: LegacyFeatureV1) -> Self {
+        let metadata = f.metadata;
+        let mut feature = Feature::from(LegacyFeatureV0 {
+            id: f.id,
+            name: f.name,
+            domain: f.domain,
+            tags: f.tags,
+            status: f.status,
+            description: f.description,
+            context: f.context,
+            files_involved: f.files_involved,
+            created_at: f.created_at,
+            last_updated: f.last_updated,
+            recency_score: f.recency_score,
+        });
+        feature.metadata = metadata;
+        feature
+    }
+}
+
+impl<F: Into<Feature>> From<LegacyState<F>> for LegendState {
+    fn from(old: LegacyState<F>) -> Self {
+        LegendState {
+            project_name: old.project_name,
+            features: old.features.into_iter().map(Into::into).collect(),
+            created_at: old.created_at,
+            last_updated: old.last_updated,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo::default(),
+            goals: Vec::new(),
+            conventions: Vec::new(),
+            focus: Vec::new(),
+        }
+    }
+}
+
+/// Archive layout before features were stored as JSON
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyArchive<F> {
+    features: Vec<LegacyArchivedFeature<F>>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyArchivedFeature<F> {
+    archived_at: i64,
+    feature: F,
+}
+
+impl<F: Into<Feature>> From<LegacyArchive<F>> for Archive {
+    fn from(old: LegacyArchive<F>) -> Self {
+        Archive {
+            features: old
+                .features
+                .into_iter()
+                .map(|a| ArchivedFeature {
+                    archived_at: a.archived_at,
+                    feature: a.feature.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage;
+    use crate::testing::TestEnv;
+    use std::fs;
+    use std::path::Path;
+
+    /// A state or archive file as an older Legend wrote it: no header
+    fn write_legacy<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+        let compressed = lz4::block::compress(&bincode::serialize(value)?, None, true)?;
+        fs::write(path, compressed)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_legacy_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyState {
+            project_name: "Old".to_string(),
+            features: vec![LegacyFeatureV0 {
+                id: "auth".to_string(),
+                name: "Auth".to_string(),
+                domain: "security".to_string(),
+                tags: vec!["backend".to_string()],
+                status: FeatureStatus::Complete,
+                description: "Login".to_string(),
+                context: None,
+                files_involved: vec!["src/auth.rs".to_string()],
+                created_at: 1,
+                last_updated: 2,
+                recency_score: 0.5,
+            }],
+            created_at: 1,
+            last_updated: 2,
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.project_name, "Old");
+        assert_eq!(loaded.features[0].status, FeatureStatus::Complete);
+        assert_eq!(loaded.features[0].files_involved, vec!["src/auth.rs"]);
+        assert!(loaded.features[0].metadata.is_empty());
+    }
+
+    #[test]
+    fn test_load_metadata_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v1-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut metadata = Metadata::new();
+        metadata.insert("tier".to_string(), serde_json::json!(1));
+        let old = LegacyState {
+            project_name: "Old".to_string(),
+            features: vec![LegacyFeatureV1 {
+                id: "auth".to_string(),
+                name: "Auth".to_string(),
+                domain: "security".to_string(),
+                tags: Vec::new(),
+                status: FeatureStatus::InProgress,
+                description: "Login".to_string(),
+                context: Some("why".to_string()),
+                files_involved: Vec::new(),
+                created_at: 1,
+                last_updated: 2,
+                recency_score: 0.5,
+                metadata,
+            }],
+            created_at: 1,
+            last_updated: 2,
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.features[0].context.as_deref(), Some("why"));
+        assert_eq!(loaded.features[0].metadata["tier"], serde_json::json!(1));
+        assert_eq!(loaded.features[0].started_at, None);
+    }
+
+    #[test]
+    fn test_load_pre_milestone_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v2-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV1 {
+            project_name: "Old".to_string(),
+            features: vec![Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "Login".to_string())],
+            created_at: 1,
+            last_updated: 2,
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.features[0].id, "auth");
+        assert!(loaded.milestones.is_empty());
+    }
+
+    #[test]
+    fn test_load_pre_decision_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v3-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV2 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: vec![Milestone {
+                id: "launch".to_string(),
+                name: "Launch".to_string(),
+                due: None,
+                feature_ids: Vec::new(),
+                created_at: 1,
+            }],
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.milestones[0].id, "launch");
+        assert!(loaded.decisions.is_empty());
+    }
+
+    #[test]
+    fn test_load_pre_session_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v4-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV3 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: vec![Decision {
+                id: 1,
+                title: "Use bincode".to_string(),
+                rationale: "Fast reads".to_string(),
+                feature_ids: Vec::new(),
+                decided_at: 1,
+            }],
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.decisions[0].title, "Use bincode");
+        assert!(loaded.sessions.is_empty());
+    }
+
+    #[test]
+    fn test_load_pre_project_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v5-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV4 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: vec![Session {
+                id: 1,
+                started_at: 1,
+                ended_at: None,
+                summary: None,
+                features_touched: Vec::new(),
+            }],
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.sessions[0].id, 1);
+        assert!(loaded.project.is_empty());
+    }
+
+    #[test]
+    fn test_load_pre_goal_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v6-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV5 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo {
+                tech_stack: vec!["Rust".to_string()],
+                ..ProjectInfo::default()
+            },
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.project.tech_stack, vec!["Rust"]);
+        assert!(loaded.goals.is_empty());
+    }
+
+    #[test]
+    fn test_load_pre_convention_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v7-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV6 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo::default(),
+            goals: vec![Goal {
+                id: "signup".to_string(),
+                title: "Self-serve signup".to_string(),
+                target_date: None,
+                feature_ids: Vec::new(),
+                created_at: 1,
+            }],
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.goals[0].id, "signup");
+        assert!(loaded.conventions.is_empty());
+    }
+
+    #[test]
+    fn test_load_pre_focus_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v8-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let old = LegacyStateV7 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo::default(),
+            goals: Vec::new(),
+            conventions: vec!["no unwrap in prod code".to_string()],
+        };
+        write_legacy(&dir.join("state.lz4"), &old).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.conventions, vec!["no unwrap in prod code"]);
+        assert!(loaded.focus.is_empty());
+    }
+
+    #[test]
+    fn test_load_features_as_json_layout() {
+        let dir = std::env::temp_dir().join(format!("legend-legacy-v10-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut feature = Feature::new("auth".to_string(), "Auth".to_string(), "security".to_string(), "Login".to_string());
+        feature.started_at = Some(5);
+        let old = LegacyStateV8 {
+            project_name: "Old".to_string(),
+            features: vec![feature],
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo::default(),
+            goals: Vec::new(),
+            conventions: Vec::new(),
+            focus: vec!["auth".to_string()],
+        };
+        let block = lz4::block::compress(&bincode::serialize(&old).unwrap(), None, true).unwrap();
+        fs::write(dir.join("state.lz4"), [header(10, &block), block].concat()).unwrap();
+
+        let loaded = storage::load_state_in(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.features[0].started_at, Some(5));
+        assert_eq!(loaded.focus, vec!["auth"]);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+
+        let block = b"lz4 block";
+        let bytes = [header(CURRENT_VERSION, block), block.to_vec()].concat();
+        let (parsed, rest) = split_header(&bytes).unwrap();
+        assert_eq!(rest, block);
+        assert!(verify(&parsed, rest).is_ok());
+        assert!(verify(&parsed, b"lz4 blocK").is_err());
+    }
+
+    #[test]
+    fn test_versioned_header() {
+        let _env = TestEnv::new(1_700_000_000);
+        let old = LegacyStateV7 {
+            project_name: "Old".to_string(),
+            features: Vec::new(),
+            created_at: 1,
+            last_updated: 2,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo::default(),
+            goals: Vec::new(),
+            conventions: vec!["no unwrap in prod code".to_string()],
+        };
+        let block = lz4::block::compress(&bincode::serialize(&old).unwrap(), None, true).unwrap();
+        fs::write(storage::state_file(), [header(8, &block), block.clone()].concat()).unwrap();
+        assert_eq!(storage::state_format_version().unwrap(), 8);
+
+        // Saving writes the current version
+        let state = storage::load_state().unwrap();
+        assert_eq!(state.conventions, vec!["no unwrap in prod code"]);
+        storage::save_state(&state).unwrap();
+        let bytes = fs::read(storage::state_file()).unwrap();
+        assert!(bytes.starts_with(b"LGND"));
+        assert_eq!(split_header(&bytes).map(|(h, _)| h.version), Some(CURRENT_VERSION));
+
+        fs::write(storage::state_file(), [header(CURRENT_VERSION + 1, &block), block].concat()).unwrap();
+        let err = storage::load_state().unwrap_err().to_string();
+        assert!(err.contains("newer Legend"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_legacy_archive() {
+        let _env = TestEnv::new(0);
+        let old = LegacyArchive {
+            features: vec![LegacyArchivedFeature {
+                archived_at: 7,
+                feature: LegacyFeatureV1 {
+                    id: "old".to_string(),
+                    name: "Old".to_string(),
+                    domain: "d".to_string(),
+                    tags: vec![],
+                    status: FeatureStatus::Complete,
+                    description: "Archived before JSON features".to_string(),
+                    context: None,
+                    files_involved: vec![],
+                    created_at: 1,
+                    last_updated: 2,
+                    recency_score: 0.5,
+                    metadata: Metadata::new(),
+                },
+            }],
+        };
+        write_legacy(&storage::archive_file(), &old).unwrap();
+
+        let archive = storage::load_archive().unwrap();
+        assert_eq!(archive.features[0].archived_at, 7);
+        assert_eq!(archive.features[0].feature.id, "old");
+    }
+}
@@ -0,0 +1,113 @@
+// Output mode - table layouts or screen-reader-friendly lines
+//
+// `legend --screen-reader <command>` (or LEGEND_SCREEN_READER=1) makes the
+// human-readable views drop column layouts, rules, and shading glyphs in
+// favor of one labeled sentence per item, e.g.
+//
+//   Feature auth: status InProgress, domain security, recency 84 percent, name Authentication.
+//
+// Screen-reader mode also turns color off. JSON output is unaffected.
+//
+// Diagnostics - everything on stderr that isn't the command's primary
+// output (timings, warnings, counts, errors) - go through diag() below.
+// `legend --diag json <command>` (or LEGEND_DIAG=json) prints each one as
+// a single-line JSON record, so hook wrappers can parse them instead of
+// guessing:
+//
+//   {"level":"info","message":"Found 2 matching feature(s).","count":2}
+//   {"level":"warning","message":"get_state took 7ms (target: <5ms)","total_ms":7}
+//   {"level":"error","message":"Feature 'x' not found","exit_code":1}
+//
+// Interactive prompts (show --interactive, merge conflicts) stay text.
+
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SCREEN_READER: AtomicBool = AtomicBool::new(false);
+
+static DIAG_JSON: AtomicBool = AtomicBool::new(false);
+
+/// Severity of a diagnostic record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Turn screen-reader mode on for the rest of the process (--screen-reader)
+pub fn set_screen_reader(enabled: bool) {
+    SCREEN_READER.store(enabled, Ordering::Relaxed);
+}
+
+/// Should views print linear labeled lines instead of tables?
+pub fn screen_reader() -> bool {
+    SCREEN_READER.load(Ordering::Relaxed)
+        || std::env::var("LEGEND_SCREEN_READER").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Emit diagnostics as JSON records for the rest of the process (--diag json)
+pub fn set_diag_json(enabled: bool) {
+    DIAG_JSON.store(enabled, Ordering::Relaxed);
+}
+
+/// Are diagnostics JSON records rather than text?
+pub fn diag_json() -> bool {
+    DIAG_JSON.load(Ordering::Relaxed) || std::env::var("LEGEND_DIAG").is_ok_and(|v| v == "json")
+}
+
+/// Print a diagnostic to stderr; `fields` (a JSON object, or null) go
+/// alongside the message in JSON mode and are dropped in text mode
+pub fn diag(level: Level, message: &str, fields: Value) {
+    eprintln!("{}", render_diag(level, message, fields, diag_json()));
+}
+
+/// An informational line (progress, counts, summaries)
+pub fn info(message: &str) {
+    diag(Level::Info, message, Value::Null);
+}
+
+/// Something the user should look at, though the command succeeded
+pub fn warning(message: &str) {
+    diag(Level::Warning, message, Value::Null);
+}
+
+fn render_diag(level: Level, message: &str, fields: Value, as_json: bool) -> String {
+    if !as_json {
+        return match level {
+            Level::Info => message.to_string(),
+            Level::Warning => format!("⚠️  {}", message),
+            Level::Error => format!("Error: {}", message),
+        };
+    }
+
+    let level = match level {
+        Level::Info => "info",
+        Level::Warning => "warning",
+        Level::Error => "error",
+    };
+    let mut record = json!({"level": level, "message": message});
+    if let (Some(record), Value::Object(fields)) = (record.as_object_mut(), fields) {
+        record.extend(fields);
+    }
+    record.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_diag() {
+        assert_eq!(render_diag(Level::Info, "Found 2", json!({"count": 2}), false), "Found 2");
+        assert_eq!(render_diag(Level::Warning, "slow", Value::Null, false), "⚠️  slow");
+        assert_eq!(
+            render_diag(Level::Info, "Found 2\nmore", json!({"count": 2}), true),
+            r#"{"count":2,"level":"info","message":"Found 2\nmore"}"#
+        );
+        assert_eq!(
+            render_diag(Level::Error, "boom", json!({"exit_code": 3}), true),
+            r#"{"exit_code":3,"level":"error","message":"boom"}"#
+        );
+    }
+}
@@ -0,0 +1,198 @@
+// Progress - reporting on long operations (discover, analysis, import)
+//
+// `legend --progress json <command>` (or LEGEND_PROGRESS=json) prints one
+// JSON record per update on stderr, for wrappers and editor plugins that
+// draw their own progress UI:
+//
+//   {"level":"progress","task":"discover","done":120,"total":null,"percent":null,"item":"src/auth/mod.rs"}
+//   {"level":"progress","task":"analyze","done":40,"total":160,"percent":25,"item":"src/db.rs"}
+//
+// A task ends with a record whose "finished" is true. `--progress bar`
+// draws a one-line bar instead, which is the default when stderr is a
+// terminal; `--progress off`, and stderr going anywhere else (hooks, CI),
+// prints nothing. Records are throttled, so a fast run emits few or none
+// besides the last.
+
+use crate::output;
+use crate::text;
+use serde_json::json;
+use std::cell::Cell;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+
+/// How progress is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Off,
+    Bar,
+    Json,
+}
+
+impl Mode {
+    pub fn parse(value: &str) -> Option<Mode> {
+        match value {
+            "off" => Some(Mode::Off),
+            "bar" => Some(Mode::Bar),
+            "json" => Some(Mode::Json),
+            _ => None,
+        }
+    }
+}
+
+// 0 = not set on the command line
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Time between two updates of the same task
+const INTERVAL: Duration = Duration::from_millis(100);
+
+/// Columns the bar line may use
+const BAR_LINE_WIDTH: usize = 78;
+
+/// Set the progress mode for the rest of the process (--progress)
+pub fn set_mode(mode: Mode) {
+    MODE.store(mode as u8 + 1, Ordering::Relaxed);
+}
+
+/// The flag, else LEGEND_PROGRESS, else a bar on a terminal
+pub fn mode() -> Mode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => return Mode::Off,
+        2 => return Mode::Bar,
+        3 => return Mode::Json,
+        _ => {}
+    }
+    if let Some(mode) = std::env::var("LEGEND_PROGRESS").ok().as_deref().and_then(Mode::parse) {
+        return mode;
+    }
+    // A redrawn line is noise to a screen reader
+    if std::io::stderr().is_terminal() && !output::screen_reader() {
+        Mode::Bar
+    } else {
+        Mode::Off
+    }
+}
+
+/// Progress of one task; `tick` as items are handled, `finish` at the end
+///
+/// Takes `&self` so it can be ticked from inside `Fn` closures
+pub struct Progress {
+    task: &'static str,
+    total: Option<usize>,
+    mode: Mode,
+    done: Cell<usize>,
+    last: Cell<Option<Instant>>,
+}
+
+impl Progress {
+    /// Start `task`; `total` is the item count, if known up front
+    pub fn start(task: &'static str, total: Option<usize>) -> Self {
+        Progress { task, total, mode: mode(), done: Cell::new(0), last: Cell::new(None) }
+    }
+
+    /// One more item done; `item` is what was just handled
+    pub fn tick(&self, item: &str) {
+        self.done.set(self.done.get() + 1);
+        if self.mode == Mode::Off {
+            return;
+        }
+        let now = Instant::now();
+        if self.last.get().is_some_and(|last| now - last < INTERVAL) {
+            return;
+        }
+        self.last.set(Some(now));
+        self.emit(item, false);
+    }
+
+    /// The task is done: the last record, or clear the bar
+    pub fn finish(self) {
+        self.emit("", true);
+    }
+
+    fn emit(&self, item: &str, finished: bool) {
+        let done = self.done.get();
+        let mut stderr = std::io::stderr();
+        match self.mode {
+            Mode::Off => {}
+            Mode::Json => {
+                let _ = writeln!(stderr, "{}", render_event(self.task, done, self.total, item, finished));
+            }
+            // Redraw in place; erase the line once finished
+            Mode::Bar if finished => {
+                let _ = write!(stderr, "\r\x1b[2K");
+            }
+            Mode::Bar => {
+                let _ = write!(stderr, "\r\x1b[2K{}", render_bar(self.task, done, self.total, item));
+            }
+        }
+        let _ = stderr.flush();
+    }
+}
+
+fn percent(done: usize, total: Option<usize>) -> Option<usize> {
+    total.map(|total| (done * 100).checked_div(total).map_or(100, |percent| percent.min(100)))
+}
+
+fn render_event(task: &str, done: usize, total: Option<usize>, item: &str, finished: bool) -> String {
+    let mut record = json!({
+        "level": "progress",
+        "task": task,
+        "done": done,
+        "total": total,
+        "percent": percent(done, total),
+    });
+    if finished {
+        record["finished"] = json!(true);
+    } else {
+        record["item"] = json!(item);
+    }
+    record.to_string()
+}
+
+/// `analyze [######--------------]  30% 48/160 src/db.rs`, or without a
+/// total `discover 1200 src/auth/mod.rs`
+fn render_bar(task: &str, done: usize, total: Option<usize>, item: &str) -> String {
+    const BAR_WIDTH: usize = 20;
+    let head = match (total, percent(done, total)) {
+        (Some(total), Some(percent)) => {
+            let filled = percent * BAR_WIDTH / 100;
+            format!(
+                "{} [{}{}] {:>3}% {}/{}",
+                task,
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled),
+                percent,
+                done,
+                total
+            )
+        }
+        _ => format!("{} {}", task, done),
+    };
+    let room = BAR_LINE_WIDTH.saturating_sub(text::width(&head) + 1);
+    format!("{} {}", head, text::truncate(item, room, ".."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_event() {
+        assert_eq!(
+            render_event("analyze", 40, Some(160), "src/db.rs", false),
+            r#"{"done":40,"item":"src/db.rs","level":"progress","percent":25,"task":"analyze","total":160}"#
+        );
+        assert_eq!(
+            render_event("discover", 7, None, "", true),
+            r#"{"done":7,"finished":true,"level":"progress","percent":null,"task":"discover","total":null}"#
+        );
+    }
+
+    #[test]
+    fn test_render_bar() {
+        assert_eq!(render_bar("analyze", 48, Some(160), "src/db.rs"), "analyze [######--------------]  30% 48/160 src/db.rs");
+        assert_eq!(render_bar("discover", 1200, None, "src/auth/mod.rs"), "discover 1200 src/auth/mod.rs");
+        let long = "a/".repeat(60);
+        assert_eq!(text::width(&render_bar("discover", 1, None, &long)), BAR_LINE_WIDTH);
+    }
+}
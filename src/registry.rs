@@ -0,0 +1,213 @@
+// Registry - every Legend project on this machine
+//
+// ~/.legend/projects.json lists each project `legend init` has set up,
+// by name (its folder's name) and Legend directory. `legend projects`
+// reads it to show them all with their feature counts and last activity,
+// and `legend -p <name> <command>` runs a command against one from
+// anywhere, like --dir with a name instead of a path.
+//
+// Only the location is stored: counts and activity are read from each
+// project's own state when listed, so they are never stale. Listing also
+// prunes entries whose Legend directory is gone (`prune`).
+
+use crate::global;
+use crate::storage;
+use crate::types::current_timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the registry inside ~/.legend
+pub const REGISTRY_FILE_NAME: &str = "projects.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredProject {
+    pub name: String,
+    /// The Legend directory (absolute), not the project folder
+    pub dir: PathBuf,
+    pub registered_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default)]
+    pub projects: Vec<RegisteredProject>,
+}
+
+/// ~/.legend/projects.json; None without a home directory
+pub fn registry_file() -> Option<PathBuf> {
+    global::global_dir().map(|dir| dir.join(REGISTRY_FILE_NAME))
+}
+
+/// The registry; empty if there is none yet
+pub fn load() -> Result<Registry, Box<dyn std::error::Error>> {
+    match registry_file() {
+        Some(path) => load_from(&path),
+        None => Ok(Registry::default()),
+    }
+}
+
+fn load_from(path: &Path) -> Result<Registry, Box<dyn std::error::Error>> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Failed to read {}: {}", path.display(), e).into()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e).into()),
+    }
+}
+
+fn save_to(path: &Path, registry: &Registry) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(registry)?;
+    storage::write_atomic(path, format!("{}\n", json).as_bytes())
+}
+
+/// Add the Legend directory `dir` to the registry; returns its name
+pub fn register(dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let path = registry_file().ok_or("No home directory: set HOME to register projects")?;
+    register_in(&path, dir)
+}
+
+/// Register in the file at `path`
+///
+/// A directory already listed keeps its name. Otherwise the name is the
+/// project folder's (the Legend directory's own for one kept elsewhere
+/// with --dir), with -2, -3, ... if another project has it
+fn register_in(path: &Path, dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let dir = fs::canonicalize(dir).map_err(|e| format!("Failed to resolve {}: {}", dir.display(), e))?;
+    let mut registry = load_from(path)?;
+    if let Some(existing) = registry.projects.iter().find(|p| p.dir == dir) {
+        return Ok(existing.name.clone());
+    }
+
+    let folder = if dir.file_name().is_some_and(|name| name == ".legend") {
+        dir.parent().unwrap_or(&dir)
+    } else {
+        &dir
+    };
+    let base = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    let mut name = base.clone();
+    let mut n = 2;
+    while registry.projects.iter().any(|p| p.name == name) {
+        name = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    registry.projects.push(RegisteredProject {
+        name: name.clone(),
+        dir,
+        registered_at: current_timestamp(),
+    });
+    save_to(path, &registry)?;
+    Ok(name)
+}
+
+/// Take `name` out of the registry; false if it wasn't there
+pub fn forget(name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let path = registry_file().ok_or("No home directory: set HOME to register projects")?;
+    let mut registry = load_from(&path)?;
+    let before = registry.projects.len();
+    registry.projects.retain(|p| p.name != name);
+    if registry.projects.len() == before {
+        return Ok(false);
+    }
+    save_to(&path, &registry)?;
+    Ok(true)
+}
+
+/// Drop every project whose Legend directory no longer exists; returns
+/// the dropped ones
+pub fn prune() -> Result<Vec<RegisteredProject>, Box<dyn std::error::Error>> {
+    match registry_file() {
+        Some(path) => prune_in(&path),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn prune_in(path: &Path) -> Result<Vec<RegisteredProject>, Box<dyn std::error::Error>> {
+    let mut registry = load_from(path)?;
+    let (kept, gone): (Vec<_>, Vec<_>) = registry.projects.into_iter().partition(|p| p.dir.is_dir());
+    registry.projects = kept;
+    if !gone.is_empty() {
+        save_to(path, &registry)?;
+    }
+    Ok(gone)
+}
+
+/// The Legend directory registered as `name` (for `-p <name>`)
+pub fn resolve(name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let registry = load()?;
+    resolve_in(&registry, name)
+}
+
+fn resolve_in(registry: &Registry, name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if let Some(project) = registry.projects.iter().find(|p| p.name == name) {
+        return Ok(project.dir.clone());
+    }
+    let known: Vec<&str> = registry.projects.iter().map(|p| p.name.as_str()).collect();
+    let hint = if known.is_empty() {
+        "no projects are registered yet; `legend init` registers one".to_string()
+    } else {
+        format!("known: {}", known.join(", "))
+    };
+    Err(format!("No project named '{}' ({})", name, hint).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_resolve() {
+        let root = std::env::temp_dir().join(format!("legend-registry-test-{}", std::process::id()));
+        let web = root.join("work").join("web").join(".legend");
+        let other_web = root.join("home").join("web").join(".legend");
+        let elsewhere = root.join("state").join("api");
+        for dir in [&web, &other_web, &elsewhere] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let file = root.join("projects.json");
+
+        let names: Vec<String> = [&web, &other_web, &elsewhere, &web]
+            .iter()
+            .map(|dir| register_in(&file, dir).unwrap())
+            .collect();
+        let registry = load_from(&file).unwrap();
+        let resolved = resolve_in(&registry, "web-2").unwrap();
+        let missing = resolve_in(&registry, "nope").unwrap_err().to_string();
+        let canonical = fs::canonicalize(&other_web).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(names, vec!["web", "web-2", "api", "web"]);
+        assert_eq!(registry.projects.len(), 3);
+        assert_eq!(resolved, canonical);
+        assert!(missing.contains("known: web, web-2, api"), "{}", missing);
+    }
+
+    #[test]
+    fn test_prune() {
+        let root = std::env::temp_dir().join(format!("legend-registry-prune-test-{}", std::process::id()));
+        let kept = root.join("kept").join(".legend");
+        let deleted = root.join("deleted").join(".legend");
+        for dir in [&kept, &deleted] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let file = root.join("projects.json");
+        register_in(&file, &kept).unwrap();
+        register_in(&file, &deleted).unwrap();
+        fs::remove_dir_all(&deleted).unwrap();
+
+        let gone = prune_in(&file).unwrap();
+        let again = prune_in(&file).unwrap();
+        let registry = load_from(&file).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(gone.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["deleted"]);
+        assert!(again.is_empty());
+        assert_eq!(registry.projects.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["kept"]);
+    }
+}
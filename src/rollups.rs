@@ -0,0 +1,259 @@
+// Rollups - per-domain summaries kept next to the state
+//
+// .legend/rollups.json holds, for each domain: how many features it has,
+// how many per status, when one last changed, and its top few features.
+// `show --by domain`, the summary in stats, and the `domains` section of
+// get_state read these instead of walking every feature.
+//
+// Every save_state refreshes the file incrementally. Besides the rollups
+// it remembers the fields they are built from for each feature; the next
+// save compares against that, and only domains where a feature was added,
+// removed, moved, or changed are rebuilt.
+//
+// A rollup file is only trusted for the state it was written with (same
+// last_updated). Anything else - an older .legend, a state restored by
+// hand, `--at` - gets rollups built in memory, so readers never see a
+// stale summary.
+//
+// With encryption on (see encryption.rs) there is no rollups file: it
+// would be plaintext next to the encrypted state, so the rollups are
+// built in memory every time instead.
+
+use crate::encryption;
+use crate::storage;
+use crate::types::{Feature, FeatureStatus, LegendState, Priority};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// File name of the rollups inside the Legend directory
+pub const ROLLUPS_FILE_NAME: &str = "rollups.json";
+
+/// Features listed in a rollup's `top_features`
+const TOP_FEATURES: usize = 3;
+
+/// Summary of one domain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainRollup {
+    pub features: usize,
+    /// Status name -> feature count
+    pub by_status: BTreeMap<String, usize>,
+    /// Newest last_updated among the domain's features
+    pub last_updated: i64,
+    /// Highest priority first, then most recently updated
+    pub top_features: Vec<String>,
+}
+
+impl DomainRollup {
+    pub fn count(&self, status: &FeatureStatus) -> usize {
+        self.by_status.get(status.name()).copied().unwrap_or(0)
+    }
+}
+
+/// What a rollup knows about one feature; a change here dirties its domain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Seen {
+    domain: String,
+    status: FeatureStatus,
+    priority: Priority,
+    last_updated: i64,
+}
+
+impl Seen {
+    fn of(feature: &Feature) -> Self {
+        Seen {
+            domain: feature.domain.clone(),
+            status: feature.status.clone(),
+            priority: feature.priority,
+            last_updated: feature.last_updated,
+        }
+    }
+}
+
+/// All domains' rollups, plus what the next refresh compares against
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rollups {
+    /// last_updated of the state these were refreshed from
+    pub state_last_updated: i64,
+    pub domains: BTreeMap<String, DomainRollup>,
+    /// Feature ID -> the fields the rollups were built from
+    #[serde(default)]
+    seen: BTreeMap<String, Seen>,
+}
+
+impl Rollups {
+    /// Rollups for `state` from scratch
+    pub fn build(state: &LegendState) -> Self {
+        let mut rollups = Rollups::default();
+        rollups.refresh(state);
+        rollups
+    }
+
+    /// Bring the rollups up to date with `state`, rebuilding only the
+    /// domains whose features changed; returns how many were rebuilt
+    pub fn refresh(&mut self, state: &LegendState) -> usize {
+        let current: BTreeMap<String, Seen> = state.features.iter().map(|f| (f.id.clone(), Seen::of(f))).collect();
+
+        let mut dirty: BTreeSet<&str> = BTreeSet::new();
+        for (id, seen) in &current {
+            match self.seen.get(id) {
+                Some(old) if old == seen => {}
+                Some(old) => {
+                    dirty.insert(&old.domain);
+                    dirty.insert(&seen.domain);
+                }
+                None => {
+                    dirty.insert(&seen.domain);
+                }
+            }
+        }
+        for (id, old) in &self.seen {
+            if !current.contains_key(id) {
+                dirty.insert(&old.domain);
+            }
+        }
+
+        for domain in &dirty {
+            match build_domain(state.features.iter().filter(|f| f.domain == *domain)) {
+                Some(rollup) => self.domains.insert(domain.to_string(), rollup),
+                None => self.domains.remove(*domain),
+            };
+        }
+        let rebuilt = dirty.len();
+        self.seen = current;
+        self.state_last_updated = state.last_updated;
+        rebuilt
+    }
+}
+
+/// Rollup of one domain's features; None when there are none
+fn build_domain<'a>(features: impl Iterator<Item = &'a Feature>) -> Option<DomainRollup> {
+    let features: Vec<&Feature> = features.collect();
+    if features.is_empty() {
+        return None;
+    }
+    let mut by_status = BTreeMap::new();
+    for feature in &features {
+        *by_status.entry(feature.status.name().to_string()).or_insert(0) += 1;
+    }
+    let mut ranked = features.clone();
+    ranked.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then(b.last_updated.cmp(&a.last_updated))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Some(DomainRollup {
+        features: features.len(),
+        by_status,
+        last_updated: features.iter().map(|f| f.last_updated).max().unwrap_or(0),
+        top_features: ranked.iter().take(TOP_FEATURES).map(|f| f.id.clone()).collect(),
+    })
+}
+
+/// Refresh the rollups file in `dir` for a state just saved there
+///
+/// Called by save_state after it writes
+pub fn update_in(dir: &Path, state: &LegendState) -> Result<(), Box<dyn std::error::Error>> {
+    let path = dir.join(ROLLUPS_FILE_NAME);
+    if encryption::is_enabled()? {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", path.display(), e).into())
+            }
+            _ => Ok(()),
+        };
+    }
+    let mut rollups = read_in(dir).unwrap_or_default();
+    rollups.refresh(state);
+    storage::write_atomic(&path, serde_json::to_string(&rollups)?.as_bytes())
+}
+
+/// The stored rollups if they match `state`, else ones built from it
+pub fn for_state(dir: &Path, state: &LegendState) -> Rollups {
+    read_in(dir)
+        .filter(|r| r.state_last_updated == state.last_updated)
+        .unwrap_or_else(|| Rollups::build(state))
+}
+
+/// Rollups file in a Legend directory; None if missing or unreadable
+fn read_in(dir: &Path) -> Option<Rollups> {
+    let content = fs::read_to_string(dir.join(ROLLUPS_FILE_NAME)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+
+    fn feature(id: &str, domain: &str, status: FeatureStatus, last_updated: i64) -> Feature {
+        let mut f = Feature::new(id.to_string(), id.to_string(), domain.to_string(), "d".to_string());
+        f.status = status;
+        f.last_updated = last_updated;
+        f
+    }
+
+    #[test]
+    fn test_build() {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features = vec![
+            feature("login", "auth", FeatureStatus::Complete, 100),
+            feature("oauth", "auth", FeatureStatus::InProgress, 300),
+            feature("sso", "auth", FeatureStatus::Pending, 200),
+            feature("mfa", "auth", FeatureStatus::Pending, 50),
+            feature("invoices", "billing", FeatureStatus::Blocked, 400),
+        ];
+        state.features[3].priority = Priority::Critical;
+
+        let rollups = Rollups::build(&state);
+        let auth = &rollups.domains["auth"];
+        assert_eq!(auth.features, 4);
+        assert_eq!(auth.count(&FeatureStatus::Pending), 2);
+        assert_eq!(auth.count(&FeatureStatus::Blocked), 0);
+        assert_eq!(auth.last_updated, 300);
+        assert_eq!(auth.top_features, vec!["mfa", "oauth", "sso"]);
+        assert_eq!(rollups.domains["billing"].count(&FeatureStatus::Blocked), 1);
+    }
+
+    #[test]
+    fn test_refresh_rebuilds_only_changed_domains() {
+        let mut state = LegendState::new("Demo".to_string());
+        state.features = vec![
+            feature("login", "auth", FeatureStatus::Pending, 100),
+            feature("invoices", "billing", FeatureStatus::Pending, 100),
+        ];
+        let mut rollups = Rollups::build(&state);
+        assert_eq!(rollups.refresh(&state), 0);
+
+        state.features[0].status = FeatureStatus::Complete;
+        assert_eq!(rollups.refresh(&state), 1);
+        assert_eq!(rollups.domains["auth"].count(&FeatureStatus::Complete), 1);
+
+        // Moving a feature dirties both domains; an emptied domain goes away
+        state.features[1].domain = "auth".to_string();
+        assert_eq!(rollups.refresh(&state), 2);
+        assert_eq!(rollups.domains.keys().collect::<Vec<_>>(), vec!["auth"]);
+        assert_eq!(rollups, Rollups::build(&state));
+
+        state.features.remove(0);
+        rollups.refresh(&state);
+        assert_eq!(rollups, Rollups::build(&state));
+    }
+
+    #[test]
+    fn test_no_rollups_file_when_encrypted() {
+        let _env = TestEnv::initialized(1_000);
+        let dir = storage::legend_dir();
+        let mut state = storage::load_state().unwrap();
+        state.features.push(feature("login", "auth", FeatureStatus::Pending, 100));
+        update_in(&dir, &state).unwrap();
+        assert!(dir.join(ROLLUPS_FILE_NAME).exists());
+
+        crate::config::set_project_value("encryption.recipients", "age1abc").unwrap();
+        update_in(&dir, &state).unwrap();
+        assert!(!dir.join(ROLLUPS_FILE_NAME).exists());
+        assert_eq!(for_state(&dir, &state), Rollups::build(&state));
+    }
+}
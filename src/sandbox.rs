@@ -0,0 +1,197 @@
+// Sandbox - run a command against a throwaway copy of .legend
+//
+// `legend --sandbox <command> ...` copies .legend to a temp directory,
+// points storage at the copy, runs the command, then prints what the
+// command *would* have changed. The real .legend is never written.
+//
+// Useful for trying out new hooks or automations without risking the
+// project's memory.
+//
+// Only .legend is copied, so commands that write anywhere else are refused
+// up front (`check`): init and `hooks upgrade` (.claude/settings.json and
+// the project registry), docs export, `config --global` edits, `projects
+// forget`, and copy (another project's .legend). Webhooks aren't sent
+// while a sandbox is active (`is_active`).
+
+use crate::diff;
+use crate::exit::{self, ExitError};
+use crate::journal::Sections;
+use crate::output;
+use crate::storage;
+use crate::types::LegendState;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether this process runs under --sandbox
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Refuse commands whose writes would land outside .legend, then mark the
+/// sandbox active; called before anything else touches the disk
+pub fn check(command: &str, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(what) = writes_outside(command, args) {
+        return Err(ExitError::new(
+            exit::USAGE,
+            format!("--sandbox only covers .legend, and `legend {}` also writes {}; run it without --sandbox", command, what),
+        )
+        .into());
+    }
+    ACTIVE.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// What a command writes outside .legend, if anything
+fn writes_outside(command: &str, args: &[String]) -> Option<&'static str> {
+    let has = |flag: &str| args.iter().any(|a| a == flag);
+    let sub = args.first().map(String::as_str);
+    match command {
+        "init" => Some(".claude/settings.json and the project registry"),
+        "hooks" if sub == Some("upgrade") && !has("--dry-run") => Some(".claude/settings.json"),
+        "export" if has("--docusaurus") || has("--mkdocs") => Some("the docs directory"),
+        "config" if has("--global") && matches!(sub, Some("set" | "unset" | "edit")) => Some("the user config"),
+        "projects" if sub == Some("forget") => Some("the project registry"),
+        "copy" => Some("the other project's .legend"),
+        "service" if matches!(sub, Some("install" | "uninstall")) => Some("a user service unit"),
+        "sync" if sub == Some("flush") => Some("the sync remote"),
+        _ => None,
+    }
+}
+
+/// Run `command` with all Legend writes redirected to a temporary copy
+pub fn run_sandboxed<F>(command: F) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnOnce() -> Result<(), Box<dyn std::error::Error>>,
+{
+    let real_dir = storage::legend_dir();
+    let sandbox_dir = std::env::temp_dir().join(format!(
+        "legend-sandbox-{}-{}",
+        std::process::id(),
+        crate::types::current_timestamp()
+    ));
+
+    if real_dir.is_dir() {
+        copy_dir(&real_dir, &sandbox_dir)
+            .map_err(|e| format!("Failed to create sandbox: {}", e))?;
+    }
+
+    let before = load_or_empty(&real_dir);
+    storage::set_project_root(storage::project_root());
+    storage::set_legend_dir(sandbox_dir.clone());
+
+    let result = command();
+
+    let after = load_or_empty(&sandbox_dir);
+    storage::set_legend_dir(real_dir);
+    let _ = fs::remove_dir_all(&sandbox_dir);
+
+    let rendered = render_diff(&before, &after);
+    if !output::diag_json() {
+        eprintln!();
+    }
+    output::diag(
+        output::Level::Info,
+        &format!("[sandbox] No changes were written to .legend\n{}", rendered.trim_end()),
+        json!({"sandbox_diff": rendered}),
+    );
+
+    result
+}
+
+/// Load state from a directory, treating "not initialized" as empty
+fn load_or_empty(dir: &Path) -> LegendState {
+    storage::load_state_in(dir).unwrap_or_else(|_| LegendState::new(String::new()))
+}
+
+/// Recursively copy a directory
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Human-readable summary of what the sandboxed command changed:
+/// features field by field, then the other sections (see journal.rs)
+fn render_diff(before: &LegendState, after: &LegendState) -> String {
+    let features = diff::diff_states(before, after);
+    let sections = Sections::changed(before, after);
+    if features.is_empty() && sections.is_none() {
+        return "[sandbox] State would be unchanged\n".to_string();
+    }
+
+    let mut lines = diff::render_lines(&features);
+    if let Some(sections) = sections {
+        let (old, new) = (json!(before), json!(after));
+        lines.extend(sections.names().into_iter().map(|name| section_line(name, &old[name], &new[name])));
+    }
+    lines.iter().map(|line| format!("[sandbox] {}\n", line)).collect()
+}
+
+/// `~ conventions: 2 -> 3 entries`, or old -> new for a single value
+fn section_line(name: &str, old: &serde_json::Value, new: &serde_json::Value) -> String {
+    match (old.as_array(), new.as_array()) {
+        (Some(old), Some(new)) => format!("~ {}: {} -> {} entries", name, old.len(), new.len()),
+        _ => format!("~ {}: {} -> {}", name, old, new),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Feature, FeatureStatus};
+
+    #[test]
+    fn test_writes_outside() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(writes_outside("init", &[]).is_some());
+        assert!(writes_outside("hooks", &args(&["upgrade"])).is_some());
+        assert!(writes_outside("hooks", &args(&["upgrade", "--dry-run"])).is_none());
+        assert!(writes_outside("export", &args(&["--mkdocs", "--out", "site"])).is_some());
+        assert!(writes_outside("export", &args(&["--format", "csv"])).is_none());
+        assert!(writes_outside("config", &args(&["set", "editor", "vi", "--global"])).is_some());
+        assert!(writes_outside("config", &args(&["set", "editor", "vi"])).is_none());
+        assert!(writes_outside("projects", &args(&["forget", "demo"])).is_some());
+        assert!(writes_outside("update", &[]).is_none());
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let mut before = LegendState::new("demo".to_string());
+        before.features.push(Feature::new(
+            "auth".to_string(),
+            "Auth".to_string(),
+            "backend".to_string(),
+            "Login flow".to_string(),
+        ));
+
+        let mut after = before.clone();
+        after.features[0].status = FeatureStatus::Complete;
+
+        let text = render_diff(&before, &after);
+        assert!(text.contains("~ auth"));
+        assert!(text.contains("status: \"Pending\" -> \"Complete\""));
+
+        let unchanged = render_diff(&before, &before);
+        assert!(unchanged.contains("unchanged"));
+
+        // Sections besides features show up too
+        let mut after = before.clone();
+        after.conventions.push("no unwrap in prod code".to_string());
+        after.project_name = "Demo".to_string();
+        let text = render_diff(&before, &after);
+        assert!(text.contains("[sandbox] ~ conventions: 0 -> 1 entries\n"), "{}", text);
+        assert!(text.contains("[sandbox] ~ project_name: \"demo\" -> \"Demo\"\n"), "{}", text);
+    }
+}
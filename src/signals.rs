@@ -0,0 +1,113 @@
+// Signals - let long-running modes stop cleanly on SIGINT/SIGTERM/SIGHUP
+//
+// Without a handler these signals kill the process on the spot: Drop
+// guards never run, so `show --interactive` would leave the terminal
+// without echo and with the cursor hidden. The handler here only records
+// which signal arrived. It's installed without SA_RESTART, so a blocking
+// read returns io::ErrorKind::Interrupted; the loop notices, leaves the
+// normal way, and every guard gets to clean up. Dropping the guard
+// install() returns puts the previous handlers back.
+//
+// Why libc? std has no signal API, and libc is already in the build (lz4
+// depends on it), so this adds no new crate. Other platforms get a no-op.
+//
+// Rust concepts in this file:
+// - extern "C" functions as callbacks for C APIs
+// - Atomics for state shared with a signal handler
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// The signal that asked us to stop, or 0
+static RECEIVED: AtomicI32 = AtomicI32::new(0);
+
+#[cfg(unix)]
+const SIGNALS: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+
+/// The handlers install() replaced, restored on drop
+pub struct Handlers {
+    #[cfg(unix)]
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+}
+
+/// Route SIGINT, SIGTERM, and SIGHUP to `received()` instead of exiting,
+/// until the returned guard is dropped
+#[must_use = "the handlers are removed again when the guard is dropped"]
+pub fn install() -> Handlers {
+    #[cfg(unix)]
+    let previous = SIGNALS
+        .iter()
+        .map(|&signal| {
+            // SAFETY: the handler only stores to an atomic, which is
+            // async-signal-safe; the sigaction structs are fully initialized
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = on_signal as *const () as libc::sighandler_t;
+                libc::sigemptyset(&mut action.sa_mask);
+                let mut old: libc::sigaction = std::mem::zeroed();
+                libc::sigaction(signal, &action, &mut old);
+                (signal, old)
+            }
+        })
+        .collect();
+    Handlers {
+        #[cfg(unix)]
+        previous,
+    }
+}
+
+impl Drop for Handlers {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        for (signal, old) in &self.previous {
+            // SAFETY: restoring a sigaction the kernel gave us in install()
+            unsafe {
+                libc::sigaction(*signal, old, std::ptr::null_mut());
+            }
+        }
+        RECEIVED.store(0, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    RECEIVED.store(signal, Ordering::SeqCst);
+}
+
+/// The signal received since install(), if any (while its guard lives)
+pub fn received() -> Option<i32> {
+    match RECEIVED.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signal_is_recorded() {
+        let current = || {
+            // SAFETY: a null new action only reads the current one
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                libc::sigaction(libc::SIGHUP, std::ptr::null(), &mut action);
+                action.sa_sigaction
+            }
+        };
+        let original = current();
+
+        assert_eq!(received(), None);
+        let handlers = install();
+        // SAFETY: raising a signal we have just installed a handler for
+        unsafe {
+            libc::raise(libc::SIGHUP);
+        }
+        assert_eq!(received(), Some(libc::SIGHUP));
+
+        drop(handlers);
+        assert_eq!(received(), None);
+        assert_eq!(current(), original);
+    }
+}
@@ -0,0 +1,171 @@
+// Signing - optional tamper evidence for state.lz4
+//
+// When a signing key is configured, every save writes an ed25519
+// signature next to the state (state.lz4.sig) and `legend verify` checks
+// it against a list of trusted keys:
+//
+//   [signing]
+//   key = "~/.ssh/id_ed25519"                    # private key used on save
+//   allowed_signers = ".legend/allowed_signers"  # default shown
+//
+// allowed_signers uses the OpenSSH format, one trusted key per line:
+//
+//   alice@example.com ssh-ed25519 AAAAC3Nza...
+//
+// events.log isn't signed, so verify fails while it has events the
+// snapshot lacks (see verify.rs).
+//
+// Why ssh-keygen and not an ed25519 crate? Minimal dependencies (see
+// PLAN.md), and every developer machine already has OpenSSH and an
+// ed25519 key. `ssh-keygen -Y sign/verify` is the same mechanism git
+// uses for SSH-signed commits.
+
+use crate::config::{self, Config};
+use crate::exit::{self, ExitError};
+use crate::storage;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Signature namespace, so a Legend signature can't be replayed as a git one
+const NAMESPACE: &str = "legend-state";
+
+const ALLOWED_SIGNERS_FILE_NAME: &str = "allowed_signers";
+
+/// Where signing keys live
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningConfig {
+    pub key: Option<PathBuf>,
+    pub allowed_signers: PathBuf,
+}
+
+impl SigningConfig {
+    /// Read the [signing] section of a config
+    pub fn from_config(config: &Config) -> Self {
+        SigningConfig {
+            key: config.get("signing.key").map(expand_home),
+            allowed_signers: config
+                .get("signing.allowed_signers")
+                .map(expand_home)
+                .unwrap_or_else(|| storage::legend_path(ALLOWED_SIGNERS_FILE_NAME)),
+        }
+    }
+}
+
+/// The signature file for a state file: state.lz4 -> state.lz4.sig
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+/// Sign a freshly saved state file, if a signing key is configured
+pub fn sign_if_configured(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let signing = SigningConfig::from_config(&config::load_config()?);
+    let Some(key) = signing.key else {
+        return Ok(());
+    };
+
+    // ssh-keygen asks before overwriting, so clear the old signature first
+    let sig = signature_path(path);
+    if sig.exists() {
+        fs::remove_file(&sig).map_err(|e| format!("Failed to replace {}: {}", sig.display(), e))?;
+    }
+
+    let output = Command::new("ssh-keygen")
+        .args(["-Y", "sign", "-n", NAMESPACE, "-f"])
+        .arg(&key)
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("State saved, but signing failed: could not run ssh-keygen: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "State saved, but signing with {} failed: {}",
+            key.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Check a state file's signature; returns the signer's identity
+pub fn verify(path: &Path, allowed_signers: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let sig = signature_path(path);
+    if !sig.exists() {
+        return Err(ExitError::new(exit::CHECK_FAILED, format!("{} is not signed", path.display())).into());
+    }
+    if !allowed_signers.exists() {
+        return Err(format!(
+            "No trusted keys: create {} (one '<identity> <public key>' per line)",
+            allowed_signers.display()
+        )
+        .into());
+    }
+
+    // Which trusted identity made this signature?
+    let found = Command::new("ssh-keygen")
+        .args(["-Y", "find-principals", "-s"])
+        .arg(&sig)
+        .arg("-f")
+        .arg(allowed_signers)
+        .output()
+        .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+    let identity = String::from_utf8_lossy(&found.stdout).lines().next().unwrap_or("").trim().to_string();
+    if !found.status.success() || identity.is_empty() {
+        return Err(ExitError::new(
+            exit::CHECK_FAILED,
+            format!("{} was signed by a key not in {}", path.display(), allowed_signers.display()),
+        )
+        .into());
+    }
+
+    // Does the signature match the content?
+    let content = fs::File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let verified = Command::new("ssh-keygen")
+        .args(["-Y", "verify", "-n", NAMESPACE, "-I", &identity, "-s"])
+        .arg(&sig)
+        .arg("-f")
+        .arg(allowed_signers)
+        .stdin(content)
+        .output()
+        .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+    if !verified.status.success() {
+        return Err(ExitError::new(
+            exit::CHECK_FAILED,
+            format!("{} does not match its signature - it was modified after signing", path.display()),
+        )
+        .into());
+    }
+
+    Ok(identity)
+}
+
+/// "~/x" -> "$HOME/x"
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_config() {
+        let config = config::parse_config("[signing]\nkey = \"/keys/legend\"\n").unwrap();
+        let signing = SigningConfig::from_config(&config);
+        assert_eq!(signing.key, Some(PathBuf::from("/keys/legend")));
+        assert!(signing.allowed_signers.ends_with("allowed_signers"));
+
+        assert_eq!(SigningConfig::from_config(&Config::default()).key, None);
+        assert_eq!(
+            signature_path(Path::new(".legend/state.lz4")),
+            PathBuf::from(".legend/state.lz4.sig")
+        );
+    }
+}
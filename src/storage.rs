@@ -4,15 +4,363 @@
 // - Reads: <5ms (decompress + deserialize pre-computed data)
 // - Writes: 100-500ms acceptable (serialize + compress + save)
 //
-// Format: Bincode (binary) + LZ4 (fast compression)
+// Format: Bincode (binary) + LZ4 (fast compression), after a version
+// header on the state file (see migrations.rs)
+//
+// With `storage.format = "json"` in config.toml the state file is pretty
+// JSON instead, so it can be read and reviewed in a diff. It keeps its
+// name (history, signing, and git lookups all use it), and loading
+// tells the two apart by the first byte. `legend convert` switches.
+//
+// Either can be encrypted with age on the way to disk (see encryption.rs);
+// loading recognizes an age file and decrypts it first.
+//
+// Concurrency: hooks can run several `legend update`s at once. Each save
+// holds an advisory lock on .legend/state.lock (flock), and a command
+// that loads, changes, and saves takes lock_state() first so another
+// process can't save in between and have its features overwritten. One
+// that asks the user before saving takes lock_unchanged() after asking
+// instead, so a hook isn't kept waiting on a prompt.
+
+use crate::access;
+use crate::config;
+use crate::dates;
+use crate::encryption;
+use crate::exit::{self, ExitError};
+use crate::history;
+use crate::journal;
+use crate::migrations;
+use crate::output;
+use crate::rollups;
+use crate::signing;
+use crate::types::{current_timestamp, Archive, LegendState};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs::{self, File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default directory for all Legend files, relative to the project root
+pub const DEFAULT_LEGEND_DIR: &str = ".legend";
+
+/// Environment variable naming the Legend directory, like --dir
+pub const LEGEND_DIR_ENV: &str = "LEGEND_DIR";
+
+/// File name of the compressed state inside the Legend directory
+const STATE_FILE_NAME: &str = "state.lz4";
+
+/// File name of the archived features inside the Legend directory
+const ARCHIVE_FILE_NAME: &str = "archive.lz4";
+
+/// File name of the lock held while the state is changed
+const LOCK_FILE_NAME: &str = "state.lock";
+
+/// How long lock_state waits for another process before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest pause between attempts (they start at 5ms and double)
+const LOCK_MAX_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Override for the Legend directory (set by --dir, LEGEND_DIR, finding
+/// .legend in a parent directory, --sandbox and --global)
+///
+/// Every module builds its paths from legend_dir(), so redirecting the
+/// whole tool is a single assignment here
+static LEGEND_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Override for the project root (set by --sandbox, which moves the
+/// Legend directory but not the project's files)
+static PROJECT_ROOT_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Override for the state file's name (set by --global, see global.rs)
+static STATE_FILE_NAME_OVERRIDE: RwLock<Option<&'static str>> = RwLock::new(None);
+
+// Per-thread Legend directory for tests (see testing.rs); wins over
+// LEGEND_DIR_OVERRIDE so parallel tests never share a state file
+#[cfg(test)]
+thread_local! {
+    pub(crate) static TEST_LEGEND_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Point every Legend path at a different directory
+pub fn set_legend_dir(dir: PathBuf) {
+    if let Ok(mut guard) = LEGEND_DIR_OVERRIDE.write() {
+        *guard = Some(dir);
+    }
+}
+
+/// Resolve project files against `dir` whatever the Legend directory is
+pub fn set_project_root(dir: PathBuf) {
+    if let Ok(mut guard) = PROJECT_ROOT_OVERRIDE.write() {
+        *guard = Some(dir);
+    }
+}
+
+/// The directory files_involved paths are relative to: the one holding
+/// the Legend directory, not the current directory, so commands work the
+/// same from a subdirectory
+pub fn project_root() -> PathBuf {
+    if let Some(dir) = PROJECT_ROOT_OVERRIDE.read().ok().and_then(|guard| guard.clone()) {
+        return dir;
+    }
+    match legend_dir().parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Read and write the state under a different file name
+pub fn set_state_file_name(name: &'static str) {
+    if let Ok(mut guard) = STATE_FILE_NAME_OVERRIDE.write() {
+        *guard = Some(name);
+    }
+}
+
+/// Name of the state file inside the Legend directory
+fn state_file_name() -> &'static str {
+    STATE_FILE_NAME_OVERRIDE
+        .read()
+        .ok()
+        .and_then(|guard| *guard)
+        .unwrap_or(STATE_FILE_NAME)
+}
+
+/// The directory holding state.lz4, the journal, metrics, etc.
+pub fn legend_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(dir) = TEST_LEGEND_DIR.with(|dir| dir.borrow().clone()) {
+        return dir;
+    }
+
+    LEGEND_DIR_OVERRIDE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_LEGEND_DIR))
+}
+
+/// Where this run's Legend directory is, if not ./.legend
+///
+/// `explicit` (--dir) wins, then $LEGEND_DIR. Otherwise, with `search`,
+/// the nearest .legend holding a state file in the working directory or
+/// any parent, the way git finds .git, so commands work from
+/// subdirectories. `init` doesn't search: it starts a project here
+pub fn locate_legend_dir(explicit: Option<PathBuf>, search: bool) -> Option<PathBuf> {
+    if let Some(dir) = explicit {
+        return Some(dir);
+    }
+    if let Some(dir) = std::env::var_os(LEGEND_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if !search {
+        return None;
+    }
+    let cwd = std::env::current_dir().ok()?;
+    find_legend_dir_from(&cwd)
+}
+
+/// The nearest `start/.legend`, `start/../.legend`, ... with a state file
+///
+/// ./.legend itself is left as the default rather than made absolute
+fn find_legend_dir_from(start: &Path) -> Option<PathBuf> {
+    if start.join(DEFAULT_LEGEND_DIR).join(STATE_FILE_NAME).is_file() {
+        return None;
+    }
+    start
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join(DEFAULT_LEGEND_DIR))
+        .find(|dir| dir.join(STATE_FILE_NAME).is_file())
+}
 
-use crate::types::LegendState;
-use std::fs;
-use std::io;
-use std::path::Path;
+/// Path of a file inside the Legend directory
+pub fn legend_path(name: &str) -> PathBuf {
+    legend_dir().join(name)
+}
+
+/// Path of the compressed state file
+pub fn state_file() -> PathBuf {
+    legend_path(state_file_name())
+}
+
+/// Path of the archive of removed features
+pub fn archive_file() -> PathBuf {
+    legend_path(ARCHIVE_FILE_NAME)
+}
 
-/// File path for the compressed state
-const STATE_FILE: &str = ".legend/state.lz4";
+/// How the state file is written (`storage.format` in config.toml)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Bincode + LZ4 after a version header: smallest and fastest
+    Binary,
+    /// Pretty JSON with a format_version field: diffable
+    Json,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name {
+            "binary" => Ok(Format::Binary),
+            "json" => Ok(Format::Json),
+            other => Err(format!("storage format must be binary or json, got '{}'", other).into()),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Binary => "binary",
+            Format::Json => "json",
+        }
+    }
+
+    /// The configured format; binary unless config.toml says otherwise
+    pub fn configured() -> Result<Self, Box<dyn std::error::Error>> {
+        match config::load_config()?.get("storage.format") {
+            Some(name) => Format::parse(name).map_err(|e| format!("config: {}", e).into()),
+            None => Ok(Format::Binary),
+        }
+    }
+
+    /// The format of a state file's bytes: JSON starts with '{'
+    ///
+    /// An old headerless LZ4 file can too (when its size prefix starts
+    /// with 0x7B), so decoding falls back to binary if the JSON doesn't
+    /// parse.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Format::Json,
+            _ => Format::Binary,
+        }
+    }
+}
+
+/// The state file in JSON, with the format version first
+#[derive(Serialize)]
+struct JsonState<'a> {
+    format_version: u16,
+    #[serde(flatten)]
+    state: &'a LegendState,
+}
+
+thread_local! {
+    // Legend directories whose lock this thread holds, so a save inside
+    // a locked load-change-save doesn't wait on itself
+    static HELD_LOCKS: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Exclusive lock on a Legend directory's state; released when dropped
+pub struct StateLock {
+    /// None when this thread already held the lock (nothing to release)
+    held: Option<(PathBuf, File)>,
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        if let Some((dir, file)) = self.held.take() {
+            let _ = file.unlock();
+            HELD_LOCKS.with(|held| held.borrow_mut().retain(|d| d != &dir));
+        }
+    }
+}
+
+/// Lock the state until the returned guard is dropped
+///
+/// Take it before load_state in a command that saves what it loaded
+pub fn lock_state() -> Result<StateLock, Box<dyn std::error::Error>> {
+    lock_state_in(&legend_dir())
+}
+
+/// Lock the state to save changes made to `loaded` without holding the
+/// lock while the user was asked about them
+///
+/// Fails instead of saving if another command saved in the meantime,
+/// since `loaded` no longer has its changes
+pub fn lock_unchanged(loaded: &LegendState) -> Result<StateLock, Box<dyn std::error::Error>> {
+    let lock = lock_state()?;
+    if bincode::serialize(&load_state()?)? != bincode::serialize(loaded)? {
+        return Err("The state changed while you were choosing (another legend command saved); run this again".into());
+    }
+    Ok(lock)
+}
+
+/// Is the state lock of `dir` held right now? Never waits, and never
+/// creates the lock file (no command has locked a directory without one)
+pub fn state_locked_in(dir: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    if HELD_LOCKS.with(|held| held.borrow().iter().any(|d| d == dir)) {
+        return Ok(true);
+    }
+    let path = dir.join(LOCK_FILE_NAME);
+    let file = match File::options().write(true).open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e).into()),
+    };
+    match file.try_lock() {
+        Ok(()) => {
+            let _ = file.unlock();
+            Ok(false)
+        }
+        Err(TryLockError::WouldBlock) => Ok(true),
+        Err(TryLockError::Error(e)) => Err(format!("Failed to lock {}: {}", path.display(), e).into()),
+    }
+}
+
+/// Lock the state of a specific Legend directory
+///
+/// Retries with backoff for up to LOCK_TIMEOUT while another process
+/// holds it. A directory that doesn't exist yet has nothing to protect,
+/// and a filesystem without locks gets a warning, not a failure.
+pub fn lock_state_in(dir: &Path) -> Result<StateLock, Box<dyn std::error::Error>> {
+    let unlocked = StateLock { held: None };
+    if !dir.is_dir() || HELD_LOCKS.with(|held| held.borrow().iter().any(|d| d == dir)) {
+        return Ok(unlocked);
+    }
+
+    let path = dir.join(LOCK_FILE_NAME);
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let deadline = Instant::now() + LOCK_TIMEOUT;
+    let mut backoff = Duration::from_millis(5);
+    loop {
+        match file.try_lock() {
+            Ok(()) => break,
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(LOCK_MAX_BACKOFF);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(format!(
+                    "Another legend process has held {} for over {}s; try again once it finishes",
+                    path.display(),
+                    LOCK_TIMEOUT.as_secs()
+                )
+                .into())
+            }
+            Err(TryLockError::Error(e)) if e.kind() == std::io::ErrorKind::Unsupported => {
+                output::warning(&format!("Saving without a lock ({} can't be locked here)", path.display()));
+                return Ok(unlocked);
+            }
+            Err(TryLockError::Error(e)) => return Err(format!("Failed to lock {}: {}", path.display(), e).into()),
+        }
+    }
+
+    HELD_LOCKS.with(|held| held.borrow_mut().push(dir.to_path_buf()));
+    Ok(StateLock {
+        held: Some((dir.to_path_buf(), file)),
+    })
+}
+
+/// Just the version of a JSON state file
+#[derive(Deserialize)]
+struct JsonVersion {
+    format_version: u16,
+}
 
 /// Save LegendState to disk
 ///
@@ -23,15 +371,79 @@ const STATE_FILE: &str = ".legend/state.lz4";
 /// 2. Compress with LZ4 - ~20ms
 /// 3. Atomic write (temp + rename) - ~10ms
 ///
+/// (or pretty JSON instead of steps 1 and 2, see Format)
+///
 /// Returns error if:
 /// - Serialization fails (shouldn't happen with valid data)
 /// - Compression fails (very rare)
 /// - Disk write fails (permissions, disk full, etc.)
 pub fn save_state(state: &LegendState) -> Result<(), Box<dyn std::error::Error>> {
+    save_state_in(&legend_dir(), state)
+}
+
+/// Save LegendState into a specific Legend directory
+///
+/// Holds the directory's lock (see lock_state) while it writes.
+///
+/// Each save also leaves a copy in history/ (see history.rs) and refreshes
+/// the domain rollups (see rollups.rs); failing to write either warns but
+/// doesn't fail the save
+pub fn save_state_in(dir: &Path, state: &LegendState) -> Result<(), Box<dyn std::error::Error>> {
+    let _lock = lock_state_in(dir)?;
+    let state_file = dir.join(state_file_name());
+    let format = Format::configured()?;
+    let now = current_timestamp();
+    if let Err(e) = history::keep_baseline(dir, &state_file, now) {
+        output::warning(&format!("State history not updated: {}", e));
+    }
+
+    // With access tracking on, the hottest features go first (see access.rs)
+    match access::counts_in(dir) {
+        Some(counts) => {
+            let mut ordered = state.clone();
+            access::order_hot_first(&mut ordered, &counts);
+            write_state_file(&state_file, &ordered, format)?;
+        }
+        None => write_state_file(&state_file, state, format)?,
+    }
+    // The snapshot now has everything journaled (see journal.rs)
+    if let Err(e) = journal::mark_applied_in(dir) {
+        output::warning(&format!("Journal position not updated: {}", e));
+    }
+    if let Err(e) = history::record(dir, &state_file, now) {
+        output::warning(&format!("State history not updated: {}", e));
+    }
+    if let Err(e) = rollups::update_in(dir, state) {
+        output::warning(&format!("Domain rollups not updated: {}", e));
+    }
+
+    // Optional tamper evidence: state.lz4.sig (see signing.rs)
+    signing::sign_if_configured(&state_file)
+}
+
+/// Write the state in `format`, marked with the current format version
+/// (see migrations.rs)
+fn write_state_file(path: &Path, state: &LegendState, format: Format) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        Format::Binary => write_compressed(path, state, true),
+        Format::Json => {
+            let json = JsonState { format_version: migrations::CURRENT_VERSION, state };
+            let mut bytes = serde_json::to_vec_pretty(&json).map_err(|e| format!("Failed to serialize state: {}", e))?;
+            bytes.push(b'\n');
+            write_atomic(path, &encryption::seal(bytes)?)
+        }
+    }
+}
+
+/// Serialize (bincode), compress (LZ4), and atomically write a value,
+/// after the format header (version and checksum) if `headed`
+///
+/// Shared by the state snapshot and the archive (which has no header)
+fn write_compressed<T: Serialize>(path: &Path, value: &T, headed: bool) -> Result<(), Box<dyn std::error::Error>> {
     // Step 1: Serialize to binary format using bincode
     // bincode::serialize takes any type that implements Serialize
     // and converts it to Vec<u8> (vector of bytes)
-    let serialized = bincode::serialize(state)
+    let serialized = bincode::serialize(value)
         .map_err(|e| format!("Failed to serialize state: {}", e))?;
 
     // Step 2: Compress with LZ4
@@ -41,17 +453,32 @@ pub fn save_state(state: &LegendState) -> Result<(), Box<dyn std::error::Error>>
     let compressed = lz4::block::compress(&serialized, None, true)
         .map_err(|e| format!("Failed to compress state: {}", e))?;
 
+    let bytes = if headed {
+        [migrations::header(migrations::CURRENT_VERSION, &compressed), compressed].concat()
+    } else {
+        compressed
+    };
+    // Encrypted to encryption.recipients, if set (see encryption.rs)
+    write_atomic(path, &encryption::seal(bytes)?)
+}
+
+/// Write a file so that it is either the old or the new contents, never
+/// half of each
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
     // Step 3: Atomic write to prevent corruption
     // Strategy: write to temp file, then rename (rename is atomic)
-    // If we crash during write, the temp file is corrupted but STATE_FILE is safe
-    let temp_file = format!("{}.tmp", STATE_FILE);
+    // If we crash during write, the temp file is corrupted but the state file is safe
+    let temp_file = path.with_extension(match path.extension() {
+        Some(ext) => format!("{}.tmp", ext.to_string_lossy()),
+        None => "tmp".to_string(),
+    });
 
-    fs::write(&temp_file, &compressed)
+    fs::write(&temp_file, bytes)
         .map_err(|e| format!("Failed to write temp file: {}", e))?;
 
     // Rename is atomic - either fully succeeds or fully fails
     // No possibility of partially-written file
-    fs::rename(&temp_file, STATE_FILE)
+    fs::rename(&temp_file, path)
         .map_err(|e| format!("Failed to rename temp file: {}", e))?;
 
     Ok(())
@@ -67,46 +494,164 @@ pub fn save_state(state: &LegendState) -> Result<(), Box<dyn std::error::Error>>
 ///
 /// Returns error if:
 /// - File doesn't exist (not initialized)
-/// - File is corrupted (bad compression or serialization)
+/// - File is corrupted (bad compression, serialization, or checksum) and
+///   no copy in history/ loads either
 /// - Deserialization fails (version mismatch, data corruption)
 pub fn load_state() -> Result<LegendState, Box<dyn std::error::Error>> {
+    load_state_in(&legend_dir())
+}
+
+/// Load LegendState from a specific Legend directory
+pub fn load_state_in(dir: &Path) -> Result<LegendState, Box<dyn std::error::Error>> {
+    let state_file = dir.join(state_file_name());
+
     // Check if file exists first
-    if !Path::new(STATE_FILE).exists() {
-        return Err("Legend not initialized. Run 'legend init' first.".into());
+    if !state_file.exists() {
+        return Err(Box::new(ExitError::new(
+            exit::NOT_INITIALIZED,
+            "Legend not initialized. Run 'legend init' first.",
+        )));
     }
 
-    // Step 1: Read compressed file from disk
-    // fs::read returns Vec<u8>
-    let compressed = fs::read(STATE_FILE)
+    let compressed = fs::read(&state_file)
         .map_err(|e| format!("Failed to read state file: {}", e))?;
+    let mut state = match decode_state(&compressed) {
+        Ok(state) => state,
+        // Not damaged, just newer: saving an older copy over it would
+        // throw away whatever the newer Legend recorded
+        Err(e) if from_newer_legend(&compressed) => return Err(e),
+        Err(e) => recover(dir, e)?,
+    };
+
+    // Changes journaled by a write that died before its save (see journal.rs)
+    journal::replay_unapplied_in(dir, &mut state);
+    Ok(state)
+}
+
+/// The newest copy in history/ that loads, for a state file that doesn't
+///
+/// Says on stderr which copy it is; the next save replaces the damaged
+/// file with it
+fn recover(dir: &Path, error: Box<dyn std::error::Error>) -> Result<LegendState, Box<dyn std::error::Error>> {
+    let snapshots = history::snapshots_in(dir);
+    for snapshot in snapshots.iter().rev() {
+        if let Ok(state) = fs::read(&snapshot.path).map_err(Into::into).and_then(|bytes| decode_state(&bytes)) {
+            output::warning(&format!(
+                "{}: {}. Loaded the copy saved {} ({}) instead; the next save replaces the damaged file.",
+                dir.join(state_file_name()).display(),
+                error,
+                dates::format_datetime(snapshot.timestamp),
+                snapshot.path.display()
+            ));
+            return Ok(state);
+        }
+    }
+    if snapshots.is_empty() {
+        return Err(error);
+    }
+    Err(format!("{}, and none of the {} saved copies in history/ loads either", error, snapshots.len()).into())
+}
 
-    // Step 2: Decompress with LZ4
+/// Whether a state file says it was written by a newer Legend
+fn from_newer_legend(bytes: &[u8]) -> bool {
+    let version = match Format::detect(bytes) {
+        Format::Json => serde_json::from_slice::<JsonVersion>(bytes).ok().map(|v| v.format_version),
+        Format::Binary => migrations::split_header(bytes).map(|(header, _)| header.version),
+    };
+    version.is_some_and(|version| version > migrations::CURRENT_VERSION)
+}
+
+/// Decode the bytes of a state.lz4 file (from disk, git, or elsewhere)
+pub fn decode_state(bytes: &[u8]) -> Result<LegendState, Box<dyn std::error::Error>> {
+    decode_state_versioned(bytes).map(|(_, state)| state)
+}
+
+/// The state in a state file's bytes, and the format version it was
+/// written in (see migrations.rs); older versions are upgraded in memory
+pub fn decode_state_versioned(bytes: &[u8]) -> Result<(u16, LegendState), Box<dyn std::error::Error>> {
+    if encryption::is_encrypted(bytes) {
+        return decode_state_versioned(&encryption::open(bytes.to_vec())?);
+    }
+    if Format::detect(bytes) == Format::Json {
+        // A JSON error is the one worth showing unless it was binary after all
+        return decode_json(bytes).or_else(|err| decode_binary(bytes).map_err(|_| err));
+    }
+    decode_binary(bytes)
+}
+
+/// A JSON state file; fields added since it was written take their
+/// #[serde(default)], so no layouts are needed
+fn decode_json(bytes: &[u8]) -> Result<(u16, LegendState), Box<dyn std::error::Error>> {
+    let parse_error = |e: serde_json::Error| format!("Failed to parse state file as JSON: {}", e);
+    let JsonVersion { format_version } = serde_json::from_slice(bytes).map_err(parse_error)?;
+    if format_version > migrations::CURRENT_VERSION {
+        return Err(migrations::too_new(format_version));
+    }
+    Ok((format_version, serde_json::from_slice(bytes).map_err(parse_error)?))
+}
+
+fn decode_binary(bytes: &[u8]) -> Result<(u16, LegendState), Box<dyn std::error::Error>> {
+    match migrations::split_header(bytes) {
+        Some((header, block)) => {
+            migrations::verify(&header, block)?;
+            Ok((header.version, migrations::upgrade(header.version, &decompress(block)?)?))
+        }
+        // Saved before files had a header
+        None => migrations::detect(&decompress(bytes)?),
+    }
+}
+
+/// Format version of the state file on disk
+pub fn state_format_version() -> Result<u16, Box<dyn std::error::Error>> {
+    let bytes = fs::read(state_file()).map_err(|e| format!("Failed to read state file: {}", e))?;
+    decode_state_versioned(&bytes).map(|(version, _)| version)
+}
+
+/// Decompress (LZ4) bytes already in memory
+fn decompress(compressed: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     // LZ4 decompression is extremely fast (>2GB/s)
-    // decompress() returns Vec<u8>
     // The size hint is embedded in the compressed data (prepend_size=true)
-    let serialized = lz4::block::decompress(&compressed, None)
-        .map_err(|e| format!("Failed to decompress state: {}", e))?;
+    lz4::block::decompress(compressed, None).map_err(|e| format!("Failed to decompress state: {}", e).into())
+}
 
-    // Step 3: Deserialize from binary to LegendState
-    // bincode::deserialize takes &[u8] and returns T (inferred from context)
-    let state: LegendState = bincode::deserialize(&serialized)
-        .map_err(|e| format!("Failed to deserialize state: {}", e))?;
+/// Load the archive of features moved out of the active state
+///
+/// A missing archive just means nothing has been archived yet
+pub fn load_archive() -> Result<Archive, Box<dyn std::error::Error>> {
+    let path = archive_file();
+    if !path.exists() {
+        return Ok(Archive::default());
+    }
+    let compressed = encryption::open(fs::read(&path).map_err(|e| format!("Failed to read archive: {}", e))?)?;
 
-    Ok(state)
+    let serialized = decompress(&compressed)?;
+
+    // Archives written before features were JSON use the legacy layouts
+    bincode::deserialize(&serialized).or_else(|err| {
+        migrations::upgrade_archive(&serialized).map_err(|_| format!("Failed to deserialize archive: {}", err).into())
+    })
+}
+
+/// Save the archive (same bincode + LZ4 format as the state)
+pub fn save_archive(archive: &Archive) -> Result<(), Box<dyn std::error::Error>> {
+    write_compressed(&archive_file(), archive, false)
 }
 
 /// Check if Legend is initialized (state file exists)
 pub fn is_initialized() -> bool {
-    Path::new(STATE_FILE).exists()
+    state_file().exists()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TestEnv;
     use crate::types::Feature;
 
     #[test]
     fn test_save_load_roundtrip() {
+        let _env = TestEnv::new(1_700_000_000);
+
         // Create a test state
         let mut state = LegendState::new("Test Project".to_string());
 
@@ -130,13 +675,72 @@ mod tests {
         assert_eq!(loaded.features.len(), 1);
         assert_eq!(loaded.features[0].id, "test-feature");
         assert_eq!(loaded.features[0].domain, "testing");
+        assert_eq!(loaded.created_at, 1_700_000_000);
+    }
+
+
+    #[test]
+    fn test_corrupt_state_falls_back_to_history() {
+        let _env = TestEnv::new(1_700_000_000);
+        let mut state = LegendState::new("Test Project".to_string());
+        save_state(&state).unwrap();
+        state.project_name = "Renamed".to_string();
+        save_state(&state).unwrap();
+
+        // A flipped bit in the LZ4 block can still decompress, just not
+        // to what was saved; the checksum catches it first
+        let mut bytes = fs::read(state_file()).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        fs::write(state_file(), &bytes).unwrap();
+        assert!(decode_state(&bytes).unwrap_err().to_string().contains("corrupt"));
+        assert_eq!(load_state().unwrap().project_name, "Renamed");
+
+        fs::remove_dir_all(legend_path("history")).unwrap();
+        assert!(load_state().is_err());
+    }
+
+    #[test]
+    fn test_lock_state() {
+        let _env = TestEnv::initialized(1_700_000_000);
+        let lock = lock_state().unwrap();
+
+        // Another open of the lock file (as another process would) can't take it
+        let other = File::options().write(true).open(legend_path(LOCK_FILE_NAME)).unwrap();
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)));
+
+        // ...but this thread can lock again and save without waiting on itself
+        let nested = lock_state().unwrap();
+        assert!(nested.held.is_none());
+        save_state(&LegendState::new("Locked".to_string())).unwrap();
+        drop(nested);
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)));
+
+        drop(lock);
+        assert!(other.try_lock().is_ok());
+    }
+
+    #[test]
+    fn test_find_legend_dir_from_subdirectory() {
+        let root = std::env::temp_dir().join(format!("legend-find-test-{}", std::process::id()));
+        let nested = root.join("src").join("db");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join(DEFAULT_LEGEND_DIR)).unwrap();
+        // A .legend without a state file (like ~/.legend) is passed over
+        fs::create_dir_all(nested.join(DEFAULT_LEGEND_DIR)).unwrap();
+        fs::write(root.join(DEFAULT_LEGEND_DIR).join(STATE_FILE_NAME), b"").unwrap();
+
+        let found = find_legend_dir_from(&nested);
+        let at_root = find_legend_dir_from(&root);
+        let _ = fs::remove_dir_all(&root);
+        assert_eq!(found, Some(root.join(DEFAULT_LEGEND_DIR)));
+        assert_eq!(at_root, None);
     }
 
     #[test]
     fn test_load_nonexistent() {
-        // Try to load when file doesn't exist
-        // First, remove the file if it exists
-        let _ = fs::remove_file(STATE_FILE);
+        // A fresh test directory has no state file
+        let _env = TestEnv::new(0);
 
         let result = load_state();
         assert!(result.is_err());
@@ -0,0 +1,224 @@
+// Sync - push journal events to a team remote, queueing them while it's down
+//
+// Configured in .legend/config.toml with the command that delivers them:
+//
+//   [sync]
+//   push = "curl -fsS -X POST --data-binary @- https://legend.example.com/web/events"
+//
+// The command runs through `sh -c` with the events not pushed yet on
+// stdin, one JSON object per line in the events.log format, and
+// LEGEND_EVENT=sync, the same way webhooks are delivered. Exiting 0
+// means the remote has them. How they get there (HTTP, scp to a shared
+// box, a git commit) is the command's business.
+//
+// The journal itself is the offline queue: .legend/sync.json keeps the
+// sequence number of the last event pushed, and everything after it is
+// queued. A push that fails (offline, auth) leaves the queue alone and
+// records the error; the queue is retried after the next command, no
+// sooner than RETRY_AFTER after a failure so hooks on a laptop without a
+// network don't wait on curl every time, or at once with `legend sync
+// flush`. `legend sync status` shows what's waiting.
+//
+// With encryption on, the command receives the events decrypted: encrypt
+// in the command if the remote shouldn't read them.
+
+use crate::config::{self, Config};
+use crate::journal::{self, Event};
+use crate::output;
+use crate::storage;
+use crate::types::current_timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, TryLockError};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// File name of the push cursor and last outcome inside the Legend directory
+const STATUS_FILE_NAME: &str = "sync.json";
+
+/// Held while pushing, so two commands don't push the same events
+const LOCK_FILE_NAME: &str = "sync.lock";
+
+/// Seconds after a failed push before a command tries again by itself
+pub const RETRY_AFTER: i64 = 5 * 60;
+
+/// What sync.json records
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncStatus {
+    /// Sequence number of the last event the remote accepted
+    #[serde(default)]
+    pub pushed_seq: u64,
+    #[serde(default)]
+    pub last_attempt: Option<i64>,
+    #[serde(default)]
+    pub last_push: Option<i64>,
+    /// Why the last push failed; None once one succeeds
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// The push command from [sync], if sync is set up
+pub fn push_command(config: &Config) -> Option<&str> {
+    config.get("sync.push").filter(|command| !command.trim().is_empty())
+}
+
+pub fn load_status() -> Result<SyncStatus, Box<dyn std::error::Error>> {
+    match fs::read_to_string(storage::legend_path(STATUS_FILE_NAME)) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("Corrupt {}: {}", STATUS_FILE_NAME, e).into()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SyncStatus::default()),
+        Err(e) => Err(format!("Failed to read {}: {}", STATUS_FILE_NAME, e).into()),
+    }
+}
+
+fn save_status(status: &SyncStatus) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(status)?;
+    storage::write_atomic(&storage::legend_path(STATUS_FILE_NAME), format!("{}\n", json).as_bytes())
+}
+
+/// Journal events the remote doesn't have yet, oldest first
+pub fn queued(status: &SyncStatus) -> Result<Vec<Event>, Box<dyn std::error::Error>> {
+    Ok(journal::read_events()?.into_iter().filter(|e| e.seq > status.pushed_seq).collect())
+}
+
+/// Push every queued event with `command`; returns how many it took
+///
+/// On failure the events stay queued and the error is kept for `sync
+/// status`, then returned.
+pub fn flush(command: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let lock_path = storage::legend_path(LOCK_FILE_NAME);
+    let lock = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open {}: {}", lock_path.display(), e))?;
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => return Err("Another legend command is pushing right now".into()),
+        Err(TryLockError::Error(e)) => return Err(format!("Failed to lock {}: {}", lock_path.display(), e).into()),
+    }
+
+    let mut status = load_status()?;
+    let events = queued(&status)?;
+    let Some(last) = events.last().map(|e| e.seq) else {
+        return Ok(0);
+    };
+
+    let mut payload = String::new();
+    for event in &events {
+        payload.push_str(&serde_json::to_string(event)?);
+        payload.push('\n');
+    }
+
+    status.last_attempt = Some(current_timestamp());
+    let result = deliver(command, &payload);
+    match &result {
+        Ok(()) => {
+            status.pushed_seq = last;
+            status.last_push = status.last_attempt;
+            status.last_error = None;
+        }
+        Err(e) => status.last_error = Some(e.to_string()),
+    }
+    save_status(&status)?;
+    result.map(|()| events.len())
+}
+
+/// After a command: push what's queued, unless sync isn't set up or the
+/// last push failed less than RETRY_AFTER ago
+///
+/// Best effort; a failure is a warning, since the command itself is done.
+pub fn retry_queued() {
+    let Ok(config) = config::load_config() else { return };
+    let Some(command) = push_command(&config) else { return };
+    let Ok(status) = load_status() else { return };
+    if status.last_error.is_some()
+        && status.last_attempt.is_some_and(|t| current_timestamp() - t < RETRY_AFTER)
+    {
+        return;
+    }
+    if journal::last_seq().map_or(true, |seq| seq <= status.pushed_seq) {
+        return;
+    }
+
+    if let Err(e) = flush(command) {
+        let waiting = queued(&status).map_or(0, |events| events.len());
+        output::warning(&format!(
+            "{} change(s) queued for sync ({}); retried after a later command, or run legend sync flush",
+            waiting, e
+        ));
+    }
+}
+
+fn deliver(command: &str, payload: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("LEGEND_EVENT", "sync")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // A command that exits early without reading stdin fails on its own
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() { format!("push command exited with {}", output.status) } else { stderr }.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestEnv;
+    use crate::types::Feature;
+
+    fn add_feature(id: &str) {
+        let before = storage::load_state().unwrap();
+        let mut after = before.clone();
+        after.add_feature(Feature::new(id.to_string(), id.to_string(), "web".to_string(), "d".to_string()));
+        journal::commit(&before, &after).unwrap();
+    }
+
+    fn set_push(command: &str) {
+        fs::write(config::config_path(), format!("[sync]\npush = \"{}\"\n", command)).unwrap();
+    }
+
+    #[test]
+    fn test_failed_push_stays_queued() {
+        let mut env = TestEnv::initialized(1_709_251_200);
+        let remote = storage::legend_path("remote.log");
+        add_feature("auth");
+
+        // Offline: nothing leaves the queue, and the error is kept
+        set_push("echo offline >&2; exit 1");
+        assert_eq!(flush(config::load_config().unwrap().get("sync.push").unwrap()).unwrap_err().to_string(), "offline");
+        let status = load_status().unwrap();
+        assert_eq!(status.last_error.as_deref(), Some("offline"));
+        assert_eq!(queued(&status).unwrap().len(), 2);
+
+        // Back online: not retried until RETRY_AFTER has passed
+        set_push(&format!("cat >> {}", remote.display()));
+        add_feature("billing");
+        retry_queued();
+        assert!(!remote.exists());
+        env.set_now(1_709_251_200 + RETRY_AFTER);
+        retry_queued();
+
+        let pushed = fs::read_to_string(&remote).unwrap();
+        assert_eq!(pushed.lines().count(), 3);
+        let status = load_status().unwrap();
+        assert_eq!((status.pushed_seq, status.last_error.as_deref()), (3, None));
+        assert!(queued(&status).unwrap().is_empty());
+
+        // Only what's new goes next time
+        add_feature("search");
+        retry_queued();
+        assert_eq!(fs::read_to_string(&remote).unwrap().lines().count(), 4);
+    }
+}
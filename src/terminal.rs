@@ -0,0 +1,123 @@
+// Terminal - just enough raw-mode input for `show --interactive`
+//
+// Why stty and not a TUI crate? Minimal dependencies (see PLAN.md). We
+// only need single keypresses without echo, and `stty` gives us that on
+// every Unix. The previous settings are saved with `stty -g` and restored
+// when the guard drops - including on early returns and errors.
+//
+// Rust concepts in this file:
+// - RAII: Drop restores the terminal no matter how we leave
+// - std::process::Command with an inherited stdin (stty acts on the tty)
+
+use std::io::{self, IsTerminal, Read};
+use std::process::{Command, Stdio};
+
+/// A key the interactive views care about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Enter,
+    Escape,
+    Char(char),
+    /// SIGINT/SIGTERM/SIGHUP arrived (see signals.rs): time to leave
+    Interrupt,
+}
+
+/// Puts the terminal in no-echo, key-at-a-time mode until dropped
+pub struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    pub fn enable() -> Result<Self, Box<dyn std::error::Error>> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err("Interactive mode needs a terminal (stdin and stdout must be a tty)".into());
+        }
+
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "1", "time", "0"])?;
+        // Hide the cursor while the list is drawn
+        print!("\x1b[?25l");
+
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[?25h");
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("Failed to run stty: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("stty {} failed", args.join(" ")).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Block until a key is pressed
+pub fn read_key() -> Result<Key, Box<dyn std::error::Error>> {
+    let mut stdin = io::stdin().lock();
+    loop {
+        if crate::signals::received().is_some() {
+            return Ok(Key::Interrupt);
+        }
+        let mut buf = [0u8; 3];
+        let n = match stdin.read(&mut buf) {
+            Ok(n) => n,
+            // A signal cut the read short; the check above decides
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if n == 0 {
+            return Ok(Key::Escape);
+        }
+        if let Some(key) = decode_key(&buf[..n]) {
+            return Ok(key);
+        }
+    }
+}
+
+/// Turn raw bytes from the terminal into a key
+///
+/// Arrow keys arrive as escape sequences: ESC [ A (up) / ESC [ B (down)
+pub fn decode_key(bytes: &[u8]) -> Option<Key> {
+    match bytes {
+        [0x1b, b'[', b'A', ..] => Some(Key::Up),
+        [0x1b, b'[', b'B', ..] => Some(Key::Down),
+        [0x1b] => Some(Key::Escape),
+        [b'\n', ..] | [b'\r', ..] => Some(Key::Enter),
+        [b, ..] if b.is_ascii_graphic() || *b == b' ' => Some(Key::Char(*b as char)),
+        _ => None,
+    }
+}
+
+/// Clear the screen and move the cursor home
+pub fn clear_screen() {
+    print!("\x1b[2J\x1b[H");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_key() {
+        assert_eq!(decode_key(&[0x1b, b'[', b'A']), Some(Key::Up));
+        assert_eq!(decode_key(&[0x1b, b'[', b'B']), Some(Key::Down));
+        assert_eq!(decode_key(b"\n"), Some(Key::Enter));
+        assert_eq!(decode_key(b"s"), Some(Key::Char('s')));
+        assert_eq!(decode_key(&[0x1b, b'[', b'C']), None);
+    }
+}
@@ -0,0 +1,62 @@
+// Test harness - a private Legend directory and a fixed clock per test
+//
+// `let env = TestEnv::new(now);` at the top of a test points every
+// storage path on this thread at a fresh temp directory and pins
+// current_timestamp() to `now`. Commands can then run end to end (load,
+// save, journal, history) without touching the repo's .legend/ or racing
+// the tests on other threads. Dropping the TestEnv undoes both.
+
+use crate::clock::{self, ClockGuard, FixedClock};
+use crate::storage;
+use crate::types::LegendState;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Distinguishes directories of tests running at the same time
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub struct TestEnv {
+    dir: PathBuf,
+    _clock: Option<ClockGuard>,
+}
+
+impl TestEnv {
+    /// An empty Legend directory, with the clock stopped at `now`
+    pub fn new(now: i64) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "legend-test-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create test directory");
+        storage::TEST_LEGEND_DIR.with(|slot| *slot.borrow_mut() = Some(dir.clone()));
+
+        TestEnv {
+            dir,
+            _clock: Some(clock::install(FixedClock(now))),
+        }
+    }
+
+    /// The same, with an initialized (empty) state saved
+    pub fn initialized(now: i64) -> Self {
+        let env = TestEnv::new(now);
+        storage::save_state(&LegendState::new("Test".to_string())).expect("save test state");
+        env
+    }
+
+    /// Move the clock to `now`
+    pub fn set_now(&mut self, now: i64) {
+        // Drop the old guard first, so the new one restores the real clock
+        self._clock = None;
+        self._clock = Some(clock::install(FixedClock(now)));
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        storage::TEST_LEGEND_DIR.with(|slot| *slot.borrow_mut() = None);
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
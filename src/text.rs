@@ -0,0 +1,222 @@
+// Text helpers - widths, truncation, and sort order for display
+//
+// Feature names come from people and Claude in any language, so display
+// code can't slice bytes (a panic on "café" at the wrong index) or count
+// chars (a flag emoji is two, wide CJK takes two columns). Everything
+// here works on approximate grapheme clusters: a base character plus the
+// combining marks, variation selectors, skin tones, and ZWJ joins that
+// follow it, and regional indicators in pairs.
+//
+// Why not unicode-segmentation and unicode-width? Minimal dependencies
+// (see PLAN.md). The tables below cover the scripts and emoji that show
+// up in feature names; an unknown character counts as one column.
+//
+// Sorting uses `collate`: case and common Latin diacritics are ignored
+// ("Émail" sorts with "email", before "Export"), with the raw strings as
+// a tiebreak so the order is total and the same on every machine.
+
+use std::cmp::Ordering;
+
+/// Zero-width characters that attach to the one before them
+fn is_extend(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F     // combining diacritics
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x20D0..=0x20FF
+        | 0xFE20..=0xFE2F
+        | 0xFE00..=0xFE0F   // variation selectors
+        | 0x1F3FB..=0x1F3FF // skin tones
+        | 0xE0020..=0xE007F // tag sequences (subdivision flags)
+        | 0x200D            // zero-width joiner
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// Columns a character takes on its own: 2 for wide CJK and emoji
+fn char_width(c: char) -> usize {
+    if is_extend(c) {
+        return 0;
+    }
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F1E6..=0x1F1FF
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Split `s` into grapheme clusters (approximately - see the top of the file)
+pub fn graphemes(s: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    // Regional indicators seen in the current cluster
+    let mut indicators = 0;
+    for (i, c) in s.char_indices() {
+        let joins = match prev {
+            None => false,
+            Some(p) => {
+                is_extend(c) || p == '\u{200D}' || (is_regional_indicator(c) && indicators == 1)
+            }
+        };
+        if !joins && i > 0 {
+            clusters.push(&s[start..i]);
+            start = i;
+            indicators = 0;
+        }
+        if is_regional_indicator(c) {
+            indicators += 1;
+        }
+        prev = Some(c);
+    }
+    if start < s.len() {
+        clusters.push(&s[start..]);
+    }
+    clusters
+}
+
+/// Columns one cluster takes
+fn cluster_width(cluster: &str) -> usize {
+    let mut chars = cluster.chars();
+    let first = chars.next().map(char_width).unwrap_or(0);
+    // An emoji presentation selector widens text-style symbols (❤️)
+    if cluster.contains('\u{FE0F}') {
+        first.max(2)
+    } else {
+        first
+    }
+}
+
+/// Columns `s` takes in a terminal
+pub fn width(s: &str) -> usize {
+    graphemes(s).into_iter().map(cluster_width).sum()
+}
+
+/// `s` cut to at most `max` columns, ending in `marker` if anything was cut
+///
+/// Never splits a cluster, so accents and emoji stay whole
+pub fn truncate(s: &str, max: usize, marker: &str) -> String {
+    if width(s) <= max {
+        return s.to_string();
+    }
+    let budget = max.saturating_sub(width(marker));
+    let mut out = String::new();
+    let mut used = 0;
+    for cluster in graphemes(s) {
+        let w = cluster_width(cluster);
+        if used + w > budget {
+            break;
+        }
+        out.push_str(cluster);
+        used += w;
+    }
+    out.push_str(marker);
+    out
+}
+
+/// `s` followed by spaces up to `columns` - `{:<n}` counts chars, not columns
+pub fn pad(s: &str, columns: usize) -> String {
+    format!("{}{}", s, " ".repeat(columns.saturating_sub(width(s))))
+}
+
+/// The ASCII letters a Latin letter with a diacritic sorts as
+fn base_letters(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// `s` lowercased with diacritics removed, for sorting and matching
+///
+/// "Émail Ñotes" -> "email notes"; combining marks (from decomposed
+/// input) are dropped too
+pub fn fold(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars().flat_map(char::to_lowercase) {
+        if is_extend(c) {
+            continue;
+        }
+        match base_letters(c) {
+            Some(letters) => out.push_str(letters),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Display order for names, IDs, and domains: folded, then raw
+pub fn collate(a: &str, b: &str) -> Ordering {
+    fold(a).cmp(&fold(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widths_and_truncation() {
+        assert_eq!(graphemes("e\u{301}te\u{301}"), vec!["e\u{301}", "t", "e\u{301}"]);
+        assert_eq!(graphemes("🇯🇵🇫🇷"), vec!["🇯🇵", "🇫🇷"]);
+        assert_eq!(graphemes("👩‍💻!"), vec!["👩‍💻", "!"]);
+        assert_eq!(width("café"), 4);
+        assert_eq!(width("日本"), 4);
+        assert_eq!(width("👩‍💻"), 2);
+
+        assert_eq!(truncate("short", 10, ".."), "short");
+        assert_eq!(truncate("Café résumé", 6, ".."), "Café..");
+        assert_eq!(truncate("e\u{301}e\u{301}e\u{301}e\u{301}", 3, "…"), "e\u{301}e\u{301}…");
+        assert_eq!(truncate("日本語の名前", 7, ".."), "日本..");
+        assert_eq!(truncate("🇯🇵🇫🇷🇩🇪", 5, ".."), "🇯🇵..");
+        assert_eq!(pad("日本", 6), "日本  ");
+    }
+
+    #[test]
+    fn test_collate_ignores_case_and_diacritics() {
+        assert_eq!(fold("Émail Ñotes"), "email notes");
+        assert_eq!(fold("Cafe\u{301}"), "cafe");
+
+        let mut names = vec!["Export", "zebra", "Émail", "apple", "email", "Zoë"];
+        names.sort_by(|a, b| collate(a, b));
+        assert_eq!(names, vec!["apple", "email", "Émail", "Export", "zebra", "Zoë"]);
+    }
+}
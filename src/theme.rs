@@ -0,0 +1,263 @@
+// Theme - terminal colors for the human-readable views
+//
+// Used by show, show --interactive, and stats. JSON output is never
+// colored, and neither is anything written to a pipe or file.
+//
+// Pick a theme in .legend/config.toml:
+//
+//   [theme]
+//   name = "colorblind"     # default | colorblind | plain
+//   accent = "cyan"         # optional override
+//
+//   [theme.status]          # optional per-status overrides
+//   Blocked = "magenta"
+//   InReview = "sky"        # custom statuses (statuses.custom) too
+//
+// Colors are names (red, green, yellow, blue, magenta, cyan, gray, orange,
+// sky, vermillion, purple) or 256-color numbers ("208").
+//
+// The compact views (show --oneline) also mark each status with one
+// character, chosen in the same section:
+//
+//   [theme]
+//   glyphs = "unicode"      # unicode (○ ● ✖ ✔) | emoji | ascii
+//
+// The colorblind palette uses Okabe-Ito colors, which stay distinct under
+// the common forms of color blindness. Blocked is also bold so it never
+// relies on hue alone. `plain` (or the NO_COLOR environment variable, or
+// --screen-reader) turns color off entirely.
+
+use crate::config::{self, Config};
+use crate::output;
+use crate::types::FeatureStatus;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+
+/// ANSI SGR codes for each themed element; None means uncolored
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub pending: Option<String>,
+    pub in_progress: Option<String>,
+    pub blocked: Option<String>,
+    pub complete: Option<String>,
+    pub accent: Option<String>,
+    /// Custom statuses by lowercase name; uncolored unless set
+    pub custom: BTreeMap<String, String>,
+}
+
+impl Theme {
+    pub fn default_theme() -> Self {
+        Theme {
+            pending: Some("90".to_string()),
+            in_progress: Some("33".to_string()),
+            blocked: Some("1;31".to_string()),
+            complete: Some("32".to_string()),
+            accent: Some("36".to_string()),
+            custom: BTreeMap::new(),
+        }
+    }
+
+    pub fn colorblind() -> Self {
+        Theme {
+            pending: Some("90".to_string()),
+            in_progress: Some("38;5;214".to_string()), // orange
+            blocked: Some("1;38;5;175".to_string()),   // reddish purple, bold
+            complete: Some("38;5;32".to_string()),     // blue
+            accent: Some("38;5;74".to_string()),       // sky blue
+            custom: BTreeMap::new(),
+        }
+    }
+
+    pub fn plain() -> Self {
+        Theme {
+            pending: None,
+            in_progress: None,
+            blocked: None,
+            complete: None,
+            accent: None,
+            custom: BTreeMap::new(),
+        }
+    }
+
+    /// Theme for stdout: configured theme on a terminal, plain otherwise
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let theme = Theme::from_config(&config::load_config()?)?;
+        let color_allowed = std::io::stdout().is_terminal()
+            && std::env::var_os("NO_COLOR").is_none()
+            && !output::screen_reader();
+        Ok(if color_allowed { theme } else { Theme::plain() })
+    }
+
+    /// Read the [theme] sections of a config
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut theme = match config.get("theme.name").unwrap_or("default") {
+            "default" => Theme::default_theme(),
+            "colorblind" => Theme::colorblind(),
+            "plain" => Theme::plain(),
+            other => {
+                return Err(format!(
+                    "config: theme.name must be default, colorblind, or plain, got '{}'",
+                    other
+                )
+                .into())
+            }
+        };
+
+        if let Some(accent) = config.get("theme.accent") {
+            theme.accent = Some(color_code("theme.accent", accent)?);
+        }
+        let custom = config::custom_statuses(config)?;
+        for (status, color) in config.section("theme.status") {
+            let key = format!("theme.status.{}", status);
+            let code = color_code(&key, color)?;
+            match status.to_lowercase().as_str() {
+                "pending" => theme.pending = Some(code),
+                "inprogress" => theme.in_progress = Some(code),
+                "blocked" => theme.blocked = Some(code),
+                "complete" => theme.complete = Some(code),
+                name if custom.iter().any(|c| c.eq_ignore_ascii_case(name)) => {
+                    theme.custom.insert(name.to_string(), code);
+                }
+                _ => return Err(format!("config: unknown status '{}' in [theme.status]", status).into()),
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Color `text` for a status
+    pub fn status(&self, status: &FeatureStatus, text: &str) -> String {
+        let code = match status {
+            FeatureStatus::Pending => &self.pending,
+            FeatureStatus::InProgress => &self.in_progress,
+            FeatureStatus::Blocked => &self.blocked,
+            FeatureStatus::Complete => &self.complete,
+            FeatureStatus::Custom(name) => match self.custom.get(&name.to_lowercase()) {
+                Some(code) => return paint(&Some(code.clone()), text),
+                None => &None,
+            },
+        };
+        paint(code, text)
+    }
+
+    /// Color `text` with the accent (headers, heatmap cells)
+    pub fn accent(&self, text: &str) -> String {
+        paint(&self.accent, text)
+    }
+}
+
+/// One-character status marks for the compact views
+///
+/// Unlike colors these stay on when piped, since a pasted list still needs
+/// them; ascii is for terminals and chat tools without the symbols
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Glyphs {
+    Unicode,
+    Emoji,
+    Ascii,
+}
+
+impl Glyphs {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        Glyphs::from_config(&config::load_config()?)
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        match config.get("theme.glyphs").unwrap_or("unicode") {
+            "unicode" => Ok(Glyphs::Unicode),
+            "emoji" => Ok(Glyphs::Emoji),
+            "ascii" => Ok(Glyphs::Ascii),
+            other => Err(format!("config: theme.glyphs must be unicode, emoji, or ascii, got '{}'", other).into()),
+        }
+    }
+
+    /// The mark for a status; planned stubs get their own whatever the status
+    pub fn status(self, status: &FeatureStatus, planned: bool) -> &'static str {
+        let marks = match self {
+            Glyphs::Unicode => ["○", "●", "✖", "✔", "◌", "◇"],
+            Glyphs::Emoji => ["⚪", "🟡", "🔴", "✅", "📝", "🔷"],
+            Glyphs::Ascii => [".", "*", "!", "+", "?", "~"],
+        };
+        if planned {
+            return marks[4];
+        }
+        match status {
+            FeatureStatus::Pending => marks[0],
+            FeatureStatus::InProgress => marks[1],
+            FeatureStatus::Blocked => marks[2],
+            FeatureStatus::Complete => marks[3],
+            FeatureStatus::Custom(_) => marks[5],
+        }
+    }
+}
+
+fn paint(code: &Option<String>, text: &str) -> String {
+    match code {
+        Some(code) => format!("\x1b[{}m{}\x1b[0m", code, text),
+        None => text.to_string(),
+    }
+}
+
+/// Turn a color name or 256-color number into an SGR code
+fn color_code(key: &str, value: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let code = match value.to_lowercase().as_str() {
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        "gray" | "grey" => "90",
+        "orange" => "38;5;214",
+        "sky" => "38;5;74",
+        "vermillion" => "38;5;166",
+        "purple" => "38;5;175",
+        other => match other.parse::<u8>() {
+            Ok(n) => return Ok(format!("38;5;{}", n)),
+            Err(_) => return Err(format!("config: {} is not a known color: '{}'", key, value).into()),
+        },
+    };
+    Ok(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    #[test]
+    fn test_theme_from_config() {
+        assert_eq!(Theme::from_config(&Config::default()).unwrap(), Theme::default_theme());
+
+        let config = parse_config("[theme]\nname = \"colorblind\"\naccent = \"208\"\n\n[theme.status]\nBlocked = \"magenta\"\n").unwrap();
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.complete, Theme::colorblind().complete);
+        assert_eq!(theme.accent.as_deref(), Some("38;5;208"));
+        assert_eq!(theme.blocked.as_deref(), Some("35"));
+
+        assert!(Theme::from_config(&parse_config("[theme]\nname = \"neon\"\n").unwrap()).is_err());
+        assert!(Theme::from_config(&parse_config("[theme.status]\nInReview = \"sky\"\n").unwrap()).is_err());
+        let custom = parse_config("[statuses]\ncustom = \"InReview\"\n\n[theme.status]\nInReview = \"sky\"\n").unwrap();
+        let theme = Theme::from_config(&custom).unwrap();
+        assert_eq!(theme.status(&FeatureStatus::Custom("InReview".to_string()), "x"), "\x1b[38;5;74mx\x1b[0m");
+        assert!(Theme::from_config(&parse_config("[theme]\naccent = \"teal\"\n").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_glyphs() {
+        assert_eq!(Glyphs::from_config(&Config::default()).unwrap(), Glyphs::Unicode);
+        let ascii = Glyphs::from_config(&parse_config("[theme]\nglyphs = \"ascii\"\n").unwrap()).unwrap();
+        assert_eq!(ascii.status(&FeatureStatus::Blocked, false), "!");
+        assert_eq!(ascii.status(&FeatureStatus::Blocked, true), "?");
+        assert_eq!(Glyphs::Unicode.status(&FeatureStatus::InProgress, false), "●");
+        assert!(Glyphs::from_config(&parse_config("[theme]\nglyphs = \"runes\"\n").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_paint() {
+        let theme = Theme::default_theme();
+        assert_eq!(theme.status(&FeatureStatus::Complete, "Complete"), "\x1b[32mComplete\x1b[0m");
+        assert_eq!(Theme::plain().status(&FeatureStatus::Complete, "Complete"), "Complete");
+        assert_eq!(Theme::plain().accent("ID"), "ID");
+    }
+}
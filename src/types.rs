@@ -4,19 +4,135 @@
 // No builders, no complex constructors - just data
 
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+// Metadata - open-ended, org-specific fields on a feature
+//
+// e.g. {"cost_center": "platform", "tier": 1}. A BTreeMap rather than a
+// HashMap so get_state output and journal diffs list keys in a stable order.
+pub type Metadata = BTreeMap<String, Value>;
 
 // FeatureStatus enum
 //
 // Rust enums are powerful - not just integers like C
 // Each variant is a distinct type-safe value
 // The compiler ensures we handle all cases in match expressions
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+//
+// The four built-in statuses drive behavior (started_at, completed_at,
+// suggest-status). A project can add its own, e.g. "InReview" or
+// "Deferred", in config (statuses.custom); those are Custom. In JSON every
+// status is just its name: "InProgress", "InReview"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum FeatureStatus {
     Pending,
     InProgress,
     Blocked,
     Complete,
+    Custom(String),
+}
+
+impl FeatureStatus {
+    pub const BUILT_IN: [FeatureStatus; 4] = [
+        FeatureStatus::Pending,
+        FeatureStatus::InProgress,
+        FeatureStatus::Blocked,
+        FeatureStatus::Complete,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            FeatureStatus::Pending => "Pending",
+            FeatureStatus::InProgress => "InProgress",
+            FeatureStatus::Blocked => "Blocked",
+            FeatureStatus::Complete => "Complete",
+            FeatureStatus::Custom(name) => name,
+        }
+    }
+
+    // Built-in names match case-insensitively; anything else is Custom.
+    // Whether a custom name is allowed is the config's call (config::resolve_status)
+    pub fn from_name(name: &str) -> FeatureStatus {
+        FeatureStatus::BUILT_IN
+            .into_iter()
+            .find(|status| status.name().eq_ignore_ascii_case(name))
+            .unwrap_or_else(|| FeatureStatus::Custom(name.to_string()))
+    }
+}
+
+impl std::fmt::Display for FeatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+// Names in JSON; in bincode (legacy layouts, see migrations.rs) the variant
+// index, as the derived impl wrote it before Custom existed
+#[derive(Serialize, Deserialize)]
+enum BinaryStatus {
+    Pending,
+    InProgress,
+    Blocked,
+    Complete,
+    Custom(String),
+}
+
+impl Serialize for FeatureStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(self.name());
+        }
+        match self.clone() {
+            FeatureStatus::Pending => BinaryStatus::Pending,
+            FeatureStatus::InProgress => BinaryStatus::InProgress,
+            FeatureStatus::Blocked => BinaryStatus::Blocked,
+            FeatureStatus::Complete => BinaryStatus::Complete,
+            FeatureStatus::Custom(name) => BinaryStatus::Custom(name),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FeatureStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let name = String::deserialize(deserializer)?;
+            return Ok(FeatureStatus::from_name(&name));
+        }
+        Ok(match BinaryStatus::deserialize(deserializer)? {
+            BinaryStatus::Pending => FeatureStatus::Pending,
+            BinaryStatus::InProgress => FeatureStatus::InProgress,
+            BinaryStatus::Blocked => FeatureStatus::Blocked,
+            BinaryStatus::Complete => FeatureStatus::Complete,
+            BinaryStatus::Custom(name) => FeatureStatus::Custom(name),
+        })
+    }
+}
+
+// Priority - how much a feature matters, independent of how recent it is
+//
+// Ordering (show, get_state) adds a weight per level to the recency
+// score; see Feature::ordering_score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub enum Priority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl Priority {
+    // Recency is 0.0-1.0, so Critical always sorts above the other levels,
+    // while a fresh Low feature can still pass a stale Medium one
+    pub fn weight(self) -> f64 {
+        match self {
+            Priority::Low => 0.0,
+            Priority::Medium => 0.5,
+            Priority::High => 1.0,
+            Priority::Critical => 2.0,
+        }
+    }
 }
 
 // Feature - represents a single feature being tracked
@@ -36,6 +152,16 @@ pub struct Feature {
     pub domain: String,              // Primary category: "auth", "storage", "api", "ui"
     pub tags: Vec<String>,           // Flexible labels: ["backend", "security", "database"]
     pub status: FeatureStatus,       // Current status
+    #[serde(default)]
+    pub priority: Priority,          // Medium unless set
+    #[serde(default)]
+    pub owner: Option<String>,       // Who it's assigned to (git user.name when created by update)
+
+    // What it's stuck on; kept only while status is Blocked (see set_status)
+    #[serde(default)]
+    pub blocked_reason: Option<String>, // "Waiting on the vendor's API keys"
+    #[serde(default)]
+    pub blocked_by: Option<String>,  // ID of the feature holding it up, if it's one of ours
 
     // Rich context (for AI understanding)
     pub description: String,         // What this feature does (used for embeddings)
@@ -44,13 +170,236 @@ pub struct Feature {
     // File tracking
     pub files_involved: Vec<String>, // Files related to this feature
 
+    // Grouping: the epic or feature this one belongs to (see hierarchy.rs)
+    #[serde(default)]
+    pub parent_id: Option<String>,
+
+    // Ordering: IDs of features that must be done first (see deps.rs)
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+
+    // Typed links to other features (see `legend relate`), kept on the
+    // source side: "auth blocks billing" is stored on auth
+    #[serde(default)]
+    pub relations: Vec<Relation>,
+
+    // Checklist within the feature (see `legend task`)
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+
+    // Append-only log (see `legend note`); update never touches it
+    #[serde(default)]
+    pub notes: Vec<Note>,
+
+    // Decisions that shape this feature (see `legend decide`). Mirrors
+    // Decision::feature_ids so get_state shows them next to the feature
+    #[serde(default)]
+    pub decisions: Vec<DecisionLink>,
+
+    // Effort: the estimate is set by update, hours are added by `legend log-time`
+    #[serde(default)]
+    pub estimate_hours: Option<f64>,
+    #[serde(default)]
+    pub logged_hours: f64,
+
+    // Deadline, UTC midnight like Milestone::due (see deadlines.rs)
+    #[serde(default)]
+    pub due_date: Option<i64>,
+
+    // 1 (trivial / safe) to 5 (hard / risky), set by update; `legend next`
+    // weighs them when suggesting what to pick up
+    #[serde(default)]
+    pub complexity: Option<u8>,
+    #[serde(default)]
+    pub risk: Option<u8>,
+
     // Temporal metadata
     pub created_at: i64,             // Unix timestamp (seconds since epoch)
     pub last_updated: i64,           // Unix timestamp
+    #[serde(default)]
+    pub last_updated_by: Option<String>, // Update `source`: "claude-code", "aider", "human"
     pub recency_score: f64,          // For temporal weighting (1.0 = most recent)
+
+    // Custom fields (see Metadata above)
+    #[serde(default, with = "metadata_format")]
+    pub metadata: Metadata,
+
+    // Lifecycle (set on status transitions; None if never observed)
+    #[serde(default)]
+    pub started_at: Option<i64>,     // First time the feature went InProgress
+    #[serde(default)]
+    pub completed_at: Option<i64>,   // When it last became Complete
+
+    // Reserved with `legend plan`: a stub until `legend promote` fills it in
+    #[serde(default)]
+    pub planned: bool,
+}
+
+// Subtask - one checklist item inside a feature
+//
+// IDs are small numbers, unique within the feature ("legend task done auth 3")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subtask {
+    pub id: u32,
+    pub title: String,
+    pub done: bool,
+    pub created_at: i64,
+    #[serde(default)]
+    pub completed_at: Option<i64>,
+}
+
+// Note - one dated entry in a feature's log
+//
+// Unlike description and context, which an update replaces, notes only
+// accumulate: "tried X, reverted", "waiting on the infra ticket"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    pub created_at: i64,
+    pub text: String,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+// Relation - "this feature <kind> <target>"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relation {
+    pub kind: RelationKind,
+    pub target: String,
+}
+
+// Unlike depends_on, relations don't order work: they say what else to
+// read. Serialized as "blocks", "relates_to", "duplicates"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    Blocks,
+    RelatesTo,
+    Duplicates,
+}
+
+impl RelationKind {
+    pub const ALL: [RelationKind; 3] = [RelationKind::Blocks, RelationKind::RelatesTo, RelationKind::Duplicates];
+
+    // "relates_to" and "relates-to" both work on the command line
+    pub fn parse(value: &str) -> Option<RelationKind> {
+        let value = value.to_lowercase().replace('-', "_");
+        RelationKind::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RelationKind::Blocks => "blocks",
+            RelationKind::RelatesTo => "relates_to",
+            RelationKind::Duplicates => "duplicates",
+        }
+    }
+}
+
+// DecisionLink - a feature's side of a decision's feature_ids
+//
+// The title is copied in so a session reading get_state sees "#3 Store state
+// as bincode" without looking the decision up. Decisions aren't edited, so
+// the copy doesn't go stale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionLink {
+    pub id: u32,
+    pub title: String,
+}
+
+// Why a custom format for metadata? serde_json::Value can only be read by
+// self-describing formats, and bincode isn't one. In state.lz4 the map is
+// stored as a JSON string; in JSON output (get_state, the journal) it is a
+// normal object.
+pub(crate) mod metadata_format {
+    use super::Metadata;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(metadata: &Metadata, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            metadata.serialize(serializer)
+        } else {
+            serde_json::to_string(metadata)
+                .map_err(S::Error::custom)?
+                .serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Metadata, D::Error> {
+        if deserializer.is_human_readable() {
+            Metadata::deserialize(deserializer)
+        } else {
+            let json = String::deserialize(deserializer)?;
+            serde_json::from_str(&json).map_err(D::Error::custom)
+        }
+    }
+}
+
+// Why store an archived feature as JSON too? Same reason, one level up:
+// bincode fields are positional, so every new Feature field would make old
+// archive files unreadable. Inside archive.lz4 a feature is a JSON string,
+// and a new field only needs #[serde(default)]. (state.lz4 did the same
+// in format v2-v10, read with features_format below; it is on the read
+// path, so it went back to plain bincode, see migrations.rs.) Outside
+// bincode, features serialize as usual.
+mod feature_format {
+    use super::Feature;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(feature: &Feature, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            feature.serialize(serializer)
+        } else {
+            serde_json::to_string(feature)
+                .map_err(S::Error::custom)?
+                .serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Feature, D::Error> {
+        if deserializer.is_human_readable() {
+            Feature::deserialize(deserializer)
+        } else {
+            let json = String::deserialize(deserializer)?;
+            serde_json::from_str(&json).map_err(D::Error::custom)
+        }
+    }
+}
+
+// The feature list of state files v2-v10 (see migrations.rs): one JSON
+// string per feature
+pub(crate) mod features_format {
+    use super::Feature;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer};
+
+    // Only tests write those files
+    #[cfg(test)]
+    pub fn serialize<S: serde::Serializer>(features: &[Feature], serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error as _;
+        use serde::Serialize;
+        features
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Feature>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|json| serde_json::from_str(json).map_err(D::Error::custom))
+            .collect()
+    }
 }
 
 // impl block - adds methods to Feature
+// Not every helper has a caller outside tests yet
+#[allow(dead_code)]
 impl Feature {
     // Associated function (like a static method in other languages)
     // Called as: Feature::new(...)
@@ -67,12 +416,32 @@ impl Feature {
             domain,
             description,
             status: FeatureStatus::Pending,
+            priority: Priority::Medium,
+            owner: None,
+            blocked_reason: None,
+            blocked_by: None,
             tags: Vec::new(),           // Start with no tags
             context: None,              // Optional context
             files_involved: Vec::new(),
+            parent_id: None,
+            depends_on: Vec::new(),
+            relations: Vec::new(),
+            subtasks: Vec::new(),
+            notes: Vec::new(),
+            decisions: Vec::new(),
+            estimate_hours: None,
+            logged_hours: 0.0,
+            due_date: None,
+            complexity: None,
+            risk: None,
             created_at: now,
             last_updated: now,
+            last_updated_by: None,
             recency_score: 1.0, // New features start with max recency
+            metadata: Metadata::new(),
+            started_at: None,
+            completed_at: None,
+            planned: false,
         }
     }
 
@@ -90,9 +459,187 @@ impl Feature {
 
     // Method that mutably borrows self
     pub fn mark_complete(&mut self) {
-        self.status = FeatureStatus::Complete;
+        self.set_status(FeatureStatus::Complete, current_timestamp());
         self.touch();
     }
+
+    // Change status and keep the lifecycle timestamps in step:
+    // - started_at is the first move to InProgress (kept forever after)
+    // - completed_at is set on Complete and cleared if the feature reopens
+    // - blocked_reason and blocked_by go once it is no longer Blocked
+    pub fn set_status(&mut self, status: FeatureStatus, now: i64) {
+        if status == self.status {
+            return;
+        }
+        if status == FeatureStatus::InProgress && self.started_at.is_none() {
+            self.started_at = Some(now);
+        }
+        if status != FeatureStatus::Blocked {
+            self.blocked_reason = None;
+            self.blocked_by = None;
+        }
+        self.completed_at = match status {
+            FeatureStatus::Complete => Some(now),
+            _ => None,
+        };
+        self.status = status;
+    }
+
+    // Sort key for show and get_state: priority weight plus recency
+    pub fn ordering_score(&self) -> f64 {
+        self.priority.weight() + self.recency_score
+    }
+
+    // Seconds from start (or creation, if never seen InProgress) to done
+    pub fn cycle_time(&self) -> Option<i64> {
+        let completed = self.completed_at?;
+        Some(completed - self.started_at.unwrap_or(self.created_at))
+    }
+
+    // (done, total) subtasks, or None when there are none
+    pub fn subtask_progress(&self) -> Option<(usize, usize)> {
+        if self.subtasks.is_empty() {
+            return None;
+        }
+        let done = self.subtasks.iter().filter(|t| t.done).count();
+        Some((done, self.subtasks.len()))
+    }
+
+    // Does this feature track the given file?
+    // Entries ending in '/' are directories and match everything below them
+    // Leading "./" is ignored on both sides so paths from git line up
+    pub fn involves_file(&self, path: &str) -> bool {
+        let path = path.trim_start_matches("./");
+        self.files_involved.iter().any(|f| {
+            let f = f.trim_start_matches("./");
+            f == path || (f.ends_with('/') && path.starts_with(f))
+        })
+    }
+}
+
+// Archive - features moved out of the active state
+//
+// Kept in .legend/archive.lz4 so get_state stays small while the history
+// of finished work is preserved
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Archive {
+    pub features: Vec<ArchivedFeature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFeature {
+    pub archived_at: i64,
+    #[serde(with = "feature_format")]
+    pub feature: Feature,
+}
+
+// Milestone - a multi-week effort grouping several features
+//
+// Managed with `legend milestone`; features stay where they are, the
+// milestone only lists their IDs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Milestone {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub due: Option<i64>,            // Unix timestamp (UTC midnight)
+    #[serde(default)]
+    pub feature_ids: Vec<String>,
+    pub created_at: i64,
+}
+
+// Decision - why an architectural choice was made (`legend decide`)
+//
+// Numbered in the order they're recorded; `feature_ids` are the features
+// the decision shaped, if any
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub id: u32,
+    pub title: String,
+    pub rationale: String,
+    #[serde(default)]
+    pub feature_ids: Vec<String>,
+    pub decided_at: i64,
+}
+
+// Goal - an outcome the project is after (`legend goal`)
+//
+// The why above features: "Self-serve signup in under 5 minutes" lists
+// the features that get it there. Like milestones, it holds IDs only
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub target_date: Option<i64>,    // Unix timestamp (UTC midnight)
+    #[serde(default)]
+    pub feature_ids: Vec<String>,
+    pub created_at: i64,
+}
+
+// A stretch of work between `legend session start` and `legend session end`.
+// `features_touched` is filled in at the end: every feature updated since
+// `started_at`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub id: u32,
+    pub started_at: i64,
+    #[serde(default)]
+    pub ended_at: Option<i64>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub features_touched: Vec<String>,
+}
+
+// ProjectInfo - what the project is, for orienting a new session
+//
+// Edited with `legend project set`; get_state prints it with the project
+// name. Everything is optional, and unset fields are left out of the JSON
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tech_stack: Vec<String>,
+    /// Free text: naming, error handling, how tests are laid out, ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conventions: Option<String>,
+}
+
+impl ProjectInfo {
+    pub fn is_empty(&self) -> bool {
+        *self == ProjectInfo::default()
+    }
+}
+
+// Same idea as metadata_format, for any state-level list: JSON inside the
+// bincode blob, so the list's items can gain fields without a new layout
+pub(crate) mod json_format {
+    use serde::de::{DeserializeOwned, Error as _};
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T: Serialize, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            value.serialize(serializer)
+        } else {
+            serde_json::to_string(value)
+                .map_err(S::Error::custom)?
+                .serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        if deserializer.is_human_readable() {
+            T::deserialize(deserializer)
+        } else {
+            let json = String::deserialize(deserializer)?;
+            serde_json::from_str(&json).map_err(D::Error::custom)
+        }
+    }
 }
 
 // LegendState - the entire state of Legend for a project
@@ -104,8 +651,25 @@ pub struct LegendState {
     pub features: Vec<Feature>,
     pub created_at: i64,
     pub last_updated: i64,
+    #[serde(default, with = "json_format")]
+    pub milestones: Vec<Milestone>,
+    #[serde(default, with = "json_format")]
+    pub decisions: Vec<Decision>,
+    #[serde(default, with = "json_format")]
+    pub sessions: Vec<Session>,
+    #[serde(default, with = "json_format")]
+    pub project: ProjectInfo,
+    #[serde(default, with = "json_format")]
+    pub goals: Vec<Goal>,
+    // Rules every change should follow (`legend convention`), in the order added
+    #[serde(default, with = "json_format")]
+    pub conventions: Vec<String>,
+    // Feature IDs being worked on right now (`legend focus`), top of the stack last
+    #[serde(default, with = "json_format")]
+    pub focus: Vec<String>,
 }
 
+#[allow(dead_code)]
 impl LegendState {
     // Create a new empty state
     pub fn new(project_name: String) -> Self {
@@ -116,6 +680,13 @@ impl LegendState {
             features: Vec::new(),
             created_at: now,
             last_updated: now,
+            milestones: Vec::new(),
+            decisions: Vec::new(),
+            sessions: Vec::new(),
+            project: ProjectInfo::default(),
+            goals: Vec::new(),
+            conventions: Vec::new(),
+            focus: Vec::new(),
         }
     }
 
@@ -138,19 +709,66 @@ impl LegendState {
         self.features.iter_mut().find(|f| f.id == id)
     }
 
+    pub fn find_milestone(&self, id: &str) -> Option<&Milestone> {
+        self.milestones.iter().find(|m| m.id == id)
+    }
+
+    pub fn find_goal(&self, id: &str) -> Option<&Goal> {
+        self.goals.iter().find(|g| g.id == id)
+    }
+
     // Update the last_updated timestamp
     pub fn touch(&mut self) {
         self.last_updated = current_timestamp();
     }
+
+    // Take features out of the state, along with the dependency edges,
+    // relations, blocked_by links, and milestone, goal, and focus entries that point at them
+    // (children of a removed feature move to the top level); returns what was taken
+    pub fn remove_features(&mut self, ids: &HashSet<String>) -> Vec<Feature> {
+        let (removed, kept) = std::mem::take(&mut self.features)
+            .into_iter()
+            .partition(|f| ids.contains(&f.id));
+        self.features = kept;
+
+        for feature in &mut self.features {
+            if feature.parent_id.as_ref().is_some_and(|p| ids.contains(p)) {
+                feature.parent_id = None;
+            }
+            if feature.blocked_by.as_ref().is_some_and(|b| ids.contains(b)) {
+                feature.blocked_by = None;
+            }
+            feature.depends_on.retain(|dep| !ids.contains(dep));
+            feature.relations.retain(|r| !ids.contains(&r.target));
+        }
+        for milestone in &mut self.milestones {
+            milestone.feature_ids.retain(|id| !ids.contains(id));
+        }
+        for goal in &mut self.goals {
+            goal.feature_ids.retain(|id| !ids.contains(id));
+        }
+        self.focus.retain(|id| !ids.contains(id));
+        removed
+    }
+
+    // Highest ordering_score first (priority, then recency); on a tie the
+    // higher priority, then the name (see text::collate), so equal scores
+    // read the same on every machine
+    pub fn sort_by_priority(&mut self) {
+        self.features.sort_by(|a, b| {
+            b.ordering_score()
+                .total_cmp(&a.ordering_score())
+                .then_with(|| b.priority.weight().total_cmp(&a.priority.weight()))
+                .then_with(|| crate::text::collate(&a.name, &b.name))
+        });
+    }
 }
 
 // Helper function to get current Unix timestamp
-// Not a method - just a utility function
-fn current_timestamp() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_secs() as i64
+// Not a method - just a utility function. Asks this thread's clock
+// (clock.rs), so tests can pin it
+pub fn current_timestamp() -> i64 {
+    crate::clock::now()
 }
 
 // Tests live with the code they test
@@ -179,6 +797,20 @@ mod tests {
         assert!(!feature.is_complete());
     }
 
+    #[test]
+    fn test_status_names_in_json_and_bincode() {
+        let statuses = vec![FeatureStatus::InProgress, FeatureStatus::Custom("InReview".to_string())];
+        let json = serde_json::to_string(&statuses).unwrap();
+        assert_eq!(json, r#"["InProgress","InReview"]"#);
+        assert_eq!(serde_json::from_str::<Vec<FeatureStatus>>(&json).unwrap(), statuses);
+        assert_eq!(serde_json::from_str::<FeatureStatus>(r#""complete""#).unwrap(), FeatureStatus::Complete);
+
+        // Built-ins keep the variant index the derived impl wrote
+        assert_eq!(bincode::serialize(&FeatureStatus::Blocked).unwrap(), 2u32.to_le_bytes());
+        let bytes = bincode::serialize(&statuses).unwrap();
+        assert_eq!(bincode::deserialize::<Vec<FeatureStatus>>(&bytes).unwrap(), statuses);
+    }
+
     #[test]
     fn test_feature_mark_complete() {
         let mut feature = Feature::new(
@@ -194,6 +826,43 @@ mod tests {
         assert!(feature.is_complete());
     }
 
+    #[test]
+    fn test_set_status_records_lifecycle() {
+        let mut feature = Feature::new("f".to_string(), "F".to_string(), "d".to_string(), "x".to_string());
+        feature.created_at = 10;
+
+        feature.set_status(FeatureStatus::InProgress, 100);
+        feature.set_status(FeatureStatus::Blocked, 150);
+        feature.set_status(FeatureStatus::Complete, 400);
+        assert_eq!(feature.started_at, Some(100));
+        assert_eq!(feature.completed_at, Some(400));
+        assert_eq!(feature.cycle_time(), Some(300));
+
+        // Reopening clears completion but keeps the original start
+        feature.set_status(FeatureStatus::InProgress, 500);
+        assert_eq!((feature.started_at, feature.completed_at), (Some(100), None));
+
+        // Never seen in progress: measured from creation
+        let mut direct = Feature::new("g".to_string(), "G".to_string(), "d".to_string(), "x".to_string());
+        direct.created_at = 10;
+        direct.set_status(FeatureStatus::Complete, 70);
+        assert_eq!(direct.cycle_time(), Some(60));
+    }
+
+    #[test]
+    fn test_set_status_clears_blocker() {
+        let mut feature = Feature::new("f".to_string(), "F".to_string(), "d".to_string(), "x".to_string());
+        feature.set_status(FeatureStatus::Blocked, 100);
+        feature.blocked_reason = Some("Waiting on API keys".to_string());
+        feature.blocked_by = Some("vendor".to_string());
+
+        feature.set_status(FeatureStatus::Blocked, 200);
+        assert_eq!(feature.blocked_by.as_deref(), Some("vendor"));
+
+        feature.set_status(FeatureStatus::InProgress, 300);
+        assert_eq!((feature.blocked_reason, feature.blocked_by), (None, None));
+    }
+
     #[test]
     fn test_legend_state() {
         let mut state = LegendState::new("My Project".to_string());
@@ -219,4 +888,61 @@ mod tests {
         let not_found = state.find_feature("nonexistent");
         assert!(not_found.is_none());
     }
+
+    #[test]
+    fn test_sort_by_priority() {
+        let mut state = LegendState::new("P".to_string());
+        for (id, priority, recency) in [
+            ("stale-medium", Priority::Medium, 0.1),
+            ("fresh-low", Priority::Low, 0.9),
+            ("old-critical", Priority::Critical, 0.0),
+            ("fresh-high", Priority::High, 1.0),
+        ] {
+            let mut feature = Feature::new(id.to_string(), id.to_string(), "d".to_string(), "x".to_string());
+            feature.priority = priority;
+            feature.recency_score = recency;
+            state.add_feature(feature);
+        }
+
+        state.sort_by_priority();
+        let ids: Vec<&str> = state.features.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["old-critical", "fresh-high", "fresh-low", "stale-medium"]);
+    }
+
+    #[test]
+    fn test_metadata_roundtrips_through_bincode_and_json() {
+        let mut feature = Feature::new(
+            "api".to_string(),
+            "API".to_string(),
+            "api".to_string(),
+            "HTTP API".to_string(),
+        );
+        feature.metadata.insert("tier".to_string(), serde_json::json!(1));
+        feature.metadata.insert("cost_center".to_string(), serde_json::json!("platform"));
+
+        let bytes = bincode::serialize(&feature).unwrap();
+        let from_bincode: Feature = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(from_bincode.metadata, feature.metadata);
+
+        let json = serde_json::to_value(&feature).unwrap();
+        assert_eq!(json["metadata"]["tier"], 1);
+        let from_json: Feature = serde_json::from_value(json).unwrap();
+        assert_eq!(from_json.metadata, feature.metadata);
+    }
+
+    #[test]
+    fn test_involves_file() {
+        let mut feature = Feature::new(
+            "api".to_string(),
+            "API".to_string(),
+            "api".to_string(),
+            "HTTP API".to_string(),
+        );
+        feature.files_involved = vec!["./src/main.rs".to_string(), "src/api/".to_string()];
+
+        assert!(feature.involves_file("src/main.rs"));
+        assert!(feature.involves_file("src/api/routes.rs"));
+        assert!(!feature.involves_file("src/storage.rs"));
+        assert!(!feature.involves_file("src/apiary.rs"));
+    }
 }
@@ -0,0 +1,129 @@
+// Validators - organization-specific checks in the update pipeline
+//
+// Registered in .legend/config.toml, one command per name:
+//
+//   [validators]
+//   naming = "./scripts/check-feature-names.sh"
+//   owners = "python3 tools/require_owner.py"
+//
+// Before `legend update` writes anything, each command runs (through
+// `sh -c`, from the project root) with the proposed change as JSON on stdin:
+//
+//   {"added": [<feature>...], "removed": [<feature>...],
+//    "changed": [{"id": "...", "changes": [{"field", "before", "after"}], "feature": <feature>}]}
+//
+// Exit 0 accepts the update. Any other exit rejects it; whatever the
+// command printed becomes the error message. Validators run in name order
+// and the first rejection stops the update, so state is never half-written.
+//
+// Only external commands are supported. WASM modules would need a runtime
+// dependency, which Legend avoids (see PLAN.md).
+
+use crate::config::Config;
+use crate::diff::StateDiff;
+use crate::exit::{self, ExitError};
+use serde_json::json;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// (name, command) pairs from [validators]
+pub fn from_config(config: &Config) -> Vec<(String, String)> {
+    config
+        .section("validators")
+        .into_iter()
+        .map(|(name, command)| (name.to_string(), command.to_string()))
+        .collect()
+}
+
+/// The JSON document validators receive on stdin
+pub fn diff_payload(diff: &StateDiff) -> String {
+    let changed: Vec<_> = diff
+        .changed
+        .iter()
+        .map(|c| json!({"id": c.id, "changes": c.changes, "feature": c.after}))
+        .collect();
+
+    json!({
+        "added": diff.added,
+        "removed": diff.removed,
+        "changed": changed,
+    })
+    .to_string()
+}
+
+/// Run every validator; the first rejection becomes the error
+pub fn run_validators(validators: &[(String, String)], payload: &str) -> Result<(), Box<dyn std::error::Error>> {
+    for (name, command) in validators {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("LEGEND_VALIDATOR", name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run validator '{}': {}", name, e))?;
+
+        // A validator may exit without reading stdin; a broken pipe is fine
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(payload.as_bytes());
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run validator '{}': {}", name, e))?;
+
+        if !output.status.success() {
+            let mut message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            if !stderr.is_empty() {
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(&stderr);
+            }
+            if message.is_empty() {
+                message = format!("exited with {}", output.status);
+            }
+
+            return Err(ExitError::new(
+                exit::CHECK_FAILED,
+                format!("Update rejected by validator '{}': {}", name, message),
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+    use crate::diff;
+    use crate::types::{Feature, LegendState};
+
+    #[test]
+    fn test_validators_accept_and_reject() {
+        let config = parse_config(
+            "[validators]\naccept = \"cat > /dev/null\"\nnaming = \"grep -q api/ || { echo 'IDs must start with api/'; exit 1; }\"\n",
+        )
+        .unwrap();
+        let validators = from_config(&config);
+        assert_eq!(validators.len(), 2);
+
+        let before = LegendState::new("Demo".to_string());
+        let mut after = before.clone();
+        after.features.push(Feature::new("login".to_string(), "Login".to_string(), "web".to_string(), "d".to_string()));
+        let payload = diff_payload(&diff::diff_states(&before, &after));
+
+        let err = run_validators(&validators, &payload).unwrap_err();
+        assert_eq!(err.to_string(), "Update rejected by validator 'naming': IDs must start with api/");
+        assert_eq!(exit::code_for(err.as_ref()), exit::CHECK_FAILED);
+
+        after.features[0].id = "api/login".to_string();
+        let payload = diff_payload(&diff::diff_states(&before, &after));
+        assert!(run_validators(&validators, &payload).is_ok());
+    }
+}
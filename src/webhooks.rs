@@ -0,0 +1,85 @@
+// Webhooks - tell other tools when something worth knowing happens
+//
+// Registered in .legend/config.toml, one command per name:
+//
+//   [webhooks]
+//   slack = "curl -sS -X POST -H 'Content-Type: application/json' -d @- $SLACK_HOOK_URL"
+//
+// Each command runs (through `sh -c`) with the event as JSON on stdin and
+// LEGEND_EVENT set to its name (e.g. "handoff"), the same way validators
+// receive updates. Posting is the command's job: Legend has no HTTP
+// client, and curl already knows about proxies and auth.
+//
+// Delivery is best effort. A failing webhook is a warning; the change that
+// triggered it is already saved.
+
+use crate::config::Config;
+use crate::output;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// (name, command) pairs from [webhooks]
+pub fn from_config(config: &Config) -> Vec<(String, String)> {
+    config
+        .section("webhooks")
+        .into_iter()
+        .map(|(name, command)| (name.to_string(), command.to_string()))
+        .collect()
+}
+
+/// Send `event` to every webhook; returns how many accepted it
+pub fn notify(webhooks: &[(String, String)], event: &str, data: Value) -> usize {
+    let payload = json!({"event": event, "data": data}).to_string();
+    webhooks
+        .iter()
+        .filter(|(name, command)| match deliver(command, event, &payload) {
+            Ok(()) => true,
+            Err(e) => {
+                output::warning(&format!("Webhook '{}' failed: {}", name, e));
+                false
+            }
+        })
+        .count()
+}
+
+fn deliver(command: &str, event: &str, payload: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("LEGEND_EVENT", event)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // A hook may exit without reading stdin; a broken pipe is fine
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() { format!("exited with {}", output.status) } else { stderr }.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config;
+
+    #[test]
+    fn test_notify_counts_deliveries() {
+        let config = parse_config(
+            "[webhooks]\nok = \"grep -q '.event.:.handoff.' && test $LEGEND_EVENT = handoff\"\nbroken = \"exit 1\"\n",
+        )
+        .unwrap();
+        let webhooks = from_config(&config);
+        assert_eq!(webhooks.len(), 2);
+        assert_eq!(notify(&webhooks, "handoff", json!({"id": "auth"})), 1);
+        assert_eq!(notify(&[], "handoff", json!({})), 0);
+    }
+}
@@ -0,0 +1,288 @@
+// YAML - the subset `legend export --format yaml` writes and import reads
+//
+// Why hand-rolled and not serde_yaml? Minimal dependencies (see PLAN.md),
+// same as the config parser. Legend only needs to round-trip a JSON value:
+//
+//   key: "string"          strings always double-quoted, JSON escapes
+//   count: 3               numbers, true/false, null as in JSON
+//   tags: []               empty sequences and mappings inline
+//   features:              block sequences; a mapping item starts on the
+//     - id: "auth"         dash line and continues two spaces in
+//       name: "Auth"
+//
+// A double-quoted JSON string is also a valid YAML scalar, so the output
+// reads anywhere YAML does. Reading back accepts that plus plain
+// unquoted strings and # comments, for files edited by hand.
+//
+// Rust concepts in this file:
+// - Recursive descent over (indent, text) lines
+// - serde_json::Value as the in-memory tree
+
+use serde_json::{Map, Value};
+
+/// Render a JSON value as block-style YAML
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_mapping(&mut out, map, 0),
+        Value::Array(items) if !items.is_empty() => write_sequence(&mut out, items, 0),
+        scalar => {
+            out.push_str(&scalar_text(scalar));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn write_mapping(out: &mut String, map: &Map<String, Value>, indent: usize) {
+    for (i, (key, value)) in map.iter().enumerate() {
+        // The first key of a sequence item is already on the dash line
+        if i > 0 || !out.ends_with("- ") {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str(&key_text(key));
+        out.push(':');
+        write_value(out, value, indent);
+    }
+}
+
+fn write_sequence(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        out.push_str(&" ".repeat(indent));
+        out.push_str("- ");
+        match item {
+            Value::Object(map) if !map.is_empty() => write_mapping(out, map, indent + 2),
+            Value::Array(items) if !items.is_empty() => {
+                out.push('\n');
+                write_sequence(out, items, indent + 2);
+            }
+            scalar => {
+                out.push_str(&scalar_text(scalar));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// After `key:`, a scalar on the same line or a block below it
+fn write_value(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            write_mapping(out, map, indent + 2);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_sequence(out, items, indent + 2);
+        }
+        scalar => {
+            out.push(' ');
+            out.push_str(&scalar_text(scalar));
+            out.push('\n');
+        }
+    }
+}
+
+/// A scalar (or an empty collection) in JSON syntax
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Keys stay bare when they can't be mistaken for anything else
+fn key_text(key: &str) -> String {
+    let bare = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+/// A line with content: its indentation, its text, and its number (1-based)
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+    number: usize,
+}
+
+/// Parse the YAML subset described at the top of this file
+pub fn parse(content: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let lines: Vec<Line> = content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, raw)| {
+            let text = raw.trim();
+            if text.is_empty() || text.starts_with('#') || text == "---" {
+                return None;
+            }
+            Some(Line {
+                indent: raw.len() - raw.trim_start().len(),
+                text,
+                number: i + 1,
+            })
+        })
+        .collect();
+
+    let Some(first) = lines.first() else {
+        return Ok(Value::Null);
+    };
+    let mut pos = 0;
+    let value = if is_block_start(first.text) {
+        parse_block(&lines, &mut pos, first.indent)?
+    } else {
+        pos = 1;
+        parse_scalar(first.text)?
+    };
+    match lines.get(pos) {
+        Some(line) => Err(format!("YAML line {}: unexpected indentation", line.number).into()),
+        None => Ok(value),
+    }
+}
+
+/// Whether a line starts a sequence or mapping rather than being a scalar
+fn is_block_start(text: &str) -> bool {
+    text == "-" || text.starts_with("- ") || split_key(text).is_some()
+}
+
+/// A sequence or mapping whose lines are at `indent`
+fn parse_block(lines: &[Line], pos: &mut usize, indent: usize) -> Result<Value, Box<dyn std::error::Error>> {
+    if lines[*pos].text == "-" || lines[*pos].text.starts_with("- ") {
+        parse_sequence(lines, pos, indent)
+    } else {
+        parse_mapping(lines, pos, indent, None)
+    }
+}
+
+fn parse_sequence(lines: &[Line], pos: &mut usize, indent: usize) -> Result<Value, Box<dyn std::error::Error>> {
+    let mut items = Vec::new();
+    while let Some(line) = lines.get(*pos).filter(|l| l.indent == indent) {
+        let rest = match line.text.strip_prefix('-') {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => rest.trim_start(),
+            _ => return Err(format!("YAML line {}: expected '- ' in a sequence", line.number).into()),
+        };
+        if rest.is_empty() {
+            // "-" alone: the item is the block below
+            *pos += 1;
+            items.push(parse_nested(lines, pos, indent, line.number)?);
+        } else if split_key(rest).is_some() {
+            // "- key: value": a mapping whose other keys line up with this one
+            let item_indent = line.indent + (line.text.len() - rest.len());
+            items.push(parse_mapping(lines, pos, item_indent, Some(rest))?);
+        } else {
+            items.push(parse_scalar(rest).map_err(|e| format!("YAML line {}: {}", line.number, e))?);
+            *pos += 1;
+        }
+    }
+    Ok(Value::Array(items))
+}
+
+/// A mapping at `indent`; `first` is its first entry when that sits on a
+/// sequence item's dash line
+fn parse_mapping(lines: &[Line], pos: &mut usize, indent: usize, first: Option<&str>) -> Result<Value, Box<dyn std::error::Error>> {
+    let mut map = Map::new();
+    let mut pending = first;
+    loop {
+        let (text, number) = match pending.take() {
+            Some(text) => (text, lines[*pos].number),
+            None => match lines.get(*pos).filter(|l| l.indent == indent) {
+                Some(line) => (line.text, line.number),
+                None => break,
+            },
+        };
+        let (key, rest) = split_key(text).ok_or_else(|| format!("YAML line {}: expected 'key: value'", number))?;
+        *pos += 1;
+        let value = if rest.is_empty() {
+            parse_nested(lines, pos, indent, number)?
+        } else {
+            parse_scalar(rest).map_err(|e| format!("YAML line {}: {}", number, e))?
+        };
+        map.insert(key, value);
+    }
+    Ok(Value::Object(map))
+}
+
+/// The block under a "key:" or "-" line: deeper lines, or a sequence at
+/// the same indentation (a common style for sequences under a key)
+fn parse_nested(lines: &[Line], pos: &mut usize, indent: usize, number: usize) -> Result<Value, Box<dyn std::error::Error>> {
+    match lines.get(*pos) {
+        Some(next) if next.indent > indent => parse_block(lines, pos, next.indent),
+        Some(next) if next.indent == indent && next.text.starts_with("- ") => parse_sequence(lines, pos, indent),
+        _ => Err(format!("YAML line {}: nothing after ':'", number).into()),
+    }
+}
+
+/// `key: rest` (rest may be empty); None if the text isn't a mapping entry
+fn split_key(text: &str) -> Option<(String, &str)> {
+    if text.starts_with('"') {
+        // A quoted key ends at the first unescaped quote
+        let mut escaped = false;
+        let end = text.char_indices().skip(1).find(|&(_, c)| {
+            let closes = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            closes
+        })?.0;
+        let key: String = serde_json::from_str(&text[..=end]).ok()?;
+        let rest = text[end + 1..].strip_prefix(':')?;
+        return (rest.is_empty() || rest.starts_with(' ')).then(|| (key, rest.trim_start()));
+    }
+    let (key, rest) = match text.split_once(": ") {
+        Some((key, rest)) => (key, rest.trim_start()),
+        None => (text.strip_suffix(':')?, ""),
+    };
+    (!key.is_empty() && !key.contains(['"', '[', '{', '#'])).then(|| (key.to_string(), rest))
+}
+
+/// A JSON scalar, an empty collection, or a plain unquoted string
+fn parse_scalar(text: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    if text.starts_with('"') || text.starts_with('[') || text.starts_with('{') {
+        return serde_json::from_str(text).map_err(|e| format!("bad value {}: {}", text, e).into());
+    }
+    if text == "~" {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_roundtrip() {
+        let value = json!({
+            "project_name": "Demo: \"quoted\"\nsecond line",
+            "count": 3,
+            "ratio": 0.5,
+            "done": false,
+            "owner": null,
+            "empty": [],
+            "none": {},
+            "weird key": 1,
+            "features": [
+                {"id": "auth", "tags": ["a", "b"], "metadata": {"jira": "AUTH-1"}},
+                {"id": "billing", "tags": []}
+            ],
+            "matrix": [[1, 2], [], "x"]
+        });
+        let yaml = to_string(&value);
+        assert!(yaml.contains("features:\n  - id: \"auth\"\n    metadata:\n      jira: \"AUTH-1\"\n    tags:\n      - \"a\"\n"), "{}", yaml);
+        assert!(yaml.contains("\"weird key\": 1\n"));
+        assert_eq!(parse(&yaml).unwrap(), value);
+    }
+
+    #[test]
+    fn test_parse_hand_written() {
+        let yaml = "# edited by hand\nproject_name: Demo\nfeatures:\n- id: auth\n  status: Complete\n  tags: [\"x\"]\n";
+        assert_eq!(
+            parse(yaml).unwrap(),
+            json!({"project_name": "Demo", "features": [{"id": "auth", "status": "Complete", "tags": ["x"]}]})
+        );
+        assert!(parse("a: 1\n    b: 2\n").unwrap_err().to_string().contains("line 2"));
+        assert!(parse("a:\n").is_err());
+    }
+}
@@ -0,0 +1,583 @@
+// End-to-end tests - drive the compiled legend binary like a hook would
+//
+// Each test gets its own project directory under the system temp dir, with
+// HOME and XDG_CONFIG_HOME pointed inside it so no user config leaks in.
+// Commands run as child processes, so what is checked here is exactly
+// what Claude Code hooks and scripts see: stdout, stderr, exit codes.
+//
+// The snapshot tests compare JSON output with files in tests/snapshots/
+// after zeroing timestamps. A change to one of those files is a change to
+// the wire format hook consumers parse, and should be deliberate.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+const UPDATE: &str = r#"{"features": [
+    {"id": "auth", "name": "Auth", "domain": "security", "tags": ["backend"],
+     "status": "InProgress", "description": "Login flow", "files_involved": ["src/auth.rs"]},
+    {"id": "billing", "name": "Billing", "domain": "payments", "description": "Invoices and plans",
+     "depends_on": ["auth"]}
+]}"#;
+
+/// A throwaway project directory to run legend in
+struct Project {
+    dir: PathBuf,
+}
+
+impl Project {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "legend-cli-test-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create project directory");
+        Project { dir }
+    }
+
+    /// A project after `legend init`
+    fn initialized() -> Self {
+        let project = Project::new();
+        project.ok(&["init"]);
+        project
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_legend"));
+        command
+            .args(args)
+            .current_dir(&self.dir)
+            .env("HOME", self.dir.join("home"))
+            .env("XDG_CONFIG_HOME", self.dir.join(".config"))
+            .env_remove("LEGEND_DIAG")
+            .env_remove("LEGEND_DIR")
+            .env_remove("LEGEND_SCREEN_READER");
+        command
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        self.run_with_stdin(args, "")
+    }
+
+    fn run_with_stdin(&self, args: &[&str], stdin: &str) -> Output {
+        let mut child = self
+            .command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("run legend");
+        child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    /// Run, fail the test unless it exits 0, and return stdout
+    fn ok(&self, args: &[&str]) -> String {
+        expect_success(args, self.run(args))
+    }
+
+    fn ok_with_stdin(&self, args: &[&str], stdin: &str) -> String {
+        expect_success(args, self.run_with_stdin(args, stdin))
+    }
+
+    fn json(&self, args: &[&str]) -> Value {
+        serde_json::from_str(&self.ok(args)).unwrap_or_else(|e| panic!("legend {:?} printed invalid JSON: {}", args, e))
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+}
+
+impl Drop for Project {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn expect_success(args: &[&str], output: Output) -> String {
+    assert!(
+        output.status.success(),
+        "legend {:?} exited with {:?}\nstderr: {}",
+        args,
+        output.status.code(),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Zero every timestamp and the recency score, which change run to run
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let volatile = key.ends_with("_at") || key == "last_updated" || key == "recency_score";
+                if volatile && field.is_number() {
+                    *field = json!(0);
+                } else {
+                    normalize(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+/// Compare `actual` with tests/snapshots/<name> after normalizing
+fn assert_snapshot(name: &str, mut actual: Value) {
+    normalize(&mut actual);
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(name);
+    let expected: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        actual,
+        expected,
+        "{} changed; if that is intended, update the snapshot to:\n{}",
+        path.display(),
+        serde_json::to_string_pretty(&actual).unwrap()
+    );
+}
+
+#[test]
+fn test_init_creates_state_and_hooks() {
+    let project = Project::new();
+    project.ok(&["init"]);
+
+    assert!(project.path(".legend/state.lz4").is_file());
+    let settings = fs::read_to_string(project.path(".claude/settings.json")).unwrap();
+    assert!(settings.contains("legend hook SessionStart"));
+    let context = project.ok(&["hook", "SessionStart"]);
+    assert!(context.starts_with("== Legend Context ==\n"), "hook printed:\n{}", context);
+    assert!(context.contains("\"project_name\""));
+
+    // A second init is not a usage error and keeps the state
+    let again = project.run(&["init"]);
+    assert!(project.path(".legend/state.lz4").is_file());
+    assert_ne!(again.status.code(), Some(2));
+}
+
+#[test]
+fn test_hooks_upgrade_rewrites_shell_hooks() {
+    let project = Project::new();
+    fs::create_dir_all(project.path(".claude")).unwrap();
+    let legacy = json!({"hooks": {"SessionStart": [{"matcher": "", "hooks": [{"type": "command",
+        "command": "echo '== Legend Context =='; legend get_state 2>/dev/null || echo 'Legend state not found'"}]}]}});
+    fs::write(project.path(".claude/settings.json"), legacy.to_string()).unwrap();
+
+    project.ok(&["hooks", "upgrade"]);
+    let settings: Value = serde_json::from_str(&fs::read_to_string(project.path(".claude/settings.json")).unwrap()).unwrap();
+    assert_eq!(settings["hooks"]["SessionStart"][0]["hooks"][0]["command"], "legend hook SessionStart");
+
+    // Without a state the hook still prints something a session can start with
+    assert_eq!(project.ok(&["hook", "SessionStart"]), "== Legend Context ==\nLegend state not found\n");
+}
+
+#[test]
+fn test_init_in_home_needs_confirmation() {
+    let project = Project::new();
+    let in_home = |args: &[&str]| project.command(args).env("HOME", &project.dir).output().expect("run legend");
+
+    let refused = in_home(&["init"]);
+    assert_eq!(refused.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("--yes-i-mean-it"));
+    assert!(!project.path(".legend").exists());
+
+    assert!(in_home(&["init", "--yes-i-mean-it"]).status.success());
+    assert!(project.path(".legend/state.lz4").is_file());
+}
+
+#[test]
+fn test_global_store_shows_in_every_project() {
+    let project = Project::initialized();
+    let convention = r#"{"features": [{"id": "commit-style", "name": "Commit style", "domain": "conventions",
+        "description": "Imperative subject lines"}]}"#;
+    project.ok_with_stdin(&["--global", "update"], convention);
+    assert!(project.path("home/.legend/global.lz4").is_file());
+
+    let found = project.json(&["--global", "search", "imperative"]);
+    assert_eq!(found[0]["id"], "commit-style");
+    assert_eq!(project.json(&["search", "imperative"]), json!([]));
+
+    let state = project.json(&["get_state"]);
+    assert_eq!(state["global"]["features"][0]["id"], "commit-style");
+    assert_eq!(state["features"], json!([]));
+
+    // Repos without Legend get it too
+    let other = Project::new();
+    let empty = other.command(&["get_state", "--or-empty"]).env("HOME", project.path("home")).output().unwrap();
+    let empty: Value = serde_json::from_slice(&empty.stdout).unwrap();
+    assert_eq!(empty["global"]["features"][0]["id"], "commit-style");
+
+    assert_eq!(project.run(&["--global", "get_state"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_update_search_show_delete() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+
+    let found = project.json(&["search", "login"]);
+    let ids: Vec<&str> = found.as_array().unwrap().iter().map(|f| f["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["auth"]);
+
+    let shown = project.ok(&["show"]);
+    assert!(shown.contains("Auth") && shown.contains("Billing"), "show printed:\n{}", shown);
+
+    project.ok_with_stdin(&["update"], r#"{"remove_features": ["auth"]}"#);
+    let state = project.json(&["get_state"]);
+    let ids: Vec<&str> = state["features"].as_array().unwrap().iter().map(|f| f["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["billing"]);
+    assert_eq!(state["features"][0]["depends_on"], json!([]));
+}
+
+#[test]
+fn test_note_from_stdin() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+    project.ok_with_stdin(&["note", "--from-stdin", "auth"], "\x1b[32mShipped the login form\x1b[0m\r\n\n\n");
+    project.ok_with_stdin(&["note", "--from-stdin", "auth"], "\n  \n");
+
+    let notes = project.json(&["note", "auth", "--json"]);
+    assert_eq!(notes.as_array().unwrap().len(), 1);
+    assert_eq!(notes[0]["text"], "Shipped the login form");
+}
+
+#[test]
+fn test_conventions_reach_every_prompt() {
+    let project = Project::initialized();
+    project.ok(&["convention", "add", "No unwrap in prod code"]);
+    assert_eq!(project.json(&["get_state"])["conventions"], json!(["No unwrap in prod code"]));
+
+    let hook = project.json(&["hook", "UserPromptSubmit"]);
+    assert!(hook["additionalContext"].as_str().unwrap().ends_with("Project conventions: No unwrap in prod code."));
+}
+
+#[test]
+fn test_metadata_round_trips_through_storage() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+    project.ok_with_stdin(
+        &["update"],
+        r#"{"features": [{"id": "auth", "metadata": {"ticket": "SEC-412", "sprint": "24.3", "points": 5}}]}"#,
+    );
+
+    let state = project.json(&["get_state"]);
+    let auth = state["features"].as_array().unwrap().iter().find(|f| f["id"] == "auth").unwrap();
+    assert_eq!(auth["metadata"], json!({"points": 5, "sprint": "24.3", "ticket": "SEC-412"}));
+
+    let found = project.json(&["search", "--meta", "ticket=SEC-412"]);
+    assert_eq!(found.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_parallel_updates_keep_every_feature() {
+    let project = Project::initialized();
+    let children: Vec<_> = (0..8)
+        .map(|i| {
+            let mut child = project
+                .command(&["update"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .spawn()
+                .unwrap();
+            let update = format!(r#"{{"features": [{{"id": "f{}", "name": "F", "domain": "d", "description": "x"}}]}}"#, i);
+            child.stdin.take().unwrap().write_all(update.as_bytes()).unwrap();
+            child
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let state = project.json(&["get_state"]);
+    assert_eq!(state["features"].as_array().unwrap().len(), 8);
+}
+
+#[test]
+fn test_json_storage_mode() {
+    let project = Project::new();
+    project.ok(&["init", "--format", "json"]);
+    project.ok_with_stdin(&["update"], r#"{"features": [{"id": "auth", "name": "Auth", "domain": "web", "description": "Login"}]}"#);
+
+    let text = fs::read_to_string(project.path(".legend/state.lz4")).unwrap();
+    let on_disk: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(on_disk["features"][0]["id"], "auth");
+    assert_eq!(project.json(&["get_state"])["features"][0]["id"], "auth");
+
+    project.ok(&["convert", "--format", "binary"]);
+    assert!(fs::read(project.path(".legend/state.lz4")).unwrap().starts_with(b"LGND"));
+    assert_eq!(project.json(&["get_state"])["features"][0]["id"], "auth");
+}
+
+#[test]
+fn test_export_import_between_projects() {
+    let source = Project::initialized();
+    source.ok_with_stdin(&["update"], UPDATE);
+    let export = source.ok(&["export"]);
+    fs::write(source.path("export.json"), &export).unwrap();
+
+    let target = Project::initialized();
+    let file = source.path("export.json");
+    target.ok(&["import", file.to_str().unwrap(), "--from", "legend"]);
+
+    let mut exported: Value = serde_json::from_str(&export).unwrap();
+    let mut imported = target.json(&["get_state"]);
+    normalize(&mut exported);
+    normalize(&mut imported);
+    assert_eq!(imported["features"], exported["features"]);
+}
+
+#[test]
+fn test_copy_to_a_registered_project() {
+    let source = Project::initialized();
+    source.ok_with_stdin(&["update"], UPDATE);
+    source.ok(&["note", "auth", "Sessions expire after a day"]);
+    source.ok(&["decide", "Use JWT", "--rationale", "Stateless", "--feature", "auth"]);
+
+    // Registered by `init` in the same HOME, with its state kept as JSON
+    let other = source.path("gateway");
+    fs::create_dir_all(&other).unwrap();
+    assert!(source.command(&["init", "--format", "json"]).current_dir(&other).output().unwrap().status.success());
+
+    source.ok(&["copy", "auth", "--to", "gateway", "--with-notes", "--with-decisions"]);
+    source.ok(&["copy", "auth", "--to", other.to_str().unwrap()]);
+
+    let output = source.command(&["get_state"]).current_dir(&other).output().unwrap();
+    let state: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let copied = &state["features"][0];
+    assert_eq!(copied["notes"][0]["text"], "Sessions expire after a day");
+    assert_eq!(copied["decisions"][0]["title"], "Use JWT");
+    assert_eq!(state["decisions"][0]["feature_ids"], json!(["auth"]));
+    let plain = &state["features"][1];
+    assert_eq!(plain["id"], "auth-2");
+    assert_eq!(plain["notes"].as_array().map_or(0, |n| n.len()), 0);
+    assert_eq!(plain["decisions"].as_array().map_or(0, |d| d.len()), 0);
+
+    assert_eq!(source.run(&["copy", "auth", "--to", "no-such-project"]).status.code(), Some(1));
+}
+
+#[test]
+fn test_full_export_round_trips() {
+    let source = Project::initialized();
+    source.ok_with_stdin(&["update"], UPDATE);
+    source.ok(&["decide", "Use JWT", "--rationale", "Stateless", "--feature", "auth"]);
+    source.ok(&["convention", "add", "Tests live next to the code"]);
+    source.ok(&["focus", "push", "auth"]);
+
+    let target = Project::initialized();
+    for format in ["yaml", "json"] {
+        let file = source.path(&format!("export.{}", format));
+        fs::write(&file, source.ok(&["export", "--format", format])).unwrap();
+        target.ok(&["import", file.to_str().unwrap()]);
+    }
+
+    // The second import finds nothing new
+    let mut expected = source.json(&["export"]);
+    let mut actual = target.json(&["export"]);
+    normalize(&mut expected);
+    normalize(&mut actual);
+    assert_eq!(actual, expected);
+
+    let csv = source.ok(&["export", "--format", "csv"]);
+    assert!(csv.starts_with("id,name,domain,status,"), "{}", csv);
+    assert!(source.ok(&["export", "--format", "markdown"]).contains("| `billing` | Billing | payments |"));
+}
+
+#[test]
+fn test_state_location() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+
+    // From a subdirectory: the project's .legend, found like git finds .git
+    let nested = project.path("src/db");
+    fs::create_dir_all(&nested).unwrap();
+    let output = project.command(&["search", "auth"]).current_dir(&nested).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("\"auth\""));
+
+    // --dir and LEGEND_DIR put the state anywhere, init included
+    let elsewhere = project.path("state-elsewhere");
+    let dir = elsewhere.to_str().unwrap();
+    project.ok(&["--dir", dir, "init"]);
+    assert!(elsewhere.join("state.lz4").is_file());
+    let output = project.command(&["search", "auth"]).env("LEGEND_DIR", dir).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "[]");
+}
+
+#[test]
+fn test_file_checks_from_a_subdirectory() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+    fs::create_dir_all(project.path("src")).unwrap();
+    fs::write(project.path("src/auth.rs"), "").unwrap();
+
+    // src/auth.rs is relative to the project root, not to src/
+    let output = project.command(&["validate", "--check"]).current_dir(project.path("src")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+    let output = project.command(&["coverage", "--min", "100"]).current_dir(project.path("src")).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+
+    fs::remove_file(project.path("src/auth.rs")).unwrap();
+    let output = project.command(&["validate", "--check"]).current_dir(project.path("src")).output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_projects_registry() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+    let name = project.dir.file_name().unwrap().to_str().unwrap().to_string();
+
+    let listed = project.json(&["projects", "--json"]);
+    assert_eq!(listed[0]["name"], name.as_str());
+    assert_eq!(listed[0]["features"], 2);
+
+    // -p finds it from a directory that has nothing to do with it
+    let output = project.command(&["-p", &name, "search", "auth"]).current_dir(std::env::temp_dir()).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("\"auth\""));
+    assert_eq!(project.run(&["-p", "no-such-project", "show"]).status.code(), Some(1));
+
+    // A project whose .legend was deleted drops out of the list
+    let other = project.path("other");
+    fs::create_dir_all(&other).unwrap();
+    assert!(project.command(&["init"]).current_dir(&other).output().unwrap().status.success());
+    assert_eq!(project.json(&["projects", "--json"]).as_array().unwrap().len(), 2);
+    fs::remove_dir_all(other.join(".legend")).unwrap();
+    assert_eq!(project.json(&["projects", "--json"]).as_array().unwrap().len(), 1);
+
+    project.ok(&["projects", "forget", &name]);
+    assert_eq!(project.json(&["projects", "--json"]), json!([]));
+}
+
+/// Every file under `dir` with its contents, for before/after comparisons
+fn tree(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(tree(&path));
+        } else {
+            files.push((path.clone(), fs::read(&path).unwrap()));
+        }
+    }
+    files.sort();
+    files
+}
+
+#[test]
+fn test_sandbox_writes_nothing_outside_the_copy() {
+    let project = Project::initialized();
+    let before = tree(&project.dir);
+
+    project.ok_with_stdin(&["--sandbox", "update"], UPDATE);
+    let output = project.run(&["--sandbox", "convention", "add", "no unwrap"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("~ conventions: 0 -> 1 entries"), "{}", stderr);
+    for args in [
+        &["--sandbox", "init"][..],
+        &["--sandbox", "hooks", "upgrade"],
+        &["--sandbox", "export", "--mkdocs"],
+        &["--sandbox", "config", "set", "editor", "vi", "--global"],
+        &["--sandbox", "update", "--global"],
+    ] {
+        let output = project.run(args);
+        assert!(!output.status.success(), "legend {:?} should be refused", args);
+    }
+    assert_eq!(project.run(&["--sandbox", "init"]).status.code(), Some(2));
+
+    assert!(tree(&project.dir) == before, "a --sandbox run changed files outside the copy");
+}
+
+#[test]
+fn test_exit_codes() {
+    let project = Project::new();
+    assert_eq!(project.run(&["get_state"]).status.code(), Some(4));
+    assert_eq!(project.run(&["--if-initialized", "get_state"]).status.code(), Some(0));
+    assert_eq!(project.run(&["no-such-command"]).status.code(), Some(2));
+
+    project.ok(&["init"]);
+    let bad = project.run_with_stdin(&["update"], "not json");
+    assert_eq!(bad.status.code(), Some(1));
+    assert!(!bad.stderr.is_empty());
+
+    for args in [
+        &["validate", "--bogus"][..],
+        &["lint", "--format", "xml"],
+        &["coverage", "--min", "150"],
+        &["coverage", "--min"],
+        &["pr-description", "--bogus"],
+        &["changelog", "--since", "someday"],
+        &["release-notes"],
+        &["verify", "extra"],
+        &["warm", "--loud"],
+        &["onboard", "--top", "0"],
+        &["show", "--bogus"],
+        &["stats", "--bogus"],
+        &["get_state", "--bogus"],
+        &["impact", "auth", "--bogus"],
+        &["import", "--bogus"],
+        &["diag", "bogus"],
+        &["search", "--bogus"],
+    ] {
+        assert_eq!(project.run(args).status.code(), Some(2), "legend {:?}", args);
+    }
+}
+
+#[test]
+fn test_closed_stdout_is_not_a_crash() {
+    let project = Project::initialized();
+    let mut child = project
+        .command(&["update"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run legend");
+    // Like `legend update | head -0`: the reader is gone before any output
+    drop(child.stdout.take());
+    child.stdin.take().unwrap().write_all(UPDATE.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!project.path(".legend/crash").exists());
+}
+
+#[test]
+fn test_get_state_wire_format() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+    assert_snapshot("get_state.json", project.json(&["get_state"]));
+}
+
+#[test]
+fn test_search_and_export_wire_format() {
+    let project = Project::initialized();
+    project.ok_with_stdin(&["update"], UPDATE);
+    assert_snapshot("search.json", project.json(&["search", "invoices"]));
+    assert_snapshot("export.json", project.json(&["export", "--filter", "domain:payments"]));
+}
+
+#[test]
+fn test_diag_json_records_on_stderr() {
+    let project = Project::initialized();
+    let output = project.run(&["--diag", "json", "search", "nothing-matches-this"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "[]");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let records: Vec<Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("not a JSON record: {} ({})", line, e)))
+        .collect();
+    assert!(records.iter().any(|r| r["level"] == "info" && r["count"] == 0), "records: {:?}", records);
+}